@@ -1,11 +1,36 @@
 use crate::model::User;
 use zino::{prelude::*, Request, Response, Result};
-use zino_model::user::JwtAuthService;
+use zino_core::request::Body;
+use zino_model::{
+    user::{AccountSecurityService, AccountVerificationService, JwtAuthService},
+    LoginAttempt,
+};
 
 pub async fn login(mut req: Request) -> Result {
     let current_time = DateTime::now();
     let body: Map = req.parse_body().await?;
-    let (user_id, mut data) = User::generate_token(body).await.extract(&req)?;
+    let account = body.get_str("account").unwrap_or_default().to_owned();
+    let client_ip = req.client_ip().map(|ip| ip.to_string()).unwrap_or_default();
+    let user_agent = req.get_header("user-agent").unwrap_or_default();
+    let mut attempt = LoginAttempt::with_account(&account, &client_ip, user_agent);
+    let (user_id, user) = match User::authenticate(body).await {
+        Ok(result) => result,
+        Err(err) => {
+            attempt.mark_failed(err.message());
+            User::record_login_attempt(None, attempt)
+                .await
+                .extract(&req)?;
+            return Err(Rejection::from_error(err).context(&req).into());
+        }
+    };
+    attempt.mark_succeeded(user_id);
+    let device = User::record_login_attempt(Some(&user_id), attempt)
+        .await
+        .extract(&req)?
+        .ok_or_else(|| warn!("401 Unauthorized: the device is not registered"))
+        .extract(&req)?;
+    let mut data = User::issue_token(&user_id, user, Some(device.id())).extract(&req)?;
+    data.upsert("device_id", device.id().to_string());
 
     let user_updates = json!({
         "status": "Active",
@@ -32,6 +57,18 @@ pub async fn login(mut req: Request) -> Result {
 
 pub async fn refresh(req: Request) -> Result {
     let claims = req.parse_jwt_claims(JwtClaims::shared_key())?;
+    let user_id = claims
+        .subject()
+        .and_then(|subject| subject.parse::<i64>().ok())
+        .unwrap_or_default();
+    if !User::verify_device(&user_id, &claims).await.extract(&req)? {
+        reject!(
+            req,
+            unauthorized,
+            "the device is unknown or has been revoked"
+        );
+    }
+
     let data = User::refresh_token(&claims).await.extract(&req)?;
     let mut res = Response::default().context(&req);
     res.set_json_data(data);
@@ -58,3 +95,145 @@ pub async fn logout(req: Request) -> Result {
     res.set_json_data(data);
     Ok(res.into())
 }
+
+pub async fn list_devices(req: Request) -> Result {
+    let user_session = req
+        .get_data::<UserSession<_>>()
+        .ok_or_else(|| warn!("401 Unauthorized: the user session is invalid"))
+        .extract(&req)?;
+    let devices = User::list_devices(user_session.user_id())
+        .await
+        .extract(&req)?;
+    let data = Map::data_entries(devices);
+    let mut res = Response::default().context(&req);
+    res.set_json_data(data);
+    Ok(res.into())
+}
+
+pub async fn revoke_device(req: Request) -> Result {
+    let user_session = req
+        .get_data::<UserSession<_>>()
+        .ok_or_else(|| warn!("401 Unauthorized: the user session is invalid"))
+        .extract(&req)?;
+    let device_id: Uuid = req.parse_param("id")?;
+    User::revoke_device(user_session.user_id(), &device_id)
+        .await
+        .extract(&req)?;
+    let res = Response::default().context(&req);
+    Ok(res.into())
+}
+
+pub async fn revoke_all_devices(req: Request) -> Result {
+    let user_session = req
+        .get_data::<UserSession<_>>()
+        .ok_or_else(|| warn!("401 Unauthorized: the user session is invalid"))
+        .extract(&req)?;
+    let except = req
+        .get_query("except")
+        .and_then(|device_id| device_id.parse::<Uuid>().ok());
+    let num_revoked = User::revoke_all_devices(user_session.user_id(), except.as_ref())
+        .await
+        .extract(&req)?;
+    let data = Map::from_entry("num_revoked", num_revoked);
+    let mut res = Response::default().context(&req);
+    res.set_json_data(data);
+    Ok(res.into())
+}
+
+pub async fn invite(mut req: Request) -> Result {
+    let (user_session, Body(body)) = req.extract::<(UserSession<i64>, Body<Map>)>().await?;
+    let email = body
+        .get_str("email")
+        .ok_or_else(|| warn!("the `email` should be specified"))
+        .extract(&req)?;
+
+    // Only the administrators can grant roles to the invited user.
+    let mut grants = Map::new();
+    if user_session.is_admin() {
+        if let Some(roles) = body.get("roles") {
+            grants.upsert("roles", roles.clone());
+        }
+    }
+    let token = User::invite_user(email, user_session.user_id(), &grants)
+        .await
+        .extract(&req)?;
+    let data = Map::from_entry("expires_at", token.expires_at());
+    let mut res = Response::default().context(&req);
+    res.set_json_data(data);
+    Ok(res.into())
+}
+
+pub async fn accept_invitation(mut req: Request) -> Result {
+    let mut body: Map = req.parse_body().await?;
+    let token = body
+        .get_str("token")
+        .map(|s| s.to_owned())
+        .ok_or_else(|| warn!("the `token` should be specified"))
+        .extract(&req)?;
+    body.remove("token");
+    let client_ip = req.client_ip().map(|ip| ip.to_string()).unwrap_or_default();
+    let (validation, user) = User::accept_invitation(&token, &mut body, &client_ip)
+        .await
+        .extract(&req)?;
+    if !validation.is_success() {
+        reject!(req, validation);
+    }
+
+    let data = Map::data_entry(user);
+    let mut res = Response::default().context(&req);
+    res.set_json_data(data);
+    Ok(res.into())
+}
+
+pub async fn send_email_verification(req: Request) -> Result {
+    let user_session = req
+        .get_data::<UserSession<_>>()
+        .ok_or_else(|| warn!("401 Unauthorized: the user session is invalid"))
+        .extract(&req)?;
+    let token = User::send_email_verification(user_session.user_id())
+        .await
+        .extract(&req)?;
+    let data = Map::from_entry("expires_at", token.expires_at());
+    let mut res = Response::default().context(&req);
+    res.set_json_data(data);
+    Ok(res.into())
+}
+
+pub async fn verify_email(req: Request) -> Result {
+    let token = req
+        .get_query("token")
+        .ok_or_else(|| warn!("the `token` should be specified"))
+        .extract(&req)?;
+    let client_ip = req.client_ip().map(|ip| ip.to_string()).unwrap_or_default();
+    User::verify_email(token, &client_ip).await.extract(&req)?;
+    let res = Response::default().context(&req);
+    Ok(res.into())
+}
+
+pub async fn forgot_password(mut req: Request) -> Result {
+    let body: Map = req.parse_body().await?;
+    let email = body
+        .get_str("email")
+        .ok_or_else(|| warn!("the `email` should be specified"))
+        .extract(&req)?;
+    User::request_password_reset(email).await.extract(&req)?;
+    let res = Response::default().context(&req);
+    Ok(res.into())
+}
+
+pub async fn reset_password(mut req: Request) -> Result {
+    let body: Map = req.parse_body().await?;
+    let (Some(token), Some(password)) = (body.get_str("token"), body.get_str("password")) else {
+        reject!(
+            req,
+            "token",
+            "the `token` and `password` should be specified"
+        );
+    };
+    let client_ip = req.client_ip().map(|ip| ip.to_string()).unwrap_or_default();
+    User::reset_password(token, password, &client_ip)
+        .await
+        .extract(&req)?;
+    let res = Response::default().context(&req);
+    Ok(res.into())
+}
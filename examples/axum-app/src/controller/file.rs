@@ -1,5 +1,10 @@
 use std::time::{Duration, Instant};
-use zino::{prelude::*, Cluster, Request, Response, Result};
+use zino::{prelude::*, Cluster, Request, Response, Result, UploadController};
+use zino_derive::QueryParams;
+
+pub struct FileUpload;
+
+impl UploadController for FileUpload {}
 
 pub async fn upload(mut req: Request) -> Result {
     let (mut body, files) = req.parse_form_data::<Map>().await?;
@@ -42,8 +47,13 @@ pub async fn upload(mut req: Request) -> Result {
     Ok(res.into())
 }
 
+#[derive(QueryParams)]
+struct DecryptQuery {
+    file_name: String,
+}
+
 pub async fn decrypt(req: Request) -> Result {
-    let query = req.parse_query::<Map>()?;
+    let query = req.parse_query_params::<DecryptQuery>()?;
     let access_key_id = req.parse_access_key_id()?;
     let secret_key = SecretAccessKey::new(&access_key_id);
     let security_token = req.parse_security_token(secret_key.as_ref())?;
@@ -51,10 +61,7 @@ pub async fn decrypt(req: Request) -> Result {
         reject!(req, forbidden, "the security token has expired");
     }
 
-    let Some(file_name) = query.get_str("file_name") else {
-        reject!(req, "file_name", "it should be specified");
-    };
-    let file_path = Cluster::shared_dir("uploads").join(file_name);
+    let file_path = Cluster::shared_dir("uploads").join(query.file_name);
 
     let mut file = NamedFile::try_from_local(file_path).extract(&req)?;
     let decryption_start_time = Instant::now();
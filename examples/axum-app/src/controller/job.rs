@@ -0,0 +1,5 @@
+use zino::JobController;
+
+pub struct JobManager;
+
+impl JobController for JobManager {}
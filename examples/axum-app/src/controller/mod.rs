@@ -1,4 +1,6 @@
 pub(crate) mod auth;
 pub(crate) mod file;
+pub(crate) mod job;
+pub(crate) mod quota;
 pub(crate) mod stats;
 pub(crate) mod user;
@@ -0,0 +1,38 @@
+use zino::{prelude::*, Request, Response, Result};
+use zino_model::AccessQuota;
+
+pub async fn view(req: Request) -> Result {
+    let access_key_id = req.parse_param::<String>("access_key_id")?;
+    let quota: AccessQuota = AccessQuota::find_by_access_key_id(&access_key_id.into())
+        .await
+        .extract(&req)?;
+
+    let mut res = Response::default().context(&req);
+    res.set_json_data(Map::data_entry(quota.into_map()));
+    Ok(res.into())
+}
+
+pub async fn adjust(mut req: Request) -> Result {
+    let access_key_id = req.parse_param::<String>("access_key_id")?;
+    let body: Map = req.parse_body().await?;
+    let plan = body.get_str("plan").unwrap_or("Free");
+    let daily_limit = body.get_u64("daily_limit").unwrap_or_default();
+    let monthly_limit = body.get_u64("monthly_limit").unwrap_or_default();
+    let quota = AccessQuota::adjust_quota(&access_key_id.into(), plan, daily_limit, monthly_limit)
+        .await
+        .extract(&req)?;
+
+    let mut res = Response::default().context(&req);
+    res.set_json_data(Map::data_entry(quota.into_map()));
+    Ok(res.into())
+}
+
+pub async fn reset(req: Request) -> Result {
+    let access_key_id = req.parse_param::<String>("access_key_id")?;
+    AccessQuota::reset_usage(&access_key_id.into())
+        .await
+        .extract(&req)?;
+
+    let res = Response::default().context(&req);
+    Ok(res.into())
+}
@@ -1,7 +1,7 @@
 use crate::model::User;
 use axum::{middleware::Next, response::Response};
 use zino::{prelude::*, Request, Result};
-use zino_model::user::JwtAuthService;
+use zino_model::user::{AccountSecurityService, JwtAuthService};
 
 pub async fn init_user_session(mut req: Request, next: Next) -> Result<Response> {
     let claims = req
@@ -10,6 +10,19 @@ pub async fn init_user_session(mut req: Request, next: Next) -> Result<Response>
     match User::verify_jwt_claims(&claims).await {
         Ok(verified) => {
             if verified {
+                let user_id = claims
+                    .subject()
+                    .and_then(|subject| subject.parse::<i64>().ok())
+                    .unwrap_or_default();
+                let device_verified = User::verify_device(&user_id, &claims).await.extract(&req)?;
+                if !device_verified {
+                    reject!(
+                        req,
+                        unauthorized,
+                        "the device is unknown or has been revoked"
+                    );
+                }
+
                 let session = UserSession::<i64>::try_from_jwt_claims(claims).extract(&req)?;
                 req.set_data(session);
             } else {
@@ -21,6 +34,14 @@ pub async fn init_user_session(mut req: Request, next: Next) -> Result<Response>
     Ok(next.run(req.into()).await)
 }
 
+pub async fn init_optional_user_session(req: Request, next: Next) -> Result<Response> {
+    if req.get_header("authorization").is_some() {
+        init_user_session(req, next).await
+    } else {
+        Ok(next.run(req.into()).await)
+    }
+}
+
 pub async fn check_admin_role(req: Request, next: Next) -> Result<Response> {
     if req.request_method() == "POST" {
         if let Some(session) = req.get_data::<UserSession<i64>>() {
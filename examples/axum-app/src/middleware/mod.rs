@@ -1,3 +1,5 @@
 mod access;
+mod quota;
 
-pub(crate) use access::{check_admin_role, init_user_session};
+pub(crate) use access::{check_admin_role, init_optional_user_session, init_user_session};
+pub(crate) use quota::check_access_quota;
@@ -0,0 +1,48 @@
+use axum::{
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use hmac::Hmac;
+use sha2::Sha256;
+use zino::{prelude::*, Request, Result};
+use zino_model::AccessQuota;
+
+pub async fn check_access_quota(mut req: Request, next: Next) -> Result<Response> {
+    let Ok(authentication) = req.parse_authentication() else {
+        return Ok(next.run(req.into()).await);
+    };
+    if authentication.signature().is_empty() {
+        return Ok(next.run(req.into()).await);
+    }
+
+    // The quota is charged only after the signature has been verified.
+    // The nonce is consumed once so that the signature can be validated again later.
+    let access_key_id = AccessKeyId::from(authentication.access_key_id());
+    let secret_key = SecretAccessKey::new(&access_key_id);
+    req.validate_authentication::<Hmac<Sha256>>(&secret_key)?;
+
+    let usage = AccessQuota::consume(&access_key_id, 1)
+        .await
+        .extract(&req)?;
+    let Some(usage) = usage else {
+        return Ok(next.run(req.into()).await);
+    };
+    if usage.is_exceeded() {
+        let err = warn!("the quota of the `{}` plan has been exceeded", usage.plan());
+        let mut rejection = Rejection::too_many_requests(err).context(&req);
+        for (name, value) in usage.headers() {
+            rejection.insert_header(name, value);
+        }
+        return Err(rejection.into());
+    }
+
+    let mut res = next.run(req.into()).await;
+    for (name, value) in usage.headers() {
+        if let Ok(header_value) = HeaderValue::try_from(value) {
+            res.headers_mut()
+                .insert(HeaderName::from_static(name), header_value);
+        }
+    }
+    Ok(res)
+}
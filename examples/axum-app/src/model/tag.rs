@@ -16,6 +16,7 @@ use zino_derive::{DecodeRow, Model, ModelAccessor, ModelHooks, Schema};
     Model,
 )]
 #[serde(default)]
+#[schema(tree = "parent_id")]
 pub struct Tag {
     // Basic fields.
     #[schema(primary_key, auto_increment, read_only)]
@@ -36,6 +37,10 @@ pub struct Tag {
         comment = "Optional parent tag"
     )]
     parent_id: Option<i64>,
+    #[schema(index_type = "btree", comment = "Materialized path of the tag")]
+    path: String,
+    #[schema(position, index_type = "btree", comment = "Manual ordering position")]
+    position: i64,
 
     // Extensions.
     #[schema(reserved)]
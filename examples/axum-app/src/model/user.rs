@@ -1,8 +1,8 @@
 use super::Tag;
 use serde::{Deserialize, Serialize};
 use zino::prelude::*;
-use zino_derive::{DecodeRow, Model, ModelAccessor, ModelHooks, Schema};
-use zino_model::user::JwtAuthService;
+use zino_derive::{DecodeRow, Model, ModelAccessor, ModelHooks, Schema, StateMachine};
+use zino_model::user::{AccountSecurityService, AccountVerificationService, JwtAuthService};
 
 /// The `User` model.
 #[derive(
@@ -16,8 +16,12 @@ use zino_model::user::JwtAuthService;
     ModelAccessor,
     ModelHooks,
     Model,
+    StateMachine,
 )]
 #[serde(default)]
+#[schema(transition = "activate: Inactive | Locked -> Active")]
+#[schema(transition = "lock: Active -> Locked")]
+#[schema(transition = "Active | Inactive | Locked -> Deleted")]
 pub struct User {
     // Basic fields.
     #[schema(primary_key, auto_increment, read_only)]
@@ -52,6 +56,8 @@ pub struct User {
     mobile: String,
     #[schema(format = "email")]
     email: String,
+    #[schema(generated)]
+    email_verified: bool,
     #[schema(format = "uri")]
     avatar: String,
     #[schema(
@@ -99,3 +105,9 @@ impl JwtAuthService<i64> for User {
     const LOGIN_AT_FIELD: Option<&'static str> = Some("current_login_at");
     const LOGIN_IP_FIELD: Option<&'static str> = Some("current_login_ip");
 }
+
+impl AccountSecurityService<i64> for User {}
+
+impl AccountVerificationService<i64> for User {
+    const EMAIL_VERIFIED_FIELD: Option<&'static str> = Some("email_verified");
+}
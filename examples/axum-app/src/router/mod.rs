@@ -1,5 +1,10 @@
 use crate::{
-    controller::{auth, file, stats, user},
+    controller::{
+        auth,
+        file::{self, FileUpload},
+        job::JobManager,
+        quota, stats, user,
+    },
     middleware,
     model::{Tag, User},
 };
@@ -8,24 +13,61 @@ use axum::{
     routing::{get, post},
     Router,
 };
-use zino::DefaultController;
+use zino::{DefaultController, JobController, RequireIdempotency, UploadController};
 
 pub fn routes() -> Vec<Router> {
     let mut routes = Vec::new();
 
     // Auth controller.
-    let router = Router::new().route("/auth/login", post(auth::login)).merge(
-        Router::new()
-            .route("/auth/refresh", get(auth::refresh))
-            .route("/auth/logout", post(auth::logout))
-            .layer(from_fn(middleware::init_user_session)),
-    );
+    let router = Router::new()
+        .route("/auth/login", post(auth::login))
+        .route("/auth/invitation/accept", post(auth::accept_invitation))
+        .route("/auth/email/verify", get(auth::verify_email))
+        .route("/auth/password/forgot", post(auth::forgot_password))
+        .route("/auth/password/reset", post(auth::reset_password))
+        .merge(
+            Router::new()
+                .route("/auth/refresh", get(auth::refresh))
+                .route("/auth/logout", post(auth::logout))
+                .route("/auth/devices", get(auth::list_devices))
+                .route("/auth/device/:id/revoke", post(auth::revoke_device))
+                .route("/auth/devices/revoke", post(auth::revoke_all_devices))
+                .route("/auth/invite", post(auth::invite).idempotent())
+                .route(
+                    "/auth/email/verification",
+                    post(auth::send_email_verification),
+                )
+                .layer(from_fn(middleware::init_user_session)),
+        );
     routes.push(router);
 
     // File controller.
     let router = Router::new()
         .route("/file/upload", post(file::upload))
         .route("/file/decrypt", get(file::decrypt))
+        .route("/file/chunk", post(FileUpload::upload_chunk))
+        .route("/file/chunks", get(FileUpload::upload_status))
+        .route("/file/complete", post(FileUpload::complete_upload))
+        .layer(from_fn(middleware::init_user_session));
+    routes.push(router);
+
+    // Job controller.
+    let router = Router::new()
+        .route("/jobs", get(JobManager::list_jobs))
+        .route("/job/:id/runs", get(JobManager::list_runs))
+        .route("/job/:id/failure", get(JobManager::last_failure))
+        .route("/job/:id/trigger", post(JobManager::trigger))
+        .route("/job/:id/pause", post(JobManager::pause))
+        .route("/job/:id/resume", post(JobManager::resume))
+        .layer(from_fn(middleware::init_user_session));
+    routes.push(router);
+
+    // Quota controller.
+    let router = Router::new()
+        .route("/quota/:access_key_id/view", get(quota::view))
+        .route("/quota/:access_key_id/adjust", post(quota::adjust))
+        .route("/quota/:access_key_id/reset", post(quota::reset))
+        .layer(from_fn(middleware::check_admin_role))
         .layer(from_fn(middleware::init_user_session));
     routes.push(router);
 
@@ -36,8 +78,16 @@ pub fn routes() -> Vec<Router> {
         .route("/user/:id/update", post(User::update))
         .route("/user/:id/view", get(user::view))
         .route("/user/list", get(User::list))
+        .route("/user/:id/tags", get(User::list_related::<Tag, i64>))
+        .route("/user/:id/tags/add", post(User::add_related::<Tag, i64>))
+        .route(
+            "/user/:id/tags/remove",
+            post(User::remove_related::<Tag, i64>),
+        )
+        .route("/user/batch", post(User::batch_mutate))
         .route("/user/import", post(User::import))
-        .route("/user/export", get(User::export));
+        .route("/user/export", get(User::export))
+        .route("/user/stream", get(User::stream));
     routes.push(router);
 
     // Tag controller.
@@ -48,10 +98,18 @@ pub fn routes() -> Vec<Router> {
         .route("/tag/:id/view", get(Tag::view))
         .route("/tag/list", get(Tag::list))
         .route("/tag/tree", get(Tag::tree))
+        .route("/tag/reorder", post(Tag::reorder))
+        .route("/tag/:id/users", get(Tag::list_related::<User, i64>))
+        .layer(from_fn(middleware::check_access_quota))
         .layer(from_fn(middleware::check_admin_role))
         .layer(from_fn(middleware::init_user_session));
     routes.push(router);
 
+    // Admin dashboard.
+    let router =
+        zino_admin::admin_router![User, Tag].layer(from_fn(middleware::init_optional_user_session));
+    routes.push(router);
+
     routes
 }
 
@@ -66,14 +124,16 @@ pub fn debug_routes() -> Vec<Router> {
     let router = Router::new()
         .route("/user/schema", get(User::schema))
         .route("/user/definition", get(User::definition))
-        .route("/user/mock", get(User::mock));
+        .route("/user/mock", get(User::mock))
+        .route("/user/console", get(User::console).post(User::console));
     routes.push(router);
 
     // Tag schema controller.
     let router = Router::new()
         .route("/tag/schema", get(Tag::schema))
         .route("/tag/definition", get(Tag::definition))
-        .route("/tag/mock", get(Tag::mock));
+        .route("/tag/mock", get(Tag::mock))
+        .route("/tag/console", get(Tag::console).post(Tag::console));
     routes.push(router);
 
     routes
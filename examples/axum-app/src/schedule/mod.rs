@@ -8,7 +8,9 @@ pub fn job_scheduler() -> JobScheduler {
     let job = Job::new("0/15 * * * * *", job::every_15s as CronJob).disable(true);
     scheduler.add(job);
 
-    let job = Job::new("0/20 * * * * *", job::every_20s as CronJob).max_ticks(3);
+    let job = Job::new("0/20 * * * * *", job::every_20s as CronJob)
+        .name("every_20s")
+        .max_ticks(3);
     scheduler.add(job);
 
     scheduler
@@ -17,7 +19,9 @@ pub fn job_scheduler() -> JobScheduler {
 pub fn async_job_scheduler() -> AsyncJobScheduler {
     let mut scheduler = AsyncJobScheduler::new();
 
-    let job = AsyncJob::new("0 0 * * * *", job::every_hour as AsyncCronJob).immediate(true);
+    let job = AsyncJob::new("@hourly", job::every_hour as AsyncCronJob)
+        .name("every_hour")
+        .immediate(true);
     scheduler.add(job);
 
     scheduler
@@ -50,7 +50,8 @@ fn user_router(cfg: &mut ServiceConfig) {
         .route("/user/{id}/view", get().to(user::view))
         .route("/user/list", get().to(User::list))
         .route("/user/import", post().to(User::import))
-        .route("/user/export", get().to(User::export));
+        .route("/user/export", get().to(User::export))
+        .route("/user/stream", get().to(User::stream));
 }
 
 fn tag_router(cfg: &mut ServiceConfig) {
@@ -69,11 +70,15 @@ fn stats_router(cfg: &mut ServiceConfig) {
 fn user_debug_router(cfg: &mut ServiceConfig) {
     cfg.route("/user/schema", get().to(User::schema))
         .route("/user/definition", get().to(User::definition))
-        .route("/user/mock", get().to(User::mock));
+        .route("/user/mock", get().to(User::mock))
+        .route("/user/console", get().to(User::console))
+        .route("/user/console", post().to(User::console));
 }
 
 fn tag_debug_router(cfg: &mut ServiceConfig) {
     cfg.route("/tag/schema", get().to(Tag::schema))
         .route("/tag/definition", get().to(Tag::definition))
-        .route("/tag/mock", get().to(Tag::mock));
+        .route("/tag/mock", get().to(Tag::mock))
+        .route("/tag/console", get().to(Tag::console))
+        .route("/tag/console", post().to(Tag::console));
 }
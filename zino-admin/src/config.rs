@@ -0,0 +1,89 @@
+use std::time::Duration;
+use zino_core::{extension::TomlTableExt, state::State, LazyLock};
+
+/// Returns the route prefix of the admin dashboard.
+#[inline]
+pub fn route_prefix() -> &'static str {
+    SHARED_ADMIN_CONFIG.route_prefix.as_str()
+}
+
+/// Returns the roles granted to access the admin dashboard.
+#[inline]
+pub fn admin_roles() -> Vec<&'static str> {
+    SHARED_ADMIN_CONFIG
+        .roles
+        .iter()
+        .map(|role| role.as_str())
+        .collect()
+}
+
+/// Returns the default page size of the list screens.
+#[inline]
+pub fn page_size() -> usize {
+    SHARED_ADMIN_CONFIG.page_size
+}
+
+/// Returns the maximum page size of the list screens.
+#[inline]
+pub fn max_page_size() -> usize {
+    SHARED_ADMIN_CONFIG.max_page_size
+}
+
+/// Returns the max age of the cookie sessions.
+#[inline]
+pub fn session_max_age() -> Duration {
+    SHARED_ADMIN_CONFIG.session_max_age
+}
+
+/// Admin config.
+#[derive(Debug)]
+struct AdminConfig {
+    /// Route prefix.
+    route_prefix: String,
+    /// Roles granted to access the admin dashboard.
+    roles: Vec<String>,
+    /// Default page size.
+    page_size: usize,
+    /// Maximum page size.
+    max_page_size: usize,
+    /// Max age of the cookie sessions.
+    session_max_age: Duration,
+}
+
+/// Shared admin config.
+///
+/// ```toml
+/// [admin]
+/// route-prefix = "/admin"
+/// roles = ["superuser", "admin"]
+/// page-size = 20
+/// max-page-size = 100
+/// session-max-age = "1h"
+/// ```
+static SHARED_ADMIN_CONFIG: LazyLock<AdminConfig> = LazyLock::new(|| {
+    let mut config = AdminConfig {
+        route_prefix: "/admin".to_owned(),
+        roles: vec!["superuser".to_owned(), "admin".to_owned()],
+        page_size: 20,
+        max_page_size: 100,
+        session_max_age: Duration::from_secs(60 * 60),
+    };
+    if let Some(admin) = State::shared().get_config("admin") {
+        if let Some(route_prefix) = admin.get_str("route-prefix") {
+            config.route_prefix = route_prefix.trim_end_matches('/').to_owned();
+        }
+        if let Some(roles) = admin.get_str_array("roles") {
+            config.roles = roles.into_iter().map(|role| role.to_owned()).collect();
+        }
+        if let Some(page_size) = admin.get_usize("page-size") {
+            config.page_size = page_size.max(1);
+        }
+        if let Some(max_page_size) = admin.get_usize("max-page-size") {
+            config.max_page_size = max_page_size.max(1);
+        }
+        if let Some(session_max_age) = admin.get_duration("session-max-age") {
+            config.session_max_age = session_max_age;
+        }
+    }
+    config
+});
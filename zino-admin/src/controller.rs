@@ -0,0 +1,392 @@
+use crate::{
+    config, render,
+    session::{self, CSRF_TOKEN_FIELD},
+    AdminModel, AdminRegistry,
+};
+use std::{fmt::Display, str::FromStr};
+use zino_core::{
+    auth::UserSession,
+    extension::JsonObjectExt,
+    model::{Model, ModelHooks, Query},
+    orm::{ModelAccessor, Schema},
+    request::RequestContext,
+    response::{ExtractRejection, Rejection, Response, StatusCode},
+    validation::Validation,
+    warn, JsonValue, Map, Uuid,
+};
+use zino_model::AuditLog;
+
+/// Admin controller for the models.
+///
+/// The screens are rendered from the column metadata of the model.
+/// The changes made by the `update` and `delete` actions are recorded as [`AuditLog`]s.
+/// The forms submitted with the cookie session are required to carry the CSRF token.
+pub trait AdminController<K> {
+    /// A type for the request extractor.
+    type Request;
+
+    /// A type for the response result.
+    type Result;
+
+    /// Lists the models with pagination.
+    async fn admin_list(req: Self::Request) -> Self::Result;
+
+    /// Views a model.
+    async fn admin_view(req: Self::Request) -> Self::Result;
+
+    /// Edits a model in a form.
+    async fn admin_edit(req: Self::Request) -> Self::Result;
+
+    /// Updates a model with the form data, and redirects to the detail screen.
+    async fn admin_update(req: Self::Request) -> Self::Result;
+
+    /// Deletes a model, and redirects to the list screen.
+    async fn admin_delete(req: Self::Request) -> Self::Result;
+}
+
+impl<K, M> AdminController<K> for M
+where
+    K: Default + Display + PartialEq + FromStr,
+    <K as FromStr>::Err: std::error::Error + Send + 'static,
+    M: ModelAccessor<K>,
+{
+    type Request = zino::Request;
+    type Result = zino::Result;
+
+    async fn admin_list(req: Self::Request) -> Self::Result {
+        check_access(&req)?;
+
+        let mut query = Self::default_list_query();
+        let mut res = req.query_validation(&mut query)?;
+        let (page_num, page_size) = parse_pagination(&req);
+        query.set_offset((page_num - 1) * page_size);
+        query.set_limit(page_size);
+
+        let mut models = Self::find::<Map>(&query).await.extract(&req)?;
+        for model in models.iter_mut() {
+            Self::after_decode(model).await.extract(&req)?;
+        }
+        let total_rows = Self::count(&query).await.extract(&req)?;
+        let page_count = total_rows.div_ceil(page_size as u64);
+
+        let admin_model = AdminModel::new::<Self>();
+        let content = render::render_list(&admin_model, &models, page_num, page_count);
+        res.set_html_response(content);
+        Ok(res.into())
+    }
+
+    async fn admin_view(req: Self::Request) -> Self::Result {
+        check_access(&req)?;
+
+        let id = req.parse_param::<K>("id")?;
+        let mut model: Map = Self::find_by_id(&id).await.extract(&req)?;
+        Self::after_decode(&mut model).await.extract(&req)?;
+
+        let admin_model = AdminModel::new::<Self>();
+        let csrf_token = get_csrf_token(&req);
+        let content = render::render_detail(&admin_model, &id.to_string(), &model, &csrf_token);
+        let mut res = Response::default().context(&req);
+        res.set_html_response(content);
+        Ok(res.into())
+    }
+
+    async fn admin_edit(req: Self::Request) -> Self::Result {
+        check_access(&req)?;
+
+        let id = req.parse_param::<K>("id")?;
+        let mut model: Map = Self::find_by_id(&id).await.extract(&req)?;
+        Self::after_decode(&mut model).await.extract(&req)?;
+
+        let admin_model = AdminModel::new::<Self>();
+        let csrf_token = get_csrf_token(&req);
+        let content = render::render_form(&admin_model, &id.to_string(), &model, &csrf_token, None);
+        let mut res = Response::default().context(&req);
+        res.set_html_response(content);
+        Ok(res.into())
+    }
+
+    async fn admin_update(mut req: Self::Request) -> Self::Result {
+        let user_id = check_access(&req)?;
+
+        let id = req.parse_param::<K>("id")?;
+        let mut body = req.parse_body::<Map>().await?;
+        check_csrf_token(&req, &body)?;
+
+        let snapshot: Map = Self::find_by_id(&id).await.extract(&req)?;
+
+        // Keeps the editable fields only and parses the JSON values from the textareas.
+        let admin_model = AdminModel::new::<Self>();
+        let columns = admin_model.editable_columns();
+        let mut validation = Validation::new();
+        body.retain(|key, _| columns.iter().any(|col| col.name() == key));
+        for col in columns.iter() {
+            let name = col.name();
+            if col.type_name() == "Map" || col.is_array_type() {
+                if let Some(value) = body.get_str(name).map(|s| s.to_owned()) {
+                    match serde_json::from_str::<JsonValue>(&value) {
+                        Ok(value) => {
+                            body.upsert(name, value);
+                        }
+                        Err(err) => validation.record_fail(name, err),
+                    }
+                }
+            }
+        }
+        if validation.is_success() {
+            let extension = req.get_data::<<Self as ModelHooks>::Extension>();
+            let mut data = body.clone();
+            let (model_validation, model) = Self::update_by_id(&id, &mut data, extension)
+                .await
+                .extract(&req)?;
+            if model_validation.is_success() {
+                let changes = diff_changes(&admin_model, &snapshot, &model.into_map());
+                record_audit_log(&req, &user_id, "update", Self::MODEL_NAME, &id, changes).await;
+
+                let location = format!(
+                    "{}/{}/{}/view",
+                    config::route_prefix(),
+                    Self::MODEL_NAME,
+                    id
+                );
+                let mut res = Response::new(StatusCode::SEE_OTHER).context(&req);
+                res.insert_header("location", location);
+                return Ok(res.into());
+            }
+            validation = model_validation;
+        }
+
+        let mut model = snapshot;
+        model.append(&mut body);
+        let csrf_token = get_csrf_token(&req);
+        let content = render::render_form(
+            &admin_model,
+            &id.to_string(),
+            &model,
+            &csrf_token,
+            Some(&validation),
+        );
+        let mut res = Response::new(StatusCode::BAD_REQUEST).context(&req);
+        res.set_html_response(content);
+        Ok(res.into())
+    }
+
+    async fn admin_delete(mut req: Self::Request) -> Self::Result {
+        let user_id = check_access(&req)?;
+
+        let id = req.parse_param::<K>("id")?;
+        let body = req.parse_body::<Map>().await?;
+        check_csrf_token(&req, &body)?;
+
+        let snapshot: Map = Self::find_by_id(&id).await.extract(&req)?;
+        let model = Self::try_get_model(&id).await.extract(&req)?;
+        model.delete().await.extract(&req)?;
+
+        let admin_model = AdminModel::new::<Self>();
+        let changes = diff_changes(&admin_model, &snapshot, &Map::new());
+        record_audit_log(&req, &user_id, "delete", Self::MODEL_NAME, &id, changes).await;
+
+        let location = format!("{}/{}/list", config::route_prefix(), Self::MODEL_NAME);
+        let mut res = Response::new(StatusCode::SEE_OTHER).context(&req);
+        res.insert_header("location", location);
+        Ok(res.into())
+    }
+}
+
+/// Renders the index of the registered models.
+pub async fn admin_index(req: zino::Request) -> zino::Result {
+    check_access(&req)?;
+
+    let content = render::render_index(&AdminRegistry::models());
+    let mut res = Response::default().context(&req);
+    res.set_html_response(content);
+    Ok(res.into())
+}
+
+/// Creates a cookie session for the user authenticated by the `UserSession`,
+/// and redirects to the index screen. The session is required to navigate
+/// the screens in the browser, since the links do not carry the bearer token.
+pub async fn admin_login(req: zino::Request) -> zino::Result {
+    let roles = config::admin_roles();
+    let Some((user_id, granted)) = get_user_session::<Uuid>(&req, &roles)
+        .or_else(|| get_user_session::<i64>(&req, &roles))
+        .or_else(|| get_user_session::<String>(&req, &roles))
+    else {
+        let err = warn!("401 Unauthorized: the user session is required");
+        return Err(Rejection::unauthorized(err).context(&req).into());
+    };
+    if !granted {
+        let err = warn!("403 Forbidden: the user has no roles to access the admin dashboard");
+        return Err(Rejection::forbidden(err).context(&req).into());
+    }
+
+    let session_id = session::create_session(user_id);
+    let mut res = Response::new(StatusCode::SEE_OTHER).context(&req);
+    res.insert_header("set-cookie", session::format_session_cookie(&session_id));
+    res.insert_header("location", config::route_prefix().to_owned());
+    Ok(res.into())
+}
+
+/// Removes the cookie session, and redirects to the index screen.
+pub async fn admin_logout(mut req: zino::Request) -> zino::Result {
+    check_access(&req)?;
+
+    let body = req.parse_body::<Map>().await?;
+    check_csrf_token(&req, &body)?;
+    session::remove_session(&req);
+
+    let mut res = Response::new(StatusCode::SEE_OTHER).context(&req);
+    res.insert_header("set-cookie", session::format_session_cookie(""));
+    res.insert_header("location", config::route_prefix().to_owned());
+    Ok(res.into())
+}
+
+/// Lists the audit logs, which can be filtered by
+/// the `model_name`, `model_id` and `user_id` query params.
+pub async fn admin_audit_logs(req: zino::Request) -> zino::Result {
+    check_access(&req)?;
+
+    let mut query = Query::default();
+    for key in ["model_name", "model_id", "user_id"] {
+        if let Some(value) = req.get_query(key) {
+            query.add_filter(key, value);
+        }
+    }
+    query.order_desc("created_at");
+
+    let (page_num, page_size) = parse_pagination(&req);
+    query.set_offset((page_num - 1) * page_size);
+    query.set_limit(page_size);
+
+    let logs = AuditLog::find::<Map>(&query).await.extract(&req)?;
+    let content = render::render_audit_logs(&logs);
+    let mut res = Response::default().context(&req);
+    res.set_html_response(content);
+    Ok(res.into())
+}
+
+/// Checks whether the user has the roles to access the admin dashboard,
+/// and returns the user ID. The user ID of the session can be `Uuid`, `i64` or `String`.
+/// If there is no `UserSession`, the user is authenticated by the cookie session
+/// whose roles have been checked when it was created.
+fn check_access(req: &zino::Request) -> Result<String, Rejection> {
+    let roles = config::admin_roles();
+    let Some((user_id, granted)) = get_user_session::<Uuid>(req, &roles)
+        .or_else(|| get_user_session::<i64>(req, &roles))
+        .or_else(|| get_user_session::<String>(req, &roles))
+    else {
+        if let Some(session) = session::get_session(req) {
+            return Ok(session.user_id().to_owned());
+        }
+        let err = warn!("401 Unauthorized: the user session is required");
+        return Err(Rejection::unauthorized(err).context(req));
+    };
+    if granted {
+        Ok(user_id)
+    } else {
+        let err = warn!("403 Forbidden: the user has no roles to access the admin dashboard");
+        Err(Rejection::forbidden(err).context(req))
+    }
+}
+
+/// Gets the user ID of the session and checks whether the user has any of the roles.
+fn get_user_session<U>(req: &zino::Request, roles: &[&str]) -> Option<(String, bool)>
+where
+    U: Clone + Display + Send + Sync + 'static,
+{
+    req.get_data::<UserSession<U>>()
+        .map(|session| (session.user_id().to_string(), session.has_any_roles(roles)))
+}
+
+/// Returns `true` if the request has a `UserSession` authenticated by the bearer token.
+fn has_user_session(req: &zino::Request) -> bool {
+    req.get_data::<UserSession<Uuid>>().is_some()
+        || req.get_data::<UserSession<i64>>().is_some()
+        || req.get_data::<UserSession<String>>().is_some()
+}
+
+/// Gets the CSRF token of the cookie session to be rendered in the forms.
+fn get_csrf_token(req: &zino::Request) -> String {
+    session::get_session(req)
+        .map(|session| session.csrf_token().to_owned())
+        .unwrap_or_default()
+}
+
+/// Checks the CSRF token of the form submitted with the cookie session.
+/// The requests with a `UserSession` are exempted, since the bearer token
+/// is not sent by the browser automatically.
+fn check_csrf_token(req: &zino::Request, form: &Map) -> Result<(), Rejection> {
+    if has_user_session(req) {
+        return Ok(());
+    }
+    let verified = session::get_session(req).is_some_and(|session| {
+        form.get_str(CSRF_TOKEN_FIELD)
+            .is_some_and(|csrf_token| session.verify_csrf_token(csrf_token))
+    });
+    if verified {
+        Ok(())
+    } else {
+        let err = warn!("403 Forbidden: the CSRF token is invalid");
+        Err(Rejection::forbidden(err).context(req))
+    }
+}
+
+/// Parses the `page_num` and `page_size` query params.
+/// The page size is limited to the `max-page-size` of the admin config.
+fn parse_pagination(req: &zino::Request) -> (usize, usize) {
+    let page_num = req
+        .get_query("page_num")
+        .and_then(|s| s.parse::<usize>().ok())
+        .unwrap_or(1)
+        .max(1);
+    let page_size = req
+        .get_query("page_size")
+        .and_then(|s| s.parse::<usize>().ok())
+        .unwrap_or_else(config::page_size)
+        .clamp(1, config::max_page_size());
+    (page_num, page_size)
+}
+
+/// Returns the changed fields with the values in the form of
+/// `{ "before": ..., "after": ... }`. Only the columns displayed in the detail screen
+/// are compared, so that the write-only columns are not recorded.
+fn diff_changes(admin_model: &AdminModel, before: &Map, after: &Map) -> Map {
+    let mut changes = Map::new();
+    for col in admin_model.detail_columns() {
+        let key = col.name();
+        if matches!(key, "updated_at" | "version") {
+            continue;
+        }
+        let Some(value) = before.get(key) else {
+            continue;
+        };
+        let current = after.get(key).cloned().unwrap_or_default();
+        if &current != value {
+            let mut change = Map::new();
+            change.upsert("before", value.clone());
+            change.upsert("after", current);
+            changes.upsert(key, change);
+        }
+    }
+    changes
+}
+
+/// Records the audit log of an action.
+/// The failure is logged instead of being returned.
+async fn record_audit_log(
+    req: &zino::Request,
+    user_id: &str,
+    action: &str,
+    model_name: &str,
+    model_id: impl Display,
+    changes: Map,
+) {
+    let mut log = AuditLog::with_action(action, model_name, model_id);
+    log.set_user_id(user_id);
+    if let Some(client_ip) = req.client_ip() {
+        log.set_client_ip(client_ip);
+    }
+    log.set_changes(changes);
+    if let Err(err) = log.insert().await {
+        tracing::error!("fail to record the audit log: {err}");
+    }
+}
@@ -0,0 +1,40 @@
+#![cfg_attr(docsrs, feature(doc_auto_cfg))]
+#![doc = include_str!("../README.md")]
+#![doc(html_favicon_url = "https://zino.cc/assets/zino-logo.png")]
+#![doc(html_logo_url = "https://zino.cc/assets/zino-logo.svg")]
+#![allow(async_fn_in_trait)]
+#![forbid(unsafe_code)]
+
+mod config;
+mod registry;
+
+pub use config::{admin_roles, max_page_size, page_size, route_prefix, session_max_age};
+pub use registry::{AdminModel, AdminRegistry};
+
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+mod controller;
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+mod render;
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+mod router;
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+mod session;
+
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+pub use controller::{admin_audit_logs, admin_index, admin_login, admin_logout, AdminController};
+
+#[doc(hidden)]
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+pub mod __private {
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "actix")] {
+            pub use actix_web;
+            pub use zino::RouterConfigure;
+        } else if #[cfg(feature = "axum")] {
+            pub use axum;
+        } else if #[cfg(feature = "ntex")] {
+            pub use ntex;
+            pub use zino::RouterConfigure;
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use std::sync::RwLock;
+use zino_core::{model::Column, orm::Schema};
+
+/// Metadata of a model registered in the admin dashboard.
+#[derive(Debug, Clone, Copy)]
+pub struct AdminModel {
+    /// Model name.
+    model_name: &'static str,
+    /// Table name.
+    table_name: &'static str,
+    /// Primary key name.
+    primary_key_name: &'static str,
+    /// Columns.
+    columns: &'static [Column<'static>],
+}
+
+impl AdminModel {
+    /// Creates a new instance for the model.
+    #[inline]
+    pub fn new<M: Schema>() -> Self {
+        Self {
+            model_name: M::MODEL_NAME,
+            table_name: M::table_name(),
+            primary_key_name: M::PRIMARY_KEY_NAME,
+            columns: M::columns(),
+        }
+    }
+
+    /// Returns the model name.
+    #[inline]
+    pub fn model_name(&self) -> &'static str {
+        self.model_name
+    }
+
+    /// Returns the table name.
+    #[inline]
+    pub fn table_name(&self) -> &'static str {
+        self.table_name
+    }
+
+    /// Returns the primary key name.
+    #[inline]
+    pub fn primary_key_name(&self) -> &'static str {
+        self.primary_key_name
+    }
+
+    /// Returns a reference to the columns.
+    #[inline]
+    pub fn columns(&self) -> &'static [Column<'static>] {
+        self.columns
+    }
+
+    /// Returns the columns displayed in the list screen.
+    /// The write-only columns and the columns of maps or bytes are excluded.
+    pub fn list_columns(&self) -> Vec<&'static Column<'static>> {
+        self.columns
+            .iter()
+            .filter(|col| !col.is_write_only() && !matches!(col.type_name(), "Map" | "Vec<u8>"))
+            .take(8)
+            .collect()
+    }
+
+    /// Returns the columns displayed in the detail screen.
+    /// The write-only columns are excluded.
+    pub fn detail_columns(&self) -> Vec<&'static Column<'static>> {
+        self.columns
+            .iter()
+            .filter(|col| !col.is_write_only())
+            .collect()
+    }
+
+    /// Returns the columns editable in the edit screen.
+    /// The primary key, read-only, write-only and generated columns are excluded.
+    pub fn editable_columns(&self) -> Vec<&'static Column<'static>> {
+        self.columns
+            .iter()
+            .filter(|col| {
+                !(col.is_primary_key()
+                    || col.is_read_only()
+                    || col.is_write_only()
+                    || col.has_attribute("generated")
+                    || matches!(col.name(), "created_at" | "updated_at" | "version"))
+            })
+            .collect()
+    }
+}
+
+/// A registry of the models managed by the admin dashboard.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AdminRegistry;
+
+impl AdminRegistry {
+    /// Registers a model and returns the model name.
+    pub fn register<M: Schema>() -> &'static str {
+        let model_name = M::MODEL_NAME;
+        if let Ok(mut models) = ADMIN_MODELS.write() {
+            if !models.iter().any(|model| model.model_name == model_name) {
+                models.push(AdminModel::new::<M>());
+            }
+        }
+        model_name
+    }
+
+    /// Returns all the registered models.
+    pub fn models() -> Vec<AdminModel> {
+        ADMIN_MODELS
+            .read()
+            .map(|models| models.clone())
+            .unwrap_or_default()
+    }
+
+    /// Gets a registered model by the model name.
+    pub fn get(model_name: &str) -> Option<AdminModel> {
+        ADMIN_MODELS.read().ok().and_then(|models| {
+            models
+                .iter()
+                .find(|model| model.model_name == model_name)
+                .copied()
+        })
+    }
+}
+
+/// Registered models.
+static ADMIN_MODELS: RwLock<Vec<AdminModel>> = RwLock::new(Vec::new());
@@ -0,0 +1,306 @@
+use crate::{config, session::CSRF_TOKEN_FIELD, AdminModel, AdminRegistry};
+use std::fmt::Write;
+use zino_core::{
+    extension::JsonObjectExt,
+    model::Column,
+    validation::{escape_html, Validation},
+    JsonValue, Map,
+};
+
+/// Renders a page with the navigation of the registered models.
+pub(crate) fn render_page(title: &str, content: &str) -> String {
+    let prefix = config::route_prefix();
+    let mut nav = String::new();
+    for model in AdminRegistry::models() {
+        let model_name = model.model_name();
+        let _ = write!(
+            nav,
+            r#"<li><a href="{prefix}/{model_name}/list">{model_name}</a></li>"#
+        );
+    }
+    let title = escape_html(title);
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title}</title>
+<style>
+body {{ margin: 0; font-family: sans-serif; display: flex; }}
+nav {{ width: 200px; min-height: 100vh; padding: 16px; background: #f5f5f5; }}
+nav ul {{ list-style: none; padding: 0; }}
+main {{ flex: 1; padding: 16px; }}
+table {{ border-collapse: collapse; width: 100%; }}
+th, td {{ border: 1px solid #ddd; padding: 6px 8px; text-align: left; }}
+label {{ display: block; margin-top: 12px; font-weight: bold; }}
+input[type=text], input[type=number], select, textarea {{ width: 100%; max-width: 600px; }}
+.error {{ color: #c00; }}
+</style>
+</head>
+<body>
+<nav>
+<a href="{prefix}"><strong>Admin</strong></a>
+<ul>{nav}</ul>
+<a href="{prefix}/audit-logs">Audit logs</a>
+</nav>
+<main>
+<h1>{title}</h1>
+{content}
+</main>
+</body>
+</html>"#
+    )
+}
+
+/// Renders the index of the registered models.
+pub(crate) fn render_index(models: &[AdminModel]) -> String {
+    let prefix = config::route_prefix();
+    let mut rows = String::new();
+    for model in models {
+        let model_name = model.model_name();
+        let table_name = escape_html(model.table_name());
+        let num_columns = model.columns().len();
+        let _ = write!(
+            rows,
+            r#"<tr><td><a href="{prefix}/{model_name}/list">{model_name}</a></td><td>{table_name}</td><td>{num_columns}</td></tr>"#
+        );
+    }
+    let content = format!(
+        "<table><thead><tr><th>Model</th><th>Table</th><th>Columns</th></tr></thead>\
+        <tbody>{rows}</tbody></table>"
+    );
+    render_page("Models", &content)
+}
+
+/// Renders the list screen of a model.
+pub(crate) fn render_list(
+    model: &AdminModel,
+    entries: &[Map],
+    page_num: usize,
+    page_count: u64,
+) -> String {
+    let prefix = config::route_prefix();
+    let model_name = model.model_name();
+    let primary_key_name = model.primary_key_name();
+    let columns = model.list_columns();
+
+    let mut content = String::from("<table><thead><tr>");
+    for col in &columns {
+        let _ = write!(content, "<th>{}</th>", escape_html(col.name()));
+    }
+    content.push_str("<th></th></tr></thead><tbody>");
+    for entry in entries {
+        content.push_str("<tr>");
+        for col in &columns {
+            let value = entry.get(col.name()).map(format_value).unwrap_or_default();
+            let _ = write!(content, "<td>{}</td>", escape_html(&value));
+        }
+        let id = entry
+            .get(primary_key_name)
+            .map(format_value)
+            .unwrap_or_default();
+        let id = escape_html(&id);
+        let _ = write!(
+            content,
+            r#"<td><a href="{prefix}/{model_name}/{id}/view">View</a> <a href="{prefix}/{model_name}/{id}/edit">Edit</a></td></tr>"#
+        );
+    }
+    content.push_str("</tbody></table><p>");
+    if page_num > 1 {
+        let _ = write!(
+            content,
+            r#"<a href="?page_num={}">Previous</a> "#,
+            page_num - 1
+        );
+    }
+    let _ = write!(content, "Page {page_num} of {}", page_count.max(1));
+    if (page_num as u64) < page_count {
+        let _ = write!(content, r#" <a href="?page_num={}">Next</a>"#, page_num + 1);
+    }
+    content.push_str("</p>");
+    render_page(model_name, &content)
+}
+
+/// Renders the detail screen of a model.
+pub(crate) fn render_detail(model: &AdminModel, id: &str, data: &Map, csrf_token: &str) -> String {
+    let prefix = config::route_prefix();
+    let model_name = model.model_name();
+    let title = format!("{model_name} {id}");
+    let id = escape_html(id);
+    let csrf_input = render_csrf_input(csrf_token);
+
+    let mut content = String::from("<table><tbody>");
+    for col in model.detail_columns() {
+        let value = data.get(col.name()).map(format_value).unwrap_or_default();
+        let _ = write!(
+            content,
+            "<tr><th>{}</th><td>{}</td></tr>",
+            escape_html(col.name()),
+            escape_html(&value)
+        );
+    }
+    let _ = write!(
+        content,
+        r#"</tbody></table>
+<p>
+<a href="{prefix}/{model_name}/{id}/edit">Edit</a>
+<a href="{prefix}/audit-logs?model_name={model_name}&amp;model_id={id}">History</a>
+</p>
+<form method="post" action="{prefix}/{model_name}/{id}/delete" onsubmit="return confirm('Delete this {model_name}?')">
+{csrf_input}
+<button type="submit">Delete</button>
+</form>"#
+    );
+    render_page(&title, &content)
+}
+
+/// Renders the edit screen of a model.
+pub(crate) fn render_form(
+    model: &AdminModel,
+    id: &str,
+    data: &Map,
+    csrf_token: &str,
+    validation: Option<&Validation>,
+) -> String {
+    let prefix = config::route_prefix();
+    let model_name = model.model_name();
+    let title = format!("Edit {model_name} {id}");
+    let id = escape_html(id);
+
+    let mut content = String::new();
+    if let Some(validation) = validation {
+        content.push_str(r#"<ul class="error">"#);
+        for (key, value) in validation.clone().into_map() {
+            let message = format_value(&value);
+            let _ = write!(
+                content,
+                "<li>{}: {}</li>",
+                escape_html(&key),
+                escape_html(&message)
+            );
+        }
+        content.push_str("</ul>");
+    }
+    let _ = write!(
+        content,
+        r#"<form method="post" action="{prefix}/{model_name}/{id}/update">"#
+    );
+    content.push_str(&render_csrf_input(csrf_token));
+    for col in model.editable_columns() {
+        let name = escape_html(col.name());
+        let _ = write!(content, r#"<label for="{name}">{name}</label>"#);
+        content.push_str(&render_input(col, data.get(col.name())));
+    }
+    let _ = write!(
+        content,
+        r#"<p><button type="submit">Save</button> <a href="{prefix}/{model_name}/{id}/view">Cancel</a></p></form>"#
+    );
+    render_page(&title, &content)
+}
+
+/// Renders the audit logs.
+pub(crate) fn render_audit_logs(logs: &[Map]) -> String {
+    let prefix = config::route_prefix();
+    let mut content = String::from(
+        "<table><thead><tr><th>Time</th><th>Action</th><th>Model</th><th>ID</th>\
+        <th>User</th><th>Client IP</th><th>Changes</th></tr></thead><tbody>",
+    );
+    for log in logs {
+        let field = |key: &str| escape_html(&log.get(key).map(format_value).unwrap_or_default());
+        let model_name = field("model_name");
+        let model_id = field("model_id");
+        let _ = write!(
+            content,
+            r#"<tr><td>{}</td><td>{}</td><td>{model_name}</td><td><a href="{prefix}/{model_name}/{model_id}/view">{model_id}</a></td><td>{}</td><td>{}</td><td><pre>{}</pre></td></tr>"#,
+            field("created_at"),
+            field("name"),
+            field("user_id"),
+            field("client_ip"),
+            escape_html(
+                &log.get("changes")
+                    .and_then(|changes| serde_json::to_string_pretty(changes).ok())
+                    .unwrap_or_default()
+            ),
+        );
+    }
+    content.push_str("</tbody></table>");
+    render_page("Audit logs", &content)
+}
+
+/// Renders the hidden input of the CSRF token.
+fn render_csrf_input(csrf_token: &str) -> String {
+    format!(
+        r#"<input type="hidden" name="{CSRF_TOKEN_FIELD}" value="{}">"#,
+        escape_html(csrf_token)
+    )
+}
+
+/// Renders the input element for a column.
+fn render_input(col: &Column<'_>, value: Option<&JsonValue>) -> String {
+    let name = escape_html(col.name());
+    let value = value.filter(|v| !v.is_null());
+    if let Some(options) = col.extra().parse_enum_values("enum_values") {
+        let current = value.map(format_value).unwrap_or_default();
+        let mut select = format!(r#"<select id="{name}" name="{name}">"#);
+        for option in options {
+            let option = format_value(&option);
+            let selected = if option == current { " selected" } else { "" };
+            let option = escape_html(&option);
+            let _ = write!(
+                select,
+                r#"<option value="{option}"{selected}>{option}</option>"#
+            );
+        }
+        select.push_str("</select>");
+        return select;
+    }
+    match col.type_name() {
+        "bool" => {
+            let checked = if value.and_then(|v| v.as_bool()) == Some(true) {
+                " checked"
+            } else {
+                ""
+            };
+            format!(
+                r#"<input type="hidden" name="{name}" value="false"><input type="checkbox" id="{name}" name="{name}" value="true"{checked}>"#
+            )
+        }
+        "i8" | "i16" | "i32" | "i64" | "isize" | "u8" | "u16" | "u32" | "u64" | "usize"
+        | "Option<i32>" | "Option<i64>" | "Option<u32>" | "Option<u64>" => {
+            let value = escape_html(&value.map(format_value).unwrap_or_default());
+            format!(r#"<input type="number" id="{name}" name="{name}" value="{value}">"#)
+        }
+        "f32" | "f64" => {
+            let value = escape_html(&value.map(format_value).unwrap_or_default());
+            format!(r#"<input type="number" step="any" id="{name}" name="{name}" value="{value}">"#)
+        }
+        "Map" => {
+            let value = value
+                .and_then(|v| serde_json::to_string_pretty(v).ok())
+                .unwrap_or_else(|| "{}".to_owned());
+            let value = escape_html(&value);
+            format!(r#"<textarea id="{name}" name="{name}" rows="6">{value}</textarea>"#)
+        }
+        _ if col.is_array_type() => {
+            let value = value
+                .and_then(|v| serde_json::to_string(v).ok())
+                .unwrap_or_else(|| "[]".to_owned());
+            let value = escape_html(&value);
+            format!(r#"<textarea id="{name}" name="{name}" rows="2">{value}</textarea>"#)
+        }
+        _ => {
+            let value = escape_html(&value.map(format_value).unwrap_or_default());
+            format!(r#"<input type="text" id="{name}" name="{name}" value="{value}">"#)
+        }
+    }
+}
+
+/// Formats a JSON value as a plain string.
+fn format_value(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Null => String::new(),
+        JsonValue::String(s) => s.to_owned(),
+        _ => value.to_string(),
+    }
+}
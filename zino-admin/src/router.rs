@@ -0,0 +1,164 @@
+cfg_if::cfg_if! {
+    if #[cfg(feature = "actix")] {
+        /// Creates a router configure of the admin dashboard for the models.
+        ///
+        /// # Examples
+        ///
+        /// ```rust,ignore
+        /// use zino::RouterConfigure;
+        /// use zino_model::{Tag, User};
+        ///
+        /// pub fn routes() -> Vec<RouterConfigure> {
+        ///     vec![zino_admin::admin_router![User, Tag]]
+        /// }
+        /// ```
+        #[macro_export]
+        macro_rules! admin_router {
+            ($($model:ty),+ $(,)?) => {{
+                fn admin_router(cfg: &mut $crate::__private::actix_web::web::ServiceConfig) {
+                    use $crate::__private::actix_web::web::{get, post};
+
+                    let prefix = $crate::route_prefix();
+                    cfg.route(prefix, get().to($crate::admin_index));
+                    cfg.route(&format!("{prefix}/audit-logs"), get().to($crate::admin_audit_logs));
+                    cfg.route(&format!("{prefix}/login"), post().to($crate::admin_login));
+                    cfg.route(&format!("{prefix}/logout"), post().to($crate::admin_logout));
+                    $(
+                        let model_name = $crate::AdminRegistry::register::<$model>();
+                        let path = format!("{prefix}/{model_name}");
+                        cfg.route(
+                            &format!("{path}/list"),
+                            get().to(<$model as $crate::AdminController<_>>::admin_list),
+                        );
+                        cfg.route(
+                            &format!("{path}/{{id}}/view"),
+                            get().to(<$model as $crate::AdminController<_>>::admin_view),
+                        );
+                        cfg.route(
+                            &format!("{path}/{{id}}/edit"),
+                            get().to(<$model as $crate::AdminController<_>>::admin_edit),
+                        );
+                        cfg.route(
+                            &format!("{path}/{{id}}/update"),
+                            post().to(<$model as $crate::AdminController<_>>::admin_update),
+                        );
+                        cfg.route(
+                            &format!("{path}/{{id}}/delete"),
+                            post().to(<$model as $crate::AdminController<_>>::admin_delete),
+                        );
+                    )+
+                }
+                admin_router as $crate::__private::RouterConfigure
+            }};
+        }
+    } else if #[cfg(feature = "axum")] {
+        /// Creates a router of the admin dashboard for the models.
+        ///
+        /// # Examples
+        ///
+        /// ```rust,ignore
+        /// use axum::{middleware::from_fn, Router};
+        /// use zino_model::{Tag, User};
+        ///
+        /// pub fn routes() -> Vec<Router> {
+        ///     let router = zino_admin::admin_router![User, Tag]
+        ///         .layer(from_fn(middleware::init_user_session));
+        ///     vec![router]
+        /// }
+        /// ```
+        #[macro_export]
+        macro_rules! admin_router {
+            ($($model:ty),+ $(,)?) => {{
+                use $crate::__private::axum::{
+                    routing::{get, post},
+                    Router,
+                };
+
+                let prefix = $crate::route_prefix();
+                let mut router = Router::new()
+                    .route(prefix, get($crate::admin_index))
+                    .route(&format!("{prefix}/audit-logs"), get($crate::admin_audit_logs))
+                    .route(&format!("{prefix}/login"), post($crate::admin_login))
+                    .route(&format!("{prefix}/logout"), post($crate::admin_logout));
+                $(
+                    let model_name = $crate::AdminRegistry::register::<$model>();
+                    let path = format!("{prefix}/{model_name}");
+                    router = router
+                        .route(
+                            &format!("{path}/list"),
+                            get(<$model as $crate::AdminController<_>>::admin_list),
+                        )
+                        .route(
+                            &format!("{path}/:id/view"),
+                            get(<$model as $crate::AdminController<_>>::admin_view),
+                        )
+                        .route(
+                            &format!("{path}/:id/edit"),
+                            get(<$model as $crate::AdminController<_>>::admin_edit),
+                        )
+                        .route(
+                            &format!("{path}/:id/update"),
+                            post(<$model as $crate::AdminController<_>>::admin_update),
+                        )
+                        .route(
+                            &format!("{path}/:id/delete"),
+                            post(<$model as $crate::AdminController<_>>::admin_delete),
+                        );
+                )+
+                router
+            }};
+        }
+    } else if #[cfg(feature = "ntex")] {
+        /// Creates a router configure of the admin dashboard for the models.
+        ///
+        /// # Examples
+        ///
+        /// ```rust,ignore
+        /// use zino::RouterConfigure;
+        /// use zino_model::{Tag, User};
+        ///
+        /// pub fn routes() -> Vec<RouterConfigure> {
+        ///     vec![zino_admin::admin_router![User, Tag]]
+        /// }
+        /// ```
+        #[macro_export]
+        macro_rules! admin_router {
+            ($($model:ty),+ $(,)?) => {{
+                fn admin_router(cfg: &mut $crate::__private::ntex::web::ServiceConfig) {
+                    use $crate::__private::ntex::web::{get, post};
+
+                    let prefix = $crate::route_prefix();
+                    cfg.route(prefix, get().to($crate::admin_index));
+                    cfg.route(&format!("{prefix}/audit-logs"), get().to($crate::admin_audit_logs));
+                    cfg.route(&format!("{prefix}/login"), post().to($crate::admin_login));
+                    cfg.route(&format!("{prefix}/logout"), post().to($crate::admin_logout));
+                    $(
+                        let model_name = $crate::AdminRegistry::register::<$model>();
+                        let path = format!("{prefix}/{model_name}");
+                        cfg.route(
+                            &format!("{path}/list"),
+                            get().to(<$model as $crate::AdminController<_>>::admin_list),
+                        );
+                        cfg.route(
+                            &format!("{path}/{{id}}/view"),
+                            get().to(<$model as $crate::AdminController<_>>::admin_view),
+                        );
+                        cfg.route(
+                            &format!("{path}/{{id}}/edit"),
+                            get().to(<$model as $crate::AdminController<_>>::admin_edit),
+                        );
+                        cfg.route(
+                            &format!("{path}/{{id}}/update"),
+                            post().to(<$model as $crate::AdminController<_>>::admin_update),
+                        );
+                        cfg.route(
+                            &format!("{path}/{{id}}/delete"),
+                            post().to(<$model as $crate::AdminController<_>>::admin_delete),
+                        );
+                    )+
+                }
+                admin_router as $crate::__private::RouterConfigure
+            }};
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use crate::config;
+use std::{
+    collections::HashMap,
+    sync::RwLock,
+    time::{Duration, Instant},
+};
+use zino_core::{request::RequestContext, LazyLock, Uuid};
+
+/// Cookie name of the admin session.
+pub(crate) const SESSION_COOKIE_NAME: &str = "zino-admin-session";
+
+/// Form field name of the CSRF token.
+pub(crate) const CSRF_TOKEN_FIELD: &str = "csrf_token";
+
+/// Maximum number of the admin sessions kept in memory.
+const MAX_SESSIONS: usize = 10_000;
+
+/// A cookie session of the admin dashboard.
+///
+/// The session is created for a user authenticated by the bearer token,
+/// so that the screens can be navigated in the browser with links and forms.
+#[derive(Debug, Clone)]
+pub(crate) struct AdminSession {
+    /// User ID.
+    user_id: String,
+    /// CSRF token for the forms.
+    csrf_token: String,
+    /// Expiration time.
+    expires_at: Instant,
+}
+
+impl AdminSession {
+    /// Returns the user ID.
+    #[inline]
+    pub(crate) fn user_id(&self) -> &str {
+        &self.user_id
+    }
+
+    /// Returns the CSRF token.
+    #[inline]
+    pub(crate) fn csrf_token(&self) -> &str {
+        &self.csrf_token
+    }
+
+    /// Returns `true` if the CSRF token matches the one of the session.
+    /// The tokens are compared in constant time.
+    pub(crate) fn verify_csrf_token(&self, csrf_token: &str) -> bool {
+        let expected = self.csrf_token.as_bytes();
+        let actual = csrf_token.as_bytes();
+        expected.len() == actual.len()
+            && expected
+                .iter()
+                .zip(actual)
+                .fold(0, |acc, (a, b)| acc | (a ^ b))
+                == 0
+    }
+}
+
+/// Creates a new session for the user, and returns the session ID.
+pub(crate) fn create_session(user_id: String) -> String {
+    let session_id = Uuid::new_v4().simple().to_string();
+    let session = AdminSession {
+        user_id,
+        csrf_token: Uuid::new_v4().simple().to_string(),
+        expires_at: Instant::now() + config::session_max_age(),
+    };
+    if let Ok(mut sessions) = ADMIN_SESSIONS.write() {
+        let now = Instant::now();
+        sessions.retain(|_, session| session.expires_at > now);
+        if sessions.len() >= MAX_SESSIONS {
+            let earliest = sessions
+                .iter()
+                .min_by_key(|(_, session)| session.expires_at)
+                .map(|(key, _)| key.clone());
+            if let Some(key) = earliest {
+                sessions.remove(&key);
+            }
+        }
+        sessions.insert(session_id.clone(), session);
+    }
+    session_id
+}
+
+/// Gets the unexpired session of the request by the session cookie.
+pub(crate) fn get_session(req: &impl RequestContext) -> Option<AdminSession> {
+    let cookie = req.get_cookie(SESSION_COOKIE_NAME)?;
+    let sessions = ADMIN_SESSIONS.read().ok()?;
+    sessions
+        .get(cookie.value())
+        .filter(|session| session.expires_at > Instant::now())
+        .cloned()
+}
+
+/// Removes the session of the request.
+pub(crate) fn remove_session(req: &impl RequestContext) {
+    if let Some(cookie) = req.get_cookie(SESSION_COOKIE_NAME) {
+        if let Ok(mut sessions) = ADMIN_SESSIONS.write() {
+            sessions.remove(cookie.value());
+        }
+    }
+}
+
+/// Formats the `set-cookie` header value for the session.
+/// An empty session ID expires the cookie.
+pub(crate) fn format_session_cookie(session_id: &str) -> String {
+    let max_age = if session_id.is_empty() {
+        Duration::ZERO
+    } else {
+        config::session_max_age()
+    };
+    let path = match config::route_prefix() {
+        "" => "/",
+        prefix => prefix,
+    };
+    format!(
+        "{SESSION_COOKIE_NAME}={session_id}; Path={path}; Max-Age={}; HttpOnly; Secure; SameSite=Strict",
+        max_age.as_secs()
+    )
+}
+
+/// Admin sessions.
+static ADMIN_SESSIONS: LazyLock<RwLock<HashMap<String, AdminSession>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
@@ -0,0 +1,155 @@
+use crate::{
+    extension::TomlTableExt,
+    request::RequestContext,
+    response::{Response, StatusCode},
+    state::State,
+    LazyLock,
+};
+use std::{
+    sync::atomic::{AtomicBool, Ordering::Relaxed},
+    time::Duration,
+};
+
+/// A runtime switch of the maintenance mode.
+///
+/// When the maintenance mode is enabled, all the requests except for the allowlisted routes
+/// are responded with `503 Service Unavailable`. A route ending with `/*`
+/// in the allowlist matches all the routes with the prefix.
+///
+/// ```toml
+/// [maintenance]
+/// enabled = false
+/// allowlist = ["/health", "/admin/*"]
+/// message = "The service is under maintenance, please retry after ${retry_after} seconds"
+/// template = "maintenance.html"
+/// retry-after = "10m"
+/// ```
+///
+/// The maintenance mode can also be toggled at runtime, such as in an admin endpoint:
+///
+/// ```rust,ignore
+/// use zino_core::application::MaintenanceMode;
+///
+/// async fn enable_maintenance(req: Request) -> Result {
+///     MaintenanceMode::enable();
+///     let res = Response::default().context(&req);
+///     Ok(res.into())
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct MaintenanceMode;
+
+impl MaintenanceMode {
+    /// Enables the maintenance mode.
+    #[inline]
+    pub fn enable() {
+        SHARED_MAINTENANCE.enabled.store(true, Relaxed);
+        tracing::warn!("maintenance mode is enabled");
+    }
+
+    /// Disables the maintenance mode.
+    #[inline]
+    pub fn disable() {
+        SHARED_MAINTENANCE.enabled.store(false, Relaxed);
+        tracing::warn!("maintenance mode is disabled");
+    }
+
+    /// Returns `true` if the maintenance mode is enabled.
+    #[inline]
+    pub fn is_enabled() -> bool {
+        SHARED_MAINTENANCE.enabled.load(Relaxed)
+    }
+
+    /// Returns `true` if the route is allowlisted in the maintenance mode.
+    pub fn is_allowed(route: &str) -> bool {
+        SHARED_MAINTENANCE.allowlist.iter().any(|pattern| {
+            if let Some(prefix) = pattern.strip_suffix("/*") {
+                route == prefix
+                    || route
+                        .strip_prefix(prefix)
+                        .is_some_and(|s| s.starts_with('/'))
+            } else {
+                route == pattern
+            }
+        })
+    }
+
+    /// Returns a `503 Service Unavailable` response for the request
+    /// if it should be rejected in the maintenance mode.
+    pub fn check<Ctx: RequestContext>(ctx: &Ctx) -> Option<Response<StatusCode>> {
+        if !Self::is_enabled() || Self::is_allowed(ctx.request_path()) {
+            return None;
+        }
+
+        let maintenance = &*SHARED_MAINTENANCE;
+        let retry_after = maintenance.retry_after.as_secs();
+        let message = maintenance
+            .message
+            .replace("${retry_after}", &retry_after.to_string());
+        let mut res = Response::new(StatusCode::SERVICE_UNAVAILABLE).context(ctx);
+        #[cfg(feature = "view")]
+        {
+            if let Some(template) = maintenance.template.as_deref() {
+                let data = serde_json::json!({
+                    "message": message,
+                    "retry_after": retry_after,
+                    "instance": ctx.instance(),
+                });
+                res = res.render(template, data);
+            } else {
+                res.set_message(message);
+            }
+        }
+        #[cfg(not(feature = "view"))]
+        res.set_message(message);
+        if retry_after > 0 {
+            res.insert_header("retry-after", retry_after);
+        }
+        Some(res)
+    }
+}
+
+/// Shared states of the maintenance mode.
+#[derive(Debug)]
+struct Maintenance {
+    /// A flag to indicate whether the maintenance mode is enabled.
+    enabled: AtomicBool,
+    /// Allowlisted routes.
+    allowlist: Vec<String>,
+    /// Message template.
+    message: String,
+    /// View template.
+    #[cfg_attr(not(feature = "view"), allow(dead_code))]
+    template: Option<String>,
+    /// Suggested delay for the clients to retry.
+    retry_after: Duration,
+}
+
+/// Shared maintenance mode.
+static SHARED_MAINTENANCE: LazyLock<Maintenance> = LazyLock::new(|| {
+    let mut maintenance = Maintenance {
+        enabled: AtomicBool::new(false),
+        allowlist: vec!["/health".to_owned(), "/admin/*".to_owned()],
+        message: "the service is under maintenance".to_owned(),
+        template: None,
+        retry_after: Duration::from_secs(600),
+    };
+    if let Some(config) = State::shared().get_config("maintenance") {
+        if let Some(enabled) = config.get_bool("enabled") {
+            maintenance.enabled = AtomicBool::new(enabled);
+        }
+        if let Some(allowlist) = config.get_str_array("allowlist") {
+            maintenance.allowlist = allowlist.into_iter().map(|s| s.to_owned()).collect();
+        }
+        if let Some(message) = config.get_str("message") {
+            maintenance.message = message.to_owned();
+        }
+        if let Some(template) = config.get_str("template") {
+            maintenance.template = Some(template.to_owned());
+        }
+        if let Some(retry_after) = config.get_duration("retry-after") {
+            maintenance.retry_after = retry_after;
+        }
+    }
+    maintenance
+});
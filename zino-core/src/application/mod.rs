@@ -53,6 +53,8 @@ use toml::value::Table;
 #[cfg(feature = "openapi")]
 use utoipa::openapi::{OpenApi, OpenApiBuilder};
 
+mod maintenance_mode;
+mod module;
 mod plugin;
 mod secret_key;
 mod server_tag;
@@ -72,6 +74,8 @@ pub(crate) mod http_client;
 
 pub(crate) use secret_key::SECRET_KEY;
 
+pub use maintenance_mode::MaintenanceMode;
+pub use module::Module;
 pub use plugin::Plugin;
 pub use server_tag::ServerTag;
 pub use static_record::StaticRecord;
@@ -175,6 +179,23 @@ pub trait Application {
         self
     }
 
+    /// Adds a custom module, which should be initialized when the application is loaded.
+    /// The routes and background jobs of the module should also be registered.
+    fn add_module<M: Module<Self>>(self, module: M) -> Self
+    where
+        Self: Sized;
+
+    /// Provides a typed state container which can be injected by
+    /// [`State::inject()`] in the handlers and hooks.
+    #[inline]
+    fn with_state<T: Send + Sync + 'static>(self, state: T) -> Self
+    where
+        Self: Sized,
+    {
+        State::provide(state);
+        self
+    }
+
     /// Gets the [OpenAPI](https://spec.openapis.org/oas/latest.html) document.
     #[cfg(feature = "openapi")]
     #[inline]
@@ -289,10 +310,31 @@ pub trait Application {
     /// Loads resources after booting the application.
     #[inline]
     async fn load() {
+        crate::datetime::sync_ntp_clock().await;
         #[cfg(feature = "oidc")]
         rauthy_client::setup::<Self>().await;
         #[cfg(feature = "orm")]
-        crate::orm::GlobalPool::connect_all().await;
+        {
+            crate::orm::GlobalPool::connect_all().await;
+            crate::orm::GlobalPool::warm_up_all().await;
+            if let Err(err) = crate::orm::verify_registered_models().await {
+                tracing::error!("{err}");
+                panic!("fail to load the application: {err}");
+            }
+
+            let two_phase_commit = State::shared()
+                .get_config("database")
+                .and_then(|config| config.get_bool("two-phase-commit"))
+                .unwrap_or_default();
+            if two_phase_commit {
+                match crate::orm::DistributedTransaction::recover().await {
+                    Ok(num_transactions) => {
+                        tracing::info!(num_transactions, "in-doubt transactions are recovered");
+                    }
+                    Err(err) => tracing::error!("fail to recover in-doubt transactions: {err}"),
+                }
+            }
+        }
     }
 
     /// Handles the graceful shutdown.
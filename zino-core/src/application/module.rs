@@ -0,0 +1,86 @@
+use super::Application;
+use crate::{error::Error, extension::TomlTableExt, schedule::AsyncJob, state::State, BoxFuture};
+use toml::value::Table;
+
+/// A reusable module packaging the routes, background jobs and lifecycle hooks,
+/// which can be registered by [`add_module()`](Application::add_module).
+///
+/// The modules are initialized in the order of registration after the application is loaded,
+/// and they are shut down in the reverse order after the servers have been stopped.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino::{prelude::*, Cluster};
+/// use zino_core::application::Module;
+///
+/// pub struct Storage;
+///
+/// impl Module<Cluster> for Storage {
+///     fn name(&self) -> &'static str {
+///         "storage"
+///     }
+///
+///     fn init(&self) -> BoxFuture<'_, Result<(), Error>> {
+///         Box::pin(async {
+///             let root = self.config().and_then(|config| config.get_str("root"));
+///             Object::migrate(root).await
+///         })
+///     }
+///
+///     fn routes(&self) -> Vec<Router> {
+///         vec![Router::new().route("/storage/:key", get(Object::view))]
+///     }
+///
+///     fn jobs(&self) -> Vec<AsyncJob> {
+///         vec![AsyncJob::new("0 0 * * * *", Object::cleanup)]
+///     }
+/// }
+///
+/// fn main() {
+///     zino::Cluster::boot()
+///         .add_module(Storage)
+///         .register(router::routes())
+///         .run()
+/// }
+/// ```
+pub trait Module<App: Application + ?Sized>: Send + Sync + 'static {
+    /// Returns the module name.
+    fn name(&self) -> &'static str;
+
+    /// Returns a reference to the config section `[modules.{name}]` of the module.
+    #[inline]
+    fn config(&self) -> Option<&'static Table> {
+        State::shared()
+            .config()
+            .get_table("modules")?
+            .get_table(self.name())
+    }
+
+    /// Initializes the module, such as running the migrations.
+    #[inline]
+    fn init(&self) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Returns the routes registered for all the servers.
+    #[inline]
+    fn routes(&self) -> App::Routes
+    where
+        App::Routes: Default,
+    {
+        App::Routes::default()
+    }
+
+    /// Returns the background jobs.
+    #[inline]
+    fn jobs(&self) -> Vec<AsyncJob> {
+        Vec::new()
+    }
+
+    /// Shuts down the module gracefully.
+    #[inline]
+    fn shutdown(&self) -> BoxFuture<'_> {
+        Box::pin(async {})
+    }
+}
@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[cfg(feature = "jwt")]
+use crate::{auth::JwtClaims, extension::JsonObjectExt};
+
+/// A set of scopes granted to an access key or a JWT token, such as `tasks:read`.
+///
+/// A granted scope supports the wildcard matching: `*` grants all the scopes,
+/// and `tasks:*` grants all the scopes with the prefix `tasks:`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessScope(Vec<String>);
+
+impl AccessScope {
+    /// Creates a new instance.
+    #[inline]
+    pub fn new(scopes: Vec<String>) -> Self {
+        Self(scopes)
+    }
+
+    /// Parses the scopes separated by whitespaces or commas.
+    pub fn parse(scopes: &str) -> Self {
+        let scopes = scopes
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_owned())
+            .collect();
+        Self(scopes)
+    }
+
+    /// Constructs an instance from the `scope` or `scopes` claim of a JWT token.
+    #[cfg(feature = "jwt")]
+    pub fn from_jwt_claims(claims: &JwtClaims) -> Self {
+        let data = claims.data();
+        if let Some(scopes) = data.get_str_array("scopes") {
+            Self(scopes.into_iter().map(|s| s.to_owned()).collect())
+        } else if let Some(scope) = data.get_str("scope").or_else(|| data.get_str("scopes")) {
+            Self::parse(scope)
+        } else {
+            Self::default()
+        }
+    }
+
+    /// Adds a scope.
+    #[inline]
+    pub fn push(&mut self, scope: impl Into<String>) {
+        self.0.push(scope.into());
+    }
+
+    /// Returns the granted scopes.
+    #[inline]
+    pub fn as_slice(&self) -> &[String] {
+        &self.0
+    }
+
+    /// Returns `true` if there are no scopes granted.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns `true` if the required scope is granted.
+    ///
+    /// The wildcard `*` is only allowed as a whole segment at the end of a granted scope,
+    /// so `tasks*` is matched literally and does not grant `taskssecret:read`.
+    pub fn contains(&self, scope: &str) -> bool {
+        self.0.iter().any(|granted| {
+            if granted == "*" {
+                true
+            } else if let Some(prefix) = granted.strip_suffix(":*") {
+                scope
+                    .strip_prefix(prefix)
+                    .is_some_and(|s| s.starts_with(':'))
+            } else {
+                granted == scope
+            }
+        })
+    }
+
+    /// Returns `true` if all of the required scopes are granted.
+    #[inline]
+    pub fn contains_all(&self, scopes: &[&str]) -> bool {
+        scopes.iter().all(|scope| self.contains(scope))
+    }
+}
+
+impl fmt::Display for AccessScope {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0.join(" "))
+    }
+}
+
+impl From<Vec<String>> for AccessScope {
+    #[inline]
+    fn from(scopes: Vec<String>) -> Self {
+        Self(scopes)
+    }
+}
+
+impl From<&str> for AccessScope {
+    #[inline]
+    fn from(scopes: &str) -> Self {
+        Self::parse(scopes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AccessScope;
+
+    #[test]
+    fn it_matches_wildcard_scopes() {
+        let scope = AccessScope::parse("tasks:* users:read");
+        assert!(scope.contains("tasks:read"));
+        assert!(scope.contains("tasks:comments:write"));
+        assert!(scope.contains("users:read"));
+        assert!(!scope.contains("tasks"));
+        assert!(!scope.contains("taskssecret:read"));
+        assert!(!scope.contains("users:write"));
+
+        let scope = AccessScope::parse("tasks*");
+        assert!(scope.contains("tasks*"));
+        assert!(!scope.contains("taskssecret:read"));
+
+        let scope = AccessScope::parse("*");
+        assert!(scope.contains("taskssecret:read"));
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{bail, crypto, datetime::DateTime, encoding::hex, error::Error, warn, Uuid};
+use std::{fmt, time::Duration};
+
+/// A signed token for a one-off action, such as
+/// an invitation, an email verification or a password reset.
+///
+/// The token is encrypted with an AEAD cipher, so that it can not be forged or tampered with.
+/// It is hex-encoded and safe to be used in URLs. The `nonce` is unique for each token,
+/// which can be used to record the token for single-use semantics.
+#[derive(Debug, Clone)]
+pub struct ActionToken {
+    /// Action.
+    action: String,
+    /// Subject.
+    subject: String,
+    /// Nonce.
+    nonce: Uuid,
+    /// Expires time.
+    expires_at: DateTime,
+    /// Token.
+    token: String,
+}
+
+impl ActionToken {
+    /// Attempts to create a new instance for the action on the subject.
+    pub fn try_new(
+        action: impl Into<String>,
+        subject: impl Into<String>,
+        expires_in: Duration,
+        key: impl AsRef<[u8]>,
+    ) -> Result<Self, Error> {
+        let action = action.into();
+        if action.contains(':') {
+            bail!("the action `{}` should not contain `:`", action);
+        }
+
+        let subject = subject.into();
+        let nonce = Uuid::now_v7();
+        let expires_at = DateTime::now() + expires_in;
+        let timestamp = expires_at.timestamp();
+        let signature = format!("{action}:{nonce}:{timestamp}:{subject}");
+        let ciphertext = crypto::encrypt(signature.as_bytes(), key.as_ref())?;
+        let token = hex::encode(ciphertext);
+        Ok(Self {
+            action,
+            subject,
+            nonce,
+            expires_at,
+            token,
+        })
+    }
+
+    /// Parses the token for the action with the encryption key.
+    pub fn parse_with(token: &str, action: &str, key: impl AsRef<[u8]>) -> Result<Self, Error> {
+        let ciphertext = hex::decode(token).map_err(|err| warn!("invalid token: {}", err))?;
+        let signature = crypto::decrypt(&ciphertext, key.as_ref())
+            .map_err(|_| warn!("fail to decrypt the token"))?;
+        let signature_str = String::from_utf8_lossy(&signature);
+        let mut parts = signature_str.splitn(4, ':');
+        let (Some(token_action), Some(nonce), Some(timestamp), Some(subject)) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            bail!("invalid format of the token");
+        };
+        if token_action != action {
+            bail!("the token is not issued for the action `{}`", action);
+        }
+
+        let nonce = nonce.parse()?;
+        let expires_at = DateTime::from_timestamp(timestamp.parse()?);
+        if expires_at <= DateTime::trusted_now() {
+            bail!("the token has expired at `{}`", expires_at);
+        }
+        Ok(Self {
+            action: token_action.to_owned(),
+            subject: subject.to_owned(),
+            nonce,
+            expires_at,
+            token: token.to_owned(),
+        })
+    }
+
+    /// Returns the action.
+    #[inline]
+    pub fn action(&self) -> &str {
+        self.action.as_str()
+    }
+
+    /// Returns the subject.
+    #[inline]
+    pub fn subject(&self) -> &str {
+        self.subject.as_str()
+    }
+
+    /// Returns the nonce.
+    #[inline]
+    pub fn nonce(&self) -> &Uuid {
+        &self.nonce
+    }
+
+    /// Returns the expires time.
+    #[inline]
+    pub fn expires_at(&self) -> DateTime {
+        self.expires_at
+    }
+
+    /// Returns `true` if the token has expired.
+    #[inline]
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= DateTime::trusted_now()
+    }
+
+    /// Returns a string slice.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        self.token.as_str()
+    }
+}
+
+impl fmt::Display for ActionToken {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ActionToken;
+    use std::time::Duration;
+
+    #[test]
+    fn it_parses_action_tokens() {
+        let key = b"01234567890123456789012345678901";
+        let expires_in = Duration::from_secs(60);
+        let token = ActionToken::try_new("verify_email", "alice@example.com", expires_in, key)
+            .expect("fail to create an action token");
+        let parsed_token = ActionToken::parse_with(token.as_str(), "verify_email", key)
+            .expect("fail to parse the action token");
+        assert_eq!(parsed_token.subject(), "alice@example.com");
+        assert_eq!(parsed_token.nonce(), token.nonce());
+        assert!(ActionToken::parse_with(token.as_str(), "reset_password", key).is_err());
+        assert!(ActionToken::parse_with(token.as_str(), "verify_email", b"invalid key").is_err());
+    }
+}
@@ -1,5 +1,8 @@
 use super::{AccessKeyId, SecretAccessKey};
-use crate::{datetime::DateTime, encoding::base64, error::Error, validation::Validation, Map};
+use crate::{
+    datetime::DateTime, encoding::base64, error::Error, extension::TomlTableExt, state::State,
+    validation::Validation, LazyLock, Map,
+};
 use hmac::{
     digest::{FixedOutput, KeyInit, MacMarker, Update},
     Mac,
@@ -26,6 +29,8 @@ pub struct Authentication {
     date_header: (&'static str, DateTime),
     /// Expires.
     expires: Option<DateTime>,
+    /// Nonce.
+    nonce: Option<String>,
     /// Canonicalized headers.
     headers: Vec<(String, String)>,
     /// Canonicalized resource.
@@ -46,6 +51,7 @@ impl Authentication {
             content_type: None,
             date_header: ("date", DateTime::now()),
             expires: None,
+            nonce: None,
             headers: Vec::new(),
             resource: String::new(),
         }
@@ -99,6 +105,13 @@ impl Authentication {
         self.expires = expires;
     }
 
+    /// Sets the nonce. It is included in the string to sign,
+    /// and each nonce is accepted at most once in the validation.
+    #[inline]
+    pub fn set_nonce(&mut self, nonce: Option<String>) {
+        self.nonce = nonce;
+    }
+
     /// Sets the canonicalized headers.
     /// The header is matched if it has a prefix in the filter list.
     #[inline]
@@ -159,6 +172,12 @@ impl Authentication {
         self.signature.as_str()
     }
 
+    /// Returns the nonce.
+    #[inline]
+    pub fn nonce(&self) -> Option<&str> {
+        self.nonce.as_deref()
+    }
+
     /// Returns an `authorization` header value.
     #[inline]
     pub fn authorization(&self) -> String {
@@ -214,6 +233,13 @@ impl Authentication {
             sign_parts.push(date);
         }
 
+        // Nonce
+        if let Some(nonce) = self.nonce.as_ref() {
+            if !self.headers.iter().any(|(name, _)| name == "x-nonce") {
+                sign_parts.push(format!("x-nonce:{nonce}"));
+            }
+        }
+
         // Canonicalized headers
         let headers = self
             .headers
@@ -240,14 +266,29 @@ impl Authentication {
     }
 
     /// Validates the signature using the secret access key.
+    /// The nonce is consumed if the signature is valid.
+    #[inline]
     pub fn validate_with<H>(&self, secret_access_key: &SecretAccessKey) -> Validation
+    where
+        H: FixedOutput + KeyInit + MacMarker + Update,
+    {
+        self.validate_signature::<H>(secret_access_key, true)
+    }
+
+    /// Validates the signature using the secret access key,
+    /// and consumes the nonce if `consume_nonce` is `true`.
+    pub(crate) fn validate_signature<H>(
+        &self,
+        secret_access_key: &SecretAccessKey,
+        consume_nonce: bool,
+    ) -> Validation
     where
         H: FixedOutput + KeyInit + MacMarker + Update,
     {
         let mut validation = Validation::new();
-        let current = DateTime::now();
+        let current = DateTime::trusted_now();
         let date = self.date_header.1;
-        let max_tolerance = Duration::from_secs(900);
+        let max_tolerance = max_clock_skew();
         if date < current && date < current - max_tolerance
             || date > current && date > current + max_tolerance
         {
@@ -268,6 +309,33 @@ impl Authentication {
         {
             validation.record("signature", "invalid signature");
         }
+
+        // The nonce is recorded only if the request is valid,
+        // so that it can not be exhausted by the forged requests.
+        if let Some(nonce) = self.nonce() {
+            if consume_nonce && validation.is_success() {
+                let key = format!("nonce:{}:{}", self.access_key_id(), nonce);
+                if !super::try_insert_nonce(&key, max_tolerance * 2) {
+                    validation.record("nonce", "the request has been replayed");
+                }
+            }
+        } else if super::is_nonce_required() {
+            validation.record("nonce", "should be nonempty");
+        }
         validation
     }
 }
+
+/// Returns the max clock skew for the signed requests.
+#[inline]
+pub(crate) fn max_clock_skew() -> Duration {
+    *MAX_CLOCK_SKEW
+}
+
+/// Max clock skew for the signed requests.
+static MAX_CLOCK_SKEW: LazyLock<Duration> = LazyLock::new(|| {
+    State::shared()
+        .get_config("authentication")
+        .and_then(|config| config.get_duration("max-clock-skew"))
+        .unwrap_or_else(|| Duration::from_secs(900))
+});
@@ -43,7 +43,9 @@ impl<T: Default + Serialize + DeserializeOwned> JwtClaims<T> {
         Self(claims)
     }
 
-    /// Generates an access token signed with the shared secret access key.
+    /// Generates a refresh token signed with the shared secret access key.
+    /// The subject and nonce are kept so that the refreshed token
+    /// can be bound to the same device.
     pub fn refresh_token(&self) -> Result<String, Error> {
         let mut claims = Claims::create((*DEFAULT_REFRESH_INTERVAL).into());
         claims.invalid_before = self
@@ -51,6 +53,7 @@ impl<T: Default + Serialize + DeserializeOwned> JwtClaims<T> {
             .expires_at
             .map(|max_age| max_age - (*DEFAULT_TIME_TOLERANCE).into());
         claims.subject = self.0.subject.as_ref().cloned();
+        claims.nonce = self.0.nonce.as_ref().cloned();
         JwtClaims::shared_key()
             .authenticate(claims)
             .map_err(|err| Error::new(err.to_string()))
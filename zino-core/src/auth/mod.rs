@@ -1,19 +1,27 @@
 //! Authentication and authorization.
 
 mod access_key;
+mod access_scope;
+mod action_token;
 mod authentication;
 mod authorization_provider;
 mod client_credentials;
+mod nonce_store;
 mod security_token;
 mod session_id;
 mod user_session;
 
+pub(crate) use authentication::max_clock_skew;
+pub(crate) use nonce_store::{is_nonce_required, try_insert_nonce};
 pub(crate) use security_token::ParseSecurityTokenError;
 
 pub use access_key::{AccessKeyId, SecretAccessKey};
+pub use access_scope::AccessScope;
+pub use action_token::ActionToken;
 pub use authentication::Authentication;
 pub use authorization_provider::AuthorizationProvider;
 pub use client_credentials::ClientCredentials;
+pub use nonce_store::{set_nonce_store, MemoryNonceStore, NonceStore};
 pub use security_token::SecurityToken;
 pub use session_id::SessionId;
 pub use user_session::UserSession;
@@ -0,0 +1,108 @@
+use crate::{extension::TomlTableExt, state::State, LazyLock};
+use parking_lot::{Mutex, RwLock};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// A store of the request nonces, which is used to accept each signed request at most once.
+///
+/// The default store is an in-memory expiring cache. For a cluster of servers,
+/// a shared store such as Redis should be set by [`set_nonce_store()`].
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use std::time::Duration;
+/// use zino_core::auth::{self, NonceStore};
+///
+/// struct RedisNonceStore(redis::Client);
+///
+/// impl NonceStore for RedisNonceStore {
+///     fn try_insert(&self, key: &str, ttl: Duration) -> bool {
+///         let Ok(mut conn) = self.0.get_connection() else {
+///             return false;
+///         };
+///         redis::cmd("SET")
+///             .arg(key)
+///             .arg(1)
+///             .arg("NX")
+///             .arg("EX")
+///             .arg(ttl.as_secs())
+///             .query::<Option<String>>(&mut conn)
+///             .is_ok_and(|reply| reply.is_some())
+///     }
+/// }
+///
+/// auth::set_nonce_store(RedisNonceStore(client));
+/// ```
+pub trait NonceStore: Send + Sync {
+    /// Attempts to insert the nonce key with a TTL. It returns `false`
+    /// if the key has already been inserted and not expired.
+    fn try_insert(&self, key: &str, ttl: Duration) -> bool;
+}
+
+/// An in-memory store of the request nonces.
+#[derive(Debug, Default)]
+pub struct MemoryNonceStore {
+    /// Nonce keys and the expiration time.
+    nonces: Mutex<HashMap<String, Instant>>,
+}
+
+impl MemoryNonceStore {
+    /// Creates a new instance.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl NonceStore for MemoryNonceStore {
+    fn try_insert(&self, key: &str, ttl: Duration) -> bool {
+        let now = Instant::now();
+        let mut nonces = self.nonces.lock();
+        if nonces.len() >= PURGE_THRESHOLD {
+            nonces.retain(|_, expires_at| *expires_at > now);
+        }
+        match nonces.get(key) {
+            Some(expires_at) if *expires_at > now => false,
+            _ => {
+                nonces.insert(key.to_owned(), now + ttl);
+                true
+            }
+        }
+    }
+}
+
+/// Sets the shared nonce store.
+#[inline]
+pub fn set_nonce_store(store: impl NonceStore + 'static) {
+    *NONCE_STORE.write() = Box::new(store);
+}
+
+/// Attempts to insert the nonce key into the shared store.
+#[inline]
+pub(crate) fn try_insert_nonce(key: &str, ttl: Duration) -> bool {
+    NONCE_STORE.read().try_insert(key, ttl)
+}
+
+/// Returns `true` if the nonce is required for the signed requests.
+#[inline]
+pub(crate) fn is_nonce_required() -> bool {
+    *NONCE_REQUIRED
+}
+
+/// Number of nonces to trigger the purging of expired ones.
+const PURGE_THRESHOLD: usize = 10000;
+
+/// Shared nonce store.
+static NONCE_STORE: LazyLock<RwLock<Box<dyn NonceStore>>> =
+    LazyLock::new(|| RwLock::new(Box::new(MemoryNonceStore::new())));
+
+/// A flag to require the nonce for the signed requests.
+static NONCE_REQUIRED: LazyLock<bool> = LazyLock::new(|| {
+    State::shared()
+        .get_config("authentication")
+        .and_then(|config| config.get_bool("require-nonce"))
+        .unwrap_or_default()
+});
@@ -53,7 +53,7 @@ impl SecurityToken {
     /// Returns `true` if the security token has expired.
     #[inline]
     pub fn is_expired(&self) -> bool {
-        self.expires_at <= DateTime::now()
+        self.expires_at <= DateTime::trusted_now()
     }
 
     /// Returns a string slice.
@@ -73,7 +73,7 @@ impl SecurityToken {
                 .parse::<i64>()
                 .map_err(|err| ParseExpiresError(err.into()))?;
             let expires_at = DateTime::from_timestamp(timestamp);
-            if expires_at >= DateTime::now() {
+            if expires_at >= DateTime::trusted_now() {
                 Ok(Self {
                     access_key_id: access_key_id.into(),
                     expires_at,
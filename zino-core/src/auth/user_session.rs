@@ -1,4 +1,4 @@
-use super::{AccessKeyId, SessionId};
+use super::{AccessKeyId, AccessScope, SessionId};
 use crate::{application::APP_DOMAIN, crypto::Digest};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
@@ -19,6 +19,9 @@ pub struct UserSession<U, R = String, T = U> {
     roles: Vec<R>,
     /// Tenant ID.
     tenant_id: Option<T>,
+    /// Granted scopes.
+    #[serde(default)]
+    scope: AccessScope,
 }
 
 impl<U, R, T> UserSession<U, R, T> {
@@ -31,6 +34,7 @@ impl<U, R, T> UserSession<U, R, T> {
             access_key_id: None,
             roles: Vec::new(),
             tenant_id: None,
+            scope: AccessScope::default(),
         }
     }
 
@@ -62,6 +66,12 @@ impl<U, R, T> UserSession<U, R, T> {
         self.tenant_id = Some(tenant_id);
     }
 
+    /// Sets the granted scopes.
+    #[inline]
+    pub fn set_scope(&mut self, scope: impl Into<AccessScope>) {
+        self.scope = scope.into();
+    }
+
     /// Returns the user ID.
     #[inline]
     pub fn user_id(&self) -> &U {
@@ -91,6 +101,18 @@ impl<U, R, T> UserSession<U, R, T> {
     pub fn roles(&self) -> &[R] {
         &self.roles
     }
+
+    /// Returns the granted scopes.
+    #[inline]
+    pub fn scope(&self) -> &AccessScope {
+        &self.scope
+    }
+
+    /// Returns `true` if the required scope is granted.
+    #[inline]
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scope.contains(scope)
+    }
 }
 
 impl<U, R, T> UserSession<U, R, T>
@@ -124,6 +146,7 @@ where
         {
             user_session.set_tenant_id(tenant_id);
         }
+        user_session.set_scope(AccessScope::from_jwt_claims(&claims));
         Ok(user_session)
     }
 }
@@ -114,6 +114,18 @@ impl<T> CloudEvent<T> {
         self.session_id.as_deref()
     }
 
+    /// Returns a reference to the event data.
+    #[inline]
+    pub fn data(&self) -> &JsonValue {
+        &self.data
+    }
+
+    /// Returns the timestamp.
+    #[inline]
+    pub fn timestamp(&self) -> DateTime {
+        self.timestamp
+    }
+
     /// Stringifies the event data as `String`.
     #[inline]
     pub fn stringify_data(&self) -> String {
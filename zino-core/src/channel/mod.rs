@@ -1,9 +1,13 @@
-//! Cloud events and subscriptions.
+//! Cloud events, subscriptions and projections.
 
 mod cloud_event;
+mod projection;
 mod subscription;
 
 pub use cloud_event::CloudEvent;
+pub use projection::{
+    new_model_event, OutboxLoader, Projection, ProjectionHandler, ProjectionResetter,
+};
 pub use subscription::Subscription;
 
 #[cfg(feature = "flume")]
@@ -0,0 +1,198 @@
+use super::CloudEvent;
+use crate::{error::Error, warn, BoxFuture, JsonValue, SharedString, Uuid};
+use parking_lot::RwLock;
+
+/// A function pointer of applying a cloud event to the read model of a projection.
+pub type ProjectionHandler = fn(event: CloudEvent) -> BoxFuture<'static, Result<(), Error>>;
+
+/// A function pointer of clearing the read model of a projection before a replay.
+pub type ProjectionResetter = fn() -> BoxFuture<'static, Result<(), Error>>;
+
+/// A function pointer of loading the events from an outbox in batches,
+/// where `after` is the ID of the last event in the previous batch.
+pub type OutboxLoader =
+    fn(after: Option<String>, limit: usize) -> BoxFuture<'static, Result<Vec<CloudEvent>, Error>>;
+
+/// A projection which consumes the model events and maintains
+/// a denormalized read model, such as a `user_task_counts` table.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino_core::channel::Projection;
+///
+/// Projection::new("user_task_counts", |event| {
+///     Box::pin(async move { UserTaskCount::apply(event).await })
+/// })
+/// .subscribe("task.*")
+/// .with_resetter(|| Box::pin(UserTaskCount::truncate()))
+/// .register();
+///
+/// // Rebuilds the read model from the outbox.
+/// let num_events = Projection::replay("user_task_counts").await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Projection {
+    /// Name.
+    name: &'static str,
+    /// Subscribed event types.
+    event_types: Vec<SharedString>,
+    /// Handler.
+    handler: ProjectionHandler,
+    /// Optional resetter.
+    resetter: Option<ProjectionResetter>,
+}
+
+impl Projection {
+    /// Creates a new instance with the handler.
+    #[inline]
+    pub fn new(name: &'static str, handler: ProjectionHandler) -> Self {
+        Self {
+            name,
+            event_types: Vec::new(),
+            handler,
+            resetter: None,
+        }
+    }
+
+    /// Subscribes the event type. A trailing `*` matches the event types with the prefix,
+    /// such as `task.*`. It subscribes all the events if no event types are specified.
+    #[inline]
+    pub fn subscribe(mut self, event_type: impl Into<SharedString>) -> Self {
+        self.event_types.push(event_type.into());
+        self
+    }
+
+    /// Sets the resetter for clearing the read model.
+    #[inline]
+    pub fn with_resetter(mut self, resetter: ProjectionResetter) -> Self {
+        self.resetter = Some(resetter);
+        self
+    }
+
+    /// Returns the name.
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns `true` if the projection subscribes the event type.
+    pub fn is_subscribed(&self, event_type: &str) -> bool {
+        self.event_types.is_empty()
+            || self.event_types.iter().any(|s| match s.strip_suffix('*') {
+                Some(prefix) => event_type.starts_with(prefix),
+                None => s == event_type,
+            })
+    }
+
+    /// Registers the projection. It replaces the existing one with the same name.
+    pub fn register(self) {
+        let mut projections = PROJECTIONS.write();
+        projections.retain(|projection| projection.name != self.name);
+        projections.push(self);
+    }
+
+    /// Sets the loader for replaying the events from an outbox.
+    #[inline]
+    pub fn set_outbox_loader(loader: OutboxLoader) {
+        *OUTBOX_LOADER.write() = Some(loader);
+    }
+
+    /// Dispatches the event to the registered projections which subscribe it.
+    /// All the projections are run even if some of them fail,
+    /// and the last error is returned.
+    pub async fn dispatch(event: &CloudEvent) -> Result<(), Error> {
+        let event_type = event.event_type();
+        let handlers = PROJECTIONS
+            .read()
+            .iter()
+            .filter(|projection| projection.is_subscribed(event_type))
+            .map(|projection| (projection.name, projection.handler))
+            .collect::<Vec<_>>();
+        let mut result = Ok(());
+        for (name, handler) in handlers {
+            if let Err(err) = handler(event.clone()).await {
+                tracing::error!(
+                    projection = name,
+                    event_id = event.id(),
+                    event_type,
+                    "fail to apply the event: {err}"
+                );
+                result = Err(err);
+            }
+        }
+        result
+    }
+
+    /// Rebuilds the projection by resetting the read model and replaying the events
+    /// from the outbox. It returns the number of events applied.
+    pub async fn replay(name: &str) -> Result<usize, Error> {
+        let Some(projection) = PROJECTIONS
+            .read()
+            .iter()
+            .find(|projection| projection.name == name)
+            .cloned()
+        else {
+            return Err(warn!(
+                "404 Not Found: the projection `{}` does not exist",
+                name
+            ));
+        };
+        let Some(loader) = *OUTBOX_LOADER.read() else {
+            return Err(warn!("the outbox loader has not been set"));
+        };
+        if let Some(resetter) = projection.resetter {
+            resetter().await?;
+        }
+
+        let mut num_events = 0;
+        let mut last_event_id = None;
+        loop {
+            let events = loader(last_event_id.take(), REPLAY_BATCH_SIZE).await?;
+            let num_loaded = events.len();
+            for event in events {
+                let event_id = event.id().to_owned();
+                if projection.is_subscribed(event.event_type()) {
+                    (projection.handler)(event).await?;
+                    num_events += 1;
+                }
+                last_event_id = Some(event_id);
+            }
+            if num_loaded < REPLAY_BATCH_SIZE {
+                break;
+            }
+        }
+        tracing::info!(
+            projection = name,
+            num_events,
+            "the projection has been replayed"
+        );
+        Ok(num_events)
+    }
+}
+
+/// Creates a new model event with the type `{model_name}.{action}`,
+/// such as `task.created`. The subject is the model ID.
+pub fn new_model_event(
+    model_name: &str,
+    action: &str,
+    model_id: impl ToString,
+    data: impl Into<JsonValue>,
+) -> CloudEvent {
+    let model_id = model_id.to_string();
+    let event_id = Uuid::now_v7();
+    let event_type = format!("{model_name}.{action}");
+    let mut event = CloudEvent::new(event_id, model_name, event_type);
+    event.set_subject(model_id);
+    event.set_data(data);
+    event
+}
+
+/// Batch size for replaying the events.
+const REPLAY_BATCH_SIZE: usize = 1000;
+
+/// Registered projections.
+static PROJECTIONS: RwLock<Vec<Projection>> = RwLock::new(Vec::new());
+
+/// Loader for the outbox events.
+static OUTBOX_LOADER: RwLock<Option<OutboxLoader>> = RwLock::new(None);
@@ -1,15 +1,13 @@
-use crate::{encoding::base64, error::Error};
+use crate::{encoding::base64, error::Error, extension::TomlTableExt, state::State, LazyLock};
 use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
-    Argon2,
+    Algorithm, Argon2, Params, ParamsBuilder, Version,
 };
 
 /// Encrypts the hashed password using `Argon2id`.
 pub(crate) fn encrypt_hashed_password(hashed_password: &[u8], key: &[u8]) -> Result<String, Error> {
     let salt = SaltString::generate(&mut OsRng);
-    let password_hash = Argon2::default()
-        .hash_password(hashed_password, &salt)?
-        .to_string();
+    let password_hash = ARGON2.hash_password(hashed_password, &salt)?.to_string();
     let ciphertext = super::encrypt(password_hash.as_bytes(), key)?;
     Ok(base64::encode(ciphertext))
 }
@@ -21,6 +19,9 @@ pub(crate) fn encrypt_raw_password(raw_password: &[u8], key: &[u8]) -> Result<St
 }
 
 /// Verifies the hashed password using `Argon2id`.
+///
+/// The hash outputs are compared in constant time. The parameters are read from
+/// the password hash, so the passwords hashed with the legacy parameters can also be verified.
 pub(crate) fn verify_hashed_password(
     hashed_password: &[u8],
     encrypted_password: &[u8],
@@ -30,7 +31,7 @@ pub(crate) fn verify_hashed_password(
     let password_hash = super::decrypt(&ciphertext, key)?;
     let password_hash_str = String::from_utf8_lossy(&password_hash);
     let parsed_hash = PasswordHash::new(&password_hash_str)?;
-    Argon2::default().verify_password(hashed_password, &parsed_hash)?;
+    ARGON2.verify_password(hashed_password, &parsed_hash)?;
     Ok(true)
 }
 
@@ -43,3 +44,67 @@ pub(crate) fn verify_raw_password(
     let hashed_password = base64::encode(super::digest(raw_password));
     verify_hashed_password(hashed_password.as_bytes(), encrypted_password, key)
 }
+
+/// Returns `true` if the encrypted password should be rehashed because
+/// the algorithm, version or parameters differ from the current settings.
+pub(crate) fn password_needs_rehash(encrypted_password: &[u8], key: &[u8]) -> Result<bool, Error> {
+    let ciphertext = base64::decode(encrypted_password)?;
+    let password_hash = super::decrypt(&ciphertext, key)?;
+    let password_hash_str = String::from_utf8_lossy(&password_hash);
+    let parsed_hash = PasswordHash::new(&password_hash_str)?;
+    if parsed_hash.algorithm != Algorithm::Argon2id.ident()
+        || parsed_hash.version != Some(Version::V0x13.into())
+    {
+        return Ok(true);
+    }
+
+    let params = Params::try_from(&parsed_hash)?;
+    let current_params = ARGON2.params();
+    Ok(params.m_cost() != current_params.m_cost()
+        || params.t_cost() != current_params.t_cost()
+        || params.p_cost() != current_params.p_cost())
+}
+
+/// Performs a dummy verification to make the time spent on a nonexistent account
+/// indistinguishable from that on a wrong password.
+pub(crate) fn verify_dummy_password(raw_password: &[u8]) {
+    let hashed_password = base64::encode(super::digest(raw_password));
+    if let Ok(parsed_hash) = PasswordHash::new(&DUMMY_PASSWORD_HASH) {
+        ARGON2
+            .verify_password(hashed_password.as_bytes(), &parsed_hash)
+            .ok();
+    }
+}
+
+/// Shared `Argon2id` hasher configured by the `[password]` table.
+static ARGON2: LazyLock<Argon2<'static>> = LazyLock::new(|| {
+    let mut builder = ParamsBuilder::default();
+    if let Some(config) = State::shared().get_config("password") {
+        if let Some(memory_cost) = config.get_u32("memory-cost") {
+            builder.m_cost(memory_cost);
+        }
+        if let Some(time_cost) = config.get_u32("time-cost") {
+            builder.t_cost(time_cost);
+        }
+        if let Some(parallelism) = config.get_u32("parallelism") {
+            builder.p_cost(parallelism);
+        }
+    }
+    let params = builder.build().unwrap_or_else(|err| {
+        tracing::error!("invalid Argon2 parameters: {err}");
+        Params::DEFAULT
+    });
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+});
+
+/// A password hash for the dummy verification.
+static DUMMY_PASSWORD_HASH: LazyLock<String> = LazyLock::new(|| {
+    let salt = SaltString::generate(&mut OsRng);
+    ARGON2
+        .hash_password(b"zino", &salt)
+        .map(|hash| hash.to_string())
+        .unwrap_or_else(|err| {
+            tracing::error!("fail to generate a dummy password hash: {err}");
+            String::new()
+        })
+});
@@ -1,4 +1,5 @@
 use sha1::{Digest, Sha1};
+use std::io::{self, Read};
 
 /// SHA1 digest
 pub(crate) fn checksum(data: &[u8]) -> [u8; 20] {
@@ -6,3 +7,17 @@ pub(crate) fn checksum(data: &[u8]) -> [u8; 20] {
     hasher.update(data);
     hasher.finalize().into()
 }
+
+/// SHA1 digest of the data read from a reader, which is processed in blocks.
+pub(crate) fn checksum_reader(mut reader: impl Read) -> Result<[u8; 20], io::Error> {
+    let mut hasher = Sha1::new();
+    let mut buffer = [0; 8192];
+    loop {
+        let size = reader.read(&mut buffer)?;
+        if size == 0 {
+            break;
+        }
+        hasher.update(&buffer[..size]);
+    }
+    Ok(hasher.finalize().into())
+}
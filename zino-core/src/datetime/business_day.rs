@@ -0,0 +1,196 @@
+use super::{Date, DateTime};
+use crate::{extension::TomlTableExt, state::State, LazyLock};
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use std::collections::BTreeSet;
+
+/// A calendar of business days with the weekend, holidays and make-up workdays.
+///
+/// # Examples
+///
+/// ```toml
+/// [calendar]
+/// weekend = ["Saturday", "Sunday"]
+/// holidays = ["2024-10-01", "2024-10-02", "2024-10-03"]
+/// workdays = ["2024-10-12"]
+/// ```
+#[derive(Debug, Clone)]
+pub struct BusinessCalendar {
+    /// Days of the weekend.
+    weekend: Vec<Weekday>,
+    /// Holidays which are not business days.
+    holidays: BTreeSet<NaiveDate>,
+    /// Make-up workdays on the weekend.
+    workdays: BTreeSet<NaiveDate>,
+}
+
+impl BusinessCalendar {
+    /// Creates a new instance with the weekend of Saturday and Sunday.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            weekend: vec![Weekday::Sat, Weekday::Sun],
+            holidays: BTreeSet::new(),
+            workdays: BTreeSet::new(),
+        }
+    }
+
+    /// Returns a reference to the shared calendar configured by the `[calendar]` table.
+    #[inline]
+    pub fn shared() -> &'static Self {
+        &SHARED_BUSINESS_CALENDAR
+    }
+
+    /// Sets the days of the weekend.
+    #[inline]
+    pub fn set_weekend(&mut self, weekend: Vec<Weekday>) {
+        self.weekend = weekend;
+    }
+
+    /// Adds a holiday.
+    #[inline]
+    pub fn add_holiday(&mut self, date: Date) {
+        self.holidays.insert(date.into());
+    }
+
+    /// Adds a make-up workday.
+    #[inline]
+    pub fn add_workday(&mut self, date: Date) {
+        self.workdays.insert(date.into());
+    }
+
+    /// Returns `true` if the date is a business day.
+    pub fn is_business_day(&self, date: Date) -> bool {
+        let date = NaiveDate::from(date);
+        if self.workdays.contains(&date) {
+            true
+        } else if self.holidays.contains(&date) {
+            false
+        } else {
+            !self.weekend.contains(&date.weekday())
+        }
+    }
+
+    /// Returns the first business day strictly after the date.
+    pub fn next_business_day(&self, date: Date) -> Date {
+        self.add_business_days(date, 1)
+    }
+
+    /// Adds the number of business days to the date. A negative value counts backward.
+    /// It returns the date itself if there are no business days in the calendar.
+    pub fn add_business_days(&self, date: Date, days: i32) -> Date {
+        let backward = days < 0;
+        let mut remaining = days.unsigned_abs();
+        let mut current = NaiveDate::from(date);
+        let mut skipped = 0;
+        while remaining > 0 {
+            let next = if backward {
+                current.checked_sub_days(Days::new(1))
+            } else {
+                current.checked_add_days(Days::new(1))
+            };
+            let Some(next) = next else {
+                break;
+            };
+            current = next;
+            if self.is_business_day(current.into()) {
+                remaining -= 1;
+                skipped = 0;
+            } else {
+                skipped += 1;
+                if skipped > MAX_NON_BUSINESS_DAYS {
+                    return date;
+                }
+            }
+        }
+        current.into()
+    }
+
+    /// Counts the business days in the half-open interval `[start, end)`.
+    /// A negative value is returned if `end` is earlier than `start`.
+    pub fn business_days_between(&self, start: Date, end: Date) -> i64 {
+        if end < start {
+            return -self.business_days_between(end, start);
+        }
+        NaiveDate::from(start)
+            .iter_days()
+            .take_while(|date| *date < NaiveDate::from(end))
+            .filter(|&date| self.is_business_day(date.into()))
+            .count() as i64
+    }
+}
+
+impl Default for BusinessCalendar {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Date {
+    /// Returns `true` if the date is a business day in the shared calendar.
+    #[inline]
+    pub fn is_business_day(&self) -> bool {
+        BusinessCalendar::shared().is_business_day(*self)
+    }
+
+    /// Adds the number of business days in the shared calendar.
+    #[inline]
+    pub fn add_business_days(self, days: i32) -> Self {
+        BusinessCalendar::shared().add_business_days(self, days)
+    }
+}
+
+impl DateTime {
+    /// Returns `true` if the date is a business day in the shared calendar.
+    #[inline]
+    pub fn is_business_day(&self) -> bool {
+        self.date().is_business_day()
+    }
+
+    /// Adds the number of business days in the shared calendar, keeping the time of day.
+    pub fn add_business_days(self, days: i32) -> Self {
+        let date = self.date();
+        let target = date.add_business_days(days);
+        let span = NaiveDate::from(target).signed_duration_since(NaiveDate::from(date));
+        Self(self.0 + span)
+    }
+}
+
+/// Maximum number of consecutive non-business days to search.
+const MAX_NON_BUSINESS_DAYS: u32 = 366;
+
+/// Shared business calendar.
+static SHARED_BUSINESS_CALENDAR: LazyLock<BusinessCalendar> = LazyLock::new(|| {
+    let mut calendar = BusinessCalendar::new();
+    if let Some(config) = State::shared().get_config("calendar") {
+        if let Some(weekend) = config.get_str_array("weekend") {
+            let weekend = weekend
+                .into_iter()
+                .filter_map(|day| match day.parse::<Weekday>() {
+                    Ok(weekday) => Some(weekday),
+                    Err(err) => {
+                        tracing::warn!("invalid weekday `{day}` in the calendar: {err}");
+                        None
+                    }
+                })
+                .collect();
+            calendar.set_weekend(weekend);
+        }
+        for (key, dates) in [
+            ("holidays", &mut calendar.holidays),
+            ("workdays", &mut calendar.workdays),
+        ] {
+            if let Some(values) = config.get_str_array(key) {
+                for value in values {
+                    match value.parse::<NaiveDate>() {
+                        Ok(date) => {
+                            dates.insert(date);
+                        }
+                        Err(err) => tracing::warn!("invalid date `{value}` in the calendar: {err}"),
+                    }
+                }
+            }
+        }
+    }
+    calendar
+});
@@ -14,13 +14,22 @@ use std::{
 };
 use uuid::{NoContext, Timestamp};
 
+mod business_day;
 mod date;
 mod duration;
+mod recurrence;
 mod time;
+mod time_provider;
 
+pub use business_day::BusinessCalendar;
 pub use date::Date;
 pub use duration::{parse_duration, ParseDurationError};
+pub use recurrence::{Frequency, RecurrenceIter, RecurrenceRule};
 pub use time::Time;
+pub use time_provider::{
+    query_ntp_offset, set_time_provider, sync_ntp_clock, FrozenTimeProvider, OffsetTimeProvider,
+    SystemTimeProvider, TimeProvider,
+};
 
 /// Alias for [`chrono::DateTime<Local>`](chrono::DateTime).
 type LocalDateTime = chrono::DateTime<Local>;
@@ -36,6 +45,13 @@ impl DateTime {
         Self(Local::now())
     }
 
+    /// Returns a new instance which corresponds to the current date and time
+    /// from the shared [`TimeProvider`].
+    #[inline]
+    pub fn trusted_now() -> Self {
+        time_provider::trusted_now()
+    }
+
     /// Returns the number of non-leap seconds since the midnight UTC on January 1, 1970.
     #[inline]
     pub fn current_timestamp() -> i64 {
@@ -0,0 +1,731 @@
+use super::DateTime;
+use crate::{bail, error::Error, warn};
+use chrono::{
+    Datelike, Days, Local, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike, Weekday,
+};
+use std::{collections::VecDeque, fmt, str::FromStr};
+
+/// Frequency of a recurrence rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    /// Repeats every hour.
+    Hourly,
+    /// Repeats every day.
+    Daily,
+    /// Repeats every week.
+    Weekly,
+    /// Repeats every month.
+    Monthly,
+    /// Repeats every year.
+    Yearly,
+}
+
+impl Frequency {
+    /// Returns the name in RFC 5545.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Hourly => "HOURLY",
+            Self::Daily => "DAILY",
+            Self::Weekly => "WEEKLY",
+            Self::Monthly => "MONTHLY",
+            Self::Yearly => "YEARLY",
+        }
+    }
+}
+
+impl FromStr for Frequency {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "HOURLY" => Ok(Self::Hourly),
+            "DAILY" => Ok(Self::Daily),
+            "WEEKLY" => Ok(Self::Weekly),
+            "MONTHLY" => Ok(Self::Monthly),
+            "YEARLY" => Ok(Self::Yearly),
+            _ => bail!("the frequency `{}` is unsupported", s),
+        }
+    }
+}
+
+/// A recurrence rule as defined in [RFC 5545](https://www.rfc-editor.org/rfc/rfc5545#section-3.3.10).
+///
+/// The parts `FREQ`, `INTERVAL`, `COUNT`, `UNTIL`, `BYMONTH`, `BYMONTHDAY`, `BYDAY`
+/// and `BYHOUR` are supported. The occurrences are computed in the local time zone,
+/// and the minutes and seconds are taken from the start time.
+///
+/// # Examples
+///
+/// ```rust
+/// use zino_core::datetime::{DateTime, RecurrenceRule};
+///
+/// let rule = "FREQ=MONTHLY;BYDAY=-1FR;COUNT=3".parse::<RecurrenceRule>()?;
+/// let dtstart = "2024-01-01T09:00:00+08:00".parse::<DateTime>()?;
+/// assert_eq!(rule.iter(dtstart).count(), 3);
+/// # Ok::<(), zino_core::error::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    /// Frequency.
+    frequency: Frequency,
+    /// Interval of the periods.
+    interval: u32,
+    /// Maximum number of occurrences.
+    count: Option<u32>,
+    /// Inclusive end of the occurrences.
+    until: Option<DateTime>,
+    /// Months in `1..=12`.
+    by_month: Vec<u32>,
+    /// Days of the month, where the negative values count from the end.
+    by_month_day: Vec<i32>,
+    /// Days of the week with an optional ordinal, where `0` means every one.
+    by_day: Vec<(i32, Weekday)>,
+    /// Hours in `0..=23`.
+    by_hour: Vec<u32>,
+    /// Maximum number of consecutive periods without any occurrences.
+    max_empty_periods: u32,
+}
+
+impl RecurrenceRule {
+    /// Creates a new instance with the frequency.
+    #[inline]
+    pub fn new(frequency: Frequency) -> Self {
+        Self {
+            frequency,
+            interval: 1,
+            count: None,
+            until: None,
+            by_month: Vec::new(),
+            by_month_day: Vec::new(),
+            by_day: Vec::new(),
+            by_hour: Vec::new(),
+            max_empty_periods: DEFAULT_MAX_EMPTY_PERIODS,
+        }
+    }
+
+    /// Sets the interval of the periods.
+    #[inline]
+    pub fn set_interval(&mut self, interval: u32) {
+        self.interval = interval.max(1);
+    }
+
+    /// Sets the maximum number of occurrences.
+    #[inline]
+    pub fn set_count(&mut self, count: u32) {
+        self.count = Some(count);
+    }
+
+    /// Sets the inclusive end of the occurrences.
+    #[inline]
+    pub fn set_until(&mut self, until: DateTime) {
+        self.until = Some(until);
+    }
+
+    /// Sets the months.
+    #[inline]
+    pub fn set_by_month(&mut self, months: Vec<u32>) {
+        self.by_month = months;
+    }
+
+    /// Sets the days of the month.
+    #[inline]
+    pub fn set_by_month_day(&mut self, days: Vec<i32>) {
+        self.by_month_day = days;
+    }
+
+    /// Sets the days of the week with an optional ordinal.
+    #[inline]
+    pub fn set_by_day(&mut self, days: Vec<(i32, Weekday)>) {
+        self.by_day = days;
+    }
+
+    /// Sets the hours.
+    #[inline]
+    pub fn set_by_hour(&mut self, hours: Vec<u32>) {
+        self.by_hour = hours;
+    }
+
+    /// Sets the maximum number of consecutive periods without any occurrences.
+    /// The iteration stops when it is exceeded, which prevents the infinite loop
+    /// for a rule such as `BYMONTHDAY=31;BYMONTH=2`. The default value is `1000`.
+    #[inline]
+    pub fn set_max_empty_periods(&mut self, max_empty_periods: u32) {
+        self.max_empty_periods = max_empty_periods;
+    }
+
+    /// Returns the frequency.
+    #[inline]
+    pub fn frequency(&self) -> Frequency {
+        self.frequency
+    }
+
+    /// Returns the interval of the periods.
+    #[inline]
+    pub fn interval(&self) -> u32 {
+        self.interval
+    }
+
+    /// Returns the maximum number of occurrences.
+    #[inline]
+    pub fn count(&self) -> Option<u32> {
+        self.count
+    }
+
+    /// Returns the inclusive end of the occurrences.
+    #[inline]
+    pub fn until(&self) -> Option<DateTime> {
+        self.until
+    }
+
+    /// Returns the maximum number of consecutive periods without any occurrences.
+    #[inline]
+    pub fn max_empty_periods(&self) -> u32 {
+        self.max_empty_periods
+    }
+
+    /// Returns an iterator over the occurrences starting from `dtstart`.
+    #[inline]
+    pub fn iter(&self, dtstart: DateTime) -> RecurrenceIter<'_> {
+        RecurrenceIter {
+            rule: self,
+            dtstart: dtstart.0.naive_local(),
+            period: 0,
+            empty_periods: 0,
+            buffer: VecDeque::new(),
+            emitted: 0,
+            done: false,
+        }
+    }
+
+    /// Returns the occurrences in the closed interval `[start, end]`.
+    pub fn occurrences_between(
+        &self,
+        dtstart: DateTime,
+        start: DateTime,
+        end: DateTime,
+    ) -> Vec<DateTime> {
+        self.iter(dtstart)
+            .take_while(|dt| dt <= &end)
+            .filter(|dt| dt >= &start)
+            .collect()
+    }
+
+    /// Returns the first occurrence strictly after the time.
+    #[inline]
+    pub fn next_after(&self, dtstart: DateTime, after: DateTime) -> Option<DateTime> {
+        self.iter(dtstart).find(|dt| dt > &after)
+    }
+
+    /// Expands the occurrences in the `n`-th period.
+    fn expand(&self, dtstart: NaiveDateTime, n: u32) -> Vec<NaiveDateTime> {
+        let start_date = dtstart.date();
+        let start_time = dtstart.time();
+        let step = n.saturating_mul(self.interval);
+        let dates = match self.frequency {
+            Frequency::Hourly => {
+                return dtstart
+                    .checked_add_signed(chrono::Duration::hours(step.into()))
+                    .filter(|dt| {
+                        (self.by_hour.is_empty() || self.by_hour.contains(&dt.hour()))
+                            && self.matches_date(dt.date())
+                    })
+                    .into_iter()
+                    .collect();
+            }
+            Frequency::Daily => start_date
+                .checked_add_days(Days::new(step.into()))
+                .filter(|&date| self.matches_date(date))
+                .into_iter()
+                .collect(),
+            Frequency::Weekly => {
+                let offset = start_date.weekday().num_days_from_monday();
+                let week_start = start_date - Days::new(offset.into());
+                let Some(week_start) = week_start.checked_add_days(Days::new(u64::from(step) * 7))
+                else {
+                    return Vec::new();
+                };
+                let weekdays = if self.by_day.is_empty() {
+                    vec![start_date.weekday()]
+                } else {
+                    self.by_day.iter().map(|&(_, weekday)| weekday).collect()
+                };
+                weekdays
+                    .into_iter()
+                    .filter_map(|weekday| {
+                        week_start
+                            .checked_add_days(Days::new(weekday.num_days_from_monday().into()))
+                    })
+                    .filter(|date| {
+                        self.by_month.is_empty() || self.by_month.contains(&date.month())
+                    })
+                    .collect()
+            }
+            Frequency::Monthly => {
+                let Some(month_start) = start_date
+                    .with_day(1)
+                    .and_then(|date| date.checked_add_months(Months::new(step)))
+                else {
+                    return Vec::new();
+                };
+                if self.by_month.is_empty() || self.by_month.contains(&month_start.month()) {
+                    self.expand_month(month_start.year(), month_start.month(), start_date.day())
+                } else {
+                    Vec::new()
+                }
+            }
+            Frequency::Yearly => {
+                let Some(year) = i32::try_from(step)
+                    .ok()
+                    .and_then(|step| start_date.year().checked_add(step))
+                else {
+                    return Vec::new();
+                };
+                if self.by_month.is_empty()
+                    && self.by_month_day.is_empty()
+                    && !self.by_day.is_empty()
+                {
+                    self.expand_year(year)
+                } else {
+                    let months = if self.by_month.is_empty() {
+                        vec![start_date.month()]
+                    } else {
+                        self.by_month.clone()
+                    };
+                    months
+                        .into_iter()
+                        .flat_map(|month| self.expand_month(year, month, start_date.day()))
+                        .collect()
+                }
+            }
+        };
+        let times = if self.by_hour.is_empty() {
+            vec![start_time]
+        } else {
+            self.by_hour
+                .iter()
+                .filter_map(|&hour| start_time.with_hour(hour))
+                .collect()
+        };
+        let mut occurrences = dates
+            .into_iter()
+            .flat_map(|date| times.iter().map(move |&time| date.and_time(time)))
+            .collect::<Vec<_>>();
+        occurrences.sort_unstable();
+        occurrences.dedup();
+        occurrences
+    }
+
+    /// Returns `true` if the date matches the `BYMONTH`, `BYMONTHDAY` and `BYDAY` parts
+    /// as the limits of the hourly and daily frequencies.
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        if !self.by_month.is_empty() && !self.by_month.contains(&date.month()) {
+            return false;
+        }
+        if !self.by_month_day.is_empty() {
+            let days = days_in_month(date.year(), date.month());
+            let matched = self
+                .by_month_day
+                .iter()
+                .any(|&day| resolve_month_day(day, days) == Some(date.day()));
+            if !matched {
+                return false;
+            }
+        }
+        self.by_day.is_empty()
+            || self
+                .by_day
+                .iter()
+                .any(|&(_, weekday)| weekday == date.weekday())
+    }
+
+    /// Expands the dates in a month.
+    fn expand_month(&self, year: i32, month: u32, default_day: u32) -> Vec<NaiveDate> {
+        let Some(month_start) = NaiveDate::from_ymd_opt(year, month, 1) else {
+            return Vec::new();
+        };
+        let days = days_in_month(year, month);
+        let month_days = (!self.by_month_day.is_empty()).then(|| {
+            self.by_month_day
+                .iter()
+                .filter_map(|&day| resolve_month_day(day, days))
+                .collect::<Vec<_>>()
+        });
+        let weekdays = (!self.by_day.is_empty()).then(|| {
+            let all_days = (1..=days).collect::<Vec<_>>();
+            self.by_day
+                .iter()
+                .flat_map(|&(ordinal, weekday)| {
+                    let matched = all_days
+                        .iter()
+                        .copied()
+                        .filter(|&day| {
+                            month_start
+                                .with_day(day)
+                                .is_some_and(|date| date.weekday() == weekday)
+                        })
+                        .collect::<Vec<_>>();
+                    select_ordinal(matched, ordinal)
+                })
+                .collect::<Vec<_>>()
+        });
+        let days = match (month_days, weekdays) {
+            (Some(month_days), Some(weekdays)) => month_days
+                .into_iter()
+                .filter(|day| weekdays.contains(day))
+                .collect(),
+            (Some(month_days), None) => month_days,
+            (None, Some(weekdays)) => weekdays,
+            (None, None) => vec![default_day],
+        };
+        days.into_iter()
+            .filter_map(|day| month_start.with_day(day))
+            .collect()
+    }
+
+    /// Expands the dates in a year for the `BYDAY` part only.
+    fn expand_year(&self, year: i32) -> Vec<NaiveDate> {
+        let Some(year_start) = NaiveDate::from_ymd_opt(year, 1, 1) else {
+            return Vec::new();
+        };
+        let all_dates = year_start
+            .iter_days()
+            .take_while(|date| date.year() == year)
+            .collect::<Vec<_>>();
+        self.by_day
+            .iter()
+            .flat_map(|&(ordinal, weekday)| {
+                let matched = all_dates
+                    .iter()
+                    .copied()
+                    .filter(|date| date.weekday() == weekday)
+                    .collect::<Vec<_>>();
+                select_ordinal(matched, ordinal)
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for RecurrenceRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "FREQ={}", self.frequency.as_str())?;
+        if self.interval > 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={count}")?;
+        }
+        if let Some(until) = self.until {
+            let until = until.0.naive_utc().format("%Y%m%dT%H%M%SZ");
+            write!(f, ";UNTIL={until}")?;
+        }
+        if !self.by_month.is_empty() {
+            write!(f, ";BYMONTH={}", join_values(&self.by_month))?;
+        }
+        if !self.by_month_day.is_empty() {
+            write!(f, ";BYMONTHDAY={}", join_values(&self.by_month_day))?;
+        }
+        if !self.by_day.is_empty() {
+            let days = self
+                .by_day
+                .iter()
+                .map(|&(ordinal, weekday)| {
+                    let weekday = weekday_abbr(weekday);
+                    if ordinal == 0 {
+                        weekday.to_owned()
+                    } else {
+                        format!("{ordinal}{weekday}")
+                    }
+                })
+                .collect::<Vec<_>>();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        if !self.by_hour.is_empty() {
+            write!(f, ";BYHOUR={}", join_values(&self.by_hour))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for RecurrenceRule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let s = s.strip_prefix("RRULE:").unwrap_or(s);
+        let mut frequency = None;
+        let mut rule = Self::new(Frequency::Daily);
+        for part in s.split(';').filter(|part| !part.is_empty()) {
+            let Some((key, value)) = part.split_once('=') else {
+                bail!("invalid recurrence rule part `{}`", part);
+            };
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => frequency = Some(value.to_ascii_uppercase().parse()?),
+                "INTERVAL" => rule.set_interval(value.parse()?),
+                "COUNT" => rule.set_count(value.parse()?),
+                "UNTIL" => rule.set_until(parse_until(value)?),
+                "BYMONTH" => {
+                    let months = parse_values::<u32>(value)?;
+                    if months.iter().any(|month| !(1..=12).contains(month)) {
+                        bail!("invalid `BYMONTH` value `{}`", value);
+                    }
+                    rule.set_by_month(months);
+                }
+                "BYMONTHDAY" => {
+                    let days = parse_values::<i32>(value)?;
+                    if days
+                        .iter()
+                        .any(|day| *day == 0 || !(-31..=31).contains(day))
+                    {
+                        bail!("invalid `BYMONTHDAY` value `{}`", value);
+                    }
+                    rule.set_by_month_day(days);
+                }
+                "BYDAY" => {
+                    let days = value
+                        .split(',')
+                        .map(parse_weekday_num)
+                        .collect::<Result<Vec<_>, _>>()?;
+                    rule.set_by_day(days);
+                }
+                "BYHOUR" => {
+                    let hours = parse_values::<u32>(value)?;
+                    if hours.iter().any(|hour| *hour > 23) {
+                        bail!("invalid `BYHOUR` value `{}`", value);
+                    }
+                    rule.set_by_hour(hours);
+                }
+                "WKST" => (),
+                _ => bail!("the recurrence rule part `{}` is unsupported", key),
+            }
+        }
+        let Some(frequency) = frequency else {
+            bail!("the `FREQ` part is required for the recurrence rule");
+        };
+        rule.frequency = frequency;
+        Ok(rule)
+    }
+}
+
+/// An iterator over the occurrences of a recurrence rule.
+#[derive(Debug)]
+pub struct RecurrenceIter<'a> {
+    /// Recurrence rule.
+    rule: &'a RecurrenceRule,
+    /// Start time in the local time zone.
+    dtstart: NaiveDateTime,
+    /// Index of the next period to expand.
+    period: u32,
+    /// Number of consecutive periods without any occurrences.
+    empty_periods: u32,
+    /// Occurrences expanded but not emitted.
+    buffer: VecDeque<NaiveDateTime>,
+    /// Number of emitted occurrences.
+    emitted: u32,
+    /// A flag to indicate that the iteration is finished.
+    done: bool,
+}
+
+impl<'a> Iterator for RecurrenceIter<'a> {
+    type Item = DateTime;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(dt) = self.buffer.pop_front() {
+                if self.rule.count.is_some_and(|count| self.emitted >= count) {
+                    self.done = true;
+                    self.buffer.clear();
+                    return None;
+                }
+
+                // Skips the nonexistent local time in a DST gap.
+                let Some(dt) = Local.from_local_datetime(&dt).earliest() else {
+                    continue;
+                };
+                let dt = DateTime::from(dt);
+                if self.rule.until.is_some_and(|until| dt > until) {
+                    self.done = true;
+                    self.buffer.clear();
+                    return None;
+                }
+                self.emitted += 1;
+                return Some(dt);
+            }
+            if self.done {
+                return None;
+            }
+
+            let occurrences = self.rule.expand(self.dtstart, self.period);
+            self.period = match self.period.checked_add(1) {
+                Some(period) => period,
+                None => {
+                    self.done = true;
+                    u32::MAX
+                }
+            };
+            self.buffer
+                .extend(occurrences.into_iter().filter(|dt| dt >= &self.dtstart));
+            if self.buffer.is_empty() {
+                self.empty_periods += 1;
+                if self.empty_periods > self.rule.max_empty_periods {
+                    self.done = true;
+                }
+            } else {
+                self.empty_periods = 0;
+            }
+        }
+    }
+}
+
+/// Default maximum number of consecutive periods without any occurrences.
+const DEFAULT_MAX_EMPTY_PERIODS: u32 = 1000;
+
+/// Returns the number of days in a month.
+fn days_in_month(year: i32, month: u32) -> u32 {
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|date| date.checked_add_months(Months::new(1)))
+        .and_then(|date| date.pred_opt())
+        .map(|date| date.day())
+        .unwrap_or(28)
+}
+
+/// Resolves the day of the month where the negative value counts from the end.
+fn resolve_month_day(day: i32, days: u32) -> Option<u32> {
+    let days = i32::try_from(days).ok()?;
+    let day = if day < 0 { days + day + 1 } else { day };
+    (1..=days).contains(&day).then_some(day as u32)
+}
+
+/// Selects the values by an ordinal where the negative value counts from the end.
+fn select_ordinal<T: Copy>(values: Vec<T>, ordinal: i32) -> Vec<T> {
+    let len = values.len();
+    if ordinal == 0 {
+        values
+    } else if ordinal > 0 {
+        values
+            .get(ordinal as usize - 1)
+            .copied()
+            .into_iter()
+            .collect()
+    } else {
+        let index = ordinal.unsigned_abs() as usize;
+        if index <= len {
+            vec![values[len - index]]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Parses the comma-separated values.
+fn parse_values<T: FromStr>(value: &str) -> Result<Vec<T>, Error> {
+    value
+        .split(',')
+        .map(|s| {
+            s.trim()
+                .parse()
+                .map_err(|_| warn!("invalid recurrence rule value `{}`", s))
+        })
+        .collect()
+}
+
+/// Parses the weekday with an optional ordinal, such as `MO` or `-1FR`.
+fn parse_weekday_num(value: &str) -> Result<(i32, Weekday), Error> {
+    let value = value.trim().to_ascii_uppercase();
+    if value.len() < 2 || !value.is_char_boundary(value.len() - 2) {
+        bail!("invalid `BYDAY` value `{}`", value);
+    }
+    let (ordinal, weekday) = value.split_at(value.len() - 2);
+    let weekday = match weekday {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => bail!("invalid `BYDAY` value `{}`", value),
+    };
+    let ordinal = if ordinal.is_empty() {
+        0
+    } else {
+        let ordinal = ordinal.trim_start_matches('+').parse::<i32>()?;
+        if ordinal == 0 || !(-53..=53).contains(&ordinal) {
+            bail!("invalid `BYDAY` value `{}`", value);
+        }
+        ordinal
+    };
+    Ok((ordinal, weekday))
+}
+
+/// Parses the `UNTIL` value as a date or a date-time in UTC or local time.
+fn parse_until(value: &str) -> Result<DateTime, Error> {
+    if let Some(value) = value.strip_suffix('Z') {
+        let dt = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")?;
+        Ok(DateTime::from(Local.from_utc_datetime(&dt)))
+    } else if value.contains('T') {
+        let dt = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")?;
+        Local
+            .from_local_datetime(&dt)
+            .earliest()
+            .map(DateTime::from)
+            .ok_or_else(|| warn!("invalid `UNTIL` value `{}`", value))
+    } else {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d")?;
+        let dt = date.and_time(NaiveTime::from_hms_opt(23, 59, 59).unwrap_or_default());
+        Local
+            .from_local_datetime(&dt)
+            .earliest()
+            .map(DateTime::from)
+            .ok_or_else(|| warn!("invalid `UNTIL` value `{}`", value))
+    }
+}
+
+/// Returns the two-letter abbreviation of the weekday.
+fn weekday_abbr(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+/// Joins the values with commas.
+fn join_values<T: ToString>(values: &[T]) -> String {
+    values
+        .iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RecurrenceRule;
+    use crate::datetime::DateTime;
+
+    #[test]
+    fn it_stops_after_empty_periods() {
+        let dtstart = "2024-01-31T09:00:00+08:00".parse::<DateTime>().unwrap();
+        let mut rule = "FREQ=MONTHLY;BYMONTHDAY=31"
+            .parse::<RecurrenceRule>()
+            .unwrap();
+        assert_eq!(rule.max_empty_periods(), 1000);
+        assert_eq!(rule.iter(dtstart).take(7).count(), 7);
+
+        rule.set_max_empty_periods(0);
+        assert_eq!(rule.iter(dtstart).count(), 1);
+
+        let mut rule = "FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30"
+            .parse::<RecurrenceRule>()
+            .unwrap();
+        assert_eq!(rule.iter(dtstart).next(), None);
+
+        rule.set_max_empty_periods(10);
+        assert_eq!(rule.iter(dtstart).next(), None);
+    }
+}
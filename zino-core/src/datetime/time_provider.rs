@@ -0,0 +1,235 @@
+use super::DateTime;
+use crate::{error::Error, extension::TomlTableExt, state::State, warn, LazyLock};
+use futures::channel::oneshot;
+use parking_lot::RwLock;
+use std::{
+    net::UdpSocket,
+    sync::{
+        atomic::{AtomicI64, Ordering::Relaxed},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+/// A provider of the current time, which is used by the time-sensitive validations
+/// such as the authentication. It can be frozen in tests or adjusted by NTP
+/// for the environments with bad clocks.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino_core::datetime::{self, DateTime, FrozenTimeProvider};
+///
+/// let provider = FrozenTimeProvider::new("2024-06-01T00:00:00Z".parse()?);
+/// datetime::set_time_provider(provider.clone());
+/// provider.advance(std::time::Duration::from_secs(60));
+/// assert_eq!(DateTime::trusted_now(), "2024-06-01T00:01:00Z".parse()?);
+/// ```
+pub trait TimeProvider: Send + Sync {
+    /// Returns the current time.
+    fn now(&self) -> DateTime;
+}
+
+/// A time provider using the system clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemTimeProvider;
+
+impl TimeProvider for SystemTimeProvider {
+    #[inline]
+    fn now(&self) -> DateTime {
+        DateTime::now()
+    }
+}
+
+/// A time provider with an offset to the system clock in milliseconds.
+#[derive(Debug, Default)]
+pub struct OffsetTimeProvider {
+    /// Offset in milliseconds.
+    offset: AtomicI64,
+}
+
+impl OffsetTimeProvider {
+    /// Creates a new instance with the offset in milliseconds.
+    #[inline]
+    pub fn new(offset: i64) -> Self {
+        Self {
+            offset: AtomicI64::new(offset),
+        }
+    }
+
+    /// Attempts to create a new instance with the offset checked by the NTP server,
+    /// such as `pool.ntp.org:123`.
+    #[inline]
+    pub fn try_with_ntp(server: &str) -> Result<Self, Error> {
+        query_ntp_offset(server).map(Self::new)
+    }
+
+    /// Sets the offset in milliseconds.
+    #[inline]
+    pub fn set_offset(&self, offset: i64) {
+        self.offset.store(offset, Relaxed);
+    }
+
+    /// Returns the offset in milliseconds.
+    #[inline]
+    pub fn offset(&self) -> i64 {
+        self.offset.load(Relaxed)
+    }
+}
+
+impl TimeProvider for OffsetTimeProvider {
+    #[inline]
+    fn now(&self) -> DateTime {
+        let millis = DateTime::now().timestamp_millis() + self.offset();
+        DateTime::from_timestamp_millis(millis)
+    }
+}
+
+/// A time provider which is frozen at a specific time. It is useful for tests.
+#[derive(Debug, Clone)]
+pub struct FrozenTimeProvider(Arc<RwLock<DateTime>>);
+
+impl FrozenTimeProvider {
+    /// Creates a new instance frozen at the time.
+    #[inline]
+    pub fn new(dt: DateTime) -> Self {
+        Self(Arc::new(RwLock::new(dt)))
+    }
+
+    /// Sets the frozen time.
+    #[inline]
+    pub fn set(&self, dt: DateTime) {
+        *self.0.write() = dt;
+    }
+
+    /// Advances the frozen time by the duration.
+    #[inline]
+    pub fn advance(&self, duration: Duration) {
+        *self.0.write() += duration;
+    }
+}
+
+impl TimeProvider for FrozenTimeProvider {
+    #[inline]
+    fn now(&self) -> DateTime {
+        *self.0.read()
+    }
+}
+
+/// Sets the shared time provider.
+#[inline]
+pub fn set_time_provider(provider: impl TimeProvider + 'static) {
+    *TIME_PROVIDER.write() = Box::new(provider);
+}
+
+/// Returns the current time from the shared time provider.
+#[inline]
+pub(super) fn trusted_now() -> DateTime {
+    TIME_PROVIDER.read().now()
+}
+
+/// Synchronizes the shared time provider with the NTP server
+/// if the `ntp-server` is configured in the `[time]` table.
+/// The blocking query runs in a separate thread, and the system clock is used if it fails.
+///
+/// ```toml
+/// [time]
+/// ntp-server = "pool.ntp.org:123"
+/// ```
+pub async fn sync_ntp_clock() {
+    let Some(server) = State::shared()
+        .get_config("time")
+        .and_then(|config| config.get_str("ntp-server"))
+    else {
+        return;
+    };
+    let (sender, receiver) = oneshot::channel();
+    thread::spawn(move || {
+        sender.send(query_ntp_offset(server)).ok();
+    });
+    match receiver.await {
+        Ok(Ok(offset)) => {
+            set_time_provider(OffsetTimeProvider::new(offset));
+            tracing::info!(server, offset, "the system clock has been checked by NTP");
+        }
+        Ok(Err(err)) => tracing::error!(server, "fail to query the NTP server: {err}"),
+        Err(_) => tracing::error!(server, "the NTP query has been cancelled"),
+    }
+}
+
+/// Queries the offset of the system clock to the NTP server in milliseconds
+/// using the SNTP protocol. It blocks the current thread for at most 3 seconds.
+pub fn query_ntp_offset(server: &str) -> Result<i64, Error> {
+    let socket = UdpSocket::bind("0.0.0.0:0")?;
+    socket.set_read_timeout(Some(Duration::from_secs(3)))?;
+    socket.connect(server)?;
+
+    // LI = 0, VN = 3, Mode = 3 (client).
+    let mut packet = [0u8; 48];
+    packet[0] = 0x1b;
+
+    let sent_at = DateTime::now().timestamp_millis();
+    socket.send(&packet)?;
+    let size = socket.recv(&mut packet)?;
+    let received_at = DateTime::now().timestamp_millis();
+    if size < 48 {
+        return Err(warn!("invalid response from the NTP server `{}`", server));
+    }
+    Ok(parse_ntp_offset(&packet, sent_at, received_at))
+}
+
+/// Parses the offset of the system clock from the NTP response packet.
+fn parse_ntp_offset(packet: &[u8; 48], sent_at: i64, received_at: i64) -> i64 {
+    // The transmit timestamp starts at the 40th byte.
+    let secs = u32::from_be_bytes([packet[40], packet[41], packet[42], packet[43]]);
+    let fraction = u32::from_be_bytes([packet[44], packet[45], packet[46], packet[47]]);
+    let millis = i64::from(secs) * 1000 + ((i64::from(fraction) * 1000) >> 32);
+    let server_time = millis - NTP_UNIX_EPOCH_DELTA_MILLIS;
+    server_time - (sent_at + received_at) / 2
+}
+
+/// Milliseconds between the NTP epoch (1900) and the Unix epoch (1970).
+const NTP_UNIX_EPOCH_DELTA_MILLIS: i64 = 2_208_988_800_000;
+
+/// Shared time provider. It is replaced by [`sync_ntp_clock()`] when the application is loaded.
+static TIME_PROVIDER: LazyLock<RwLock<Box<dyn TimeProvider>>> =
+    LazyLock::new(|| RwLock::new(Box::new(SystemTimeProvider)));
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_ntp_offset, FrozenTimeProvider, OffsetTimeProvider, TimeProvider};
+    use crate::datetime::DateTime;
+    use std::time::Duration;
+
+    #[test]
+    fn it_parses_ntp_offsets() {
+        // 2023-11-14T22:13:20.500Z in the NTP timestamp format.
+        let mut packet = [0u8; 48];
+        packet[40..44].copy_from_slice(&3_908_988_800_u32.to_be_bytes());
+        packet[44..48].copy_from_slice(&0x8000_0000_u32.to_be_bytes());
+
+        let sent_at = 1_699_999_999_000;
+        let received_at = 1_700_000_000_000;
+        assert_eq!(parse_ntp_offset(&packet, sent_at, received_at), 1000);
+        assert_eq!(
+            parse_ntp_offset(&packet, sent_at + 2000, received_at + 2000),
+            -1000
+        );
+    }
+
+    #[test]
+    fn it_provides_adjusted_time() {
+        let dt = "2024-06-01T00:00:00Z".parse::<DateTime>().unwrap();
+        let provider = FrozenTimeProvider::new(dt);
+        provider.advance(Duration::from_secs(60));
+        assert_eq!(
+            provider.now(),
+            "2024-06-01T00:01:00Z".parse::<DateTime>().unwrap()
+        );
+
+        let provider = OffsetTimeProvider::new(3_600_000);
+        let offset = provider.now().timestamp_millis() - DateTime::now().timestamp_millis();
+        assert!((3_599_000..=3_600_000).contains(&offset));
+    }
+}
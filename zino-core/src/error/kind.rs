@@ -0,0 +1,177 @@
+use crate::SharedString;
+use std::fmt;
+
+/// Structured kinds of errors which can be handled specifically.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A unique constraint is violated.
+    UniqueViolation {
+        /// The column name inferred from the constraint if available.
+        column: Option<SharedString>,
+    },
+    /// A foreign key constraint is violated.
+    ForeignKeyViolation,
+    /// The requested record can not be found.
+    NotFound,
+    /// An operation has timed out.
+    Timeout,
+    /// No connection can be acquired from the pool in time.
+    PoolExhausted,
+    /// A transaction failed due to a serialization failure or deadlock.
+    Serialization,
+    /// Any other errors.
+    Other,
+}
+
+impl ErrorKind {
+    /// Returns the HTTP status code corresponding to the error kind.
+    #[inline]
+    pub fn status_code(&self) -> u16 {
+        match self {
+            Self::UniqueViolation { .. } | Self::ForeignKeyViolation | Self::Serialization => 409,
+            Self::NotFound => 404,
+            Self::Timeout | Self::PoolExhausted => 503,
+            Self::Other => 500,
+        }
+    }
+
+    /// Returns `true` if the operation can be retried safely.
+    #[inline]
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::Serialization | Self::PoolExhausted)
+    }
+
+    /// Classifies the error by the SQLSTATE or the SQLite extended result code.
+    ///
+    /// MySQL reports the SQLSTATE `23000` for both unique and foreign key violations,
+    /// so the error number should be classified by
+    /// [`from_mysql_error_number()`](Self::from_mysql_error_number) instead.
+    pub fn from_error_code(code: &str) -> Self {
+        match code {
+            // SQLSTATE codes for PostgreSQL and MySQL.
+            "23505" => Self::UniqueViolation { column: None },
+            "23503" => Self::ForeignKeyViolation,
+            "40001" | "40P01" => Self::Serialization,
+            "57014" => Self::Timeout,
+            // SQLite extended result codes.
+            "1555" | "2067" => Self::UniqueViolation { column: None },
+            "787" => Self::ForeignKeyViolation,
+            "5" | "6" | "261" | "517" => Self::Serialization,
+            _ => Self::Other,
+        }
+    }
+
+    /// Classifies the error by the MySQL error number.
+    pub fn from_mysql_error_number(number: u16) -> Self {
+        match number {
+            1062 | 1169 | 1586 => Self::UniqueViolation { column: None },
+            1216 | 1217 | 1451 | 1452 => Self::ForeignKeyViolation,
+            1205 | 3024 => Self::Timeout,
+            1213 => Self::Serialization,
+            _ => Self::Other,
+        }
+    }
+
+    /// Extracts the column name from the index name `{table_name}_{column_name}_index`
+    /// which is used by the schema when creating indexes.
+    pub(super) fn parse_index_column(table_name: Option<&str>, index_name: &str) -> SharedString {
+        let index_name = index_name.strip_suffix("_index").unwrap_or(index_name);
+        let column_name = table_name
+            .and_then(|table_name| index_name.strip_prefix(table_name))
+            .and_then(|name| name.strip_prefix('_'))
+            .unwrap_or(index_name);
+        column_name.to_owned().into()
+    }
+
+    /// Extracts the column names from the SQLite error message
+    /// `UNIQUE constraint failed: {table_name}.{column_name}, ...`.
+    pub(super) fn parse_sqlite_columns(message: &str) -> Option<SharedString> {
+        let (_, columns) = message.split_once(": ")?;
+        let column_names = columns
+            .split(", ")
+            .map(|column| column.rsplit_once('.').map_or(column, |(_, name)| name))
+            .collect::<Vec<_>>();
+        Some(column_names.join(", ").into())
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UniqueViolation {
+                column: Some(column),
+            } => write!(f, "unique violation on the column `{column}`"),
+            Self::UniqueViolation { column: None } => write!(f, "unique violation"),
+            Self::ForeignKeyViolation => write!(f, "foreign key violation"),
+            Self::NotFound => write!(f, "not found"),
+            Self::Timeout => write!(f, "timeout"),
+            Self::PoolExhausted => write!(f, "pool exhausted"),
+            Self::Serialization => write!(f, "serialization failure"),
+            Self::Other => write!(f, "other error"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ErrorKind;
+
+    #[test]
+    fn it_classifies_error_codes() {
+        assert_eq!(
+            ErrorKind::from_error_code("23505"),
+            ErrorKind::UniqueViolation { column: None }
+        );
+        assert_eq!(
+            ErrorKind::from_error_code("40P01"),
+            ErrorKind::Serialization
+        );
+        assert_eq!(ErrorKind::from_error_code("57014"), ErrorKind::Timeout);
+        assert_eq!(ErrorKind::from_error_code("42601"), ErrorKind::Other);
+    }
+
+    #[test]
+    fn it_classifies_mysql_error_numbers() {
+        assert_eq!(
+            ErrorKind::from_mysql_error_number(1062),
+            ErrorKind::UniqueViolation { column: None }
+        );
+        assert_eq!(
+            ErrorKind::from_mysql_error_number(1452),
+            ErrorKind::ForeignKeyViolation
+        );
+        assert_eq!(
+            ErrorKind::from_mysql_error_number(1213),
+            ErrorKind::Serialization
+        );
+        assert_eq!(ErrorKind::from_mysql_error_number(1064), ErrorKind::Other);
+        assert_eq!(ErrorKind::from_error_code("23000"), ErrorKind::Other);
+    }
+
+    #[test]
+    fn it_classifies_sqlite_error_codes() {
+        assert_eq!(
+            ErrorKind::from_error_code("2067"),
+            ErrorKind::UniqueViolation { column: None }
+        );
+        assert_eq!(
+            ErrorKind::from_error_code("787"),
+            ErrorKind::ForeignKeyViolation
+        );
+        assert_eq!(ErrorKind::from_error_code("517"), ErrorKind::Serialization);
+
+        let columns = ErrorKind::parse_sqlite_columns("UNIQUE constraint failed: user.email");
+        assert_eq!(columns.as_deref(), Some("email"));
+        let columns = ErrorKind::parse_sqlite_columns("UNIQUE constraint failed: t.a, t.b");
+        assert_eq!(columns.as_deref(), Some("a, b"));
+    }
+
+    #[test]
+    fn it_parses_index_column() {
+        let column = ErrorKind::parse_index_column(Some("user"), "user_email_index");
+        assert_eq!(column, "email");
+        let column = ErrorKind::parse_index_column(None, "tag_name_index");
+        assert_eq!(column, "tag_name");
+    }
+}
@@ -2,10 +2,13 @@
 use crate::SharedString;
 use std::{any::Any, error, fmt};
 
+mod kind;
 mod source;
 
 use source::Source;
 
+pub use kind::ErrorKind;
+
 /// An error type backed by an allocation-optimized string.
 #[derive(Debug)]
 pub struct Error {
@@ -20,10 +23,19 @@ pub struct Error {
 impl Clone for Error {
     #[inline]
     fn clone(&self) -> Self {
+        // The timeout marker and the error kind are the only contexts which can be cloned.
+        let context = if self.has_context::<Timeout>() {
+            Some(Box::new(Timeout) as Box<dyn Any + Send>)
+        } else {
+            match self.own_kind() {
+                ErrorKind::Other => None,
+                kind => Some(Box::new(kind) as Box<dyn Any + Send>),
+            }
+        };
         Self {
             message: self.message.clone(),
             source: self.source.clone(),
-            context: None,
+            context,
         }
     }
 }
@@ -56,6 +68,26 @@ impl Error {
         }
     }
 
+    /// Creates a new instance for an operation which has timed out.
+    #[inline]
+    pub fn timeout(message: impl Into<SharedString>) -> Self {
+        Self {
+            message: message.into(),
+            source: None,
+            context: Some(Box::new(Timeout)),
+        }
+    }
+
+    /// Creates a new instance with the structured error kind.
+    #[inline]
+    pub fn with_kind(message: impl Into<SharedString>, kind: ErrorKind) -> Self {
+        Self {
+            message: message.into(),
+            source: None,
+            context: Some(Box::new(kind)),
+        }
+    }
+
     /// Creates a new instance from [`std::error::Error`] by discarding the context.
     #[inline]
     pub fn from_error(err: impl error::Error) -> Self {
@@ -110,6 +142,90 @@ impl Error {
         self.context.as_ref().is_some_and(|ctx| ctx.is::<T>())
     }
 
+    /// Returns `true` if the error is caused by a timeout.
+    #[inline]
+    pub fn is_timeout(&self) -> bool {
+        self.has_context::<Timeout>() || self.source.as_ref().is_some_and(|err| err.is_timeout())
+    }
+
+    /// Returns `true` if the error is caused by a unique constraint violation in the database.
+    #[inline]
+    pub fn is_unique_violation(&self) -> bool {
+        matches!(self.kind(), ErrorKind::UniqueViolation { .. })
+    }
+
+    /// Returns the structured kind of the error.
+    ///
+    /// The sources are inspected in order and the first kind other than
+    /// [`ErrorKind::Other`] is returned.
+    pub fn kind(&self) -> ErrorKind {
+        self.sources()
+            .map(|err| err.own_kind())
+            .find(|kind| kind != &ErrorKind::Other)
+            .unwrap_or(ErrorKind::Other)
+    }
+
+    /// Returns the kind of the error without inspecting the sources.
+    fn own_kind(&self) -> ErrorKind {
+        if self.has_context::<Timeout>() {
+            return ErrorKind::Timeout;
+        }
+        if let Some(kind) = self.get_context::<ErrorKind>() {
+            return kind.clone();
+        }
+        #[cfg(feature = "orm-sqlx")]
+        if let Some(err) = self.get_context::<sqlx::Error>() {
+            return match err {
+                sqlx::Error::RowNotFound => ErrorKind::NotFound,
+                sqlx::Error::PoolTimedOut => ErrorKind::PoolExhausted,
+                sqlx::Error::Database(err) => {
+                    let kind = match err.kind() {
+                        sqlx::error::ErrorKind::UniqueViolation => {
+                            ErrorKind::UniqueViolation { column: None }
+                        }
+                        sqlx::error::ErrorKind::ForeignKeyViolation => {
+                            ErrorKind::ForeignKeyViolation
+                        }
+                        _ => {
+                            // MySQL returns the SQLSTATE as the code, so the error number is used.
+                            #[cfg(any(
+                                feature = "orm-mariadb",
+                                feature = "orm-mysql",
+                                feature = "orm-tidb"
+                            ))]
+                            let kind = err
+                                .try_downcast_ref::<sqlx::mysql::MySqlDatabaseError>()
+                                .map(|err| ErrorKind::from_mysql_error_number(err.number()));
+                            #[cfg(not(any(
+                                feature = "orm-mariadb",
+                                feature = "orm-mysql",
+                                feature = "orm-tidb"
+                            )))]
+                            let kind = None;
+                            kind.or_else(|| {
+                                err.code().map(|code| ErrorKind::from_error_code(&code))
+                            })
+                            .unwrap_or(ErrorKind::Other)
+                        }
+                    };
+                    if let ErrorKind::UniqueViolation { .. } = kind {
+                        let column = if let Some(constraint) = err.constraint() {
+                            Some(ErrorKind::parse_index_column(err.table(), constraint))
+                        } else {
+                            // SQLite reports the violation as `UNIQUE constraint failed: table.column`.
+                            ErrorKind::parse_sqlite_columns(err.message())
+                        };
+                        ErrorKind::UniqueViolation { column }
+                    } else {
+                        kind
+                    }
+                }
+                _ => ErrorKind::Other,
+            };
+        }
+        ErrorKind::Other
+    }
+
     /// Returns the error message.
     #[inline]
     pub fn message(&self) -> &str {
@@ -137,6 +253,10 @@ impl Error {
     }
 }
 
+/// A marker context for the timeout errors.
+#[derive(Debug, Clone, Copy)]
+struct Timeout;
+
 impl<E: error::Error + Send + 'static> From<E> for Error {
     #[inline]
     fn from(err: E) -> Self {
@@ -1,10 +1,12 @@
 use super::JsonValueExt;
 use crate::{
+    bail,
     datetime::{self, Date, DateTime, Time},
+    error::Error,
     helper,
     model::Model,
     validation::Validation,
-    JsonValue, Map, Record, Uuid,
+    warn, JsonValue, Map, Record, Uuid,
 };
 use rust_decimal::Decimal;
 use std::{
@@ -226,6 +228,15 @@ pub trait JsonObjectExt {
     /// otherwise `None` is returned.
     fn upsert(&mut self, key: impl Into<String>, value: impl Into<JsonValue>) -> Option<JsonValue>;
 
+    /// Applies a JSON Merge Patch to the map.
+    /// See [RFC 7396](https://datatracker.ietf.org/doc/html/rfc7396).
+    fn merge_patch(&mut self, patch: &Map);
+
+    /// Applies a list of JSON Patch operations to the map.
+    /// The map will not be modified if any of the operations fails.
+    /// See [RFC 6902](https://datatracker.ietf.org/doc/html/rfc6902).
+    fn apply_json_patch(&mut self, operations: &[JsonValue]) -> Result<(), Error>;
+
     /// Copies values from the populated data corresponding to the key into `self`.
     fn clone_from_populated(&mut self, key: &str, fields: &[&str]);
 
@@ -713,6 +724,80 @@ impl JsonObjectExt for Map {
         self.insert(key.into(), value.into())
     }
 
+    fn merge_patch(&mut self, patch: &Map) {
+        for (key, value) in patch {
+            if value.is_null() {
+                self.remove(key);
+            } else {
+                let target = self.entry(key.to_owned()).or_insert(JsonValue::Null);
+                merge_json_value(target, value);
+            }
+        }
+    }
+
+    fn apply_json_patch(&mut self, operations: &[JsonValue]) -> Result<(), Error> {
+        let mut document = JsonValue::Object(self.clone());
+        for operation in operations {
+            let Some(operation) = operation.as_object() else {
+                bail!("the JSON Patch operation should be an object");
+            };
+            let op = operation
+                .get_str("op")
+                .ok_or_else(|| warn!("the `op` member should be specified"))?;
+            let path = operation
+                .get_str("path")
+                .ok_or_else(|| warn!("the `path` member should be specified"))?;
+            match op {
+                "add" => {
+                    let value = get_patch_value(operation)?;
+                    add_json_value(&mut document, path, value)?;
+                }
+                "remove" => {
+                    remove_json_value(&mut document, path)?;
+                }
+                "replace" => {
+                    let value = get_patch_value(operation)?;
+                    let target = document
+                        .pointer_mut(path)
+                        .ok_or_else(|| warn!("the target location `{}` does not exist", path))?;
+                    *target = value;
+                }
+                "move" => {
+                    let from = get_patch_from(operation)?;
+                    if path.starts_with(from) && path[from.len()..].starts_with('/') {
+                        bail!("the location `{}` can not be moved into its children", from);
+                    }
+                    let value = remove_json_value(&mut document, from)?;
+                    add_json_value(&mut document, path, value)?;
+                }
+                "copy" => {
+                    let from = get_patch_from(operation)?;
+                    let value = document
+                        .pointer(from)
+                        .cloned()
+                        .ok_or_else(|| warn!("the source location `{}` does not exist", from))?;
+                    add_json_value(&mut document, path, value)?;
+                }
+                "test" => {
+                    let value = get_patch_value(operation)?;
+                    if document.pointer(path) != Some(&value) {
+                        bail!(
+                            "the value at the location `{}` is not equal to the expected",
+                            path
+                        );
+                    }
+                }
+                _ => bail!("the JSON Patch operation `{}` is unsupported", op),
+            }
+        }
+        if let JsonValue::Object(map) = document {
+            *self = map;
+            Ok(())
+        } else {
+            Err(warn!("the JSON Patch should not replace the root object"))
+        }
+    }
+
     fn clone_from_populated(&mut self, key: &str, fields: &[&str]) {
         let mut object = Map::new();
         if let Some(map) = self.get_populated(key) {
@@ -818,11 +903,94 @@ impl JsonObjectExt for Map {
     }
 }
 
+/// Merges the patch into the target value recursively.
+fn merge_json_value(target: &mut JsonValue, patch: &JsonValue) {
+    if let JsonValue::Object(patch) = patch {
+        if !target.is_object() {
+            *target = JsonValue::Object(Map::new());
+        }
+        if let JsonValue::Object(map) = target {
+            map.merge_patch(patch);
+        }
+    } else {
+        *target = patch.clone();
+    }
+}
+
+/// Gets the `value` member of a JSON Patch operation.
+fn get_patch_value(operation: &Map) -> Result<JsonValue, Error> {
+    operation
+        .get("value")
+        .cloned()
+        .ok_or_else(|| warn!("the `value` member should be specified"))
+}
+
+/// Gets the `from` member of a JSON Patch operation.
+fn get_patch_from(operation: &Map) -> Result<&str, Error> {
+    operation
+        .get_str("from")
+        .ok_or_else(|| warn!("the `from` member should be specified"))
+}
+
+/// Splits the JSON Pointer into the parent pointer and the last reference token.
+fn split_json_pointer(pointer: &str) -> Result<(&str, String), Error> {
+    let index = pointer
+        .rfind('/')
+        .ok_or_else(|| warn!("invalid JSON Pointer `{}`", pointer))?;
+    let token = pointer[index + 1..].replace("~1", "/").replace("~0", "~");
+    Ok((&pointer[..index], token))
+}
+
+/// Adds a value to the location specified by the JSON Pointer.
+fn add_json_value(document: &mut JsonValue, pointer: &str, value: JsonValue) -> Result<(), Error> {
+    if pointer.is_empty() {
+        *document = value;
+        return Ok(());
+    }
+
+    let (parent_pointer, token) = split_json_pointer(pointer)?;
+    match document.pointer_mut(parent_pointer) {
+        Some(JsonValue::Object(map)) => {
+            map.insert(token, value);
+        }
+        Some(JsonValue::Array(vec)) => {
+            if token == "-" {
+                vec.push(value);
+            } else {
+                match token.parse::<usize>() {
+                    Ok(index) if index <= vec.len() => vec.insert(index, value),
+                    _ => bail!(
+                        "invalid array index `{}` for the location `{}`",
+                        token,
+                        pointer
+                    ),
+                }
+            }
+        }
+        _ => bail!("the target location `{}` does not exist", pointer),
+    }
+    Ok(())
+}
+
+/// Removes the value at the location specified by the JSON Pointer.
+fn remove_json_value(document: &mut JsonValue, pointer: &str) -> Result<JsonValue, Error> {
+    let (parent_pointer, token) = split_json_pointer(pointer)?;
+    let value = match document.pointer_mut(parent_pointer) {
+        Some(JsonValue::Object(map)) => map.remove(&token),
+        Some(JsonValue::Array(vec)) => match token.parse::<usize>() {
+            Ok(index) if index < vec.len() => Some(vec.remove(index)),
+            _ => None,
+        },
+        _ => None,
+    };
+    value.ok_or_else(|| warn!("the target location `{}` does not exist", pointer))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
         extension::{JsonObjectExt, JsonValueExt},
-        Map,
+        JsonValue, Map,
     };
 
     #[test]
@@ -857,4 +1025,52 @@ mod tests {
             Some("alice")
         );
     }
+
+    #[test]
+    fn it_applies_merge_patch() {
+        let mut map = Map::new();
+        map.upsert("name", "alice");
+        map.upsert("extra", Map::from_entry("locale", "en"));
+
+        let mut patch = Map::new();
+        patch.upsert("name", JsonValue::Null);
+        patch.upsert("extra", Map::from_entry("timezone", "UTC"));
+        map.merge_patch(&patch);
+        assert_eq!(map.get("name"), None);
+        assert_eq!(
+            map.pointer("/extra/locale").and_then(|v| v.as_str()),
+            Some("en")
+        );
+        assert_eq!(
+            map.pointer("/extra/timezone").and_then(|v| v.as_str()),
+            Some("UTC")
+        );
+    }
+
+    #[test]
+    fn it_applies_json_patch() {
+        let mut map = Map::new();
+        map.upsert("name", "alice");
+        map.upsert("tags", vec!["a", "b"]);
+
+        let operations = serde_json::json!([
+            { "op": "replace", "path": "/name", "value": "bob" },
+            { "op": "add", "path": "/tags/-", "value": "c" },
+            { "op": "remove", "path": "/tags/0" },
+            { "op": "copy", "from": "/name", "path": "/nickname" },
+        ]);
+        let operations = operations.as_array().unwrap();
+        assert!(map.apply_json_patch(operations).is_ok());
+        assert_eq!(map.get_str("name"), Some("bob"));
+        assert_eq!(map.get_str("nickname"), Some("bob"));
+        assert_eq!(map.get_str_array("tags"), Some(vec!["b", "c"]));
+
+        let operations = serde_json::json!([
+            { "op": "replace", "path": "/name", "value": "carol" },
+            { "op": "test", "path": "/name", "value": "alice" },
+        ]);
+        let operations = operations.as_array().unwrap();
+        assert!(map.apply_json_patch(operations).is_err());
+        assert_eq!(map.get_str("name"), Some("bob"));
+    }
 }
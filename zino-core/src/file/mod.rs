@@ -5,7 +5,7 @@ use crate::{
     crypto,
     encoding::{base64, hex},
     error::Error,
-    extension::JsonObjectExt,
+    extension::{JsonObjectExt, JsonValueExt},
     json,
     trace::TraceContext,
     warn, JsonValue, Map,
@@ -26,6 +26,12 @@ use std::{
     path::Path,
 };
 
+mod scanner;
+
+pub use scanner::{
+    content_scanner, set_content_scanner, ClamavScanner, ContentScanner, ScanVerdict,
+};
+
 /// A file with an associated name.
 #[derive(Debug, Clone, Default)]
 pub struct NamedFile {
@@ -162,6 +168,17 @@ impl NamedFile {
         Vec::from(checksum).into()
     }
 
+    /// Scans the file content with the shared content scanner.
+    /// It returns [`ScanVerdict::Clean`] if no scanner has been set.
+    pub fn scan(&self) -> Result<ScanVerdict, Error> {
+        if let Some(scanner) = content_scanner() {
+            let mut bytes = self.bytes.as_ref();
+            scanner.scan(&mut bytes)
+        } else {
+            Ok(ScanVerdict::Clean)
+        }
+    }
+
     /// Returns the ETag for the file.
     #[inline]
     pub fn etag(&self) -> EntityTag {
@@ -365,6 +382,14 @@ impl NamedFile {
         })
     }
 
+    /// Computes the checksum for a local file without reading the whole file into memory.
+    /// It is consistent with the [`checksum()`](Self::checksum) for the same content.
+    pub fn checksum_local(path: impl AsRef<Path>) -> Result<Bytes, io::Error> {
+        let file = File::open(path)?;
+        let checksum = crypto::checksum_reader(file)?;
+        Ok(Vec::from(checksum).into())
+    }
+
     /// Attempts to create an instance from a field in a multipart stream.
     pub async fn try_from_multipart_field(field: Field<'_>) -> Result<Self, multer::Error> {
         let field_name = field.name().map(|s| s.to_owned());
@@ -486,7 +511,7 @@ impl NamedFile {
 
         let mut form = Form::new().part(field_name, part).percent_encode_noop();
         for (key, value) in self.extra() {
-            form = form.text(key.to_owned(), value.to_string());
+            form = form.text(key.to_owned(), value.to_string_unquoted());
         }
 
         let request_builder = if options.is_some() {
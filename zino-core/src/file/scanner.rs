@@ -0,0 +1,148 @@
+use crate::{bail, error::Error, extension::TomlTableExt, state::State, warn};
+use std::{
+    io::{self, Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    sync::OnceLock,
+    time::Duration,
+};
+
+/// Verdict of scanning the file content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanVerdict {
+    /// No threats have been found.
+    Clean,
+    /// The content is infected with the signature.
+    Infected(String),
+}
+
+impl ScanVerdict {
+    /// Returns `true` if the content is infected.
+    #[inline]
+    pub fn is_infected(&self) -> bool {
+        matches!(self, Self::Infected(_))
+    }
+}
+
+/// An interface for scanning the uploaded files, such as an antivirus engine.
+pub trait ContentScanner: Send + Sync {
+    /// Returns the scanner name.
+    fn name(&self) -> &'static str;
+
+    /// Scans the content read from the reader.
+    fn scan(&self, reader: &mut dyn Read) -> Result<ScanVerdict, Error>;
+}
+
+/// A content scanner backed by the `clamd` daemon of ClamAV over TCP,
+/// which uses the `INSTREAM` command.
+#[derive(Debug, Clone)]
+pub struct ClamavScanner {
+    /// Address of the daemon.
+    address: String,
+    /// Timeout for the connection and the scan.
+    timeout: Duration,
+}
+
+impl ClamavScanner {
+    /// Creates a new instance with the address of the daemon, such as `127.0.0.1:3310`.
+    #[inline]
+    pub fn new(address: impl Into<String>) -> Self {
+        Self {
+            address: address.into(),
+            timeout: Duration::from_secs(60),
+        }
+    }
+
+    /// Creates a new instance with the configuration of the `[clamav]` table.
+    pub fn with_config() -> Self {
+        let config = State::shared().get_config("clamav");
+        let host = config
+            .and_then(|t| t.get_str("host"))
+            .unwrap_or("127.0.0.1");
+        let port = config.and_then(|t| t.get_u16("port")).unwrap_or(3310);
+        let mut scanner = Self::new(format!("{host}:{port}"));
+        if let Some(timeout) = config.and_then(|t| t.get_duration("timeout")) {
+            scanner.timeout = timeout;
+        }
+        scanner
+    }
+
+    /// Sets the timeout for the connection and the scan.
+    #[inline]
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Connects to the daemon.
+    fn connect(&self) -> Result<TcpStream, io::Error> {
+        let mut last_error = None;
+        for addr in self.address.to_socket_addrs()? {
+            match TcpStream::connect_timeout(&addr, self.timeout) {
+                Ok(stream) => {
+                    stream.set_read_timeout(Some(self.timeout))?;
+                    stream.set_write_timeout(Some(self.timeout))?;
+                    return Ok(stream);
+                }
+                Err(err) => last_error = Some(err),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| io::Error::from(io::ErrorKind::AddrNotAvailable)))
+    }
+}
+
+impl ContentScanner for ClamavScanner {
+    #[inline]
+    fn name(&self) -> &'static str {
+        "clamav"
+    }
+
+    fn scan(&self, reader: &mut dyn Read) -> Result<ScanVerdict, Error> {
+        let mut stream = self.connect()?;
+        stream.write_all(b"zINSTREAM\0")?;
+
+        let mut buffer = vec![0; CHUNK_SIZE];
+        loop {
+            let size = reader.read(&mut buffer)?;
+            if size == 0 {
+                break;
+            }
+            let length = u32::try_from(size)?;
+            stream.write_all(&length.to_be_bytes())?;
+            stream.write_all(&buffer[..size])?;
+        }
+        stream.write_all(&[0; 4])?;
+        stream.flush()?;
+
+        let mut reply = Vec::new();
+        stream.read_to_end(&mut reply)?;
+
+        let reply = String::from_utf8_lossy(&reply);
+        let reply = reply.trim_end_matches(['\0', '\n']);
+        let result = reply.strip_prefix("stream: ").unwrap_or(reply);
+        if result == "OK" {
+            Ok(ScanVerdict::Clean)
+        } else if let Some(signature) = result.strip_suffix(" FOUND") {
+            Ok(ScanVerdict::Infected(signature.to_owned()))
+        } else {
+            bail!("fail to scan the content with ClamAV: {}", result);
+        }
+    }
+}
+
+/// Sets the shared content scanner. It can only be set once.
+pub fn set_content_scanner(scanner: impl ContentScanner + 'static) -> Result<(), Error> {
+    SHARED_CONTENT_SCANNER
+        .set(Box::new(scanner))
+        .map_err(|_| warn!("the content scanner has already been set"))
+}
+
+/// Returns the shared content scanner if it has been set.
+#[inline]
+pub fn content_scanner() -> Option<&'static dyn ContentScanner> {
+    SHARED_CONTENT_SCANNER.get().map(|scanner| scanner.as_ref())
+}
+
+/// Size of the chunks sent to the daemon.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Shared content scanner.
+static SHARED_CONTENT_SCANNER: OnceLock<Box<dyn ContentScanner>> = OnceLock::new();
@@ -0,0 +1,112 @@
+use crate::{error::Error, JsonValue};
+use serde::de::DeserializeOwned;
+
+/// A function pointer of encoding the JSON value in a binary format.
+pub(crate) type BodyEncoder = fn(value: &JsonValue) -> Result<Vec<u8>, Error>;
+
+/// Negotiates the content type of the response body by parsing the `accept` header.
+/// The media ranges are weighted by the `q` parameters, and the first one wins on ties.
+/// It returns `None` if JSON is preferred or no binary formats are acceptable.
+#[cfg(any(feature = "cbor", feature = "msgpack"))]
+pub(crate) fn negotiate_content_type(accept: &str) -> Option<&'static str> {
+    let mut content_type = None;
+    let mut max_quality = 0.0;
+    for media_range in accept.split(',') {
+        let mut parts = media_range.split(';').map(|s| s.trim());
+        let essence = parts.next().unwrap_or_default();
+        let quality = parts
+            .find_map(|param| param.strip_prefix("q="))
+            .map_or(Some(1.0), |q| q.parse::<f32>().ok())
+            .unwrap_or_default();
+        if quality <= max_quality {
+            continue;
+        }
+        let negotiated = match essence {
+            #[cfg(feature = "msgpack")]
+            "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack" => {
+                Some("application/msgpack")
+            }
+            #[cfg(feature = "cbor")]
+            "application/cbor" => Some("application/cbor"),
+            "application/json" | "application/problem+json" | "application/*" | "*/*" => None,
+            _ => continue,
+        };
+        content_type = negotiated;
+        max_quality = quality;
+    }
+    content_type
+}
+
+/// Deserializes the body bytes according to the data type.
+/// The data type other than `msgpack` and `cbor` is treated as JSON.
+pub(crate) fn deserialize_body<T: DeserializeOwned>(
+    data_type: &str,
+    bytes: &[u8],
+) -> Result<T, Error> {
+    match data_type {
+        "msgpack" => {
+            #[cfg(feature = "msgpack")]
+            {
+                Ok(rmp_serde::from_slice(bytes)?)
+            }
+            #[cfg(not(feature = "msgpack"))]
+            {
+                Err(crate::warn!("the `msgpack` feature should be enabled"))
+            }
+        }
+        "cbor" => {
+            #[cfg(feature = "cbor")]
+            {
+                Ok(ciborium::from_reader(bytes)?)
+            }
+            #[cfg(not(feature = "cbor"))]
+            {
+                Err(crate::warn!("the `cbor` feature should be enabled"))
+            }
+        }
+        _ => Ok(serde_json::from_slice(bytes)?),
+    }
+}
+
+/// Returns the encoder for the binary content type of the response body.
+#[cfg(any(feature = "cbor", feature = "msgpack"))]
+pub(crate) fn get_body_encoder(content_type: &str) -> Option<BodyEncoder> {
+    let essence = content_type.split(';').next().unwrap_or_default().trim();
+    match essence {
+        #[cfg(feature = "msgpack")]
+        "application/msgpack" => Some(|value| Ok(rmp_serde::to_vec_named(value)?)),
+        #[cfg(feature = "cbor")]
+        "application/cbor" => Some(|value| {
+            let mut bytes = Vec::new();
+            ciborium::into_writer(value, &mut bytes)?;
+            Ok(bytes)
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(all(test, feature = "msgpack"))]
+mod tests {
+    use super::negotiate_content_type;
+
+    #[test]
+    fn it_negotiates_content_types() {
+        let msgpack = Some("application/msgpack");
+        assert_eq!(negotiate_content_type("application/msgpack"), msgpack);
+        assert_eq!(negotiate_content_type("application/msgpack, */*"), msgpack);
+        assert_eq!(negotiate_content_type("*/*, application/msgpack"), None);
+        assert_eq!(
+            negotiate_content_type("application/json;q=0.5, application/msgpack;q=0.9"),
+            msgpack
+        );
+        assert_eq!(
+            negotiate_content_type("application/msgpack;q=0.5, application/json"),
+            None
+        );
+        assert_eq!(negotiate_content_type("application/msgpack;q=0"), None);
+        assert_eq!(
+            negotiate_content_type("text/html, application/msgpack;q=0.1"),
+            msgpack
+        );
+    }
+}
@@ -29,11 +29,14 @@ pub(crate) fn get_data_type(content_type: &str) -> &str {
     match content_type {
         "application/json" | "application/problem+json" => "json",
         "application/jsonlines" | "application/x-ndjson" => "ndjson",
+        "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack" => "msgpack",
+        "application/cbor" => "cbor",
         "application/octet-stream" => "bytes",
         "application/x-www-form-urlencoded" => "form",
         "multipart/form-data" => "multipart",
         "text/csv" => "csv",
         "text/plain" => "text",
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet" => "xlsx",
         _ => {
             if content_type.starts_with("application/") && content_type.ends_with("+json") {
                 "json"
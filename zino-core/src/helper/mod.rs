@@ -1,16 +1,21 @@
 /// Helper utilities.
+mod body_format;
 mod form_data;
 mod header;
 mod mask_text;
 mod query;
 mod str_array;
 
+pub(crate) use body_format::{deserialize_body, BodyEncoder};
 pub(crate) use form_data::parse_form_data;
 pub(crate) use header::{check_json_content_type, displayed_inline, get_data_type};
 pub(crate) use mask_text::mask_text;
 pub(crate) use query::format_query;
 pub(crate) use str_array::parse_str_array;
 
+#[cfg(any(feature = "cbor", feature = "msgpack"))]
+pub(crate) use body_format::{get_body_encoder, negotiate_content_type};
+
 #[cfg(any(
     feature = "connector-mysql",
     feature = "connector-sqlite",
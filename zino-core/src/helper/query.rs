@@ -24,7 +24,7 @@ pub(crate) fn format_query<'a>(query: &'a str, params: Option<&'a Map>) -> Cow<'
 }
 
 /// Regex for the interpolation parameter.
-static INTERPOLATION_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+pub(super) static INTERPOLATION_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"\$\{\s*([a-zA-Z]+[\w\.]*)\s*\}")
         .expect("fail to create a regex for the interpolation parameter")
 });
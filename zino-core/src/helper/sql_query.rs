@@ -7,11 +7,26 @@ use std::borrow::Cow;
 ///
 /// The parameter is represented as `${param}` or `#{param}`,
 /// in which `param` can only contain restricted chracters `[a-zA-Z]+[\w\.]*`.
+///
+/// The interpolation of `${param}` is deprecated for the values which are not identifiers,
+/// since it is prone to SQL injection. Use `#{param}` to bind the values instead.
 pub(crate) fn prepare_sql_query<'a>(
     query: &'a str,
     params: Option<&'a Map>,
     placeholder: char,
 ) -> (Cow<'a, str>, Vec<&'a JsonValue>) {
+    if let Some(params) = params.filter(|_| query.contains('$')) {
+        for captures in super::query::INTERPOLATION_PATTERN.captures_iter(query) {
+            let key = &captures[1];
+            if params.get(key).is_some_and(|value| !is_identifier(value)) {
+                tracing::warn!(
+                    "the interpolation of `${{{key}}}` with a non-identifier value is deprecated; \
+                        please use `#{{{key}}}` to bind the value instead"
+                );
+            }
+        }
+    }
+
     let sql = super::format_query(query, params);
     if let Some(params) = params.filter(|_| sql.contains('#')) {
         let mut values = Vec::new();
@@ -31,6 +46,22 @@ pub(crate) fn prepare_sql_query<'a>(
     }
 }
 
+/// Returns `true` if the value can be safely interpolated as identifiers,
+/// such as a comma-separated list of column names matching `[A-Za-z_][\w.]*`, or a number.
+fn is_identifier(value: &JsonValue) -> bool {
+    match value {
+        JsonValue::Bool(_) | JsonValue::Number(_) => true,
+        JsonValue::String(s) => s.split(',').all(|name| {
+            let mut chars = name.trim().chars();
+            chars
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.'))
+        }),
+        _ => false,
+    }
+}
+
 /// Regex for the prepared statement.
 static STATEMENT_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"\#\{\s*([a-zA-Z]+[\w\.]*)\s*\}")
@@ -62,4 +93,19 @@ mod tests {
         );
         assert_eq!(values[0], 18);
     }
+
+    #[test]
+    fn it_checks_identifiers() {
+        assert!(super::is_identifier(&"id, name, age".into()));
+        assert!(super::is_identifier(&10.into()));
+        assert!(super::is_identifier(&"u.id,u.name, _score".into()));
+        assert!(super::is_identifier(&true.into()));
+        assert!(!super::is_identifier(&"alice' OR '1' = '1".into()));
+        assert!(!super::is_identifier(
+            &"id FROM users UNION SELECT password".into()
+        ));
+        assert!(!super::is_identifier(&"id, , name".into()));
+        assert!(!super::is_identifier(&"1id".into()));
+        assert!(!super::is_identifier(&"`id`".into()));
+    }
 }
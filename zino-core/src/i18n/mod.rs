@@ -13,6 +13,11 @@ mod language;
 
 pub use language::select_language;
 
+#[doc(no_inline)]
+pub use fluent::FluentArgs;
+#[doc(no_inline)]
+pub use unic_langid::LanguageIdentifier;
+
 /// Translates the localization message.
 pub fn translate(
     locale: &LanguageIdentifier,
@@ -60,6 +65,18 @@ pub fn translate(
     }
 }
 
+/// Returns the supported locales.
+#[inline]
+pub fn supported_locales() -> &'static [&'static str] {
+    SUPPORTED_LOCALES.as_slice()
+}
+
+/// Returns the default locale.
+#[inline]
+pub fn default_locale() -> &'static str {
+    *DEFAULT_LOCALE
+}
+
 /// Translation type.
 type Translation = FluentBundle<FluentResource, IntlLangMemoizer>;
 
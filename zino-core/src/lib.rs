@@ -3,7 +3,7 @@
 #![doc(html_favicon_url = "https://zino.cc/assets/zino-logo.png")]
 #![doc(html_logo_url = "https://zino.cc/assets/zino-logo.svg")]
 #![allow(async_fn_in_trait)]
-#![forbid(unsafe_code)]
+#![deny(unsafe_code)]
 
 mod crypto;
 mod encoding;
@@ -33,6 +33,7 @@ pub mod error;
 pub mod extension;
 pub mod file;
 pub mod model;
+pub mod money;
 pub mod request;
 pub mod response;
 pub mod schedule;
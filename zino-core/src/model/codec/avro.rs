@@ -0,0 +1,59 @@
+use crate::error::Error;
+use apache_avro::Schema;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// An Avro codec for the model payloads, which encodes a single datum without the header.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino_core::{model::AvroCodec, orm::Schema};
+///
+/// let codec = AvroCodec::new(User::schema());
+/// let bytes = codec.encode(&user)?;
+/// let user = codec.decode::<User>(&bytes)?;
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct AvroCodec<'a> {
+    /// Avro schema.
+    schema: &'a Schema,
+}
+
+impl<'a> AvroCodec<'a> {
+    /// Creates a new instance with the Avro schema.
+    #[inline]
+    pub fn new(schema: &'a Schema) -> Self {
+        Self { schema }
+    }
+
+    /// Returns a reference to the Avro schema.
+    #[inline]
+    pub fn schema(&self) -> &'a Schema {
+        self.schema
+    }
+
+    /// Encodes the value as an Avro datum.
+    pub fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, Error> {
+        let value = apache_avro::to_value(value)?.resolve(self.schema)?;
+        let datum = apache_avro::to_avro_datum(self.schema, value)?;
+        Ok(datum)
+    }
+
+    /// Decodes an Avro datum written with the same schema.
+    #[inline]
+    pub fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, Error> {
+        self.decode_with_writer_schema(bytes, self.schema)
+    }
+
+    /// Decodes an Avro datum written with the writer schema,
+    /// which will be resolved to the schema of the codec.
+    pub fn decode_with_writer_schema<T: DeserializeOwned>(
+        &self,
+        mut bytes: &[u8],
+        writer_schema: &Schema,
+    ) -> Result<T, Error> {
+        let value = apache_avro::from_avro_datum(writer_schema, &mut bytes, Some(self.schema))?;
+        let data = apache_avro::from_value(&value)?;
+        Ok(data)
+    }
+}
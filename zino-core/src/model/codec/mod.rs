@@ -0,0 +1,9 @@
+//! Binary codecs for the model payloads.
+
+mod avro;
+mod protobuf;
+mod registry;
+
+pub use avro::AvroCodec;
+pub use protobuf::ProtobufCodec;
+pub use registry::{SchemaFormat, SchemaRegistry};
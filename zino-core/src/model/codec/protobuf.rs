@@ -0,0 +1,477 @@
+use crate::{bail, error::Error, model::Column, warn, JsonValue, Map};
+use convert_case::{Case, Casing};
+use std::fmt::Write;
+
+/// A Protobuf codec for the model payloads, which is derived from the column metadata.
+///
+/// The field numbers are specified by the `field_number` attribute of the columns,
+/// which should never be changed or reused to keep the wire compatibility.
+/// The columns without a field number are not part of the message.
+/// Nested objects are encoded as JSON strings.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino_core::{model::ProtobufCodec, orm::Schema};
+///
+/// let codec = ProtobufCodec::new("User", User::columns());
+/// let bytes = codec.encode(&user.into_map())?;
+/// let data = codec.decode(&bytes)?;
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ProtobufCodec<'a> {
+    /// Message name.
+    message_name: &'a str,
+    /// Model columns.
+    columns: &'a [Column<'a>],
+}
+
+impl<'a> ProtobufCodec<'a> {
+    /// Creates a new instance with the message name and the model columns.
+    #[inline]
+    pub fn new(message_name: &'a str, columns: &'a [Column<'a>]) -> Self {
+        Self {
+            message_name,
+            columns,
+        }
+    }
+
+    /// Returns the message definition in the `proto3` syntax,
+    /// which can be registered in the schema registry.
+    pub fn proto_definition(&self) -> Result<String, Error> {
+        let message_name = self.message_name.to_case(Case::Pascal);
+        let mut definition = format!("syntax = \"proto3\";\n\nmessage {message_name} {{\n");
+        for (number, column) in self.fields()? {
+            let type_name = column.type_name();
+            let (field_type, repeated) = FieldType::parse(type_name);
+            let label = if repeated {
+                "repeated "
+            } else if type_name.starts_with("Option<") {
+                "optional "
+            } else {
+                ""
+            };
+            let proto_type = field_type.proto_type();
+            let name = column.name();
+            if let Some(comment) = column.comment() {
+                let _ = writeln!(definition, "  // {comment}");
+            }
+            let _ = writeln!(definition, "  {label}{proto_type} {name} = {number};");
+        }
+        definition.push_str("}\n");
+        Ok(definition)
+    }
+
+    /// Encodes the model data as a Protobuf message.
+    /// The values of unknown columns are ignored.
+    pub fn encode(&self, data: &Map) -> Result<Vec<u8>, Error> {
+        let mut buffer = Vec::new();
+        for (number, column) in self.fields()? {
+            let Some(value) = data.get(column.name()).filter(|v| !v.is_null()) else {
+                continue;
+            };
+            let (field_type, repeated) = FieldType::parse(column.type_name());
+            let result = if repeated {
+                let Some(values) = value.as_array() else {
+                    bail!("the column `{}` should be an array", column.name());
+                };
+                if field_type.is_packable() {
+                    let mut packed = Vec::new();
+                    for value in values {
+                        field_type.encode_value(&mut packed, value)?;
+                    }
+                    write_tag(&mut buffer, number, WIRE_TYPE_LEN);
+                    write_bytes(&mut buffer, &packed);
+                    Ok(())
+                } else {
+                    values.iter().try_for_each(|value| {
+                        write_tag(&mut buffer, number, field_type.wire_type());
+                        field_type.encode_value(&mut buffer, value)
+                    })
+                }
+            } else {
+                write_tag(&mut buffer, number, field_type.wire_type());
+                field_type.encode_value(&mut buffer, value)
+            };
+            result.map_err(|err| {
+                err.wrap(format!("fail to encode the column `{}`", column.name()))
+            })?;
+        }
+        Ok(buffer)
+    }
+
+    /// Decodes a Protobuf message as the model data.
+    /// Unknown fields are skipped.
+    pub fn decode(&self, bytes: &[u8]) -> Result<Map, Error> {
+        let fields = self.fields()?;
+        let mut data = Map::new();
+        let mut cursor = bytes;
+        while !cursor.is_empty() {
+            let key = read_varint(&mut cursor)?;
+            let number = key >> 3;
+            let wire_type = key & 0x07;
+            let Some(&(_, column)) = fields.iter().find(|(n, _)| *n == number) else {
+                skip_field(&mut cursor, wire_type)?;
+                continue;
+            };
+
+            let column_name = column.name();
+            let (field_type, repeated) = FieldType::parse(column.type_name());
+            if repeated {
+                let entry = data
+                    .entry(column_name)
+                    .or_insert_with(|| JsonValue::Array(Vec::new()));
+                let Some(values) = entry.as_array_mut() else {
+                    continue;
+                };
+                if field_type.is_packable() && wire_type == WIRE_TYPE_LEN {
+                    let mut packed = read_bytes(&mut cursor)?;
+                    while !packed.is_empty() {
+                        values.push(field_type.decode_value(&mut packed)?);
+                    }
+                } else {
+                    field_type.check_wire_type(column_name, wire_type)?;
+                    values.push(field_type.decode_value(&mut cursor)?);
+                }
+            } else {
+                field_type.check_wire_type(column_name, wire_type)?;
+                let value = field_type.decode_value(&mut cursor)?;
+                data.insert(column_name.to_owned(), value);
+            }
+        }
+        Ok(data)
+    }
+
+    /// Returns the columns with the field numbers, which are checked to be valid and unique.
+    fn fields(&self) -> Result<Vec<(u64, &'a Column<'a>)>, Error> {
+        let mut fields = Vec::new();
+        for column in self.columns {
+            let Some(value) = column.extra().get("field_number") else {
+                continue;
+            };
+            let column_name = column.name();
+            let Some(number) = value
+                .as_u64()
+                .filter(|&n| (1..=MAX_FIELD_NUMBER).contains(&n))
+            else {
+                bail!("the field number of the column `{column_name}` is invalid");
+            };
+            if RESERVED_FIELD_NUMBERS.contains(&number) {
+                bail!("the field number `{number}` of the column `{column_name}` is reserved");
+            }
+            if let Some((_, col)) = fields.iter().find(|(n, _)| *n == number) {
+                bail!(
+                    "the field number `{number}` is used by both `{}` and `{column_name}`",
+                    col.name()
+                );
+            }
+            fields.push((number, column));
+        }
+        Ok(fields)
+    }
+}
+
+/// Field types of the Protobuf message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldType {
+    /// `bool`
+    Bool,
+    /// `sint32`
+    Int32,
+    /// `sint64`
+    Int64,
+    /// `uint32`
+    UInt32,
+    /// `uint64`
+    UInt64,
+    /// `float`
+    Float,
+    /// `double`
+    Double,
+    /// `string`
+    String,
+    /// `bytes`
+    Bytes,
+    /// A JSON value encoded as `string`.
+    Json,
+}
+
+impl FieldType {
+    /// Parses the column type, and returns the field type with a flag for the repeated field.
+    fn parse(type_name: &str) -> (Self, bool) {
+        let type_name = type_name
+            .strip_prefix("Option<")
+            .and_then(|s| s.strip_suffix('>'))
+            .unwrap_or(type_name);
+        if type_name == "Vec<u8>" {
+            return (Self::Bytes, false);
+        }
+        if let Some(type_name) = type_name
+            .strip_prefix("Vec<")
+            .and_then(|s| s.strip_suffix('>'))
+        {
+            return (Self::parse(type_name).0, true);
+        }
+
+        let field_type = match type_name {
+            "bool" => Self::Bool,
+            "i32" | "i16" | "i8" => Self::Int32,
+            "i64" | "isize" => Self::Int64,
+            "u32" | "u16" | "u8" => Self::UInt32,
+            "u64" | "usize" => Self::UInt64,
+            "f32" => Self::Float,
+            "f64" => Self::Double,
+            "Map" | "JsonValue" => Self::Json,
+            _ => Self::String,
+        };
+        (field_type, false)
+    }
+
+    /// Returns the type name in the `.proto` file.
+    fn proto_type(self) -> &'static str {
+        match self {
+            Self::Bool => "bool",
+            Self::Int32 => "sint32",
+            Self::Int64 => "sint64",
+            Self::UInt32 => "uint32",
+            Self::UInt64 => "uint64",
+            Self::Float => "float",
+            Self::Double => "double",
+            Self::String | Self::Json => "string",
+            Self::Bytes => "bytes",
+        }
+    }
+
+    /// Returns the wire type.
+    fn wire_type(self) -> u64 {
+        match self {
+            Self::Bool | Self::Int32 | Self::Int64 | Self::UInt32 | Self::UInt64 => {
+                WIRE_TYPE_VARINT
+            }
+            Self::Float => WIRE_TYPE_I32,
+            Self::Double => WIRE_TYPE_I64,
+            Self::String | Self::Bytes | Self::Json => WIRE_TYPE_LEN,
+        }
+    }
+
+    /// Returns `true` if the repeated field can be packed.
+    fn is_packable(self) -> bool {
+        self.wire_type() != WIRE_TYPE_LEN
+    }
+
+    /// Checks whether the wire type matches the field type.
+    fn check_wire_type(self, field: &str, wire_type: u64) -> Result<(), Error> {
+        if self.wire_type() != wire_type {
+            bail!(
+                "the wire type `{}` does not match the field `{}`",
+                wire_type,
+                field
+            );
+        }
+        Ok(())
+    }
+
+    /// Encodes the value without the tag.
+    fn encode_value(self, buffer: &mut Vec<u8>, value: &JsonValue) -> Result<(), Error> {
+        match self {
+            Self::Bool => {
+                let b = match value {
+                    JsonValue::Bool(b) => *b,
+                    _ => value.as_str().unwrap_or_default().parse()?,
+                };
+                write_varint(buffer, b.into());
+            }
+            Self::Int32 | Self::Int64 => {
+                let i = match value.as_i64() {
+                    Some(i) => i,
+                    None => value.as_str().unwrap_or_default().parse()?,
+                };
+                write_varint(buffer, ((i << 1) ^ (i >> 63)) as u64);
+            }
+            Self::UInt32 | Self::UInt64 => {
+                let u = match value.as_u64() {
+                    Some(u) => u,
+                    None => value.as_str().unwrap_or_default().parse()?,
+                };
+                write_varint(buffer, u);
+            }
+            Self::Float => {
+                let f = match value.as_f64() {
+                    Some(f) => f as f32,
+                    None => value.as_str().unwrap_or_default().parse()?,
+                };
+                buffer.extend_from_slice(&f.to_le_bytes());
+            }
+            Self::Double => {
+                let f = match value.as_f64() {
+                    Some(f) => f,
+                    None => value.as_str().unwrap_or_default().parse()?,
+                };
+                buffer.extend_from_slice(&f.to_le_bytes());
+            }
+            Self::String => match value {
+                JsonValue::String(s) => write_bytes(buffer, s.as_bytes()),
+                _ => write_bytes(buffer, value.to_string().as_bytes()),
+            },
+            Self::Bytes => {
+                let bytes = value
+                    .as_array()
+                    .ok_or_else(|| warn!("the value should be an array of bytes"))?
+                    .iter()
+                    .map(|v| v.as_u64().and_then(|u| u8::try_from(u).ok()))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| warn!("the value should be an array of bytes"))?;
+                write_bytes(buffer, &bytes);
+            }
+            Self::Json => write_bytes(buffer, value.to_string().as_bytes()),
+        }
+        Ok(())
+    }
+
+    /// Decodes a value without the tag.
+    fn decode_value(self, cursor: &mut &[u8]) -> Result<JsonValue, Error> {
+        let value = match self {
+            Self::Bool => (read_varint(cursor)? != 0).into(),
+            Self::Int32 | Self::Int64 => {
+                let u = read_varint(cursor)?;
+                (((u >> 1) as i64) ^ -((u & 1) as i64)).into()
+            }
+            Self::UInt32 | Self::UInt64 => read_varint(cursor)?.into(),
+            Self::Float => f32::from_le_bytes(read_array(cursor)?).into(),
+            Self::Double => f64::from_le_bytes(read_array(cursor)?).into(),
+            Self::String => String::from_utf8(read_bytes(cursor)?.to_vec())?.into(),
+            Self::Bytes => read_bytes(cursor)?.to_vec().into(),
+            Self::Json => serde_json::from_slice(read_bytes(cursor)?)?,
+        };
+        Ok(value)
+    }
+}
+
+/// Writes the field tag.
+#[inline]
+fn write_tag(buffer: &mut Vec<u8>, number: u64, wire_type: u64) {
+    write_varint(buffer, (number << 3) | wire_type);
+}
+
+/// Writes a length-delimited bytes.
+#[inline]
+fn write_bytes(buffer: &mut Vec<u8>, bytes: &[u8]) {
+    write_varint(buffer, bytes.len() as u64);
+    buffer.extend_from_slice(bytes);
+}
+
+/// Writes a variable-length integer.
+fn write_varint(buffer: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buffer.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buffer.push(value as u8);
+}
+
+/// Reads a variable-length integer.
+pub(super) fn read_varint(cursor: &mut &[u8]) -> Result<u64, Error> {
+    let mut value = 0;
+    for (index, byte) in cursor.iter().enumerate().take(10) {
+        value |= u64::from(byte & 0x7f) << (index * 7);
+        if byte & 0x80 == 0 {
+            *cursor = &cursor[index + 1..];
+            return Ok(value);
+        }
+    }
+    bail!("invalid varint in the Protobuf message");
+}
+
+/// Reads a length-delimited bytes.
+fn read_bytes<'a>(cursor: &mut &'a [u8]) -> Result<&'a [u8], Error> {
+    let length = usize::try_from(read_varint(cursor)?)?;
+    if cursor.len() < length {
+        bail!("unexpected end of the Protobuf message");
+    }
+    let (bytes, remaining) = cursor.split_at(length);
+    *cursor = remaining;
+    Ok(bytes)
+}
+
+/// Reads a fixed-length array.
+fn read_array<const N: usize>(cursor: &mut &[u8]) -> Result<[u8; N], Error> {
+    if cursor.len() < N {
+        bail!("unexpected end of the Protobuf message");
+    }
+    let (bytes, remaining) = cursor.split_at(N);
+    *cursor = remaining;
+    Ok(bytes.try_into()?)
+}
+
+/// Skips an unknown field.
+fn skip_field(cursor: &mut &[u8], wire_type: u64) -> Result<(), Error> {
+    match wire_type {
+        WIRE_TYPE_VARINT => {
+            read_varint(cursor)?;
+        }
+        WIRE_TYPE_I64 => {
+            read_array::<8>(cursor)?;
+        }
+        WIRE_TYPE_LEN => {
+            read_bytes(cursor)?;
+        }
+        WIRE_TYPE_I32 => {
+            read_array::<4>(cursor)?;
+        }
+        _ => bail!("unsupported wire type `{}`", wire_type),
+    }
+    Ok(())
+}
+
+/// Maximum field number.
+const MAX_FIELD_NUMBER: u64 = (1 << 29) - 1;
+
+/// Field numbers reserved for the Protobuf implementation.
+const RESERVED_FIELD_NUMBERS: std::ops::RangeInclusive<u64> = 19000..=19999;
+
+/// Wire type for `int32`, `int64`, `uint32`, `uint64`, `sint32`, `sint64`, `bool`, `enum`.
+const WIRE_TYPE_VARINT: u64 = 0;
+
+/// Wire type for `fixed64`, `sfixed64`, `double`.
+const WIRE_TYPE_I64: u64 = 1;
+
+/// Wire type for `string`, `bytes`, embedded messages, packed repeated fields.
+const WIRE_TYPE_LEN: u64 = 2;
+
+/// Wire type for `fixed32`, `sfixed32`, `float`.
+const WIRE_TYPE_I32: u64 = 5;
+
+#[cfg(test)]
+mod tests {
+    use super::ProtobufCodec;
+    use crate::{extension::JsonObjectExt, model::Column, Map};
+
+    #[test]
+    fn it_numbers_protobuf_fields() {
+        let mut id = Column::new("id", "i64", true);
+        id.set_extra_attribute("field_number", 2);
+        let mut name = Column::new("name", "String", true);
+        name.set_extra_attribute("field_number", 1);
+        let note = Column::new("note", "String", false);
+        let columns = [id, name, note];
+        let codec = ProtobufCodec::new("user", &columns);
+
+        let mut data = Map::new();
+        data.upsert("id", 150);
+        data.upsert("name", "alice");
+        data.upsert("note", "ignored");
+        let bytes = codec.encode(&data).unwrap();
+        assert_eq!(&bytes[..2], &[0x10, 0xac]);
+
+        // The field numbers are independent of the column positions.
+        let columns = [columns[2].clone(), columns[1].clone(), columns[0].clone()];
+        let data = ProtobufCodec::new("user", &columns).decode(&bytes).unwrap();
+        assert_eq!(data.get_i64("id"), Some(150));
+        assert_eq!(data.get_str("name"), Some("alice"));
+        assert!(!data.contains_key("note"));
+
+        let mut columns = columns;
+        columns[0].set_extra_attribute("field_number", 1);
+        assert!(ProtobufCodec::new("user", &columns).encode(&data).is_err());
+    }
+}
@@ -0,0 +1,198 @@
+use super::protobuf::read_varint;
+use crate::{
+    application::http_client,
+    bail,
+    error::Error,
+    extension::{JsonObjectExt, TomlTableExt},
+    state::State,
+    warn, LazyLock, Map,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use parking_lot::RwLock;
+use std::{collections::HashMap, sync::Arc};
+
+/// Formats of the schemas in the schema registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SchemaFormat {
+    /// Apache Avro.
+    Avro,
+    /// Protocol Buffers.
+    Protobuf,
+}
+
+impl SchemaFormat {
+    /// Returns the schema type used by the schema registry.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Avro => "AVRO",
+            Self::Protobuf => "PROTOBUF",
+        }
+    }
+
+    /// Encodes the payload in the Confluent wire format,
+    /// which is prefixed with a magic byte and the schema ID.
+    /// For Protobuf, the message indexes are always `[0]` referring to the first message.
+    pub fn encode_wire_format(&self, schema_id: u32, payload: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(payload.len() + 6);
+        bytes.push(MAGIC_BYTE);
+        bytes.extend_from_slice(&schema_id.to_be_bytes());
+        if *self == Self::Protobuf {
+            bytes.push(0);
+        }
+        bytes.extend_from_slice(payload);
+        bytes
+    }
+
+    /// Decodes the data in the Confluent wire format,
+    /// and returns the schema ID and the payload.
+    pub fn decode_wire_format<'a>(&self, bytes: &'a [u8]) -> Result<(u32, &'a [u8]), Error> {
+        let Some((&MAGIC_BYTE, remaining)) = bytes.split_first() else {
+            bail!("the data is not in the Confluent wire format");
+        };
+        if remaining.len() < 4 {
+            bail!("the data is not in the Confluent wire format");
+        }
+        let (schema_id, mut payload) = remaining.split_at(4);
+        let schema_id = u32::from_be_bytes(schema_id.try_into()?);
+        if *self == Self::Protobuf {
+            // The message indexes are encoded as a zigzag varint array.
+            let length = read_varint(&mut payload)? >> 1;
+            for _ in 0..length {
+                read_varint(&mut payload)?;
+            }
+        }
+        Ok((schema_id, payload))
+    }
+}
+
+/// A client for the Confluent Schema Registry.
+/// The registered schemas are cached in memory.
+///
+/// ```toml
+/// [schema-registry]
+/// url = "http://localhost:8081"
+/// username = "registry"
+/// password = "secret"
+/// ```
+#[derive(Debug, Clone)]
+pub struct SchemaRegistry {
+    /// Base URL.
+    base_url: String,
+    /// Value for the `authorization` header.
+    authorization: Option<String>,
+    /// Schema IDs cached by the subject and the schema.
+    schema_ids: Arc<RwLock<HashMap<(String, String), u32>>>,
+    /// Schemas cached by the ID.
+    schemas: Arc<RwLock<HashMap<u32, String>>>,
+}
+
+impl SchemaRegistry {
+    /// Creates a new instance with the base URL.
+    #[inline]
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_owned(),
+            authorization: None,
+            schema_ids: Arc::new(RwLock::new(HashMap::new())),
+            schemas: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Sets the credentials for the HTTP basic authentication.
+    #[inline]
+    pub fn set_basic_auth(&mut self, username: &str, password: &str) {
+        let credentials = STANDARD.encode(format!("{username}:{password}"));
+        self.authorization = Some(format!("Basic {credentials}"));
+    }
+
+    /// Registers the schema under the subject, and returns the schema ID.
+    /// It is idempotent if the schema has already been registered.
+    pub async fn register(
+        &self,
+        subject: &str,
+        format: SchemaFormat,
+        schema: &str,
+    ) -> Result<u32, Error> {
+        let key = (subject.to_owned(), schema.to_owned());
+        if let Some(schema_id) = self.schema_ids.read().get(&key) {
+            return Ok(*schema_id);
+        }
+
+        let mut body = Map::from_entry("schema", schema);
+        if format != SchemaFormat::Avro {
+            body.upsert("schemaType", format.as_str());
+        }
+        let url = format!("{}/subjects/{subject}/versions", self.base_url);
+        let data = self.request(&url, Some(body)).await?;
+        let schema_id = data
+            .get_u32("id")
+            .ok_or_else(|| warn!("invalid response from the schema registry"))?;
+        self.schema_ids.write().insert(key, schema_id);
+        self.schemas.write().insert(schema_id, schema.to_owned());
+        Ok(schema_id)
+    }
+
+    /// Gets the schema by ID.
+    pub async fn get_schema(&self, schema_id: u32) -> Result<String, Error> {
+        if let Some(schema) = self.schemas.read().get(&schema_id) {
+            return Ok(schema.clone());
+        }
+
+        let url = format!("{}/schemas/ids/{schema_id}", self.base_url);
+        let data = self.request(&url, None).await?;
+        let schema = data
+            .get_str("schema")
+            .ok_or_else(|| warn!("invalid response from the schema registry"))?;
+        self.schemas.write().insert(schema_id, schema.to_owned());
+        Ok(schema.to_owned())
+    }
+
+    /// Sends a request to the schema registry.
+    async fn request(&self, url: &str, body: Option<Map>) -> Result<Map, Error> {
+        let mut headers = Map::from_entry("accept", CONTENT_TYPE);
+        if let Some(authorization) = self.authorization.as_deref() {
+            headers.upsert("authorization", authorization);
+        }
+
+        let mut options = Map::new();
+        if let Some(body) = body {
+            headers.upsert("content-type", CONTENT_TYPE);
+            options.upsert("method", "POST");
+            options.upsert("data_type", "json");
+            options.upsert("body", body);
+        }
+        options.upsert("headers", headers);
+
+        let response = http_client::request_builder(url, Some(&options))?
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+
+    /// Returns the shared schema registry configured by the `[schema-registry]` table.
+    #[inline]
+    pub fn shared() -> Option<&'static Self> {
+        SHARED_SCHEMA_REGISTRY.as_ref()
+    }
+}
+
+/// Magic byte of the Confluent wire format.
+const MAGIC_BYTE: u8 = 0;
+
+/// Content type for the schema registry.
+const CONTENT_TYPE: &str = "application/vnd.schemaregistry.v1+json";
+
+/// Shared schema registry.
+static SHARED_SCHEMA_REGISTRY: LazyLock<Option<SchemaRegistry>> = LazyLock::new(|| {
+    let config = State::shared().get_config("schema-registry")?;
+    let url = config.get_str("url")?;
+    let mut registry = SchemaRegistry::new(url);
+    if let Some(username) = config.get_str("username") {
+        let password = config.get_str("password").unwrap_or_default();
+        registry.set_basic_auth(username, password);
+    }
+    Some(registry)
+});
@@ -184,6 +184,12 @@ impl<'a> Column<'a> {
         self.has_attribute("primary_key")
     }
 
+    /// Returns `true` if the column has a unique index.
+    #[inline]
+    pub fn is_unique(&self) -> bool {
+        self.index_type() == Some("unique") || self.has_attribute("unique")
+    }
+
     /// Returns `true` if the column is read-only.
     #[inline]
     pub fn is_read_only(&self) -> bool {
@@ -196,6 +202,33 @@ impl<'a> Column<'a> {
         self.has_attribute("write_only")
     }
 
+    /// Returns `true` if the column is a generated column computed by the database.
+    ///
+    /// A bare `generated` annotation only indicates that the value is set by the backend,
+    /// so the column is still writable.
+    #[inline]
+    pub fn is_generated(&self) -> bool {
+        self.generated_expression().is_some()
+    }
+
+    /// Returns `true` if the values of the generated column are stored.
+    #[inline]
+    pub fn is_stored(&self) -> bool {
+        self.has_attribute("stored")
+    }
+
+    /// Returns the expression of the generated column.
+    #[inline]
+    pub fn generated_expression(&self) -> Option<&str> {
+        self.extra.get_str("generated")
+    }
+
+    /// Returns the default expression evaluated by the database.
+    #[inline]
+    pub fn default_expression(&self) -> Option<&str> {
+        self.extra.get_str("default_expression")
+    }
+
     /// Returns `true` if the column is an option type.
     ///
     /// Only supports `Option<Uuid>` | `Option<String>` | `Option<i64>` | `Option<u64>`
@@ -256,7 +289,8 @@ impl<'a> Column<'a> {
             "i64" | "u64" | "isize" | "usize" => Schema::Long,
             "f32" => Schema::Float,
             "f64" => Schema::Double,
-            "String" => Schema::String,
+            "String" | "Money" => Schema::String,
+            "Range<i32>" | "Range<i64>" | "Range<Date>" | "Range<DateTime>" => Schema::String,
             "Date" => Schema::Date,
             "DateTime" => Schema::TimestampMicros,
             "Uuid" => Schema::Uuid,
@@ -388,6 +422,14 @@ impl<'a> Column<'a> {
                     definition.upsert("multipleOf", Decimal::new(1, scale).to_string());
                 }
             }
+            "Money" => {
+                definition.upsert("type", "string");
+                definition.upsert("format", "money");
+            }
+            "Range<i32>" | "Range<i64>" | "Range<Date>" | "Range<DateTime>" => {
+                definition.upsert("type", "string");
+                definition.upsert("format", "range");
+            }
             "String" | "Option<String>" => {
                 definition.upsert("type", "string");
                 if name == "password" {
@@ -1,5 +1,5 @@
 use crate::Uuid;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 /// Data associated with a query.
 #[derive(Debug, Clone)]
@@ -8,6 +8,8 @@ pub struct QueryContext {
     model_name: &'static str,
     /// Start time.
     start_time: Instant,
+    /// Execution time.
+    execution_time: Option<Duration>,
     /// Query ID.
     query_id: Uuid,
     /// A query.
@@ -31,6 +33,7 @@ impl QueryContext {
         Self {
             model_name,
             start_time: Instant::now(),
+            execution_time: None,
             query_id: Uuid::now_v7(),
             query: String::new(),
             arguments: Vec::new(),
@@ -74,9 +77,14 @@ impl QueryContext {
     /// Sets the query result.
     #[inline]
     pub fn set_query_result(&mut self, rows_affected: impl Into<Option<u64>>, success: bool) {
+        self.execution_time = Some(self.start_time.elapsed());
         self.rows_affected = rows_affected.into();
         self.success = success;
         self.cancelled = false;
+        crate::request::record_query(self);
+
+        #[cfg(feature = "orm")]
+        crate::orm::QueryLog::record(self);
     }
 
     /// Returns the model name.
@@ -91,6 +99,14 @@ impl QueryContext {
         self.start_time
     }
 
+    /// Returns the execution time of the query.
+    /// It is the time elapsed since the start time if the query result has not been set.
+    #[inline]
+    pub fn execution_time(&self) -> Duration {
+        self.execution_time
+            .unwrap_or_else(|| self.start_time.elapsed())
+    }
+
     /// Returns the query ID.
     #[inline]
     pub fn query_id(&self) -> Uuid {
@@ -175,6 +191,6 @@ impl QueryContext {
             "model_name" => self.model_name(),
             "action" => action.into(),
         )
-        .record(self.start_time().elapsed().as_secs_f64());
+        .record(self.execution_time().as_secs_f64());
     }
 }
@@ -0,0 +1,150 @@
+use crate::{error::Error, warn, JsonValue};
+
+/// Mapped enum types for the model fields.
+///
+/// This trait can be derived by `zino_derive::ModelEnum` for the enums with unit variants.
+/// The variants are stored as strings in the database by default,
+/// or as integers if the `#[schema(repr = "int")]` attribute is specified.
+/// The fields of the enum types should be annotated with `#[schema(model_enum)]`
+/// so that they are mapped to the corresponding column types.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino_core::model::{ModelEnum, Query};
+/// use zino_derive::{ModelEnum, Schema};
+///
+/// #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ModelEnum)]
+/// pub enum TaskStatus {
+///     #[default]
+///     Active,
+///     #[schema(value = "OnHold")]
+///     Paused,
+///     Completed,
+/// }
+///
+/// #[derive(Debug, Clone, Default, Serialize, Deserialize, Schema)]
+/// pub struct Task {
+///     #[schema(model_enum)]
+///     status: TaskStatus,
+/// }
+///
+/// let mut query = Query::default();
+/// query.add_filter("status", TaskStatus::Completed);
+/// ```
+pub trait ModelEnum: Copy + 'static {
+    /// A flag to indicate whether the variants are stored as integers.
+    const INTEGER_REPR: bool = false;
+
+    /// Returns all the variants.
+    fn variants() -> &'static [Self];
+
+    /// Returns the string value of the variant.
+    fn as_str(&self) -> &'static str;
+
+    /// Returns the integer value of the variant.
+    fn as_i32(&self) -> i32;
+
+    /// Returns the type name of the column.
+    #[inline]
+    fn type_name(optional: bool) -> &'static str {
+        match (Self::INTEGER_REPR, optional) {
+            (false, false) => "String",
+            (false, true) => "Option<String>",
+            (true, false) => "i32",
+            (true, true) => "Option<i32>",
+        }
+    }
+
+    /// Returns the values of all the variants.
+    #[inline]
+    fn enum_values() -> Vec<JsonValue> {
+        Self::variants().iter().map(|v| v.to_json_value()).collect()
+    }
+
+    /// Converts the variant to a JSON value.
+    #[inline]
+    fn to_json_value(&self) -> JsonValue {
+        if Self::INTEGER_REPR {
+            self.as_i32().into()
+        } else {
+            self.as_str().into()
+        }
+    }
+
+    /// Parses the variant from a JSON value, which can be a string or an integer.
+    /// The error lists all the valid variants.
+    fn parse_value(value: &JsonValue) -> Result<Self, Error> {
+        let variants = Self::variants();
+        let variant = match value {
+            JsonValue::String(s) => {
+                let s = s.trim();
+                variants
+                    .iter()
+                    .find(|v| v.as_str() == s || s.parse::<i32>().is_ok_and(|i| v.as_i32() == i))
+            }
+            JsonValue::Number(n) => n
+                .as_i64()
+                .and_then(|i| variants.iter().find(|v| i64::from(v.as_i32()) == i)),
+            _ => None,
+        };
+        variant.copied().ok_or_else(|| {
+            let values = variants
+                .iter()
+                .map(|v| v.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            warn!("invalid value `{}`, expected one of: {}", value, values)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ModelEnum;
+    use serde_json::json;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum TaskStatus {
+        Active,
+        Paused,
+    }
+
+    impl ModelEnum for TaskStatus {
+        fn variants() -> &'static [Self] {
+            &[Self::Active, Self::Paused]
+        }
+
+        fn as_str(&self) -> &'static str {
+            match self {
+                Self::Active => "Active",
+                Self::Paused => "OnHold",
+            }
+        }
+
+        fn as_i32(&self) -> i32 {
+            match self {
+                Self::Active => 0,
+                Self::Paused => 1,
+            }
+        }
+    }
+
+    #[test]
+    fn it_parses_enum_values() {
+        assert_eq!(
+            TaskStatus::parse_value(&json!("OnHold")).ok(),
+            Some(TaskStatus::Paused)
+        );
+        assert_eq!(
+            TaskStatus::parse_value(&json!(0)).ok(),
+            Some(TaskStatus::Active)
+        );
+        assert_eq!(
+            TaskStatus::enum_values(),
+            vec![json!("Active"), json!("OnHold")]
+        );
+        assert!(TaskStatus::parse_value(&json!("Paused"))
+            .is_err_and(|err| err.to_string().contains("Active, OnHold")));
+    }
+}
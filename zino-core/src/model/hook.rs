@@ -78,8 +78,7 @@ pub trait ModelHooks: Model {
             }
             _ => Cow::Borrowed("the query result has not been recorded"),
         };
-        let execution_time = ctx.start_time().elapsed();
-        let execution_time_millis = execution_time.as_millis();
+        let execution_time_millis = ctx.execution_time().as_millis();
         tracing::info!(
             model_name,
             query_id,
@@ -160,6 +159,13 @@ pub trait ModelHooks: Model {
         Ok(())
     }
 
+    /// A hook running before transitioning the status of a model.
+    /// It can be used as a guard to reject the transition.
+    #[inline]
+    async fn before_transition(&mut self, _status: &str) -> Result<(), Error> {
+        Ok(())
+    }
+
     /// A hook running before updating a model in the table.
     #[inline]
     async fn before_update(&mut self) -> Result<Self::Data, Error> {
@@ -227,8 +233,9 @@ pub trait ModelHooks: Model {
     }
 
     /// A hook running before counting the models in the table.
+    /// The query can be modified to inject the filters.
     #[inline]
-    async fn before_count(_query: &Query) -> Result<(), Error> {
+    async fn before_count(_query: &mut Query) -> Result<(), Error> {
         Ok(())
     }
 
@@ -244,12 +251,15 @@ pub trait ModelHooks: Model {
     }
 
     /// A hook running before selecting the models with a `Query` from the table.
+    /// The query can be modified to inject the filters, such as the tenant ID
+    /// or the access control predicates.
     #[inline]
-    async fn before_query(_query: &Query) -> Result<(), Error> {
+    async fn before_query(_query: &mut Query) -> Result<(), Error> {
         Ok(())
     }
 
     /// A hook running after selecting the models with a `Query` from the table.
+    /// The number of rows fetched and the execution time are available in the context.
     #[inline]
     async fn after_query(ctx: &QueryContext) -> Result<(), Error> {
         if !ctx.is_success() {
@@ -0,0 +1,154 @@
+use crate::{
+    bail, crypto, error::Error, extension::TomlTableExt, state::State, warn, LazyLock, Uuid,
+};
+use aes_gcm_siv::{
+    aead::{generic_array::GenericArray, Aead},
+    Aes256GcmSiv, KeyInit, Nonce,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+/// A codec for obfuscating the model IDs exposed in public APIs,
+/// so that the external clients see short opaque IDs while the database
+/// keeps the native keys.
+///
+/// Both integer IDs and UUIDs are supported. The encoding is deterministic,
+/// so the same ID is always encoded as the same public ID.
+///
+/// # Examples
+///
+/// ```toml
+/// [id-codec]
+/// algorithm = "sqids"
+/// alphabet = "k3G7QAe51FCsPW92uEOyq4Bg6Sp8YzVTmnU0liwDdHXLajZrfxNhobJIRcMvKt"
+/// min-length = 8
+/// ```
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum IdCodec {
+    /// [Sqids](https://sqids.org/) with a custom alphabet.
+    #[cfg(feature = "sqids")]
+    Sqids(sqids::Sqids),
+    /// Deterministic encryption using `AES-GCM-SIV` with a fixed nonce.
+    AesSiv([u8; 32]),
+}
+
+impl IdCodec {
+    /// Attempts to create a new instance with Sqids.
+    #[cfg(feature = "sqids")]
+    pub fn try_with_sqids(alphabet: Option<&str>, min_length: u8) -> Result<Self, Error> {
+        let mut builder = sqids::Sqids::builder().min_length(min_length);
+        if let Some(alphabet) = alphabet {
+            builder = builder.alphabet(alphabet.chars().collect());
+        }
+        let sqids = builder.build().map_err(|err| warn!("{}", err))?;
+        Ok(Self::Sqids(sqids))
+    }
+
+    /// Creates a new instance with `AES-SIV` derived from the secret.
+    pub fn with_aes_siv(secret: &[u8]) -> Self {
+        let checksum = crypto::digest(secret);
+        let secret_key = crypto::derive_key("ZINO:ID-CODEC", &checksum);
+        let mut key = [0; 32];
+        key.copy_from_slice(&secret_key[..32]);
+        Self::AesSiv(key)
+    }
+
+    /// Returns a reference to the shared codec configured by the `[id-codec]` table.
+    #[inline]
+    pub fn shared() -> &'static Self {
+        &SHARED_ID_CODEC
+    }
+
+    /// Encodes the ID as a public ID.
+    pub fn encode(&self, id: &str) -> Result<String, Error> {
+        match self {
+            #[cfg(feature = "sqids")]
+            Self::Sqids(sqids) => {
+                let numbers = if let Ok(id) = id.parse::<u64>() {
+                    vec![id]
+                } else if let Ok(id) = id.parse::<Uuid>() {
+                    let (hi, lo) = id.as_u64_pair();
+                    vec![hi, lo]
+                } else {
+                    bail!("the ID `{}` should be a nonnegative integer or a UUID", id);
+                };
+                sqids.encode(&numbers).map_err(|err| warn!("{}", err))
+            }
+            Self::AesSiv(key) => {
+                let cipher = Aes256GcmSiv::new(GenericArray::from_slice(key));
+                let nonce = Nonce::from_slice(&FIXED_NONCE);
+                let ciphertext = cipher
+                    .encrypt(nonce, id.as_bytes())
+                    .map_err(|_| warn!("fail to encrypt the ID `{}`", id))?;
+                Ok(URL_SAFE_NO_PAD.encode(ciphertext))
+            }
+        }
+    }
+
+    /// Decodes the public ID as the native ID.
+    pub fn decode(&self, public_id: &str) -> Result<String, Error> {
+        match self {
+            #[cfg(feature = "sqids")]
+            Self::Sqids(sqids) => {
+                let numbers = sqids.decode(public_id);
+                let id = match numbers.as_slice() {
+                    [id] => id.to_string(),
+                    [hi, lo] => Uuid::from_u64_pair(*hi, *lo).to_string(),
+                    _ => bail!("404 Not Found: invalid public ID `{}`", public_id),
+                };
+                if sqids.encode(&numbers).ok().as_deref() != Some(public_id) {
+                    bail!("404 Not Found: invalid public ID `{}`", public_id);
+                }
+                Ok(id)
+            }
+            Self::AesSiv(key) => {
+                let data = URL_SAFE_NO_PAD
+                    .decode(public_id)
+                    .map_err(|_| warn!("404 Not Found: invalid public ID `{}`", public_id))?;
+                let cipher = Aes256GcmSiv::new(GenericArray::from_slice(key));
+                let nonce = Nonce::from_slice(&FIXED_NONCE);
+                let plaintext = cipher
+                    .decrypt(nonce, data.as_slice())
+                    .map_err(|_| warn!("404 Not Found: invalid public ID `{}`", public_id))?;
+                String::from_utf8(plaintext).map_err(Error::from)
+            }
+        }
+    }
+}
+
+/// Fixed nonce for the deterministic encryption.
+const FIXED_NONCE: [u8; 12] = [0; 12];
+
+/// Shared ID codec.
+static SHARED_ID_CODEC: LazyLock<IdCodec> = LazyLock::new(|| {
+    let config = State::shared().get_config("id-codec");
+    let algorithm = config
+        .and_then(|config| config.get_str("algorithm"))
+        .unwrap_or(if cfg!(feature = "sqids") {
+            "sqids"
+        } else {
+            "aes-siv"
+        });
+    #[cfg(feature = "sqids")]
+    if algorithm == "sqids" {
+        let alphabet = config.and_then(|config| config.get_str("alphabet"));
+        let min_length = config
+            .and_then(|config| config.get_u8("min-length"))
+            .unwrap_or(8);
+        match IdCodec::try_with_sqids(alphabet, min_length) {
+            Ok(codec) => return codec,
+            Err(err) => tracing::error!("fail to create the Sqids codec: {err}"),
+        }
+    }
+    if algorithm != "aes-siv" {
+        tracing::warn!("the ID codec `{algorithm}` is unsupported, falling back to `aes-siv`");
+    }
+
+    let secret = config
+        .and_then(|config| config.get_str("secret"))
+        .unwrap_or_else(|| {
+            tracing::warn!("an auto-generated `secret` is used for the ID codec");
+            State::shared().env().as_str()
+        });
+    IdCodec::with_aes_siv(secret.as_bytes())
+});
@@ -2,25 +2,37 @@
 use crate::{validation::Validation, AvroValue, JsonValue, Map, Record};
 use serde::{de::DeserializeOwned, Serialize};
 
+mod codec;
 mod column;
 mod context;
+mod enumeration;
 mod hook;
+mod id_codec;
 mod mutation;
+mod primary_key;
 mod query;
+mod range;
 mod reference;
 mod row;
+mod sheet;
 mod translation;
 
 #[doc(no_inline)]
 pub use apache_avro::schema;
 
+pub use codec::{AvroCodec, ProtobufCodec, SchemaFormat, SchemaRegistry};
 pub use column::{Column, EncodeColumn};
 pub use context::QueryContext;
+pub use enumeration::ModelEnum;
 pub use hook::ModelHooks;
+pub use id_codec::IdCodec;
 pub use mutation::Mutation;
+pub use primary_key::{PrimaryKeyStrategy, Snowflake};
 pub use query::Query;
+pub use range::Range;
 pub use reference::Reference;
 pub use row::DecodeRow;
+pub use sheet::{SheetReader, SheetRow};
 pub use translation::Translation;
 
 /// General data model.
@@ -44,6 +56,11 @@ pub trait Model: Default + Serialize + DeserializeOwned {
         Self::MODEL_NAME
     }
 
+    /// Sanitizes the model data before validation.
+    /// It is applied to the data for both creating and updating the models.
+    #[inline]
+    fn sanitize(_data: &mut Map) {}
+
     /// Updates the model using the json object and returns the validation result.
     #[must_use]
     fn read_map(&mut self, data: &Map) -> Validation {
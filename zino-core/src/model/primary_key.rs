@@ -0,0 +1,179 @@
+use crate::{datetime::DateTime, extension::TomlTableExt, state::State, LazyLock};
+use parking_lot::Mutex;
+use std::str::FromStr;
+
+/// Strategies for generating the primary key values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PrimaryKeyStrategy {
+    /// Randomly generated UUIDv4.
+    UuidV4,
+    /// Time-ordered UUIDv7.
+    #[default]
+    UuidV7,
+    /// Auto-incremented integer assigned by the database.
+    AutoIncrement,
+    /// Time-ordered 64-bit integer generated by the [`Snowflake`] generator.
+    Snowflake,
+    /// Values assigned by the application.
+    Manual,
+}
+
+impl PrimaryKeyStrategy {
+    /// Returns the strategy as a string.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::UuidV4 => "uuid_v4",
+            Self::UuidV7 => "uuid_v7",
+            Self::AutoIncrement => "auto_increment",
+            Self::Snowflake => "snowflake",
+            Self::Manual => "manual",
+        }
+    }
+
+    /// Returns `true` if the primary key value is assigned by the database.
+    #[inline]
+    pub fn is_database_generated(&self) -> bool {
+        matches!(self, Self::AutoIncrement)
+    }
+}
+
+impl FromStr for PrimaryKeyStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "uuid_v4" => Ok(Self::UuidV4),
+            "uuid_v7" => Ok(Self::UuidV7),
+            "auto_increment" => Ok(Self::AutoIncrement),
+            "snowflake" => Ok(Self::Snowflake),
+            "manual" => Ok(Self::Manual),
+            _ => Err(format!("invalid primary key strategy `{s}`")),
+        }
+    }
+}
+
+/// A generator of the snowflake IDs.
+///
+/// A snowflake ID is a positive `i64` composed of a 41-bit timestamp in milliseconds
+/// since `2024-01-01T00:00:00Z`, a 10-bit worker ID and a 12-bit sequence number.
+/// The worker ID of the shared generator is configured by the `[snowflake]` table:
+///
+/// ```toml
+/// [snowflake]
+/// worker-id = 1
+/// ```
+#[derive(Debug)]
+pub struct Snowflake {
+    /// Worker ID.
+    worker_id: i64,
+    /// The last timestamp and sequence number.
+    state: Mutex<(i64, i64)>,
+}
+
+impl Snowflake {
+    /// Creates a new instance with the worker ID.
+    /// Only the lowest 10 bits of the worker ID are used.
+    #[inline]
+    pub fn new(worker_id: u16) -> Self {
+        Self {
+            worker_id: i64::from(worker_id) & MAX_WORKER_ID,
+            state: Mutex::new((0, 0)),
+        }
+    }
+
+    /// Returns a reference to the shared generator.
+    #[inline]
+    pub fn shared() -> &'static Self {
+        LazyLock::force(&SHARED_SNOWFLAKE)
+    }
+
+    /// Returns the worker ID.
+    #[inline]
+    pub fn worker_id(&self) -> u16 {
+        self.worker_id as u16
+    }
+
+    /// Generates a new ID.
+    ///
+    /// The IDs are strictly increasing even if the system clock goes backwards.
+    pub fn next_id(&self) -> i64 {
+        let mut state = self.state.lock();
+        let (last_timestamp, sequence) = *state;
+        let mut timestamp = current_timestamp().max(last_timestamp);
+        let sequence = if timestamp == last_timestamp {
+            let sequence = (sequence + 1) & MAX_SEQUENCE;
+            if sequence == 0 {
+                // Borrows the next millisecond when the sequence is exhausted.
+                timestamp += 1;
+            }
+            sequence
+        } else {
+            0
+        };
+        *state = (timestamp, sequence);
+        (timestamp << TIMESTAMP_SHIFT) | (self.worker_id << WORKER_ID_SHIFT) | sequence
+    }
+
+    /// Decodes an ID into the creation time, worker ID and sequence number.
+    pub fn decode(id: i64) -> (DateTime, u16, u16) {
+        let timestamp = (id >> TIMESTAMP_SHIFT) + EPOCH_MILLIS;
+        let worker_id = (id >> WORKER_ID_SHIFT) & MAX_WORKER_ID;
+        let sequence = id & MAX_SEQUENCE;
+        (
+            DateTime::from_timestamp_millis(timestamp),
+            worker_id as u16,
+            sequence as u16,
+        )
+    }
+}
+
+/// Returns the number of milliseconds since the custom epoch.
+#[inline]
+fn current_timestamp() -> i64 {
+    DateTime::current_timestamp_millis() - EPOCH_MILLIS
+}
+
+/// Custom epoch `2024-01-01T00:00:00Z` in milliseconds.
+const EPOCH_MILLIS: i64 = 1_704_067_200_000;
+
+/// Maximum worker ID.
+const MAX_WORKER_ID: i64 = (1 << 10) - 1;
+
+/// Maximum sequence number.
+const MAX_SEQUENCE: i64 = (1 << 12) - 1;
+
+/// Number of bits to shift the worker ID.
+const WORKER_ID_SHIFT: u32 = 12;
+
+/// Number of bits to shift the timestamp.
+const TIMESTAMP_SHIFT: u32 = 22;
+
+/// Shared snowflake generator.
+static SHARED_SNOWFLAKE: LazyLock<Snowflake> = LazyLock::new(|| {
+    let worker_id = State::shared()
+        .get_config("snowflake")
+        .and_then(|config| config.get_u16("worker-id"))
+        .unwrap_or_default();
+    Snowflake::new(worker_id)
+});
+
+#[cfg(test)]
+mod tests {
+    use super::Snowflake;
+
+    #[test]
+    fn it_generates_snowflake_ids() {
+        let snowflake = Snowflake::new(7);
+        let mut last_id = 0;
+        for _ in 0..10000 {
+            let id = snowflake.next_id();
+            assert!(id > last_id);
+            last_id = id;
+        }
+
+        let (_, worker_id, _) = Snowflake::decode(last_id);
+        assert_eq!(worker_id, 7);
+    }
+}
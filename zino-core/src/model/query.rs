@@ -4,6 +4,7 @@ use crate::{
     JsonValue, Map, SharedString,
 };
 use smallvec::SmallVec;
+use std::time::Duration;
 
 #[derive(Debug, Clone)]
 /// A query type for models.
@@ -20,6 +21,14 @@ pub struct Query {
     limit: usize,
     // Extra flags.
     extra: Map,
+    // Timeout.
+    timeout: Option<Duration>,
+    // Max number of rows.
+    max_rows: Option<usize>,
+    // Model columns restricted by the filter allowlist.
+    filter_columns: &'static [&'static str],
+    // Allowlist of filterable fields and operators.
+    filter_allowlist: &'static [(&'static str, &'static [&'static str])],
 }
 
 impl Query {
@@ -33,6 +42,10 @@ impl Query {
             offset: 0,
             limit: 0,
             extra: Map::new(),
+            timeout: None,
+            max_rows: None,
+            filter_columns: &[],
+            filter_allowlist: &[],
         }
     }
 
@@ -49,6 +62,8 @@ impl Query {
         let mut pagination_current_page = None;
         let filters = &mut self.filters;
         let extra = &mut self.extra;
+        let filter_columns = self.filter_columns;
+        let filter_allowlist = self.filter_allowlist;
         for (key, value) in data.iter().filter(|(_, v)| !v.is_ignorable()) {
             match key.as_str() {
                 "fields" | "columns" => {
@@ -110,24 +125,32 @@ impl Query {
                     extra.upsert(key, value.clone());
                 }
                 _ => {
-                    if let Some(value) = value.as_str().filter(|&s| s != "all") {
+                    let filter = if let Some(value) = value.as_str().filter(|&s| s != "all") {
                         if key.starts_with('$') {
                             if let Some(expr) = value.strip_prefix('(') {
-                                filters.upsert(key, Self::parse_logical_query(expr));
+                                Self::parse_logical_query(expr).into()
                             } else {
-                                filters.upsert(key, value);
+                                value.into()
                             }
+                        } else if let Some(filter) = Self::parse_filter_expr(value) {
+                            filter.into()
                         } else if value.starts_with('$') {
                             if let Some((operator, value)) = value.split_once('.') {
-                                filters.upsert(key, Map::from_entry(operator, value));
+                                Map::from_entry(operator, value).into()
                             } else {
-                                filters.upsert(key, value);
+                                value.into()
                             }
                         } else {
-                            filters.upsert(key, value);
+                            value.into()
                         }
                     } else {
-                        filters.upsert(key, value.clone());
+                        value.clone()
+                    };
+                    let restricted = key.starts_with('$') || filter_columns.contains(&key.as_str());
+                    if !restricted || Self::allows_filter(filter_allowlist, key, &filter) {
+                        filters.upsert(key, filter);
+                    } else {
+                        validation.record(key.to_owned(), "the filter is not allowed");
                     }
                 }
             }
@@ -161,6 +184,100 @@ impl Query {
         filters
     }
 
+    /// Parses the PostgREST-style filter expression `operator.value`
+    /// such as `ne.Deleted`, `gte.18` and `in.(a,b)`.
+    fn parse_filter_expr(expr: &str) -> Option<Map> {
+        let (operator, value) = expr.split_once('.')?;
+        let operator = match operator.strip_prefix('$').unwrap_or(operator) {
+            "eq" => "$eq",
+            "ne" | "neq" => "$ne",
+            "lt" => "$lt",
+            "le" | "lte" => "$le",
+            "gt" => "$gt",
+            "ge" | "gte" => "$ge",
+            "in" => "$in",
+            "nin" => "$nin",
+            "betw" => "$betw",
+            "like" => "$like",
+            "ilike" => "$ilike",
+            "rlike" => "$rlike",
+            "is" => "$is",
+            _ => return None,
+        };
+        let value = match operator {
+            "$in" | "$nin" | "$betw" => {
+                if let Some(values) = value.strip_prefix('(').and_then(|s| s.strip_suffix(')')) {
+                    values
+                        .split(',')
+                        .map(|s| s.trim())
+                        .collect::<Vec<_>>()
+                        .into()
+                } else {
+                    value
+                        .split(',')
+                        .map(|s| s.trim())
+                        .collect::<Vec<_>>()
+                        .into()
+                }
+            }
+            "$is" => match value {
+                "null" => JsonValue::Null,
+                "true" => true.into(),
+                "false" => false.into(),
+                _ => value.into(),
+            },
+            _ => value.into(),
+        };
+        Some(Map::from_entry(operator, value))
+    }
+
+    /// Returns `true` if the filter is allowed by the allowlist.
+    /// An empty allowlist allows all the filters.
+    fn allows_filter(allowlist: &[(&str, &[&str])], key: &str, filter: &JsonValue) -> bool {
+        if allowlist.is_empty() {
+            return true;
+        }
+        if matches!(key, "$and" | "$or" | "$not" | "$nor") {
+            return filter.as_array().is_some_and(|filters| {
+                filters.iter().all(|filter| {
+                    filter.as_object().is_some_and(|filter| {
+                        filter
+                            .iter()
+                            .all(|(key, value)| Self::allows_filter(allowlist, key, value))
+                    })
+                })
+            });
+        }
+
+        let Some((_, operators)) = allowlist.iter().find(|(field, _)| *field == key) else {
+            return false;
+        };
+        if operators.is_empty() {
+            true
+        } else if let Some(filter) = filter.as_object() {
+            filter.keys().all(|operator| {
+                let operator = operator.strip_prefix('$').unwrap_or(operator);
+                operators.contains(&operator)
+            })
+        } else {
+            operators.contains(&"eq")
+        }
+    }
+
+    /// Sets the allowlist of filterable fields and operators for [`read_map()`](Self::read_map).
+    /// An empty list of operators allows all the operators for the field.
+    /// The allowlist only applies to the model columns and the filter operators,
+    /// so the other params such as `mode` and `total_rows` are not rejected.
+    #[inline]
+    pub fn allow_filters(
+        &mut self,
+        columns: &'static [&'static str],
+        allowlist: &'static [(&'static str, &'static [&'static str])],
+    ) {
+        self.filter_columns = columns;
+        self.filter_allowlist = allowlist;
+    }
+
     /// Retains the projection fields in the allow list.
     /// If the projection fields are empty, it will be set to the list.
     #[inline]
@@ -179,6 +296,15 @@ impl Query {
             .retain(|field| !fields.contains(&field.as_str()))
     }
 
+    /// Adds a projection field if it does not exist.
+    #[inline]
+    pub fn add_field(&mut self, field: impl Into<String>) {
+        let field = field.into();
+        if !self.fields.contains(&field) {
+            self.fields.push(field);
+        }
+    }
+
     /// Adds a projection field with the alias.
     #[inline]
     pub fn add_field_alias(&mut self, expr: impl Into<String>, alias: impl Into<String>) {
@@ -251,12 +377,37 @@ impl Query {
         self.limit = limit;
     }
 
+    /// Sets the timeout of the query. The query will be cancelled if it takes too long.
+    #[inline]
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = Some(timeout);
+    }
+
+    /// Sets the max number of rows returned by the query,
+    /// which takes precedence over the row cap of the model.
+    #[inline]
+    pub fn set_max_rows(&mut self, max_rows: usize) {
+        self.max_rows = Some(max_rows);
+    }
+
     /// Disables the query limit.
     #[inline]
     pub fn disable_limit(&mut self) {
         self.limit = 0;
     }
 
+    /// Caps the query limit by the max number of rows, and returns `true` if it is capped.
+    /// In this case, the limit is set to `max_rows + 1` so that the extra row
+    /// can be used to detect whether the results are truncated.
+    #[inline]
+    pub fn cap_limit(&mut self, max_rows: usize) -> bool {
+        let capped = self.limit == 0 || self.limit > max_rows;
+        if capped {
+            self.limit = max_rows.saturating_add(1);
+        }
+        capped
+    }
+
     /// Returns a reference to the projection fields.
     #[inline]
     pub fn fields(&self) -> &[String] {
@@ -288,6 +439,31 @@ impl Query {
         self.limit
     }
 
+    /// Returns the query timeout.
+    #[inline]
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Returns the max number of rows returned by the query.
+    #[inline]
+    pub fn max_rows(&self) -> Option<usize> {
+        self.max_rows
+    }
+
+    /// Resolves the max number of rows, which is determined by the query
+    /// and the row cap of the model in order, and capped by the `upper_bound`.
+    #[inline]
+    pub(crate) fn resolve_max_rows(
+        &self,
+        model_max_rows: Option<usize>,
+        upper_bound: usize,
+    ) -> usize {
+        self.max_rows
+            .or(model_max_rows)
+            .map_or(upper_bound, |max_rows| max_rows.min(upper_bound))
+    }
+
     /// Returns `true` if the `flag` has been enabled.
     #[inline]
     pub fn enabled(&self, flag: &str) -> bool {
@@ -335,6 +511,91 @@ impl Default for Query {
             offset: 0,
             limit: 10,
             extra: Map::new(),
+            timeout: None,
+            max_rows: None,
+            filter_columns: &[],
+            filter_allowlist: &[],
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Query;
+    use crate::{extension::JsonObjectExt, JsonValue, Map};
+
+    #[test]
+    fn it_parses_filter_expressions() {
+        let mut data = Map::new();
+        data.upsert("status", "ne.Deleted");
+        data.upsert("age", "gte.18");
+        data.upsert("tags", "in.(a,b)");
+        data.upsert("name", "alice");
+
+        let mut query = Query::default();
+        assert!(query.read_map(&data).is_success());
+
+        let filters = query.filters();
+        assert_eq!(
+            filters.get("status"),
+            Some(&Map::from_entry("$ne", "Deleted").into())
+        );
+        assert_eq!(
+            filters.get("age"),
+            Some(&Map::from_entry("$ge", "18").into())
+        );
+        assert_eq!(
+            filters.get("tags"),
+            Some(&Map::from_entry("$in", vec!["a", "b"]).into())
+        );
+        assert_eq!(filters.get_str("name"), Some("alice"));
+
+        let mut query = Query::default();
+        query.allow_filters(
+            &["status", "age", "tags", "name"],
+            &[("status", &["eq", "ne"]), ("tags", &[])],
+        );
+        assert!(!query.read_map(&data).is_success());
+        assert!(query.filters().contains_key("status"));
+        assert!(query.filters().contains_key("tags"));
+        assert!(!query.filters().contains_key("age"));
+        assert!(!query.filters().contains_key("name"));
+        assert_eq!(
+            query.filters().get("status"),
+            Some(&JsonValue::from(Map::from_entry("$ne", "Deleted")))
+        );
+
+        let mut query = Query::default();
+        query.allow_filters(&["status"], &[("status", &[])]);
+        let mut data = Map::from_entry("status", "Active");
+        data.upsert("mode", "snapshot");
+        data.upsert("total_rows", "1024");
+        assert!(query.read_map(&data).is_success());
+        assert!(query.filters().contains_key("mode"));
+    }
+
+    #[test]
+    fn it_caps_query_limits() {
+        let mut query = Query::default();
+        assert_eq!(query.resolve_max_rows(None, 10000), 10000);
+        assert_eq!(query.resolve_max_rows(Some(100), 10000), 100);
+        assert_eq!(query.resolve_max_rows(Some(20000), 10000), 10000);
+
+        query.set_max_rows(50);
+        assert_eq!(query.resolve_max_rows(Some(100), 10000), 50);
+        query.set_max_rows(500);
+        assert_eq!(query.resolve_max_rows(Some(100), 10000), 500);
+        assert_eq!(query.resolve_max_rows(Some(100), 200), 200);
+
+        query.set_limit(10);
+        assert!(!query.cap_limit(10));
+        assert_eq!(query.limit(), 10);
+        assert!(query.cap_limit(5));
+        assert_eq!(query.limit(), 6);
+
+        query.disable_limit();
+        assert!(query.cap_limit(100));
+        assert_eq!(query.limit(), 101);
+        assert!(!query.cap_limit(101));
+    }
+}
@@ -0,0 +1,280 @@
+use crate::{bail, error::Error, warn};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+/// A range of values with optional bounds, which is mapped to the range types
+/// such as `int4range`, `int8range`, `daterange` and `tstzrange` in PostgreSQL.
+///
+/// It is serialized as a string in the canonical form of PostgreSQL,
+/// such as `[1,10)` or `[2024-06-01T09:00:00+08:00,)`, and an empty range as `empty`.
+///
+/// # Examples
+///
+/// ```rust
+/// use zino_core::{datetime::DateTime, model::Range};
+///
+/// let first = "[2024-06-01T09:00:00+08:00,2024-06-01T10:00:00+08:00)".parse::<Range<DateTime>>()?;
+/// let second = "[2024-06-01T10:00:00+08:00,2024-06-01T11:00:00+08:00)".parse::<Range<DateTime>>()?;
+/// assert!(!first.overlaps(&second));
+/// # Ok::<(), zino_core::error::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Range<T> {
+    /// Lower bound.
+    start: Option<T>,
+    /// Upper bound.
+    end: Option<T>,
+    /// A flag to indicate that the lower bound is inclusive.
+    include_start: bool,
+    /// A flag to indicate that the upper bound is inclusive.
+    include_end: bool,
+    /// A flag to indicate that the range is empty.
+    empty: bool,
+}
+
+impl<T> Range<T> {
+    /// Creates a new instance with an inclusive lower bound and an exclusive upper bound,
+    /// which is the canonical form for discrete types.
+    #[inline]
+    pub fn new(start: T, end: T) -> Self {
+        Self::with_bounds(Some(start), Some(end), true, false)
+    }
+
+    /// Creates a new instance with both bounds inclusive.
+    #[inline]
+    pub fn closed(start: T, end: T) -> Self {
+        Self::with_bounds(Some(start), Some(end), true, true)
+    }
+
+    /// Creates a new instance with the bounds. A missing bound means unbounded.
+    #[inline]
+    pub fn with_bounds(
+        start: Option<T>,
+        end: Option<T>,
+        include_start: bool,
+        include_end: bool,
+    ) -> Self {
+        Self {
+            include_start: include_start && start.is_some(),
+            include_end: include_end && end.is_some(),
+            start,
+            end,
+            empty: false,
+        }
+    }
+
+    /// Creates an unbounded range.
+    #[inline]
+    pub fn unbounded() -> Self {
+        Self::with_bounds(None, None, false, false)
+    }
+
+    /// Creates an empty range.
+    #[inline]
+    pub fn empty() -> Self {
+        Self {
+            start: None,
+            end: None,
+            include_start: false,
+            include_end: false,
+            empty: true,
+        }
+    }
+
+    /// Returns the lower bound.
+    #[inline]
+    pub fn start(&self) -> Option<&T> {
+        self.start.as_ref()
+    }
+
+    /// Returns the upper bound.
+    #[inline]
+    pub fn end(&self) -> Option<&T> {
+        self.end.as_ref()
+    }
+
+    /// Returns `true` if the lower bound is inclusive.
+    #[inline]
+    pub fn include_start(&self) -> bool {
+        self.include_start
+    }
+
+    /// Returns `true` if the upper bound is inclusive.
+    #[inline]
+    pub fn include_end(&self) -> bool {
+        self.include_end
+    }
+}
+
+impl<T: PartialOrd> Range<T> {
+    /// Returns `true` if the range contains no values.
+    pub fn is_empty(&self) -> bool {
+        if self.empty {
+            return true;
+        }
+        match (&self.start, &self.end) {
+            (Some(start), Some(end)) => {
+                start > end || (start == end && !(self.include_start && self.include_end))
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the range contains the value.
+    pub fn contains(&self, value: &T) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+        let after_start = match &self.start {
+            Some(start) if self.include_start => value >= start,
+            Some(start) => value > start,
+            None => true,
+        };
+        let before_end = match &self.end {
+            Some(end) if self.include_end => value <= end,
+            Some(end) => value < end,
+            None => true,
+        };
+        after_start && before_end
+    }
+
+    /// Returns `true` if the range contains all the values of the other range.
+    pub fn contains_range(&self, other: &Self) -> bool {
+        if other.is_empty() {
+            return true;
+        } else if self.is_empty() {
+            return false;
+        }
+        let start_covered = match (&self.start, &other.start) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(start), Some(other_start)) => {
+                start < other_start
+                    || (start == other_start && (self.include_start || !other.include_start))
+            }
+        };
+        let end_covered = match (&self.end, &other.end) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(end), Some(other_end)) => {
+                end > other_end || (end == other_end && (self.include_end || !other.include_end))
+            }
+        };
+        start_covered && end_covered
+    }
+
+    /// Returns `true` if the two ranges have values in common,
+    /// which can be used to detect the conflicts of time slots.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        if self.is_empty() || other.is_empty() {
+            return false;
+        }
+        let starts_before_other_ends = match (&self.start, &other.end) {
+            (Some(start), Some(end)) => {
+                start < end || (start == end && self.include_start && other.include_end)
+            }
+            _ => true,
+        };
+        let ends_after_other_starts = match (&self.end, &other.start) {
+            (Some(end), Some(start)) => {
+                end > start || (end == start && self.include_end && other.include_start)
+            }
+            _ => true,
+        };
+        starts_before_other_ends && ends_after_other_starts
+    }
+}
+
+impl<T> Default for Range<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Range<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.empty {
+            return f.write_str("empty");
+        }
+        f.write_str(if self.include_start { "[" } else { "(" })?;
+        if let Some(start) = &self.start {
+            write_bound(f, start)?;
+        }
+        f.write_str(",")?;
+        if let Some(end) = &self.end {
+            write_bound(f, end)?;
+        }
+        f.write_str(if self.include_end { "]" } else { ")" })
+    }
+}
+
+impl<T: FromStr> FromStr for Range<T> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("empty") {
+            return Ok(Self::empty());
+        }
+
+        let include_start = match s.chars().next() {
+            Some('[') => true,
+            Some('(') => false,
+            _ => bail!("invalid range `{}`", s),
+        };
+        let include_end = match s.chars().last() {
+            Some(']') => true,
+            Some(')') => false,
+            _ => bail!("invalid range `{}`", s),
+        };
+        let Some((start, end)) = s.get(1..s.len() - 1).and_then(|s| s.split_once(',')) else {
+            bail!("invalid range `{}`", s);
+        };
+        let parse_bound = |bound: &str| -> Result<Option<T>, Error> {
+            let bound = bound.trim().trim_matches('"');
+            if bound.is_empty()
+                || bound.eq_ignore_ascii_case("infinity")
+                || bound.eq_ignore_ascii_case("-infinity")
+            {
+                Ok(None)
+            } else {
+                bound
+                    .parse()
+                    .map(Some)
+                    .map_err(|_| warn!("invalid range bound `{}`", bound))
+            }
+        };
+        let start = parse_bound(start)?;
+        let end = parse_bound(end)?;
+        Ok(Self::with_bounds(start, end, include_start, include_end))
+    }
+}
+
+impl<T: fmt::Display> Serialize for Range<T> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de, T: FromStr> Deserialize<'de> for Range<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Writes a range bound, which is quoted if it contains special characters.
+fn write_bound(f: &mut fmt::Formatter, bound: &impl fmt::Display) -> fmt::Result {
+    let bound = bound.to_string();
+    if bound.contains(|c: char| c.is_whitespace() || "\",()[]\\".contains(c)) {
+        write!(
+            f,
+            "\"{}\"",
+            bound.replace('\\', "\\\\").replace('"', "\\\"")
+        )
+    } else {
+        f.write_str(&bound)
+    }
+}
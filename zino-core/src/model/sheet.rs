@@ -0,0 +1,383 @@
+use super::Column;
+use crate::{
+    datetime::{Date, DateTime, Time},
+    error::Error,
+    validation::Validation,
+    Decimal, JsonValue, Map, Uuid,
+};
+use std::{collections::HashMap, io::Read};
+
+/// A reader for the spreadsheets, which maps the sheet columns to the model columns
+/// and coerces the cell values according to the column types.
+///
+/// The sheet columns are mapped by the user-supplied mapping first,
+/// and then by matching the headers with the column names or comments.
+/// Unmapped sheet columns are ignored.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino_core::{model::SheetReader, orm::Schema};
+///
+/// let mut reader = SheetReader::new(User::columns());
+/// reader.add_column_mapping("Full Name", "name");
+/// for row in reader.read_csv(file.as_ref()) {
+///     let row = row?;
+///     if row.is_valid() {
+///         let mut user = User::new();
+///         user.read_map(row.data());
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SheetReader<'a> {
+    /// Model columns.
+    columns: &'a [Column<'a>],
+    /// A mapping from the sheet headers to the column names.
+    mapping: HashMap<String, String>,
+    /// Sheet name of the workbook.
+    sheet_name: Option<String>,
+}
+
+impl<'a> SheetReader<'a> {
+    /// Creates a new instance with the model columns.
+    #[inline]
+    pub fn new(columns: &'a [Column<'a>]) -> Self {
+        Self {
+            columns,
+            mapping: HashMap::new(),
+            sheet_name: None,
+        }
+    }
+
+    /// Adds a mapping from the sheet header to the column name.
+    #[inline]
+    pub fn add_column_mapping(&mut self, header: impl Into<String>, column: impl Into<String>) {
+        self.mapping.insert(header.into(), column.into());
+    }
+
+    /// Appends the mapping from the sheet headers to the column names.
+    pub fn append_column_mapping(&mut self, mapping: &Map) {
+        for (header, column) in mapping {
+            if let Some(column) = column.as_str() {
+                self.mapping.insert(header.to_owned(), column.to_owned());
+            }
+        }
+    }
+
+    /// Sets the sheet name of the workbook. Defaults to the first sheet.
+    #[inline]
+    pub fn set_sheet_name(&mut self, sheet_name: impl Into<String>) {
+        self.sheet_name = Some(sheet_name.into());
+    }
+
+    /// Reads the CSV data as a stream of rows. The first record should be the headers.
+    pub fn read_csv<R: Read + 'a>(
+        &self,
+        reader: R,
+    ) -> impl Iterator<Item = Result<SheetRow, Error>> + 'a {
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(reader);
+        let (columns, header_error) = match reader.headers() {
+            Ok(headers) => (self.match_headers(headers.iter()), None),
+            Err(err) => (Vec::new(), Some(Error::from(err))),
+        };
+        let records = reader
+            .into_records()
+            .enumerate()
+            .map(move |(index, record)| {
+                let record = record?;
+                let mut row = SheetRow::new(index + 2);
+                for (column_index, value) in record.iter().enumerate() {
+                    if let Some(Some(column)) = columns.get(column_index) {
+                        row.insert_cell(column, column_index, value);
+                    }
+                }
+                Ok(row)
+            });
+        header_error.map(Err).into_iter().chain(records)
+    }
+
+    /// Reads the XLSX workbook as rows. The first row of the sheet should be the headers.
+    ///
+    /// The cells are streamed from the worksheet so that the unmapped columns
+    /// are never loaded into memory.
+    #[cfg(feature = "xlsx")]
+    pub fn read_xlsx<R: Read + std::io::Seek>(&self, reader: R) -> Result<Vec<SheetRow>, Error> {
+        use calamine::{DataRef, Reader, Xlsx};
+
+        let mut workbook = Xlsx::new(reader)?;
+        let sheet_name = match self.sheet_name.as_ref() {
+            Some(sheet_name) => sheet_name.to_owned(),
+            None => workbook
+                .sheet_names()
+                .first()
+                .cloned()
+                .ok_or_else(|| crate::warn!("the workbook does not have any sheets"))?,
+        };
+        let mut cells = workbook.worksheet_cells_reader(&sheet_name)?;
+
+        let mut header_row = None;
+        let mut headers = Vec::new();
+        let mut columns = Vec::new();
+        let mut rows = Vec::new();
+        let mut current_row: Option<SheetRow> = None;
+        while let Some(cell) = cells.next_cell()? {
+            let (row_index, column_index) = cell.get_position();
+            let row_index = usize::try_from(row_index)?;
+            let column_index = usize::try_from(column_index)?;
+            let value = match cell.get_value() {
+                DataRef::Empty => continue,
+                DataRef::String(s) | DataRef::DateTimeIso(s) | DataRef::DurationIso(s) => {
+                    s.to_owned()
+                }
+                DataRef::SharedString(s) => (*s).to_owned(),
+                DataRef::Int(i) => i.to_string(),
+                DataRef::Float(f) => f.to_string(),
+                DataRef::Bool(b) => b.to_string(),
+                DataRef::DateTime(dt) => match dt.as_datetime() {
+                    Some(dt) if dt.time() == chrono::NaiveTime::default() => {
+                        dt.format("%Y-%m-%d").to_string()
+                    }
+                    Some(dt) => dt.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                    None => dt.as_f64().to_string(),
+                },
+                DataRef::Error(err) => format!("#{err}"),
+            };
+
+            // The first row with any values is treated as the headers.
+            if header_row.map_or(true, |index| index == row_index) {
+                header_row = Some(row_index);
+                if headers.len() <= column_index {
+                    headers.resize(column_index + 1, String::new());
+                }
+                headers[column_index] = value;
+                continue;
+            }
+            if columns.is_empty() {
+                columns = self.match_headers(headers.iter().map(|s| s.as_str()));
+            }
+
+            let Some(Some(column)) = columns.get(column_index) else {
+                continue;
+            };
+            let row_number = row_index + 1;
+            if current_row
+                .as_ref()
+                .is_some_and(|row| row.row_number != row_number)
+            {
+                rows.extend(current_row.take());
+            }
+            let row = current_row.get_or_insert_with(|| SheetRow::new(row_number));
+            if matches!(cell.get_value(), DataRef::Error(_)) {
+                let message = format!(
+                    "the cell `{}` has an error value `{value}`",
+                    cell_reference(column_index, row_number)
+                );
+                row.validation.record(column.name().to_owned(), message);
+            } else {
+                row.insert_cell(column, column_index, &value);
+            }
+        }
+        rows.extend(current_row);
+        Ok(rows)
+    }
+
+    /// Matches the sheet headers with the model columns.
+    fn match_headers<'b>(
+        &self,
+        headers: impl Iterator<Item = &'b str>,
+    ) -> Vec<Option<&'a Column<'a>>> {
+        let columns = self.columns;
+        headers
+            .map(|header| {
+                let header = header.trim();
+                if let Some(column_name) = self.mapping.get(header) {
+                    return columns.iter().find(|col| col.name() == column_name);
+                }
+
+                let normalized_header = normalize_header(header);
+                columns
+                    .iter()
+                    .filter(|col| !col.is_read_only() && !col.is_generated())
+                    .find(|col| {
+                        col.name() == normalized_header
+                            || col
+                                .comment()
+                                .is_some_and(|comment| comment.trim().eq_ignore_ascii_case(header))
+                    })
+            })
+            .collect()
+    }
+}
+
+/// A row read from the spreadsheet.
+#[derive(Debug, Clone)]
+pub struct SheetRow {
+    /// Row number in the sheet, which starts from `1` for the headers.
+    row_number: usize,
+    /// Data of the mapped columns.
+    data: Map,
+    /// Validation of the cells.
+    validation: Validation,
+}
+
+impl SheetRow {
+    /// Creates a new instance.
+    #[inline]
+    fn new(row_number: usize) -> Self {
+        Self {
+            row_number,
+            data: Map::new(),
+            validation: Validation::new(),
+        }
+    }
+
+    /// Returns the row number in the sheet.
+    #[inline]
+    pub fn row_number(&self) -> usize {
+        self.row_number
+    }
+
+    /// Returns a reference to the data of the mapped columns.
+    #[inline]
+    pub fn data(&self) -> &Map {
+        &self.data
+    }
+
+    /// Returns `true` if all the cells have been coerced successfully.
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.validation.is_success()
+    }
+
+    /// Consumes `self` and returns the data and the validation of the cells.
+    #[inline]
+    pub fn into_parts(self) -> (Map, Validation) {
+        (self.data, self.validation)
+    }
+
+    /// Coerces the cell value and inserts it into the data.
+    /// Empty cells are skipped so that the default values can be used.
+    fn insert_cell(&mut self, column: &Column<'_>, column_index: usize, value: &str) {
+        let value = value.trim();
+        if value.is_empty() {
+            return;
+        }
+
+        let column_name = column.name();
+        match coerce_value(column.type_name(), value) {
+            Ok(value) => {
+                self.data.insert(column_name.to_owned(), value);
+            }
+            Err(err) => {
+                let message = format!(
+                    "invalid value `{value}` in the cell `{}`: {err}",
+                    cell_reference(column_index, self.row_number)
+                );
+                self.validation.record(column_name.to_owned(), message);
+            }
+        }
+    }
+}
+
+/// Coerces the cell value according to the column type.
+fn coerce_value(type_name: &str, value: &str) -> Result<JsonValue, Error> {
+    let type_name = type_name
+        .strip_prefix("Option<")
+        .and_then(|s| s.strip_suffix('>'))
+        .unwrap_or(type_name);
+    if let Some(type_name) = type_name
+        .strip_prefix("Vec<")
+        .and_then(|s| s.strip_suffix('>'))
+    {
+        if type_name == "Map" {
+            return Ok(serde_json::from_str(value)?);
+        }
+        let values = value
+            .split([',', ';'])
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(|s| coerce_value(type_name, s))
+            .collect::<Result<Vec<_>, _>>()?;
+        return Ok(values.into());
+    }
+
+    let value = match type_name {
+        "bool" => match value.to_ascii_lowercase().as_str() {
+            "true" | "yes" | "y" | "1" => true.into(),
+            "false" | "no" | "n" | "0" => false.into(),
+            _ => return Err(crate::warn!("the value is not a boolean")),
+        },
+        "u64" | "u32" | "u16" | "u8" | "usize" => u64::try_from(parse_integer(value)?)
+            .map_err(|_| crate::warn!("the value is not an unsigned integer"))?
+            .into(),
+        "i64" | "i32" | "i16" | "i8" | "isize" => parse_integer(value)?.into(),
+        "f64" | "f32" => value.parse::<f64>()?.into(),
+        "Decimal" => Decimal::from_str_exact(value)
+            .or_else(|_| Decimal::from_scientific(value))?
+            .to_string()
+            .into(),
+        "Uuid" => value.parse::<Uuid>()?.to_string().into(),
+        "Date" | "NaiveDate" => value.parse::<Date>()?.to_string().into(),
+        "Time" | "NaiveTime" => value.parse::<Time>()?.to_string().into(),
+        "DateTime" | "NaiveDateTime" => value.parse::<DateTime>()?.to_string().into(),
+        "Map" | "JsonValue" => serde_json::from_str(value)?,
+        _ => value.into(),
+    };
+    Ok(value)
+}
+
+/// Parses an integer, which may be formatted as a float with a zero fraction in the spreadsheet.
+fn parse_integer(value: &str) -> Result<i64, Error> {
+    let integer = match value.strip_suffix(".0") {
+        Some(value) => value.parse()?,
+        None => value.parse()?,
+    };
+    Ok(integer)
+}
+
+/// Normalizes the sheet header as a column name.
+fn normalize_header(header: &str) -> String {
+    header
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_whitespace() || c == '-' {
+                '_'
+            } else {
+                c.to_ascii_lowercase()
+            }
+        })
+        .collect()
+}
+
+/// Returns the A1-style reference of a cell.
+fn cell_reference(column_index: usize, row_number: usize) -> String {
+    let mut column_name = Vec::new();
+    let mut index = column_index + 1;
+    while index > 0 {
+        let remainder = u8::try_from((index - 1) % 26).unwrap_or_default();
+        column_name.push(char::from(b'A' + remainder));
+        index = (index - 1) / 26;
+    }
+    column_name.iter().rev().collect::<String>() + &row_number.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::coerce_value;
+
+    #[test]
+    fn it_coerces_decimals() {
+        let value = coerce_value("Decimal", "1234567890.123456789").unwrap();
+        assert_eq!(value, "1234567890.123456789");
+
+        let value = coerce_value("Option<Decimal>", "1.5E+3").unwrap();
+        assert_eq!(value, "1500");
+
+        assert!(coerce_value("Decimal", "abc").is_err());
+    }
+}
@@ -0,0 +1,113 @@
+use crate::{bail, error::Error, extension::TomlTableExt, state::State, LazyLock};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+/// An ISO 4217 currency code, such as `USD` or `EUR`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Currency([u8; 3]);
+
+impl Currency {
+    /// Attempts to create a new instance from the three-letter currency code.
+    pub fn try_new(code: &str) -> Result<Self, Error> {
+        match code.as_bytes() {
+            [a, b, c] if code.chars().all(|c| c.is_ascii_alphabetic()) => Ok(Self([
+                a.to_ascii_uppercase(),
+                b.to_ascii_uppercase(),
+                c.to_ascii_uppercase(),
+            ])),
+            _ => bail!("invalid currency code `{}`", code),
+        }
+    }
+
+    /// Returns the three-letter currency code.
+    #[inline]
+    pub fn code(&self) -> &str {
+        std::str::from_utf8(&self.0).unwrap_or_default()
+    }
+
+    /// Returns the number of digits after the decimal separator in the minor unit.
+    pub fn minor_unit(&self) -> u32 {
+        match self.code() {
+            "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF"
+            | "UGX" | "UYI" | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+            "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+            _ => 2,
+        }
+    }
+
+    /// Returns the commonly used symbol of the currency if it has one.
+    pub fn symbol(&self) -> Option<&'static str> {
+        let symbol = match self.code() {
+            "USD" => "$",
+            "EUR" => "€",
+            "GBP" => "£",
+            "JPY" | "CNY" => "¥",
+            "KRW" => "₩",
+            "INR" => "₹",
+            "RUB" => "₽",
+            "AUD" => "A$",
+            "CAD" => "CA$",
+            "HKD" => "HK$",
+            "BRL" => "R$",
+            _ => return None,
+        };
+        Some(symbol)
+    }
+}
+
+impl Default for Currency {
+    /// Returns the default currency configured by the `[money]` table.
+    #[inline]
+    fn default() -> Self {
+        *DEFAULT_CURRENCY
+    }
+}
+
+impl fmt::Debug for Currency {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Currency").field(&self.code()).finish()
+    }
+}
+
+impl fmt::Display for Currency {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for Currency {
+    type Err = Error;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_new(s.trim())
+    }
+}
+
+impl Serialize for Currency {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for Currency {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        Self::try_new(&code).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Default currency.
+static DEFAULT_CURRENCY: LazyLock<Currency> = LazyLock::new(|| {
+    let code = State::shared()
+        .get_config("money")
+        .and_then(|config| config.get_str("currency"))
+        .unwrap_or("USD");
+    Currency::try_new(code).unwrap_or_else(|err| {
+        tracing::error!("fail to parse the default currency: {err}");
+        Currency(*b"USD")
+    })
+});
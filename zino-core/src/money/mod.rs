@@ -0,0 +1,371 @@
+//! Monetary amounts with currency-aware arithmetic.
+
+use crate::{bail, error::Error, warn, Decimal, JsonValue};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{cmp::Ordering, fmt, ops::Neg, str::FromStr};
+
+mod currency;
+mod rounding;
+
+pub use currency::Currency;
+pub use rounding::RoundingPolicy;
+
+/// A monetary amount represented as an integer number of minor units with a currency,
+/// which avoids the rounding errors of floating-point numbers.
+///
+/// It is serialized as a string such as `"1234.56 USD"`, and can be deserialized from
+/// such a string, a number or a string without the currency code using the default currency,
+/// or an object with the `amount` and `currency` fields. The default currency can be
+/// configured by the `[money]` table.
+///
+/// # Examples
+///
+/// ```rust
+/// use zino_core::{money::{Money, RoundingPolicy}, Decimal};
+///
+/// let price = "19.99 USD".parse::<Money>()?;
+/// let total = price.checked_mul(Decimal::new(3, 0), RoundingPolicy::HalfEven)?;
+/// assert_eq!(total.to_string(), "59.97 USD");
+/// assert_eq!(total.format_locale("en-US"), "$59.97");
+///
+/// let parts = total.allocate(&[1, 1, 1])?;
+/// assert_eq!(parts[0].to_string(), "19.99 USD");
+/// # Ok::<(), zino_core::error::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Money {
+    /// Amount in minor units.
+    amount: i128,
+    /// Currency.
+    currency: Currency,
+}
+
+impl Money {
+    /// Creates a new instance with the amount in minor units.
+    #[inline]
+    pub fn new(minor_units: i128, currency: Currency) -> Self {
+        Self {
+            amount: minor_units,
+            currency,
+        }
+    }
+
+    /// Creates a zero amount of the currency.
+    #[inline]
+    pub fn zero(currency: Currency) -> Self {
+        Self::new(0, currency)
+    }
+
+    /// Creates a new instance with the amount in major units.
+    #[inline]
+    pub fn from_major(major_units: i64, currency: Currency) -> Self {
+        let amount = i128::from(major_units) * 10_i128.pow(currency.minor_unit());
+        Self::new(amount, currency)
+    }
+
+    /// Creates a new instance from a decimal amount in major units,
+    /// which is rounded to the minor unit with the policy.
+    pub fn from_decimal(
+        amount: Decimal,
+        currency: Currency,
+        policy: RoundingPolicy,
+    ) -> Result<Self, Error> {
+        let scale = currency.minor_unit();
+        let mut amount = amount.round_dp_with_strategy(scale, policy.strategy());
+        amount.rescale(scale);
+        if amount.scale() != scale {
+            bail!("the amount `{}` is out of range", amount);
+        }
+        Ok(Self::new(amount.mantissa(), currency))
+    }
+
+    /// Returns the amount in minor units.
+    #[inline]
+    pub fn minor_units(&self) -> i128 {
+        self.amount
+    }
+
+    /// Returns the currency.
+    #[inline]
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    /// Converts the amount in major units to a `Decimal`.
+    #[inline]
+    pub fn to_decimal(&self) -> Result<Decimal, Error> {
+        Decimal::try_from_i128_with_scale(self.amount, self.currency.minor_unit())
+            .map_err(Error::from)
+    }
+
+    /// Formats the amount in major units without the currency, such as `1234.56`.
+    pub fn amount_string(&self) -> String {
+        let scale = self.currency.minor_unit();
+        let (integer, fraction) = self.split_units();
+        let sign = if self.amount < 0 { "-" } else { "" };
+        if scale == 0 {
+            format!("{sign}{integer}")
+        } else {
+            format!("{sign}{integer}.{fraction:0width$}", width = scale as usize)
+        }
+    }
+
+    /// Formats the amount with the currency symbol and separators of the locale,
+    /// such as `$1,234.56` for `en-US` and `1.234,56 €` for `de-DE`.
+    pub fn format_locale(&self, locale: &str) -> String {
+        let language = locale
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let (group_separator, decimal_separator, symbol_first) = match language.as_str() {
+            "de" | "es" | "it" | "nl" | "pt" | "id" | "tr" | "da" | "el" => (".", ",", false),
+            "fr" | "ru" | "pl" | "cs" | "sv" | "nb" | "fi" | "uk" | "hu" => ("\u{a0}", ",", false),
+            _ => (",", ".", true),
+        };
+
+        let scale = self.currency.minor_unit();
+        let (integer, fraction) = self.split_units();
+        let digits = integer.to_string();
+        let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+        for (index, digit) in digits.chars().enumerate() {
+            if index > 0 && (digits.len() - index) % 3 == 0 {
+                grouped.push_str(group_separator);
+            }
+            grouped.push(digit);
+        }
+        if scale > 0 {
+            grouped.push_str(decimal_separator);
+            grouped.push_str(&format!("{fraction:0width$}", width = scale as usize));
+        }
+
+        let sign = if self.amount < 0 { "-" } else { "" };
+        match self.currency.symbol() {
+            Some(symbol) if symbol_first => format!("{sign}{symbol}{grouped}"),
+            Some(symbol) => format!("{sign}{grouped}\u{a0}{symbol}"),
+            None if symbol_first => format!("{sign}{}\u{a0}{grouped}", self.currency),
+            None => format!("{sign}{grouped}\u{a0}{}", self.currency),
+        }
+    }
+
+    /// Returns `true` if the amount is zero.
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        self.amount == 0
+    }
+
+    /// Returns `true` if the amount is positive.
+    #[inline]
+    pub fn is_positive(&self) -> bool {
+        self.amount > 0
+    }
+
+    /// Returns `true` if the amount is negative.
+    #[inline]
+    pub fn is_negative(&self) -> bool {
+        self.amount < 0
+    }
+
+    /// Returns the absolute amount.
+    #[inline]
+    pub fn abs(self) -> Self {
+        Self::new(self.amount.abs(), self.currency)
+    }
+
+    /// Adds two amounts of the same currency.
+    pub fn checked_add(self, other: Self) -> Result<Self, Error> {
+        self.check_currency(&other)?;
+        self.amount
+            .checked_add(other.amount)
+            .map(|amount| Self::new(amount, self.currency))
+            .ok_or_else(|| warn!("the amount overflows when adding `{}` to `{}`", other, self))
+    }
+
+    /// Subtracts two amounts of the same currency.
+    pub fn checked_sub(self, other: Self) -> Result<Self, Error> {
+        self.check_currency(&other)?;
+        self.amount
+            .checked_sub(other.amount)
+            .map(|amount| Self::new(amount, self.currency))
+            .ok_or_else(|| {
+                warn!(
+                    "the amount overflows when subtracting `{}` from `{}`",
+                    other, self
+                )
+            })
+    }
+
+    /// Multiplies the amount by a factor, such as a quantity or a tax rate,
+    /// and rounds the result to the minor unit with the policy.
+    pub fn checked_mul(self, factor: Decimal, policy: RoundingPolicy) -> Result<Self, Error> {
+        let amount = self
+            .to_decimal()?
+            .checked_mul(factor)
+            .ok_or_else(|| warn!("the amount overflows when multiplying `{}`", self))?;
+        Self::from_decimal(amount, self.currency, policy)
+    }
+
+    /// Divides the amount by a divisor and rounds the result to the minor unit with the policy.
+    /// Use [`allocate()`](Self::allocate) instead to split the amount without losing minor units.
+    pub fn checked_div(self, divisor: Decimal, policy: RoundingPolicy) -> Result<Self, Error> {
+        if divisor.is_zero() {
+            bail!("the amount `{}` can not be divided by zero", self);
+        }
+        let amount = self
+            .to_decimal()?
+            .checked_div(divisor)
+            .ok_or_else(|| warn!("the amount overflows when dividing `{}`", self))?;
+        Self::from_decimal(amount, self.currency, policy)
+    }
+
+    /// Allocates the amount by the ratios so that the sum of the parts equals the amount.
+    /// The remainder is distributed one minor unit at a time from the first part.
+    pub fn allocate(self, ratios: &[u32]) -> Result<Vec<Self>, Error> {
+        let total = ratios.iter().map(|&ratio| i128::from(ratio)).sum::<i128>();
+        if total == 0 {
+            bail!(
+                "the ratios for allocating `{}` should not be all zeros",
+                self
+            );
+        }
+
+        let mut parts = ratios
+            .iter()
+            .map(|&ratio| {
+                self.amount
+                    .checked_mul(i128::from(ratio))
+                    .map(|amount| amount / total)
+                    .ok_or_else(|| warn!("the amount overflows when allocating `{}`", self))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let remainder = self.amount - parts.iter().sum::<i128>();
+        let unit = remainder.signum();
+        for part in parts.iter_mut().take(remainder.unsigned_abs() as usize) {
+            *part += unit;
+        }
+        Ok(parts
+            .into_iter()
+            .map(|amount| Self::new(amount, self.currency))
+            .collect())
+    }
+
+    /// Checks whether the two amounts have the same currency.
+    fn check_currency(&self, other: &Self) -> Result<(), Error> {
+        if self.currency != other.currency {
+            bail!(
+                "currency mismatch between `{}` and `{}`",
+                self.currency,
+                other.currency
+            );
+        }
+        Ok(())
+    }
+
+    /// Splits the absolute amount into the integer and fractional parts.
+    fn split_units(&self) -> (u128, u128) {
+        let base = 10_u128.pow(self.currency.minor_unit());
+        let amount = self.amount.unsigned_abs();
+        (amount / base, amount % base)
+    }
+}
+
+impl fmt::Display for Money {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.amount_string(), self.currency)
+    }
+}
+
+impl FromStr for Money {
+    type Err = Error;
+
+    /// Parses a string such as `1234.56 USD`, `USD 1234.56` or `1234.56`
+    /// with the default currency. The amount should not have more decimal places
+    /// than the minor unit of the currency.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split_whitespace();
+        let (amount, currency) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(amount), None, None) => (amount, Currency::default()),
+            (Some(first), Some(second), None) => {
+                if first.chars().all(|c| c.is_ascii_alphabetic()) {
+                    (second, first.parse()?)
+                } else {
+                    (first, second.parse()?)
+                }
+            }
+            _ => bail!("invalid monetary amount `{}`", s),
+        };
+        let amount = amount
+            .parse::<Decimal>()
+            .map_err(|_| warn!("invalid monetary amount `{}`", s))?
+            .normalize();
+        if amount.scale() > currency.minor_unit() {
+            bail!(
+                "the amount `{}` has more decimal places than the currency `{}`",
+                amount,
+                currency
+            );
+        }
+        Self::from_decimal(amount, currency, RoundingPolicy::default())
+    }
+}
+
+impl PartialOrd for Money {
+    /// Compares two amounts of the same currency. It returns `None` for different currencies.
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (self.currency == other.currency).then(|| self.amount.cmp(&other.amount))
+    }
+}
+
+impl Neg for Money {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self::Output {
+        Self::new(-self.amount, self.currency)
+    }
+}
+
+impl From<Money> for JsonValue {
+    #[inline]
+    fn from(money: Money) -> Self {
+        JsonValue::String(money.to_string())
+    }
+}
+
+impl Serialize for Money {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error as _;
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum MoneyRepr {
+            Text(String),
+            Integer(i64),
+            Float(f64),
+            Object {
+                amount: Decimal,
+                currency: Option<Currency>,
+            },
+        }
+
+        match MoneyRepr::deserialize(deserializer)? {
+            MoneyRepr::Text(s) => s.parse().map_err(D::Error::custom),
+            MoneyRepr::Integer(value) => Ok(Self::from_major(value, Currency::default())),
+            MoneyRepr::Float(value) => value.to_string().parse().map_err(D::Error::custom),
+            MoneyRepr::Object { amount, currency } => {
+                let currency = currency.unwrap_or_default();
+                Self::from_decimal(amount, currency, RoundingPolicy::default())
+                    .map_err(D::Error::custom)
+            }
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use crate::{bail, error::Error};
+use rust_decimal::RoundingStrategy;
+use std::str::FromStr;
+
+/// Rounding policies for the monetary amounts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RoundingPolicy {
+    /// Rounds half to the nearest even number, which is also known as the banker's rounding.
+    #[default]
+    HalfEven,
+    /// Rounds half away from zero.
+    HalfUp,
+    /// Rounds half toward zero.
+    HalfDown,
+    /// Rounds toward zero.
+    Down,
+    /// Rounds away from zero.
+    Up,
+    /// Rounds toward negative infinity.
+    Floor,
+    /// Rounds toward positive infinity.
+    Ceiling,
+}
+
+impl RoundingPolicy {
+    /// Returns the corresponding rounding strategy for `Decimal`.
+    pub(super) fn strategy(self) -> RoundingStrategy {
+        match self {
+            Self::HalfEven => RoundingStrategy::MidpointNearestEven,
+            Self::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+            Self::HalfDown => RoundingStrategy::MidpointTowardZero,
+            Self::Down => RoundingStrategy::ToZero,
+            Self::Up => RoundingStrategy::AwayFromZero,
+            Self::Floor => RoundingStrategy::ToNegativeInfinity,
+            Self::Ceiling => RoundingStrategy::ToPositiveInfinity,
+        }
+    }
+}
+
+impl FromStr for RoundingPolicy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "half-even" => Ok(Self::HalfEven),
+            "half-up" => Ok(Self::HalfUp),
+            "half-down" => Ok(Self::HalfDown),
+            "down" => Ok(Self::Down),
+            "up" => Ok(Self::Up),
+            "floor" => Ok(Self::Floor),
+            "ceiling" => Ok(Self::Ceiling),
+            _ => bail!("the rounding policy `{}` is unsupported", s),
+        }
+    }
+}
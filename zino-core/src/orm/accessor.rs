@@ -185,6 +185,43 @@ where
         self.status().eq_ignore_ascii_case("Archived")
     }
 
+    /// Returns the allowed transitions of the `status` field.
+    /// An empty list means that there are no restrictions on the transitions.
+    #[inline]
+    fn status_transitions() -> &'static [(&'static str, &'static str)] {
+        &[]
+    }
+
+    /// Returns the allowlist of filterable fields and operators for the list query.
+    /// An empty list means that there are no restrictions on the filters.
+    #[inline]
+    fn filterable_fields() -> &'static [(&'static str, &'static [&'static str])] {
+        &[]
+    }
+
+    /// Returns `true` if the `status` can be transitioned to the specific value.
+    fn can_transition(&self, status: &str) -> bool {
+        let transitions = Self::status_transitions();
+        transitions.is_empty()
+            || self.has_status(status)
+            || transitions
+                .iter()
+                .any(|&(from, to)| self.has_status(from) && to.eq_ignore_ascii_case(status))
+    }
+
+    /// Checks whether the `status` can be transitioned to the specific value.
+    fn check_status_transition(&self, status: &str) -> Result<(), Error> {
+        if !self.can_transition(status) {
+            bail!(
+                "409 Conflict: invalid status transition from `{}` to `{}` for the model `{}`",
+                self.status(),
+                status,
+                self.id()
+            );
+        }
+        Ok(())
+    }
+
     /// Returns `true` if the `description` is nonempty.
     #[inline]
     fn has_description(&self) -> bool {
@@ -315,6 +352,15 @@ where
         mutation
     }
 
+    /// Constructs a `Mutation` for transitioning the status of the model.
+    fn transition_mutation(&self, status: &str) -> Mutation {
+        let mut mutation = Self::default_mutation();
+        let mut updates = self.next_version_updates();
+        updates.upsert("status", status);
+        mutation.append_updates(&mut updates);
+        mutation
+    }
+
     /// Constructs a default snapshot `Query` for the model.
     fn default_snapshot_query() -> Query {
         let mut query = Query::default();
@@ -371,9 +417,67 @@ where
         Ok(model)
     }
 
+    /// Parses the sparse fieldsets and checks them against the readable fields.
+    fn parse_sparse_fields(fields: &str) -> Result<Vec<&'static str>, Error> {
+        let write_only_fields = Self::write_only_fields();
+        let mut readable_fields = Vec::new();
+        for field in fields
+            .split(',')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+        {
+            let field = Self::fields()
+                .iter()
+                .find(|&&f| f == field && !write_only_fields.contains(&f))
+                .ok_or_else(|| warn!("the field `{}` is not readable", field))?;
+            readable_fields.push(*field);
+        }
+        Ok(readable_fields)
+    }
+
+    /// Parses the included relations into the reference columns.
+    /// A relation can be specified by the column name with or without the `_id` or `_ids` suffix.
+    fn parse_included_columns(includes: &str) -> Result<Vec<&'static str>, Error> {
+        let mut columns = Vec::new();
+        for include in includes
+            .split(',')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+        {
+            let column = Self::columns()
+                .iter()
+                .find(|col| {
+                    let name = col.name();
+                    col.reference().is_some()
+                        && (name == include
+                            || name.strip_suffix("_id") == Some(include)
+                            || name.strip_suffix("_ids") == Some(include))
+                })
+                .ok_or_else(|| warn!("the relation `{}` can not be included", include))?;
+            columns.push(column.name());
+        }
+        Ok(columns)
+    }
+
+    /// Embeds the referenced models in the corresponding `columns` for the data of models.
+    async fn include_references(
+        _query: &Query,
+        _models: &mut Vec<Map>,
+        _columns: &[&str],
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Embeds the referenced models in the corresponding `columns` for the data of a model.
+    async fn include_references_one(_model: &mut Map, _columns: &[&str]) -> Result<(), Error> {
+        Ok(())
+    }
+
     /// Deletes a model of the primary key by setting the status as `Deleted`.
     async fn soft_delete_by_id(id: &K) -> Result<(), Error> {
         let mut model = Self::try_get_model(id).await?;
+        model.check_status_transition("Deleted")?;
+
         let model_data = model.before_soft_delete().await?;
 
         let query = model.current_version_query();
@@ -386,6 +490,8 @@ where
     /// Locks a model of the primary key by setting the status as `Locked`.
     async fn lock_by_id(id: &K) -> Result<(), Error> {
         let mut model = Self::try_get_model(id).await?;
+        model.check_status_transition("Locked")?;
+
         let model_data = model.before_lock().await?;
 
         let query = model.current_version_query();
@@ -398,6 +504,8 @@ where
     /// Archives a model of the primary key by setting the status as `Archived`.
     async fn archive_by_id(id: &K) -> Result<(), Error> {
         let mut model = Self::try_get_model(id).await?;
+        model.check_status_transition("Archived")?;
+
         let model_data = model.before_archive().await?;
 
         let query = model.current_version_query();
@@ -407,6 +515,26 @@ where
         Ok(())
     }
 
+    /// Transitions the status of the model and reloads it from the table.
+    async fn transition(&mut self, status: &str) -> Result<(), Error> {
+        self.check_status_transition(status)?;
+        self.before_transition(status).await?;
+
+        let query = self.current_version_query();
+        let mut mutation = self.transition_mutation(status);
+        let model_data = self.before_update().await?;
+        let ctx = Self::update_one(&query, &mut mutation).await?;
+        Self::after_update(&ctx, model_data).await?;
+        *self = Self::try_get_model(self.id()).await?;
+        Ok(())
+    }
+
+    /// Transitions the status of a model of the primary key.
+    async fn transition_by_id(id: &K, status: &str) -> Result<(), Error> {
+        let mut model = Self::try_get_model(id).await?;
+        model.transition(status).await
+    }
+
     /// Updates a model of the primary key using the json object.
     async fn update_by_id(
         id: &K,
@@ -421,10 +549,18 @@ where
             .is_some_and(|version| model.version() != version)
         {
             bail!(
-                "409 Conflict: there is a version conflict for the model `{}`",
+                "412 Precondition Failed: the model `{}` has been modified",
                 id
             );
         }
+        if let Some(status) = data
+            .get_str("status")
+            .filter(|&status| !model.has_status(status))
+        {
+            model.check_status_transition(status)?;
+            model.before_transition(status).await?;
+        }
+        Self::sanitize(data);
         Self::before_validation(data, extension.as_ref()).await?;
 
         let validation = model.read_map(data);
@@ -448,15 +584,96 @@ where
         }
         model.after_validation(data).await?;
 
+        let tree_parent_changed =
+            Self::TREE_PARENT_FIELD.is_some_and(|field| data.contains_key(field));
+        if let Some(parent_field) = Self::TREE_PARENT_FIELD.filter(|_| tree_parent_changed) {
+            super::tree::check_tree_parent::<Self>(&id.to_string(), data.get(parent_field)).await?;
+        }
+
         let query = model.current_version_query();
         let mut mutation = model.next_version_mutation(data);
 
         let model_data = model.before_update().await?;
         let ctx = Self::update_one(&query, &mut mutation).await?;
+        if ctx.rows_affected() == Some(0) {
+            bail!(
+                "412 Precondition Failed: the model `{}` has been modified",
+                id
+            );
+        }
+        if tree_parent_changed {
+            super::tree::rebuild_tree_path::<Self>(&id.to_string()).await?;
+        }
         Self::after_update(&ctx, model_data).await?;
         Ok((validation, model))
     }
 
+    /// Patches a model of the primary key using a JSON Merge Patch object
+    /// or a list of JSON Patch operations. The fields removed by the patch,
+    /// such as the `null` members of a JSON Merge Patch, are reset to the default values.
+    async fn patch_by_id(
+        id: &K,
+        patch: &JsonValue,
+        extension: Option<<Self as ModelHooks>::Extension>,
+    ) -> Result<(Validation, Self), Error> {
+        let mut model_data = Self::find_by_id::<Map>(id)
+            .await?
+            .ok_or_else(|| warn!("404 Not Found: cannot find the model `{}`", id))?;
+        let mut patched_data = model_data.clone();
+        match patch {
+            JsonValue::Object(patch) => patched_data.merge_patch(patch),
+            JsonValue::Array(operations) => {
+                if let Err(err) = patched_data.apply_json_patch(operations) {
+                    let validation = Validation::from_entry("patch", err);
+                    return Ok((validation, Self::default()));
+                }
+            }
+            _ => {
+                let validation = Validation::from_entry(
+                    "patch",
+                    warn!("the patch should be an object or an array"),
+                );
+                return Ok((validation, Self::default()));
+            }
+        }
+
+        let version = patched_data.get("version").cloned();
+        let mut data = Map::new();
+        for (key, value) in patched_data {
+            if model_data.remove(&key).as_ref() != Some(&value) {
+                data.upsert(key, value);
+            }
+        }
+        if !model_data.is_empty() {
+            let mut default_data = Self::new().into_map();
+            for (key, _value) in model_data {
+                let value = default_data.remove(&key).unwrap_or_default();
+                data.upsert(key, value);
+            }
+        }
+        if let Some(version) = version.filter(|v| v.is_u64()) {
+            // Conditions the update on the version which the patch has been applied to.
+            data.entry("version").or_insert(version);
+        }
+        Self::update_by_id(id, &mut data, extension).await
+    }
+
+    /// Deletes the model of the current version. It returns `412 Precondition Failed`
+    /// if the model has been modified concurrently.
+    async fn delete_current_version(mut self) -> Result<(), Error> {
+        let model_data = self.before_delete().await?;
+        let query = self.current_version_query();
+        let ctx = Self::delete_one(&query).await?;
+        if ctx.rows_affected() == Some(0) {
+            bail!(
+                "412 Precondition Failed: the model `{}` has been modified",
+                self.id()
+            );
+        }
+        self.after_delete(&ctx, model_data).await?;
+        Ok(())
+    }
+
     /// Generates random associations for the model.
     async fn random_associations() -> Result<Map, Error> {
         let mut associations = Map::new();
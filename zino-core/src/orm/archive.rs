@@ -0,0 +1,322 @@
+//! Archival export of the rows to the storage backend.
+
+use crate::{datetime::DateTime, error::Error, extension::JsonObjectExt, Map};
+use std::{fs, path::PathBuf, str::FromStr};
+
+#[cfg(feature = "accessor")]
+use crate::accessor::GlobalAccessor;
+
+#[cfg(feature = "accessor")]
+use opendal::Operator;
+
+/// File formats of the archives.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ArchiveFormat {
+    /// Newline delimited JSON.
+    #[default]
+    Ndjson,
+    /// Apache Parquet.
+    #[cfg(feature = "connector-arrow")]
+    Parquet,
+}
+
+impl ArchiveFormat {
+    /// Returns the file extension.
+    #[inline]
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Ndjson => "ndjson",
+            #[cfg(feature = "connector-arrow")]
+            Self::Parquet => "parquet",
+        }
+    }
+}
+
+impl FromStr for ArchiveFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ndjson" => Ok(Self::Ndjson),
+            #[cfg(feature = "connector-arrow")]
+            "parquet" => Ok(Self::Parquet),
+            _ => Err(format!("unsupported archive format `{s}`")),
+        }
+    }
+}
+
+/// Backends of the archive sink.
+#[derive(Debug, Clone)]
+enum ArchiveBackend {
+    /// Local directory.
+    Local(PathBuf),
+    /// Storage accessor.
+    #[cfg(feature = "accessor")]
+    Accessor(&'static Operator),
+}
+
+/// A sink for writing the archives to the local directory or the storage backend.
+///
+/// Each archive consists of the data files in chunks and a `manifest.json` file,
+/// which are written into the directory `{table_name}/{timestamp}` under the sink root.
+#[derive(Debug, Clone)]
+pub struct ArchiveSink {
+    /// Backend.
+    backend: ArchiveBackend,
+    /// Root path.
+    root: String,
+    /// File format.
+    format: ArchiveFormat,
+    /// Number of rows in a chunk.
+    chunk_size: usize,
+}
+
+impl ArchiveSink {
+    /// Creates a new instance for the local directory.
+    #[inline]
+    pub fn local(dir: impl Into<PathBuf>) -> Self {
+        Self::new(ArchiveBackend::Local(dir.into()))
+    }
+
+    /// Creates a new instance for the storage accessor with the name.
+    #[cfg(feature = "accessor")]
+    #[inline]
+    pub fn with_accessor(name: &str) -> Option<Self> {
+        GlobalAccessor::get(name).map(|operator| Self::new(ArchiveBackend::Accessor(operator)))
+    }
+
+    /// Sets the root path.
+    #[inline]
+    pub fn root(mut self, root: impl Into<String>) -> Self {
+        self.root = root.into();
+        self
+    }
+
+    /// Sets the file format.
+    #[inline]
+    pub fn format(mut self, format: ArchiveFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Sets the number of rows in a chunk.
+    #[inline]
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Returns the file format.
+    #[inline]
+    pub fn file_format(&self) -> ArchiveFormat {
+        self.format
+    }
+
+    /// Returns the number of rows in a chunk.
+    #[inline]
+    pub fn rows_per_chunk(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Creates a new instance with the backend.
+    fn new(backend: ArchiveBackend) -> Self {
+        Self {
+            backend,
+            root: String::new(),
+            format: ArchiveFormat::default(),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    /// Returns the path of an archive for the table.
+    pub(super) fn archive_path(&self, table_name: &str) -> String {
+        let timestamp = DateTime::now().format("%Y%m%d%H%M%S%3f");
+        let root = self.root.trim_end_matches('/');
+        if root.is_empty() {
+            format!("{table_name}/{timestamp}")
+        } else {
+            format!("{root}/{table_name}/{timestamp}")
+        }
+    }
+
+    /// Writes the bytes to the file.
+    pub(super) async fn write(&self, path: &str, bytes: Vec<u8>) -> Result<(), Error> {
+        match &self.backend {
+            ArchiveBackend::Local(dir) => {
+                let path = dir.join(path);
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(path, bytes)?;
+            }
+            #[cfg(feature = "accessor")]
+            ArchiveBackend::Accessor(operator) => {
+                operator.write(path, bytes).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Encodes the rows in the file format.
+    #[cfg_attr(not(feature = "connector-arrow"), allow(unused_variables))]
+    pub(super) fn encode(
+        &self,
+        columns: &[crate::model::Column<'static>],
+        rows: Vec<Map>,
+    ) -> Result<Vec<u8>, Error> {
+        match self.format {
+            ArchiveFormat::Ndjson => {
+                let mut buffer = Vec::new();
+                for row in rows {
+                    serde_json::to_writer(&mut buffer, &row)?;
+                    buffer.push(b'\n');
+                }
+                Ok(buffer)
+            }
+            #[cfg(feature = "connector-arrow")]
+            ArchiveFormat::Parquet => encode_parquet(columns, rows),
+        }
+    }
+}
+
+/// A manifest of the archive.
+#[derive(Debug, Clone, Default)]
+pub struct ArchiveManifest {
+    /// Path of the archive.
+    path: String,
+    /// Manifest data.
+    data: Map,
+    /// Data files in the form `(path, num_rows)`.
+    files: Vec<(String, usize)>,
+}
+
+impl ArchiveManifest {
+    /// Creates a new instance.
+    pub(super) fn new(path: String, model_name: &str, table_name: &str, format: &str) -> Self {
+        let mut data = Map::new();
+        data.upsert("model_name", model_name);
+        data.upsert("table_name", table_name);
+        data.upsert("format", format);
+        data.upsert("created_at", DateTime::now());
+        Self {
+            path,
+            data,
+            files: Vec::new(),
+        }
+    }
+
+    /// Sets the schema of the rows.
+    #[inline]
+    pub(super) fn set_schema(&mut self, schema: crate::JsonValue) {
+        self.data.upsert("schema", schema);
+    }
+
+    /// Adds a data file.
+    #[inline]
+    pub(super) fn add_file(&mut self, path: String, num_rows: usize) {
+        self.files.push((path, num_rows));
+    }
+
+    /// Returns the path of the archive.
+    #[inline]
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the total number of rows.
+    #[inline]
+    pub fn num_rows(&self) -> usize {
+        self.files.iter().map(|(_, num_rows)| num_rows).sum()
+    }
+
+    /// Returns the data files in the form `(path, num_rows)`.
+    #[inline]
+    pub fn files(&self) -> &[(String, usize)] {
+        &self.files
+    }
+
+    /// Converts `self` to a json object.
+    pub fn to_map(&self) -> Map {
+        let files = self
+            .files
+            .iter()
+            .map(|(path, num_rows)| {
+                let mut file = Map::new();
+                file.upsert("path", path.as_str());
+                file.upsert("num_rows", *num_rows);
+                file
+            })
+            .collect::<Vec<_>>();
+        let mut map = self.data.clone();
+        map.upsert("num_rows", self.num_rows());
+        map.upsert("files", files);
+        map
+    }
+}
+
+/// Encodes the rows as a Parquet file.
+#[cfg(feature = "connector-arrow")]
+fn encode_parquet(
+    columns: &[crate::model::Column<'static>],
+    rows: Vec<Map>,
+) -> Result<Vec<u8>, Error> {
+    use crate::extension::JsonValueExt;
+    use datafusion::{
+        arrow::{
+            datatypes::{DataType, Field, Schema},
+            json::ReaderBuilder,
+        },
+        parquet::arrow::ArrowWriter,
+    };
+    use std::sync::Arc;
+
+    let fields = columns
+        .iter()
+        .map(|col| {
+            let type_name = col
+                .type_name()
+                .strip_prefix("Option<")
+                .and_then(|s| s.strip_suffix('>'))
+                .unwrap_or(col.type_name());
+            let data_type = match type_name {
+                "bool" => DataType::Boolean,
+                "i8" | "i16" | "i32" | "i64" | "isize" => DataType::Int64,
+                "u8" | "u16" | "u32" | "u64" | "usize" => DataType::UInt64,
+                "f32" | "f64" => DataType::Float64,
+                _ => DataType::Utf8,
+            };
+            Field::new(col.name(), data_type, true)
+        })
+        .collect::<Vec<_>>();
+    let rows = rows
+        .into_iter()
+        .map(|mut row| {
+            for field in fields.iter() {
+                if field.data_type() == &DataType::Utf8 {
+                    if let Some(value) = row.get_mut(field.name()) {
+                        if !(value.is_string() || value.is_null()) {
+                            *value = value.to_string_unquoted().into();
+                        }
+                    }
+                }
+            }
+            row
+        })
+        .collect::<Vec<_>>();
+
+    let schema = Arc::new(Schema::new(fields));
+    let mut decoder = ReaderBuilder::new(schema.clone()).build_decoder()?;
+    decoder.serialize(&rows)?;
+
+    let mut buffer = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut buffer, schema, None)?;
+    if let Some(batch) = decoder.flush()? {
+        writer.write(&batch)?;
+    }
+    writer.close()?;
+    Ok(buffer)
+}
+
+/// Default number of rows in a chunk.
+const DEFAULT_CHUNK_SIZE: usize = 10000;
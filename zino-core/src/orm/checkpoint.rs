@@ -0,0 +1,219 @@
+//! WAL checkpoints for the SQLite databases.
+
+use super::{ConnectionPool, SHARED_CONNECTION_POOLS};
+use crate::{
+    datetime::DateTime,
+    error::Error,
+    extension::{JsonObjectExt, TomlTableExt},
+    schedule::AsyncJob,
+    state::State,
+    BoxFuture, LazyLock, Map, Uuid,
+};
+use parking_lot::RwLock;
+use toml::Table;
+
+/// Creates a scheduled job for checkpointing the WAL files of the SQLite databases.
+/// The job is configured by the `[checkpoint]` table:
+///
+/// ```toml
+/// [checkpoint]
+/// cron = "0 */5 * * * *"
+/// mode = "passive"
+/// ```
+///
+/// The `passive` mode never blocks the readers or writers, which plays well with
+/// the backup tools like Litestream that replicate the WAL files continuously.
+/// In this case, the `wal-autocheckpoint` of the database service can be set to `0`
+/// so that the checkpoints are only performed by the job.
+pub fn new_checkpoint_job() -> AsyncJob {
+    let config = &SHARED_CHECKPOINT_CONFIG;
+    let mut job = AsyncJob::new(&config.cron, checkpoint_all).name("sqlite_checkpoint");
+    job.data_mut().upsert("mode", config.mode.as_str());
+    job
+}
+
+/// Adds the hooks running around the checkpoints, which can be used to
+/// coordinate with the backup tools.
+#[inline]
+pub fn add_checkpoint_hook(hook: impl CheckpointHook) {
+    CHECKPOINT_HOOKS.write().push(Box::leak(Box::new(hook)));
+}
+
+/// Checkpoints the WAL file of the database service with the mode.
+/// It will be skipped if any of the `before_checkpoint` hooks returns an error.
+pub async fn checkpoint(
+    cp: &'static ConnectionPool,
+    mode: CheckpointMode,
+) -> Result<CheckpointStats, Error> {
+    let hooks = CHECKPOINT_HOOKS.read().clone();
+    for hook in hooks.iter() {
+        hook.before_checkpoint(cp).await?;
+    }
+
+    let sql = format!("PRAGMA wal_checkpoint({});", mode.as_str());
+    let (busy, log_frames, checkpointed_frames) = sqlx::query_as::<_, (i64, i64, i64)>(&sql)
+        .fetch_one(cp.pool())
+        .await?;
+    let stats = CheckpointStats {
+        busy: busy != 0,
+        log_frames,
+        checkpointed_frames,
+    };
+    for hook in hooks.iter() {
+        hook.after_checkpoint(cp, &stats).await?;
+    }
+    Ok(stats)
+}
+
+/// Hooks running around the WAL checkpoints.
+pub trait CheckpointHook: Send + Sync + 'static {
+    /// A hook running before the checkpoint.
+    /// The checkpoint will be skipped if an error is returned.
+    #[inline]
+    fn before_checkpoint(&self, _cp: &'static ConnectionPool) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// A hook running after the checkpoint.
+    #[inline]
+    fn after_checkpoint<'a>(
+        &'a self,
+        _cp: &'static ConnectionPool,
+        _stats: &'a CheckpointStats,
+    ) -> BoxFuture<'a, Result<(), Error>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Mode of the WAL checkpoint.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CheckpointMode {
+    /// Checkpoints as many frames as possible without waiting for the readers or writers.
+    #[default]
+    Passive,
+    /// Blocks the writers until all frames are checkpointed.
+    Full,
+    /// Works like `Full` and waits for the readers to restart the WAL file.
+    Restart,
+    /// Works like `Restart` and truncates the WAL file to zero bytes.
+    Truncate,
+}
+
+impl CheckpointMode {
+    /// Parses the mode.
+    #[inline]
+    pub fn parse(mode: &str) -> Option<Self> {
+        match mode.to_ascii_lowercase().as_str() {
+            "passive" => Some(Self::Passive),
+            "full" => Some(Self::Full),
+            "restart" => Some(Self::Restart),
+            "truncate" => Some(Self::Truncate),
+            _ => None,
+        }
+    }
+
+    /// Returns the mode as a str.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Passive => "PASSIVE",
+            Self::Full => "FULL",
+            Self::Restart => "RESTART",
+            Self::Truncate => "TRUNCATE",
+        }
+    }
+}
+
+/// Statistics of a WAL checkpoint.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CheckpointStats {
+    /// Indicates the checkpoint is blocked from completion or not.
+    busy: bool,
+    /// Number of frames in the WAL file.
+    log_frames: i64,
+    /// Number of frames checkpointed into the database file.
+    checkpointed_frames: i64,
+}
+
+impl CheckpointStats {
+    /// Returns `true` if the checkpoint was blocked from completion.
+    #[inline]
+    pub fn is_busy(&self) -> bool {
+        self.busy
+    }
+
+    /// Returns the number of frames in the WAL file.
+    #[inline]
+    pub fn log_frames(&self) -> i64 {
+        self.log_frames
+    }
+
+    /// Returns the number of frames checkpointed into the database file.
+    #[inline]
+    pub fn checkpointed_frames(&self) -> i64 {
+        self.checkpointed_frames
+    }
+}
+
+/// Checkpoints the WAL files of all the database services.
+fn checkpoint_all<'a>(_job_id: Uuid, data: &'a mut Map, _last_tick: DateTime) -> BoxFuture<'a> {
+    Box::pin(async move {
+        let mode = data
+            .get_str("mode")
+            .and_then(CheckpointMode::parse)
+            .unwrap_or_default();
+        let mut outputs = Vec::new();
+        for cp in SHARED_CONNECTION_POOLS.0.iter() {
+            let name = cp.name();
+            let mut output = Map::from_entry("name", name);
+            match checkpoint(cp, mode).await {
+                Ok(stats) => {
+                    output.upsert("busy", stats.is_busy());
+                    output.upsert("log_frames", stats.log_frames());
+                    output.upsert("checkpointed_frames", stats.checkpointed_frames());
+                }
+                Err(err) => {
+                    tracing::error!(
+                        "fail to checkpoint the database for the `{name}` service: {err}"
+                    );
+                    output.upsert("error", err.to_string());
+                }
+            }
+            outputs.push(output);
+        }
+        data.upsert("$output", outputs);
+    })
+}
+
+/// Checkpoint config.
+struct CheckpointConfig {
+    /// Cron expression of the checkpoint job.
+    cron: String,
+    /// Checkpoint mode.
+    mode: CheckpointMode,
+}
+
+impl CheckpointConfig {
+    /// Creates a new instance with the configuration.
+    fn with_config(config: &Table) -> Self {
+        Self {
+            cron: config.get_str("cron").unwrap_or("0 */5 * * * *").to_owned(),
+            mode: config
+                .get_str("mode")
+                .and_then(CheckpointMode::parse)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Shared checkpoint config.
+static SHARED_CHECKPOINT_CONFIG: LazyLock<CheckpointConfig> = LazyLock::new(|| {
+    let config = State::shared()
+        .get_config("checkpoint")
+        .cloned()
+        .unwrap_or_default();
+    CheckpointConfig::with_config(&config)
+});
+
+/// Checkpoint hooks.
+static CHECKPOINT_HOOKS: RwLock<Vec<&'static dyn CheckpointHook>> = RwLock::new(Vec::new());
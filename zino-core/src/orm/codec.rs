@@ -0,0 +1,110 @@
+use super::Schema;
+use crate::{
+    error::Error,
+    model::{AvroCodec, ProtobufCodec, SchemaFormat, SchemaRegistry},
+    warn, JsonValue,
+};
+
+/// Binary encoding of the models with the schemas derived from the column metadata,
+/// which can be used for the event payloads over message brokers such as Kafka.
+///
+/// The Confluent wire format is supported with the shared [`SchemaRegistry`].
+/// The subject is named as `{MODEL_NAME}-value` by default.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino_core::{model::SchemaFormat, orm::ModelCodec};
+///
+/// let payload = user.encode_confluent(SchemaFormat::Avro).await?;
+/// let user = User::decode_confluent(&payload, SchemaFormat::Avro).await?;
+/// ```
+pub trait ModelCodec: Schema {
+    /// Returns the subject name in the schema registry.
+    #[inline]
+    fn subject_name() -> String {
+        format!("{}-value", Self::MODEL_NAME)
+    }
+
+    /// Returns the Protobuf codec.
+    #[inline]
+    fn protobuf_codec() -> ProtobufCodec<'static> {
+        ProtobufCodec::new(Self::MODEL_NAME, Self::columns())
+    }
+
+    /// Returns the schema definition in the format.
+    fn schema_definition(format: SchemaFormat) -> Result<String, Error> {
+        match format {
+            SchemaFormat::Protobuf => Self::protobuf_codec().proto_definition(),
+            _ => Ok(Self::schema().canonical_form()),
+        }
+    }
+
+    /// Encodes the model as an Avro datum.
+    #[inline]
+    fn encode_avro(&self) -> Result<Vec<u8>, Error> {
+        AvroCodec::new(Self::schema()).encode(self)
+    }
+
+    /// Decodes the model from an Avro datum.
+    #[inline]
+    fn decode_avro(bytes: &[u8]) -> Result<Self, Error> {
+        AvroCodec::new(Self::schema()).decode(bytes)
+    }
+
+    /// Encodes the model as a Protobuf message.
+    fn encode_protobuf(&self) -> Result<Vec<u8>, Error> {
+        let JsonValue::Object(data) = serde_json::to_value(self)? else {
+            return Err(warn!(
+                "the `{}` model cann't be converted to a json object",
+                Self::MODEL_NAME
+            ));
+        };
+        Self::protobuf_codec().encode(&data)
+    }
+
+    /// Decodes the model from a Protobuf message.
+    fn decode_protobuf(bytes: &[u8]) -> Result<Self, Error> {
+        let data = Self::protobuf_codec().decode(bytes)?;
+        Ok(Self::try_from_map(data)?)
+    }
+
+    /// Encodes the model in the Confluent wire format.
+    /// The schema is registered in the shared schema registry if necessary.
+    async fn encode_confluent(&self, format: SchemaFormat) -> Result<Vec<u8>, Error> {
+        let registry = shared_registry()?;
+        let schema = Self::schema_definition(format)?;
+        let schema_id = registry
+            .register(&Self::subject_name(), format, &schema)
+            .await?;
+        let payload = match format {
+            SchemaFormat::Protobuf => self.encode_protobuf()?,
+            _ => self.encode_avro()?,
+        };
+        Ok(format.encode_wire_format(schema_id, &payload))
+    }
+
+    /// Decodes the model from the data in the Confluent wire format.
+    /// For Avro, the writer schema is fetched from the shared schema registry
+    /// and resolved to the model schema.
+    async fn decode_confluent(bytes: &[u8], format: SchemaFormat) -> Result<Self, Error> {
+        let (schema_id, payload) = format.decode_wire_format(bytes)?;
+        match format {
+            SchemaFormat::Protobuf => Self::decode_protobuf(payload),
+            _ => {
+                let registry = shared_registry()?;
+                let writer_schema = registry.get_schema(schema_id).await?;
+                let writer_schema = apache_avro::Schema::parse_str(&writer_schema)?;
+                AvroCodec::new(Self::schema()).decode_with_writer_schema(payload, &writer_schema)
+            }
+        }
+    }
+}
+
+impl<M: Schema> ModelCodec for M {}
+
+/// Returns the shared schema registry.
+fn shared_registry() -> Result<&'static SchemaRegistry, Error> {
+    SchemaRegistry::shared()
+        .ok_or_else(|| warn!("the `[schema-registry]` table should be configured"))
+}
@@ -1,8 +1,12 @@
+use super::query::QueryExt;
 use crate::{
-    extension::JsonObjectExt,
-    model::{Column, EncodeColumn},
+    extension::{JsonObjectExt, JsonValueExt},
+    model::{Column, EncodeColumn, Query},
+    money::{Currency, Money},
+    JsonValue,
 };
 use convert_case::{Case, Casing};
+use std::borrow::Cow;
 
 /// Extension trait for [`Column`].
 pub(super) trait ColumnExt {
@@ -12,11 +16,33 @@ pub(super) trait ColumnExt {
     /// Returns the type annotation.
     fn type_annotation(&self) -> &'static str;
 
+    /// Returns the column name. An explicit `column_name` is used as it is,
+    /// otherwise the naming conventions are applied to the field name.
+    fn column_name(&self) -> Cow<'_, str>;
+
     /// Returns the field definition.
     fn field_definition(&self, primary_key_name: &str) -> String;
 
-    /// Returns the constraints.
-    fn constraints(&self) -> Vec<String>;
+    /// Returns the named constraints for the table.
+    fn constraints(&self, table_name: &str) -> Vec<(String, String)>;
+
+    /// Returns `true` if the column should be written by the `INSERT` statement.
+    fn is_insertable(&self) -> bool;
+
+    /// Encodes the value for the `INSERT` statement.
+    /// The default expression is used if the value is absent or ignorable.
+    fn encode_insert_value<'b>(&self, value: Option<&'b JsonValue>) -> Cow<'b, str>;
+
+    /// Returns the name of the unique index for the table.
+    fn unique_index_name(&self, table_name: &str) -> String;
+
+    /// Returns the SQL to create the unique index if the column is unique.
+    fn unique_index_definition(&self, table_name: &str, table_name_escaped: &str)
+        -> Option<String>;
+
+    /// Returns the amount in major units if the monetary value is in the currency of the column.
+    /// A bare amount is interpreted in that currency.
+    fn encode_money(&self, value: &str) -> Option<String>;
 }
 
 impl<'a> ColumnExt for Column<'a> {
@@ -61,17 +87,36 @@ impl<'a> ColumnExt for Column<'a> {
         }
     }
 
+    fn column_name(&self) -> Cow<'_, str> {
+        if let Some(column_name) = self.extra().get_str("column_name") {
+            Cow::Borrowed(column_name)
+        } else {
+            super::NamingConvention::shared().column_name(self.name())
+        }
+    }
+
     fn field_definition(&self, primary_key_name: &str) -> String {
-        let column_name = self
-            .extra()
-            .get_str("column_name")
-            .unwrap_or_else(|| self.name());
+        let column_name = self.column_name();
         let column_type = self.column_type();
-        let mut definition = format!("{column_name} {column_type}");
-        if column_name == primary_key_name {
+        let column_field = Query::quote_identifier(&column_name);
+        let mut definition = format!("{column_field} {column_type}");
+        if self.name() == primary_key_name {
             definition += " PRIMARY KEY";
         }
-        if let Some(value) = self.default_value() {
+        if let Some(expr) = self.generated_expression() {
+            // PostgreSQL only supports stored generated columns.
+            let storage = if self.is_stored() || cfg!(feature = "orm-postgres") {
+                "STORED"
+            } else {
+                "VIRTUAL"
+            };
+            definition = format!("{definition} GENERATED ALWAYS AS ({expr}) {storage}");
+            if self.is_not_null() {
+                definition += " NOT NULL";
+            }
+        } else if let Some(expr) = self.default_expression() {
+            definition = format!("{definition} DEFAULT ({expr})");
+        } else if let Some(value) = self.default_value() {
             if self.auto_increment() {
                 definition += if cfg!(any(
                     feature = "orm-mariadb",
@@ -104,21 +149,19 @@ impl<'a> ColumnExt for Column<'a> {
         definition
     }
 
-    fn constraints(&self) -> Vec<String> {
+    fn constraints(&self, table_name: &str) -> Vec<(String, String)> {
         let mut constraints = Vec::new();
         let extra = self.extra();
-        let column_name = self
-            .extra()
-            .get_str("column_name")
-            .unwrap_or_else(|| self.name());
+        let column_name = self.column_name();
         if let Some(reference) = self
             .reference()
             .filter(|_| extra.contains_key("foreign_key"))
         {
-            let parent_table_name = reference.name();
-            let parent_column_name = reference.column_name();
+            let column_field = Query::quote_identifier(&column_name);
+            let parent_table_name = Query::quote_identifier(reference.name());
+            let parent_column_field = Query::format_field(reference.column_name());
             let mut constraint = format!(
-                "FOREIGN KEY ({column_name}) REFERENCES {parent_table_name}({parent_column_name})"
+                "FOREIGN KEY ({column_field}) REFERENCES {parent_table_name}({parent_column_field})"
             );
             if let Some(action) = extra.get_str("on_delete") {
                 constraint.push_str(" ON DELETE ");
@@ -128,8 +171,132 @@ impl<'a> ColumnExt for Column<'a> {
                 constraint.push_str(" ON UPDATE ");
                 constraint.push_str(&action.to_case(Case::Upper));
             }
-            constraints.push(constraint);
+
+            // The naming convention is consistent with the default names in PostgreSQL.
+            let constraint_name = format!("{table_name}_{column_name}_fkey");
+            constraints.push((constraint_name, constraint));
+        }
+        if let Some(expr) = extra.get_str("check") {
+            let constraint_name = format!("{table_name}_{column_name}_check");
+            constraints.push((constraint_name, format!("CHECK ({expr})")));
         }
         constraints
     }
+
+    #[inline]
+    fn is_insertable(&self) -> bool {
+        !(self.auto_increment() || self.is_generated())
+    }
+
+    fn encode_insert_value<'b>(&self, value: Option<&'b JsonValue>) -> Cow<'b, str> {
+        if let Some(expr) = self.default_expression() {
+            if value.map_or(true, |v| v.is_ignorable()) {
+                return format!("({expr})").into();
+            }
+        }
+        self.encode_value(value)
+    }
+
+    fn unique_index_name(&self, table_name: &str) -> String {
+        let column_name = self.column_name();
+        if self.index_type() == Some("unique") {
+            format!("{table_name}_{column_name}_index")
+        } else {
+            format!("{table_name}_{column_name}_unique_index")
+        }
+    }
+
+    fn unique_index_definition(
+        &self,
+        table_name: &str,
+        table_name_escaped: &str,
+    ) -> Option<String> {
+        if !self.is_unique() {
+            return None;
+        }
+
+        let column_name = self.column_name();
+        let column_field = Query::quote_identifier(&column_name);
+        let index_name = self.unique_index_name(table_name);
+        let is_mysql = cfg!(any(
+            feature = "orm-mariadb",
+            feature = "orm-mysql",
+            feature = "orm-tidb"
+        ));
+        let mut sql = if is_mysql {
+            format!("CREATE UNIQUE INDEX {index_name} ON {table_name_escaped} ({column_field})")
+        } else {
+            format!(
+                "CREATE UNIQUE INDEX IF NOT EXISTS {index_name} \
+                    ON {table_name_escaped} ({column_field})"
+            )
+        };
+        if self.type_name() == "String" && !is_mysql {
+            // Empty strings stand for the absence of values, which should not conflict.
+            sql.push_str(&format!(" WHERE {column_field} <> ''"));
+        }
+        sql.push(';');
+        Some(sql)
+    }
+
+    fn encode_money(&self, value: &str) -> Option<String> {
+        let currency = match self.extra().get_str("currency") {
+            Some(code) => code.parse::<Currency>().ok()?,
+            None => Currency::default(),
+        };
+        let result = if value.split_whitespace().count() == 1 {
+            format!("{value} {currency}").parse::<Money>()
+        } else {
+            value.parse::<Money>()
+        };
+        match result {
+            Ok(money) if money.currency() == currency => Some(money.amount_string()),
+            Ok(money) => {
+                // Only the amount is stored, so a different currency can not be restored.
+                tracing::warn!(
+                    "the currency `{}` does not match the currency `{}` of the column `{}`",
+                    money.currency(),
+                    currency,
+                    self.name()
+                );
+                None
+            }
+            Err(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ColumnExt;
+    use crate::{
+        model::Column,
+        money::{Money, RoundingPolicy},
+    };
+
+    #[test]
+    fn it_encodes_money() {
+        let mut column = Column::new("price", "Money", true);
+        column.set_extra_attribute("currency", "EUR");
+        for value in ["1234.56 EUR", "EUR -0.05", "7.5"] {
+            let amount = column.encode_money(value).unwrap();
+            let money = Money::from_decimal(
+                amount.parse().unwrap(),
+                "EUR".parse().unwrap(),
+                RoundingPolicy::default(),
+            )
+            .unwrap();
+            let expected = if value.contains("EUR") {
+                value.parse::<Money>().unwrap()
+            } else {
+                format!("{value} EUR").parse::<Money>().unwrap()
+            };
+            assert_eq!(money, expected);
+        }
+        assert_eq!(column.encode_money("1234.56 USD"), None);
+
+        column.set_extra_attribute("currency", "JPY");
+        assert_eq!(column.encode_money("JPY 1500").as_deref(), Some("1500"));
+        assert_eq!(column.encode_money("1500.5"), None);
+    }
 }
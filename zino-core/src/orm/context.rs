@@ -0,0 +1,241 @@
+use super::{ConnectionPool, GlobalPool};
+use crate::{auth::UserSession, error::Error, warn};
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+/// A database context for selecting the connection pool used by the `Schema` calls
+/// and enabling the dry-run mode.
+///
+/// The selected pool takes precedence over the reader and writer of the models
+/// while the scoped future is being polled. It is not inherited by the spawned tasks.
+/// The tables are assumed to have been created in the selected database.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino_core::orm::DatabaseContext;
+///
+/// let context = DatabaseContext::use_pool("shard-eu")?;
+/// let users = context.scope(async {
+///     User::find::<Map>(&query).await
+/// }).await?;
+///
+/// // Previews the SQL without touching the database.
+/// let ctx = DatabaseContext::default()
+///     .enable_dry_run()
+///     .scope(user.update())
+///     .await?;
+/// let (sql, arguments) = (ctx.query(), ctx.arguments());
+///
+/// // Sets `app.current_user_id` for the row-level security policies.
+/// let context = DatabaseContext::default().with_user_session(&session);
+/// let tasks = context.scope(Task::find::<Map>(&query)).await?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DatabaseContext {
+    /// Connection pool.
+    pool: Option<&'static ConnectionPool>,
+    /// Dry-run mode.
+    dry_run: bool,
+    /// Session variables.
+    session_variables: Arc<Vec<(&'static str, String)>>,
+}
+
+impl DatabaseContext {
+    /// Creates a new instance for the connection pool with the name.
+    #[inline]
+    pub fn use_pool(name: &str) -> Result<Self, Error> {
+        GlobalPool::get(name)
+            .map(|pool| Self {
+                pool: Some(pool),
+                ..Self::default()
+            })
+            .ok_or_else(|| warn!("the connection pool `{}` does not exist", name))
+    }
+
+    /// Enables the dry-run mode, in which the mutations are not executed
+    /// and the returned query context contains the SQL and arguments.
+    #[inline]
+    pub fn enable_dry_run(mut self) -> Self {
+        self.dry_run = true;
+        self
+    }
+
+    /// Sets a session variable for the statements executed in the scope,
+    /// which can be read by `current_setting(name, true)` in the RLS policies.
+    /// The variable is set by `set_config(name, value, true)` inside of a transaction,
+    /// so each statement executed on the pool runs in its own transaction.
+    /// The streaming queries are not covered.
+    ///
+    /// Only PostgreSQL supports the session variables.
+    pub fn set_session_variable(mut self, name: &'static str, value: impl ToString) -> Self {
+        let value = value.to_string();
+        let variables = Arc::make_mut(&mut self.session_variables);
+        if let Some(variable) = variables.iter_mut().find(|(key, _)| *key == name) {
+            variable.1 = value;
+        } else {
+            variables.push((name, value));
+        }
+        self
+    }
+
+    /// Sets the session variables `app.current_user_id`, `app.current_tenant_id`
+    /// and `app.current_roles` with the user session.
+    pub fn with_user_session<U, R, T>(self, session: &UserSession<U, R, T>) -> Self
+    where
+        U: ToString,
+        R: ToString,
+        T: ToString,
+    {
+        let roles = session
+            .roles()
+            .iter()
+            .map(|role| role.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        let context = self
+            .set_session_variable("app.current_user_id", session.user_id())
+            .set_session_variable("app.current_roles", roles);
+        if let Some(tenant_id) = session.tenant_id() {
+            context.set_session_variable("app.current_tenant_id", tenant_id)
+        } else {
+            context
+        }
+    }
+
+    /// Returns the selected connection pool.
+    #[inline]
+    pub fn pool(&self) -> Option<&'static ConnectionPool> {
+        self.pool
+    }
+
+    /// Returns `true` if the dry-run mode is enabled.
+    #[inline]
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Returns the value of a session variable.
+    #[inline]
+    pub fn get_session_variable(&self, name: &str) -> Option<&str> {
+        self.session_variables
+            .iter()
+            .find_map(|(key, value)| (*key == name).then_some(value.as_str()))
+    }
+
+    /// Runs the future with the database context.
+    #[inline]
+    pub fn scope<F: Future>(self, future: F) -> ScopedFuture<F> {
+        ScopedFuture {
+            context: self,
+            future: Box::pin(future),
+        }
+    }
+
+    /// Returns the database context for the current scope.
+    #[inline]
+    pub fn current() -> Option<Self> {
+        CURRENT_CONTEXT.with(|context| context.borrow().clone())
+    }
+
+    /// Returns the connection pool selected for the current scope.
+    #[inline]
+    pub fn current_pool() -> Option<&'static ConnectionPool> {
+        CURRENT_CONTEXT.with(|context| context.borrow().as_ref().and_then(|ctx| ctx.pool))
+    }
+}
+
+/// A future running with a selected connection pool.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct ScopedFuture<F> {
+    /// Database context.
+    context: DatabaseContext,
+    /// Inner future.
+    future: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for ScopedFuture<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let context = Some(this.context.clone());
+        let _guard = ContextGuard(CURRENT_CONTEXT.with(|current| current.replace(context)));
+        this.future.as_mut().poll(cx)
+    }
+}
+
+/// A guard which restores the previous database context.
+struct ContextGuard(Option<DatabaseContext>);
+
+impl Drop for ContextGuard {
+    #[inline]
+    fn drop(&mut self) {
+        let context = self.0.take();
+        CURRENT_CONTEXT.with(|current| *current.borrow_mut() = context);
+    }
+}
+
+/// Begins a transaction on the connection pool with the session variables of the current scope,
+/// which are set by `set_config(name, value, true)` and reset when the transaction ends,
+/// so that they never leak through the pooled connections.
+#[cfg(feature = "orm-sqlx")]
+pub(super) async fn begin_transaction(
+    pool: &sqlx::Pool<super::DatabaseDriver>,
+) -> Result<sqlx::Transaction<'static, super::DatabaseDriver>, sqlx::Error> {
+    let mut transaction = pool.begin().await?;
+    #[cfg(feature = "orm-postgres")]
+    set_local_session_variables(&mut transaction).await?;
+    Ok(transaction)
+}
+
+/// Begins a transaction as [`begin_transaction()`] if there are session variables
+/// in the current scope. Each statement executed on the pool runs inside of it.
+#[cfg(feature = "orm-sqlx")]
+pub(super) async fn begin_scoped_transaction(
+    pool: &&sqlx::Pool<super::DatabaseDriver>,
+) -> Result<Option<sqlx::Transaction<'static, super::DatabaseDriver>>, sqlx::Error> {
+    let has_session_variables =
+        DatabaseContext::current().is_some_and(|context| !context.session_variables.is_empty());
+    if cfg!(feature = "orm-postgres") && has_session_variables {
+        begin_transaction(pool).await.map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Sets the session variables of the current scope for the transaction.
+#[cfg(feature = "orm-postgres")]
+pub(super) async fn set_local_session_variables(
+    conn: &mut super::DatabaseConnection,
+) -> Result<(), sqlx::Error> {
+    let Some(context) = DatabaseContext::current() else {
+        return Ok(());
+    };
+    let variables = context.session_variables.as_slice();
+    if variables.is_empty() {
+        return Ok(());
+    }
+
+    let setters = (0..variables.len())
+        .map(|index| format!("set_config(${}, ${}, true)", 2 * index + 1, 2 * index + 2))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let sql = format!("SELECT {setters};");
+    let mut query = sqlx::query(&sql);
+    for (name, value) in variables {
+        query = query.bind(*name).bind(value.as_str());
+    }
+    query.execute(conn).await?;
+    Ok(())
+}
+
+thread_local! {
+    /// Database context for the current scope.
+    static CURRENT_CONTEXT: RefCell<Option<DatabaseContext>> = const { RefCell::new(None) };
+}
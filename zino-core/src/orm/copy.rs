@@ -0,0 +1,156 @@
+//! Bulk ingestion of the rows.
+
+use super::{column::ColumnExt, executor, query::QueryExt, DatabasePool};
+use crate::{
+    error::Error,
+    model::{Column, Query},
+    Map,
+};
+
+/// Copies the rows into the table by `COPY FROM STDIN`
+/// and returns the number of rows copied.
+///
+/// Since `COPY` does not accept expressions, the rows are grouped by the columns
+/// falling back to their default expressions, which are then omitted from the column list.
+#[cfg(feature = "orm-postgres")]
+pub(super) async fn copy_in(
+    pool: &DatabasePool,
+    table_name: &str,
+    columns: &[&Column<'static>],
+    rows: &[Map],
+) -> Result<u64, Error> {
+    use crate::extension::JsonValueExt;
+    use sqlx::postgres::PgPoolCopyExt;
+
+    let mut groups: Vec<(Vec<bool>, Vec<&Map>)> = Vec::new();
+    for row in rows {
+        let mask = columns
+            .iter()
+            .map(|col| {
+                col.default_expression().is_none()
+                    || row.get(col.name()).is_some_and(|v| !v.is_ignorable())
+            })
+            .collect::<Vec<_>>();
+        if let Some((_, rows)) = groups.iter_mut().find(|(key, _)| *key == mask) {
+            rows.push(row);
+        } else {
+            groups.push((mask, vec![row]));
+        }
+    }
+
+    let mut num_rows = 0;
+    for (mask, rows) in groups {
+        let columns = columns
+            .iter()
+            .zip(mask)
+            .filter_map(|(col, included)| included.then_some(*col))
+            .collect::<Vec<_>>();
+        let fields = format_fields(&columns);
+        let sql = format!("COPY {table_name} ({fields}) FROM STDIN;");
+        let mut buffer = String::new();
+        for row in rows {
+            for (index, col) in columns.iter().enumerate() {
+                if index > 0 {
+                    buffer.push('\t');
+                }
+                encode_text_value(&mut buffer, row.get(col.name()));
+            }
+            buffer.push('\n');
+        }
+
+        let copy = async {
+            let mut copy = pool.copy_in_raw(&sql).await?;
+            copy.send(buffer.into_bytes()).await?;
+            copy.finish().await.map_err(Error::from)
+        };
+        num_rows += executor::with_timeout(None, copy).await?;
+    }
+    Ok(num_rows)
+}
+
+/// Copies the rows into the table by a multi-row `INSERT`
+/// and returns the number of rows copied.
+///
+/// `LOAD DATA LOCAL INFILE` is not supported for MySQL since the sqlx driver
+/// does not implement the client side of the local file transfer.
+#[cfg(not(feature = "orm-postgres"))]
+pub(super) async fn copy_in(
+    pool: &DatabasePool,
+    table_name: &str,
+    columns: &[&Column<'static>],
+    rows: &[Map],
+) -> Result<u64, Error> {
+    use super::Executor;
+
+    let fields = format_fields(columns);
+    let values = rows
+        .iter()
+        .map(|row| {
+            let values = columns
+                .iter()
+                .map(|col| col.encode_insert_value(row.get(col.name())))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("({values})")
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let sql = format!("INSERT INTO {table_name} ({fields}) VALUES {values};");
+    let query_result = executor::with_timeout(None, pool.execute(&sql)).await?;
+    Ok(query_result.rows_affected())
+}
+
+/// Formats the column list with the column names quoted.
+fn format_fields(columns: &[&Column<'static>]) -> String {
+    columns
+        .iter()
+        .map(|col| Query::quote_identifier(&col.column_name()).into_owned())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Encodes a value in the text format of `COPY`.
+#[cfg(feature = "orm-postgres")]
+fn encode_text_value(buffer: &mut String, value: Option<&crate::JsonValue>) {
+    use crate::JsonValue;
+
+    match value {
+        None | Some(JsonValue::Null) => buffer.push_str("\\N"),
+        Some(JsonValue::Bool(value)) => buffer.push(if *value { 't' } else { 'f' }),
+        Some(JsonValue::Number(value)) => buffer.push_str(&value.to_string()),
+        Some(JsonValue::String(value)) => escape_text(buffer, value),
+        Some(JsonValue::Array(values)) => {
+            let elements = values
+                .iter()
+                .map(|value| match value {
+                    JsonValue::Null => "NULL".to_owned(),
+                    JsonValue::String(value) => {
+                        let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+                        format!("\"{value}\"")
+                    }
+                    _ => value.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(",");
+            escape_text(buffer, &format!("{{{elements}}}"));
+        }
+        Some(value) => escape_text(buffer, &value.to_string()),
+    }
+}
+
+/// Escapes the special characters in the text format of `COPY`.
+#[cfg(feature = "orm-postgres")]
+fn escape_text(buffer: &mut String, value: &str) {
+    for ch in value.chars() {
+        match ch {
+            '\\' => buffer.push_str("\\\\"),
+            '\n' => buffer.push_str("\\n"),
+            '\r' => buffer.push_str("\\r"),
+            '\t' => buffer.push_str("\\t"),
+            _ => buffer.push(ch),
+        }
+    }
+}
+
+/// Number of rows in a batch.
+pub(super) const BATCH_SIZE: usize = 10000;
@@ -1,7 +1,13 @@
 use super::{DatabaseDriver, DatabaseRow};
-use crate::{error::Error, BoxError, Decimal, Uuid};
+use crate::{
+    error::Error,
+    model::{ModelEnum, Range},
+    money::{Currency, Money, RoundingPolicy},
+    BoxError, Decimal, JsonValue, Uuid,
+};
 use chrono::{DateTime, Local, NaiveDate, NaiveTime};
 use sqlx::{database::HasValueRef, Database, Decode, Row, Type};
+use std::time::Duration;
 
 impl<DB> Type<DB> for crate::datetime::Date
 where
@@ -78,6 +84,30 @@ where
     row.try_get_unchecked(field).map_err(Error::from)
 }
 
+/// Decodes a single value as the [`ModelEnum`] type for the field in a row.
+pub fn decode_enum<T: ModelEnum>(row: &DatabaseRow, field: &str) -> Result<T, Error> {
+    let value = if T::INTEGER_REPR {
+        decode::<i32>(row, field)?.into()
+    } else {
+        decode::<String>(row, field)?.into()
+    };
+    T::parse_value(&value)
+}
+
+/// Decodes a single value as the optional [`ModelEnum`] type for the field in a row.
+/// A `NULL` value is decoded as `None`, and an invalid value is rejected with an error.
+pub fn decode_optional_enum<T: ModelEnum>(
+    row: &DatabaseRow,
+    field: &str,
+) -> Result<Option<T>, Error> {
+    let value = if T::INTEGER_REPR {
+        decode::<Option<i32>>(row, field)?.map(JsonValue::from)
+    } else {
+        decode::<Option<String>>(row, field)?.map(JsonValue::from)
+    };
+    value.as_ref().map(T::parse_value).transpose()
+}
+
 /// Decodes a single value as `Decimal` for the field in a row.
 #[cfg(any(
     feature = "orm-mariadb",
@@ -104,6 +134,21 @@ pub fn decode_decimal(row: &DatabaseRow, field: &str) -> Result<Decimal, Error>
         .map_err(Error::from)
 }
 
+/// Decodes a single value as `Money` for the field in a row.
+/// The default currency is used if the `currency` is not specified.
+pub fn decode_money(
+    row: &DatabaseRow,
+    field: &str,
+    currency: Option<&str>,
+) -> Result<Money, Error> {
+    let currency = match currency {
+        Some(code) => code.parse()?,
+        None => Currency::default(),
+    };
+    let amount = decode_decimal(row, field)?;
+    Money::from_decimal(amount, currency, RoundingPolicy::default())
+}
+
 /// Decodes a single value as `Uuid` for the field in a row.
 #[cfg(feature = "orm-postgres")]
 #[inline]
@@ -120,6 +165,43 @@ pub fn decode_uuid(row: &DatabaseRow, field: &str) -> Result<Uuid, Error> {
         .map_err(Error::from)
 }
 
+/// Decodes a single value as `Range<T>` for the field in a row.
+#[cfg(feature = "orm-postgres")]
+#[inline]
+pub fn decode_range<'r, T>(row: &'r DatabaseRow, field: &str) -> Result<Range<T>, Error>
+where
+    Range<T>: Decode<'r, DatabaseDriver> + Type<DatabaseDriver>,
+{
+    row.try_get_unchecked(field).map_err(Error::from)
+}
+
+/// Decodes a single value as `Range<T>` for the field in a row.
+#[cfg(not(feature = "orm-postgres"))]
+#[inline]
+pub fn decode_range<T: std::str::FromStr>(
+    row: &DatabaseRow,
+    field: &str,
+) -> Result<Range<T>, Error> {
+    decode::<String>(row, field).and_then(|value| value.parse())
+}
+
+/// Decodes a single value as `Duration` for the field in a row.
+#[cfg(feature = "orm-postgres")]
+#[inline]
+pub fn decode_duration(row: &DatabaseRow, field: &str) -> Result<Duration, Error> {
+    row.try_get_unchecked(field)
+        .and_then(|interval| interval_to_duration(field, interval))
+        .map_err(Error::from)
+}
+
+/// Decodes a single value as `Duration` for the field in a row.
+#[cfg(not(feature = "orm-postgres"))]
+#[inline]
+pub fn decode_duration(row: &DatabaseRow, field: &str) -> Result<Duration, Error> {
+    decode::<String>(row, field)
+        .and_then(|value| crate::datetime::parse_duration(&value).map_err(Error::from))
+}
+
 /// Decodes a single value as `Vec<T>` for the field in a row.
 #[cfg(feature = "orm-postgres")]
 #[inline]
@@ -180,3 +262,29 @@ where
         }
     })
 }
+
+/// Converts a `PgInterval` into a `Duration`, where a month is assumed to be 30 days.
+/// A negative interval is rejected since it can not be represented by `Duration`.
+#[cfg(feature = "orm-postgres")]
+pub(super) fn interval_to_duration(
+    field: &str,
+    interval: sqlx::postgres::types::PgInterval,
+) -> Result<Duration, sqlx::Error> {
+    use crate::{validation::Validation, warn};
+
+    const MICROS_PER_DAY: i64 = 86_400_000_000;
+    let days = i64::from(interval.months) * 30 + i64::from(interval.days);
+    let micros = days
+        .saturating_mul(MICROS_PER_DAY)
+        .saturating_add(interval.microseconds);
+    u64::try_from(micros)
+        .map(Duration::from_micros)
+        .map_err(|_| {
+            let validation = Validation::from_entry(field.to_owned(), warn!("negative interval"));
+            tracing::error!("fail to decode the `{}` field", field);
+            sqlx::Error::ColumnDecode {
+                index: field.to_owned(),
+                source: validation.to_string().into(),
+            }
+        })
+}
@@ -0,0 +1,384 @@
+use super::{DatabaseConnection, DatabaseDriver, Executor, GlobalPool};
+use crate::{bail, error::Error, extension::TomlTableExt, state::State, warn, LazyLock, Uuid};
+use sqlx::pool::PoolConnection;
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Prefix for the global transaction identifiers.
+const XID_PREFIX: &str = "zino.";
+
+/// A transaction spanning multiple connection pools with the two-phase commit protocol.
+///
+/// The first pool acts as the coordinator. It is prepared first and committed first,
+/// so that the outcome of an in-doubt transaction can be decided during recovery:
+/// if the coordinator branch is still prepared, no branch has been committed
+/// and the transaction is rolled back; otherwise, the transaction is committed.
+///
+/// Only PostgreSQL (`PREPARE TRANSACTION`) and MySQL (`XA`) are supported.
+/// PostgreSQL requires `max_prepared_transactions` to be greater than zero.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino_core::orm::{DistributedTransaction, Executor};
+///
+/// let mut transaction = DistributedTransaction::begin(&["main", "audit"]).await?;
+/// transaction.connection("main")?.execute(transfer_sql).await?;
+/// transaction.connection("audit")?.execute(audit_sql).await?;
+/// transaction.commit().await?;
+/// ```
+pub struct DistributedTransaction {
+    /// Global transaction identifier.
+    xid: String,
+    /// Transaction branches.
+    branches: Vec<Branch>,
+    /// A flag to indicate whether the transaction has entered the prepare phase.
+    prepared: bool,
+}
+
+/// A transaction branch on a connection pool.
+struct Branch {
+    /// Name of the connection pool.
+    name: &'static str,
+    /// Database connection.
+    connection: Option<PoolConnection<DatabaseDriver>>,
+    /// A flag to indicate whether the branch has been prepared.
+    prepared: bool,
+}
+
+impl DistributedTransaction {
+    /// Begins a distributed transaction across the connection pools with the names.
+    pub async fn begin(names: &[&str]) -> Result<Self, Error> {
+        if !Self::is_supported() {
+            bail!(
+                "two-phase commit is not supported by the `{}` driver",
+                super::DRIVER_NAME
+            );
+        }
+
+        let Some(coordinator) = names.first() else {
+            bail!("at least one connection pool should be specified");
+        };
+        let xid = format!(
+            "{XID_PREFIX}{}.{}.{}",
+            SHARED_RECOVERY_CONFIG.instance_id,
+            escape_literal(coordinator),
+            Uuid::now_v7().simple()
+        );
+        let mut transaction = Self {
+            xid,
+            branches: Vec::with_capacity(names.len()),
+            prepared: false,
+        };
+        for &name in names {
+            let connection_pool = GlobalPool::get(name)
+                .ok_or_else(|| warn!("the connection pool `{}` does not exist", name))?;
+            let mut connection = connection_pool.pool().acquire().await?;
+            let sql = if is_mysql() {
+                format!("XA START '{}';", transaction.xid)
+            } else {
+                "BEGIN;".to_owned()
+            };
+            if let Err(err) = (&mut *connection).execute(&sql).await {
+                transaction.rollback().await?;
+                return Err(err);
+            }
+            transaction.branches.push(Branch {
+                name: connection_pool.name(),
+                connection: Some(connection),
+                prepared: false,
+            });
+        }
+        Ok(transaction)
+    }
+
+    /// Returns the global transaction identifier.
+    #[inline]
+    pub fn xid(&self) -> &str {
+        &self.xid
+    }
+
+    /// Returns a connection of the transaction branch for the pool with the name.
+    pub fn connection(&mut self, name: &str) -> Result<&mut DatabaseConnection, Error> {
+        if self.prepared {
+            bail!("the transaction `{}` has been prepared", self.xid);
+        }
+        self.branches
+            .iter_mut()
+            .find(|branch| branch.name == name)
+            .and_then(|branch| branch.connection.as_deref_mut())
+            .ok_or_else(|| warn!("the connection pool `{}` is not in the transaction", name))
+    }
+
+    /// Prepares all the transaction branches, starting with the coordinator.
+    /// If any of them fails, the transaction will be rolled back.
+    pub async fn prepare(&mut self) -> Result<(), Error> {
+        if self.prepared {
+            return Ok(());
+        }
+
+        let xid = self.xid.as_str();
+        let mut result = Ok(());
+        for branch in self.branches.iter_mut() {
+            let Some(connection) = branch.connection.as_deref_mut() else {
+                continue;
+            };
+            let prepared = if is_mysql() {
+                match connection.execute(&format!("XA END '{xid}';")).await {
+                    Ok(_) => connection.execute(&format!("XA PREPARE '{xid}';")).await,
+                    Err(err) => Err(err),
+                }
+            } else {
+                connection
+                    .execute(&format!("PREPARE TRANSACTION '{xid}';"))
+                    .await
+            };
+            if let Err(err) = prepared {
+                result = Err(err);
+                break;
+            }
+            branch.prepared = true;
+        }
+        self.prepared = true;
+        if let Err(err) = result {
+            // The rollback errors have been logged, and the original error is returned.
+            self.rollback_branches().await.ok();
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Commits the transaction. The branches will be prepared if necessary.
+    pub async fn commit(mut self) -> Result<(), Error> {
+        self.prepare().await?;
+
+        let xid = self.xid.as_str();
+        let sql = if is_mysql() {
+            format!("XA COMMIT '{xid}';")
+        } else {
+            format!("COMMIT PREPARED '{xid}';")
+        };
+        for branch in self.branches.iter_mut() {
+            if let Some(mut connection) = branch.connection.take() {
+                (&mut *connection).execute(&sql).await.map_err(|err| {
+                    let name = branch.name;
+                    err.wrap(format!(
+                        "fail to commit the branch `{name}` of the in-doubt transaction `{xid}`"
+                    ))
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Rolls back the transaction.
+    #[inline]
+    pub async fn rollback(mut self) -> Result<(), Error> {
+        self.rollback_branches().await
+    }
+
+    /// Rolls back the branches in the reverse order, so that the coordinator
+    /// is still prepared if the process crashes during the rollback.
+    async fn rollback_branches(&mut self) -> Result<(), Error> {
+        let xid = self.xid.as_str();
+        let mut result = Ok(());
+        for branch in self.branches.iter_mut().rev() {
+            let Some(mut connection) = branch.connection.take() else {
+                continue;
+            };
+            let connection = &mut *connection;
+            let rollback = if is_mysql() {
+                if !branch.prepared {
+                    // The error is ignored since the branch may have been ended.
+                    connection.execute(&format!("XA END '{xid}';")).await.ok();
+                }
+                connection.execute(&format!("XA ROLLBACK '{xid}';")).await
+            } else if branch.prepared {
+                connection
+                    .execute(&format!("ROLLBACK PREPARED '{xid}';"))
+                    .await
+            } else {
+                connection.execute("ROLLBACK;").await
+            };
+            if let Err(err) = rollback {
+                tracing::warn!(xid, "fail to roll back the branch `{}`: {err}", branch.name);
+                if result.is_ok() {
+                    result = Err(err);
+                }
+            }
+        }
+        result
+    }
+
+    /// Recovers the in-doubt transactions left by a crashed process.
+    /// It is called at startup if `two-phase-commit` is enabled in the `[database]` table,
+    /// and returns the number of transactions resolved.
+    ///
+    /// Only the transactions started by the same instance or older than the lease
+    /// are resolved, so that the in-flight transactions of other instances are not touched.
+    /// They can be configured by the `[database]` table:
+    ///
+    /// ```toml
+    /// [database]
+    /// two-phase-commit = true
+    /// instance-id = "web-1"
+    /// transaction-lease = "10m"
+    /// ```
+    ///
+    /// The `instance-id` defaults to the `HOSTNAME` environment variable.
+    pub async fn recover() -> Result<usize, Error> {
+        if !Self::is_supported() {
+            return Ok(0);
+        }
+
+        let mut prepared_xids = HashMap::<String, Vec<&'static str>>::new();
+        for connection_pool in super::SHARED_CONNECTION_POOLS.0.iter() {
+            let sql = if is_mysql() {
+                "XA RECOVER;"
+            } else {
+                "SELECT gid AS data FROM pg_prepared_xacts WHERE database = current_database();"
+            };
+            let rows = connection_pool.pool().fetch(sql).await?;
+            for row in rows {
+                let xid = super::decode::<String>(&row, "data")?;
+                if xid.starts_with(XID_PREFIX) && is_recoverable(&xid) {
+                    prepared_xids
+                        .entry(xid)
+                        .or_default()
+                        .push(connection_pool.name());
+                }
+            }
+        }
+
+        let num_transactions = prepared_xids.len();
+        for (xid, names) in prepared_xids {
+            let coordinator = xid
+                .strip_prefix(XID_PREFIX)
+                .and_then(|s| s.split_once('.'))
+                .and_then(|(_, s)| s.rsplit_once('.'))
+                .map(|(coordinator, _)| coordinator)
+                .unwrap_or_default();
+            let committed = !names.iter().any(|&name| name == coordinator);
+            let sql = match (is_mysql(), committed) {
+                (true, true) => format!("XA COMMIT '{xid}';"),
+                (true, false) => format!("XA ROLLBACK '{xid}';"),
+                (false, true) => format!("COMMIT PREPARED '{xid}';"),
+                (false, false) => format!("ROLLBACK PREPARED '{xid}';"),
+            };
+            for name in names {
+                if let Some(connection_pool) = GlobalPool::get(name) {
+                    connection_pool.pool().execute(&sql).await?;
+                    tracing::warn!(
+                        xid,
+                        committed,
+                        "in-doubt transaction is resolved for `{name}`"
+                    );
+                }
+            }
+        }
+        Ok(num_transactions)
+    }
+
+    /// Returns `true` if the two-phase commit is supported by the driver.
+    #[inline]
+    fn is_supported() -> bool {
+        is_mysql() || cfg!(feature = "orm-postgres")
+    }
+}
+
+impl Drop for DistributedTransaction {
+    fn drop(&mut self) {
+        for branch in self.branches.iter_mut() {
+            if let Some(connection) = branch.connection.take() {
+                // Closes the connection so that the unprepared branch is rolled back
+                // by the server and the prepared branch is left for recovery.
+                drop(connection.detach());
+                tracing::warn!(
+                    xid = self.xid.as_str(),
+                    "the branch `{}` is dropped without being finished",
+                    branch.name
+                );
+            }
+        }
+    }
+}
+
+/// Returns `true` if the MySQL driver is used.
+#[inline]
+fn is_mysql() -> bool {
+    cfg!(any(
+        feature = "orm-mariadb",
+        feature = "orm-mysql",
+        feature = "orm-tidb"
+    ))
+}
+
+/// Escapes the value so that it can be used in a string literal.
+#[inline]
+fn escape_literal(value: &str) -> String {
+    value.replace('\'', "''")
+}
+
+/// Returns `true` if the in-doubt transaction is started by the current instance
+/// or its lease has expired.
+fn is_recoverable(xid: &str) -> bool {
+    let Some((instance_id, s)) = xid.strip_prefix(XID_PREFIX).and_then(|s| s.split_once('.'))
+    else {
+        return false;
+    };
+    if instance_id == SHARED_RECOVERY_CONFIG.instance_id {
+        return true;
+    }
+
+    let started_at = s
+        .rsplit_once('.')
+        .and_then(|(_, id)| Uuid::parse_str(id).ok())
+        .and_then(|id| id.get_timestamp())
+        .map(|ts| {
+            let (secs, nanos) = ts.to_unix();
+            UNIX_EPOCH + Duration::new(secs, nanos)
+        });
+    started_at.is_some_and(|started_at| {
+        SystemTime::now()
+            .duration_since(started_at)
+            .is_ok_and(|elapsed| elapsed > SHARED_RECOVERY_CONFIG.lease)
+    })
+}
+
+/// Config of the in-doubt transaction recovery.
+struct RecoveryConfig {
+    /// Identifier of the current instance.
+    instance_id: String,
+    /// Lease of an in-doubt transaction started by other instances.
+    lease: Duration,
+}
+
+/// Shared recovery config.
+static SHARED_RECOVERY_CONFIG: LazyLock<RecoveryConfig> = LazyLock::new(|| {
+    let config = State::shared().get_config("database");
+    let instance_id = config
+        .and_then(|config| config.get_str("instance-id"))
+        .map(|s| s.to_owned())
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .unwrap_or_default();
+
+    // The XID length is limited to 64 bytes in MySQL.
+    let instance_id = instance_id
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .take(16)
+        .collect::<String>();
+    let lease = config
+        .and_then(|config| config.get_duration("transaction-lease"))
+        .unwrap_or_else(|| Duration::from_secs(10 * 60));
+    RecoveryConfig {
+        instance_id: if instance_id.is_empty() {
+            "default".to_owned()
+        } else {
+            instance_id
+        },
+        lease,
+    }
+});
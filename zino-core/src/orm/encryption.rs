@@ -0,0 +1,49 @@
+//! Database encryption for SQLite with SQLCipher.
+
+use super::{ConnectionPool, PoolManager};
+use crate::error::Error;
+use sqlx::Executor;
+
+/// Formats the value of the `key` pragma for the passphrase.
+///
+/// The passphrase is passed to SQLCipher as a string literal, so that the raw key
+/// is derived by the PBKDF2 of SQLCipher with the random salt stored in the database file.
+pub(super) fn format_key(passphrase: &str) -> String {
+    format!("'{}'", passphrase.replace('\'', "''"))
+}
+
+/// Changes the encryption key of the database with a new passphrase.
+/// The initial passphrase is configured by the `password` field of the `[[sqlite]]` table.
+///
+/// All the connections opened with the old passphrase are closed after rekeying,
+/// and the new connections of the pool will be opened with the new key.
+pub async fn rekey(cp: &'static ConnectionPool, passphrase: &str) -> Result<(), Error> {
+    let key = format_key(passphrase);
+    let pool = cp.pool();
+    let sql = format!("PRAGMA rekey = {key};");
+    let mut conn = pool.acquire().await?;
+    conn.execute(sql.as_str()).await?;
+    drop(conn);
+
+    let connect_options = pool.connect_options().as_ref().clone().pragma("key", key);
+    pool.set_connect_options(connect_options);
+
+    let name = cp.name();
+    let num_closed = cp.reconnect().await;
+    tracing::warn!(
+        num_closed,
+        "the database for the `{name}` service has been rekeyed"
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_key;
+
+    #[test]
+    fn it_formats_key() {
+        assert_eq!(format_key("secret"), "'secret'");
+        assert_eq!(format_key("it's"), "'it''s'");
+    }
+}
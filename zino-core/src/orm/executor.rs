@@ -1,4 +1,10 @@
-use crate::error::Error;
+use crate::{error::Error, model::QueryContext, request, JsonValue};
+use futures::{
+    future::{self, Either},
+    stream::BoxStream,
+};
+use futures_timer::Delay;
+use std::{future::Future, pin::pin, time::Duration};
 
 /// Executing queries against the database.
 pub trait Executor {
@@ -18,9 +24,21 @@ pub trait Executor {
         arguments: &[T],
     ) -> Result<Self::QueryResult, Error>;
 
+    /// Executes the query with the JSON values bound as typed arguments
+    /// and return the total number of rows affected.
+    async fn execute_bound(
+        self,
+        sql: &str,
+        values: &[&JsonValue],
+    ) -> Result<Self::QueryResult, Error>;
+
     /// Executes the query and return all the generated results.
     async fn fetch(self, sql: &str) -> Result<Vec<Self::Row>, Error>;
 
+    /// Executes the query with the JSON values bound as typed arguments
+    /// and return all the generated results.
+    async fn fetch_bound(self, sql: &str, values: &[&JsonValue]) -> Result<Vec<Self::Row>, Error>;
+
     /// Executes the query with arguments and return all the generated results.
     async fn fetch_with<T: ToString>(
         self,
@@ -28,6 +46,11 @@ pub trait Executor {
         arguments: &[T],
     ) -> Result<Vec<Self::Row>, Error>;
 
+    /// Executes the query and returns a stream of rows as they come off the database cursor.
+    fn fetch_stream<'a>(self, sql: &'a str) -> BoxStream<'a, Result<Self::Row, Error>>
+    where
+        Self: 'a;
+
     /// Executes the query and returns exactly one row.
     async fn fetch_one(self, sql: &str) -> Result<Self::Row, Error>;
 
@@ -44,11 +67,16 @@ pub trait Executor {
 
 #[cfg(feature = "orm-sqlx")]
 macro_rules! impl_sqlx_executor {
-    () => {
+    ($read:ident, $begin:path) => {
         type Row = super::DatabaseRow;
         type QueryResult = <super::DatabaseDriver as sqlx::Database>::QueryResult;
 
         async fn execute(self, sql: &str) -> Result<Self::QueryResult, Error> {
+            if let Some(mut transaction) = $begin(&self).await? {
+                let data = Executor::execute(&mut *transaction, sql).await?;
+                transaction.commit().await?;
+                return Ok(data);
+            }
             match sqlx::query(sql).execute(self).await {
                 Ok(result) => Ok(result),
                 Err(err) => {
@@ -65,6 +93,11 @@ macro_rules! impl_sqlx_executor {
             sql: &str,
             arguments: &[T],
         ) -> Result<Self::QueryResult, Error> {
+            if let Some(mut transaction) = $begin(&self).await? {
+                let data = Executor::execute_with(&mut *transaction, sql, arguments).await?;
+                transaction.commit().await?;
+                return Ok(data);
+            }
             let mut query = sqlx::query(sql);
             for arg in arguments {
                 query = query.bind(arg.to_string());
@@ -80,29 +113,37 @@ macro_rules! impl_sqlx_executor {
             }
         }
 
-        async fn fetch(self, sql: &str) -> Result<Vec<Self::Row>, Error> {
-            use futures::StreamExt;
-            use std::sync::atomic::Ordering::Relaxed;
-
-            let mut stream = sqlx::query(sql).fetch(self);
-            let mut max_rows = super::MAX_ROWS.load(Relaxed);
-            let mut rows = Vec::with_capacity(stream.size_hint().0.min(max_rows));
-            while let Some(result) = stream.next().await {
-                match result {
-                    Ok(row) if max_rows > 0 => {
-                        rows.push(row);
-                        max_rows -= 1;
-                    }
-                    Err(err) => {
-                        if matches!(err, sqlx::error::Error::PoolTimedOut) {
-                            super::GlobalPool::connect_all().await;
-                        }
-                        return Err(err.into());
+        async fn execute_bound(
+            self,
+            sql: &str,
+            values: &[&JsonValue],
+        ) -> Result<Self::QueryResult, Error> {
+            if let Some(mut transaction) = $begin(&self).await? {
+                let data = Executor::execute_bound(&mut *transaction, sql, values).await?;
+                transaction.commit().await?;
+                return Ok(data);
+            }
+            match bind_values(sqlx::query(sql), values).execute(self).await {
+                Ok(result) => Ok(result),
+                Err(err) => {
+                    if matches!(err, sqlx::error::Error::PoolTimedOut) {
+                        super::GlobalPool::connect_all().await;
                     }
-                    _ => break,
+                    Err(err.into())
                 }
             }
-            Ok(rows)
+        }
+
+        async fn fetch(self, sql: &str) -> Result<Vec<Self::Row>, Error> {
+            if let Some(mut transaction) = $begin(&self).await? {
+                let data = Executor::fetch(&mut *transaction, sql).await?;
+                transaction.commit().await?;
+                return Ok(data);
+            }
+            $read(sql, move || async move {
+                collect_rows(sqlx::query(sql).fetch(self)).await
+            })
+            .await
         }
 
         async fn fetch_with<T: ToString>(
@@ -110,57 +151,63 @@ macro_rules! impl_sqlx_executor {
             sql: &str,
             arguments: &[T],
         ) -> Result<Vec<Self::Row>, Error> {
-            use futures::StreamExt;
-            use std::sync::atomic::Ordering::Relaxed;
-
-            let mut query = sqlx::query(sql);
-            for arg in arguments {
-                query = query.bind(arg.to_string());
+            if let Some(mut transaction) = $begin(&self).await? {
+                let data = Executor::fetch_with(&mut *transaction, sql, arguments).await?;
+                transaction.commit().await?;
+                return Ok(data);
             }
-
-            let mut stream = query.fetch(self);
-            let mut max_rows = super::MAX_ROWS.load(Relaxed);
-            let mut rows = Vec::with_capacity(stream.size_hint().0.min(max_rows));
-            while let Some(result) = stream.next().await {
-                match result {
-                    Ok(row) if max_rows > 0 => {
-                        rows.push(row);
-                        max_rows -= 1;
-                    }
-                    Err(err) => {
-                        if matches!(err, sqlx::error::Error::PoolTimedOut) {
-                            super::GlobalPool::connect_all().await;
-                        }
-                        return Err(err.into());
-                    }
-                    _ => break,
+            $read(sql, move || async move {
+                let mut query = sqlx::query(sql);
+                for arg in arguments {
+                    query = query.bind(arg.to_string());
                 }
+
+                collect_rows(query.fetch(self)).await
+            })
+            .await
+        }
+
+        async fn fetch_bound(
+            self,
+            sql: &str,
+            values: &[&JsonValue],
+        ) -> Result<Vec<Self::Row>, Error> {
+            if let Some(mut transaction) = $begin(&self).await? {
+                let data = Executor::fetch_bound(&mut *transaction, sql, values).await?;
+                transaction.commit().await?;
+                return Ok(data);
             }
-            Ok(rows)
+            $read(sql, move || async move {
+                collect_rows(bind_values(sqlx::query(sql), values).fetch(self)).await
+            })
+            .await
+        }
+
+        fn fetch_stream<'a>(self, sql: &'a str) -> BoxStream<'a, Result<Self::Row, Error>>
+        where
+            Self: 'a,
+        {
+            use futures::{StreamExt, TryStreamExt};
+
+            sqlx::query(sql).fetch(self).map_err(Error::from).boxed()
         }
 
         async fn fetch_one(self, sql: &str) -> Result<Self::Row, Error> {
-            match sqlx::query(sql).fetch_one(self).await {
-                Ok(row) => Ok(row),
-                Err(err) => {
-                    if matches!(err, sqlx::error::Error::PoolTimedOut) {
-                        super::GlobalPool::connect_all().await;
-                    }
-                    Err(err.into())
-                }
+            if let Some(mut transaction) = $begin(&self).await? {
+                let data = Executor::fetch_one(&mut *transaction, sql).await?;
+                transaction.commit().await?;
+                return Ok(data);
             }
+            $read(sql, move || sqlx::query(sql).fetch_one(self)).await
         }
 
         async fn fetch_optional(self, sql: &str) -> Result<Option<Self::Row>, Error> {
-            match sqlx::query(sql).fetch_optional(self).await {
-                Ok(row) => Ok(row),
-                Err(err) => {
-                    if matches!(err, sqlx::error::Error::PoolTimedOut) {
-                        super::GlobalPool::connect_all().await;
-                    }
-                    Err(err.into())
-                }
+            if let Some(mut transaction) = $begin(&self).await? {
+                let data = Executor::fetch_optional(&mut *transaction, sql).await?;
+                transaction.commit().await?;
+                return Ok(data);
             }
+            $read(sql, move || sqlx::query(sql).fetch_optional(self)).await
         }
 
         async fn fetch_optional_with<T: ToString>(
@@ -168,29 +215,293 @@ macro_rules! impl_sqlx_executor {
             sql: &str,
             arguments: &[T],
         ) -> Result<Option<Self::Row>, Error> {
-            let mut query = sqlx::query(sql);
-            for arg in arguments {
-                query = query.bind(arg.to_string());
+            if let Some(mut transaction) = $begin(&self).await? {
+                let data = Executor::fetch_optional_with(&mut *transaction, sql, arguments).await?;
+                transaction.commit().await?;
+                return Ok(data);
             }
-            match query.fetch_optional(self).await {
-                Ok(row) => Ok(row),
-                Err(err) => {
-                    if matches!(err, sqlx::error::Error::PoolTimedOut) {
-                        super::GlobalPool::connect_all().await;
-                    }
-                    Err(err.into())
+            $read(sql, move || {
+                let mut query = sqlx::query(sql);
+                for arg in arguments {
+                    query = query.bind(arg.to_string());
                 }
-            }
+                query.fetch_optional(self)
+            })
+            .await
         }
     };
 }
 
 #[cfg(feature = "orm-sqlx")]
 impl<'c> Executor for &'c sqlx::Pool<super::DatabaseDriver> {
-    impl_sqlx_executor!();
+    impl_sqlx_executor!(read_with_retry, super::context::begin_scoped_transaction);
 }
 
 #[cfg(feature = "orm-sqlx")]
 impl<'c> Executor for &'c mut super::DatabaseConnection {
-    impl_sqlx_executor!();
+    impl_sqlx_executor!(read_once, begin_no_transaction);
+}
+
+/// Returns `None` since the statements executed on a connection are never wrapped
+/// in a new transaction.
+#[cfg(feature = "orm-sqlx")]
+async fn begin_no_transaction<T>(
+    _conn: &T,
+) -> Result<Option<sqlx::Transaction<'static, super::DatabaseDriver>>, sqlx::Error> {
+    Ok(None)
+}
+
+/// A type for the query of the database driver.
+#[cfg(feature = "orm-sqlx")]
+type DatabaseQuery<'q> = sqlx::query::Query<
+    'q,
+    super::DatabaseDriver,
+    <super::DatabaseDriver as sqlx::database::HasArguments<'q>>::Arguments,
+>;
+
+/// Binds the JSON values as typed arguments of the query.
+/// The arrays and objects are bound as JSON values.
+#[cfg(feature = "orm-sqlx")]
+fn bind_values<'q>(mut query: DatabaseQuery<'q>, values: &'q [&'q JsonValue]) -> DatabaseQuery<'q> {
+    for &value in values {
+        query = match value {
+            JsonValue::Null => query.bind(None::<String>),
+            JsonValue::Bool(b) => query.bind(*b),
+            JsonValue::Number(n) => {
+                if let Some(i) = n.as_i64() {
+                    query.bind(i)
+                } else if let Some(f) = n.as_f64() {
+                    query.bind(f)
+                } else {
+                    query.bind(n.to_string())
+                }
+            }
+            JsonValue::String(s) => query.bind(s.as_str()),
+            _ => query.bind(sqlx::types::Json(value)),
+        };
+    }
+    query
+}
+
+/// Collects the rows of the stream up to the `max-rows` in the `[database]` table.
+#[cfg(feature = "orm-sqlx")]
+async fn collect_rows<T>(
+    stream: impl futures::Stream<Item = Result<T, sqlx::Error>>,
+) -> Result<Vec<T>, sqlx::Error> {
+    use futures::StreamExt;
+    use std::sync::atomic::Ordering::Relaxed;
+
+    let mut stream = pin!(stream);
+    let max_rows = super::MAX_ROWS.load(Relaxed);
+    let mut rows = Vec::with_capacity(stream.size_hint().0.min(max_rows));
+    while let Some(result) = stream.next().await {
+        match result {
+            Ok(row) if rows.len() < max_rows => rows.push(row),
+            Ok(_) => {
+                tracing::warn!(max_rows, "query results are truncated by the `max-rows`");
+                break;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(rows)
+}
+
+/// Runs the query once. It is used for a connection which can not be reused after failures.
+#[cfg(feature = "orm-sqlx")]
+async fn read_once<T, F, Fut>(_sql: &str, query: F) -> Result<T, Error>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T, sqlx::Error>>,
+{
+    match query().await {
+        Ok(data) => Ok(data),
+        Err(err) => {
+            if matches!(err, sqlx::error::Error::PoolTimedOut) {
+                super::GlobalPool::connect_all().await;
+            }
+            Err(err.into())
+        }
+    }
+}
+
+/// Runs the query and retries it on the transient errors if the statement is read-only.
+/// The retries are disabled unless `read-retries` is configured in the `[database]` table,
+/// and the interval is doubled for each retry with a random jitter.
+#[cfg(feature = "orm-sqlx")]
+async fn read_with_retry<T, F, Fut>(sql: &str, mut query: F) -> Result<T, Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, sqlx::Error>>,
+{
+    use rand::{thread_rng, Rng};
+    use std::sync::atomic::Ordering::Relaxed;
+
+    let max_retries = if is_read_only(sql) {
+        super::READ_RETRIES.load(Relaxed)
+    } else {
+        0
+    };
+    let mut retries = 0;
+    loop {
+        match query().await {
+            Ok(data) => return Ok(data),
+            Err(err) => {
+                if matches!(err, sqlx::error::Error::PoolTimedOut) {
+                    super::GlobalPool::connect_all().await;
+                }
+                if retries >= max_retries || !is_transient_error(&err) {
+                    return Err(err.into());
+                }
+                retries += 1;
+
+                // Exponential backoff with a random jitter.
+                let interval_millis = super::READ_RETRY_INTERVAL
+                    .get()
+                    .and_then(|interval| u64::try_from(interval.as_millis()).ok())
+                    .unwrap_or(100);
+                let base_millis = interval_millis << (retries - 1).min(10);
+                let millis = base_millis + thread_rng().gen_range(0..=base_millis);
+                tracing::warn!(
+                    retries,
+                    "retry the read-only statement after {millis}ms: {err}"
+                );
+
+                // Emit metrics.
+                #[cfg(feature = "metrics")]
+                metrics::counter!("zino_query_retries_total").increment(1);
+
+                Delay::new(Duration::from_millis(millis)).await;
+            }
+        }
+    }
+}
+
+/// Returns `true` if the SQL statement is read-only and can be safely retried.
+/// The statements like `INSERT ... RETURNING`, `SELECT ... FOR UPDATE`,
+/// data-modifying CTEs and the calls of volatile functions such as `nextval()` are excluded
+/// since they are not idempotent. The check is conservative: keywords appearing
+/// in string literals or quoted identifiers also disable the retries.
+fn is_read_only(sql: &str) -> bool {
+    let mut keywords = sql
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|s| !s.is_empty());
+    let is_query = keywords.next().is_some_and(|keyword| {
+        keyword.eq_ignore_ascii_case("SELECT") || keyword.eq_ignore_ascii_case("WITH")
+    });
+    is_query
+        && !keywords.any(|keyword| {
+            let keyword = keyword.to_ascii_uppercase();
+            matches!(
+                keyword.as_str(),
+                "INSERT"
+                    | "UPDATE"
+                    | "DELETE"
+                    | "MERGE"
+                    | "INTO"
+                    | "FOR"
+                    | "LOCK"
+                    | "NEXTVAL"
+                    | "SETVAL"
+                    | "GET_LOCK"
+                    | "RELEASE_LOCK"
+                    | "SLEEP"
+                    | "PG_SLEEP"
+            ) || keyword.starts_with("PG_ADVISORY")
+                || keyword.starts_with("PG_TRY_ADVISORY")
+        })
+}
+
+/// Returns `true` if the error is transient, such as a connection reset or a failover in progress.
+#[cfg(feature = "orm-sqlx")]
+fn is_transient_error(err: &sqlx::Error) -> bool {
+    match err {
+        sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut => true,
+        sqlx::Error::Database(err) => err.code().is_some_and(|code| {
+            // SQLSTATE class `08` is for the connection exceptions,
+            // and `57P01`, `57P02`, `57P03` are for the server shutdown or startup.
+            code.starts_with("08") || matches!(code.as_ref(), "57P01" | "57P02" | "57P03")
+        }),
+        _ => false,
+    }
+}
+
+/// Awaits the query with a timeout. If the timeout is not specified,
+/// the `query-timeout` in the `[database]` table will be used.
+///
+/// The query is cancelled by dropping the future if it takes too long,
+/// and an error satisfying [`Error::is_timeout()`] is returned.
+/// It never exceeds the remaining time of the handler deadline
+/// set by [`with_deadline()`](crate::request::with_deadline).
+/// The default timeout is also set as the session `statement_timeout` for PostgreSQL
+/// and `max_execution_time` for MySQL when a connection is established.
+pub(super) async fn with_timeout<T>(
+    timeout: Option<Duration>,
+    query: impl Future<Output = Result<T, Error>>,
+) -> Result<T, Error> {
+    let timeout = timeout.or_else(|| super::QUERY_TIMEOUT.get().copied());
+    let timeout = match (timeout, request::remaining_time()) {
+        (Some(timeout), Some(remaining_time)) => Some(timeout.min(remaining_time)),
+        (timeout, remaining_time) => timeout.or(remaining_time),
+    };
+    let Some(timeout) = timeout else {
+        return query.await;
+    };
+    match future::select(pin!(query), Delay::new(timeout)).await {
+        Either::Left((result, _)) => result,
+        Either::Right(_) => {
+            let millis = timeout.as_millis();
+            tracing::warn!("query is cancelled after {millis}ms");
+
+            // Emit metrics.
+            #[cfg(feature = "metrics")]
+            metrics::counter!("zino_query_timeouts_total").increment(1);
+
+            Err(Error::timeout(format!(
+                "the query exceeded the timeout of {millis}ms"
+            )))
+        }
+    }
+}
+
+/// Awaits the query of the context with a timeout as [`with_timeout()`],
+/// and records the query in the query log if it fails or times out.
+pub(super) async fn run_query<T>(
+    ctx: &QueryContext,
+    timeout: Option<Duration>,
+    query: impl Future<Output = Result<T, Error>>,
+) -> Result<T, Error> {
+    let result = with_timeout(timeout, query).await;
+    if result.is_err() {
+        super::QueryLog::record(ctx);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_read_only;
+
+    #[test]
+    fn it_checks_read_only_statements() {
+        assert!(is_read_only("SELECT * FROM users;"));
+        assert!(is_read_only("  select count(*) from users;"));
+        assert!(!is_read_only(
+            "INSERT INTO users (name) VALUES ('a') RETURNING id;"
+        ));
+        assert!(!is_read_only("SELECTED"));
+        assert!(!is_read_only(
+            "SELECT * FROM users WHERE id = 1 FOR UPDATE;"
+        ));
+        assert!(!is_read_only("SELECT * FROM users LOCK IN SHARE MODE;"));
+        assert!(!is_read_only("SELECT nextval('users_id_seq');"));
+        assert!(!is_read_only("SELECT pg_advisory_lock(1);"));
+        assert!(!is_read_only("SELECT * INTO archived_users FROM users;"));
+        assert!(is_read_only(
+            "WITH active AS (SELECT * FROM users WHERE status = 'Active') SELECT * FROM active;"
+        ));
+        assert!(!is_read_only(
+            "WITH deleted AS (DELETE FROM users RETURNING *) SELECT * FROM deleted;"
+        ));
+    }
 }
@@ -0,0 +1,312 @@
+//! Application-defined SQL functions for the SQLite databases.
+//!
+//! This is the only module using `unsafe` code, which is required by the FFI of SQLite.
+
+use super::DatabaseConnection;
+use crate::{error::Error, BoxFuture, JsonValue};
+use libsqlite3_sys::{
+    sqlite3_aggregate_context, sqlite3_context, sqlite3_create_function_v2, sqlite3_result_double,
+    sqlite3_result_error, sqlite3_result_int64, sqlite3_result_null, sqlite3_result_text,
+    sqlite3_user_data, sqlite3_value, sqlite3_value_blob, sqlite3_value_bytes,
+    sqlite3_value_double, sqlite3_value_int64, sqlite3_value_text, sqlite3_value_type, SQLITE_BLOB,
+    SQLITE_DETERMINISTIC, SQLITE_FLOAT, SQLITE_INTEGER, SQLITE_OK, SQLITE_TEXT, SQLITE_TRANSIENT,
+    SQLITE_UTF8,
+};
+use parking_lot::RwLock;
+use std::{
+    ffi::{c_char, c_int, c_void, CString},
+    mem,
+    panic::{self, AssertUnwindSafe},
+    ptr, slice,
+};
+
+/// A scalar function which takes the SQL values as arguments.
+pub type ScalarFunction = fn(&[JsonValue]) -> Result<JsonValue, Error>;
+
+/// An aggregate function which accumulates the SQL values row by row.
+pub trait AggregateFunction: Default + Send + 'static {
+    /// Accumulates the arguments of a row.
+    fn step(&mut self, args: &[JsonValue]) -> Result<(), Error>;
+
+    /// Returns the result of the aggregation.
+    fn finalize(self) -> Result<JsonValue, Error>;
+}
+
+/// Registers a scalar function on every pooled connection at creation time.
+/// The SQL values are converted to JSON values, where blobs become arrays of bytes.
+/// A negative `num_args` means that the function takes any number of arguments.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino_core::{error::Error, orm, JsonValue};
+///
+/// fn reverse(args: &[JsonValue]) -> Result<JsonValue, Error> {
+///     let text = args.first().and_then(|v| v.as_str()).unwrap_or_default();
+///     Ok(text.chars().rev().collect::<String>().into())
+/// }
+///
+/// orm::register_scalar_function("reverse", 1, true, reverse);
+/// ```
+pub fn register_scalar_function(
+    name: &str,
+    num_args: i32,
+    deterministic: bool,
+    function: ScalarFunction,
+) {
+    register_function(SqlFunction {
+        name: name.to_owned(),
+        num_args,
+        deterministic,
+        scalar: Some(function),
+        x_func: Some(call_scalar_function),
+        x_step: None,
+        x_final: None,
+    });
+}
+
+/// Registers an aggregate function on every pooled connection at creation time.
+/// A new instance of the aggregator is created for each group of rows.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino_core::{error::Error, orm::{self, AggregateFunction}, JsonValue};
+///
+/// #[derive(Default)]
+/// struct Product(f64);
+///
+/// impl AggregateFunction for Product {
+///     fn step(&mut self, args: &[JsonValue]) -> Result<(), Error> {
+///         self.0 *= args.first().and_then(|v| v.as_f64()).unwrap_or(1.0);
+///         Ok(())
+///     }
+///
+///     fn finalize(self) -> Result<JsonValue, Error> {
+///         Ok(self.0.into())
+///     }
+/// }
+///
+/// orm::register_aggregate_function::<Product>("product", 1, true);
+/// ```
+pub fn register_aggregate_function<A: AggregateFunction>(
+    name: &str,
+    num_args: i32,
+    deterministic: bool,
+) {
+    register_function(SqlFunction {
+        name: name.to_owned(),
+        num_args,
+        deterministic,
+        scalar: None,
+        x_func: None,
+        x_step: Some(step_aggregate_function::<A>),
+        x_final: Some(finalize_aggregate_function::<A>),
+    });
+}
+
+/// Adds the function to the registry and installs the connection initializer once.
+fn register_function(function: SqlFunction) {
+    let mut functions = SQL_FUNCTIONS.write();
+    if functions.is_empty() {
+        super::add_connection_initializer(create_functions);
+    }
+    if let Some(index) = functions.iter().position(|f| f.name == function.name) {
+        tracing::warn!("the SQL function `{}` has been replaced", function.name);
+        functions[index] = function;
+    } else {
+        functions.push(function);
+    }
+}
+
+/// Creates the registered functions for the connection.
+fn create_functions(conn: &mut DatabaseConnection) -> BoxFuture<'_, Result<(), sqlx::Error>> {
+    Box::pin(async move {
+        let mut handle = conn.lock_handle().await?;
+        let db = handle.as_raw_handle().as_ptr();
+        for function in SQL_FUNCTIONS.read().iter() {
+            let name = CString::new(function.name.as_str())
+                .map_err(|err| sqlx::Error::Configuration(err.into()))?;
+            let mut flags = SQLITE_UTF8;
+            if function.deterministic {
+                flags |= SQLITE_DETERMINISTIC;
+            }
+            let (app, destroy) = match function.scalar {
+                Some(scalar) => (
+                    Box::into_raw(Box::new(scalar)).cast::<c_void>(),
+                    Some(drop_scalar_function as unsafe extern "C" fn(*mut c_void)),
+                ),
+                None => (ptr::null_mut(), None),
+            };
+            // The ownership of `app` is transferred to SQLite, which calls `destroy`
+            // even if the function fails to be created.
+            let rc = unsafe {
+                sqlite3_create_function_v2(
+                    db,
+                    name.as_ptr(),
+                    function.num_args,
+                    flags,
+                    app,
+                    function.x_func,
+                    function.x_step,
+                    function.x_final,
+                    destroy,
+                )
+            };
+            if rc != SQLITE_OK {
+                let message = format!("fail to create the SQL function `{}`", function.name);
+                return Err(sqlx::Error::Configuration(message.into()));
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Calls the scalar function stored in the user data.
+unsafe extern "C" fn call_scalar_function(
+    ctx: *mut sqlite3_context,
+    argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    let function = *sqlite3_user_data(ctx).cast::<ScalarFunction>();
+    let args = decode_values(argc, argv);
+    match panic::catch_unwind(AssertUnwindSafe(|| function(&args))) {
+        Ok(Ok(value)) => set_result(ctx, &value),
+        Ok(Err(err)) => set_error(ctx, &err.to_string()),
+        Err(_) => set_error(ctx, "the SQL function panicked"),
+    }
+}
+
+/// Drops the scalar function stored in the user data.
+unsafe extern "C" fn drop_scalar_function(app: *mut c_void) {
+    drop(Box::from_raw(app.cast::<ScalarFunction>()));
+}
+
+/// Accumulates a row for the aggregator stored in the aggregate context.
+unsafe extern "C" fn step_aggregate_function<A: AggregateFunction>(
+    ctx: *mut sqlite3_context,
+    argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    let state = sqlite3_aggregate_context(ctx, mem::size_of::<*mut A>() as c_int).cast::<*mut A>();
+    if state.is_null() {
+        set_error(ctx, "fail to allocate the aggregate context");
+        return;
+    }
+    if (*state).is_null() {
+        *state = Box::into_raw(Box::<A>::default());
+    }
+    let aggregator = &mut **state;
+    let args = decode_values(argc, argv);
+    match panic::catch_unwind(AssertUnwindSafe(|| aggregator.step(&args))) {
+        Ok(Ok(())) => (),
+        Ok(Err(err)) => set_error(ctx, &err.to_string()),
+        Err(_) => set_error(ctx, "the SQL function panicked"),
+    }
+}
+
+/// Finalizes the aggregator stored in the aggregate context.
+unsafe extern "C" fn finalize_aggregate_function<A: AggregateFunction>(ctx: *mut sqlite3_context) {
+    // The aggregate context is not allocated if there are no rows.
+    let state = sqlite3_aggregate_context(ctx, 0).cast::<*mut A>();
+    let aggregator = if state.is_null() || (*state).is_null() {
+        A::default()
+    } else {
+        *Box::from_raw(mem::replace(&mut *state, ptr::null_mut()))
+    };
+    match panic::catch_unwind(AssertUnwindSafe(|| aggregator.finalize())) {
+        Ok(Ok(value)) => set_result(ctx, &value),
+        Ok(Err(err)) => set_error(ctx, &err.to_string()),
+        Err(_) => set_error(ctx, "the SQL function panicked"),
+    }
+}
+
+/// Decodes the SQL values as JSON values.
+unsafe fn decode_values(argc: c_int, argv: *mut *mut sqlite3_value) -> Vec<JsonValue> {
+    if argc <= 0 || argv.is_null() {
+        return Vec::new();
+    }
+    slice::from_raw_parts(argv, argc as usize)
+        .iter()
+        .map(|&value| match sqlite3_value_type(value) {
+            SQLITE_INTEGER => sqlite3_value_int64(value).into(),
+            SQLITE_FLOAT => sqlite3_value_double(value).into(),
+            SQLITE_TEXT => {
+                let text = sqlite3_value_text(value);
+                let len = sqlite3_value_bytes(value) as usize;
+                if text.is_null() {
+                    JsonValue::Null
+                } else {
+                    String::from_utf8_lossy(slice::from_raw_parts(text, len)).into()
+                }
+            }
+            SQLITE_BLOB => {
+                let blob = sqlite3_value_blob(value).cast::<u8>();
+                let len = sqlite3_value_bytes(value) as usize;
+                if blob.is_null() {
+                    Vec::<u8>::new().into()
+                } else {
+                    slice::from_raw_parts(blob, len).to_vec().into()
+                }
+            }
+            _ => JsonValue::Null,
+        })
+        .collect()
+}
+
+/// Sets the JSON value as the result of the function.
+unsafe fn set_result(ctx: *mut sqlite3_context, value: &JsonValue) {
+    match value {
+        JsonValue::Null => sqlite3_result_null(ctx),
+        JsonValue::Bool(b) => sqlite3_result_int64(ctx, i64::from(*b)),
+        JsonValue::Number(n) => {
+            if let Some(n) = n.as_i64() {
+                sqlite3_result_int64(ctx, n);
+            } else {
+                sqlite3_result_double(ctx, n.as_f64().unwrap_or_default());
+            }
+        }
+        JsonValue::String(s) => set_text(ctx, s),
+        _ => set_text(ctx, &value.to_string()),
+    }
+}
+
+/// Sets the text as the result of the function.
+unsafe fn set_text(ctx: *mut sqlite3_context, text: &str) {
+    sqlite3_result_text(
+        ctx,
+        text.as_ptr().cast::<c_char>(),
+        text.len() as c_int,
+        SQLITE_TRANSIENT(),
+    );
+}
+
+/// Sets the error message of the function.
+unsafe fn set_error(ctx: *mut sqlite3_context, message: &str) {
+    sqlite3_result_error(
+        ctx,
+        message.as_ptr().cast::<c_char>(),
+        message.len() as c_int,
+    );
+}
+
+/// Definition of an application-defined SQL function.
+struct SqlFunction {
+    /// Function name.
+    name: String,
+    /// Number of arguments.
+    num_args: c_int,
+    /// A flag to indicate whether the function is deterministic.
+    deterministic: bool,
+    /// Scalar function stored in the user data.
+    scalar: Option<ScalarFunction>,
+    /// Callback for the scalar function.
+    x_func: Option<unsafe extern "C" fn(*mut sqlite3_context, c_int, *mut *mut sqlite3_value)>,
+    /// Callback for each row of the aggregate function.
+    x_step: Option<unsafe extern "C" fn(*mut sqlite3_context, c_int, *mut *mut sqlite3_value)>,
+    /// Callback for the result of the aggregate function.
+    x_final: Option<unsafe extern "C" fn(*mut sqlite3_context)>,
+}
+
+/// Application-defined SQL functions.
+static SQL_FUNCTIONS: RwLock<Vec<SqlFunction>> = RwLock::new(Vec::new());
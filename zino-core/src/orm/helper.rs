@@ -52,6 +52,21 @@ where
         }
     }
 
+    /// Returns `true` if the encrypted password should be rehashed with the current
+    /// `Argon2id` parameters configured by the `[password]` table.
+    fn password_needs_rehash(encrypted_password: &str) -> Result<bool, Error> {
+        let key = Self::secret_key();
+        crypto::password_needs_rehash(encrypted_password.as_bytes(), key)
+            .map_err(|err| warn!("fail to parse encrypted password: {}", err.message()))
+    }
+
+    /// Performs a dummy password verification, which should be used when the account
+    /// does not exist so that it can not be inferred from the response time.
+    #[inline]
+    fn verify_dummy_password(password: &str) {
+        crypto::verify_dummy_password(password.as_bytes());
+    }
+
     /// Translates the model data.
     fn translate_model(model: &mut Map) {
         #[cfg(feature = "openapi")]
@@ -0,0 +1,563 @@
+//! Database introspection for tables, columns, indexes and constraints.
+//!
+//! # Examples
+//!
+//! ```rust,ignore
+//! use zino_core::orm::{inspect, GlobalPool};
+//!
+//! let connection_pool = GlobalPool::get("main").unwrap();
+//! for table in inspect::list_tables(connection_pool).await? {
+//!     let columns = inspect::list_columns(connection_pool, table.name()).await?;
+//!     let indexes = inspect::list_indexes(connection_pool, table.name()).await?;
+//!     let constraints = inspect::list_constraints(connection_pool, table.name()).await?;
+//! }
+//! ```
+
+use super::{ConnectionPool, Executor};
+use crate::{error::Error, extension::JsonObjectExt, model::DecodeRow, Map};
+use serde::Serialize;
+use std::fmt;
+
+/// Information of a table.
+#[derive(Debug, Clone, Serialize)]
+pub struct TableInfo {
+    /// Table name.
+    name: String,
+    /// Table comment.
+    comment: Option<String>,
+}
+
+impl TableInfo {
+    /// Returns the table name.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the table comment.
+    #[inline]
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+}
+
+/// Information of a column.
+#[derive(Debug, Clone, Serialize)]
+pub struct ColumnInfo {
+    /// Column name.
+    name: String,
+    /// Data type reported by the database.
+    data_type: String,
+    /// A flag to indicate whether the column is nullable.
+    nullable: bool,
+    /// Default expression.
+    default_value: Option<String>,
+}
+
+impl ColumnInfo {
+    /// Returns the column name.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the data type reported by the database.
+    #[inline]
+    pub fn data_type(&self) -> &str {
+        &self.data_type
+    }
+
+    /// Returns `true` if the column is nullable.
+    #[inline]
+    pub fn is_nullable(&self) -> bool {
+        self.nullable
+    }
+
+    /// Returns the default expression.
+    #[inline]
+    pub fn default_value(&self) -> Option<&str> {
+        self.default_value.as_deref()
+    }
+}
+
+/// Information of an index.
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexInfo {
+    /// Index name.
+    name: String,
+    /// Indexed columns in order.
+    columns: Vec<String>,
+    /// A flag to indicate whether the index is unique.
+    unique: bool,
+}
+
+impl IndexInfo {
+    /// Returns the index name.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the indexed columns.
+    #[inline]
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Returns `true` if the index is unique.
+    #[inline]
+    pub fn is_unique(&self) -> bool {
+        self.unique
+    }
+}
+
+/// Information of a constraint.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConstraintInfo {
+    /// Constraint name.
+    name: String,
+    /// Constraint type: `PRIMARY KEY` | `UNIQUE` | `FOREIGN KEY` | `CHECK`.
+    constraint_type: String,
+    /// Constrained columns in order.
+    columns: Vec<String>,
+}
+
+impl ConstraintInfo {
+    /// Returns the constraint name.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the constraint type.
+    #[inline]
+    pub fn constraint_type(&self) -> &str {
+        &self.constraint_type
+    }
+
+    /// Returns the constrained columns.
+    #[inline]
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+}
+
+/// A column whose data type is inconsistent with the model definition.
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeMismatch {
+    /// Column name.
+    column: String,
+    /// Column type of the model.
+    expected: String,
+    /// Data type reported by the database.
+    actual: String,
+}
+
+impl TypeMismatch {
+    /// Creates a new instance.
+    #[inline]
+    pub(super) fn new(column: String, expected: String, actual: String) -> Self {
+        Self {
+            column,
+            expected,
+            actual,
+        }
+    }
+
+    /// Returns the column name.
+    #[inline]
+    pub fn column(&self) -> &str {
+        &self.column
+    }
+
+    /// Returns the column type of the model.
+    #[inline]
+    pub fn expected(&self) -> &str {
+        &self.expected
+    }
+
+    /// Returns the data type reported by the database.
+    #[inline]
+    pub fn actual(&self) -> &str {
+        &self.actual
+    }
+}
+
+/// A report of the schema drift between a model and the live table.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SchemaDiff {
+    /// Table name.
+    table_name: String,
+    /// Columns defined by the model but missing in the table.
+    missing_columns: Vec<String>,
+    /// Columns in the table but not defined by the model.
+    extra_columns: Vec<String>,
+    /// Columns with inconsistent data types.
+    type_mismatches: Vec<TypeMismatch>,
+    /// Indexes defined by the model but missing in the table.
+    missing_indexes: Vec<String>,
+    /// Indexes in the table but not defined by the model.
+    extra_indexes: Vec<String>,
+}
+
+impl SchemaDiff {
+    /// Creates a new instance for the table.
+    #[inline]
+    pub(super) fn new(table_name: impl Into<String>) -> Self {
+        Self {
+            table_name: table_name.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Adds a missing column.
+    #[inline]
+    pub(super) fn add_missing_column(&mut self, column: impl Into<String>) {
+        self.missing_columns.push(column.into());
+    }
+
+    /// Adds an extra column.
+    #[inline]
+    pub(super) fn add_extra_column(&mut self, column: impl Into<String>) {
+        self.extra_columns.push(column.into());
+    }
+
+    /// Adds a type mismatch.
+    #[inline]
+    pub(super) fn add_type_mismatch(&mut self, mismatch: TypeMismatch) {
+        self.type_mismatches.push(mismatch);
+    }
+
+    /// Adds a missing index.
+    #[inline]
+    pub(super) fn add_missing_index(&mut self, index: impl Into<String>) {
+        self.missing_indexes.push(index.into());
+    }
+
+    /// Adds an extra index.
+    #[inline]
+    pub(super) fn add_extra_index(&mut self, index: impl Into<String>) {
+        self.extra_indexes.push(index.into());
+    }
+
+    /// Returns the table name.
+    #[inline]
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
+    /// Returns the columns defined by the model but missing in the table.
+    #[inline]
+    pub fn missing_columns(&self) -> &[String] {
+        &self.missing_columns
+    }
+
+    /// Returns the columns in the table but not defined by the model.
+    #[inline]
+    pub fn extra_columns(&self) -> &[String] {
+        &self.extra_columns
+    }
+
+    /// Returns the columns with inconsistent data types.
+    #[inline]
+    pub fn type_mismatches(&self) -> &[TypeMismatch] {
+        &self.type_mismatches
+    }
+
+    /// Returns the indexes defined by the model but missing in the table.
+    #[inline]
+    pub fn missing_indexes(&self) -> &[String] {
+        &self.missing_indexes
+    }
+
+    /// Returns the indexes in the table but not defined by the model.
+    #[inline]
+    pub fn extra_indexes(&self) -> &[String] {
+        &self.extra_indexes
+    }
+
+    /// Returns `true` if there is no drift.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.missing_columns.is_empty()
+            && self.extra_columns.is_empty()
+            && self.type_mismatches.is_empty()
+            && self.missing_indexes.is_empty()
+            && self.extra_indexes.is_empty()
+    }
+}
+
+impl fmt::Display for SchemaDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut entries = Vec::new();
+        if !self.missing_columns.is_empty() {
+            entries.push(format!(
+                "missing columns: {}",
+                self.missing_columns.join(", ")
+            ));
+        }
+        if !self.extra_columns.is_empty() {
+            entries.push(format!("extra columns: {}", self.extra_columns.join(", ")));
+        }
+        for TypeMismatch {
+            column,
+            expected,
+            actual,
+        } in &self.type_mismatches
+        {
+            entries.push(format!(
+                "`{column}` should be `{expected}` instead of `{actual}`"
+            ));
+        }
+        if !self.missing_indexes.is_empty() {
+            entries.push(format!(
+                "missing indexes: {}",
+                self.missing_indexes.join(", ")
+            ));
+        }
+        if !self.extra_indexes.is_empty() {
+            entries.push(format!("extra indexes: {}", self.extra_indexes.join(", ")));
+        }
+        write!(
+            f,
+            "schema drift of `{}`: {}",
+            self.table_name,
+            entries.join("; ")
+        )
+    }
+}
+
+/// Lists the tables in the database of the connection pool.
+pub async fn list_tables(connection_pool: &ConnectionPool) -> Result<Vec<TableInfo>, Error> {
+    let sql = if is_mysql() {
+        let table_schema = escape_literal(connection_pool.database());
+        format!(
+            "SELECT table_name AS table_name, table_comment AS table_comment \
+                FROM information_schema.tables \
+                    WHERE table_schema = '{table_schema}' AND table_type = 'BASE TABLE' \
+                        ORDER BY table_name;"
+        )
+    } else if cfg!(feature = "orm-postgres") {
+        "SELECT c.relname::text AS table_name, obj_description(c.oid, 'pg_class') AS table_comment \
+            FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace \
+                WHERE n.nspname = 'public' AND c.relkind IN ('r', 'p') AND NOT c.relispartition \
+                    ORDER BY c.relname;"
+            .to_owned()
+    } else {
+        "SELECT name AS table_name, NULL AS table_comment FROM sqlite_master \
+            WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name;"
+            .to_owned()
+    };
+    let rows = fetch_maps(connection_pool, &sql).await?;
+    let tables = rows
+        .iter()
+        .filter_map(|map| {
+            let name = map.get_str("table_name")?.to_owned();
+            let comment = map
+                .get_str("table_comment")
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_owned());
+            Some(TableInfo { name, comment })
+        })
+        .collect();
+    Ok(tables)
+}
+
+/// Lists the columns of a table in the ordinal position.
+pub async fn list_columns(
+    connection_pool: &ConnectionPool,
+    table_name: &str,
+) -> Result<Vec<ColumnInfo>, Error> {
+    let table_name = escape_literal(table_name);
+    let sql = if is_mysql() {
+        let table_schema = escape_literal(connection_pool.database());
+        format!(
+            "SELECT column_name AS column_name, data_type AS data_type, \
+                    is_nullable AS is_nullable, column_default AS column_default \
+                FROM information_schema.columns \
+                    WHERE table_schema = '{table_schema}' AND table_name = '{table_name}' \
+                        ORDER BY ordinal_position;"
+        )
+    } else if cfg!(feature = "orm-postgres") {
+        format!(
+            "SELECT column_name::text AS column_name, data_type::text AS data_type, \
+                    is_nullable::text AS is_nullable, column_default::text AS column_default \
+                FROM information_schema.columns \
+                    WHERE table_schema = 'public' AND table_name = '{table_name}' \
+                        ORDER BY ordinal_position;"
+        )
+    } else {
+        format!(
+            "SELECT name AS column_name, type AS data_type, \
+                    CASE WHEN [notnull] = 1 THEN 'NO' ELSE 'YES' END AS is_nullable, \
+                    dflt_value AS column_default \
+                FROM pragma_table_info('{table_name}') ORDER BY cid;"
+        )
+    };
+    let rows = fetch_maps(connection_pool, &sql).await?;
+    let columns = rows
+        .iter()
+        .filter_map(|map| {
+            let name = map.get_str("column_name")?.to_owned();
+            let data_type = map.get_str("data_type").unwrap_or_default().to_owned();
+            let nullable = !map
+                .get_str("is_nullable")
+                .is_some_and(|s| s.eq_ignore_ascii_case("NO"));
+            let default_value = map.get_str("column_default").map(|s| s.to_owned());
+            Some(ColumnInfo {
+                name,
+                data_type,
+                nullable,
+                default_value,
+            })
+        })
+        .collect();
+    Ok(columns)
+}
+
+/// Lists the indexes of a table.
+pub async fn list_indexes(
+    connection_pool: &ConnectionPool,
+    table_name: &str,
+) -> Result<Vec<IndexInfo>, Error> {
+    let table_name = escape_literal(table_name);
+    let sql = if is_mysql() {
+        let table_schema = escape_literal(connection_pool.database());
+        format!(
+            "SELECT index_name AS index_name, column_name AS column_name, \
+                    CASE WHEN non_unique = 0 THEN 'YES' ELSE 'NO' END AS is_unique \
+                FROM information_schema.statistics \
+                    WHERE table_schema = '{table_schema}' AND table_name = '{table_name}' \
+                        ORDER BY index_name, seq_in_index;"
+        )
+    } else if cfg!(feature = "orm-postgres") {
+        format!(
+            "SELECT i.relname::text AS index_name, a.attname::text AS column_name, \
+                    CASE WHEN x.indisunique THEN 'YES' ELSE 'NO' END AS is_unique \
+                FROM pg_index x \
+                    JOIN pg_class t ON t.oid = x.indrelid \
+                    JOIN pg_class i ON i.oid = x.indexrelid \
+                    JOIN pg_namespace n ON n.oid = t.relnamespace \
+                    JOIN pg_attribute a ON a.attrelid = t.oid AND a.attnum = ANY(x.indkey) \
+                WHERE n.nspname = 'public' AND t.relname = '{table_name}' \
+                    ORDER BY i.relname, array_position(x.indkey::int2[], a.attnum);"
+        )
+    } else {
+        format!(
+            "SELECT l.name AS index_name, i.name AS column_name, \
+                    CASE WHEN l.[unique] = 1 THEN 'YES' ELSE 'NO' END AS is_unique \
+                FROM pragma_index_list('{table_name}') l JOIN pragma_index_info(l.name) i \
+                    ORDER BY l.name, i.seqno;"
+        )
+    };
+    let rows = fetch_maps(connection_pool, &sql).await?;
+    let mut indexes = Vec::<IndexInfo>::new();
+    for map in rows.iter() {
+        let (Some(name), Some(column)) = (map.get_str("index_name"), map.get_str("column_name"))
+        else {
+            continue;
+        };
+        if let Some(index) = indexes.iter_mut().find(|index| index.name == name) {
+            index.columns.push(column.to_owned());
+        } else {
+            indexes.push(IndexInfo {
+                name: name.to_owned(),
+                columns: vec![column.to_owned()],
+                unique: map.get_str("is_unique") == Some("YES"),
+            });
+        }
+    }
+    Ok(indexes)
+}
+
+/// Lists the constraints of a table.
+///
+/// SQLite only reports the primary key and foreign keys.
+pub async fn list_constraints(
+    connection_pool: &ConnectionPool,
+    table_name: &str,
+) -> Result<Vec<ConstraintInfo>, Error> {
+    let table_name = escape_literal(table_name);
+    let sql = if cfg!(any(
+        feature = "orm-mariadb",
+        feature = "orm-mysql",
+        feature = "orm-postgres",
+        feature = "orm-tidb"
+    )) {
+        let table_schema = if is_mysql() {
+            escape_literal(connection_pool.database())
+        } else {
+            "public".to_owned()
+        };
+        format!(
+            "SELECT c.constraint_name AS constraint_name, \
+                    c.constraint_type AS constraint_type, k.column_name AS column_name \
+                FROM information_schema.table_constraints c \
+                    LEFT JOIN information_schema.key_column_usage k \
+                        ON k.constraint_schema = c.constraint_schema \
+                            AND k.constraint_name = c.constraint_name \
+                            AND k.table_name = c.table_name \
+                WHERE c.table_schema = '{table_schema}' AND c.table_name = '{table_name}' \
+                    ORDER BY c.constraint_name, k.ordinal_position;"
+        )
+    } else {
+        format!(
+            "SELECT 'primary_key' AS constraint_name, 'PRIMARY KEY' AS constraint_type, \
+                    name AS column_name \
+                FROM pragma_table_info('{table_name}') WHERE pk > 0 \
+            UNION ALL \
+            SELECT 'foreign_key_' || id AS constraint_name, 'FOREIGN KEY' AS constraint_type, \
+                    \"from\" AS column_name \
+                FROM pragma_foreign_key_list('{table_name}');"
+        )
+    };
+    let rows = fetch_maps(connection_pool, &sql).await?;
+    let mut constraints = Vec::<ConstraintInfo>::new();
+    for map in rows.iter() {
+        let Some(name) = map.get_str("constraint_name") else {
+            continue;
+        };
+        let column = map.get_str("column_name");
+        if let Some(constraint) = constraints.iter_mut().find(|c| c.name == name) {
+            if let Some(column) = column {
+                constraint.columns.push(column.to_owned());
+            }
+        } else {
+            constraints.push(ConstraintInfo {
+                name: name.to_owned(),
+                constraint_type: map
+                    .get_str("constraint_type")
+                    .unwrap_or_default()
+                    .to_owned(),
+                columns: column.map(|s| s.to_owned()).into_iter().collect(),
+            });
+        }
+    }
+    Ok(constraints)
+}
+
+/// Fetches the rows as maps.
+async fn fetch_maps(connection_pool: &ConnectionPool, sql: &str) -> Result<Vec<Map>, Error> {
+    let rows = connection_pool.pool().fetch(sql).await?;
+    let mut data = Vec::with_capacity(rows.len());
+    for row in rows {
+        data.push(Map::decode_row(&row)?);
+    }
+    Ok(data)
+}
+
+/// Returns `true` if the MySQL driver is used.
+#[inline]
+fn is_mysql() -> bool {
+    cfg!(any(
+        feature = "orm-mariadb",
+        feature = "orm-mysql",
+        feature = "orm-tidb"
+    ))
+}
+
+/// Escapes the value so that it can be used in a string literal.
+#[inline]
+fn escape_literal(value: &str) -> String {
+    value.replace('\'', "''")
+}
@@ -1,4 +1,7 @@
-use super::{pool::ConnectionPool, DatabasePool};
+use super::{
+    pool::{ConnectionPool, FailoverPolicy},
+    DatabasePool,
+};
 use crate::extension::TomlTableExt;
 use std::time::Duration;
 use toml::value::Table;
@@ -11,6 +14,16 @@ pub trait PoolManager {
     /// Checks the availability of the connection pool.
     async fn check_availability(&self) -> bool;
 
+    /// Establishes the minimum number of connections in advance,
+    /// and returns the number of connections established.
+    async fn warm_up(&self) -> usize;
+
+    /// Closes all the connections opened before, so that the connections
+    /// will be reopened with the current connect options.
+    /// It returns the number of idle connections closed,
+    /// and the connections in use are closed when they are released.
+    async fn reconnect(&self) -> usize;
+
     /// Shuts down the connection pool.
     async fn close(&self);
 }
@@ -31,6 +44,22 @@ impl PoolManager for ConnectionPool<DatabasePool> {
             connect_options = connect_options.statement_cache_capacity(statement_cache_capacity);
         }
 
+        // Endpoints for the failover.
+        let endpoints = config.get_str_array("hosts").unwrap_or_default();
+        let failover_policy = config
+            .get_str("failover-policy")
+            .and_then(FailoverPolicy::parse)
+            .unwrap_or_default();
+        #[cfg(any(
+            feature = "orm-mariadb",
+            feature = "orm-mysql",
+            feature = "orm-postgres",
+            feature = "orm-tidb"
+        ))]
+        if let Some(endpoint) = endpoints.first() {
+            connect_options = apply_endpoint(connect_options, endpoint);
+        }
+
         // Pool options.
         let max_connections = config.get_u32("max-connections").unwrap_or(16);
         let min_connections = config.get_u32("min-connections").unwrap_or(1);
@@ -53,6 +82,9 @@ impl PoolManager for ConnectionPool<DatabasePool> {
             .test_before_acquire(false)
             .before_acquire(move |conn, meta| {
                 Box::pin(async move {
+                    if super::GlobalPool::get(name).is_some_and(|cp| cp.is_outdated(meta.age)) {
+                        return Ok(false);
+                    }
                     if meta.idle_for.as_secs() > health_check_interval {
                         if let Some(cp) = super::GlobalPool::get(name) {
                             if let Err(err) = conn.ping().await {
@@ -70,7 +102,15 @@ impl PoolManager for ConnectionPool<DatabasePool> {
                     Ok(true)
                 })
             })
+            .after_release(move |_conn, meta| {
+                Box::pin(async move {
+                    let outdated =
+                        super::GlobalPool::get(name).is_some_and(|cp| cp.is_outdated(meta.age));
+                    Ok(!outdated)
+                })
+            })
             .after_connect(|conn, _meta| {
+                let initializers = super::CONNECTION_INITIALIZERS.read().clone();
                 Box::pin(async move {
                     if let Some(time_zone) = super::TIME_ZONE.get() {
                         if cfg!(any(
@@ -85,17 +125,49 @@ impl PoolManager for ConnectionPool<DatabasePool> {
                             conn.execute(sql.as_str()).await?;
                         }
                     }
+                    if let Some(query_timeout) = super::QUERY_TIMEOUT.get() {
+                        // The server-side timeout makes sure that the statement is aborted
+                        // even if the future is dropped on the client side.
+                        let millis = query_timeout.as_millis();
+                        if cfg!(feature = "orm-mariadb") {
+                            let secs = query_timeout.as_secs_f64();
+                            let sql = format!("SET SESSION max_statement_time = {secs};");
+                            conn.execute(sql.as_str()).await?;
+                        } else if cfg!(any(feature = "orm-mysql", feature = "orm-tidb")) {
+                            let sql = format!("SET SESSION max_execution_time = {millis};");
+                            conn.execute(sql.as_str()).await?;
+                        } else if cfg!(feature = "orm-postgres") {
+                            let sql = format!("SET statement_timeout = {millis};");
+                            conn.execute(sql.as_str()).await?;
+                        }
+                    }
+                    for initializer in initializers {
+                        initializer(conn).await?;
+                    }
                     Ok(())
                 })
             })
             .connect_lazy_with(connect_options);
+        let read_only = config.get_bool("read-only").unwrap_or_default();
         Self::new(name, database, pool)
+            .with_endpoints(endpoints, failover_policy)
+            .with_read_only(read_only)
     }
 
     async fn check_availability(&self) -> bool {
         if let Err(err) = self.pool().acquire().await {
             let name = self.name();
             tracing::error!("fail to acquire a connection for the `{name}` service: {err}");
+            #[cfg(any(
+                feature = "orm-mariadb",
+                feature = "orm-mysql",
+                feature = "orm-postgres",
+                feature = "orm-tidb"
+            ))]
+            if self.failover().await {
+                self.store_availability(true);
+                return true;
+            }
             self.store_availability(false);
             false
         } else {
@@ -104,6 +176,52 @@ impl PoolManager for ConnectionPool<DatabasePool> {
         }
     }
 
+    async fn warm_up(&self) -> usize {
+        let pool = self.pool();
+        let min_connections = usize::try_from(pool.options().get_min_connections()).unwrap_or(1);
+        let num_connections = min_connections.saturating_sub(pool.size().try_into().unwrap_or(0));
+        if num_connections == 0 {
+            return 0;
+        }
+
+        // The connections are acquired concurrently and then released to the pool as idle ones.
+        let results = futures::future::join_all((0..num_connections).map(|_| pool.acquire())).await;
+        let mut num_established = 0;
+        for result in results {
+            match result {
+                Ok(_) => num_established += 1,
+                Err(err) => {
+                    let name = self.name();
+                    tracing::error!(
+                        "fail to warm up the connection pool for the `{name}` service: {err}"
+                    );
+                }
+            }
+        }
+        num_established
+    }
+
+    async fn reconnect(&self) -> usize {
+        use sqlx::Connection;
+
+        self.mark_connections_outdated();
+
+        let pool = self.pool();
+        let mut connections = Vec::new();
+        while let Some(conn) = pool.try_acquire() {
+            connections.push(conn.detach());
+        }
+
+        let num_closed = connections.len();
+        for conn in connections {
+            if let Err(err) = conn.close().await {
+                let name = self.name();
+                tracing::warn!("fail to close a connection for the `{name}` service: {err}");
+            }
+        }
+        num_closed
+    }
+
     async fn close(&self) {
         let name = self.name();
         tracing::warn!("closing the connection pool for the `{name}` service");
@@ -111,6 +229,74 @@ impl PoolManager for ConnectionPool<DatabasePool> {
     }
 }
 
+#[cfg(any(
+    feature = "orm-mariadb",
+    feature = "orm-mysql",
+    feature = "orm-postgres",
+    feature = "orm-tidb"
+))]
+impl ConnectionPool<DatabasePool> {
+    /// Switches to the next available endpoint according to the failover policy,
+    /// and returns `true` if the failover succeeds.
+    async fn failover(&self) -> bool {
+        use sqlx::Connection;
+
+        let endpoints = self.endpoints();
+        let Some(current_endpoint) = self.current_endpoint() else {
+            return false;
+        };
+        let name = self.name();
+        let pool = self.pool();
+        for index in self.failover_candidates() {
+            let endpoint = endpoints[index];
+            let connect_options = apply_endpoint(pool.connect_options().as_ref().clone(), endpoint);
+            match super::DatabaseConnection::connect_with(&connect_options).await {
+                Ok(mut conn) => {
+                    let writable = self.is_read_only() || is_writable(&mut conn).await;
+                    if let Err(err) = conn.close().await {
+                        tracing::warn!("fail to close the connection to `{endpoint}`: {err}");
+                    }
+                    if !writable {
+                        tracing::warn!(
+                            "skip the read-only endpoint `{endpoint}` for the `{name}` service"
+                        );
+                        continue;
+                    }
+                    pool.set_connect_options(connect_options);
+                    self.store_endpoint_index(index);
+
+                    // All the connections to the previous endpoint are closed,
+                    // since it may still be reachable as a demoted replica.
+                    let num_closed = self.reconnect().await;
+                    tracing::warn!(
+                        name,
+                        from = current_endpoint,
+                        to = endpoint,
+                        num_closed,
+                        "the `{name}` service fails over to another endpoint"
+                    );
+
+                    // Emit metrics.
+                    #[cfg(feature = "metrics")]
+                    metrics::counter!(
+                        "zino_database_failovers_total",
+                        "name" => name,
+                        "endpoint" => endpoint,
+                    )
+                    .increment(1);
+                    return true;
+                }
+                Err(err) => {
+                    tracing::error!(
+                        "fail to connect to `{endpoint}` for the `{name}` service: {err}"
+                    );
+                }
+            }
+        }
+        false
+    }
+}
+
 cfg_if::cfg_if! {
     if #[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))] {
         use crate::state::State;
@@ -141,6 +327,25 @@ cfg_if::cfg_if! {
             }
             connect_options
         }
+
+        /// Applies the endpoint in the form of `host[:port]` to the connect options.
+        fn apply_endpoint(connect_options: MySqlConnectOptions, endpoint: &str) -> MySqlConnectOptions {
+            match super::pool::split_endpoint(endpoint) {
+                (host, Some(port)) => connect_options.host(host).port(port),
+                (host, None) => connect_options.host(host),
+            }
+        }
+
+        /// Returns `true` if the server of the connection is writable.
+        async fn is_writable(conn: &mut super::DatabaseConnection) -> bool {
+            match sqlx::query_scalar::<_, i64>("SELECT @@read_only;").fetch_one(conn).await {
+                Ok(read_only) => read_only == 0,
+                Err(err) => {
+                    tracing::warn!("fail to check whether the server is writable: {err}");
+                    false
+                }
+            }
+        }
     } else if #[cfg(feature = "orm-postgres")] {
         use crate::state::State;
         use sqlx::postgres::{PgConnectOptions, PgSslMode};
@@ -170,8 +375,28 @@ cfg_if::cfg_if! {
             }
             connect_options
         }
+
+        /// Applies the endpoint in the form of `host[:port]` to the connect options.
+        fn apply_endpoint(connect_options: PgConnectOptions, endpoint: &str) -> PgConnectOptions {
+            match super::pool::split_endpoint(endpoint) {
+                (host, Some(port)) => connect_options.host(host).port(port),
+                (host, None) => connect_options.host(host),
+            }
+        }
+
+        /// Returns `true` if the server of the connection is writable.
+        async fn is_writable(conn: &mut super::DatabaseConnection) -> bool {
+            let sql = "SELECT pg_is_in_recovery();";
+            match sqlx::query_scalar::<_, bool>(sql).fetch_one(conn).await {
+                Ok(in_recovery) => !in_recovery,
+                Err(err) => {
+                    tracing::warn!("fail to check whether the server is writable: {err}");
+                    false
+                }
+            }
+        }
     } else {
-        use sqlx::sqlite::SqliteConnectOptions;
+        use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqliteSynchronous};
 
         /// Options and flags which can be used to configure a SQLite connection.
         fn new_connect_options(database: &'static str, config: &'static Table) -> SqliteConnectOptions {
@@ -180,6 +405,44 @@ cfg_if::cfg_if! {
                 connect_options = connect_options.read_only(read_only);
             }
 
+            // The WAL mode with the `NORMAL` synchronous level allows the readers
+            // to proceed concurrently with a writer.
+            let journal_mode = config
+                .get_str("journal-mode")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(SqliteJournalMode::Wal);
+            let synchronous = config
+                .get_str("synchronous")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(SqliteSynchronous::Normal);
+            let busy_timeout = config
+                .get_duration("busy-timeout")
+                .unwrap_or_else(|| Duration::from_secs(10));
+            connect_options = connect_options
+                .journal_mode(journal_mode)
+                .synchronous(synchronous)
+                .busy_timeout(busy_timeout);
+            if let Some(mmap_size) = config.get_u64("mmap-size") {
+                connect_options = connect_options.pragma("mmap_size", mmap_size.to_string());
+            }
+            if let Some(wal_autocheckpoint) = config.get_u32("wal-autocheckpoint") {
+                connect_options =
+                    connect_options.pragma("wal_autocheckpoint", wal_autocheckpoint.to_string());
+            }
+            #[cfg(feature = "orm-sqlcipher")]
+            if let Some(passphrase) = crate::state::State::decrypt_password(config) {
+                let key = super::encryption::format_key(passphrase.as_ref());
+                connect_options = connect_options.pragma("key", key);
+            }
+            if config.get_bool("regexp").unwrap_or(true) {
+                connect_options = connect_options.with_regexp();
+            }
+            if let Some(extensions) = config.get_str_array("extensions") {
+                for extension in extensions {
+                    connect_options = connect_options.extension(extension);
+                }
+            }
+
             let database_path = std::path::Path::new(database);
             let database_file = if database_path.is_relative() {
                 crate::application::PROJECT_DIR.join(database_path)
@@ -4,13 +4,14 @@
 //!
 //! The following optional features are available:
 //!
-//! | Feature flag   | Description                                          | Default? |
-//! |----------------|------------------------------------------------------|----------|
-//! | `orm-mariadb`  | Enables the MariaDB database driver.                 | No       |
-//! | `orm-mysql`    | Enables the MySQL database driver.                   | No       |
-//! | `orm-postgres` | Enables the PostgreSQL database driver.              | No       |
-//! | `orm-sqlite`   | Enables the SQLite database driver.                  | No       |
-//! | `orm-tidb`     | Enables the TiDB database driver.                    | No       |
+//! | Feature flag    | Description                                          | Default? |
+//! |-----------------|------------------------------------------------------|----------|
+//! | `orm-mariadb`   | Enables the MariaDB database driver.                 | No       |
+//! | `orm-mysql`     | Enables the MySQL database driver.                   | No       |
+//! | `orm-postgres`  | Enables the PostgreSQL database driver.              | No       |
+//! | `orm-sqlcipher` | Enables the SQLite database driver with SQLCipher.   | No       |
+//! | `orm-sqlite`    | Enables the SQLite database driver.                  | No       |
+//! | `orm-tidb`      | Enables the TiDB database driver.                    | No       |
 //!
 //! # Design references
 //!
@@ -108,41 +109,88 @@
 //! [`TypeORM`]: https://typeorm.io/
 //! [`PostgREST`]: https://postgrest.org/
 
-use crate::{extension::TomlTableExt, state::State, LazyLock};
+use crate::{
+    bail, error::Error, extension::TomlTableExt, schedule::AsyncJob, state::State, LazyLock,
+};
 use smallvec::SmallVec;
-use std::sync::{
-    atomic::{AtomicBool, AtomicUsize, Ordering::Relaxed},
-    OnceLock,
+use std::{
+    future::Future,
+    mem,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering::Relaxed},
+        OnceLock,
+    },
+    time::Duration,
 };
 
+pub mod inspect;
+
 mod accessor;
+mod archive;
+mod codec;
 mod column;
+mod context;
+mod copy;
+mod distributed;
 mod executor;
 mod helper;
 mod manager;
 mod mutation;
+mod naming;
+mod partition;
 mod pool;
 mod query;
+mod query_log;
+mod retention;
 mod schema;
 mod transaction;
+mod view;
 
 pub use accessor::ModelAccessor;
+pub use archive::{ArchiveFormat, ArchiveManifest, ArchiveSink};
+pub use codec::ModelCodec;
+pub use context::{DatabaseContext, ScopedFuture};
+pub use distributed::DistributedTransaction;
 pub use executor::Executor;
 pub use helper::ModelHelper;
 pub use manager::PoolManager;
-pub use pool::ConnectionPool;
+pub use naming::NamingConvention;
+pub use partition::new_partition_job;
+pub use pool::{ConnectionPool, FailoverPolicy};
+pub use query_log::{QueryLog, QueryLogEntry};
+pub use retention::new_retention_job;
 pub use schema::Schema;
-pub use transaction::Transaction;
+pub use transaction::{AccessMode, IsolationLevel, Transaction};
+pub use view::new_refresh_job;
+
+#[cfg(feature = "orm-sqlx")]
+pub use transaction::transaction_with_retry;
 
 #[cfg(feature = "orm-sqlx")]
 mod decode;
 #[cfg(feature = "orm-sqlx")]
+mod position;
+#[cfg(feature = "orm-sqlx")]
 mod scalar;
+#[cfg(feature = "orm-sqlx")]
+mod sync;
+#[cfg(feature = "orm-sqlx")]
+mod tree;
 
 #[cfg(feature = "orm-sqlx")]
-pub use decode::{decode, decode_array, decode_decimal, decode_uuid};
+pub use decode::{
+    decode, decode_array, decode_decimal, decode_duration, decode_enum, decode_money,
+    decode_optional_enum, decode_range, decode_uuid,
+};
+#[cfg(feature = "orm-sqlx")]
+pub use position::PositionQuery;
 #[cfg(feature = "orm-sqlx")]
 pub use scalar::ScalarQuery;
+#[cfg(feature = "orm-sqlx")]
+pub use sync::{DataSync, SyncPlan, SyncReport};
+#[cfg(feature = "orm-sqlx")]
+pub use tree::TreeQuery;
 
 cfg_if::cfg_if! {
     if #[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))] {
@@ -186,8 +234,27 @@ cfg_if::cfg_if! {
         /// A single row from the PostgreSQL database.
         pub type DatabaseRow = sqlx::postgres::PgRow;
     } else {
+        mod checkpoint;
         mod sqlite;
 
+        #[allow(unsafe_code)]
+        mod function;
+
+        pub use checkpoint::{
+            add_checkpoint_hook, checkpoint, new_checkpoint_job, CheckpointHook, CheckpointMode,
+            CheckpointStats,
+        };
+        pub use function::{
+            register_aggregate_function, register_scalar_function, AggregateFunction,
+            ScalarFunction,
+        };
+
+        #[cfg(feature = "orm-sqlcipher")]
+        mod encryption;
+
+        #[cfg(feature = "orm-sqlcipher")]
+        pub use encryption::rekey;
+
         /// Driver name.
         static DRIVER_NAME: &str = "sqlite";
 
@@ -244,6 +311,22 @@ impl GlobalPool {
         }
     }
 
+    /// Establishes the minimum number of connections for each of the shared connection pools,
+    /// which avoids the latency spikes of the first requests.
+    #[inline]
+    pub async fn warm_up_all() {
+        for cp in SHARED_CONNECTION_POOLS.0.iter() {
+            if cp.is_available() {
+                let num_connections = cp.warm_up().await;
+                tracing::info!(
+                    name = cp.name(),
+                    num_connections,
+                    "connection pool is warmed up"
+                );
+            }
+        }
+    }
+
     /// Shuts down the shared connection pools to ensure all connections are gracefully closed.
     #[inline]
     pub async fn close_all() {
@@ -253,6 +336,116 @@ impl GlobalPool {
     }
 }
 
+/// Enables or disables the global dry-run mode, in which the mutations are not executed
+/// and the returned query context contains the SQL and arguments.
+/// It can also be configured by `dry-run` in the `[database]` table.
+#[inline]
+pub fn set_dry_run(enabled: bool) {
+    DRY_RUN.store(enabled, Relaxed);
+}
+
+/// Sets the max number of retries for the read-only statements which encounter
+/// the transient errors, such as a connection reset or a failover in progress.
+/// It can also be configured by `read-retries` in the `[database]` table.
+/// The retries are disabled by default.
+#[inline]
+pub fn set_read_retries(max_retries: usize) {
+    READ_RETRIES.store(max_retries, Relaxed);
+}
+
+/// A function to initialize a new database connection.
+#[cfg(feature = "orm-sqlx")]
+pub type ConnectionInitializer =
+    for<'a> fn(&'a mut DatabaseConnection) -> crate::BoxFuture<'a, Result<(), sqlx::Error>>;
+
+/// Adds a function which runs on every pooled connection at creation time,
+/// such as setting the session variables.
+/// It should be called before any connection is established.
+/// For SQLite, the application-defined SQL functions can be registered by
+/// `register_scalar_function()` and `register_aggregate_function()` instead.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use sqlx::Executor;
+/// use zino_core::{orm::{self, DatabaseConnection}, BoxFuture};
+///
+/// fn set_cache_size(conn: &mut DatabaseConnection) -> BoxFuture<'_, Result<(), sqlx::Error>> {
+///     Box::pin(async move {
+///         conn.execute("PRAGMA cache_size = -64000;").await?;
+///         Ok(())
+///     })
+/// }
+///
+/// orm::add_connection_initializer(set_cache_size);
+/// ```
+#[cfg(feature = "orm-sqlx")]
+#[inline]
+pub fn add_connection_initializer(initializer: ConnectionInitializer) {
+    CONNECTION_INITIALIZERS.write().push(initializer);
+}
+
+/// A function to verify the schema of a registered model.
+type SchemaVerifier = fn() -> Pin<Box<dyn Future<Output = Result<(), Error>>>>;
+
+/// Registers the model so that its schema is verified when the application is loaded
+/// if `strict-schema` is enabled in the `[database]` table.
+/// The job for refreshing the materialized view of the model is also scheduled
+/// by the application.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino_core::orm;
+///
+/// orm::register_model::<User>();
+/// orm::register_model::<Tag>();
+/// ```
+#[inline]
+pub fn register_model<M: Schema>() {
+    SCHEMA_VERIFIERS
+        .write()
+        .push((M::MODEL_NAME, || Box::pin(M::verify_schema())));
+    if let Some(job) = M::refresh_job() {
+        MODEL_JOBS.lock().push(job);
+    }
+}
+
+/// Takes the scheduled jobs of the registered models.
+/// It is used by the application to add the jobs to the scheduler.
+#[inline]
+pub fn take_model_jobs() -> Vec<AsyncJob> {
+    mem::take(&mut *MODEL_JOBS.lock())
+}
+
+/// Verifies the schemas of all the registered models in the strict schema mode.
+/// The first inconsistency found is returned as an error.
+pub(crate) async fn verify_registered_models() -> Result<(), Error> {
+    if !STRICT_SCHEMA.load(Relaxed) {
+        return Ok(());
+    }
+
+    let verifiers = SCHEMA_VERIFIERS.read().clone();
+    for (model_name, verifier) in verifiers {
+        if let Err(err) = verifier().await {
+            bail!(
+                "fail to verify the schema of the model `{}`: {}",
+                model_name,
+                err
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Returns `true` if the mutations should not be executed.
+#[inline]
+fn dry_run_enabled() -> bool {
+    DRY_RUN.load(Relaxed)
+        || (cfg!(debug_assertions) && DEBUG_ONLY.load(Relaxed))
+        || DatabaseContext::current().is_some_and(|ctx| ctx.is_dry_run())
+}
+
 /// Shared connection pools.
 static SHARED_CONNECTION_POOLS: LazyLock<ConnectionPools> = LazyLock::new(|| {
     let config = State::shared().config();
@@ -273,6 +466,25 @@ static SHARED_CONNECTION_POOLS: LazyLock<ConnectionPools> = LazyLock::new(|| {
     if let Some(debug_only) = database_config.get_bool("debug-only") {
         DEBUG_ONLY.store(debug_only, Relaxed);
     }
+    if let Some(strict_schema) = database_config.get_bool("strict-schema") {
+        STRICT_SCHEMA.store(strict_schema, Relaxed);
+    }
+    if let Some(dry_run) = database_config.get_bool("dry-run") {
+        DRY_RUN.store(dry_run, Relaxed);
+    }
+    if let Some(query_timeout) = database_config.get_duration("query-timeout") {
+        QUERY_TIMEOUT
+            .set(query_timeout)
+            .expect("fail to set the default query timeout");
+    }
+    if let Some(read_retries) = database_config.get_usize("read-retries") {
+        READ_RETRIES.store(read_retries, Relaxed);
+    }
+    if let Some(read_retry_interval) = database_config.get_duration("read-retry-interval") {
+        READ_RETRY_INTERVAL
+            .set(read_retry_interval)
+            .expect("fail to set the interval of retrying read-only statements");
+    }
 
     // Database connection pools.
     let driver = DRIVER_NAME;
@@ -328,6 +540,15 @@ static TABLE_PREFIX: LazyLock<&'static str> = LazyLock::new(|| {
 /// Optional time zone.
 static TIME_ZONE: OnceLock<&'static str> = OnceLock::new();
 
+/// Default query timeout.
+static QUERY_TIMEOUT: OnceLock<Duration> = OnceLock::new();
+
+/// Max number of retries for the read-only statements.
+static READ_RETRIES: AtomicUsize = AtomicUsize::new(0);
+
+/// Interval of retrying the read-only statements.
+static READ_RETRY_INTERVAL: OnceLock<Duration> = OnceLock::new();
+
 /// Max number of returning rows.
 static MAX_ROWS: AtomicUsize = AtomicUsize::new(10000);
 
@@ -336,3 +557,21 @@ static AUTO_MIGRATION: AtomicBool = AtomicBool::new(true);
 
 /// Debug-only mode.
 static DEBUG_ONLY: AtomicBool = AtomicBool::new(false);
+
+/// Strict schema mode.
+static STRICT_SCHEMA: AtomicBool = AtomicBool::new(false);
+
+/// Dry-run mode.
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+/// Schema verifiers of the registered models.
+static SCHEMA_VERIFIERS: parking_lot::RwLock<Vec<(&'static str, SchemaVerifier)>> =
+    parking_lot::RwLock::new(Vec::new());
+
+/// Scheduled jobs of the registered models.
+static MODEL_JOBS: parking_lot::Mutex<Vec<AsyncJob>> = parking_lot::Mutex::new(Vec::new());
+
+/// Connection initializers.
+#[cfg(feature = "orm-sqlx")]
+static CONNECTION_INITIALIZERS: parking_lot::RwLock<Vec<ConnectionInitializer>> =
+    parking_lot::RwLock::new(Vec::new());
@@ -1,4 +1,4 @@
-use super::{query::QueryExt, DatabaseDriver, DatabaseRow, Schema};
+use super::{column::ColumnExt, query::QueryExt, DatabaseDriver, DatabaseRow, Schema};
 use crate::{
     datetime::{Date, DateTime, Time},
     error::Error,
@@ -29,7 +29,7 @@ impl<'c> EncodeColumn<DatabaseDriver> for Column<'c> {
             "i8" => "TINYINT",
             "f64" => "DOUBLE",
             "f32" => "FLOAT",
-            "Decimal" => "NUMERIC",
+            "Decimal" | "Money" => "NUMERIC",
             "String" | "Option<String>" => {
                 if self.default_value().or(self.index_type()).is_some() {
                     "VARCHAR(255)"
@@ -119,6 +119,10 @@ impl<'c> EncodeColumn<DatabaseDriver> for Column<'c> {
                     "NULL".into()
                 }
             }
+            "Money" => match self.encode_money(value) {
+                Some(amount) => amount.into(),
+                None => "NULL".into(),
+            },
             "DateTime" | "NaiveDateTime" => match value {
                 "epoch" => "from_unixtime(0)".into(),
                 "now" => "current_timestamp(6)".into(),
@@ -422,6 +426,7 @@ impl DecodeRow<DatabaseRow> for Map {
     fn decode_row(row: &DatabaseRow) -> Result<Self, Self::Error> {
         let columns = row.columns();
         let mut map = Map::with_capacity(columns.len());
+        let naming = super::NamingConvention::shared();
         for col in columns {
             let field = col.name();
             let index = col.ordinal();
@@ -481,7 +486,7 @@ impl DecodeRow<DatabaseRow> for Map {
                 }
             };
             if !value.is_ignorable() {
-                map.insert(field.to_owned(), value);
+                map.insert(naming.field_name(field).into_owned(), value);
             }
         }
         Ok(map)
@@ -606,19 +611,19 @@ impl QueryExt<DatabaseDriver> for Query {
         crate::helper::prepare_sql_query(query, params, '?')
     }
 
+    #[inline]
     fn format_field(field: &str) -> Cow<'_, str> {
-        if field.contains('.') {
-            field
-                .split('.')
-                .map(|s| format!("`{s}`"))
-                .collect::<Vec<_>>()
-                .join(".")
-                .into()
-        } else {
-            format!("`{field}`").into()
+        match super::NamingConvention::shared().column_name(field) {
+            Cow::Borrowed(field) => super::query::quote_field(field, '`'),
+            Cow::Owned(field) => super::query::quote_field(&field, '`').into_owned().into(),
         }
     }
 
+    #[inline]
+    fn quote_identifier(ident: &str) -> Cow<'_, str> {
+        super::query::quote_identifier(ident, '`')
+    }
+
     fn format_table_fields<M: Schema>(&self) -> Cow<'_, str> {
         let model_name = M::model_name();
         let fields = self.query_fields();
@@ -629,16 +634,12 @@ impl QueryExt<DatabaseDriver> for Query {
                 .iter()
                 .map(|field| {
                     if let Some((alias, expr)) = field.split_once(':') {
-                        let alias = Self::format_field(alias.trim());
+                        let alias = Self::quote_identifier(alias.trim());
                         format!(r#"{expr} AS {alias}"#)
                     } else if field.contains('.') {
-                        field
-                            .split('.')
-                            .map(|s| format!("`{s}`"))
-                            .collect::<Vec<_>>()
-                            .join(".")
+                        Self::format_field(field).into_owned()
                     } else {
-                        format!(r#"`{model_name}`.`{field}`"#)
+                        Self::format_field(&format!("{model_name}.{field}")).into_owned()
                     }
                 })
                 .collect::<Vec<_>>()
@@ -651,13 +652,17 @@ impl QueryExt<DatabaseDriver> for Query {
     fn format_table_name<M: Schema>(&self) -> String {
         let table_name = M::table_name();
         let model_name = M::model_name();
-        format!(r#"`{table_name}` AS `{model_name}`"#)
+        format!(
+            "{} AS {}",
+            super::query::quote_identifier(table_name, '`'),
+            super::query::quote_identifier(model_name, '`')
+        )
     }
 
     #[inline]
     fn table_name_escaped<M: Schema>() -> String {
         let table_name = M::table_name();
-        format!(r#"`{table_name}`"#)
+        super::query::quote_identifier(table_name, '`').into_owned()
     }
 
     fn parse_text_search(filter: &Map) -> Option<String> {
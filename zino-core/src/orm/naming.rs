@@ -0,0 +1,225 @@
+//! Naming conventions for the tables and columns.
+
+use crate::{extension::TomlTableExt, state::State, LazyLock};
+use convert_case::{Case, Casing};
+use parking_lot::RwLock;
+use std::{borrow::Cow, collections::HashMap};
+use toml::Table;
+
+/// Naming conventions for the tables and columns.
+/// They are configured by the `[database.naming]` table:
+///
+/// ```toml
+/// [database.naming]
+/// table-case = "snake_case"
+/// table-plural = true
+/// table-prefix = "app_"
+/// table-suffix = ""
+/// column-case = "camelCase"
+/// auto-rename = true
+///
+/// [database.naming.models.user]
+/// table-prefix = "legacy_"
+/// table-suffix = "_v2"
+/// ```
+///
+/// Supported cases: `snake_case` | `camelCase` | `PascalCase` | `SCREAMING_SNAKE_CASE`.
+/// The table name is derived from the model name with the case, plural form, prefix and suffix,
+/// and the prefix of the `namespace` is prepended at last. A model with the `table_name`
+/// attribute is not affected. The column names are converted from the field names
+/// with the column case, and the columns of the decoded rows are renamed back to
+/// the field names if `auto-rename` is enabled, which is the default.
+/// Only the columns of the models are renamed, so the explicit `column_name` values
+/// and the aliases in the `SELECT` statements are kept as they are.
+#[derive(Debug, Default)]
+pub struct NamingConvention {
+    /// Case of the table names.
+    table_case: Option<Case>,
+    /// A flag to use the plural form of the table names.
+    table_plural: bool,
+    /// Prefix of the table names.
+    table_prefix: String,
+    /// Suffix of the table names.
+    table_suffix: String,
+    /// Case of the column names.
+    column_case: Option<Case>,
+    /// A flag to rename the columns back to the field names.
+    auto_rename: bool,
+    /// Per-model prefix and suffix of the table name.
+    models: HashMap<String, (String, String)>,
+    /// Field names of the registered columns.
+    fields: RwLock<HashMap<String, String>>,
+}
+
+impl NamingConvention {
+    /// Creates a new instance with the configuration.
+    pub fn with_config(config: &Table) -> Self {
+        let table_prefix = config.get_str("table-prefix").unwrap_or_default();
+        let table_suffix = config.get_str("table-suffix").unwrap_or_default();
+        let mut models = HashMap::new();
+        if let Some(tables) = config.get_table("models") {
+            for (model_name, value) in tables {
+                if let Some(table) = value.as_table() {
+                    let prefix = table.get_str("table-prefix").unwrap_or(table_prefix);
+                    let suffix = table.get_str("table-suffix").unwrap_or(table_suffix);
+                    models.insert(
+                        model_name.to_owned(),
+                        (prefix.to_owned(), suffix.to_owned()),
+                    );
+                }
+            }
+        }
+        Self {
+            table_case: config.get_str("table-case").and_then(parse_case),
+            table_plural: config.get_bool("table-plural").unwrap_or_default(),
+            table_prefix: table_prefix.to_owned(),
+            table_suffix: table_suffix.to_owned(),
+            column_case: config.get_str("column-case").and_then(parse_case),
+            auto_rename: config.get_bool("auto-rename").unwrap_or(true),
+            models,
+            fields: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Returns a reference to the shared naming conventions.
+    #[inline]
+    pub fn shared() -> &'static Self {
+        &SHARED_NAMING_CONVENTION
+    }
+
+    /// Returns the table name for the model without the namespace prefix.
+    pub fn table_name(&self, model_name: &str) -> String {
+        let (prefix, suffix) = self
+            .models
+            .get(model_name)
+            .map(|(prefix, suffix)| (prefix.as_str(), suffix.as_str()))
+            .unwrap_or((&self.table_prefix, &self.table_suffix));
+        let mut table_name = if self.table_plural {
+            pluralize(model_name)
+        } else {
+            model_name.to_owned()
+        };
+        if let Some(case) = self.table_case {
+            table_name = table_name.to_case(case);
+        }
+        [prefix, &table_name, suffix].concat()
+    }
+
+    /// Returns the column name for the field.
+    /// The qualified field is converted in the last segment.
+    pub fn column_name<'a>(&self, field: &'a str) -> Cow<'a, str> {
+        let Some(case) = self.column_case else {
+            return Cow::Borrowed(field);
+        };
+        if field.contains(|c| matches!(c, '(' | ')' | ' ' | '\'' | '"' | '`' | ':' | '>' | '*')) {
+            return Cow::Borrowed(field);
+        }
+        let column_name = if let Some((table_name, field)) = field.rsplit_once('.') {
+            [table_name, ".", &field.to_case(case)].concat()
+        } else {
+            field.to_case(case)
+        };
+        if column_name == field {
+            Cow::Borrowed(field)
+        } else {
+            Cow::Owned(column_name)
+        }
+    }
+
+    /// Registers the fields of a model so that the converted columns can be renamed back.
+    pub fn register_fields<'a>(&self, fields: impl IntoIterator<Item = &'a str>) {
+        if self.column_case.is_none() || !self.auto_rename {
+            return;
+        }
+
+        let mut registered_fields = self.fields.write();
+        for field in fields {
+            let column_name = self.column_name(field);
+            if column_name != field {
+                registered_fields.insert(column_name.into_owned(), field.to_owned());
+            }
+        }
+    }
+
+    /// Returns the field name for the column of a decoded row.
+    /// The column is renamed only if it has been converted from a registered field.
+    pub fn field_name<'a>(&self, column_name: &'a str) -> Cow<'a, str> {
+        if self.column_case.is_some() && self.auto_rename {
+            if let Some(field) = self.fields.read().get(column_name) {
+                return Cow::Owned(field.to_owned());
+            }
+        }
+        Cow::Borrowed(column_name)
+    }
+}
+
+/// Parses the case of the naming convention.
+fn parse_case(case: &str) -> Option<Case> {
+    match case {
+        "snake_case" => Some(Case::Snake),
+        "camelCase" => Some(Case::Camel),
+        "PascalCase" => Some(Case::Pascal),
+        "SCREAMING_SNAKE_CASE" => Some(Case::UpperSnake),
+        _ => {
+            tracing::warn!("unsupported naming case `{case}`");
+            None
+        }
+    }
+}
+
+/// Returns the plural form of an English word in the common cases.
+fn pluralize(word: &str) -> String {
+    if word.ends_with(['s', 'x', 'z']) || word.ends_with("ch") || word.ends_with("sh") {
+        [word, "es"].concat()
+    } else if let Some(stem) = word.strip_suffix('y') {
+        if stem.ends_with(['a', 'e', 'i', 'o', 'u']) {
+            [word, "s"].concat()
+        } else {
+            [stem, "ies"].concat()
+        }
+    } else {
+        [word, "s"].concat()
+    }
+}
+
+/// Shared naming conventions.
+static SHARED_NAMING_CONVENTION: LazyLock<NamingConvention> = LazyLock::new(|| {
+    State::shared()
+        .get_config("database")
+        .and_then(|config| config.get_table("naming"))
+        .map(NamingConvention::with_config)
+        .unwrap_or_default()
+});
+
+#[cfg(test)]
+mod tests {
+    use super::NamingConvention;
+
+    #[test]
+    fn it_formats_names() {
+        let config = r#"
+            table-case = "snake_case"
+            table-plural = true
+            table-prefix = "app_"
+            column-case = "camelCase"
+
+            [models.category]
+            table-prefix = ""
+            table-suffix = "_v2"
+        "#
+        .parse()
+        .unwrap();
+        let naming = NamingConvention::with_config(&config);
+        assert_eq!(naming.table_name("user"), "app_users");
+        assert_eq!(naming.table_name("address"), "app_addresses");
+        assert_eq!(naming.table_name("category"), "categories_v2");
+        assert_eq!(naming.column_name("created_at"), "createdAt");
+        assert_eq!(naming.column_name("u.created_at"), "u.createdAt");
+        assert_eq!(naming.column_name("count(*)"), "count(*)");
+        assert_eq!(naming.field_name("createdAt"), "createdAt");
+
+        naming.register_fields(["id", "created_at"]);
+        assert_eq!(naming.field_name("createdAt"), "created_at");
+        assert_eq!(naming.field_name("totalCount"), "totalCount");
+    }
+}
@@ -0,0 +1,249 @@
+//! Table partitioning for the models.
+
+use super::{retention, Schema};
+use crate::{
+    datetime::Date,
+    extension::{JsonObjectExt, TomlTableExt},
+    schedule::{AsyncCronJob, AsyncJob},
+    state::State,
+    LazyLock,
+};
+use chrono::{Datelike, NaiveDate};
+use std::str::FromStr;
+use toml::Table;
+
+/// Units of the partition interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IntervalUnit {
+    /// Day.
+    Day,
+    /// Week starting on Monday.
+    Week,
+    /// Month.
+    Month,
+    /// Year.
+    Year,
+}
+
+/// Interval of the range partitions, such as `1 month` or `7 days`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct PartitionInterval {
+    /// Number of units.
+    count: u32,
+    /// Interval unit.
+    unit: IntervalUnit,
+}
+
+impl PartitionInterval {
+    /// Returns the start date of the partition which contains the date.
+    ///
+    /// The partitions are aligned to `1970-01-01` (or `1970-01-05` for weeks)
+    /// so that the ranges never overlap.
+    pub(super) fn floor(&self, date: Date) -> Date {
+        let count = i64::from(self.count);
+        let date = NaiveDate::from(date);
+        let start = match self.unit {
+            IntervalUnit::Day => {
+                let days = i64::from(date.num_days_from_ce() - EPOCH_DAYS_FROM_CE);
+                let days = days.div_euclid(count) * count;
+                NaiveDate::from_num_days_from_ce_opt(EPOCH_DAYS_FROM_CE + days as i32)
+            }
+            IntervalUnit::Week => {
+                let days = i64::from(date.num_days_from_ce() - EPOCH_DAYS_FROM_CE - 4);
+                let days = days.div_euclid(7 * count) * 7 * count;
+                NaiveDate::from_num_days_from_ce_opt(EPOCH_DAYS_FROM_CE + 4 + days as i32)
+            }
+            IntervalUnit::Month => {
+                let months = i64::from(date.year() - 1970) * 12 + i64::from(date.month0());
+                let months = months.div_euclid(count) * count;
+                let year = 1970 + months.div_euclid(12) as i32;
+                let month = months.rem_euclid(12) as u32 + 1;
+                NaiveDate::from_ymd_opt(year, month, 1)
+            }
+            IntervalUnit::Year => {
+                let years = i64::from(date.year() - 1970);
+                let years = years.div_euclid(count) * count;
+                NaiveDate::from_ymd_opt(1970 + years as i32, 1, 1)
+            }
+        };
+        start.unwrap_or(date).into()
+    }
+
+    /// Returns the start date of the next partition.
+    pub(super) fn next(&self, start: Date) -> Option<Date> {
+        match self.unit {
+            IntervalUnit::Day => start.checked_add_days(self.count),
+            IntervalUnit::Week => start.checked_add_days(self.count * 7),
+            IntervalUnit::Month => start.checked_add_months(self.count),
+            IntervalUnit::Year => start.checked_add_months(self.count * 12),
+        }
+    }
+}
+
+impl FromStr for PartitionInterval {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let index = s.find(|ch: char| !ch.is_ascii_digit()).unwrap_or(s.len());
+        let (count, unit) = s.split_at(index);
+        let count = if count.is_empty() {
+            1
+        } else {
+            count
+                .parse::<u32>()
+                .map_err(|err| format!("invalid partition interval `{s}`: {err}"))?
+        };
+        let unit = match unit.trim().trim_end_matches('s') {
+            "d" | "day" => IntervalUnit::Day,
+            "w" | "week" => IntervalUnit::Week,
+            "month" => IntervalUnit::Month,
+            "y" | "year" => IntervalUnit::Year,
+            _ => return Err(format!("invalid partition interval `{s}`")),
+        };
+        if count == 0 {
+            return Err(format!("partition interval `{s}` should be positive"));
+        }
+        Ok(Self { count, unit })
+    }
+}
+
+/// Parses the `partition_by` value in the form `range(column)`
+/// and returns the partition column.
+pub(super) fn parse_range_column(partition_by: &str) -> Result<&str, String> {
+    partition_by
+        .trim()
+        .strip_prefix("range(")
+        .and_then(|s| s.strip_suffix(')'))
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| format!("unsupported partition method `{partition_by}`"))
+}
+
+/// Returns the partition column and interval of the model partitioned by range.
+pub(super) fn parse_spec<M: Schema>() -> Option<(&'static str, PartitionInterval)> {
+    let model_name = M::MODEL_NAME;
+    let partition_column = match parse_range_column(M::PARTITION_BY?) {
+        Ok(column) => column,
+        Err(err) => {
+            tracing::warn!(model_name, "{err}");
+            return None;
+        }
+    };
+    let interval = M::PARTITION_INTERVAL.unwrap_or("1 month");
+    match interval.parse() {
+        Ok(interval) => Some((partition_column, interval)),
+        Err(err) => {
+            tracing::warn!(model_name, "{err}");
+            None
+        }
+    }
+}
+
+/// Returns the name of the partition starting at the date.
+#[inline]
+pub(super) fn partition_name(table_name: &str, start: Date) -> String {
+    format!("{table_name}_p{}", start.format("%Y%m%d"))
+}
+
+/// Parses the start date of the partition from its name.
+pub(super) fn parse_partition_start(table_name: &str, partition_name: &str) -> Option<Date> {
+    let suffix = partition_name
+        .strip_prefix(table_name)?
+        .strip_prefix("_p")?;
+    NaiveDate::parse_from_str(suffix, "%Y%m%d")
+        .ok()
+        .map(Date::from)
+}
+
+/// Creates a scheduled job for maintaining the partitions of a model.
+/// The job is configured by the `[partition]` table:
+///
+/// ```toml
+/// [partition]
+/// cron = "0 0 1 * * *"
+/// premake = 3
+/// ```
+///
+/// Only PostgreSQL supports table partitioning, and `None` is returned for the other drivers.
+///
+/// It is used by the `Schema` derive macro and should not be called directly.
+#[doc(hidden)]
+pub fn new_partition_job(model_name: &str, exec: AsyncCronJob) -> Option<AsyncJob> {
+    if !cfg!(feature = "orm-postgres") {
+        tracing::warn!(
+            model_name,
+            "table partitioning is only supported by PostgreSQL"
+        );
+        return None;
+    }
+
+    let cron = &SHARED_PARTITION_CONFIG.cron;
+    let mut job = AsyncJob::new(cron, exec).name(format!("{model_name}_partition"));
+    job.data_mut().upsert("dry_run", retention::dry_run());
+    Some(job)
+}
+
+/// Returns the number of upcoming partitions to be created in advance.
+#[inline]
+pub(super) fn premake() -> usize {
+    SHARED_PARTITION_CONFIG.premake
+}
+
+/// Partition config.
+struct PartitionConfig {
+    /// Cron expression of the maintenance job.
+    cron: String,
+    /// Number of upcoming partitions.
+    premake: usize,
+}
+
+impl PartitionConfig {
+    /// Creates a new instance with the configuration.
+    fn with_config(config: &Table) -> Self {
+        Self {
+            cron: config.get_str("cron").unwrap_or("@daily").to_owned(),
+            premake: config.get_usize("premake").unwrap_or(3),
+        }
+    }
+}
+
+/// Number of days from the common era to `1970-01-01`.
+const EPOCH_DAYS_FROM_CE: i32 = 719_163;
+
+/// Shared partition config.
+static SHARED_PARTITION_CONFIG: LazyLock<PartitionConfig> = LazyLock::new(|| {
+    let config = State::shared()
+        .get_config("partition")
+        .cloned()
+        .unwrap_or_default();
+    PartitionConfig::with_config(&config)
+});
+
+#[cfg(test)]
+mod tests {
+    use super::PartitionInterval;
+    use crate::datetime::Date;
+
+    #[test]
+    fn it_aligns_partition_ranges() {
+        let date = "2024-05-17".parse::<Date>().unwrap();
+
+        let interval = "1 month".parse::<PartitionInterval>().unwrap();
+        let start = interval.floor(date);
+        assert_eq!(start.to_string(), "2024-05-01");
+        assert_eq!(interval.next(start).unwrap().to_string(), "2024-06-01");
+
+        let interval = "3 months".parse::<PartitionInterval>().unwrap();
+        assert_eq!(interval.floor(date).to_string(), "2024-04-01");
+
+        let interval = "1 week".parse::<PartitionInterval>().unwrap();
+        assert_eq!(interval.floor(date).to_string(), "2024-05-13");
+
+        let interval = "1 year".parse::<PartitionInterval>().unwrap();
+        assert_eq!(interval.floor(date).to_string(), "2024-01-01");
+
+        assert!("0 days".parse::<PartitionInterval>().is_err());
+        assert!("1 fortnight".parse::<PartitionInterval>().is_err());
+    }
+}
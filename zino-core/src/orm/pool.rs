@@ -1,5 +1,8 @@
 use super::DatabasePool;
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering::Relaxed};
+use std::{
+    sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering::Relaxed},
+    time::{Duration, Instant},
+};
 
 /// A database connection pool with metadata.
 #[derive(Debug)]
@@ -14,6 +17,18 @@ pub struct ConnectionPool<P = DatabasePool> {
     available: AtomicBool,
     /// Missed count.
     missed_count: AtomicUsize,
+    /// Endpoints for the failover.
+    endpoints: Vec<&'static str>,
+    /// Index of the current endpoint.
+    endpoint_index: AtomicUsize,
+    /// Failover policy.
+    failover_policy: FailoverPolicy,
+    /// Read-only flag.
+    read_only: bool,
+    /// Time when the pool was created.
+    created_at: Instant,
+    /// Milliseconds elapsed since the creation when the connections were reset.
+    reset_at: AtomicU64,
 }
 
 impl<P> ConnectionPool<P> {
@@ -26,9 +41,42 @@ impl<P> ConnectionPool<P> {
             pool,
             available: AtomicBool::new(true),
             missed_count: AtomicUsize::new(0),
+            endpoints: Vec::new(),
+            endpoint_index: AtomicUsize::new(0),
+            failover_policy: FailoverPolicy::default(),
+            read_only: false,
+            created_at: Instant::now(),
+            reset_at: AtomicU64::new(0),
         }
     }
 
+    /// Sets the endpoints in the form of `host[:port]` and the failover policy.
+    /// The first endpoint is regarded as the primary one.
+    #[inline]
+    pub fn with_endpoints(
+        mut self,
+        endpoints: Vec<&'static str>,
+        failover_policy: FailoverPolicy,
+    ) -> Self {
+        self.endpoints = endpoints;
+        self.failover_policy = failover_policy;
+        self
+    }
+
+    /// Sets the read-only flag. The failover of a pool which is not read-only
+    /// only switches to the writable endpoints.
+    #[inline]
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Returns `true` if the connection pool is read-only.
+    #[inline]
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
     /// Returns `true` if the connection pool is available.
     #[inline]
     pub fn is_available(&self) -> bool {
@@ -87,4 +135,165 @@ impl<P> ConnectionPool<P> {
     pub fn pool(&self) -> &P {
         &self.pool
     }
+
+    /// Returns the endpoints.
+    #[inline]
+    pub fn endpoints(&self) -> &[&'static str] {
+        &self.endpoints
+    }
+
+    /// Returns the current endpoint.
+    #[inline]
+    pub fn current_endpoint(&self) -> Option<&'static str> {
+        self.endpoints.get(self.endpoint_index()).copied()
+    }
+
+    /// Returns the index of the current endpoint.
+    #[inline]
+    pub fn endpoint_index(&self) -> usize {
+        self.endpoint_index.load(Relaxed)
+    }
+
+    /// Stores the index of the current endpoint.
+    #[inline]
+    pub(super) fn store_endpoint_index(&self, index: usize) {
+        self.endpoint_index.store(index, Relaxed);
+    }
+
+    /// Marks all the connections opened before now as outdated,
+    /// so that they will be closed instead of being reused.
+    #[inline]
+    pub(super) fn mark_connections_outdated(&self) {
+        let elapsed = self.created_at.elapsed().as_millis();
+        self.reset_at
+            .store(u64::try_from(elapsed).unwrap_or(u64::MAX).max(1), Relaxed);
+    }
+
+    /// Returns `true` if a connection with the age was opened before
+    /// the connections were reset.
+    pub fn is_outdated(&self, age: Duration) -> bool {
+        let reset_at = self.reset_at.load(Relaxed);
+        if reset_at == 0 {
+            return false;
+        }
+        let opened_at = self.created_at.elapsed().saturating_sub(age).as_millis();
+        opened_at <= u128::from(reset_at)
+    }
+
+    /// Returns the failover policy.
+    #[inline]
+    pub fn failover_policy(&self) -> FailoverPolicy {
+        self.failover_policy
+    }
+
+    /// Returns the indexes of the endpoints to try in order when the current one is down.
+    pub fn failover_candidates(&self) -> Vec<usize> {
+        let num_endpoints = self.endpoints.len();
+        let current_index = self.endpoint_index();
+        match self.failover_policy {
+            FailoverPolicy::Priority => (0..num_endpoints)
+                .filter(|&index| index != current_index)
+                .collect(),
+            FailoverPolicy::RoundRobin => (1..num_endpoints)
+                .map(|offset| (current_index + offset) % num_endpoints)
+                .collect(),
+        }
+    }
+}
+
+/// Policy of choosing the next endpoint when the current one is down.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FailoverPolicy {
+    /// Tries the endpoints in the listed order, so the primary one is always preferred.
+    #[default]
+    Priority,
+    /// Tries the endpoints following the current one in turn.
+    RoundRobin,
+}
+
+impl FailoverPolicy {
+    /// Parses the policy.
+    #[inline]
+    pub fn parse(policy: &str) -> Option<Self> {
+        match policy.to_ascii_lowercase().as_str() {
+            "priority" => Some(Self::Priority),
+            "round-robin" | "round_robin" => Some(Self::RoundRobin),
+            _ => None,
+        }
+    }
+}
+
+/// Splits the endpoint in the form of `host[:port]` into the host and port.
+/// An IPv6 address should be enclosed in brackets if the port is specified.
+#[cfg(any(
+    feature = "orm-mariadb",
+    feature = "orm-mysql",
+    feature = "orm-postgres",
+    feature = "orm-tidb"
+))]
+pub(super) fn split_endpoint(endpoint: &str) -> (&str, Option<u16>) {
+    if let Some(endpoint) = endpoint.strip_prefix('[') {
+        if let Some((host, port)) = endpoint.split_once(']') {
+            let port = port.strip_prefix(':').and_then(|port| port.parse().ok());
+            return (host, port);
+        }
+    } else if let Some((host, port)) = endpoint.split_once(':') {
+        if !port.contains(':') {
+            if let Ok(port) = port.parse() {
+                return (host, Some(port));
+            }
+        }
+    }
+    (endpoint, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConnectionPool, FailoverPolicy};
+    use std::time::Duration;
+
+    #[cfg(any(
+        feature = "orm-mariadb",
+        feature = "orm-mysql",
+        feature = "orm-postgres",
+        feature = "orm-tidb"
+    ))]
+    #[test]
+    fn it_splits_endpoints() {
+        use super::split_endpoint;
+
+        assert_eq!(split_endpoint("db1"), ("db1", None));
+        assert_eq!(split_endpoint("db1:5432"), ("db1", Some(5432)));
+        assert_eq!(split_endpoint("[::1]:5432"), ("::1", Some(5432)));
+        assert_eq!(split_endpoint("[::1]"), ("::1", None));
+        assert_eq!(split_endpoint("fe80::1"), ("fe80::1", None));
+    }
+
+    #[test]
+    fn it_lists_failover_candidates() {
+        let endpoints = vec!["db1:5432", "db2:5432", "db3:5432"];
+        let cp = ConnectionPool::new("main", "test", ())
+            .with_endpoints(endpoints.clone(), FailoverPolicy::Priority);
+        cp.store_endpoint_index(1);
+        assert_eq!(cp.failover_candidates(), vec![0, 2]);
+
+        let cp = ConnectionPool::new("main", "test", ())
+            .with_endpoints(endpoints, FailoverPolicy::RoundRobin);
+        cp.store_endpoint_index(1);
+        assert_eq!(cp.failover_candidates(), vec![2, 0]);
+        assert_eq!(cp.current_endpoint(), Some("db2:5432"));
+    }
+
+    #[test]
+    fn it_marks_connections_outdated() {
+        let cp = ConnectionPool::new("main", "test", ());
+        assert!(!cp.is_outdated(Duration::from_secs(60)));
+
+        std::thread::sleep(Duration::from_millis(5));
+        cp.mark_connections_outdated();
+        assert!(cp.is_outdated(Duration::from_secs(60)));
+
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(!cp.is_outdated(Duration::ZERO));
+    }
 }
@@ -0,0 +1,201 @@
+use super::{query::QueryExt, schema::Schema};
+use crate::{
+    bail,
+    error::Error,
+    extension::JsonObjectExt,
+    model::{Mutation, Query},
+    warn, Map,
+};
+use std::fmt::Display;
+
+/// Gap between two adjacent positions.
+const POSITION_STEP: i64 = 1 << 16;
+
+/// Manual ordering of models, such as drag-and-drop in UIs.
+///
+/// A model is ordered manually if the `POSITION_FIELD` is specified,
+/// which can be derived by `#[schema(position)]`. The positions are sparse integers,
+/// so that moving a model only updates the model itself in most cases.
+pub trait PositionQuery<K>: Schema<PrimaryKey = K>
+where
+    K: Default + Display + PartialEq,
+{
+    /// Moves the model before the target.
+    /// It returns the new position of the model.
+    async fn move_before(id: &K, target_id: &K) -> Result<i64, Error> {
+        move_to::<Self>(&id.to_string(), &target_id.to_string(), true).await
+    }
+
+    /// Moves the model after the target.
+    /// It returns the new position of the model.
+    async fn move_after(id: &K, target_id: &K) -> Result<i64, Error> {
+        move_to::<Self>(&id.to_string(), &target_id.to_string(), false).await
+    }
+
+    /// Reorders the models in the order of the IDs.
+    /// The positions occupied by these models are reassigned among them,
+    /// so that the other models are not affected.
+    /// It returns the number of rows affected.
+    async fn reorder(ids: &[K]) -> Result<u64, Error> {
+        let position_field = position_field::<Self>()?;
+        let primary_key_name = Self::PRIMARY_KEY_NAME;
+        let ids = ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        let mut query = Query::default();
+        query.allow_fields(&[primary_key_name, position_field]);
+        query.add_filter(primary_key_name, Map::from_entry("$in", ids.as_slice()));
+        query.disable_limit();
+
+        let models = Self::find::<Map>(&query).await?;
+        let mut current_positions = Vec::with_capacity(ids.len());
+        for id in ids.iter() {
+            let Some(model) = models
+                .iter()
+                .find(|model| model.parse_string(primary_key_name).as_deref() == Some(id))
+            else {
+                bail!("404 Not Found: cannot find the model `{}`", id);
+            };
+            current_positions.push(model.get_i64(position_field).unwrap_or_default());
+        }
+
+        let mut positions = current_positions.clone();
+        positions.sort_unstable();
+        positions.dedup();
+        if positions.len() < ids.len() {
+            // Spreads out the positions if some of them are duplicated.
+            let start = positions.first().copied().unwrap_or_default();
+            positions = (0..ids.len() as i64)
+                .map(|index| start + index * POSITION_STEP)
+                .collect();
+        }
+
+        let mut rows_affected = 0;
+        for ((id, current_position), position) in ids
+            .iter()
+            .zip(current_positions.into_iter())
+            .zip(positions.into_iter())
+        {
+            if current_position != position {
+                rows_affected += update_position::<Self>(id, position).await?;
+            }
+        }
+        Ok(rows_affected)
+    }
+}
+
+impl<M, K> PositionQuery<K> for M
+where
+    M: Schema<PrimaryKey = K>,
+    K: Default + Display + PartialEq,
+{
+}
+
+/// Returns the position field of a manually ordered model.
+fn position_field<M: Schema>() -> Result<&'static str, Error> {
+    let model_name = M::model_name();
+    M::POSITION_FIELD.ok_or_else(|| warn!("the model `{}` is not ordered manually", model_name))
+}
+
+/// Finds the position of the model.
+async fn find_position<M: Schema>(id: &str) -> Result<i64, Error> {
+    let position_field = position_field::<M>()?;
+    let mut query = Query::default();
+    query.allow_fields(&[position_field]);
+    query.add_filter(M::PRIMARY_KEY_NAME, id);
+
+    let Some(model) = M::find_one::<Map>(&query).await? else {
+        bail!("404 Not Found: cannot find the model `{}`", id);
+    };
+    Ok(model.get_i64(position_field).unwrap_or_default())
+}
+
+/// Finds the position of the nearest neighbor of the target, excluding the model itself.
+async fn find_neighbor_position<M: Schema>(
+    id: &str,
+    target_position: i64,
+    before: bool,
+) -> Result<Option<i64>, Error> {
+    let position_field = position_field::<M>()?;
+    let mut query = Query::default();
+    query.allow_fields(&[position_field]);
+    query.add_filter(M::PRIMARY_KEY_NAME, Map::from_entry("$ne", id));
+    if before {
+        query.add_filter(position_field, Map::from_entry("$lt", target_position));
+        query.order_desc(position_field);
+    } else {
+        query.add_filter(position_field, Map::from_entry("$gt", target_position));
+        query.order_asc(position_field);
+    }
+
+    let neighbor = M::find_one::<Map>(&query).await?;
+    Ok(neighbor.and_then(|model| model.get_i64(position_field)))
+}
+
+/// Updates the position of the model.
+async fn update_position<M: Schema>(id: &str, position: i64) -> Result<u64, Error> {
+    let position_field = position_field::<M>()?;
+    let mut query = Query::default();
+    query.add_filter(M::PRIMARY_KEY_NAME, id);
+
+    let mut mutation = Mutation::from_entry(position_field, position);
+    let ctx = M::update_one(&query, &mut mutation).await?;
+    Ok(ctx.rows_affected().unwrap_or_default())
+}
+
+/// Moves the model next to the target.
+async fn move_to<M: Schema>(id: &str, target_id: &str, before: bool) -> Result<i64, Error> {
+    if id == target_id {
+        bail!("the model `{}` can not be moved next to itself", id);
+    }
+
+    let target_position = find_position::<M>(target_id).await?;
+    let neighbor_position = find_neighbor_position::<M>(id, target_position, before).await?;
+    let (lower, upper) = if before {
+        (neighbor_position, Some(target_position))
+    } else {
+        (Some(target_position), neighbor_position)
+    };
+    let position = match (lower, upper) {
+        (Some(lower), Some(upper)) if upper - lower > 1 => lower + (upper - lower) / 2,
+        (Some(lower), Some(upper)) => {
+            // Makes room for the model by shifting the subsequent models.
+            let position_field = position_field::<M>()?;
+            let table_name = Query::table_name_escaped::<M>();
+            let position_column = Query::format_field(position_field);
+            let sql = format!(
+                "UPDATE {table_name} SET {position_column} = {position_column} + {POSITION_STEP} \
+                    WHERE {position_column} >= {upper};"
+            );
+            M::execute(&sql, None).await?;
+            lower + POSITION_STEP / 2
+        }
+        (None, Some(upper)) => upper - POSITION_STEP,
+        (Some(lower), None) => lower + POSITION_STEP,
+        (None, None) => 0,
+    };
+    if update_position::<M>(id, position).await? == 0 {
+        bail!("404 Not Found: cannot find the model `{}`", id);
+    }
+    Ok(position)
+}
+
+/// Appends the model to the end of the list if its position has not been set.
+pub(super) async fn append_position<M: Schema>(id: &str) -> Result<(), Error> {
+    let position_field = position_field::<M>()?;
+    let mut query = Query::default();
+    query.allow_fields(&[position_field]);
+    query.add_filter(M::PRIMARY_KEY_NAME, Map::from_entry("$ne", id));
+    query.order_desc(position_field);
+
+    let last_position = M::find_one::<Map>(&query)
+        .await?
+        .and_then(|model| model.get_i64(position_field));
+    if let Some(last_position) = last_position {
+        let mut query = Query::default();
+        query.add_filter(M::PRIMARY_KEY_NAME, id);
+        query.add_filter(position_field, 0);
+
+        let mut mutation = Mutation::from_entry(position_field, last_position + POSITION_STEP);
+        M::update_one(&query, &mut mutation).await?;
+    }
+    Ok(())
+}
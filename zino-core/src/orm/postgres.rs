@@ -1,16 +1,23 @@
-use super::{query::QueryExt, DatabaseDriver, DatabaseRow, Schema};
+use super::{
+    column::ColumnExt, decode::interval_to_duration, query::QueryExt, DatabaseDriver, DatabaseRow,
+    Schema,
+};
 use crate::{
-    datetime::{Date, DateTime, Time},
+    datetime::{self, Date, DateTime, Time},
     error::Error,
     extension::{JsonObjectExt, JsonValueExt},
-    model::{Column, DecodeRow, EncodeColumn, Query},
-    AvroValue, JsonValue, Map, Record, SharedString, Uuid,
+    model::{Column, DecodeRow, EncodeColumn, Query, Range},
+    AvroValue, BoxError, JsonValue, Map, Record, SharedString, Uuid,
 };
 use chrono::NaiveDateTime;
-use std::borrow::Cow;
+use std::{borrow::Cow, ops::Bound, time::Duration};
 
 #[cfg(feature = "orm-sqlx")]
-use sqlx::{types::Decimal, Column as _, Row, TypeInfo, ValueRef};
+use sqlx::{
+    postgres::types::{PgInterval, PgRange},
+    types::Decimal,
+    Column as _, Row, TypeInfo, ValueRef,
+};
 
 impl<'c> EncodeColumn<DatabaseDriver> for Column<'c> {
     fn column_type(&self) -> &str {
@@ -42,7 +49,7 @@ impl<'c> EncodeColumn<DatabaseDriver> for Column<'c> {
             }
             "f64" => "DOUBLE PRECISION",
             "f32" => "REAL",
-            "Decimal" => "NUMERIC",
+            "Decimal" | "Money" => "NUMERIC",
             "Date" | "NaiveDate" => "DATE",
             "Time" | "NaiveTime" => "TIME",
             "DateTime" => "TIMESTAMPTZ",
@@ -54,6 +61,11 @@ impl<'c> EncodeColumn<DatabaseDriver> for Column<'c> {
             "Vec<u64>" | "Vec<i64>" => "BIGINT[]",
             "Vec<u32>" | "Vec<i32>" => "INT[]",
             "Map" => "JSONB",
+            "Range<i32>" => "INT4RANGE",
+            "Range<i64>" => "INT8RANGE",
+            "Range<Date>" => "DATERANGE",
+            "Range<DateTime>" => "TSTZRANGE",
+            "Duration" => "INTERVAL",
             _ => "TEXT",
         }
     }
@@ -92,6 +104,14 @@ impl<'c> EncodeColumn<DatabaseDriver> for Column<'c> {
                         .collect::<Vec<_>>();
                     format!("ARRAY[{}]::{}", values.join(","), self.column_type()).into()
                 }
+                JsonValue::Object(_) if self.type_name() == "Duration" => {
+                    match serde_json::from_value::<Duration>(value.clone()) {
+                        Ok(duration) => {
+                            format!("'{} microseconds'::interval", duration.as_micros()).into()
+                        }
+                        Err(_) => "NULL".into(),
+                    }
+                }
                 JsonValue::Object(_) => {
                     format!("{}::{}", Query::escape_string(value), self.column_type()).into()
                 }
@@ -130,6 +150,10 @@ impl<'c> EncodeColumn<DatabaseDriver> for Column<'c> {
                     "NULL".into()
                 }
             }
+            "Money" => match self.encode_money(value) {
+                Some(amount) => amount.into(),
+                None => "NULL".into(),
+            },
             "DateTime" | "NaiveDateTime" => match value {
                 "epoch" => "'epoch'".into(),
                 "now" => "now()".into(),
@@ -169,6 +193,28 @@ impl<'c> EncodeColumn<DatabaseDriver> for Column<'c> {
                 let value = Query::escape_string(value);
                 format!("{value}::jsonb").into()
             }
+            "Range<i32>" | "Range<i64>" | "Range<Date>" | "Range<DateTime>" => {
+                let value_type = if value.starts_with(['[', '(']) || value == "empty" {
+                    self.column_type()
+                } else {
+                    // Casts a single element for the containment operators.
+                    match self.type_name() {
+                        "Range<i32>" => "int4",
+                        "Range<i64>" => "int8",
+                        "Range<Date>" => "date",
+                        _ => "timestamptz",
+                    }
+                };
+                let value = Query::escape_string(value);
+                format!("{value}::{value_type}").into()
+            }
+            "Duration" => match datetime::parse_duration(value) {
+                Ok(duration) => format!("'{} milliseconds'::interval", duration.as_millis()).into(),
+                Err(_) => {
+                    let value = Query::escape_string(value);
+                    format!("{value}::interval").into()
+                }
+            },
             _ => Query::escape_string(value).into(),
         }
     }
@@ -199,6 +245,9 @@ impl<'c> EncodeColumn<DatabaseDriver> for Column<'c> {
                         "$rlike" => "~*",
                         "$is" => "IS",
                         "$size" => "array_length",
+                        "$overlaps" => "&&",
+                        "$contains" => "@>",
+                        "$within" => "<@",
                         _ => {
                             if cfg!(debug_assertions) && name.starts_with('$') {
                                 tracing::warn!("unsupported operator `{name}` for PostgreSQL");
@@ -452,6 +501,7 @@ impl DecodeRow<DatabaseRow> for Map {
     fn decode_row(row: &DatabaseRow) -> Result<Self, Self::Error> {
         let columns = row.columns();
         let mut map = Map::with_capacity(columns.len());
+        let naming = super::NamingConvention::shared();
         for col in columns {
             let field = col.name();
             let index = col.ordinal();
@@ -491,11 +541,27 @@ impl DecodeRow<DatabaseRow> for Map {
                             .into()
                     }
                     "JSONB" | "JSON" => decode_raw::<JsonValue>(field, raw_value)?,
+                    "INT4RANGE" => decode_raw::<Range<i32>>(field, raw_value)?
+                        .to_string()
+                        .into(),
+                    "INT8RANGE" => decode_raw::<Range<i64>>(field, raw_value)?
+                        .to_string()
+                        .into(),
+                    "DATERANGE" => decode_raw::<Range<Date>>(field, raw_value)?
+                        .to_string()
+                        .into(),
+                    "TSTZRANGE" => decode_raw::<Range<DateTime>>(field, raw_value)?
+                        .to_string()
+                        .into(),
+                    "INTERVAL" => {
+                        let interval = decode_raw::<PgInterval>(field, raw_value)?;
+                        serde_json::to_value(interval_to_duration(field, interval)?)?
+                    }
                     _ => decode_raw::<String>(field, raw_value)?.into(),
                 }
             };
             if !value.is_ignorable() {
-                map.insert(field.to_owned(), value);
+                map.insert(naming.field_name(field).into_owned(), value);
             }
         }
         Ok(map)
@@ -553,6 +619,23 @@ impl DecodeRow<DatabaseRow> for Record {
                         AvroValue::Array(vec)
                     }
                     "JSONB" | "JSON" => decode_raw::<JsonValue>(field, raw_value)?.into(),
+                    "INT4RANGE" => decode_raw::<Range<i32>>(field, raw_value)?
+                        .to_string()
+                        .into(),
+                    "INT8RANGE" => decode_raw::<Range<i64>>(field, raw_value)?
+                        .to_string()
+                        .into(),
+                    "DATERANGE" => decode_raw::<Range<Date>>(field, raw_value)?
+                        .to_string()
+                        .into(),
+                    "TSTZRANGE" => decode_raw::<Range<DateTime>>(field, raw_value)?
+                        .to_string()
+                        .into(),
+                    "INTERVAL" => {
+                        let interval = decode_raw::<PgInterval>(field, raw_value)?;
+                        let micros = interval_to_duration(field, interval)?.as_micros();
+                        AvroValue::Long(i64::try_from(micros).unwrap_or(i64::MAX))
+                    }
                     _ => decode_raw::<String>(field, raw_value)?.into(),
                 }
             };
@@ -614,19 +697,19 @@ impl QueryExt<DatabaseDriver> for Query {
         crate::helper::prepare_sql_query(query, params, '$')
     }
 
+    #[inline]
     fn format_field(field: &str) -> Cow<'_, str> {
-        if field.contains('.') {
-            field
-                .split('.')
-                .map(|s| format!(r#""{s}""#))
-                .collect::<Vec<_>>()
-                .join(".")
-                .into()
-        } else {
-            format!(r#""{field}""#).into()
+        match super::NamingConvention::shared().column_name(field) {
+            Cow::Borrowed(field) => super::query::quote_field(field, '"'),
+            Cow::Owned(field) => super::query::quote_field(&field, '"').into_owned().into(),
         }
     }
 
+    #[inline]
+    fn quote_identifier(ident: &str) -> Cow<'_, str> {
+        super::query::quote_identifier(ident, '"')
+    }
+
     fn format_table_fields<M: Schema>(&self) -> Cow<'_, str> {
         let model_name = M::model_name();
         let fields = self.query_fields();
@@ -637,16 +720,12 @@ impl QueryExt<DatabaseDriver> for Query {
                 .iter()
                 .map(|field| {
                     if let Some((alias, expr)) = field.split_once(':') {
-                        let alias = Self::format_field(alias.trim());
+                        let alias = Self::quote_identifier(alias.trim());
                         format!(r#"{expr} AS {alias}"#)
                     } else if field.contains('.') {
-                        field
-                            .split('.')
-                            .map(|s| format!(r#""{s}""#))
-                            .collect::<Vec<_>>()
-                            .join(".")
+                        Self::format_field(field).into_owned()
                     } else {
-                        format!(r#""{model_name}"."{field}""#)
+                        Self::format_field(&format!("{model_name}.{field}")).into_owned()
                     }
                 })
                 .collect::<Vec<_>>()
@@ -659,13 +738,17 @@ impl QueryExt<DatabaseDriver> for Query {
     fn format_table_name<M: Schema>(&self) -> String {
         let table_name = M::table_name();
         let model_name = M::model_name();
-        format!(r#""{table_name}" AS "{model_name}""#)
+        format!(
+            "{} AS {}",
+            super::query::quote_identifier(table_name, '"'),
+            super::query::quote_identifier(model_name, '"')
+        )
     }
 
     #[inline]
     fn table_name_escaped<M: Schema>() -> String {
         let table_name = M::table_name();
-        format!(r#""{table_name}""#)
+        super::query::quote_identifier(table_name, '"').into_owned()
     }
 
     fn parse_text_search(filter: &Map) -> Option<String> {
@@ -679,3 +762,49 @@ impl QueryExt<DatabaseDriver> for Query {
         })
     }
 }
+
+/// Implements `Type` and `Decode` for the range types.
+macro_rules! impl_pg_range {
+    ($($ty:ty => $pg_ty:ty),* $(,)?) => {
+        $(
+            #[cfg(feature = "orm-sqlx")]
+            impl sqlx::Type<DatabaseDriver> for Range<$ty> {
+                #[inline]
+                fn type_info() -> sqlx::postgres::PgTypeInfo {
+                    <PgRange<$pg_ty> as sqlx::Type<DatabaseDriver>>::type_info()
+                }
+            }
+
+            #[cfg(feature = "orm-sqlx")]
+            impl<'r> sqlx::Decode<'r, DatabaseDriver> for Range<$ty> {
+                fn decode(value: sqlx::postgres::PgValueRef<'r>) -> Result<Self, BoxError> {
+                    let range = <PgRange<$pg_ty> as sqlx::Decode<'r, DatabaseDriver>>::decode(value)?;
+                    Ok(from_pg_range(range))
+                }
+            }
+        )*
+    };
+}
+
+impl_pg_range! {
+    i32 => i32,
+    i64 => i64,
+    Date => chrono::NaiveDate,
+    DateTime => chrono::DateTime<chrono::Local>,
+}
+
+/// Converts a `PgRange` into a `Range`.
+#[cfg(feature = "orm-sqlx")]
+fn from_pg_range<T, U: From<T>>(range: PgRange<T>) -> Range<U> {
+    let (start, include_start) = match range.start {
+        Bound::Included(value) => (Some(value.into()), true),
+        Bound::Excluded(value) => (Some(value.into()), false),
+        Bound::Unbounded => (None, false),
+    };
+    let (end, include_end) = match range.end {
+        Bound::Included(value) => (Some(value.into()), true),
+        Bound::Excluded(value) => (Some(value.into()), false),
+        Bound::Unbounded => (None, false),
+    };
+    Range::with_bounds(start, end, include_start, include_end)
+}
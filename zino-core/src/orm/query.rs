@@ -1,7 +1,7 @@
 use super::Schema;
 use crate::{
     extension::{JsonObjectExt, JsonValueExt},
-    model::EncodeColumn,
+    model::{EncodeColumn, Query},
     JsonValue, Map, SharedString,
 };
 use std::{borrow::Cow, fmt::Display};
@@ -41,6 +41,10 @@ pub(super) trait QueryExt<DB> {
     /// Formats a field for the query.
     fn format_field(field: &str) -> Cow<'_, str>;
 
+    /// Quotes an identifier with the quote character of the dialect.
+    /// Unlike [`format_field()`](Self::format_field), the naming conventions are not applied.
+    fn quote_identifier(ident: &str) -> Cow<'_, str>;
+
     /// Formats table fields.
     fn format_table_fields<M: Schema>(&self) -> Cow<'_, str>;
 
@@ -69,7 +73,7 @@ pub(super) trait QueryExt<DB> {
                 .iter()
                 .map(|field| {
                     if let Some((alias, expr)) = field.split_once(':') {
-                        let alias = Self::format_field(alias.trim());
+                        let alias = Self::quote_identifier(alias.trim());
                         format!(r#"{expr} AS {alias}"#).into()
                     } else {
                         Self::format_field(field)
@@ -93,20 +97,23 @@ pub(super) trait QueryExt<DB> {
         for (key, value) in filters {
             match key.as_str() {
                 "$and" => {
-                    if let Some(filters) = value.as_array() {
-                        let condition = Self::format_logical_filters::<M>(filters, " AND ");
+                    if let Some(condition) = value.as_array().and_then(|filters| {
+                        Self::format_logical_filters::<M>(filters, " AND ", false)
+                    }) {
                         logical_and_conditions.push(condition);
                     }
                 }
                 "$not" => {
-                    if let Some(filters) = value.as_array() {
-                        let condition = Self::format_logical_filters::<M>(filters, " AND ");
-                        logical_and_conditions.push(format!("(NOT {condition})"));
+                    if let Some(condition) = value.as_array().and_then(|filters| {
+                        Self::format_logical_filters::<M>(filters, " AND ", true)
+                    }) {
+                        logical_and_conditions.push(condition);
                     }
                 }
                 "$or" => {
-                    if let Some(filters) = value.as_array() {
-                        let condition = Self::format_logical_filters::<M>(filters, " OR ");
+                    if let Some(condition) = value.as_array().and_then(|filters| {
+                        Self::format_logical_filters::<M>(filters, " OR ", false)
+                    }) {
                         logical_and_conditions.push(condition);
                     }
                 }
@@ -194,8 +201,28 @@ pub(super) trait QueryExt<DB> {
         expression
     }
 
-    // Formats the filters with a logic operator.
-    fn format_logical_filters<M: Schema>(filters: &[JsonValue], operator: &str) -> String {
+    /// Formats the query filters with an additional condition
+    /// to generate SQL `WHERE` expression.
+    fn format_filters_with<M: Schema>(&self, condition: &str) -> String {
+        let filters = self.format_filters::<M>();
+        if let Some(expression) = filters.strip_prefix("WHERE ") {
+            format!("WHERE {condition} AND {expression}")
+        } else {
+            format!("WHERE {condition}{filters}")
+        }
+    }
+
+    // Formats the filters with a logic operator, which can be negated.
+    // It returns `None` if there are no conditions to apply.
+    fn format_logical_filters<M: Schema>(
+        filters: &[JsonValue],
+        operator: &str,
+        negated: bool,
+    ) -> Option<String> {
+        if filters.is_empty() {
+            return format_empty_group(operator, negated).map(|s| s.to_owned());
+        }
+
         let mut conditions = Vec::with_capacity(filters.len());
         for filter in filters {
             if let JsonValue::Object(filter) = filter {
@@ -203,26 +230,30 @@ pub(super) trait QueryExt<DB> {
                 for (key, value) in filter {
                     match key.as_str() {
                         "$and" => {
-                            if let Some(filters) = value.as_array() {
-                                let condition = Self::format_logical_filters::<M>(filters, " AND ");
+                            if let Some(condition) = value.as_array().and_then(|filters| {
+                                Self::format_logical_filters::<M>(filters, " AND ", false)
+                            }) {
                                 logical_and_conditions.push(condition);
                             }
                         }
                         "$not" => {
-                            if let Some(filters) = value.as_array() {
-                                let condition = Self::format_logical_filters::<M>(filters, " AND ");
-                                logical_and_conditions.push(format!("(NOT {condition})"));
+                            if let Some(condition) = value.as_array().and_then(|filters| {
+                                Self::format_logical_filters::<M>(filters, " AND ", true)
+                            }) {
+                                logical_and_conditions.push(condition);
                             }
                         }
                         "$nor" => {
-                            if let Some(filters) = value.as_array() {
-                                let condition = Self::format_logical_filters::<M>(filters, " OR ");
-                                logical_and_conditions.push(format!("(NOT {condition})"));
+                            if let Some(condition) = value.as_array().and_then(|filters| {
+                                Self::format_logical_filters::<M>(filters, " OR ", true)
+                            }) {
+                                logical_and_conditions.push(condition);
                             }
                         }
                         "$or" => {
-                            if let Some(filters) = value.as_array() {
-                                let condition = Self::format_logical_filters::<M>(filters, " OR ");
+                            if let Some(condition) = value.as_array().and_then(|filters| {
+                                Self::format_logical_filters::<M>(filters, " OR ", false)
+                            }) {
                                 logical_and_conditions.push(condition);
                             }
                         }
@@ -278,9 +309,11 @@ pub(super) trait QueryExt<DB> {
             }
         }
         if conditions.is_empty() {
-            String::new()
+            None
+        } else if negated {
+            Some(format!("(NOT ({}))", conditions.join(operator)))
         } else {
-            format!("({})", conditions.join(operator))
+            Some(format!("({})", conditions.join(operator)))
         }
     }
 
@@ -320,23 +353,9 @@ pub(super) trait QueryExt<DB> {
     }
 
     /// Formats the query sort to generate SQL `ORDER BY` expression.
+    #[inline]
     fn format_sort(&self) -> String {
-        let sort_order = self.query_order();
-        if sort_order.is_empty() {
-            String::new()
-        } else {
-            let sort_order = sort_order
-                .iter()
-                .map(|(sort, descending)| {
-                    if *descending {
-                        format!("{sort} DESC")
-                    } else {
-                        format!("{sort} ASC")
-                    }
-                })
-                .collect::<Vec<_>>();
-            format!("ORDER BY {}", sort_order.join(", "))
-        }
+        format_sort_order(self.query_order(), Self::format_field)
     }
 
     /// Formats the query pagination to generate SQL `LIMIT` expression.
@@ -350,3 +369,147 @@ pub(super) trait QueryExt<DB> {
         format!("LIMIT {limit} OFFSET {offset}")
     }
 }
+
+impl Query {
+    /// Returns the `SELECT` statement of the query for the model without executing it.
+    pub fn to_sql<M: Schema>(&self) -> String {
+        let table_name = self.format_table_name::<M>();
+        let projection = self.format_table_fields::<M>();
+        let filters = self.format_filters::<M>();
+        let sort = self.format_sort();
+        let pagination = self.format_pagination();
+        format!("SELECT {projection} FROM {table_name} {filters} {sort} {pagination};")
+    }
+}
+
+/// Formats the sort order to generate SQL `ORDER BY` expression.
+/// The fields are formatted in the same way as the filters,
+/// so that the naming conventions are applied.
+pub(super) fn format_sort_order(
+    sort_order: &[(SharedString, bool)],
+    format_field: impl Fn(&str) -> Cow<'_, str>,
+) -> String {
+    if sort_order.is_empty() {
+        String::new()
+    } else {
+        let sort_order = sort_order
+            .iter()
+            .map(|(sort, descending)| {
+                let field = format_field(sort.as_ref());
+                if *descending {
+                    format!("{field} DESC")
+                } else {
+                    format!("{field} ASC")
+                }
+            })
+            .collect::<Vec<_>>();
+        format!("ORDER BY {}", sort_order.join(", "))
+    }
+}
+
+/// Quotes an identifier with the quote character of the dialect,
+/// in which the embedded quote characters are escaped by doubling them.
+/// The identifier is kept as it is if it has already been quoted.
+pub(super) fn quote_identifier(ident: &str, quote: char) -> Cow<'_, str> {
+    if ident == "*" || is_quoted(ident) {
+        Cow::Borrowed(ident)
+    } else {
+        let escaped = ident.replace(quote, &format!("{quote}{quote}"));
+        Cow::Owned(format!("{quote}{escaped}{quote}"))
+    }
+}
+
+/// Quotes a field with the quote character of the dialect.
+/// Each part of a qualified name such as `user.order` is quoted separately.
+///
+/// As an escape hatch, the field is kept as it is if it has already been quoted
+/// or it is an expression such as `count(*)` or `data->>'name'`.
+pub(super) fn quote_field(field: &str, quote: char) -> Cow<'_, str> {
+    if is_quoted(field) || is_expression(field) {
+        Cow::Borrowed(field)
+    } else if field.contains('.') {
+        field
+            .split('.')
+            .map(|s| quote_identifier(s, quote))
+            .collect::<Vec<_>>()
+            .join(".")
+            .into()
+    } else {
+        quote_identifier(field, quote)
+    }
+}
+
+/// Returns `true` if the identifier has been quoted.
+fn is_quoted(ident: &str) -> bool {
+    let mut chars = ident.chars();
+    match (chars.next(), chars.next_back()) {
+        (Some('"'), Some('"')) | (Some('`'), Some('`')) | (Some('['), Some(']')) => true,
+        _ => false,
+    }
+}
+
+/// Returns `true` if the field is an expression instead of a name.
+/// Only the function calls, string literals, casts and JSON operators are regarded
+/// as the expressions, so a name with spaces such as `first name` is still quoted.
+fn is_expression(field: &str) -> bool {
+    field.contains(|c| matches!(c, '(' | ')' | '\'' | '"' | '`' | ':' | '>'))
+}
+
+/// Formats the condition of an empty group of filters with a logic operator.
+/// An empty `OR` group never holds, while an empty `AND` group always holds
+/// and can be omitted.
+fn format_empty_group(operator: &str, negated: bool) -> Option<&'static str> {
+    let holds = (operator.trim() == "AND") != negated;
+    (!holds).then_some("FALSE")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_empty_group, format_sort_order, quote_field};
+    use crate::{model::Query, orm::NamingConvention, LazyLock};
+    use std::borrow::Cow;
+
+    #[test]
+    fn it_quotes_fields() {
+        assert_eq!(quote_field("order", '"'), r#""order""#);
+        assert_eq!(quote_field("user.userName", '"'), r#""user"."userName""#);
+        assert_eq!(quote_field(r#""user"."order""#, '"'), r#""user"."order""#);
+        assert_eq!(quote_field("user.*", '`'), "`user`.*");
+        assert_eq!(quote_field("count(*)", '`'), "count(*)");
+        assert_eq!(quote_field("data->>'name'", '"'), "data->>'name'");
+        assert_eq!(quote_field("first name", '"'), r#""first name""#);
+        assert_eq!(quote_field("price::numeric", '"'), "price::numeric");
+    }
+
+    #[test]
+    fn it_formats_sort_with_column_case() {
+        static NAMING: LazyLock<NamingConvention> = LazyLock::new(|| {
+            let config = r#"column-case = "camelCase""#.parse().unwrap();
+            NamingConvention::with_config(&config)
+        });
+
+        fn format_field(field: &str) -> Cow<'_, str> {
+            match NAMING.column_name(field) {
+                Cow::Borrowed(field) => quote_field(field, '"'),
+                Cow::Owned(field) => quote_field(&field, '"').into_owned().into(),
+            }
+        }
+
+        let mut query = Query::default();
+        query.order_desc("updated_at");
+        query.order_asc("user.created_at");
+        query.order_asc("order");
+        assert_eq!(
+            format_sort_order(query.sort_order(), format_field),
+            r#"ORDER BY "updatedAt" DESC, "user"."createdAt" ASC, "order" ASC"#
+        );
+    }
+
+    #[test]
+    fn it_formats_empty_groups() {
+        assert_eq!(format_empty_group(" OR ", false), Some("FALSE"));
+        assert_eq!(format_empty_group(" AND ", false), None);
+        assert_eq!(format_empty_group(" AND ", true), Some("FALSE"));
+        assert_eq!(format_empty_group(" OR ", true), None);
+    }
+}
@@ -0,0 +1,273 @@
+use super::{executor::Executor, DatabaseContext, GlobalPool};
+use crate::{
+    datetime::DateTime,
+    error::Error,
+    extension::TomlTableExt,
+    model::{DecodeRow, QueryContext},
+    request::REDACTED,
+    state::State,
+    warn, LazyLock, Map, Uuid,
+};
+use parking_lot::{Mutex, RwLock};
+use serde::{Serialize, Serializer};
+use std::collections::{HashMap, VecDeque};
+
+/// A log of the recent SQL statements executed by the ORM for debugging.
+///
+/// It is enabled by default in the `dev` mode, and the statements can be viewed
+/// in the `/debug/queries` route of the debug server. The failed and timed-out statements
+/// are also logged, and the arguments are redacted when the entries are serialized.
+///
+/// ```toml
+/// [database.query-log]
+/// enabled = true
+/// capacity = 500
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct QueryLog;
+
+impl QueryLog {
+    /// Returns `true` if the query log is enabled.
+    #[inline]
+    pub fn is_enabled() -> bool {
+        SHARED_QUERY_LOG.is_some()
+    }
+
+    /// Returns the log entries with the newest first.
+    #[inline]
+    pub fn entries() -> Vec<QueryLogEntry> {
+        SHARED_QUERY_LOG
+            .as_ref()
+            .map(|log| log.entries.lock().iter().rev().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Gets a log entry by the query ID.
+    #[inline]
+    pub fn get(query_id: &Uuid) -> Option<QueryLogEntry> {
+        SHARED_QUERY_LOG.as_ref().and_then(|log| {
+            log.entries
+                .lock()
+                .iter()
+                .find(|entry| &entry.query_id == query_id)
+                .cloned()
+        })
+    }
+
+    /// Removes all the log entries.
+    #[inline]
+    pub fn clear() {
+        if let Some(log) = SHARED_QUERY_LOG.as_ref() {
+            log.entries.lock().clear();
+        }
+    }
+
+    /// Explains the execution plan of a logged statement with the connection pool
+    /// on which it has been executed.
+    pub async fn explain(query_id: &Uuid) -> Result<Vec<Map>, Error> {
+        let entry = Self::get(query_id)
+            .ok_or_else(|| warn!("404 Not Found: the query `{}` does not exist", query_id))?;
+        let pool_name = entry.pool.unwrap_or("main");
+        let connection_pool = GlobalPool::get(pool_name)
+            .ok_or_else(|| warn!("the connection pool `{}` does not exist", pool_name))?;
+        let sql = format!("{} {}", explain_keyword(), entry.query);
+        let pool = connection_pool.pool();
+        let rows = if entry.arguments.is_empty() {
+            pool.fetch(&sql).await?
+        } else {
+            pool.fetch_with(&sql, &entry.arguments).await?
+        };
+        let mut plan = Vec::with_capacity(rows.len());
+        for row in rows {
+            plan.push(Map::decode_row(&row)?);
+        }
+        Ok(plan)
+    }
+
+    /// Appends a log entry for the executed query.
+    pub(crate) fn record(ctx: &QueryContext) {
+        let Some(log) = SHARED_QUERY_LOG.as_ref() else {
+            return;
+        };
+
+        let query = ctx.query();
+        let model_name = ctx.model_name();
+        let pool = DatabaseContext::current_pool()
+            .map(|pool| pool.name())
+            .or_else(|| {
+                let is_read = ["SELECT", "WITH"].iter().any(|keyword| {
+                    query
+                        .trim_start()
+                        .get(..keyword.len())
+                        .is_some_and(|s| s.eq_ignore_ascii_case(keyword))
+                });
+                MODEL_POOLS
+                    .read()
+                    .get(model_name)
+                    .and_then(|&(reader, writer)| if is_read { reader } else { writer })
+            });
+        let span = tracing::Span::current()
+            .metadata()
+            .map(|metadata| format!("{}::{}", metadata.target(), metadata.name()));
+        let entry = QueryLogEntry {
+            query_id: ctx.query_id(),
+            model_name,
+            query: query.to_owned(),
+            arguments: ctx.arguments().to_vec(),
+            rows_affected: ctx.rows_affected(),
+            success: ctx.is_success(),
+            duration_millis: ctx.execution_time().as_secs_f64() * 1000.0,
+            span,
+            pool,
+            executed_at: DateTime::now(),
+        };
+
+        let mut entries = log.entries.lock();
+        while entries.len() >= log.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// Registers the connection pool of a model reader or writer.
+    pub(super) fn register_pool(model_name: &'static str, pool_name: &'static str, reader: bool) {
+        if !Self::is_enabled() {
+            return;
+        }
+
+        let mut model_pools = MODEL_POOLS.write();
+        let pools = model_pools.entry(model_name).or_default();
+        if reader {
+            pools.0 = Some(pool_name);
+        } else {
+            pools.1 = Some(pool_name);
+        }
+    }
+}
+
+/// An entry of the query log.
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryLogEntry {
+    /// Query ID.
+    query_id: Uuid,
+    /// Model name.
+    model_name: &'static str,
+    /// SQL statement.
+    query: String,
+    /// Arguments, which are redacted when serialized.
+    #[serde(serialize_with = "serialize_redacted")]
+    arguments: Vec<String>,
+    /// Number of rows affected or fetched.
+    rows_affected: Option<u64>,
+    /// Indicates the query execution is successful or not.
+    success: bool,
+    /// Duration in milliseconds.
+    duration_millis: f64,
+    /// Span in which the query has been executed.
+    span: Option<String>,
+    /// Name of the connection pool.
+    pool: Option<&'static str>,
+    /// Execution time.
+    executed_at: DateTime,
+}
+
+impl QueryLogEntry {
+    /// Returns the query ID.
+    #[inline]
+    pub fn query_id(&self) -> Uuid {
+        self.query_id
+    }
+
+    /// Returns the model name.
+    #[inline]
+    pub fn model_name(&self) -> &'static str {
+        self.model_name
+    }
+
+    /// Returns the SQL statement.
+    #[inline]
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Returns the number of rows affected or fetched.
+    #[inline]
+    pub fn rows_affected(&self) -> Option<u64> {
+        self.rows_affected
+    }
+
+    /// Returns `true` if the query execution is successful.
+    #[inline]
+    pub fn is_success(&self) -> bool {
+        self.success
+    }
+
+    /// Returns the duration in milliseconds.
+    #[inline]
+    pub fn duration_millis(&self) -> f64 {
+        self.duration_millis
+    }
+
+    /// Returns the span in which the query has been executed.
+    #[inline]
+    pub fn span(&self) -> Option<&str> {
+        self.span.as_deref()
+    }
+
+    /// Returns the name of the connection pool.
+    #[inline]
+    pub fn pool(&self) -> Option<&'static str> {
+        self.pool
+    }
+}
+
+/// Serializes the arguments with the values redacted.
+fn serialize_redacted<S: Serializer>(
+    arguments: &[String],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(arguments.iter().map(|_| REDACTED))
+}
+
+/// Returns the keyword for explaining the execution plan.
+#[inline]
+fn explain_keyword() -> &'static str {
+    if super::DRIVER_NAME == "sqlite" {
+        "EXPLAIN QUERY PLAN"
+    } else {
+        "EXPLAIN"
+    }
+}
+
+/// A ring buffer of the query log entries.
+#[derive(Debug)]
+struct QueryLogBuffer {
+    /// Max number of the entries.
+    capacity: usize,
+    /// Log entries.
+    entries: Mutex<VecDeque<QueryLogEntry>>,
+}
+
+/// Shared query log.
+static SHARED_QUERY_LOG: LazyLock<Option<QueryLogBuffer>> = LazyLock::new(|| {
+    let app_state = State::shared();
+    let config = app_state
+        .get_config("database")
+        .and_then(|config| config.get_table("query-log"));
+    let enabled = config
+        .and_then(|config| config.get_bool("enabled"))
+        .unwrap_or_else(|| app_state.env().is_dev());
+    let capacity = config
+        .and_then(|config| config.get_usize("capacity"))
+        .unwrap_or(500)
+        .max(1);
+    enabled.then(|| QueryLogBuffer {
+        capacity,
+        entries: Mutex::new(VecDeque::with_capacity(capacity)),
+    })
+});
+
+/// Connection pools of the model readers and writers.
+static MODEL_POOLS: LazyLock<
+    RwLock<HashMap<&'static str, (Option<&'static str>, Option<&'static str>)>>,
+> = LazyLock::new(|| RwLock::new(HashMap::new()));
@@ -0,0 +1,118 @@
+//! Data retention policies for the models.
+
+use super::ArchiveSink;
+use crate::{
+    application::PROJECT_DIR,
+    extension::{JsonObjectExt, TomlTableExt},
+    schedule::{AsyncCronJob, AsyncJob},
+    state::State,
+    LazyLock,
+};
+use toml::Table;
+
+/// Creates a scheduled job for purging the expired rows of a model.
+/// The job is configured by the `[retention]` table:
+///
+/// ```toml
+/// [retention]
+/// cron = "0 0 2 * * *"
+/// batch-size = 1000
+/// dry-run = false
+/// archive-dir = "local/archive"
+/// archive-format = "ndjson"
+/// ```
+///
+/// The expired rows can also be archived to a storage backend by specifying `archive-accessor`.
+///
+/// It is used by the `Schema` derive macro and should not be called directly.
+#[doc(hidden)]
+pub fn new_retention_job(model_name: &str, exec: AsyncCronJob) -> AsyncJob {
+    let config = &SHARED_RETENTION_CONFIG;
+    let mut job = AsyncJob::new(&config.cron, exec).name(format!("{model_name}_retention"));
+    job.data_mut().upsert("dry_run", config.dry_run);
+    job
+}
+
+/// Returns the batch size for purging the expired rows.
+#[inline]
+pub(super) fn batch_size() -> usize {
+    SHARED_RETENTION_CONFIG.batch_size
+}
+
+/// Returns `true` if the expired rows are only counted.
+#[inline]
+pub(super) fn dry_run() -> bool {
+    SHARED_RETENTION_CONFIG.dry_run
+}
+
+/// Returns the sink for archiving the expired rows before purging.
+#[inline]
+pub(super) fn archive_sink() -> Option<&'static ArchiveSink> {
+    SHARED_RETENTION_CONFIG.archive_sink.as_ref()
+}
+
+/// Retention config.
+struct RetentionConfig {
+    /// Cron expression of the purge job.
+    cron: String,
+    /// Batch size.
+    batch_size: usize,
+    /// Flag to indicate whether the expired rows are only counted.
+    dry_run: bool,
+    /// Sink for archiving the expired rows.
+    archive_sink: Option<ArchiveSink>,
+}
+
+impl RetentionConfig {
+    /// Creates a new instance with the configuration.
+    fn with_config(config: &Table) -> Self {
+        Self {
+            cron: config.get_str("cron").unwrap_or("@daily").to_owned(),
+            batch_size: config.get_usize("batch-size").unwrap_or(1000).max(1),
+            dry_run: config.get_bool("dry-run").unwrap_or_default(),
+            archive_sink: Self::parse_archive_sink(config),
+        }
+    }
+
+    /// Parses the archive sink.
+    fn parse_archive_sink(config: &Table) -> Option<ArchiveSink> {
+        #[cfg(feature = "accessor")]
+        let sink = if let Some(accessor) = config.get_str("archive-accessor") {
+            let sink = ArchiveSink::with_accessor(accessor);
+            if sink.is_none() {
+                tracing::warn!("the storage accessor `{accessor}` does not exist");
+            }
+            sink
+        } else {
+            config
+                .get_str("archive-dir")
+                .map(|dir| ArchiveSink::local(PROJECT_DIR.join(dir)))
+        };
+        #[cfg(not(feature = "accessor"))]
+        let sink = config
+            .get_str("archive-dir")
+            .map(|dir| ArchiveSink::local(PROJECT_DIR.join(dir)));
+
+        sink.map(|mut sink| {
+            if let Some(format) = config.get_str("archive-format") {
+                match format.parse() {
+                    Ok(format) => sink = sink.format(format),
+                    Err(err) => tracing::warn!("{err}"),
+                }
+            }
+            if let Some(chunk_size) = config.get_usize("archive-chunk-size") {
+                sink = sink.chunk_size(chunk_size);
+            }
+            sink
+        })
+    }
+}
+
+/// Shared retention config.
+static SHARED_RETENTION_CONFIG: LazyLock<RetentionConfig> = LazyLock::new(|| {
+    let config = State::shared()
+        .get_config("retention")
+        .cloned()
+        .unwrap_or_default();
+    RetentionConfig::with_config(&config)
+});
@@ -2,7 +2,7 @@ use super::{column::ColumnExt, query::QueryExt, schema::Schema, DatabaseDriver};
 use crate::{error::Error, extension::JsonValueExt, model::Query, Map};
 use futures::TryStreamExt;
 use sqlx::{Decode, Row, Type};
-use std::{fmt::Display, sync::atomic::Ordering::Relaxed};
+use std::fmt::Display;
 
 /// Query on scalar values.
 pub trait ScalarQuery<K>: Schema<PrimaryKey = K>
@@ -15,7 +15,9 @@ where
     where
         T: Send + Unpin + Type<DatabaseDriver> + for<'r> Decode<'r, DatabaseDriver>,
     {
-        Self::before_query(query).await?;
+        let mut query = query.clone();
+        Self::before_query(&mut query).await?;
+        let query = &query;
 
         let table_name = query.format_table_name::<Self>();
         let projection = query.format_projection();
@@ -39,7 +41,13 @@ where
     where
         T: Send + Unpin + Type<DatabaseDriver> + for<'r> Decode<'r, DatabaseDriver>,
     {
-        Self::before_query(query).await?;
+        let mut query = query.clone();
+        Self::before_query(&mut query).await?;
+
+        // The extra row is fetched to detect whether the results are truncated.
+        let max_rows = Self::max_rows(&query);
+        query.cap_limit(max_rows);
+        let query = &query;
 
         let table_name = query.format_table_name::<Self>();
         let projection = query.format_projection();
@@ -53,12 +61,12 @@ where
         let pool = Self::acquire_reader().await?.pool();
         let mut rows = sqlx::query(&sql).fetch(pool);
         let mut data = Vec::new();
-        let mut max_rows = super::MAX_ROWS.load(Relaxed);
         while let Some(row) = rows.try_next().await? {
-            if max_rows > 0 {
+            if data.len() < max_rows {
                 data.push(row.try_get_unchecked(0)?);
-                max_rows -= 1;
             } else {
+                let model_name = Self::MODEL_NAME;
+                tracing::warn!(model_name, max_rows, "query results are truncated");
                 break;
             }
         }
@@ -108,14 +116,15 @@ where
             arguments.push(value.to_string_unquoted());
         }
 
+        let max_rows = Self::max_rows(&Query::default());
         let mut rows = query.fetch(Self::acquire_reader().await?.pool());
         let mut data = Vec::new();
-        let mut max_rows = super::MAX_ROWS.load(Relaxed);
         while let Some(row) = rows.try_next().await? {
-            if max_rows > 0 {
+            if data.len() < max_rows {
                 data.push(row.try_get_unchecked(0)?);
-                max_rows -= 1;
             } else {
+                let model_name = Self::MODEL_NAME;
+                tracing::warn!(model_name, max_rows, "query results are truncated");
                 break;
             }
         }
@@ -131,21 +140,22 @@ where
     where
         T: Send + Unpin + Type<DatabaseDriver> + for<'r> Decode<'r, DatabaseDriver>,
     {
+        let mut query = Query::default();
+        Self::before_query(&mut query).await?;
+
         let primary_key_name = Self::PRIMARY_KEY_NAME;
+        let primary_key_field = Query::format_field(primary_key_name);
         let table_name = Query::table_name_escaped::<Self>();
         let projection = Query::format_field(column);
         let placeholder = Query::placeholder(1);
-        let sql = if cfg!(feature = "orm-postgres") {
+        let condition = if cfg!(feature = "orm-postgres") {
             let type_annotation = Self::primary_key_column().type_annotation();
-            format!(
-                "SELECT {projection} FROM {table_name} \
-                    WHERE {primary_key_name} = ({placeholder}){type_annotation};"
-            )
+            format!("{primary_key_field} = ({placeholder}){type_annotation}")
         } else {
-            format!(
-                "SELECT {projection} FROM {table_name} WHERE {primary_key_name} = {placeholder};"
-            )
+            format!("{primary_key_field} = {placeholder}")
         };
+        let filters = query.format_filters_with::<Self>(&condition);
+        let sql = format!("SELECT {projection} FROM {table_name} {filters};");
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(sql);
 
@@ -163,7 +173,9 @@ where
     where
         K: Send + Unpin + Type<DatabaseDriver> + for<'r> Decode<'r, DatabaseDriver>,
     {
-        Self::before_query(query).await?;
+        let mut query = query.clone();
+        Self::before_query(&mut query).await?;
+        let query = &query;
 
         let projection = Self::PRIMARY_KEY_NAME;
         let table_name = query.format_table_name::<Self>();
@@ -186,7 +198,13 @@ where
     where
         K: Send + Unpin + Type<DatabaseDriver> + for<'r> Decode<'r, DatabaseDriver>,
     {
-        Self::before_query(query).await?;
+        let mut query = query.clone();
+        Self::before_query(&mut query).await?;
+
+        // The extra row is fetched to detect whether the results are truncated.
+        let max_rows = Self::max_rows(&query);
+        query.cap_limit(max_rows);
+        let query = &query;
 
         let projection = Self::PRIMARY_KEY_NAME;
         let table_name = query.format_table_name::<Self>();
@@ -200,12 +218,12 @@ where
         let pool = Self::acquire_reader().await?.pool();
         let mut rows = sqlx::query(&sql).fetch(pool);
         let mut data = Vec::new();
-        let mut max_rows = super::MAX_ROWS.load(Relaxed);
         while let Some(row) = rows.try_next().await? {
-            if max_rows > 0 {
+            if data.len() < max_rows {
                 data.push(row.try_get_unchecked(0)?);
-                max_rows -= 1;
             } else {
+                let model_name = Self::MODEL_NAME;
+                tracing::warn!(model_name, max_rows, "query results are truncated");
                 break;
             }
         }
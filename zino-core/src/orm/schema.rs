@@ -1,16 +1,34 @@
 use super::{
-    column::ColumnExt, mutation::MutationExt, query::QueryExt, ConnectionPool, DatabaseRow,
-    Executor, GlobalPool, ModelHelper,
+    column::ColumnExt,
+    copy, executor,
+    inspect::{self, SchemaDiff, TypeMismatch},
+    mutation::MutationExt,
+    partition,
+    query::QueryExt,
+    retention, view, ArchiveManifest, ArchiveSink, ConnectionPool, DatabaseRow, Executor,
+    GlobalPool, ModelHelper,
 };
 use crate::{
     bail,
+    datetime::{self, Date, DateTime},
     error::Error,
     extension::{JsonObjectExt, JsonValueExt},
-    model::{Column, DecodeRow, EncodeColumn, ModelHooks, Mutation, Query, QueryContext},
+    model::{
+        Column, DecodeRow, EncodeColumn, IdCodec, ModelHooks, Mutation, PrimaryKeyStrategy, Query,
+        QueryContext,
+    },
+    schedule::AsyncJob,
     warn, JsonValue, Map,
 };
+use futures::{
+    channel::mpsc,
+    future,
+    stream::{self, BoxStream},
+    SinkExt, Stream, StreamExt,
+};
 use serde::de::DeserializeOwned;
-use std::{fmt::Display, sync::atomic::Ordering::Relaxed};
+use sqlx::Acquire;
+use std::{fmt::Display, pin::pin, sync::atomic::Ordering::Relaxed, time::Duration};
 
 /// Database schema.
 ///
@@ -21,12 +39,39 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
 
     /// Primary key name.
     const PRIMARY_KEY_NAME: &'static str = "id";
+    /// Primary key strategy.
+    const PRIMARY_KEY_STRATEGY: PrimaryKeyStrategy = PrimaryKeyStrategy::UuidV7;
     /// Reader name.
     const READER_NAME: &'static str = "main";
     /// Writer name.
     const WRITER_NAME: &'static str = "main";
     /// Optional custom table name.
     const TABLE_NAME: Option<&'static str> = None;
+    /// Optional retention period of the rows, such as `90d`.
+    const RETENTION: Option<&'static str> = None;
+    /// Field to determine whether a row is expired.
+    const RETENTION_FIELD: &'static str = "created_at";
+    /// Optional partition method of the table, such as `range(created_at)`.
+    const PARTITION_BY: Option<&'static str> = None;
+    /// Optional interval of the range partitions, such as `1 month`.
+    const PARTITION_INTERVAL: Option<&'static str> = None;
+    /// Optional parent field for a hierarchical model, such as `parent_id`.
+    const TREE_PARENT_FIELD: Option<&'static str> = None;
+    /// Field to store the materialized path of a hierarchical model.
+    const TREE_PATH_FIELD: &'static str = "path";
+    /// Optional field to store the manual ordering position, such as `position`.
+    const POSITION_FIELD: Option<&'static str> = None;
+    /// Fields exposed as public IDs encoded by the shared [`IdCodec`].
+    const PUBLIC_ID_FIELDS: &'static [&'static str] = &[];
+    /// Computed fields derived from the methods of the model when it is serialized.
+    const COMPUTED_FIELDS: &'static [&'static str] = &[];
+    /// Optional max number of rows returned by a query,
+    /// which is capped by the `max-rows` setting in the `[database]` table.
+    const MAX_ROWS: Option<usize> = None;
+    /// Optional SQL query of the view backing a read-only model.
+    const VIEW: Option<&'static str> = None;
+    /// A flag to indicate whether the view is materialized. It is only supported by PostgreSQL.
+    const MATERIALIZED_VIEW: bool = false;
 
     /// Returns the primary key.
     fn primary_key(&self) -> &Self::PrimaryKey;
@@ -75,7 +120,10 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     /// Returns the table name.
     #[inline]
     fn table_name() -> &'static str {
-        Self::TABLE_NAME.unwrap_or_else(|| [Self::table_prefix(), Self::MODEL_NAME].concat().leak())
+        Self::TABLE_NAME.unwrap_or_else(|| {
+            let table_name = super::NamingConvention::shared().table_name(Self::MODEL_NAME);
+            [Self::table_prefix(), &table_name].concat().leak()
+        })
     }
 
     /// Returns the model namespace.
@@ -84,6 +132,13 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         [Self::namespace_prefix(), Self::MODEL_NAME].concat().leak()
     }
 
+    /// Returns the max number of rows returned by the query, which is determined by
+    /// the query and the model in order, and capped by the `max-rows` setting.
+    #[inline]
+    fn max_rows(query: &Query) -> usize {
+        query.resolve_max_rows(Self::MAX_ROWS, super::MAX_ROWS.load(Relaxed))
+    }
+
     /// Returns the primary key as a JSON value.
     #[inline]
     fn primary_key_value(&self) -> JsonValue {
@@ -129,6 +184,17 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
             .find(|col| col.name() == key && !col.is_read_only())
     }
 
+    /// Gets a column which references the model `M`.
+    /// If the field is specified, the column name should also be matched.
+    #[inline]
+    fn get_reference_column<M: Schema>(field: Option<&str>) -> Option<&'static Column<'static>> {
+        let table_name = M::table_name();
+        Self::columns().iter().find(|col| {
+            col.reference().is_some_and(|r| r.name() == table_name)
+                && (field.is_none() || field == Some(col.name()))
+        })
+    }
+
     /// Returns `true` if the model has a column for the specific field.
     #[inline]
     fn has_column(key: &str) -> bool {
@@ -144,6 +210,93 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         Self::columns().iter().any(|col| col.name() == key)
     }
 
+    /// Encodes the values of the public ID fields in the model data.
+    fn encode_public_ids(data: &mut Map) -> Result<(), Error> {
+        let codec = IdCodec::shared();
+        for &field in Self::PUBLIC_ID_FIELDS {
+            match data.get_mut(field) {
+                Some(JsonValue::Array(vec)) => {
+                    for value in vec.iter_mut() {
+                        if !value.is_null() {
+                            *value = codec.encode(&value.to_string_unquoted())?.into();
+                        }
+                    }
+                }
+                Some(value) => {
+                    if !value.is_null() {
+                        *value = codec.encode(&value.to_string_unquoted())?.into();
+                    }
+                }
+                None => (),
+            }
+        }
+        Ok(())
+    }
+
+    /// Decodes the values of the public ID fields in the model data.
+    fn decode_public_ids(data: &mut Map) -> Result<(), Error> {
+        let codec = IdCodec::shared();
+        for &field in Self::PUBLIC_ID_FIELDS {
+            match data.get_mut(field) {
+                Some(JsonValue::Array(vec)) => {
+                    for value in vec.iter_mut() {
+                        if let Some(public_id) = value.as_str() {
+                            *value = codec.decode(public_id)?.into();
+                        }
+                    }
+                }
+                Some(value) => {
+                    if let Some(public_id) = value.as_str() {
+                        *value = codec.decode(public_id)?.into();
+                    }
+                }
+                None => (),
+            }
+        }
+        Ok(())
+    }
+
+    /// Decodes the values of the public ID fields in the query filters,
+    /// including the operands of the comparison operators.
+    fn decode_public_id_filters(query: &mut Query) -> Result<(), Error> {
+        let codec = IdCodec::shared();
+        for &field in Self::PUBLIC_ID_FIELDS {
+            if let Some(mut filter) = query.remove_filter(field) {
+                decode_public_id_filter(codec, &mut filter)?;
+                query.add_filter(field, filter);
+            }
+        }
+        Ok(())
+    }
+
+    /// Decodes the values of the public ID fields in a JSON merge patch
+    /// or the operations of a JSON patch.
+    fn decode_patch_public_ids(patch: &mut JsonValue) -> Result<(), Error> {
+        match patch {
+            JsonValue::Object(data) => Self::decode_public_ids(data),
+            JsonValue::Array(operations) => {
+                let codec = IdCodec::shared();
+                for operation in operations.iter_mut().filter_map(|v| v.as_object_mut()) {
+                    let is_public_id = operation.get_str("path").is_some_and(|path| {
+                        path.strip_prefix('/')
+                            .and_then(|path| path.split('/').next())
+                            .is_some_and(|field| Self::PUBLIC_ID_FIELDS.contains(&field))
+                    });
+                    if let Some(value) = operation.get_mut("value").filter(|_| is_public_id) {
+                        decode_public_id_filter(codec, value)?;
+                    }
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Computes the values of the computed fields in the model data.
+    /// The dependent fields should be present in the data.
+    #[inline]
+    fn compute_fields(_data: &mut Map) {}
+
     /// Constructs a default `Query` for the model.
     #[inline]
     fn default_query() -> Query {
@@ -162,9 +315,51 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         mutation
     }
 
+    /// Returns the retention period of the rows.
+    fn retention_period() -> Option<Duration> {
+        let retention = Self::RETENTION?;
+        match datetime::parse_duration(retention) {
+            Ok(period) => Some(period),
+            Err(err) => {
+                let model_name = Self::MODEL_NAME;
+                tracing::warn!(model_name, "invalid retention period `{retention}`: {err}");
+                None
+            }
+        }
+    }
+
+    /// Returns a scheduled job for purging the expired rows.
+    /// It is implemented by the `Schema` derive macro if the `retention` attribute is specified.
+    #[inline]
+    fn retention_job() -> Option<AsyncJob> {
+        None
+    }
+
+    /// Returns a scheduled job for creating the upcoming partitions and pruning the expired ones.
+    /// It is implemented by the `Schema` derive macro if the `partition_by` attribute is specified.
+    #[inline]
+    fn partition_job() -> Option<AsyncJob> {
+        None
+    }
+
+    /// Returns a scheduled job for refreshing the materialized view.
+    /// It is implemented by the `Schema` derive macro if the `materialized_view` attribute
+    /// is specified.
+    #[inline]
+    fn refresh_job() -> Option<AsyncJob> {
+        None
+    }
+
     /// Initializes the model reader.
     #[inline]
     fn init_reader() -> Result<&'static ConnectionPool, Error> {
+        super::QueryLog::register_pool(Self::MODEL_NAME, Self::READER_NAME, true);
+        super::NamingConvention::shared().register_fields(
+            Self::columns()
+                .iter()
+                .filter(|col| !col.extra().contains_key("column_name"))
+                .map(|col| col.name()),
+        );
         GlobalPool::get(Self::READER_NAME)
             .ok_or_else(|| warn!("connection to the database is unavailable"))
     }
@@ -172,6 +367,13 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     /// Initializes the model writer.
     #[inline]
     fn init_writer() -> Result<&'static ConnectionPool, Error> {
+        super::QueryLog::register_pool(Self::MODEL_NAME, Self::WRITER_NAME, false);
+        super::NamingConvention::shared().register_fields(
+            Self::columns()
+                .iter()
+                .filter(|col| !col.extra().contains_key("column_name"))
+                .map(|col| col.name()),
+        );
         GlobalPool::get(Self::WRITER_NAME)
             .ok_or_else(|| warn!("connection to the database is unavailable"))
     }
@@ -182,35 +384,165 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
             return Ok(());
         }
         Self::before_create_table().await?;
+        if Self::VIEW.is_some() {
+            Self::create_view().await?;
+            return Self::after_create_table().await;
+        }
 
         let primary_key_name = Self::PRIMARY_KEY_NAME;
         let table_name = Self::table_name();
         let table_name_escaped = Query::table_name_escaped::<Self>();
         let columns = Self::columns();
+        let partition_column = partition::parse_spec::<Self>()
+            .filter(|_| cfg!(feature = "orm-postgres"))
+            .map(|(column, _)| column);
         let mut definitions = columns
             .iter()
-            .map(|col| col.field_definition(primary_key_name))
+            .map(|col| {
+                if partition_column.is_some() {
+                    // The primary key of a partitioned table should include the partition column.
+                    col.field_definition("")
+                } else {
+                    col.field_definition(primary_key_name)
+                }
+            })
             .collect::<Vec<_>>();
+        if let Some(partition_column) = partition_column {
+            let primary_key_field = Query::format_field(primary_key_name);
+            if partition_column == primary_key_name {
+                definitions.push(format!("PRIMARY KEY ({primary_key_field})"));
+            } else {
+                let partition_field = Query::format_field(partition_column);
+                definitions.push(format!(
+                    "PRIMARY KEY ({primary_key_field}, {partition_field})"
+                ));
+            }
+        }
         for col in columns {
-            let mut constraints = col.constraints();
-            if !constraints.is_empty() {
-                definitions.append(&mut constraints);
+            for (constraint_name, constraint) in col.constraints(table_name) {
+                definitions.push(format!("CONSTRAINT {constraint_name} {constraint}"));
             }
         }
 
         let definitions = definitions.join(",\n  ");
-        let sql = format!("CREATE TABLE IF NOT EXISTS {table_name_escaped} (\n  {definitions}\n);");
+        let sql = if let Some(partition_column) = partition_column {
+            let partition_column = Query::format_field(partition_column);
+            format!(
+                "CREATE TABLE IF NOT EXISTS {table_name_escaped} (\n  {definitions}\n) \
+                    PARTITION BY RANGE ({partition_column});"
+            )
+        } else {
+            format!("CREATE TABLE IF NOT EXISTS {table_name_escaped} (\n  {definitions}\n);")
+        };
         if let Err(err) = Self::init_writer()?.pool().execute(&sql).await {
             tracing::error!(table_name, "fail to execute `{sql}`");
             return Err(err);
         }
+        if partition_column.is_some() {
+            Self::create_partitions().await?;
+        }
         Self::after_create_table().await?;
         Ok(())
     }
 
+    /// Creates or replaces a database view for the model.
+    /// The materialized view is recreated only if the definition has been changed.
+    async fn create_view() -> Result<(), Error> {
+        let Some(definition) = Self::VIEW else {
+            return Ok(());
+        };
+        let definition = definition.trim().trim_end_matches(';');
+        let table_name = Self::table_name();
+        let table_name_escaped = Query::table_name_escaped::<Self>();
+        let pool = Self::init_writer()?.pool();
+        let statements = if Self::MATERIALIZED_VIEW && cfg!(feature = "orm-postgres") {
+            let checksum = view::checksum(definition);
+            let sql = format!(
+                "SELECT obj_description(to_regclass('{table_name_escaped}'), 'pg_class') \
+                    AS comment;"
+            );
+            let row = pool.fetch_one(&sql).await?;
+            if Map::decode_row(&row)?.get_str("comment") == Some(checksum.as_str()) {
+                return Ok(());
+            }
+            vec![
+                format!("DROP MATERIALIZED VIEW IF EXISTS {table_name_escaped};"),
+                format!("CREATE MATERIALIZED VIEW {table_name_escaped} AS {definition};"),
+                format!("COMMENT ON MATERIALIZED VIEW {table_name_escaped} IS '{checksum}';"),
+            ]
+        } else {
+            if Self::MATERIALIZED_VIEW {
+                let model_name = Self::MODEL_NAME;
+                tracing::warn!(
+                    model_name,
+                    table_name,
+                    "materialized views are not supported, a regular view is created instead"
+                );
+            }
+            if cfg!(feature = "orm-sqlite") {
+                vec![
+                    format!("DROP VIEW IF EXISTS {table_name_escaped};"),
+                    format!("CREATE VIEW {table_name_escaped} AS {definition};"),
+                ]
+            } else {
+                let sql = format!("CREATE OR REPLACE VIEW {table_name_escaped} AS {definition};");
+                if pool.execute(&sql).await.is_ok() {
+                    return Ok(());
+                }
+
+                // The view should be recreated if the columns have been changed.
+                vec![
+                    format!("DROP VIEW IF EXISTS {table_name_escaped};"),
+                    format!("CREATE VIEW {table_name_escaped} AS {definition};"),
+                ]
+            }
+        };
+
+        // The view is replaced inside of a transaction so that the readers never see
+        // a missing relation. Note that the DDL statements are not transactional in MySQL.
+        let mut transaction = pool.begin().await?;
+        let connection = transaction.acquire().await?;
+        for sql in statements {
+            if let Err(err) = connection.execute(&sql).await {
+                tracing::error!(table_name, "fail to execute `{sql}`");
+                return Err(err);
+            }
+        }
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Refreshes the materialized view for the model.
+    /// Refreshing it concurrently requires a unique index on the view.
+    async fn refresh_view(concurrently: bool) -> Result<(), Error> {
+        if Self::VIEW.is_none() || !Self::MATERIALIZED_VIEW {
+            bail!(
+                "the model `{}` is not backed by a materialized view",
+                Self::MODEL_NAME
+            );
+        }
+        if !cfg!(feature = "orm-postgres") {
+            // A regular view is always up to date.
+            return Ok(());
+        }
+
+        let table_name = Self::table_name();
+        let table_name_escaped = Query::table_name_escaped::<Self>();
+        let sql = if concurrently {
+            format!("REFRESH MATERIALIZED VIEW CONCURRENTLY {table_name_escaped};")
+        } else {
+            format!("REFRESH MATERIALIZED VIEW {table_name_escaped};")
+        };
+        if let Err(err) = Self::init_writer()?.pool().execute(&sql).await {
+            tracing::error!(table_name, "fail to execute `{sql}`");
+            return Err(err);
+        }
+        Ok(())
+    }
+
     /// Synchronizes the table schema for the model.
     async fn synchronize_schema() -> Result<(), Error> {
-        if !super::AUTO_MIGRATION.load(Relaxed) {
+        if !super::AUTO_MIGRATION.load(Relaxed) || Self::VIEW.is_some() {
             return Ok(());
         }
 
@@ -253,10 +585,8 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         let primary_key_name = Self::PRIMARY_KEY_NAME;
         for col in Self::columns() {
             let column_type = col.column_type();
-            let column_name = col
-                .extra()
-                .get_str("column_name")
-                .unwrap_or_else(|| col.name());
+            let column_name = col.column_name();
+            let column_name = column_name.as_ref();
             let column_opt = data.iter().find(|d| {
                 d.get_str("column_name")
                     .or_else(|| d.get_str("COLUMN_NAME"))
@@ -297,11 +627,54 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
                         "`NOT NULL` constraint of `{column_name}` should be consistent",
                     );
                 }
+                if let Some(expr) = col.default_expression() {
+                    if column_default.is_none() {
+                        // SQLite does not support altering the default value of a column.
+                        if cfg!(feature = "orm-sqlite") {
+                            tracing::warn!(
+                                model_name,
+                                table_name,
+                                column_name,
+                                "default expression of `{column_name}` should be `{expr}`",
+                            );
+                        } else {
+                            let column_name_escaped = Query::quote_identifier(column_name);
+                            let sql = format!(
+                                "ALTER TABLE {table_name_escaped} \
+                                    ALTER COLUMN {column_name_escaped} SET DEFAULT ({expr});"
+                            );
+                            pool.execute(&sql).await?;
+                            tracing::warn!(
+                                model_name,
+                                table_name,
+                                column_name,
+                                "default expression of `{column_name}` has been set",
+                            );
+                        }
+                    }
+                }
             } else {
-                let column_definition = col.field_definition(primary_key_name);
+                let mut column_definition = col.field_definition(primary_key_name);
+                let backfilled_expression = col
+                    .default_expression()
+                    .filter(|_| cfg!(feature = "orm-sqlite"));
+                if let Some(expr) = backfilled_expression {
+                    // SQLite does not allow adding a column with a non-constant default,
+                    // so the existing rows are backfilled with the expression instead.
+                    column_definition =
+                        column_definition.replace(&format!(" DEFAULT ({expr})"), "");
+                }
+
                 let sql =
                     format!("ALTER TABLE {table_name_escaped} ADD COLUMN {column_definition};");
                 pool.execute(&sql).await?;
+                if let Some(expr) = backfilled_expression {
+                    let column_name_escaped = Query::quote_identifier(column_name);
+                    let sql = format!(
+                        "UPDATE {table_name_escaped} SET {column_name_escaped} = ({expr});"
+                    );
+                    pool.execute(&sql).await?;
+                }
                 tracing::warn!(
                     model_name,
                     table_name,
@@ -311,6 +684,66 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
                 );
             }
         }
+
+        // SQLite does not support adding constraints to an existing table.
+        if cfg!(any(
+            feature = "orm-mariadb",
+            feature = "orm-mysql",
+            feature = "orm-postgres",
+            feature = "orm-tidb"
+        )) {
+            let table_schema = if cfg!(any(
+                feature = "orm-mariadb",
+                feature = "orm-mysql",
+                feature = "orm-tidb"
+            )) {
+                connection_pool.database()
+            } else {
+                "public"
+            };
+            let sql = format!(
+                "SELECT constraint_name FROM information_schema.table_constraints \
+                    WHERE table_schema = '{table_schema}' AND table_name = '{table_name}';"
+            );
+            let rows = pool.fetch(&sql).await?;
+            let mut constraint_names = Vec::with_capacity(rows.len());
+            for row in rows {
+                let map = Map::decode_row(&row)?;
+                if let Some(constraint_name) = map
+                    .get_str("constraint_name")
+                    .or_else(|| map.get_str("CONSTRAINT_NAME"))
+                {
+                    constraint_names.push(constraint_name.to_owned());
+                }
+            }
+            for col in Self::columns() {
+                for (constraint_name, constraint) in col.constraints(table_name) {
+                    if constraint_names.contains(&constraint_name) {
+                        continue;
+                    }
+
+                    let sql = format!(
+                        "ALTER TABLE {table_name_escaped} ADD CONSTRAINT {constraint_name} {constraint};"
+                    );
+                    let constraint_name = constraint_name.as_str();
+                    if let Err(err) = pool.execute(&sql).await {
+                        tracing::error!(
+                            model_name,
+                            table_name,
+                            constraint_name,
+                            "fail to add the constraint `{constraint_name}`: {err}",
+                        );
+                    } else {
+                        tracing::warn!(
+                            model_name,
+                            table_name,
+                            constraint_name,
+                            "a new constraint `{constraint_name}` has been added",
+                        );
+                    }
+                }
+            }
+        }
         Ok(())
     }
 
@@ -319,6 +752,10 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         if !super::AUTO_MIGRATION.load(Relaxed) {
             return Ok(0);
         }
+        if Self::VIEW.is_some() && !(Self::MATERIALIZED_VIEW && cfg!(feature = "orm-postgres")) {
+            // Only the materialized views can be indexed.
+            return Ok(0);
+        }
 
         let pool = Self::init_writer()?.pool();
         let columns = Self::columns();
@@ -337,22 +774,26 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
 
             let mut text_search_columns = Vec::new();
             for col in columns {
-                if let Some(index_type) = col.index_type() {
-                    let column_name = col.name();
+                if let Some(sql) = col.unique_index_definition(table_name, &table_name_escaped) {
+                    rows = pool.execute(&sql).await?.rows_affected().max(rows);
+                }
+                if let Some(index_type) = col.index_type().filter(|&t| t != "unique") {
+                    let column_name = col.column_name();
+                    let column_field = Query::quote_identifier(&column_name);
                     if matches!(index_type, "fulltext" | "text") {
-                        text_search_columns.push(column_name);
-                    } else if matches!(index_type, "unique" | "spatial") {
+                        text_search_columns.push(column_field);
+                    } else if index_type == "spatial" {
                         let index_type = index_type.to_uppercase();
                         let sql = format!(
                             "CREATE {index_type} INDEX {table_name}_{column_name}_index \
-                                ON {table_name_escaped} ({column_name});"
+                                ON {table_name_escaped} ({column_field});"
                         );
                         rows = pool.execute(&sql).await?.rows_affected().max(rows);
                     } else if matches!(index_type, "btree" | "hash") {
                         let index_type = index_type.to_uppercase();
                         let sql = format!(
                             "CREATE INDEX {table_name}_{column_name}_index \
-                                ON {table_name_escaped} ({column_name}) USING {index_type};"
+                                ON {table_name_escaped} ({column_field}) USING {index_type};"
                         );
                         rows = pool.execute(&sql).await?.rows_affected().max(rows);
                     }
@@ -370,25 +811,23 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
             let mut text_search_columns = Vec::new();
             let mut text_search_languages = Vec::new();
             for col in columns {
-                if let Some(index_type) = col.index_type() {
-                    let column_name = col.name();
+                if let Some(sql) = col.unique_index_definition(table_name, &table_name_escaped) {
+                    rows = pool.execute(&sql).await?.rows_affected().max(rows);
+                }
+                if let Some(index_type) = col.index_type().filter(|&t| t != "unique") {
+                    let column_name = col.column_name();
+                    let column_field = Query::quote_identifier(&column_name);
                     if index_type.starts_with("text") {
                         let language = index_type.strip_prefix("text:").unwrap_or("english");
-                        let column = format!("coalesce({column_name}, '')");
+                        let column = format!("coalesce({column_field}, '')");
                         text_search_languages.push(language);
                         text_search_columns.push((language, column));
-                    } else if index_type == "unique" {
-                        let sql = format!(
-                            "CREATE UNIQUE INDEX IF NOT EXISTS {table_name}_{column_name}_index \
-                                ON {table_name_escaped} ({column_name});"
-                        );
-                        rows = pool.execute(&sql).await?.rows_affected().max(rows);
                     } else {
                         let sort_order = if index_type == "btree" { " DESC" } else { "" };
                         let sql = format!(
                             "CREATE INDEX IF NOT EXISTS {table_name}_{column_name}_index \
                                 ON {table_name_escaped} \
-                                    USING {index_type}({column_name}{sort_order});"
+                                    USING {index_type}({column_field}{sort_order});"
                         );
                         rows = pool.execute(&sql).await?.rows_affected().max(rows);
                     }
@@ -409,12 +848,15 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
             }
         } else {
             for col in columns {
-                if let Some(index_type) = col.index_type() {
-                    let column_name = col.name();
-                    let index_type = if index_type == "unique" { "UNIQUE" } else { "" };
+                if let Some(sql) = col.unique_index_definition(table_name, &table_name_escaped) {
+                    rows = pool.execute(&sql).await?.rows_affected().max(rows);
+                }
+                if col.index_type().is_some_and(|t| t != "unique") {
+                    let column_name = col.column_name();
+                    let column_field = Query::quote_identifier(&column_name);
                     let sql = format!(
-                        "CREATE {index_type} INDEX IF NOT EXISTS {table_name}_{column_name}_index \
-                            ON {table_name_escaped} ({column_name});"
+                        "CREATE INDEX IF NOT EXISTS {table_name}_{column_name}_index \
+                            ON {table_name_escaped} ({column_field});"
                     );
                     rows = pool.execute(&sql).await?.rows_affected().max(rows);
                 }
@@ -423,6 +865,109 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         Ok(rows)
     }
 
+    /// Compares the columns and indexes of the model with the live table
+    /// and returns a report of the schema drift.
+    async fn diff_database() -> Result<SchemaDiff, Error> {
+        let connection_pool = Self::init_writer()?;
+        let table_name = Self::table_name();
+        let columns = inspect::list_columns(connection_pool, table_name).await?;
+        let indexes = inspect::list_indexes(connection_pool, table_name).await?;
+        let constraints = inspect::list_constraints(connection_pool, table_name).await?;
+
+        let mut diff = SchemaDiff::new(table_name);
+        let mut expected_indexes = Vec::new();
+        for col in Self::columns() {
+            let column_name = col.column_name();
+            let column_name = column_name.as_ref();
+            if let Some(info) = columns.iter().find(|info| info.name() == column_name) {
+                let data_type = info.data_type();
+                if !col.is_compatible(data_type) {
+                    let column_type = col.column_type();
+                    let mismatch = TypeMismatch::new(
+                        column_name.to_owned(),
+                        column_type.to_owned(),
+                        data_type.to_owned(),
+                    );
+                    diff.add_type_mismatch(mismatch);
+                }
+            } else {
+                diff.add_missing_column(column_name);
+            }
+
+            // Index names should be consistent with those in `create_indexes()`.
+            if col.is_unique() {
+                expected_indexes.push(col.unique_index_name(table_name));
+            }
+            if let Some(index_type) = col.index_type().filter(|&t| t != "unique") {
+                let index_name = if cfg!(any(
+                    feature = "orm-mariadb",
+                    feature = "orm-mysql",
+                    feature = "orm-tidb"
+                )) {
+                    if matches!(index_type, "fulltext" | "text") {
+                        format!("{table_name}_text_search_index")
+                    } else if matches!(index_type, "spatial" | "btree" | "hash") {
+                        format!("{table_name}_{column_name}_index")
+                    } else {
+                        continue;
+                    }
+                } else if cfg!(feature = "orm-postgres") && index_type.starts_with("text") {
+                    let language = index_type.strip_prefix("text:").unwrap_or("english");
+                    format!("{table_name}_text_search_{language}_index")
+                } else {
+                    format!("{table_name}_{column_name}_index")
+                };
+                if !expected_indexes.contains(&index_name) {
+                    expected_indexes.push(index_name);
+                }
+            }
+        }
+        for info in columns.iter() {
+            let column_name = info.name();
+            let column_opt = Self::columns()
+                .iter()
+                .find(|col| col.column_name() == column_name);
+            if column_opt.is_none() {
+                diff.add_extra_column(column_name);
+            }
+        }
+        for index_name in expected_indexes.iter() {
+            if !indexes.iter().any(|index| index.name() == index_name) {
+                diff.add_missing_index(index_name);
+            }
+        }
+        for index in indexes.iter() {
+            let index_name = index.name();
+            let is_implicit = index_name == "PRIMARY"
+                || index_name.ends_with("_pkey")
+                || index_name.starts_with("sqlite_autoindex_")
+                || constraints.iter().any(|c| c.name() == index_name);
+            if !is_implicit && !expected_indexes.iter().any(|name| name == index_name) {
+                diff.add_extra_index(index_name);
+            }
+        }
+        Ok(diff)
+    }
+
+    /// Verifies that the live table is consistent with the model
+    /// if `strict-schema` is enabled in the `[database]` table.
+    /// It runs for the models registered by [`register_model()`](super::register_model)
+    /// when the application is loaded, and the startup is aborted on failure.
+    async fn verify_schema() -> Result<(), Error> {
+        if !super::STRICT_SCHEMA.load(Relaxed) || Self::VIEW.is_some() {
+            return Ok(());
+        }
+
+        let diff = Self::diff_database().await?;
+        if !diff.is_empty() {
+            let model_name = Self::model_name();
+            let table_name = Self::table_name();
+            tracing::error!(model_name, table_name, "{diff}");
+            bail!("{}", diff);
+        }
+        Ok(())
+    }
+
     /// Prepares the SQL to insert the model into the table.
     async fn prepare_insert(self) -> Result<QueryContext, Error> {
         let map = self.into_map();
@@ -433,21 +978,32 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         let values = columns
             .iter()
             .filter_map(|col| {
-                if col.auto_increment() {
-                    None
-                } else {
+                if col.is_insertable() {
                     let name = col.name();
-                    fields.push(name);
-                    Some(col.encode_value(map.get(name)))
+                    fields.push(Query::format_field(name));
+                    Some(col.encode_insert_value(map.get(name)))
+                } else {
+                    None
                 }
             })
             .collect::<Vec<_>>()
             .join(", ");
         let fields = fields.join(", ");
-        let sql = format!("INSERT INTO {table_name} ({fields}) VALUES ({values});");
+        let sql = if cfg!(feature = "orm-postgres")
+            && Self::PRIMARY_KEY_STRATEGY.is_database_generated()
+        {
+            let primary_key_name = Self::PRIMARY_KEY_NAME;
+            let primary_key_field = Query::format_field(primary_key_name);
+            format!(
+                "INSERT INTO {table_name} ({fields}) VALUES ({values}) \
+                    RETURNING {primary_key_field};"
+            )
+        } else {
+            format!("INSERT INTO {table_name} ({fields}) VALUES ({values});")
+        };
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(sql);
-        if cfg!(debug_assertions) && super::DEBUG_ONLY.load(Relaxed) {
+        if super::dry_run_enabled() {
             ctx.cancel();
         }
         Ok(ctx)
@@ -456,20 +1012,41 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     /// Inserts the model into the table.
     async fn insert(mut self) -> Result<QueryContext, Error> {
         let model_data = self.before_insert().await?;
+        let primary_key = self.primary_key().to_string();
         let mut ctx = self.prepare_insert().await?;
         if ctx.is_cancelled() {
             return Ok(ctx);
         }
 
         let pool = Self::acquire_writer().await?.pool();
-        let query_result = pool.execute(ctx.query()).await?;
-        let (last_insert_id, rows_affected) = Query::parse_query_result(query_result);
+        let (last_insert_id, rows_affected) = if cfg!(feature = "orm-postgres")
+            && Self::PRIMARY_KEY_STRATEGY.is_database_generated()
+        {
+            // PostgreSQL returns the generated primary key by the `RETURNING` clause.
+            let row = executor::run_query(&ctx, None, pool.fetch_one(ctx.query())).await?;
+            let last_insert_id = super::decode::<i64>(&row, Self::PRIMARY_KEY_NAME)?;
+            (Some(last_insert_id), 1)
+        } else {
+            let query_result = executor::run_query(&ctx, None, pool.execute(ctx.query())).await?;
+            Query::parse_query_result(query_result)
+        };
         let success = rows_affected == 1;
         if let Some(last_insert_id) = last_insert_id {
             ctx.set_last_insert_id(last_insert_id);
         }
         ctx.set_query_result(rows_affected, success);
         Self::after_scan(&ctx).await?;
+        if success {
+            let primary_key = last_insert_id
+                .map(|id| id.to_string())
+                .unwrap_or(primary_key);
+            if Self::TREE_PARENT_FIELD.is_some() {
+                super::tree::rebuild_tree_path::<Self>(&primary_key).await?;
+            }
+            if Self::POSITION_FIELD.is_some() {
+                super::position::append_position::<Self>(&primary_key).await?;
+            }
+        }
         Self::after_insert(&ctx, model_data).await?;
         if success {
             Ok(ctx)
@@ -487,7 +1064,10 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
             bail!("the list of models to be inserted should be nonempty");
         }
 
-        let columns = Self::columns();
+        let columns = Self::columns()
+            .iter()
+            .filter(|col| !col.is_generated())
+            .collect::<Vec<_>>();
         let mut values = Vec::with_capacity(models.len());
         for mut model in models.into_iter() {
             let _model_data = model.before_insert().await?;
@@ -495,19 +1075,23 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
             let map = model.into_map();
             let entries = columns
                 .iter()
-                .map(|col| col.encode_value(map.get(col.name())))
+                .map(|col| col.encode_insert_value(map.get(col.name())))
                 .collect::<Vec<_>>()
                 .join(", ");
             values.push(format!("({entries})"));
         }
 
         let table_name = Query::table_name_escaped::<Self>();
-        let fields = Self::fields().join(", ");
+        let fields = columns
+            .iter()
+            .map(|col| Query::format_field(col.name()))
+            .collect::<Vec<_>>()
+            .join(", ");
         let values = values.join(", ");
         let sql = format!("INSERT INTO {table_name} ({fields}) VALUES {values};");
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(sql);
-        if cfg!(debug_assertions) && super::DEBUG_ONLY.load(Relaxed) {
+        if super::dry_run_enabled() {
             ctx.cancel();
         }
         Ok(ctx)
@@ -521,15 +1105,62 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         }
 
         let pool = Self::acquire_writer().await?.pool();
-        let query_result = pool.execute(ctx.query()).await?;
+        let query_result = executor::run_query(&ctx, None, pool.execute(ctx.query())).await?;
         ctx.set_query_result(query_result.rows_affected(), true);
         Self::after_scan(&ctx).await?;
         Ok(ctx)
     }
 
+    /// Copies the models into the table in batches, which is much faster than `insert_many`
+    /// for bulk ingestion. Returns the number of rows copied in each batch.
+    ///
+    /// It uses `COPY FROM STDIN` for PostgreSQL and falls back to multi-row inserts
+    /// for the other drivers. `LOAD DATA LOCAL INFILE` is not supported for MySQL.
+    /// The model hooks are not called for the copied rows.
+    async fn copy_in<I>(models: I) -> Result<Vec<u64>, Error>
+    where
+        I: IntoIterator<Item = Self> + Send,
+        I::IntoIter: Send,
+    {
+        Self::copy_in_stream(futures::stream::iter(models)).await
+    }
+
+    /// Copies the models from a stream into the table in batches.
+    /// Returns the number of rows copied in each batch.
+    async fn copy_in_stream<S>(models: S) -> Result<Vec<u64>, Error>
+    where
+        S: Stream<Item = Self> + Send,
+    {
+        let mut batch_counts = Vec::new();
+        if super::dry_run_enabled() {
+            return Ok(batch_counts);
+        }
+
+        let model_name = Self::MODEL_NAME;
+        let table_name = Query::table_name_escaped::<Self>();
+        let columns = Self::columns()
+            .iter()
+            .filter(|col| !col.auto_increment() && !col.is_generated())
+            .collect::<Vec<_>>();
+        let pool = Self::acquire_writer().await?.pool();
+        let mut batches = pin!(models.chunks(copy::BATCH_SIZE));
+        while let Some(models) = batches.next().await {
+            let rows = models
+                .into_iter()
+                .map(|model| model.into_map())
+                .collect::<Vec<_>>();
+            let num_rows = copy::copy_in(pool, &table_name, &columns, &rows).await?;
+            let batch = batch_counts.len();
+            tracing::info!(model_name, batch, num_rows, "copied rows into the table");
+            batch_counts.push(num_rows);
+        }
+        Ok(batch_counts)
+    }
+
     /// Prepares the SQL to update the model in the table.
     async fn prepare_update(self) -> Result<QueryContext, Error> {
         let primary_key_name = Self::PRIMARY_KEY_NAME;
+        let primary_key_field = Query::format_field(primary_key_name);
         let table_name = Query::table_name_escaped::<Self>();
         let primary_key = Query::escape_string(self.primary_key());
         let map = self.into_map();
@@ -547,11 +1178,11 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
 
         let mutations = mutations.join(", ");
         let sql = format!(
-            "UPDATE {table_name} SET {mutations} WHERE {primary_key_name} = {primary_key};"
+            "UPDATE {table_name} SET {mutations} WHERE {primary_key_field} = {primary_key};"
         );
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(sql);
-        if cfg!(debug_assertions) && super::DEBUG_ONLY.load(Relaxed) {
+        if super::dry_run_enabled() {
             ctx.cancel();
         }
         Ok(ctx)
@@ -566,7 +1197,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         }
 
         let pool = Self::acquire_writer().await?.pool();
-        let query_result = pool.execute(ctx.query()).await?;
+        let query_result = executor::run_query(&ctx, None, pool.execute(ctx.query())).await?;
         let rows_affected = query_result.rows_affected();
         let success = rows_affected == 1;
         ctx.set_query_result(rows_affected, success);
@@ -590,6 +1221,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         Self::before_mutation(query, mutation).await?;
 
         let primary_key_name = Self::PRIMARY_KEY_NAME;
+        let primary_key_field = Query::format_field(primary_key_name);
         let table_name = query.format_table_name::<Self>();
         let filters = query.format_filters::<Self>();
         let updates = mutation.format_updates::<Self>();
@@ -601,20 +1233,20 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
             // MySQL doesn't yet support 'LIMIT & IN/ALL/ANY/SOME subquery'
             // and self-referencing in UPDATE/DELETE
             format!(
-                "UPDATE {table_name} SET {updates} WHERE {primary_key_name} IN \
-                    (SELECT * from (SELECT {primary_key_name} FROM {table_name} {filters}) AS t);"
+                "UPDATE {table_name} SET {updates} WHERE {primary_key_field} IN \
+                    (SELECT * from (SELECT {primary_key_field} FROM {table_name} {filters}) AS t);"
             )
         } else {
             // Both PostgreQL and SQLite support a `LIMIT` in subquery
             let sort = query.format_sort();
             format!(
-                "UPDATE {table_name} SET {updates} WHERE {primary_key_name} IN \
-                    (SELECT {primary_key_name} FROM {table_name} {filters} {sort} LIMIT 1);"
+                "UPDATE {table_name} SET {updates} WHERE {primary_key_field} IN \
+                    (SELECT {primary_key_field} FROM {table_name} {filters} {sort} LIMIT 1);"
             )
         };
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(sql);
-        if cfg!(debug_assertions) && super::DEBUG_ONLY.load(Relaxed) {
+        if super::dry_run_enabled() {
             ctx.cancel();
         }
         Ok(ctx)
@@ -628,7 +1260,8 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         }
 
         let pool = Self::acquire_writer().await?.pool();
-        let query_result = pool.execute(ctx.query()).await?;
+        let query_result =
+            executor::run_query(&ctx, query.timeout(), pool.execute(ctx.query())).await?;
         let rows_affected = query_result.rows_affected();
         let success = rows_affected <= 1;
         ctx.set_query_result(rows_affected, success);
@@ -657,7 +1290,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         let sql = format!("UPDATE {table_name} SET {updates} {filters};");
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(sql);
-        if cfg!(debug_assertions) && super::DEBUG_ONLY.load(Relaxed) {
+        if super::dry_run_enabled() {
             ctx.cancel();
         }
         Ok(ctx)
@@ -671,7 +1304,8 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         }
 
         let pool = Self::acquire_writer().await?.pool();
-        let query_result = pool.execute(ctx.query()).await?;
+        let query_result =
+            executor::run_query(&ctx, query.timeout(), pool.execute(ctx.query())).await?;
         ctx.set_query_result(query_result.rows_affected(), true);
         Self::after_scan(&ctx).await?;
         Self::after_mutation(&ctx).await?;
@@ -682,19 +1316,25 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     async fn prepare_upsert(self) -> Result<QueryContext, Error> {
         let map = self.into_map();
         let table_name = Query::table_name_escaped::<Self>();
-        let fields = Self::fields();
-        let num_fields = fields.len();
+        let num_fields = Self::fields().len();
         let read_only_fields = Self::read_only_fields();
         let num_writable_fields = num_fields - read_only_fields.len();
+        let mut fields = Vec::with_capacity(num_fields);
         let mut values = Vec::with_capacity(num_fields);
         let mut mutations = Vec::with_capacity(num_writable_fields);
-        for col in Self::columns() {
+        for col in Self::columns().iter().filter(|col| !col.is_generated()) {
             let field = col.name();
-            let value = col.encode_value(map.get(field));
-            if !read_only_fields.contains(&field) {
+            let value = map.get(field);
+
+            // The existing value is kept if the default expression is used.
+            let defaulted =
+                col.default_expression().is_some() && value.map_or(true, |v| v.is_ignorable());
+            let value = col.encode_insert_value(value);
+            if !(defaulted || read_only_fields.contains(&field)) {
                 let field = Query::format_field(field);
                 mutations.push(format!("{field} = {value}"));
             }
+            fields.push(field);
             values.push(value);
         }
 
@@ -712,16 +1352,17 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
             )
         } else {
             let primary_key_name = Self::PRIMARY_KEY_NAME;
+            let primary_key_field = Query::format_field(primary_key_name);
 
             // Both PostgreQL and SQLite (3.24+) support this syntax.
             format!(
                 "INSERT INTO {table_name} ({fields}) VALUES ({values}) \
-                    ON CONFLICT ({primary_key_name}) DO UPDATE SET {mutations};"
+                    ON CONFLICT ({primary_key_field}) DO UPDATE SET {mutations};"
             )
         };
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(sql);
-        if cfg!(debug_assertions) && super::DEBUG_ONLY.load(Relaxed) {
+        if super::dry_run_enabled() {
             ctx.cancel();
         }
         Ok(ctx)
@@ -736,7 +1377,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         }
 
         let pool = Self::acquire_writer().await?.pool();
-        let query_result = pool.execute(ctx.query()).await?;
+        let query_result = executor::run_query(&ctx, None, pool.execute(ctx.query())).await?;
         let (last_insert_id, rows_affected) = Query::parse_query_result(query_result);
         let success = rows_affected == 1;
         if let Some(last_insert_id) = last_insert_id {
@@ -758,20 +1399,21 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     /// Prepares the SQL to delete the model in the table.
     async fn prepare_delete() -> Result<QueryContext, Error> {
         let primary_key_name = Self::PRIMARY_KEY_NAME;
+        let primary_key_field = Query::format_field(primary_key_name);
         let table_name = Query::table_name_escaped::<Self>();
         let placeholder = Query::placeholder(1);
         let sql = if cfg!(feature = "orm-postgres") {
             let type_annotation = Self::primary_key_column().type_annotation();
             format!(
                 "DELETE FROM {table_name} \
-                    WHERE {primary_key_name} = ({placeholder}){type_annotation};"
+                    WHERE {primary_key_field} = ({placeholder}){type_annotation};"
             )
         } else {
-            format!("DELETE FROM {table_name} WHERE {primary_key_name} = {placeholder};")
+            format!("DELETE FROM {table_name} WHERE {primary_key_field} = {placeholder};")
         };
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(sql);
-        if cfg!(debug_assertions) && super::DEBUG_ONLY.load(Relaxed) {
+        if super::dry_run_enabled() {
             ctx.cancel();
         }
         Ok(ctx)
@@ -781,16 +1423,17 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     async fn delete(mut self) -> Result<QueryContext, Error> {
         let model_data = self.before_delete().await?;
         let mut ctx = Self::prepare_delete().await?;
+        let primary_key = self.primary_key();
+        ctx.add_argument(primary_key);
         if ctx.is_cancelled() {
             return Ok(ctx);
         }
 
         let pool = Self::acquire_writer().await?.pool();
-        let primary_key = self.primary_key();
-        let query_result = pool.execute_with(ctx.query(), &[primary_key]).await?;
+        let query_result =
+            executor::run_query(&ctx, None, pool.execute_with(ctx.query(), &[primary_key])).await?;
         let rows_affected = query_result.rows_affected();
         let success = rows_affected == 1;
-        ctx.add_argument(primary_key);
         ctx.set_query_result(rows_affected, success);
         Self::after_scan(&ctx).await?;
         self.after_delete(&ctx, model_data).await?;
@@ -806,19 +1449,22 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
 
     /// Prepares the SQL to delete at most one model selected by the query in the table.
     async fn prepare_delete_one(query: &Query) -> Result<QueryContext, Error> {
-        Self::before_query(query).await?;
+        let mut query = query.clone();
+        Self::before_query(&mut query).await?;
+        let query = &query;
 
         let primary_key_name = Self::PRIMARY_KEY_NAME;
+        let primary_key_field = Query::format_field(primary_key_name);
         let table_name = query.format_table_name::<Self>();
         let filters = query.format_filters::<Self>();
         let sort = query.format_sort();
         let sql = format!(
-            "DELETE FROM {table_name} WHERE {primary_key_name} IN \
-                (SELECT {primary_key_name} FROM {table_name} {filters} {sort} LIMIT 1);"
+            "DELETE FROM {table_name} WHERE {primary_key_field} IN \
+                (SELECT {primary_key_field} FROM {table_name} {filters} {sort} LIMIT 1);"
         );
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(sql);
-        if cfg!(debug_assertions) && super::DEBUG_ONLY.load(Relaxed) {
+        if super::dry_run_enabled() {
             ctx.cancel();
         }
         Ok(ctx)
@@ -832,7 +1478,8 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         }
 
         let pool = Self::acquire_writer().await?.pool();
-        let query_result = pool.execute(ctx.query()).await?;
+        let query_result =
+            executor::run_query(&ctx, query.timeout(), pool.execute(ctx.query())).await?;
         let rows_affected = query_result.rows_affected();
         let success = rows_affected <= 1;
         ctx.set_query_result(rows_affected, success);
@@ -850,14 +1497,16 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
 
     /// Prepares the SQL to delete many models selected by the query in the table.
     async fn prepare_delete_many(query: &Query) -> Result<QueryContext, Error> {
-        Self::before_query(query).await?;
+        let mut query = query.clone();
+        Self::before_query(&mut query).await?;
+        let query = &query;
 
         let table_name = query.format_table_name::<Self>();
         let filters = query.format_filters::<Self>();
         let sql = format!("DELETE FROM {table_name} {filters};");
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(sql);
-        if cfg!(debug_assertions) && super::DEBUG_ONLY.load(Relaxed) {
+        if super::dry_run_enabled() {
             ctx.cancel();
         }
         Ok(ctx)
@@ -871,32 +1520,265 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         }
 
         let pool = Self::acquire_writer().await?.pool();
-        let query_result = pool.execute(ctx.query()).await?;
+        let query_result =
+            executor::run_query(&ctx, query.timeout(), pool.execute(ctx.query())).await?;
         ctx.set_query_result(query_result.rows_affected(), true);
         Self::after_scan(&ctx).await?;
         Self::after_query(&ctx).await?;
         Ok(ctx)
     }
 
+    /// Purges the rows older than the retention period in batches,
+    /// and returns the number of rows purged. The rows will be archived before purging
+    /// if the `archive-dir` or `archive-accessor` is configured. If `dry_run` is `true`,
+    /// it only returns the number of expired rows.
+    async fn purge_expired(dry_run: bool) -> Result<u64, Error> {
+        let Some(period) = Self::retention_period() else {
+            return Ok(0);
+        };
+        let model_name = Self::MODEL_NAME;
+        let retention_field = Self::RETENTION_FIELD;
+        let expires_at = DateTime::now() - period;
+        let mut query = Query::default();
+        query.add_filter(retention_field, Map::from_entry("$lt", expires_at));
+        if dry_run {
+            let num_rows = Self::count(&query).await?;
+            tracing::info!(model_name, num_rows, "found expired rows in dry-run mode");
+            return Ok(num_rows);
+        }
+
+        if let Some(sink) = retention::archive_sink() {
+            Self::archive(&query, sink).await?;
+        }
+
+        let batch_size = retention::batch_size();
+        query.allow_fields(&[Self::PRIMARY_KEY_NAME]);
+        query.order_asc(retention_field);
+        query.set_limit(batch_size);
+
+        let mut num_purged = 0;
+        loop {
+            let rows = Self::find::<Map>(&query).await?;
+            if rows.is_empty() {
+                break;
+            }
+
+            let primary_key_values = rows
+                .iter()
+                .filter_map(|row| row.get(Self::PRIMARY_KEY_NAME).cloned())
+                .collect::<Vec<_>>();
+            let mut batch_query = Query::default();
+            batch_query.add_filter(
+                Self::PRIMARY_KEY_NAME,
+                Map::from_entry("$in", primary_key_values),
+            );
+
+            let ctx = Self::delete_many(&batch_query).await?;
+            let rows_affected = ctx.rows_affected().unwrap_or_default();
+            num_purged += rows_affected;
+            if ctx.is_cancelled() || rows_affected == 0 || rows.len() < batch_size {
+                break;
+            }
+        }
+        tracing::info!(model_name, num_purged, "purged expired rows");
+
+        // Emit metrics.
+        #[cfg(feature = "metrics")]
+        metrics::counter!("zino_model_purged_rows_total", "model_name" => model_name)
+            .increment(num_purged);
+
+        Ok(num_purged)
+    }
+
+    /// Lists the names of the partitions for the table.
+    async fn list_partitions() -> Result<Vec<String>, Error> {
+        if !cfg!(feature = "orm-postgres") {
+            return Ok(Vec::new());
+        }
+
+        let table_name = Self::table_name();
+        let sql = format!(
+            "SELECT child.relname::TEXT AS partition_name FROM pg_inherits \
+                JOIN pg_class parent ON pg_inherits.inhparent = parent.oid \
+                JOIN pg_class child ON pg_inherits.inhrelid = child.oid \
+                WHERE parent.relname = '{table_name}';"
+        );
+        let pool = Self::acquire_reader().await?.pool();
+        let rows = pool.fetch(&sql).await?;
+        rows.iter()
+            .map(|row| super::decode::<String>(row, "partition_name"))
+            .collect()
+    }
+
+    /// Creates the current and upcoming partitions for the table partitioned by range.
+    /// The number of upcoming partitions is configured by `premake` in the `[partition]` table.
+    /// Returns the number of created partitions.
+    async fn create_partitions() -> Result<u64, Error> {
+        if !cfg!(feature = "orm-postgres") {
+            return Ok(0);
+        }
+
+        let Some((_, interval)) = partition::parse_spec::<Self>() else {
+            return Ok(0);
+        };
+        let model_name = Self::MODEL_NAME;
+        let table_name = Self::table_name();
+        let table_name_escaped = Query::table_name_escaped::<Self>();
+        let partitions = Self::list_partitions().await?;
+        let pool = Self::init_writer()?.pool();
+
+        let mut start = interval.floor(Date::today());
+        let mut num_created = 0;
+        for _ in 0..=partition::premake() {
+            let Some(end) = interval.next(start) else {
+                break;
+            };
+            let partition_name = partition::partition_name(table_name, start);
+            if !partitions.contains(&partition_name) {
+                let sql = format!(
+                    "CREATE TABLE IF NOT EXISTS \"{partition_name}\" \
+                        PARTITION OF {table_name_escaped} FOR VALUES FROM ('{start}') TO ('{end}');"
+                );
+                if let Err(err) = pool.execute(&sql).await {
+                    tracing::error!(table_name, "fail to execute `{sql}`");
+                    return Err(err);
+                }
+                tracing::info!(model_name, "partition `{partition_name}` is created");
+                num_created += 1;
+            }
+            start = end;
+        }
+        Ok(num_created)
+    }
+
+    /// Drops the partitions whose ranges are older than the retention period.
+    /// The rows will be archived before dropping if an archive sink is configured
+    /// in the `[retention]` table. Returns the number of expired partitions.
+    async fn prune_partitions(dry_run: bool) -> Result<u64, Error> {
+        let Some((partition_column, interval)) = partition::parse_spec::<Self>() else {
+            return Ok(0);
+        };
+        let Some(period) = Self::retention_period() else {
+            return Ok(0);
+        };
+        let model_name = Self::MODEL_NAME;
+        let table_name = Self::table_name();
+        let expires_at = (DateTime::now() - period).date();
+
+        let mut num_pruned = 0;
+        for partition_name in Self::list_partitions().await? {
+            let Some(start) = partition::parse_partition_start(table_name, &partition_name) else {
+                continue;
+            };
+            if interval.next(start).map_or(true, |end| end > expires_at) {
+                continue;
+            }
+            num_pruned += 1;
+            if dry_run {
+                tracing::info!(model_name, "found expired partition `{partition_name}`");
+                continue;
+            }
+
+            if let Some(sink) = retention::archive_sink() {
+                let mut filter = Map::from_entry("$ge", DateTime::from(start));
+                if let Some(end) = interval.next(start) {
+                    filter.upsert("$lt", DateTime::from(end));
+                }
+
+                let mut query = Query::default();
+                query.add_filter(partition_column, filter);
+                Self::archive(&query, sink).await?;
+            }
+
+            let sql = format!("DROP TABLE IF EXISTS \"{partition_name}\";");
+            Self::acquire_writer().await?.pool().execute(&sql).await?;
+            tracing::info!(model_name, "partition `{partition_name}` is dropped");
+        }
+        if dry_run {
+            return Ok(num_pruned);
+        }
+
+        // Emit metrics.
+        #[cfg(feature = "metrics")]
+        metrics::counter!("zino_model_pruned_partitions_total", "model_name" => model_name)
+            .increment(num_pruned);
+
+        Ok(num_pruned)
+    }
+
+    /// Archives the rows selected by the query into the sink in chunks,
+    /// and writes a `manifest.json` file describing the archive.
+    async fn archive(query: &Query, sink: &ArchiveSink) -> Result<ArchiveManifest, Error> {
+        let model_name = Self::MODEL_NAME;
+        let table_name = Self::table_name();
+        let format = sink.file_format().extension();
+        let path = sink.archive_path(table_name);
+        let mut manifest = ArchiveManifest::new(path.clone(), model_name, table_name, format);
+        manifest.set_schema(serde_json::to_value(Self::schema())?);
+
+        let chunk_size = sink.rows_per_chunk();
+        let mut query = query.clone();
+        query.order_asc(Self::PRIMARY_KEY_NAME);
+        query.set_limit(chunk_size);
+
+        let mut offset = 0;
+        loop {
+            query.set_offset(offset);
+
+            let rows = Self::find::<Map>(&query).await?;
+            let num_rows = rows.len();
+            if num_rows == 0 {
+                break;
+            }
+
+            let index = manifest.files().len();
+            let file_path = format!("{path}/part-{index:05}.{format}");
+            let bytes = sink.encode(Self::columns(), rows)?;
+            sink.write(&file_path, bytes).await?;
+            manifest.add_file(file_path, num_rows);
+            if num_rows < chunk_size {
+                break;
+            }
+            offset += num_rows;
+        }
+
+        let manifest_path = format!("{path}/manifest.json");
+        let bytes = serde_json::to_vec_pretty(&manifest.to_map())?;
+        sink.write(&manifest_path, bytes).await?;
+
+        let num_rows = manifest.num_rows();
+        tracing::info!(model_name, num_rows, path = path.as_str(), "archived rows");
+        Ok(manifest)
+    }
+
     /// Finds a list of models selected by the query in the table,
     /// and decodes it as `Vec<T>`.
     async fn find<T>(query: &Query) -> Result<Vec<T>, Error>
     where
         T: DecodeRow<DatabaseRow, Error = Error>,
     {
-        Self::before_query(query).await?;
+        let mut query = query.clone();
+        Self::before_query(&mut query).await?;
 
-        let table_name = query.format_table_name::<Self>();
-        let projection = query.format_table_fields::<Self>();
-        let filters = query.format_filters::<Self>();
-        let sort = query.format_sort();
-        let pagination = query.format_pagination();
-        let sql = format!("SELECT {projection} FROM {table_name} {filters} {sort} {pagination};");
+        // The query limit is capped by the max number of rows,
+        // and the extra row is fetched to detect whether the results are truncated.
+        let max_rows = Self::max_rows(&query);
+        let capped = query.cap_limit(max_rows);
+
+        let query = &query;
+        let sql = query.to_sql::<Self>();
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(&sql);
 
         let pool = Self::acquire_reader().await?.pool();
-        let rows = pool.fetch(ctx.query()).await?;
+        let mut rows = executor::run_query(&ctx, query.timeout(), pool.fetch(ctx.query())).await?;
+        if capped && rows.len() > max_rows {
+            rows.truncate(max_rows);
+
+            let model_name = Self::MODEL_NAME;
+            tracing::warn!(model_name, max_rows, "query results are truncated");
+        }
+
         let mut data = Vec::with_capacity(rows.len());
         for row in rows {
             data.push(T::decode_row(&row)?);
@@ -919,13 +1801,72 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         serde_json::from_value(data.into()).map_err(Error::from)
     }
 
+    /// Finds a stream of models selected by the query in the table,
+    /// and decodes the rows as instances of type `T` as they come off the database cursor.
+    ///
+    /// # Note
+    ///
+    /// Unlike [`find()`](Schema::find), the rows are not buffered in memory
+    /// and the number of rows is not restricted by the `max-rows` setting.
+    /// The query result is recorded when the stream has been consumed,
+    /// but the `after_scan` and `after_query` hooks will not be called.
+    async fn find_stream<T>(query: &Query) -> Result<BoxStream<'static, Result<T, Error>>, Error>
+    where
+        T: DecodeRow<DatabaseRow, Error = Error> + Send + 'static,
+    {
+        let mut query = query.clone();
+        Self::before_query(&mut query).await?;
+        let query = &query;
+
+        let sql = query.to_sql::<Self>();
+        let mut ctx = Self::before_scan(&sql).await?;
+        ctx.set_query(sql);
+
+        // The rows are sent through a bounded channel so that the cursor is advanced
+        // only when the consumer is ready to receive more data.
+        let pool = Self::acquire_reader().await?.pool();
+        let (mut sender, receiver) = mpsc::channel(STREAM_BUFFER_SIZE);
+        let producer = async move {
+            let mut num_rows = 0u64;
+            let mut success = true;
+            let mut rows = pool.fetch_stream(ctx.query());
+            while let Some(result) = rows.next().await {
+                let result = result.and_then(|row| T::decode_row(&row));
+                success = result.is_ok();
+                if sender.send(result).await.is_err() || !success {
+                    break;
+                }
+                num_rows += 1;
+            }
+            drop(rows);
+
+            ctx.set_query_result(num_rows, success);
+            if !success {
+                ctx.record_error("fail to select the models from the table");
+            }
+            #[cfg(feature = "metrics")]
+            ctx.emit_metrics("query");
+            tracing::info!(
+                model_name = ctx.model_name(),
+                query_id = ctx.query_id().to_string(),
+                query = ctx.query(),
+                execution_time_millis = ctx.execution_time().as_millis(),
+                "{num_rows} rows streamed"
+            );
+        };
+        let producer = stream::once(producer).filter_map(|_| future::ready(None));
+        Ok(stream::select(producer, receiver).boxed())
+    }
+
     /// Finds one model selected by the query in the table,
     /// and decodes it as an instance of type `T`.
     async fn find_one<T>(query: &Query) -> Result<Option<T>, Error>
     where
         T: DecodeRow<DatabaseRow, Error = Error>,
     {
-        Self::before_query(query).await?;
+        let mut query = query.clone();
+        Self::before_query(&mut query).await?;
+        let query = &query;
 
         let table_name = query.format_table_name::<Self>();
         let projection = query.format_table_fields::<Self>();
@@ -936,7 +1877,9 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         ctx.set_query(sql);
 
         let pool = Self::acquire_reader().await?.pool();
-        let (num_rows, data) = if let Some(row) = pool.fetch_optional(ctx.query()).await? {
+        let optional_row =
+            executor::run_query(&ctx, query.timeout(), pool.fetch_optional(ctx.query())).await?;
+        let (num_rows, data) = if let Some(row) = optional_row {
             (1, Some(T::decode_row(&row)?))
         } else {
             (0, None)
@@ -1005,7 +1948,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         ctx.set_query(&sql);
 
         let pool = Self::acquire_reader().await?.pool();
-        let rows = pool.fetch(ctx.query()).await?;
+        let rows = executor::run_query(&ctx, query.timeout(), pool.fetch(ctx.query())).await?;
         let translate_enabled = query.translate_enabled();
         let mut associations = Vec::with_capacity(num_values);
         for row in rows {
@@ -1096,7 +2039,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         ctx.set_query(&sql);
 
         let pool = Self::acquire_reader().await?.pool();
-        let rows = pool.fetch(ctx.query()).await?;
+        let rows = executor::run_query(&ctx, query.timeout(), pool.fetch(ctx.query())).await?;
         let translate_enabled = query.translate_enabled();
         let mut associations = Vec::with_capacity(num_values);
         for row in rows {
@@ -1149,7 +2092,12 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         M: Schema,
         T: DecodeRow<DatabaseRow, Error = Error>,
     {
-        Self::before_query(query).await?;
+        let mut query = query.clone();
+        Self::before_query(&mut query).await?;
+
+        let max_rows = Self::max_rows(&query);
+        let capped = query.cap_limit(max_rows);
+        let query = &query;
 
         let model_name = Self::model_name();
         let other_model_name = M::model_name();
@@ -1179,7 +2127,13 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         ctx.set_query(&sql);
 
         let pool = Self::acquire_reader().await?.pool();
-        let rows = pool.fetch(ctx.query()).await?;
+        let mut rows = executor::run_query(&ctx, query.timeout(), pool.fetch(ctx.query())).await?;
+        if capped && rows.len() > max_rows {
+            rows.truncate(max_rows);
+
+            let model_name = Self::MODEL_NAME;
+            tracing::warn!(model_name, max_rows, "query results are truncated");
+        }
         let mut data = Vec::with_capacity(rows.len());
         for row in rows {
             data.push(T::decode_row(&row)?);
@@ -1208,7 +2162,9 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
 
     /// Checks whether there is a model selected by the query in the table.
     async fn exists(query: &Query) -> Result<bool, Error> {
-        Self::before_query(query).await?;
+        let mut query = query.clone();
+        Self::before_query(&mut query).await?;
+        let query = &query;
 
         let table_name = query.format_table_name::<Self>();
         let filters = query.format_filters::<Self>();
@@ -1217,7 +2173,8 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         ctx.set_query(sql);
 
         let pool = Self::acquire_reader().await?.pool();
-        let optional_row = pool.fetch_optional(ctx.query()).await?;
+        let optional_row =
+            executor::run_query(&ctx, query.timeout(), pool.fetch_optional(ctx.query())).await?;
         let num_rows = if optional_row.is_some() { 1 } else { 0 };
         ctx.set_query_result(num_rows, true);
         Self::after_scan(&ctx).await?;
@@ -1227,7 +2184,9 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
 
     /// Counts the number of rows selected by the query in the table.
     async fn count(query: &Query) -> Result<u64, Error> {
-        Self::before_count(query).await?;
+        let mut query = query.clone();
+        Self::before_count(&mut query).await?;
+        let query = &query;
 
         let table_name = query.format_table_name::<Self>();
         let filters = query.format_filters::<Self>();
@@ -1236,7 +2195,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         ctx.set_query(sql);
 
         let pool = Self::acquire_reader().await?.pool();
-        let row = pool.fetch_one(ctx.query()).await?;
+        let row = executor::run_query(&ctx, query.timeout(), pool.fetch_one(ctx.query())).await?;
         let map = Map::decode_row(&row)?;
 
         // SQLite may return a string value for the count value.
@@ -1253,7 +2212,9 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     where
         T: DecodeRow<DatabaseRow, Error = Error>,
     {
-        Self::before_count(query).await?;
+        let mut query = query.clone();
+        Self::before_count(&mut query).await?;
+        let query = &query;
 
         let table_name = query.format_table_name::<Self>();
         let filters = query.format_filters::<Self>();
@@ -1278,7 +2239,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         ctx.set_query(sql);
 
         let pool = Self::acquire_reader().await?.pool();
-        let row = pool.fetch_one(ctx.query()).await?;
+        let row = executor::run_query(&ctx, query.timeout(), pool.fetch_one(ctx.query())).await?;
         ctx.set_query_result(1, true);
         Self::after_scan(&ctx).await?;
         Self::after_count(&ctx).await?;
@@ -1300,7 +2261,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         let (sql, values) = Query::prepare_query(query, params);
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(sql);
-        if cfg!(debug_assertions) && super::DEBUG_ONLY.load(Relaxed) {
+        if super::dry_run_enabled() {
             ctx.cancel();
         }
         if ctx.is_cancelled() {
@@ -1312,7 +2273,8 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
             .map(|v| v.to_string_unquoted())
             .collect::<Vec<_>>();
         let pool = Self::acquire_writer().await?.pool();
-        let query_result = pool.execute_with(ctx.query(), &arguments).await?;
+        let query_result =
+            executor::run_query(&ctx, None, pool.execute_with(ctx.query(), &arguments)).await?;
         ctx.append_arguments(&mut arguments);
         ctx.set_query_result(query_result.rows_affected(), true);
         Self::after_scan(&ctx).await?;
@@ -1320,6 +2282,9 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     }
 
     /// Executes the query in the table, and decodes it as `Vec<T>`.
+    ///
+    /// The interpolation of `${param}` is deprecated for the user-supplied values,
+    /// and [`query_bound()`](Schema::query_bound) should be used instead.
     async fn query<T>(query: &str, params: Option<&Map>) -> Result<Vec<T>, Error>
     where
         T: DecodeRow<DatabaseRow, Error = Error>,
@@ -1333,11 +2298,84 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
             .map(|v| v.to_string_unquoted())
             .collect::<Vec<_>>();
         let pool = Self::acquire_reader().await?.pool();
-        let rows = pool.fetch_with(ctx.query(), &arguments).await?;
+        let mut rows =
+            executor::run_query(&ctx, None, pool.fetch_with(ctx.query(), &arguments)).await?;
+        rows.truncate(Self::max_rows(&Query::default()));
+        let mut data = Vec::with_capacity(rows.len());
+        for row in rows {
+            data.push(T::decode_row(&row)?);
+        }
+        ctx.append_arguments(&mut arguments);
+        ctx.set_query_result(u64::try_from(data.len())?, true);
+        Self::after_scan(&ctx).await?;
+        Ok(data)
+    }
+
+    /// Executes the query in the table with the parameters bound as typed arguments,
+    /// and returns the total number of rows affected.
+    ///
+    /// The parameter `#{param}` is bound as a driver argument whose type is inferred
+    /// from the JSON value, so it is safe to use for the user-supplied values.
+    async fn execute_bound(query: &str, params: &Map) -> Result<QueryContext, Error> {
+        let (sql, values) = Query::prepare_query(query, Some(params));
+        let mut ctx = Self::before_scan(&sql).await?;
+        ctx.set_query(sql);
+        if super::dry_run_enabled() {
+            ctx.cancel();
+        }
+        if ctx.is_cancelled() {
+            return Ok(ctx);
+        }
+
+        let pool = Self::acquire_writer().await?.pool();
+        let query_result =
+            executor::run_query(&ctx, None, pool.execute_bound(ctx.query(), &values)).await?;
+        let mut arguments = values
+            .iter()
+            .map(|v| v.to_string_unquoted())
+            .collect::<Vec<_>>();
+        ctx.append_arguments(&mut arguments);
+        ctx.set_query_result(query_result.rows_affected(), true);
+        Self::after_scan(&ctx).await?;
+        Ok(ctx)
+    }
+
+    /// Executes the query in the table with the parameters bound as typed arguments,
+    /// and decodes it as `Vec<T>`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use zino_core::{extension::JsonObjectExt, orm::Schema, Map};
+    ///
+    /// let mut params = Map::new();
+    /// params.upsert("name", user_input);
+    /// params.upsert("age", 18);
+    ///
+    /// let sql = "SELECT * FROM users WHERE name = #{name} AND age >= #{age};";
+    /// let users = User::query_bound::<Map>(sql, &params).await?;
+    /// ```
+    async fn query_bound<T>(query: &str, params: &Map) -> Result<Vec<T>, Error>
+    where
+        T: DecodeRow<DatabaseRow, Error = Error>,
+    {
+        let (sql, values) = Query::prepare_query(query, Some(params));
+        let mut ctx = Self::before_scan(&sql).await?;
+        ctx.set_query(sql);
+
+        let pool = Self::acquire_reader().await?.pool();
+        let mut rows =
+            executor::run_query(&ctx, None, pool.fetch_bound(ctx.query(), &values)).await?;
+        rows.truncate(Self::max_rows(&Query::default()));
         let mut data = Vec::with_capacity(rows.len());
         for row in rows {
             data.push(T::decode_row(&row)?);
         }
+
+        let mut arguments = values
+            .iter()
+            .map(|v| v.to_string_unquoted())
+            .collect::<Vec<_>>();
         ctx.append_arguments(&mut arguments);
         ctx.set_query_result(u64::try_from(data.len())?, true);
         Self::after_scan(&ctx).await?;
@@ -1370,7 +2408,12 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
             .map(|v| v.to_string_unquoted())
             .collect::<Vec<_>>();
         let pool = Self::acquire_reader().await?.pool();
-        let optional_row = pool.fetch_optional_with(ctx.query(), &arguments).await?;
+        let optional_row = executor::run_query(
+            &ctx,
+            None,
+            pool.fetch_optional_with(ctx.query(), &arguments),
+        )
+        .await?;
         let (num_rows, data) = if let Some(row) = optional_row {
             (1, Some(T::decode_row(&row)?))
         } else {
@@ -1399,20 +2442,21 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     /// Prepares the SQL to delete a model selected by the primary key in the table.
     async fn prepare_delete_by_id() -> Result<QueryContext, Error> {
         let primary_key_name = Self::PRIMARY_KEY_NAME;
+        let primary_key_field = Query::format_field(primary_key_name);
         let table_name = Query::table_name_escaped::<Self>();
         let placeholder = Query::placeholder(1);
         let sql = if cfg!(feature = "orm-postgres") {
             let type_annotation = Self::primary_key_column().type_annotation();
             format!(
                 "DELETE FROM {table_name} \
-                    WHERE {primary_key_name} = ({placeholder}){type_annotation};"
+                    WHERE {primary_key_field} = ({placeholder}){type_annotation};"
             )
         } else {
-            format!("DELETE FROM {table_name} WHERE {primary_key_name} = {placeholder};")
+            format!("DELETE FROM {table_name} WHERE {primary_key_field} = {placeholder};")
         };
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(sql);
-        if cfg!(debug_assertions) && super::DEBUG_ONLY.load(Relaxed) {
+        if super::dry_run_enabled() {
             ctx.cancel();
         }
         Ok(ctx)
@@ -1421,15 +2465,16 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     /// Deletes a model selected by the primary key in the table.
     async fn delete_by_id(primary_key: &Self::PrimaryKey) -> Result<QueryContext, Error> {
         let mut ctx = Self::prepare_delete_by_id().await?;
+        ctx.add_argument(primary_key);
         if ctx.is_cancelled() {
             return Ok(ctx);
         }
 
         let pool = Self::acquire_writer().await?.pool();
-        let query_result = pool.execute_with(ctx.query(), &[primary_key]).await?;
+        let query_result =
+            executor::run_query(&ctx, None, pool.execute_with(ctx.query(), &[primary_key])).await?;
         let rows_affected = query_result.rows_affected();
         let success = rows_affected == 1;
-        ctx.add_argument(primary_key);
         ctx.set_query_result(rows_affected, success);
         Self::after_scan(&ctx).await?;
         if success {
@@ -1449,28 +2494,31 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         T: DecodeRow<DatabaseRow, Error = Error>,
     {
         let primary_key_name = Self::PRIMARY_KEY_NAME;
-        let query = Self::default_query();
+        let primary_key_field = Query::format_field(primary_key_name);
+        let mut query = Self::default_query();
+        Self::before_query(&mut query).await?;
+
         let table_name = query.format_table_name::<Self>();
         let projection = query.format_projection();
         let placeholder = Query::placeholder(1);
-        let sql = if cfg!(feature = "orm-postgres") {
+        let condition = if cfg!(feature = "orm-postgres") {
             let type_annotation = Self::primary_key_column().type_annotation();
-            format!(
-                "SELECT {projection} FROM {table_name} \
-                    WHERE {primary_key_name} = ({placeholder}){type_annotation};"
-            )
+            format!("{primary_key_field} = ({placeholder}){type_annotation}")
         } else {
-            format!(
-                "SELECT {projection} FROM {table_name} WHERE {primary_key_name} = {placeholder};"
-            )
+            format!("{primary_key_field} = {placeholder}")
         };
+        let filters = query.format_filters_with::<Self>(&condition);
+        let sql = format!("SELECT {projection} FROM {table_name} {filters};");
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(sql);
 
         let pool = Self::acquire_reader().await?.pool();
-        let optional_row = pool
-            .fetch_optional_with(ctx.query(), &[primary_key])
-            .await?;
+        let optional_row = executor::run_query(
+            &ctx,
+            None,
+            pool.fetch_optional_with(ctx.query(), &[primary_key]),
+        )
+        .await?;
         let (num_rows, data) = if let Some(row) = optional_row {
             (1, Some(T::decode_row(&row)?))
         } else {
@@ -1486,30 +2534,33 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     /// Finds a model selected by the primary key in the table, and parses it as `Self`.
     async fn try_get_model(primary_key: &Self::PrimaryKey) -> Result<Self, Error> {
         let primary_key_name = Self::PRIMARY_KEY_NAME;
-        let query = Self::default_query();
+        let primary_key_field = Query::format_field(primary_key_name);
+        let mut query = Self::default_query();
+        Self::before_query(&mut query).await?;
+
         let table_name = query.format_table_name::<Self>();
         let projection = query.format_projection();
         let placeholder = Query::placeholder(1);
-        let sql = if cfg!(feature = "orm-postgres") {
+        let condition = if cfg!(feature = "orm-postgres") {
             let type_annotation = Self::primary_key_column().type_annotation();
-            format!(
-                "SELECT {projection} FROM {table_name} \
-                    WHERE {primary_key_name} = ({placeholder}){type_annotation};"
-            )
+            format!("{primary_key_field} = ({placeholder}){type_annotation}")
         } else {
-            format!(
-                "SELECT {projection} FROM {table_name} WHERE {primary_key_name} = {placeholder};"
-            )
+            format!("{primary_key_field} = {placeholder}")
         };
+        let filters = query.format_filters_with::<Self>(&condition);
+        let sql = format!("SELECT {projection} FROM {table_name} {filters};");
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(sql);
         ctx.add_argument(primary_key);
 
         let pool = Self::acquire_reader().await?.pool();
-        if let Some(row) = pool
-            .fetch_optional_with(ctx.query(), &[primary_key])
-            .await?
-        {
+        let optional_row = executor::run_query(
+            &ctx,
+            None,
+            pool.fetch_optional_with(ctx.query(), &[primary_key]),
+        )
+        .await?;
+        if let Some(row) = optional_row {
             ctx.set_query_result(1, true);
             Self::after_scan(&ctx).await?;
             Self::after_query(&ctx).await?;
@@ -1611,3 +2662,30 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         }
     }
 }
+
+/// Decodes the public IDs in a filter value recursively.
+/// The operand of the `$is` operator is left as it is.
+fn decode_public_id_filter(codec: &IdCodec, value: &mut JsonValue) -> Result<(), Error> {
+    match value {
+        JsonValue::String(public_id) => {
+            *value = codec.decode(public_id)?.into();
+        }
+        JsonValue::Array(vec) => {
+            for value in vec.iter_mut() {
+                decode_public_id_filter(codec, value)?;
+            }
+        }
+        JsonValue::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if key != "$is" {
+                    decode_public_id_filter(codec, value)?;
+                }
+            }
+        }
+        _ => (),
+    }
+    Ok(())
+}
+
+/// Buffer size of the channel for streaming rows.
+const STREAM_BUFFER_SIZE: usize = 64;
@@ -1,4 +1,4 @@
-use super::{query::QueryExt, DatabaseDriver, DatabaseRow, Schema};
+use super::{column::ColumnExt, query::QueryExt, DatabaseDriver, DatabaseRow, Schema};
 use crate::{
     datetime::{Date, DateTime, Time},
     error::Error,
@@ -93,6 +93,10 @@ impl<'c> EncodeColumn<DatabaseDriver> for Column<'c> {
                     "NULL".into()
                 }
             }
+            "Money" => match self.encode_money(value) {
+                Some(amount) => Query::escape_string(amount).into(),
+                None => "NULL".into(),
+            },
             "DateTime" | "NaiveDateTime" => match value {
                 "epoch" => "datetime(0, 'unixepoch')".into(),
                 "now" => "datetime('now', 'localtime')".into(),
@@ -407,6 +411,7 @@ impl DecodeRow<DatabaseRow> for Map {
     fn decode_row(row: &DatabaseRow) -> Result<Self, Self::Error> {
         let columns = row.columns();
         let mut map = Map::with_capacity(columns.len());
+        let naming = super::NamingConvention::shared();
         for col in columns {
             let field = col.name();
             let index = col.ordinal();
@@ -448,7 +453,7 @@ impl DecodeRow<DatabaseRow> for Map {
                 }
             };
             if !value.is_ignorable() {
-                map.insert(field.to_owned(), value);
+                map.insert(naming.field_name(field).into_owned(), value);
             }
         }
         Ok(map)
@@ -557,19 +562,19 @@ impl QueryExt<DatabaseDriver> for Query {
         crate::helper::prepare_sql_query(query, params, '?')
     }
 
+    #[inline]
     fn format_field(field: &str) -> Cow<'_, str> {
-        if field.contains('.') {
-            field
-                .split('.')
-                .map(|s| format!("`{s}`"))
-                .collect::<Vec<_>>()
-                .join(".")
-                .into()
-        } else {
-            format!("`{field}`").into()
+        match super::NamingConvention::shared().column_name(field) {
+            Cow::Borrowed(field) => super::query::quote_field(field, '`'),
+            Cow::Owned(field) => super::query::quote_field(&field, '`').into_owned().into(),
         }
     }
 
+    #[inline]
+    fn quote_identifier(ident: &str) -> Cow<'_, str> {
+        super::query::quote_identifier(ident, '`')
+    }
+
     fn format_table_fields<M: Schema>(&self) -> Cow<'_, str> {
         let model_name = M::model_name();
         let fields = self.query_fields();
@@ -580,16 +585,12 @@ impl QueryExt<DatabaseDriver> for Query {
                 .iter()
                 .map(|field| {
                     if let Some((alias, expr)) = field.split_once(':') {
-                        let alias = Self::format_field(alias.trim());
+                        let alias = Self::quote_identifier(alias.trim());
                         format!(r#"{expr} AS {alias}"#)
                     } else if field.contains('.') {
-                        field
-                            .split('.')
-                            .map(|s| format!("`{s}`"))
-                            .collect::<Vec<_>>()
-                            .join(".")
+                        Self::format_field(field).into_owned()
                     } else {
-                        format!(r#"`{model_name}`.`{field}`"#)
+                        Self::format_field(&format!("{model_name}.{field}")).into_owned()
                     }
                 })
                 .collect::<Vec<_>>()
@@ -609,11 +610,13 @@ impl QueryExt<DatabaseDriver> for Query {
                 match col.type_name() {
                     "Vec<String>" | "Vec<Uuid>" | "Vec<u64>" | "Vec<i64>" | "Vec<u32>"
                     | "Vec<i32>" => {
-                        let virtual_table = format!("json_each(`{model_name}`.`{col_name}`)");
+                        let field = Self::format_field(&format!("{model_name}.{col_name}"));
+                        let virtual_table = format!("json_each({field})");
                         virtual_tables.push(virtual_table);
                     }
                     "Map" => {
-                        let virtual_table = format!("json_tree(`{model_name}`.`{col_name}`)");
+                        let field = Self::format_field(&format!("{model_name}.{col_name}"));
+                        let virtual_table = format!("json_tree({field})");
                         virtual_tables.push(virtual_table);
                     }
                     _ => (),
@@ -621,10 +624,16 @@ impl QueryExt<DatabaseDriver> for Query {
             }
         }
         if virtual_tables.is_empty() {
-            format!(r#"`{table_name}` AS `{model_name}`"#)
+            format!(
+                "{} AS {}",
+                super::query::quote_identifier(table_name, '`'),
+                super::query::quote_identifier(model_name, '`')
+            )
         } else {
             format!(
-                r#"`{table_name}` AS `{model_name}`, {}"#,
+                "{} AS {}, {}",
+                super::query::quote_identifier(table_name, '`'),
+                super::query::quote_identifier(model_name, '`'),
                 virtual_tables.join(", ")
             )
         }
@@ -633,7 +642,7 @@ impl QueryExt<DatabaseDriver> for Query {
     #[inline]
     fn table_name_escaped<M: Schema>() -> String {
         let table_name = M::table_name();
-        format!(r#"`{table_name}`"#)
+        super::query::quote_identifier(table_name, '`').into_owned()
     }
 
     fn parse_text_search(filter: &Map) -> Option<String> {
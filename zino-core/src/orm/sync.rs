@@ -0,0 +1,354 @@
+use super::{executor::Executor, query::QueryExt, schema::Schema, DatabaseContext, GlobalPool};
+use crate::{
+    crypto,
+    encoding::hex,
+    error::Error,
+    extension::JsonObjectExt,
+    model::{EncodeColumn, Query},
+    warn, JsonValue, Map,
+};
+use serde::Serialize;
+use sqlx::Acquire;
+use std::{collections::HashMap, marker::PhantomData};
+
+/// A utility for synchronizing the rows of a model between two connection pools,
+/// such as promoting the reference data from staging to production.
+///
+/// The rows are matched by the primary key and compared by a hash of the columns.
+/// The plan is applied to the target database inside of a transaction.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino_core::orm::DataSync;
+///
+/// let mut sync = DataSync::<Tag>::try_new("staging", "main")?;
+/// sync.ignore_fields(&["updated_at", "version"]);
+///
+/// let plan = sync.plan().await?;
+/// let report = sync.apply(&plan, true).await?;
+/// for sql in report.statements() {
+///     println!("{sql}");
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DataSync<M: Schema> {
+    /// Source pool name.
+    source: String,
+    /// Target pool name.
+    target: String,
+    /// Query for selecting the rows.
+    query: Query,
+    /// Fields ignored by the comparison.
+    ignored_fields: Vec<&'static str>,
+    /// A flag to delete the rows absent in the source.
+    delete_enabled: bool,
+    /// Phantom type of the model.
+    phantom: PhantomData<M>,
+}
+
+impl<M: Schema> DataSync<M> {
+    /// Attempts to create a new instance for the source and target pools.
+    pub fn try_new(source: &str, target: &str) -> Result<Self, Error> {
+        for name in [source, target] {
+            if GlobalPool::get(name).is_none() {
+                return Err(warn!("the connection pool `{}` does not exist", name));
+            }
+        }
+        if source == target {
+            return Err(warn!("the source and target pools should be different"));
+        }
+
+        let mut query = Query::default();
+        query.disable_limit();
+        Ok(Self {
+            source: source.to_owned(),
+            target: target.to_owned(),
+            query,
+            ignored_fields: Vec::new(),
+            delete_enabled: true,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Sets the query for selecting the rows in both databases.
+    #[inline]
+    pub fn set_query(&mut self, mut query: Query) {
+        query.disable_limit();
+        self.query = query;
+    }
+
+    /// Ignores the fields in the comparison and the updates.
+    #[inline]
+    pub fn ignore_fields(&mut self, fields: &[&'static str]) {
+        self.ignored_fields.extend_from_slice(fields);
+    }
+
+    /// Disables the deletion of the rows which are absent in the source.
+    #[inline]
+    pub fn disable_delete(&mut self) {
+        self.delete_enabled = false;
+    }
+
+    /// Compares the rows between the source and target, and produces a plan.
+    pub async fn plan(&self) -> Result<SyncPlan, Error> {
+        let source_rows = self.fetch_rows(&self.source).await?;
+        let target_rows = self.fetch_rows(&self.target).await?;
+        let primary_key_name = M::PRIMARY_KEY_NAME;
+        let mut target_hashes = target_rows
+            .iter()
+            .filter_map(|row| {
+                let primary_key = row.parse_string(primary_key_name)?.into_owned();
+                Some((primary_key, self.row_hash(row)))
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut plan = SyncPlan::default();
+        for row in source_rows {
+            let Some(primary_key) = row.parse_string(primary_key_name) else {
+                continue;
+            };
+            match target_hashes.remove(primary_key.as_ref()) {
+                Some(hash) if hash == self.row_hash(&row) => (),
+                Some(_) => plan.updates.push(row),
+                None => plan.inserts.push(row),
+            }
+        }
+        if self.delete_enabled {
+            plan.deletes = target_rows
+                .into_iter()
+                .filter_map(|mut row| {
+                    let deleted = row
+                        .parse_string(primary_key_name)
+                        .is_some_and(|key| target_hashes.contains_key(key.as_ref()));
+                    if deleted {
+                        row.remove(primary_key_name)
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+        }
+        Ok(plan)
+    }
+
+    /// Applies the plan to the target database inside of a transaction.
+    /// In the dry-run mode, the statements are reported without being executed.
+    pub async fn apply(&self, plan: &SyncPlan, dry_run: bool) -> Result<SyncReport, Error> {
+        let statements = self.format_statements(plan);
+        let mut report = SyncReport {
+            source: self.source.clone(),
+            target: self.target.clone(),
+            dry_run,
+            num_inserts: plan.inserts.len(),
+            num_updates: plan.updates.len(),
+            num_deletes: plan.deletes.len(),
+            rows_affected: 0,
+            statements,
+        };
+        if dry_run || plan.is_empty() {
+            return Ok(report);
+        }
+
+        let pool = GlobalPool::get(&self.target)
+            .ok_or_else(|| warn!("the connection pool `{}` does not exist", self.target))?;
+        let mut transaction = pool.pool().begin().await?;
+        let connection = transaction.acquire().await?;
+
+        let mut rows_affected = 0;
+        for sql in report.statements.iter() {
+            let query_result = connection.execute(sql).await?;
+            rows_affected += query_result.rows_affected();
+        }
+        transaction.commit().await?;
+        report.rows_affected = rows_affected;
+        tracing::info!(
+            model_name = M::model_name(),
+            source = self.source.as_str(),
+            target = self.target.as_str(),
+            rows_affected,
+            "the data has been synchronized"
+        );
+        Ok(report)
+    }
+
+    /// Produces a plan and applies it.
+    pub async fn sync(&self, dry_run: bool) -> Result<SyncReport, Error> {
+        let plan = self.plan().await?;
+        self.apply(&plan, dry_run).await
+    }
+
+    /// Fetches the rows from the connection pool.
+    async fn fetch_rows(&self, pool_name: &str) -> Result<Vec<Map>, Error> {
+        DatabaseContext::use_pool(pool_name)?
+            .scope(M::find::<Map>(&self.query))
+            .await
+    }
+
+    /// Computes a hash of the columns for the row.
+    fn row_hash(&self, row: &Map) -> String {
+        let values = M::columns()
+            .iter()
+            .map(|col| col.name())
+            .filter(|name| !self.ignored_fields.contains(name))
+            .map(|name| row.get(name).unwrap_or(&JsonValue::Null))
+            .collect::<Vec<_>>();
+        let bytes = serde_json::to_vec(&values).unwrap_or_default();
+        hex::encode(crypto::digest(&bytes))
+    }
+
+    /// Formats the SQL statements for the plan.
+    fn format_statements(&self, plan: &SyncPlan) -> Vec<String> {
+        let table_name = Query::table_name_escaped::<M>();
+        let primary_key_column = M::primary_key_column();
+        let primary_key_field = Query::format_field(M::PRIMARY_KEY_NAME);
+        let columns = M::columns();
+        let mut statements = Vec::with_capacity(plan.num_changes());
+        for row in plan.inserts.iter() {
+            let mut fields = Vec::with_capacity(columns.len());
+            let mut values = Vec::with_capacity(columns.len());
+            for col in columns {
+                if let Some(value) = row.get(col.name()) {
+                    fields.push(Query::format_field(col.name()));
+                    values.push(col.encode_value(Some(value)));
+                }
+            }
+            let fields = fields.join(", ");
+            let values = values.join(", ");
+            statements.push(format!(
+                "INSERT INTO {table_name} ({fields}) VALUES ({values});"
+            ));
+        }
+        for row in plan.updates.iter() {
+            let updates = columns
+                .iter()
+                .filter(|col| {
+                    let name = col.name();
+                    !col.is_primary_key() && !self.ignored_fields.contains(&name)
+                })
+                .filter_map(|col| {
+                    let value = row.get(col.name())?;
+                    let field = Query::format_field(col.name());
+                    let value = col.encode_value(Some(value));
+                    Some(format!("{field} = {value}"))
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            if !updates.is_empty() {
+                let primary_key = primary_key_column.encode_value(row.get(M::PRIMARY_KEY_NAME));
+                statements.push(format!(
+                    "UPDATE {table_name} SET {updates} WHERE {primary_key_field} = {primary_key};"
+                ));
+            }
+        }
+        for value in plan.deletes.iter() {
+            let primary_key = primary_key_column.encode_value(Some(value));
+            statements.push(format!(
+                "DELETE FROM {table_name} WHERE {primary_key_field} = {primary_key};"
+            ));
+        }
+        statements
+    }
+}
+
+/// A plan for synchronizing the rows of a model.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SyncPlan {
+    /// Rows to be inserted.
+    inserts: Vec<Map>,
+    /// Rows to be updated.
+    updates: Vec<Map>,
+    /// Primary keys of the rows to be deleted.
+    deletes: Vec<JsonValue>,
+}
+
+impl SyncPlan {
+    /// Returns the rows to be inserted.
+    #[inline]
+    pub fn inserts(&self) -> &[Map] {
+        &self.inserts
+    }
+
+    /// Returns the rows to be updated.
+    #[inline]
+    pub fn updates(&self) -> &[Map] {
+        &self.updates
+    }
+
+    /// Returns the primary keys of the rows to be deleted.
+    #[inline]
+    pub fn deletes(&self) -> &[JsonValue] {
+        &self.deletes
+    }
+
+    /// Returns the total number of changes.
+    #[inline]
+    pub fn num_changes(&self) -> usize {
+        self.inserts.len() + self.updates.len() + self.deletes.len()
+    }
+
+    /// Returns `true` if there are no changes.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.num_changes() == 0
+    }
+}
+
+/// A report of the data synchronization.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SyncReport {
+    /// Source pool name.
+    source: String,
+    /// Target pool name.
+    target: String,
+    /// Dry-run mode.
+    dry_run: bool,
+    /// Number of inserts.
+    num_inserts: usize,
+    /// Number of updates.
+    num_updates: usize,
+    /// Number of deletes.
+    num_deletes: usize,
+    /// Number of rows affected.
+    rows_affected: u64,
+    /// SQL statements.
+    statements: Vec<String>,
+}
+
+impl SyncReport {
+    /// Returns `true` if the statements have not been executed.
+    #[inline]
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Returns the number of inserts.
+    #[inline]
+    pub fn num_inserts(&self) -> usize {
+        self.num_inserts
+    }
+
+    /// Returns the number of updates.
+    #[inline]
+    pub fn num_updates(&self) -> usize {
+        self.num_updates
+    }
+
+    /// Returns the number of deletes.
+    #[inline]
+    pub fn num_deletes(&self) -> usize {
+        self.num_deletes
+    }
+
+    /// Returns the number of rows affected.
+    #[inline]
+    pub fn rows_affected(&self) -> u64 {
+        self.rows_affected
+    }
+
+    /// Returns the SQL statements.
+    #[inline]
+    pub fn statements(&self) -> &[String] {
+        &self.statements
+    }
+}
@@ -1,17 +1,189 @@
 use super::{
-    executor::Executor, mutation::MutationExt, query::QueryExt, schema::Schema, DatabaseDriver,
+    column::ColumnExt, executor::Executor, mutation::MutationExt, query::QueryExt, schema::Schema,
+    DatabaseContext, DatabaseDriver, GlobalPool,
 };
 use crate::{
-    error::Error,
+    bail,
+    error::{Error, ErrorKind},
     extension::JsonValueExt,
-    model::{EncodeColumn, Mutation, Query},
-    BoxFuture, Map,
+    model::{DecodeRow, EncodeColumn, Mutation, Query, QueryContext},
+    warn, BoxFuture, Map,
 };
-use std::fmt::Display;
+use futures_timer::Delay;
+use rand::{thread_rng, Rng};
+use std::{fmt::Display, time::Duration};
 
+#[cfg(feature = "orm-sqlx")]
+use super::context::begin_transaction;
 #[cfg(feature = "orm-sqlx")]
 use sqlx::Acquire;
 
+/// Isolation levels of a transaction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IsolationLevel {
+    /// The `READ UNCOMMITTED` isolation level.
+    ReadUncommitted,
+    /// The `READ COMMITTED` isolation level.
+    #[default]
+    ReadCommitted,
+    /// The `REPEATABLE READ` isolation level.
+    RepeatableRead,
+    /// The `SERIALIZABLE` isolation level.
+    Serializable,
+}
+
+impl IsolationLevel {
+    /// Returns the SQL representation of the isolation level.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ReadUncommitted => "READ UNCOMMITTED",
+            Self::ReadCommitted => "READ COMMITTED",
+            Self::RepeatableRead => "REPEATABLE READ",
+            Self::Serializable => "SERIALIZABLE",
+        }
+    }
+}
+
+/// Access modes of a transaction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AccessMode {
+    /// The `READ WRITE` access mode.
+    #[default]
+    ReadWrite,
+    /// The `READ ONLY` access mode.
+    ReadOnly,
+    /// The `READ ONLY DEFERRABLE` access mode, in which a serializable transaction
+    /// waits for a safe snapshot and then runs without the risk of serialization failures.
+    /// It is only supported by PostgreSQL and falls back to `READ ONLY` for other drivers.
+    ReadOnlyDeferrable,
+}
+
+impl AccessMode {
+    /// Returns the SQL representation of the access mode.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ReadWrite => "READ WRITE",
+            Self::ReadOnly => "READ ONLY",
+            Self::ReadOnlyDeferrable => {
+                if cfg!(any(
+                    feature = "orm-mariadb",
+                    feature = "orm-mysql",
+                    feature = "orm-tidb"
+                )) || !cfg!(feature = "orm-postgres")
+                {
+                    "READ ONLY"
+                } else {
+                    "READ ONLY DEFERRABLE"
+                }
+            }
+        }
+    }
+
+    /// Returns `true` if the transaction is read-only.
+    #[inline]
+    pub fn is_read_only(&self) -> bool {
+        !matches!(self, Self::ReadWrite)
+    }
+}
+
+/// Begins a transaction with the isolation level and access mode on the connection pool.
+///
+/// MySQL requires the characteristics to be set before the transaction starts,
+/// while PostgreSQL requires them to be the first statement inside of the transaction.
+/// SQLite transactions are always serializable, so the characteristics are ignored.
+#[cfg(feature = "orm-sqlx")]
+async fn begin_with_options(
+    pool: &sqlx::Pool<DatabaseDriver>,
+    isolation: IsolationLevel,
+    access_mode: AccessMode,
+) -> Result<sqlx::Transaction<'static, DatabaseDriver>, Error> {
+    let isolation_level = isolation.as_str();
+    let access_mode = access_mode.as_str();
+    let sql = format!("SET TRANSACTION ISOLATION LEVEL {isolation_level}, {access_mode};");
+    if cfg!(any(
+        feature = "orm-mariadb",
+        feature = "orm-mysql",
+        feature = "orm-tidb"
+    )) {
+        let mut connection = pool.acquire().await?;
+        (&mut *connection).execute(&sql).await?;
+        Ok(sqlx::Transaction::begin(connection).await?)
+    } else if cfg!(feature = "orm-postgres") {
+        let mut transaction = pool.begin().await?;
+        transaction.acquire().await?.execute(&sql).await?;
+        #[cfg(feature = "orm-postgres")]
+        super::context::set_local_session_variables(&mut transaction).await?;
+        Ok(transaction)
+    } else {
+        Ok(pool.begin().await?)
+    }
+}
+
+/// Runs the closure inside of a transaction with the isolation level
+/// and retries it on serialization failures or deadlocks with an exponential backoff.
+/// The connection pool is selected by the current [`DatabaseContext`]
+/// or falls back to the `main` pool.
+///
+/// The closure may be called several times, so it should not have side effects
+/// outside of the transaction.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino_core::orm::{self, IsolationLevel};
+///
+/// orm::transaction_with_retry(IsolationLevel::Serializable, 3, |tx| {
+///     Box::pin(async move {
+///         let connection = tx.acquire().await?;
+///         connection.execute(withdraw_sql).await?;
+///         connection.execute(deposit_sql).await?;
+///         Ok(())
+///     })
+/// })
+/// .await?;
+/// ```
+#[cfg(feature = "orm-sqlx")]
+pub async fn transaction_with_retry<F, T>(
+    isolation: IsolationLevel,
+    max_retries: usize,
+    mut tx: F,
+) -> Result<T, Error>
+where
+    F: for<'t> FnMut(
+        &'t mut sqlx::Transaction<'static, DatabaseDriver>,
+    ) -> BoxFuture<'t, Result<T, Error>>,
+{
+    let pool = DatabaseContext::current_pool()
+        .or_else(|| GlobalPool::get("main"))
+        .ok_or_else(|| warn!("503 Service Unavailable: no connection pool is available"))?
+        .pool();
+    let mut retries = 0;
+    loop {
+        let result = async {
+            let mut transaction =
+                begin_with_options(pool, isolation, AccessMode::ReadWrite).await?;
+            let data = tx(&mut transaction).await?;
+            transaction.commit().await?;
+            Ok::<_, Error>(data)
+        }
+        .await;
+        match result {
+            Err(err) if retries < max_retries && err.kind() == ErrorKind::Serialization => {
+                retries += 1;
+
+                // Exponential backoff with a random jitter.
+                let base_millis = 10 << retries.min(10);
+                let millis = base_millis + thread_rng().gen_range(0..=base_millis);
+                tracing::warn!(retries, "retry the transaction after {millis}ms: {err}");
+                Delay::new(Duration::from_millis(millis)).await;
+            }
+            result => return result,
+        }
+    }
+}
+
 /// An in-progress database transaction.
 pub trait Transaction<K, Tx>: Schema<PrimaryKey = K>
 where
@@ -24,11 +196,42 @@ where
     where
         F: for<'t> FnOnce(&'t mut Tx) -> BoxFuture<'t, Result<T, Error>>;
 
+    /// Begins a transaction with the isolation level and access mode.
+    /// The reader is used for a read-only transaction, and the writer is used otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use zino_core::orm::{AccessMode, IsolationLevel, Transaction};
+    ///
+    /// // Runs the reporting queries under snapshot isolation without blocking writers.
+    /// let mut transaction = Order::begin_with(
+    ///     IsolationLevel::Serializable,
+    ///     AccessMode::ReadOnlyDeferrable,
+    /// )
+    /// .await?;
+    /// let rows = transaction.acquire().await?.fetch(report_sql).await?;
+    /// transaction.commit().await?;
+    /// ```
+    async fn begin_with(isolation: IsolationLevel, access_mode: AccessMode) -> Result<Tx, Error>;
+
     /// Executes the queries sequentially inside of a transaction.
     /// If it returns an error, the transaction will be rolled back;
     /// if not, the transaction will be committed.
     async fn transactional_execute(queries: &[&str], params: Option<&Map>) -> Result<u64, Error>;
 
+    /// Executes the prepared queries sequentially inside of a transaction.
+    /// If it returns an error, the transaction will be rolled back;
+    /// if not, the transaction will be committed. Each query is expected to affect
+    /// at least one row, otherwise a `409 Conflict` error will be returned,
+    /// e.g. the version of a model to be updated has been changed.
+    async fn transactional_batch(ctxs: &mut [QueryContext]) -> Result<u64, Error>;
+
+    /// Executes the prepared queries sequentially inside of a sandbox transaction,
+    /// which is always rolled back at the end. The rows fetched by the `SELECT` queries
+    /// are returned in order, and an empty list is returned for the other queries.
+    async fn sandbox_batch(ctxs: &mut [QueryContext]) -> Result<Vec<Vec<Map>>, Error>;
+
     /// Inserts the model and its associations inside of a transaction.
     async fn transactional_insert<M: Schema>(self, models: Vec<M>) -> Result<u64, Error>;
 
@@ -54,14 +257,26 @@ where
             &'t mut sqlx::Transaction<'c, DatabaseDriver>,
         ) -> BoxFuture<'t, Result<T, Error>>,
     {
-        let mut transaction = Self::acquire_writer().await?.pool().begin().await?;
+        let mut transaction = begin_transaction(Self::acquire_writer().await?.pool()).await?;
         let data = tx(&mut transaction).await?;
         transaction.commit().await?;
         Ok(data)
     }
 
+    async fn begin_with(
+        isolation: IsolationLevel,
+        access_mode: AccessMode,
+    ) -> Result<sqlx::Transaction<'c, DatabaseDriver>, Error> {
+        let connection_pool = if access_mode.is_read_only() {
+            Self::acquire_reader().await?
+        } else {
+            Self::acquire_writer().await?
+        };
+        begin_with_options(connection_pool.pool(), isolation, access_mode).await
+    }
+
     async fn transactional_execute(queries: &[&str], params: Option<&Map>) -> Result<u64, Error> {
-        let mut transaction = Self::acquire_writer().await?.pool().begin().await?;
+        let mut transaction = begin_transaction(Self::acquire_writer().await?.pool()).await?;
         let connection = transaction.acquire().await?;
 
         let mut total_rows = 0;
@@ -87,8 +302,75 @@ where
         Ok(total_rows)
     }
 
+    async fn transactional_batch(ctxs: &mut [QueryContext]) -> Result<u64, Error> {
+        let mut transaction = begin_transaction(Self::acquire_writer().await?.pool()).await?;
+        let connection = transaction.acquire().await?;
+
+        let mut total_rows = 0;
+        for ctx in ctxs.iter_mut().filter(|ctx| !ctx.is_cancelled()) {
+            let query_result = connection.execute(ctx.query()).await?;
+            let (last_insert_id, rows_affected) = Query::parse_query_result(query_result);
+            if let Some(last_insert_id) = last_insert_id {
+                ctx.set_last_insert_id(last_insert_id);
+            }
+            total_rows += rows_affected;
+            ctx.set_query_result(rows_affected, rows_affected > 0);
+            Self::after_scan(ctx).await?;
+            if rows_affected == 0 {
+                transaction.rollback().await?;
+                bail!(
+                    "409 Conflict: no rows are affected by the query `{}`",
+                    ctx.query_id()
+                );
+            }
+        }
+        transaction.commit().await?;
+        Ok(total_rows)
+    }
+
+    async fn sandbox_batch(ctxs: &mut [QueryContext]) -> Result<Vec<Vec<Map>>, Error> {
+        let mut transaction = begin_transaction(Self::acquire_writer().await?.pool()).await?;
+        let connection = transaction.acquire().await?;
+
+        let mut results = Vec::with_capacity(ctxs.len());
+        for ctx in ctxs.iter_mut() {
+            if ctx.is_cancelled() {
+                results.push(Vec::new());
+                continue;
+            }
+
+            let is_select = ctx
+                .query()
+                .trim_start()
+                .get(..6)
+                .is_some_and(|s| s.eq_ignore_ascii_case("select"));
+            if is_select {
+                let rows = connection.fetch(ctx.query()).await?;
+                let mut data = Vec::with_capacity(rows.len());
+                for row in rows {
+                    data.push(Map::decode_row(&row)?);
+                }
+                ctx.set_query_result(u64::try_from(data.len())?, true);
+                results.push(data);
+            } else {
+                let query_result = connection.execute(ctx.query()).await?;
+                let (last_insert_id, rows_affected) = Query::parse_query_result(query_result);
+                if let Some(last_insert_id) = last_insert_id {
+                    ctx.set_last_insert_id(last_insert_id);
+                }
+                ctx.set_query_result(rows_affected, true);
+                results.push(Vec::new());
+            }
+            Self::after_scan(ctx).await?;
+        }
+
+        // Rolls back the transaction
+        transaction.rollback().await?;
+        Ok(results)
+    }
+
     async fn transactional_insert<S: Schema>(mut self, associations: Vec<S>) -> Result<u64, Error> {
-        let mut transaction = Self::acquire_writer().await?.pool().begin().await?;
+        let mut transaction = begin_transaction(Self::acquire_writer().await?.pool()).await?;
         let connection = transaction.acquire().await?;
 
         // Inserts the model
@@ -100,12 +382,12 @@ where
         let values = columns
             .iter()
             .filter_map(|col| {
-                if col.auto_increment() {
-                    None
-                } else {
+                if col.is_insertable() {
                     let name = col.name();
                     fields.push(name);
-                    Some(col.encode_value(map.get(name)))
+                    Some(col.encode_insert_value(map.get(name)))
+                } else {
+                    None
                 }
             })
             .collect::<Vec<_>>()
@@ -129,21 +411,28 @@ where
         Self::after_insert(&ctx, model_data).await?;
 
         // Inserts associations
-        let columns = S::columns();
+        let columns = S::columns()
+            .iter()
+            .filter(|col| !col.is_generated())
+            .collect::<Vec<_>>();
         let mut values = Vec::with_capacity(associations.len());
         for mut association in associations.into_iter() {
             let _association_data = association.before_insert().await?;
             let map = association.into_map();
             let entries = columns
                 .iter()
-                .map(|col| col.encode_value(map.get(col.name())))
+                .map(|col| col.encode_insert_value(map.get(col.name())))
                 .collect::<Vec<_>>()
                 .join(", ");
             values.push(format!("({entries})"));
         }
 
         let table_name = Query::table_name_escaped::<S>();
-        let fields = S::fields().join(", ");
+        let fields = columns
+            .iter()
+            .map(|col| col.name())
+            .collect::<Vec<_>>()
+            .join(", ");
         let values = values.join(", ");
         let sql = format!("INSERT INTO {table_name} ({fields}) VALUES {values};");
         let mut ctx = S::before_scan(&sql).await?;
@@ -163,7 +452,7 @@ where
         queries: (&Query, &Query),
         mutations: (&mut Mutation, &mut Mutation),
     ) -> Result<u64, Error> {
-        let mut transaction = Self::acquire_writer().await?.pool().begin().await?;
+        let mut transaction = begin_transaction(Self::acquire_writer().await?.pool()).await?;
         let connection = transaction.acquire().await?;
 
         let query = queries.0;
@@ -207,11 +496,13 @@ where
     }
 
     async fn transactional_delete<S: Schema>(queries: (&Query, &Query)) -> Result<u64, Error> {
-        let mut transaction = Self::acquire_writer().await?.pool().begin().await?;
+        let mut transaction = begin_transaction(Self::acquire_writer().await?.pool()).await?;
         let connection = transaction.acquire().await?;
 
         let query = queries.0;
-        Self::before_query(query).await?;
+        let mut query = query.clone();
+        Self::before_query(&mut query).await?;
+        let query = &query;
 
         let table_name = query.format_table_name::<Self>();
         let filters = query.format_filters::<Self>();
@@ -227,7 +518,9 @@ where
         Self::after_query(&ctx).await?;
 
         let query = queries.1;
-        S::before_query(query).await?;
+        let mut query = query.clone();
+        S::before_query(&mut query).await?;
+        let query = &query;
 
         let table_name = query.format_table_name::<S>();
         let filters = query.format_filters::<S>();
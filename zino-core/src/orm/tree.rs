@@ -0,0 +1,221 @@
+use super::{query::QueryExt, schema::Schema};
+use crate::{
+    bail,
+    error::Error,
+    extension::{JsonObjectExt, JsonValueExt},
+    model::{Mutation, Query},
+    warn, JsonValue, Map, Uuid,
+};
+use std::fmt::Display;
+
+/// Queries on hierarchical models.
+///
+/// A model is hierarchical if the `TREE_PARENT_FIELD` is specified,
+/// which can be derived by `#[schema(tree = "parent_id")]`.
+/// The materialized path of each node is maintained in the `TREE_PATH_FIELD` column
+/// in the form of `/{root_id}/.../{parent_id}/{id}/`, so that the ancestors and descendants
+/// of a node can be found by a single query.
+pub trait TreeQuery<K>: Schema<PrimaryKey = K>
+where
+    K: Default + Display + PartialEq,
+{
+    /// Returns the materialized path of the node.
+    async fn tree_path(id: &K) -> Result<String, Error> {
+        find_tree_path::<Self>(&id.to_string()).await
+    }
+
+    /// Finds the ancestors of the node, in the order from the root to the parent.
+    async fn ancestors(id: &K) -> Result<Vec<Map>, Error> {
+        let id = id.to_string();
+        let path = find_tree_path::<Self>(&id).await?;
+        let ancestor_ids = path
+            .split('/')
+            .filter(|&s| !s.is_empty() && s != id)
+            .collect::<Vec<_>>();
+        if ancestor_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut query = Self::default_query();
+        query.add_filter(Self::PRIMARY_KEY_NAME, Map::from_entry("$in", ancestor_ids));
+        query.disable_limit();
+
+        let mut ancestors = Self::find::<Map>(&query).await?;
+        let path_field = Self::TREE_PATH_FIELD;
+        ancestors.sort_by_key(|model| model.get_str(path_field).map(|s| s.len()));
+        Ok(ancestors)
+    }
+
+    /// Finds the descendants of the node, in the order of the materialized paths.
+    async fn descendants(id: &K) -> Result<Vec<Map>, Error> {
+        let path = find_tree_path::<Self>(&id.to_string()).await?;
+        let path_field = Self::TREE_PATH_FIELD;
+        let mut query = Self::default_query();
+        query.add_filter(path_field, Map::from_entry("$like", format!("{path}_%")));
+        query.order_asc(path_field);
+        query.disable_limit();
+        Self::find(&query).await
+    }
+
+    /// Collects the unique values of an array field, such as `members`,
+    /// over the subtree rooted at the node.
+    async fn subtree_members(id: &K, field: &str) -> Result<Vec<JsonValue>, Error> {
+        let path = find_tree_path::<Self>(&id.to_string()).await?;
+        let mut query = Query::default();
+        query.allow_fields(&[field]);
+        query.add_filter(
+            Self::TREE_PATH_FIELD,
+            Map::from_entry("$like", format!("{path}%")),
+        );
+        query.disable_limit();
+
+        let mut members = Vec::new();
+        for mut model in Self::find::<Map>(&query).await? {
+            if let Some(JsonValue::Array(values)) = model.remove(field) {
+                for value in values {
+                    if !members.contains(&value) {
+                        members.push(value);
+                    }
+                }
+            }
+        }
+        Ok(members)
+    }
+
+    /// Rebuilds the materialized path of the node from its parent,
+    /// and updates the paths of its descendants.
+    /// It returns the number of rows affected.
+    async fn rebuild_tree_path(id: &K) -> Result<u64, Error> {
+        rebuild_tree_path::<Self>(&id.to_string()).await
+    }
+}
+
+impl<M, K> TreeQuery<K> for M
+where
+    M: Schema<PrimaryKey = K>,
+    K: Default + Display + PartialEq,
+{
+}
+
+/// Returns the parent field of a hierarchical model.
+fn parent_field<M: Schema>() -> Result<&'static str, Error> {
+    let model_name = M::model_name();
+    M::TREE_PARENT_FIELD.ok_or_else(|| warn!("the model `{}` is not hierarchical", model_name))
+}
+
+/// Parses the parent ID, which is `None` for a root node.
+fn parse_parent_id(value: Option<&JsonValue>) -> Option<String> {
+    value
+        .and_then(|v| v.parse_string())
+        .filter(|s| !s.is_empty() && s.parse::<Uuid>().map_or(true, |id| !id.is_nil()))
+        .map(|s| s.into_owned())
+}
+
+/// Finds the materialized path of the node.
+async fn find_tree_path<M: Schema>(id: &str) -> Result<String, Error> {
+    let parent_field = parent_field::<M>()?;
+    let path_field = M::TREE_PATH_FIELD;
+    let mut query = Query::default();
+    query.allow_fields(&[path_field, parent_field]);
+    query.add_filter(M::PRIMARY_KEY_NAME, id);
+
+    let Some(model) = M::find_one::<Map>(&query).await? else {
+        bail!("404 Not Found: cannot find the node `{}`", id);
+    };
+    match model.get_str(path_field).filter(|s| !s.is_empty()) {
+        Some(path) => Ok(path.to_owned()),
+        None => compute_tree_path::<M>(id, parse_parent_id(model.get(parent_field))).await,
+    }
+}
+
+/// Computes the materialized path of the node with the parent.
+async fn compute_tree_path<M: Schema>(
+    id: &str,
+    parent_id: Option<String>,
+) -> Result<String, Error> {
+    let parent_field = parent_field::<M>()?;
+    let path_field = M::TREE_PATH_FIELD;
+    let mut segments = vec![id.to_owned()];
+    let mut next_parent_id = parent_id;
+    while let Some(parent_id) = next_parent_id.take() {
+        if segments.contains(&parent_id) {
+            bail!("the node `{}` can not be a descendant of itself", id);
+        }
+
+        let mut query = Query::default();
+        query.allow_fields(&[path_field, parent_field]);
+        query.add_filter(M::PRIMARY_KEY_NAME, parent_id.as_str());
+
+        let Some(parent) = M::find_one::<Map>(&query).await? else {
+            bail!("404 Not Found: cannot find the parent node `{}`", parent_id);
+        };
+        if let Some(parent_path) = parent.get_str(path_field).filter(|s| !s.is_empty()) {
+            if parent_path.contains(&format!("/{id}/")) {
+                bail!("the node `{}` can not be a descendant of itself", id);
+            }
+            segments.reverse();
+            return Ok(format!("{parent_path}{}/", segments.join("/")));
+        }
+        segments.push(parent_id);
+        next_parent_id = parse_parent_id(parent.get(parent_field));
+    }
+    segments.reverse();
+    Ok(format!("/{}/", segments.join("/")))
+}
+
+/// Checks that the parent does not introduce a cycle for the node.
+pub(super) async fn check_tree_parent<M: Schema>(
+    id: &str,
+    parent_id: Option<&JsonValue>,
+) -> Result<(), Error> {
+    compute_tree_path::<M>(id, parse_parent_id(parent_id))
+        .await
+        .map(|_| ())
+}
+
+/// Rebuilds the materialized path of the node and its descendants.
+pub(super) async fn rebuild_tree_path<M: Schema>(id: &str) -> Result<u64, Error> {
+    let parent_field = parent_field::<M>()?;
+    let path_field = M::TREE_PATH_FIELD;
+    let mut query = Query::default();
+    query.allow_fields(&[path_field, parent_field]);
+    query.add_filter(M::PRIMARY_KEY_NAME, id);
+
+    let Some(model) = M::find_one::<Map>(&query).await? else {
+        bail!("404 Not Found: cannot find the node `{}`", id);
+    };
+    let old_path = model.get_str(path_field).unwrap_or_default();
+    let new_path = compute_tree_path::<M>(id, parse_parent_id(model.get(parent_field))).await?;
+    if old_path == new_path {
+        return Ok(0);
+    }
+
+    let mut mutation = Mutation::from_entry(path_field, new_path.as_str());
+    let ctx = M::update_one(&query, &mut mutation).await?;
+    let mut rows_affected = ctx.rows_affected().unwrap_or_default();
+    if !old_path.is_empty() {
+        let table_name = Query::table_name_escaped::<M>();
+        let path_column = Query::format_field(path_field);
+        let start = old_path.len() + 1;
+        let new_path_expr = if cfg!(any(
+            feature = "orm-mariadb",
+            feature = "orm-mysql",
+            feature = "orm-tidb"
+        )) {
+            format!("CONCAT(#{{new_path}}, SUBSTR({path_column}, {start}))")
+        } else {
+            format!("#{{new_path}} || SUBSTR({path_column}, {start})")
+        };
+        let sql = format!(
+            "UPDATE {table_name} SET {path_column} = {new_path_expr} \
+                WHERE {path_column} LIKE #{{pattern}};"
+        );
+        let mut params = Map::new();
+        params.upsert("new_path", new_path);
+        params.upsert("pattern", format!("{old_path}_%"));
+
+        let ctx = M::execute(&sql, Some(&params)).await?;
+        rows_affected += ctx.rows_affected().unwrap_or_default();
+    }
+    Ok(rows_affected)
+}
@@ -0,0 +1,69 @@
+//! Database views and materialized views for the models.
+
+use crate::{
+    crypto,
+    encoding::hex,
+    extension::{JsonObjectExt, TomlTableExt},
+    schedule::{AsyncCronJob, AsyncJob},
+    state::State,
+    LazyLock,
+};
+use toml::Table;
+
+/// Creates a scheduled job for refreshing the materialized view of a model.
+/// The job is configured by the `[view]` table:
+///
+/// ```toml
+/// [view]
+/// refresh-cron = "0 0 * * * *"
+/// concurrently = false
+/// ```
+///
+/// Refreshing a materialized view concurrently does not block the readers,
+/// but it requires a unique index on the view.
+///
+/// It is used by the `Schema` derive macro and should not be called directly.
+#[doc(hidden)]
+pub fn new_refresh_job(model_name: &str, exec: AsyncCronJob) -> AsyncJob {
+    let config = &SHARED_VIEW_CONFIG;
+    let mut job = AsyncJob::new(&config.refresh_cron, exec).name(format!("{model_name}_refresh"));
+    job.data_mut().upsert("concurrently", config.concurrently);
+    job
+}
+
+/// Returns the checksum of the view definition, which is stored as the comment of
+/// the materialized view to determine whether it should be recreated.
+pub(super) fn checksum(definition: &str) -> String {
+    let digest = crypto::digest(definition.as_bytes());
+    format!("zino:view:{}", hex::encode(&digest[..16]))
+}
+
+/// View config.
+struct ViewConfig {
+    /// Cron expression of the refresh job.
+    refresh_cron: String,
+    /// Flag to indicate whether the materialized views are refreshed concurrently.
+    concurrently: bool,
+}
+
+impl ViewConfig {
+    /// Creates a new instance with the configuration.
+    fn with_config(config: &Table) -> Self {
+        Self {
+            refresh_cron: config
+                .get_str("refresh-cron")
+                .unwrap_or("@hourly")
+                .to_owned(),
+            concurrently: config.get_bool("concurrently").unwrap_or_default(),
+        }
+    }
+}
+
+/// Shared view config.
+static SHARED_VIEW_CONFIG: LazyLock<ViewConfig> = LazyLock::new(|| {
+    let config = State::shared()
+        .get_config("view")
+        .cloned()
+        .unwrap_or_default();
+    ViewConfig::with_config(&config)
+});
@@ -1,5 +1,5 @@
 use crate::Uuid;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 #[cfg(feature = "i18n")]
 use unic_langid::LanguageIdentifier;
@@ -17,6 +17,10 @@ pub struct Context {
     trace_id: Uuid,
     /// Session ID.
     session_id: Option<String>,
+    /// Negotiated content type of the response body.
+    content_type: Option<&'static str>,
+    /// Handler timeout.
+    timeout: Option<Duration>,
     /// Locale.
     #[cfg(feature = "i18n")]
     locale: Option<LanguageIdentifier>,
@@ -31,6 +35,8 @@ impl Context {
             request_id,
             trace_id: Uuid::nil(),
             session_id: None,
+            content_type: None,
+            timeout: None,
             #[cfg(feature = "i18n")]
             locale: None,
         }
@@ -54,6 +60,12 @@ impl Context {
         self.session_id = session_id;
     }
 
+    /// Sets the negotiated content type of the response body.
+    #[inline]
+    pub fn set_content_type(&mut self, content_type: Option<&'static str>) {
+        self.content_type = content_type;
+    }
+
     /// Sets the locale.
     #[cfg(feature = "i18n")]
     #[inline]
@@ -64,6 +76,12 @@ impl Context {
         }
     }
 
+    /// Sets the handler timeout.
+    #[inline]
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
     /// Returns the start time.
     #[inline]
     pub fn start_time(&self) -> Instant {
@@ -94,6 +112,25 @@ impl Context {
         self.session_id.as_deref()
     }
 
+    /// Returns the negotiated content type of the response body.
+    #[inline]
+    pub fn content_type(&self) -> Option<&'static str> {
+        self.content_type
+    }
+
+    /// Returns the handler timeout.
+    #[inline]
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Returns the remaining time before the handler times out.
+    #[inline]
+    pub fn remaining_time(&self) -> Option<Duration> {
+        self.timeout
+            .map(|timeout| timeout.saturating_sub(self.start_time.elapsed()))
+    }
+
     /// Returns the locale.
     #[cfg(feature = "i18n")]
     pub fn locale(&self) -> Option<&LanguageIdentifier> {
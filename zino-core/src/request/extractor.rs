@@ -0,0 +1,250 @@
+use super::{Context, QueryParams, RequestContext};
+use crate::{
+    auth::UserSession,
+    model::ModelHooks,
+    response::{Rejection, StatusCode},
+    state::{Container, Service, State},
+    warn,
+};
+use serde::de::DeserializeOwned;
+use std::marker::PhantomData;
+
+#[cfg(feature = "i18n")]
+use unic_langid::LanguageIdentifier;
+
+/// Types that can be extracted from a request.
+///
+/// It is implemented for the built-in extractors and the tuples of them,
+/// so a handler can declare all the values it needs in a single statement
+/// with [`extract()`](RequestContext::extract), no matter which framework is used.
+/// The extractors consuming the request body should be placed at the end of a tuple.
+/// An optional extractor `Option<T>` only yields `None` when the value is absent,
+/// and the other rejections are still returned.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino::{prelude::*, Request, Response, Result};
+/// use zino_core::request::{Params, Tenant, Validated};
+///
+/// pub async fn new(mut req: Request) -> Result {
+///     let (session, Tenant(tenant_id, ..), Params(params), Validated(task)) = req
+///         .extract::<(UserSession<Uuid>, Tenant<Uuid>, Params<TaskParams>, Validated<Task>)>()
+///         .await?;
+///     ...
+/// }
+/// ```
+pub trait FromRequest: Sized {
+    /// Extracts the value from the request.
+    async fn from_request<Ctx: RequestContext>(req: &mut Ctx) -> Result<Self, Rejection>;
+
+    /// Extracts the optional value from the request.
+    /// It should return `None` only if the value is absent.
+    #[inline]
+    async fn from_request_optional<Ctx: RequestContext>(
+        req: &mut Ctx,
+    ) -> Result<Option<Self>, Rejection> {
+        Self::from_request(req).await.map(Some)
+    }
+}
+
+impl<T: FromRequest> FromRequest for Option<T> {
+    #[inline]
+    async fn from_request<Ctx: RequestContext>(req: &mut Ctx) -> Result<Self, Rejection> {
+        T::from_request_optional(req).await
+    }
+}
+
+impl FromRequest for Context {
+    #[inline]
+    async fn from_request<Ctx: RequestContext>(req: &mut Ctx) -> Result<Self, Rejection> {
+        req.get_context().ok_or_else(|| {
+            let err = warn!("the request context has not been initialized");
+            Rejection::internal_server_error(err).context(req)
+        })
+    }
+
+    #[inline]
+    async fn from_request_optional<Ctx: RequestContext>(
+        req: &mut Ctx,
+    ) -> Result<Option<Self>, Rejection> {
+        Ok(req.get_context())
+    }
+}
+
+impl<U, R, T> FromRequest for UserSession<U, R, T>
+where
+    Self: Clone + Send + Sync + 'static,
+{
+    #[inline]
+    async fn from_request<Ctx: RequestContext>(req: &mut Ctx) -> Result<Self, Rejection> {
+        req.get_data::<Self>().ok_or_else(|| {
+            let err = warn!("401 Unauthorized: the user session is missing");
+            Rejection::unauthorized(err).context(req)
+        })
+    }
+
+    #[inline]
+    async fn from_request_optional<Ctx: RequestContext>(
+        req: &mut Ctx,
+    ) -> Result<Option<Self>, Rejection> {
+        Ok(req.get_data::<Self>())
+    }
+}
+
+/// An extractor for the typed state container provided by [`State::provide()`].
+#[derive(Debug)]
+pub struct Inject<T: 'static>(pub &'static T);
+
+impl<T: Send + Sync + 'static> FromRequest for Inject<T> {
+    #[inline]
+    async fn from_request<Ctx: RequestContext>(req: &mut Ctx) -> Result<Self, Rejection> {
+        State::inject().map(Self).ok_or_else(|| {
+            let err = warn!(
+                "the state of type `{}` has not been provided",
+                std::any::type_name::<T>()
+            );
+            Rejection::internal_server_error(err).context(req)
+        })
+    }
+
+    #[inline]
+    async fn from_request_optional<Ctx: RequestContext>(
+        _req: &mut Ctx,
+    ) -> Result<Option<Self>, Rejection> {
+        Ok(State::inject().map(Self))
+    }
+}
+
+impl<T: Send + Sync + 'static> FromRequest for Service<T> {
+    #[inline]
+    async fn from_request<Ctx: RequestContext>(req: &mut Ctx) -> Result<Self, Rejection> {
+        Container::shared().resolve_in(req).ok_or_else(|| {
+            let err = warn!(
+                "the service of type `{}` has not been registered",
+                std::any::type_name::<T>()
+            );
+            Rejection::internal_server_error(err).context(req)
+        })
+    }
+
+    #[inline]
+    async fn from_request_optional<Ctx: RequestContext>(
+        req: &mut Ctx,
+    ) -> Result<Option<Self>, Rejection> {
+        Ok(Container::shared().resolve_in(req))
+    }
+}
+
+/// An extractor for the tenant ID of the [`UserSession<U, R, T>`](UserSession).
+#[derive(Debug, Clone)]
+pub struct Tenant<T, U = T, R = String>(pub T, pub PhantomData<(U, R)>);
+
+impl<T, U, R> Tenant<T, U, R> {
+    /// Consumes `self` and returns the tenant ID.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T, U, R> FromRequest for Tenant<T, U, R>
+where
+    T: Clone,
+    UserSession<U, R, T>: Clone + Send + Sync + 'static,
+{
+    async fn from_request<Ctx: RequestContext>(req: &mut Ctx) -> Result<Self, Rejection> {
+        let session = UserSession::<U, R, T>::from_request(req).await?;
+        match session.tenant_id() {
+            Some(tenant_id) => Ok(Self(tenant_id.clone(), PhantomData)),
+            None => {
+                let err = warn!("403 Forbidden: the user does not belong to any tenant");
+                Err(Rejection::forbidden(err).context(req))
+            }
+        }
+    }
+
+    async fn from_request_optional<Ctx: RequestContext>(
+        req: &mut Ctx,
+    ) -> Result<Option<Self>, Rejection> {
+        let session = UserSession::<U, R, T>::from_request_optional(req).await?;
+        Ok(session.and_then(|session| {
+            let tenant_id = session.tenant_id()?.clone();
+            Some(Self(tenant_id, PhantomData))
+        }))
+    }
+}
+
+/// An extractor for the locale of the request,
+/// which falls back to the default locale.
+#[cfg(feature = "i18n")]
+#[derive(Debug, Clone)]
+pub struct Locale(pub LanguageIdentifier);
+
+#[cfg(feature = "i18n")]
+impl FromRequest for Locale {
+    async fn from_request<Ctx: RequestContext>(req: &mut Ctx) -> Result<Self, Rejection> {
+        if let Some(locale) = req.locale() {
+            return Ok(Self(locale));
+        }
+
+        let default_locale = crate::i18n::default_locale();
+        default_locale
+            .parse()
+            .map(Self)
+            .map_err(|err| Rejection::from_validation_entry("locale", err).context(req))
+    }
+}
+
+/// An extractor for the typed query parameters which implement [`QueryParams`].
+#[derive(Debug, Clone)]
+pub struct Params<T>(pub T);
+
+impl<T: QueryParams> FromRequest for Params<T> {
+    #[inline]
+    async fn from_request<Ctx: RequestContext>(req: &mut Ctx) -> Result<Self, Rejection> {
+        req.parse_query_params().map(Self)
+    }
+}
+
+/// An extractor for the request body deserialized by [`parse_body()`](RequestContext::parse_body).
+#[derive(Debug, Clone)]
+pub struct Body<T>(pub T);
+
+impl<T: DeserializeOwned> FromRequest for Body<T> {
+    #[inline]
+    async fn from_request<Ctx: RequestContext>(req: &mut Ctx) -> Result<Self, Rejection> {
+        req.parse_body().await.map(Self)
+    }
+}
+
+/// An extractor for the model which has been validated by
+/// [`model_validation()`](RequestContext::model_validation).
+#[derive(Debug, Clone)]
+pub struct Validated<M>(pub M);
+
+impl<M: ModelHooks> FromRequest for Validated<M> {
+    async fn from_request<Ctx: RequestContext>(req: &mut Ctx) -> Result<Self, Rejection> {
+        let mut model = M::new();
+        req.model_validation::<M, StatusCode>(&mut model).await?;
+        Ok(Self(model))
+    }
+}
+
+macro_rules! impl_from_request_for_tuple {
+    ($($ty:ident),+) => {
+        impl<$($ty: FromRequest),+> FromRequest for ($($ty,)+) {
+            #[inline]
+            async fn from_request<Ctx: RequestContext>(req: &mut Ctx) -> Result<Self, Rejection> {
+                Ok(($($ty::from_request(req).await?,)+))
+            }
+        }
+    };
+}
+
+impl_from_request_for_tuple!(T1);
+impl_from_request_for_tuple!(T1, T2);
+impl_from_request_for_tuple!(T1, T2, T3);
+impl_from_request_for_tuple!(T1, T2, T3, T4);
+impl_from_request_for_tuple!(T1, T2, T3, T4, T5);
+impl_from_request_for_tuple!(T1, T2, T3, T4, T5, T6);
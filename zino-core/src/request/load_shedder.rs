@@ -0,0 +1,440 @@
+use crate::{error::Error, extension::TomlTableExt, response::Rejection, state::State, LazyLock};
+use futures::{
+    channel::oneshot,
+    future::{self, Either},
+};
+use futures_timer::Delay;
+use parking_lot::Mutex;
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+use toml::value::Table;
+
+/// A global limiter of the in-flight requests with a bounded waiting queue.
+///
+/// When the latency target is configured, the concurrency limit is adapted
+/// to the moving average of the handler latencies, so that the service sheds
+/// the excess load early instead of increasing the latency for every request.
+/// When the CPU target is configured, the limit is also reduced in proportion
+/// to the system-wide CPU usage, which is sampled from `/proc/stat` on Linux.
+/// The rejected requests are responded with `503 Service Unavailable`
+/// and the `retry-after` header.
+///
+/// ```toml
+/// [server.load-shedding]
+/// max-concurrency = 1024
+/// min-concurrency = 16
+/// queue-size = 256
+/// queue-timeout = "5s"
+/// latency-target = "500ms"
+/// cpu-target = 0.8
+/// retry-after = "1s"
+/// ```
+#[derive(Debug)]
+pub struct LoadShedder {
+    /// Max number of in-flight requests.
+    max_concurrency: usize,
+    /// Min number of in-flight requests when the limit is adapted.
+    min_concurrency: usize,
+    /// Max number of the queued requests.
+    queue_size: usize,
+    /// Max waiting time of a queued request.
+    queue_timeout: Duration,
+    /// Target latency for the adaptive concurrency limit.
+    latency_target: Option<Duration>,
+    /// Target CPU usage in the range `(0, 1]` for the adaptive concurrency limit.
+    cpu_target: Option<f64>,
+    /// Suggested delay for the clients to retry.
+    retry_after: Duration,
+    /// Mutable states.
+    state: Mutex<ShedderState>,
+}
+
+/// Mutable states of the load shedder.
+#[derive(Debug)]
+struct ShedderState {
+    /// Number of in-flight requests.
+    in_flight: usize,
+    /// Current concurrency limit.
+    limit: usize,
+    /// Exponential moving average of the latencies in seconds.
+    latency: Option<f64>,
+    /// Last sample of the CPU usage.
+    cpu_sample: Option<CpuSample>,
+    /// Waiters of the queued requests.
+    waiters: VecDeque<oneshot::Sender<()>>,
+}
+
+impl LoadShedder {
+    /// Creates a new instance with the configuration.
+    pub fn with_config(config: &Table) -> Self {
+        let max_concurrency = config.get_usize("max-concurrency").unwrap_or(1024).max(1);
+        let min_concurrency = config
+            .get_usize("min-concurrency")
+            .unwrap_or(1)
+            .clamp(1, max_concurrency);
+        Self {
+            max_concurrency,
+            min_concurrency,
+            queue_size: config.get_usize("queue-size").unwrap_or(0),
+            queue_timeout: config
+                .get_duration("queue-timeout")
+                .unwrap_or_else(|| Duration::from_secs(5)),
+            latency_target: config
+                .get_duration("latency-target")
+                .filter(|target| !target.is_zero()),
+            cpu_target: config
+                .get_f64("cpu-target")
+                .filter(|&target| target > 0.0)
+                .map(|target| target.min(1.0)),
+            retry_after: config
+                .get_duration("retry-after")
+                .unwrap_or_else(|| Duration::from_secs(1)),
+            state: Mutex::new(ShedderState {
+                in_flight: 0,
+                limit: max_concurrency,
+                latency: None,
+                cpu_sample: None,
+                waiters: VecDeque::new(),
+            }),
+        }
+    }
+
+    /// Returns the current concurrency limit.
+    #[inline]
+    pub fn concurrency_limit(&self) -> usize {
+        self.state.lock().limit
+    }
+
+    /// Returns the number of in-flight requests.
+    #[inline]
+    pub fn in_flight(&self) -> usize {
+        self.state.lock().in_flight
+    }
+
+    /// Acquires a permit for the request. If the concurrency limit is reached,
+    /// the request waits in the queue until a permit is released or the queue timeout elapses.
+    pub async fn acquire(&'static self) -> Result<LoadPermit, Rejection> {
+        let receiver = {
+            let mut state = self.state.lock();
+            if state.in_flight < state.limit && state.waiters.is_empty() {
+                state.in_flight += 1;
+                return Ok(LoadPermit::new(self));
+            }
+            if state.waiters.len() >= self.queue_size {
+                drop(state);
+                return Err(self.shed("overloaded", "the request queue is full"));
+            }
+
+            let (sender, receiver) = oneshot::channel();
+            state.waiters.push_back(sender);
+
+            #[cfg(feature = "metrics")]
+            metrics::gauge!("zino_http_requests_queued").set(state.waiters.len() as f64);
+
+            receiver
+        };
+
+        let mut ticket = QueueTicket {
+            receiver: Some(receiver),
+            shedder: self,
+        };
+        if let Some(receiver) = ticket.receiver.as_mut() {
+            let delay = Delay::new(self.queue_timeout);
+            if let Either::Left((Ok(()), _)) = future::select(receiver, delay).await {
+                ticket.receiver = None;
+                return Ok(LoadPermit::new(self));
+            }
+        }
+        drop(ticket);
+
+        let millis = self.queue_timeout.as_millis();
+        let message = format!("the request has been queued for more than {millis}ms");
+        Err(self.shed("queue_timeout", message))
+    }
+
+    /// Releases a permit and hands it over to the first waiter if possible.
+    fn release(&self, latency: Option<Duration>) {
+        let mut state = self.state.lock();
+        if let Some(latency) = latency {
+            let cpu_usage = if self.cpu_target.is_some() {
+                sample_cpu_usage(&mut state.cpu_sample)
+            } else {
+                None
+            };
+            self.adapt_limit(&mut state, latency, cpu_usage);
+        }
+        if state.in_flight <= state.limit {
+            while let Some(sender) = state.waiters.pop_front() {
+                if sender.send(()).is_ok() {
+                    #[cfg(feature = "metrics")]
+                    metrics::gauge!("zino_http_requests_queued").set(state.waiters.len() as f64);
+
+                    return;
+                }
+            }
+        }
+        state.in_flight = state.in_flight.saturating_sub(1);
+
+        #[cfg(feature = "metrics")]
+        metrics::gauge!("zino_http_requests_queued").set(state.waiters.len() as f64);
+    }
+
+    /// Adapts the concurrency limit to the moving average of the latencies
+    /// and the CPU usage. The most constrained signal determines the limit.
+    fn adapt_limit(&self, state: &mut ShedderState, latency: Duration, cpu_usage: Option<f64>) {
+        if self.latency_target.is_none() && self.cpu_target.is_none() {
+            return;
+        }
+
+        let mut ratio = 1.0_f64;
+        if let Some(target) = self.latency_target {
+            let secs = latency.as_secs_f64();
+            let average = state
+                .latency
+                .map_or(secs, |average| 0.8 * average + 0.2 * secs);
+            state.latency = Some(average);
+            ratio = ratio.min(target.as_secs_f64() / average.max(f64::EPSILON));
+        }
+        if let Some((target, usage)) = self.cpu_target.zip(cpu_usage) {
+            ratio = ratio.min(target / usage.max(f64::EPSILON));
+        }
+
+        let limit = ((self.max_concurrency as f64) * ratio).round() as usize;
+        state.limit = limit.clamp(self.min_concurrency, self.max_concurrency);
+
+        #[cfg(feature = "metrics")]
+        metrics::gauge!("zino_http_concurrency_limit").set(state.limit as f64);
+    }
+
+    /// Creates a `503 Service Unavailable` rejection for the shed request.
+    fn shed(&self, reason: &'static str, message: impl Into<String>) -> Rejection {
+        #[cfg(feature = "metrics")]
+        metrics::counter!("zino_http_requests_shed_total", "reason" => reason).increment(1);
+
+        tracing::warn!(reason, "request is shed due to the overload");
+
+        let secs = self.retry_after.as_secs_f64().ceil().max(1.0) as u64;
+        let mut rejection = Rejection::service_unavailable(Error::new(message.into()));
+        rejection.insert_header("retry-after", secs);
+        rejection
+    }
+
+    /// Returns the shared load shedder if the `[server.load-shedding]` table is configured.
+    #[inline]
+    pub fn shared() -> Option<&'static Self> {
+        SHARED_LOAD_SHEDDER.as_ref()
+    }
+}
+
+/// A permit of the in-flight request, which is released when dropped.
+#[derive(Debug)]
+pub struct LoadPermit {
+    /// Load shedder.
+    shedder: &'static LoadShedder,
+    /// Start time.
+    start_time: Instant,
+}
+
+impl LoadPermit {
+    /// Creates a new instance.
+    #[inline]
+    fn new(shedder: &'static LoadShedder) -> Self {
+        Self {
+            shedder,
+            start_time: Instant::now(),
+        }
+    }
+}
+
+impl Drop for LoadPermit {
+    #[inline]
+    fn drop(&mut self) {
+        self.shedder.release(Some(self.start_time.elapsed()));
+    }
+}
+
+/// A ticket of the queued request. If a permit has been handed over
+/// while the request is cancelled or timed out, it will be released when dropped.
+struct QueueTicket {
+    /// Receiver of the permit.
+    receiver: Option<oneshot::Receiver<()>>,
+    /// Load shedder.
+    shedder: &'static LoadShedder,
+}
+
+impl Drop for QueueTicket {
+    fn drop(&mut self) {
+        if let Some(mut receiver) = self.receiver.take() {
+            receiver.close();
+            if let Ok(Some(())) = receiver.try_recv() {
+                self.shedder.release(None);
+            }
+        }
+    }
+}
+
+/// A sample of the CPU usage.
+#[derive(Debug, Clone, Copy)]
+struct CpuSample {
+    /// Sampling time.
+    sampled_at: Instant,
+    /// Busy CPU time in clock ticks.
+    busy_time: u64,
+    /// Total CPU time in clock ticks.
+    total_time: u64,
+    /// CPU usage in the range `[0, 1]` since the previous sample.
+    usage: Option<f64>,
+}
+
+/// Samples the system-wide CPU usage at most once per [`CPU_SAMPLE_INTERVAL`].
+fn sample_cpu_usage(last_sample: &mut Option<CpuSample>) -> Option<f64> {
+    if let Some(sample) = last_sample {
+        if sample.sampled_at.elapsed() < CPU_SAMPLE_INTERVAL {
+            return sample.usage;
+        }
+    }
+
+    let (busy_time, total_time) = read_cpu_times()?;
+    let usage = last_sample.and_then(|sample| {
+        let total = total_time.checked_sub(sample.total_time)?;
+        let busy = busy_time.checked_sub(sample.busy_time)?;
+        (total > 0).then(|| (busy as f64 / total as f64).min(1.0))
+    });
+    *last_sample = Some(CpuSample {
+        sampled_at: Instant::now(),
+        busy_time,
+        total_time,
+        usage,
+    });
+
+    #[cfg(feature = "metrics")]
+    if let Some(usage) = usage {
+        metrics::gauge!("zino_cpu_usage").set(usage);
+    }
+
+    usage
+}
+
+/// Reads the busy and total CPU time from `/proc/stat`.
+#[cfg(target_os = "linux")]
+fn read_cpu_times() -> Option<(u64, u64)> {
+    let stat = std::fs::read_to_string("/proc/stat").ok()?;
+    parse_cpu_times(stat.lines().next()?)
+}
+
+/// Reads the busy and total CPU time, which is unsupported on this platform.
+#[cfg(not(target_os = "linux"))]
+fn read_cpu_times() -> Option<(u64, u64)> {
+    None
+}
+
+/// Parses the busy and total CPU time from the aggregated `cpu` line of `/proc/stat`.
+/// The `idle` and `iowait` columns are counted as the idle time.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_cpu_times(line: &str) -> Option<(u64, u64)> {
+    let mut columns = line.split_whitespace();
+    if columns.next() != Some("cpu") {
+        return None;
+    }
+
+    // The `guest` and `guest_nice` columns have been included in `user` and `nice`.
+    let times = columns
+        .take(8)
+        .map(|column| column.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    let total_time = times.iter().sum::<u64>();
+    let idle_time = times.get(3)? + times.get(4).copied().unwrap_or_default();
+    Some((total_time.saturating_sub(idle_time), total_time))
+}
+
+/// Min interval between two samples of the CPU usage.
+const CPU_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Shared load shedder.
+static SHARED_LOAD_SHEDDER: LazyLock<Option<LoadShedder>> = LazyLock::new(|| {
+    State::shared()
+        .get_config("server")
+        .and_then(|config| config.get_table("load-shedding"))
+        .map(LoadShedder::with_config)
+});
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_cpu_times, LoadShedder};
+    use futures::FutureExt;
+    use std::time::Duration;
+    use toml::value::Table;
+
+    fn new_shedder(config: &str) -> &'static LoadShedder {
+        let config = config.parse::<Table>().unwrap();
+        Box::leak(Box::new(LoadShedder::with_config(&config)))
+    }
+
+    #[test]
+    fn it_sheds_excess_requests() {
+        let shedder = new_shedder("max-concurrency = 1");
+        let permit = shedder.acquire().now_or_never().unwrap().unwrap();
+        assert_eq!(shedder.in_flight(), 1);
+
+        let rejection = shedder.acquire().now_or_never().unwrap().unwrap_err();
+        assert_eq!(rejection.status_code(), 503);
+
+        drop(permit);
+        assert_eq!(shedder.in_flight(), 0);
+        assert!(shedder.acquire().now_or_never().unwrap().is_ok());
+    }
+
+    #[test]
+    fn it_hands_over_permits_to_waiters() {
+        let shedder = new_shedder("max-concurrency = 1\nqueue-size = 1");
+        let permit = shedder.acquire().now_or_never().unwrap().unwrap();
+        let mut waiter = Box::pin(shedder.acquire());
+        assert!((&mut waiter).now_or_never().is_none());
+
+        let rejection = shedder.acquire().now_or_never().unwrap().unwrap_err();
+        assert_eq!(rejection.status_code(), 503);
+
+        drop(permit);
+        assert_eq!(shedder.in_flight(), 1);
+        assert!(waiter.now_or_never().unwrap().is_ok());
+        assert_eq!(shedder.in_flight(), 0);
+    }
+
+    #[test]
+    fn it_adapts_concurrency_limits() {
+        let config = "max-concurrency = 100\nmin-concurrency = 10\nlatency-target = \"100ms\"";
+        let shedder = new_shedder(config);
+        let mut state = shedder.state.lock();
+        shedder.adapt_limit(&mut state, Duration::from_millis(50), None);
+        assert_eq!(state.limit, 100);
+        shedder.adapt_limit(&mut state, Duration::from_millis(50), None);
+        assert_eq!(state.limit, 100);
+
+        state.latency = None;
+        shedder.adapt_limit(&mut state, Duration::from_millis(400), None);
+        assert_eq!(state.limit, 25);
+
+        state.latency = None;
+        shedder.adapt_limit(&mut state, Duration::from_secs(10), None);
+        assert_eq!(state.limit, 10);
+
+        let shedder = new_shedder("max-concurrency = 100\ncpu-target = 0.8");
+        let mut state = shedder.state.lock();
+        shedder.adapt_limit(&mut state, Duration::from_secs(10), Some(0.5));
+        assert_eq!(state.limit, 100);
+        shedder.adapt_limit(&mut state, Duration::from_secs(10), Some(1.0));
+        assert_eq!(state.limit, 80);
+        shedder.adapt_limit(&mut state, Duration::from_secs(10), None);
+        assert_eq!(state.limit, 100);
+    }
+
+    #[test]
+    fn it_parses_cpu_times() {
+        let line = "cpu  4705 150 1120 16250 520 30 45 0 0 0";
+        assert_eq!(parse_cpu_times(line), Some((6050, 22820)));
+        assert_eq!(parse_cpu_times("cpu0 1 2 3 4 5 6 7 8"), None);
+        assert_eq!(parse_cpu_times("intr 1 2 3"), None);
+    }
+}
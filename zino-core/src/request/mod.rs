@@ -2,7 +2,10 @@
 
 use crate::{
     application::http_client,
-    auth::{AccessKeyId, Authentication, ParseSecurityTokenError, SecurityToken, SessionId},
+    auth::{
+        AccessKeyId, Authentication, ParseSecurityTokenError, SecretAccessKey, SecurityToken,
+        SessionId,
+    },
     channel::{CloudEvent, Subscription},
     datetime::DateTime,
     error::Error,
@@ -15,9 +18,17 @@ use crate::{
     validation::Validation,
     warn, JsonValue, Map, SharedString, Uuid,
 };
+use bytes::Bytes;
+use futures::Stream;
+use hmac::digest::{FixedOutput, KeyInit, MacMarker, Update};
 use multer::Multipart;
 use serde::de::DeserializeOwned;
-use std::{borrow::Cow, net::IpAddr, str::FromStr, time::Instant};
+use std::{
+    borrow::Cow,
+    net::IpAddr,
+    str::FromStr,
+    time::{Duration, Instant},
+};
 
 #[cfg(feature = "cookie")]
 use cookie::{Cookie, SameSite};
@@ -27,9 +38,6 @@ use crate::auth::JwtClaims;
 #[cfg(feature = "jwt")]
 use jwt_simple::algorithms::MACLike;
 
-#[cfg(any(feature = "cookie", feature = "jwt"))]
-use std::time::Duration;
-
 #[cfg(feature = "i18n")]
 use crate::i18n;
 #[cfg(feature = "i18n")]
@@ -38,8 +46,26 @@ use fluent::FluentArgs;
 use unic_langid::LanguageIdentifier;
 
 mod context;
+mod extractor;
+mod load_shedder;
+mod query_params;
+mod recorder;
+mod timeout;
 
 pub use context::Context;
+pub use extractor::{Body, FromRequest, Inject, Params, Tenant, Validated};
+pub use load_shedder::{LoadPermit, LoadShedder};
+pub use query_params::QueryParams;
+pub use recorder::{RecordingFuture, RequestRecord, RequestRecorder};
+pub use timeout::with_deadline;
+
+pub(crate) use recorder::{record_query, REDACTED};
+
+#[cfg(feature = "orm")]
+pub(crate) use timeout::remaining_time;
+
+#[cfg(feature = "i18n")]
+pub use extractor::Locale;
 
 /// The URI component of a request for http v0.2.
 #[cfg(feature = "http02")]
@@ -79,6 +105,15 @@ pub trait RequestContext {
     /// Reads the entire request body into a byte buffer.
     async fn read_body_bytes(&mut self) -> Result<Vec<u8>, Error>;
 
+    /// Takes the request body as a stream of byte chunks without buffering the entire content.
+    /// The chunks are read from the connection only when the stream is polled,
+    /// which is suitable for large uploads and proxying.
+    ///
+    /// # Note
+    ///
+    /// The body can only be consumed once. Subsequent calls return an empty stream.
+    fn body_stream(&mut self) -> impl Stream<Item = Result<Bytes, Error>> + Unpin + 'static;
+
     /// Returns the request path regardless of nesting.
     #[inline]
     fn request_path(&self) -> &str {
@@ -123,6 +158,13 @@ pub trait RequestContext {
         ctx.set_instance(self.request_path());
         ctx.set_trace_id(trace_id);
         ctx.set_session_id(session_id);
+        ctx.set_timeout(self.request_timeout());
+
+        // Negotiate the content type of the response body.
+        #[cfg(any(feature = "cbor", feature = "msgpack"))]
+        if let Some(accept) = self.get_header("accept") {
+            ctx.set_content_type(helper::negotiate_content_type(accept));
+        }
 
         // Set locale.
         #[cfg(feature = "i18n")]
@@ -210,6 +252,14 @@ pub trait RequestContext {
             .unwrap_or_else(Instant::now)
     }
 
+    /// Returns the handler timeout for the matched route.
+    /// It can be configured by the `route-timeouts` table in `[server]`,
+    /// and defaults to the `request-timeout` which is 60 seconds.
+    #[inline]
+    fn request_timeout(&self) -> Option<Duration> {
+        timeout::route_timeout(&self.matched_route())
+    }
+
     /// Returns the instance.
     #[inline]
     fn instance(&self) -> String {
@@ -252,8 +302,8 @@ pub trait RequestContext {
     ///
     /// # Note
     ///
-    /// Currently, we support the following values: `bytes` | `csv` | `form` | `json` | `multipart`
-    /// | `ndjson` | `text`.
+    /// Currently, we support the following values: `bytes` | `cbor` | `csv` | `form` | `json`
+    /// | `msgpack` | `multipart` | `ndjson` | `text` | `xlsx`.
     fn data_type(&self) -> Option<&str> {
         self.get_header("content-type")
             .map(|content_type| {
@@ -362,7 +412,8 @@ pub trait RequestContext {
         if let Some(query) = self.original_uri().query() {
             #[cfg(feature = "jwt")]
             if let Some(timestamp) = self.get_query("timestamp").and_then(|s| s.parse().ok()) {
-                let duration = DateTime::from_timestamp(timestamp).span_between_now();
+                let current = DateTime::trusted_now();
+                let duration = DateTime::from_timestamp(timestamp).span_between(current);
                 if duration > crate::auth::default_time_tolerance() {
                     let err = warn!("timestamp `{}` can not be trusted", timestamp);
                     let rejection = Rejection::from_validation_entry("timestamp", err);
@@ -376,6 +427,24 @@ pub trait RequestContext {
         }
     }
 
+    /// Parses the query as typed parameters which implement [`QueryParams`].
+    /// All the failed parameters are recorded in the validation of the rejection.
+    fn parse_query_params<T: QueryParams>(&self) -> Result<T, Rejection> {
+        let query = self.parse_query::<Map>()?;
+        T::from_query_map(&query)
+            .map_err(|validation| Rejection::bad_request(validation).context(self))
+    }
+
+    /// Extracts a value of type `T` which implements [`FromRequest`].
+    /// Multiple values can be extracted at once with a tuple.
+    #[inline]
+    async fn extract<T: FromRequest>(&mut self) -> Result<T, Rejection>
+    where
+        Self: Sized,
+    {
+        T::from_request(self).await
+    }
+
     /// Parses the request body as an instance of type `T`.
     ///
     /// # Note
@@ -405,7 +474,7 @@ pub trait RequestContext {
             serde_qs::from_bytes(&bytes)
                 .map_err(|err| Rejection::from_validation_entry("body", err).context(self))
         } else {
-            serde_json::from_slice(&bytes)
+            helper::deserialize_body(data_type, &bytes)
                 .map_err(|err| Rejection::from_validation_entry("body", err).context(self))
         }
     }
@@ -459,6 +528,7 @@ pub trait RequestContext {
     /// The value is extracted from the query or the `authorization` header.
     /// By default, the `Accept` header value is ignored and
     /// the canonicalized resource is set to the request path.
+    /// The nonce is extracted from the `x-nonce` header or the query parameter `nonce`.
     /// You should always manually set canonicalized headers by calling
     /// `Authentication`'s method [`set_headers()`](Authentication::set_headers).
     fn parse_authentication(&self) -> Result<Authentication, Rejection> {
@@ -474,7 +544,7 @@ pub trait RequestContext {
                 validation.record("access_key_id", "should be nonempty");
             }
             if let Some(Ok(secs)) = query.parse_i64("expires") {
-                if DateTime::now().timestamp() <= secs {
+                if DateTime::trusted_now().timestamp() <= secs {
                     let expires = DateTime::from_timestamp(secs);
                     authentication.set_expires(Some(expires));
                 } else {
@@ -508,14 +578,12 @@ pub trait RequestContext {
         if let Some(date) = self.get_header("date") {
             match DateTime::parse_utc_str(date) {
                 Ok(date) => {
-                    #[cfg(feature = "jwt")]
-                    if date.span_between_now() <= crate::auth::default_time_tolerance() {
+                    let current = DateTime::trusted_now();
+                    if date.span_between(current) <= crate::auth::max_clock_skew() {
                         authentication.set_date_header("date", date);
                     } else {
                         validation.record("date", "untrusted date");
                     }
-                    #[cfg(not(feature = "jwt"))]
-                    authentication.set_date_header("date", date);
                 }
                 Err(err) => {
                     validation.record_fail("date", err);
@@ -523,11 +591,42 @@ pub trait RequestContext {
                 }
             }
         }
+        if let Some(nonce) = self
+            .get_header("x-nonce")
+            .or_else(|| query.get_str("nonce"))
+        {
+            authentication.set_nonce(Some(nonce.to_owned()));
+        }
         authentication.set_content_type(self.get_header("content-type").map(|s| s.to_owned()));
         authentication.set_resource(self.request_path().to_owned(), None);
         Ok(authentication)
     }
 
+    /// Parses the authentication and validates the signature using the secret access key.
+    /// The nonce is consumed only once for the request, so the signature can be validated
+    /// again by the subsequent middlewares or the handler.
+    fn validate_authentication<H>(
+        &mut self,
+        secret_access_key: &SecretAccessKey,
+    ) -> Result<Authentication, Rejection>
+    where
+        H: FixedOutput + KeyInit + MacMarker + Update,
+    {
+        let authentication = self.parse_authentication()?;
+        let signature = authentication.signature();
+        let consumed = self
+            .get_data::<VerifiedSignature>()
+            .is_some_and(|verified| verified.0 == signature);
+        let validation = authentication.validate_signature::<H>(secret_access_key, !consumed);
+        if !validation.is_success() {
+            return Err(Rejection::bad_request(validation).context(self));
+        }
+        if !consumed {
+            self.set_data(VerifiedSignature(signature.to_owned()));
+        }
+        Ok(authentication)
+    }
+
     /// Attempts to construct an instance of `AccessKeyId` from an HTTP request.
     /// The value is extracted from the query parameter `access_key_id`
     /// or the `authorization` header.
@@ -643,6 +742,9 @@ pub trait RequestContext {
             .and_then(|s| s.parse().ok())
             .map(|i| Duration::from_secs(i).into());
         options.required_nonce = self.get_query("nonce").map(|s| s.to_owned());
+        options.artificial_time = u64::try_from(DateTime::trusted_now().timestamp())
+            .ok()
+            .map(|secs| Duration::from_secs(secs).into());
 
         match key.verify_token(token, Some(options)) {
             Ok(claims) => Ok(JwtClaims(claims)),
@@ -664,6 +766,18 @@ pub trait RequestContext {
             Ok(data) => {
                 let validation = query.read_map(&data);
                 if validation.is_success() {
+                    // Propagates the handler timeout so that the query will be cancelled
+                    // instead of running after the request has timed out.
+                    if let Some(remaining_time) =
+                        self.get_context().and_then(|ctx| ctx.remaining_time())
+                    {
+                        if query
+                            .timeout()
+                            .map_or(true, |timeout| timeout > remaining_time)
+                        {
+                            query.set_timeout(remaining_time);
+                        }
+                    }
                     Ok(Response::with_context(S::OK, self))
                 } else {
                     Err(Rejection::bad_request(validation).context(self))
@@ -674,7 +788,8 @@ pub trait RequestContext {
     }
 
     /// Returns a `Response` or `Rejection` from a model validation.
-    /// The data is extracted from [`parse_body()`](RequestContext::parse_body).
+    /// The data is extracted from [`parse_body()`](RequestContext::parse_body)
+    /// and sanitized by [`Model::sanitize()`](crate::model::Model::sanitize) before validation.
     async fn model_validation<M, S>(&mut self, model: &mut M) -> Result<Response<S>, Rejection>
     where
         Self: Sized,
@@ -703,6 +818,7 @@ pub trait RequestContext {
         if is_form {
             let mut data = serde_qs::from_bytes(&bytes)
                 .map_err(|err| Rejection::from_validation_entry("body", err).context(self))?;
+            M::sanitize(&mut data);
             match M::before_validation(&mut data, extension.as_ref()).await {
                 Ok(()) => {
                     let validation = model.read_map(&data);
@@ -725,8 +841,9 @@ pub trait RequestContext {
                 Err(err) => Err(Rejection::from_error(err).context(self)),
             }
         } else {
-            let mut data = serde_json::from_slice(&bytes)
+            let mut data = helper::deserialize_body(data_type, &bytes)
                 .map_err(|err| Rejection::from_validation_entry("body", err).context(self))?;
+            M::sanitize(&mut data);
             match M::before_validation(&mut data, extension.as_ref()).await {
                 Ok(()) => {
                     let validation = model.read_map(&data);
@@ -813,3 +930,7 @@ pub trait RequestContext {
         event
     }
 }
+
+/// Signature of the request which has been verified with the nonce consumed.
+#[derive(Clone)]
+struct VerifiedSignature(String);
@@ -0,0 +1,30 @@
+use crate::{validation::Validation, Map};
+
+/// Typed query parameters parsed from the query string of a request.
+///
+/// This trait can be derived by `zino_derive::QueryParams`, and the instance
+/// can be extracted by [`parse_query_params()`](super::RequestContext::parse_query_params).
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino_core::{datetime::Date, request::RequestContext};
+/// use zino_derive::QueryParams;
+///
+/// #[derive(QueryParams)]
+/// struct TaskQuery {
+///     #[schema(rename = "q", max_length = 100)]
+///     keyword: Option<String>,
+///     tags: Vec<String>,
+///     #[schema(default_value = "10")]
+///     limit: usize,
+///     start_date: Date,
+/// }
+///
+/// let query = req.parse_query_params::<TaskQuery>()?;
+/// ```
+pub trait QueryParams: Sized {
+    /// Parses the query parameters from a JSON object,
+    /// and returns the validation result if it fails.
+    fn from_query_map(query: &Map) -> Result<Self, Validation>;
+}
@@ -0,0 +1,463 @@
+use crate::{
+    datetime::DateTime,
+    extension::{JsonObjectExt, TomlTableExt},
+    model::QueryContext,
+    state::State,
+    JsonValue, LazyLock, Map, Uuid,
+};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Instant,
+};
+use toml::value::Table;
+
+/// Placeholder of the redacted values.
+pub(crate) const REDACTED: &str = "[redacted]";
+
+/// A recorder of the request/response pairs in a ring buffer for debugging.
+///
+/// The values of the sensitive headers, query parameters and body fields are redacted
+/// when a pair is recorded, and the SQL queries executed while handling the request
+/// are captured with the arguments redacted.
+/// It is enabled by default in the `dev` mode.
+///
+/// ```toml
+/// [server.request-recorder]
+/// enabled = true
+/// capacity = 100
+/// max-body-size = 65536
+/// redacted-headers = ["authorization", "cookie", "set-cookie", "x-api-key"]
+/// redacted-params = ["access_key_id", "security_token", "signature"]
+/// redacted-fields = ["password", "secret", "token"]
+/// ```
+#[derive(Debug)]
+pub struct RequestRecorder {
+    /// Max number of the records.
+    capacity: usize,
+    /// Max size of the request or response body to be recorded.
+    max_body_size: usize,
+    /// Lowercase names of the redacted headers.
+    redacted_headers: Vec<String>,
+    /// Lowercase names of the redacted query parameters.
+    redacted_params: Vec<String>,
+    /// Lowercase names of the redacted body fields.
+    redacted_fields: Vec<String>,
+    /// Records in the ring buffer.
+    records: Mutex<VecDeque<RequestRecord>>,
+}
+
+impl RequestRecorder {
+    /// Creates a new instance with the configuration.
+    pub fn with_config(config: &Table) -> Self {
+        let redacted_headers = config
+            .get_str_array("redacted-headers")
+            .unwrap_or_else(|| vec!["authorization", "cookie", "set-cookie", "x-api-key"]);
+        let redacted_params = config
+            .get_str_array("redacted-params")
+            .unwrap_or_else(|| vec!["access_key_id", "security_token", "signature"]);
+        let redacted_fields = config
+            .get_str_array("redacted-fields")
+            .unwrap_or_else(|| vec!["password", "secret", "token"]);
+        let capacity = config.get_usize("capacity").unwrap_or(100).max(1);
+        Self {
+            capacity,
+            max_body_size: config.get_usize("max-body-size").unwrap_or(64 * 1024),
+            redacted_headers: redacted_headers
+                .into_iter()
+                .map(|s| s.to_ascii_lowercase())
+                .collect(),
+            redacted_params: redacted_params
+                .into_iter()
+                .map(|s| s.to_ascii_lowercase())
+                .collect(),
+            redacted_fields: redacted_fields
+                .into_iter()
+                .map(|s| s.to_ascii_lowercase())
+                .collect(),
+            records: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// Returns the max size of the request or response body to be recorded.
+    #[inline]
+    pub fn max_body_size(&self) -> usize {
+        self.max_body_size
+    }
+
+    /// Returns the records with the newest first.
+    #[inline]
+    pub fn records(&self) -> Vec<RequestRecord> {
+        self.records.lock().iter().rev().cloned().collect()
+    }
+
+    /// Gets a record by the ID.
+    #[inline]
+    pub fn get(&self, id: &Uuid) -> Option<RequestRecord> {
+        self.records
+            .lock()
+            .iter()
+            .find(|record| &record.id == id)
+            .cloned()
+    }
+
+    /// Removes all the records.
+    #[inline]
+    pub fn clear(&self) {
+        self.records.lock().clear();
+    }
+
+    /// Finishes the record, redacts the sensitive values, and pushes it into the ring buffer.
+    /// The oldest record will be evicted if the capacity is reached.
+    pub fn record(&self, mut record: RequestRecord) {
+        record.duration_millis =
+            u64::try_from(record.start.elapsed().as_millis()).unwrap_or_default();
+        record.queries = std::mem::take(&mut *record.query_sink.lock());
+        self.redact_uri(&mut record.uri);
+        self.redact_headers(&mut record.request_headers);
+        self.redact_headers(&mut record.response_headers);
+        if let Some(body) = record.request_body.as_mut() {
+            self.redact_body(body);
+        }
+        if let Some(body) = record.response_body.as_mut() {
+            self.redact_body(body);
+        }
+
+        let mut records = self.records.lock();
+        while records.len() >= self.capacity {
+            records.pop_front();
+        }
+        records.push_back(record);
+    }
+
+    /// Redacts the values of the sensitive headers.
+    fn redact_headers(&self, headers: &mut Map) {
+        for (key, value) in headers.iter_mut() {
+            if self.redacted_headers.iter().any(|name| name == key) {
+                *value = REDACTED.into();
+            }
+        }
+    }
+
+    /// Redacts the values of the sensitive query parameters in the URI.
+    fn redact_uri(&self, uri: &mut String) {
+        let Some((path, query)) = uri.split_once('?') else {
+            return;
+        };
+        let pairs = query
+            .split('&')
+            .map(|pair| match pair.split_once('=') {
+                Some((key, _)) if self.is_redacted_param(key) => format!("{key}={REDACTED}"),
+                _ => pair.to_owned(),
+            })
+            .collect::<Vec<_>>();
+        *uri = format!("{path}?{}", pairs.join("&"));
+    }
+
+    /// Returns `true` if the value of the query parameter should be redacted.
+    fn is_redacted_param(&self, key: &str) -> bool {
+        let key = key.to_ascii_lowercase();
+        self.redacted_params.iter().any(|param| param == &key)
+            || self.redacted_fields.iter().any(|field| key.contains(field))
+    }
+
+    /// Redacts the values of the sensitive fields in a JSON or URL-encoded form body.
+    fn redact_body(&self, body: &mut JsonValue) {
+        match body {
+            JsonValue::Object(map) => {
+                for (key, value) in map.iter_mut() {
+                    let key = key.to_ascii_lowercase();
+                    if self.redacted_fields.iter().any(|field| key.contains(field)) {
+                        *value = REDACTED.into();
+                    } else {
+                        self.redact_body(value);
+                    }
+                }
+            }
+            JsonValue::Array(vec) => {
+                for value in vec.iter_mut() {
+                    self.redact_body(value);
+                }
+            }
+            JsonValue::String(s) if s.contains('=') && !s.contains(char::is_whitespace) => {
+                let pairs = s
+                    .split('&')
+                    .map(|pair| match pair.split_once('=') {
+                        Some((key, _))
+                            if self
+                                .redacted_fields
+                                .iter()
+                                .any(|field| key.to_ascii_lowercase().contains(field)) =>
+                        {
+                            format!("{key}={REDACTED}")
+                        }
+                        _ => pair.to_owned(),
+                    })
+                    .collect::<Vec<_>>();
+                *s = pairs.join("&");
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the shared request recorder if it is enabled.
+    #[inline]
+    pub fn shared() -> Option<&'static Self> {
+        SHARED_REQUEST_RECORDER.as_ref()
+    }
+}
+
+/// A recorded request/response pair.
+#[derive(Debug, Clone, Serialize)]
+pub struct RequestRecord {
+    /// Record ID.
+    id: Uuid,
+    /// Request method.
+    method: String,
+    /// Request URI.
+    uri: String,
+    /// Request headers.
+    request_headers: Map,
+    /// Request body.
+    request_body: Option<JsonValue>,
+    /// Status code of the response.
+    status_code: u16,
+    /// Response headers.
+    response_headers: Map,
+    /// Response body.
+    response_body: Option<JsonValue>,
+    /// Start time.
+    start_time: DateTime,
+    /// Duration in milliseconds.
+    duration_millis: u64,
+    /// SQL queries executed while handling the request.
+    queries: Vec<Map>,
+    /// Start instant.
+    #[serde(skip)]
+    start: Instant,
+    /// A sink of the SQL queries.
+    #[serde(skip)]
+    query_sink: Arc<Mutex<Vec<Map>>>,
+}
+
+impl RequestRecord {
+    /// Creates a new instance.
+    pub fn new(method: impl Into<String>, uri: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::now_v7(),
+            method: method.into(),
+            uri: uri.into(),
+            request_headers: Map::new(),
+            request_body: None,
+            status_code: 0,
+            response_headers: Map::new(),
+            response_body: None,
+            start_time: DateTime::now(),
+            duration_millis: 0,
+            queries: Vec::new(),
+            start: Instant::now(),
+            query_sink: Arc::default(),
+        }
+    }
+
+    /// Sets a request header. The header name will be converted to lowercase.
+    #[inline]
+    pub fn set_request_header(&mut self, name: &str, value: &str) {
+        self.request_headers
+            .upsert(name.to_ascii_lowercase(), value.to_owned());
+    }
+
+    /// Sets the request body.
+    #[inline]
+    pub fn set_request_body(&mut self, body: &[u8]) {
+        self.request_body = Some(parse_body(body));
+    }
+
+    /// Sets the status code of the response.
+    #[inline]
+    pub fn set_status_code(&mut self, status_code: u16) {
+        self.status_code = status_code;
+    }
+
+    /// Sets a response header. The header name will be converted to lowercase.
+    #[inline]
+    pub fn set_response_header(&mut self, name: &str, value: &str) {
+        self.response_headers
+            .upsert(name.to_ascii_lowercase(), value.to_owned());
+    }
+
+    /// Sets the response body.
+    #[inline]
+    pub fn set_response_body(&mut self, body: &[u8]) {
+        self.response_body = Some(parse_body(body));
+    }
+
+    /// Runs the future with the SQL queries being captured in the record.
+    #[inline]
+    pub fn scope<F: Future>(&self, future: F) -> RecordingFuture<F> {
+        RecordingFuture {
+            query_sink: self.query_sink.clone(),
+            future: Box::pin(future),
+        }
+    }
+
+    /// Returns the record ID.
+    #[inline]
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Returns the request method.
+    #[inline]
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+
+    /// Returns the request URI.
+    #[inline]
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    /// Returns the request headers.
+    #[inline]
+    pub fn request_headers(&self) -> &Map {
+        &self.request_headers
+    }
+
+    /// Returns the request body.
+    #[inline]
+    pub fn request_body(&self) -> Option<&JsonValue> {
+        self.request_body.as_ref()
+    }
+
+    /// Returns the status code of the response.
+    #[inline]
+    pub fn status_code(&self) -> u16 {
+        self.status_code
+    }
+
+    /// Returns the SQL queries executed while handling the request.
+    #[inline]
+    pub fn queries(&self) -> &[Map] {
+        &self.queries
+    }
+
+    /// Returns the options to re-issue the request with [`fetch`].
+    /// The redacted headers and the hop-by-hop headers are not included.
+    ///
+    /// [`fetch`]: crate::application::Application::fetch
+    pub fn to_fetch_options(&self) -> Map {
+        let mut headers = Map::new();
+        for (key, value) in self.request_headers.iter() {
+            let skipped = matches!(
+                key.as_str(),
+                "host" | "connection" | "content-length" | "transfer-encoding" | "accept-encoding"
+            );
+            if !skipped && value.as_str() != Some(REDACTED) {
+                headers.upsert(key, value.clone());
+            }
+        }
+
+        let mut options = Map::from_entry("method", self.method.as_str());
+        options.upsert("headers", headers);
+        match self.request_body.as_ref() {
+            Some(JsonValue::String(body)) => {
+                options.upsert("body", body.as_str());
+            }
+            Some(body) if !body.is_null() => {
+                options.upsert("body", body.to_string());
+            }
+            _ => (),
+        }
+        options
+    }
+}
+
+/// A future with the SQL queries being captured.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct RecordingFuture<F> {
+    /// A sink of the SQL queries.
+    query_sink: Arc<Mutex<Vec<Map>>>,
+    /// Inner future.
+    future: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for RecordingFuture<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let query_sink = Some(this.query_sink.clone());
+        let _guard = SinkGuard(CURRENT_QUERY_SINK.with(|current| current.replace(query_sink)));
+        this.future.as_mut().poll(cx)
+    }
+}
+
+/// A guard which restores the previous query sink.
+struct SinkGuard(Option<Arc<Mutex<Vec<Map>>>>);
+
+impl Drop for SinkGuard {
+    #[inline]
+    fn drop(&mut self) {
+        let query_sink = self.0.take();
+        CURRENT_QUERY_SINK.with(|current| *current.borrow_mut() = query_sink);
+    }
+}
+
+/// Captures the executed query if the current future is being recorded.
+pub(crate) fn record_query(ctx: &QueryContext) {
+    CURRENT_QUERY_SINK.with(|current| {
+        if let Some(query_sink) = current.borrow().as_ref() {
+            let mut query = Map::from_entry("model_name", ctx.model_name());
+            query.upsert("query", ctx.query());
+            let arguments = vec![REDACTED; ctx.arguments().len()];
+            query.upsert("arguments", arguments);
+            query.upsert("rows_affected", ctx.rows_affected());
+            query.upsert(
+                "execution_time_millis",
+                u64::try_from(ctx.execution_time().as_millis()).unwrap_or_default(),
+            );
+            query_sink.lock().push(query);
+        }
+    });
+}
+
+/// Parses the body as a JSON value, or a string if it is not valid JSON.
+fn parse_body(body: &[u8]) -> JsonValue {
+    if body.is_empty() {
+        return JsonValue::Null;
+    }
+    if let Ok(value) = serde_json::from_slice(body) {
+        return value;
+    }
+    match std::str::from_utf8(body) {
+        Ok(s) => s.into(),
+        Err(_) => format!("[binary data of {} bytes]", body.len()).into(),
+    }
+}
+
+thread_local! {
+    /// Query sink for the current recorded future.
+    static CURRENT_QUERY_SINK: RefCell<Option<Arc<Mutex<Vec<Map>>>>> = const { RefCell::new(None) };
+}
+
+/// Shared request recorder.
+static SHARED_REQUEST_RECORDER: LazyLock<Option<RequestRecorder>> = LazyLock::new(|| {
+    let app_state = State::shared();
+    let config = app_state
+        .get_config("server")
+        .and_then(|config| config.get_table("request-recorder"))
+        .cloned()
+        .unwrap_or_default();
+    let enabled = config
+        .get_bool("enabled")
+        .unwrap_or_else(|| app_state.env().is_dev());
+    enabled.then(|| RequestRecorder::with_config(&config))
+});
@@ -0,0 +1,100 @@
+use crate::{extension::TomlTableExt, state::State, LazyLock};
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    future::{self, Future},
+    pin::pin,
+    time::{Duration, Instant},
+};
+
+thread_local! {
+    /// Deadline of the handler which is being polled on the current thread.
+    static HANDLER_DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+/// Returns the handler timeout for the matched route.
+/// A zero duration means that the handler will never time out.
+pub(super) fn route_timeout(route: &str) -> Option<Duration> {
+    let (default_timeout, route_timeouts) = &*SHARED_ROUTE_TIMEOUTS;
+    let timeout = route_timeouts
+        .get(route)
+        .copied()
+        .unwrap_or(*default_timeout);
+    (!timeout.is_zero()).then_some(timeout)
+}
+
+/// Runs the handler future with a deadline after the timeout,
+/// which is respected by the database queries awaited in the handler.
+pub async fn with_deadline<F: Future>(timeout: Duration, fut: F) -> F::Output {
+    let deadline = Instant::now() + timeout;
+    let mut fut = pin!(fut);
+    future::poll_fn(|cx| {
+        let _guard = DeadlineGuard(HANDLER_DEADLINE.replace(Some(deadline)));
+        fut.as_mut().poll(cx)
+    })
+    .await
+}
+
+/// Returns the remaining time before the deadline of the current handler.
+#[cfg(feature = "orm")]
+pub(crate) fn remaining_time() -> Option<Duration> {
+    HANDLER_DEADLINE
+        .get()
+        .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+}
+
+/// Restores the previous deadline when the handler future yields.
+struct DeadlineGuard(Option<Instant>);
+
+impl Drop for DeadlineGuard {
+    #[inline]
+    fn drop(&mut self) {
+        HANDLER_DEADLINE.set(self.0);
+    }
+}
+
+/// Default handler timeout and the custom timeouts for the routes.
+///
+/// ```toml
+/// [server]
+/// request-timeout = "60s"
+///
+/// [server.route-timeouts]
+/// "/user/import" = "5m"
+/// "/user/stream" = "0s"
+/// ```
+static SHARED_ROUTE_TIMEOUTS: LazyLock<(Duration, HashMap<String, Duration>)> =
+    LazyLock::new(|| {
+        let mut default_timeout = Duration::from_secs(60); // 60 seconds
+        let mut route_timeouts = HashMap::new();
+        if let Some(config) = State::shared().get_config("server") {
+            if let Some(timeout) = config.get_duration("request-timeout") {
+                default_timeout = timeout;
+            }
+            if let Some(timeouts) = config.get_table("route-timeouts") {
+                for route in timeouts.keys() {
+                    if let Some(timeout) = timeouts.get_duration(route) {
+                        route_timeouts.insert(route.to_owned(), timeout);
+                    } else {
+                        tracing::warn!("invalid timeout for the route `{route}`");
+                    }
+                }
+            }
+        }
+        (default_timeout, route_timeouts)
+    });
+
+#[cfg(all(test, feature = "orm"))]
+mod tests {
+    use super::{remaining_time, with_deadline};
+    use std::time::Duration;
+
+    #[test]
+    fn it_propagates_deadlines() {
+        let timeout = Duration::from_secs(5);
+        let remaining =
+            futures::executor::block_on(with_deadline(timeout, async { remaining_time() }));
+        assert!(remaining.is_some_and(|time| time <= timeout && !time.is_zero()));
+        assert_eq!(remaining_time(), None);
+    }
+}
@@ -1,8 +1,9 @@
 //! Constructing responses and rejections.
 
 use crate::{
+    datetime::DateTime,
     error::Error,
-    extension::JsonValueExt,
+    extension::{JsonObjectExt, JsonValueExt},
     file::NamedFile,
     helper,
     request::RequestContext,
@@ -12,10 +13,22 @@ use crate::{
 };
 use bytes::Bytes;
 use etag::EntityTag;
+use futures::{
+    future,
+    stream::{self, BoxStream},
+    Stream, StreamExt,
+};
+use futures_timer::Delay;
+use parking_lot::Mutex;
 use serde::Serialize;
 use smallvec::SmallVec;
 use std::{
+    fmt,
+    fs::File,
+    io::{Read, Seek, SeekFrom},
     marker::PhantomData,
+    path::Path,
+    sync::Arc,
     time::{Duration, Instant},
 };
 
@@ -24,10 +37,12 @@ use cookie::Cookie;
 
 mod rejection;
 mod response_code;
+mod response_formatter;
 mod webhook;
 
 pub use rejection::{ExtractRejection, Rejection};
 pub use response_code::ResponseCode;
+pub use response_formatter::{format_pagination, ResponseFormatter};
 pub use webhook::WebHook;
 
 /// An HTTP status code for http v0.2.
@@ -38,9 +53,15 @@ pub type StatusCode = http02::StatusCode;
 #[cfg(not(feature = "http02"))]
 pub type StatusCode = http::StatusCode;
 
+/// Size of the chunks for streaming a local file.
+const FILE_CHUNK_SIZE: u64 = 64 * 1024;
+
 /// A function pointer of transforming the response data.
 pub type DataTransformer = fn(data: &JsonValue) -> Result<Bytes, Error>;
 
+/// A stream of byte chunks for the response body.
+pub type BodyStream = BoxStream<'static, Result<Bytes, Error>>;
+
 /// An HTTP response.
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -90,9 +111,15 @@ pub struct Response<S: ResponseCode> {
     /// Transformer of the response data.
     #[serde(skip)]
     data_transformer: Option<DataTransformer>,
+    /// Streaming body.
+    #[serde(skip)]
+    body_stream: Option<SharedBodyStream>,
     /// Content type.
     #[serde(skip)]
     content_type: Option<SharedString>,
+    /// Content type negotiated by the `accept` header.
+    #[serde(skip)]
+    negotiated_content_type: Option<&'static str>,
     /// Trace context.
     #[serde(skip)]
     trace_context: Option<TraceContext>,
@@ -127,7 +154,9 @@ impl<S: ResponseCode> Response<S> {
             json_data: JsonValue::Null,
             bytes_data: Bytes::new(),
             data_transformer: None,
+            body_stream: None,
             content_type: None,
+            negotiated_content_type: None,
             trace_context: None,
             server_timing: ServerTiming::new(),
             headers: SmallVec::new(),
@@ -160,7 +189,9 @@ impl<S: ResponseCode> Response<S> {
             json_data: JsonValue::Null,
             bytes_data: Bytes::new(),
             data_transformer: None,
+            body_stream: None,
             content_type: None,
+            negotiated_content_type: None,
             trace_context: None,
             server_timing: ServerTiming::new(),
             headers: SmallVec::new(),
@@ -172,6 +203,12 @@ impl<S: ResponseCode> Response<S> {
             res.detail = message;
         }
         res.trace_context = Some(ctx.new_trace_context());
+        #[cfg(any(feature = "cbor", feature = "msgpack"))]
+        {
+            res.negotiated_content_type = ctx
+                .get_header("accept")
+                .and_then(helper::negotiate_content_type);
+        }
         res
     }
 
@@ -181,6 +218,12 @@ impl<S: ResponseCode> Response<S> {
         self.start_time = ctx.start_time();
         self.request_id = ctx.request_id();
         self.trace_context = Some(ctx.new_trace_context());
+        #[cfg(any(feature = "cbor", feature = "msgpack"))]
+        {
+            self.negotiated_content_type = ctx
+                .get_header("accept")
+                .and_then(helper::negotiate_content_type);
+        }
         self
     }
 
@@ -221,6 +264,60 @@ impl<S: ResponseCode> Response<S> {
         self
     }
 
+    /// Sets a stream of byte chunks as the response body,
+    /// which will be sent to the client without buffering the entire content.
+    ///
+    /// The chunks are polled only when the connection is ready to write,
+    /// so a slow client applies backpressure to the producer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use futures::stream;
+    ///
+    /// let lines = records.into_iter().map(|record| {
+    ///     let mut line = serde_json::to_vec(&record)?;
+    ///     line.push(b'\n');
+    ///     Ok(line.into())
+    /// });
+    /// let mut res = Response::default().context(&req).stream(stream::iter(lines));
+    /// res.set_content_type("application/jsonlines; charset=utf-8");
+    /// ```
+    pub fn stream<St>(mut self, body: St) -> Self
+    where
+        St: Stream<Item = Result<Bytes, Error>> + Send + 'static,
+    {
+        self.set_body_stream(body);
+        self
+    }
+
+    /// Sets a stream of JSON values as the response body in the JSON Lines format.
+    /// An empty line is sent as a heartbeat at every interval until the stream ends,
+    /// which keeps the connection alive through proxies if the values are produced slowly.
+    pub fn stream_jsonlines<St>(self, values: St, heartbeat_interval: Duration) -> Self
+    where
+        St: Stream<Item = Result<JsonValue, Error>> + Send + 'static,
+    {
+        let lines = values
+            .map(|result| -> Result<Bytes, Error> {
+                let mut bytes = serde_json::to_vec(&result?)?;
+                bytes.push(b'\n');
+                Ok(bytes.into())
+            })
+            .map(Some)
+            .chain(stream::iter([None]));
+        let heartbeats = stream::unfold((), move |_| async move {
+            Delay::new(heartbeat_interval).await;
+            Some((Some(Ok(Bytes::from_static(b"\n"))), ()))
+        });
+        let body = stream::select(lines, heartbeats)
+            .take_while(|item| future::ready(item.is_some()))
+            .filter_map(future::ready);
+        let mut res = self.stream(body);
+        res.set_content_type("application/jsonlines; charset=utf-8");
+        res
+    }
+
     /// Sets the response code.
     pub fn set_code(&mut self, code: S) {
         let success = code.is_success();
@@ -306,6 +403,7 @@ impl<S: ResponseCode> Response<S> {
     pub fn set_json_data(&mut self, data: impl Into<JsonValue>) {
         self.json_data = data.into();
         self.bytes_data = Bytes::new();
+        self.body_stream = None;
     }
 
     /// Sets the bytes data.
@@ -313,6 +411,7 @@ impl<S: ResponseCode> Response<S> {
     pub fn set_bytes_data(&mut self, data: impl Into<Bytes>) {
         self.json_data = JsonValue::Null;
         self.bytes_data = data.into();
+        self.body_stream = None;
     }
 
     /// Sets the response data for the validation.
@@ -320,6 +419,7 @@ impl<S: ResponseCode> Response<S> {
     pub fn set_validation_data(&mut self, validation: Validation) {
         self.json_data = validation.into_map().into();
         self.bytes_data = Bytes::new();
+        self.body_stream = None;
     }
 
     /// Sets a transformer for the response data.
@@ -335,7 +435,9 @@ impl<S: ResponseCode> Response<S> {
     /// Currently, we have built-in support for the following values:
     ///
     /// - `application/json`
+    /// - `application/cbor`
     /// - `application/jsonlines`
+    /// - `application/msgpack`
     /// - `application/octet-stream`
     /// - `application/problem+json`
     /// - `application/x-www-form-urlencoded`
@@ -389,6 +491,13 @@ impl<S: ResponseCode> Response<S> {
         self.set_content_type("text/plain; charset=utf-8");
     }
 
+    /// Sets the HTML content as the response body.
+    #[inline]
+    pub fn set_html_response(&mut self, data: impl Into<String>) {
+        self.set_json_data(data.into());
+        self.set_content_type("text/html; charset=utf-8");
+    }
+
     /// Sets the bytes data as the response body.
     #[inline]
     pub fn set_bytes_response(&mut self, data: impl Into<Bytes>) {
@@ -408,6 +517,12 @@ impl<S: ResponseCode> Response<S> {
         self.trace_context = trace_context;
     }
 
+    /// Sets the content type negotiated by the `accept` header.
+    #[inline]
+    pub(crate) fn set_negotiated_content_type(&mut self, content_type: Option<&'static str>) {
+        self.negotiated_content_type = content_type;
+    }
+
     /// Sets the start time.
     #[inline]
     pub(crate) fn set_start_time(&mut self, start_time: Instant) {
@@ -506,8 +621,10 @@ impl<S: ResponseCode> Response<S> {
     #[inline]
     pub fn content_type(&self) -> &str {
         self.content_type.as_deref().unwrap_or_else(|| {
-            if !self.bytes_data.is_empty() {
+            if !self.bytes_data.is_empty() || self.body_stream.is_some() {
                 "application/octet-stream"
+            } else if let Some(content_type) = self.negotiated_content_type {
+                content_type
             } else if self.is_success() {
                 "application/json; charset=utf-8"
             } else {
@@ -542,6 +659,15 @@ impl<S: ResponseCode> Response<S> {
         self.server_timing.to_string()
     }
 
+    /// Takes the streaming body if it has been set by [`Response::stream()`].
+    /// The stream can only be taken once even if the response has been cloned.
+    #[inline]
+    pub fn take_body_stream(&mut self) -> Option<BodyStream> {
+        self.body_stream
+            .as_ref()
+            .and_then(|body| body.0.lock().take())
+    }
+
     /// Reads the response into a byte buffer.
     pub fn read_bytes(&mut self) -> Result<Bytes, Error> {
         let has_bytes_data = !self.bytes_data.is_empty();
@@ -564,6 +690,10 @@ impl<S: ResponseCode> Response<S> {
         }
 
         let content_type = self.content_type();
+        #[cfg(any(feature = "cbor", feature = "msgpack"))]
+        let body_encoder = helper::get_body_encoder(content_type);
+        #[cfg(not(any(feature = "cbor", feature = "msgpack")))]
+        let body_encoder: Option<helper::BodyEncoder> = None;
         let (bytes, etag_opt) = if crate::helper::check_json_content_type(content_type) {
             let (capacity, etag_opt) = if has_json_data {
                 let data = serde_json::to_vec(&self.json_data)?;
@@ -573,7 +703,23 @@ impl<S: ResponseCode> Response<S> {
                 (128, None)
             };
             let mut bytes = Vec::with_capacity(capacity);
-            serde_json::to_writer(&mut bytes, &self)?;
+            if let Some(body) = self.format_body()? {
+                serde_json::to_writer(&mut bytes, &body)?;
+            } else {
+                serde_json::to_writer(&mut bytes, &self)?;
+            }
+            (bytes, etag_opt)
+        } else if let Some(encoder) = body_encoder {
+            let etag_opt = if has_json_data {
+                Some(EntityTag::from_data(&encoder(&self.json_data)?))
+            } else {
+                None
+            };
+            let bytes = if let Some(body) = self.format_body()? {
+                encoder(&body)?
+            } else {
+                encoder(&serde_json::to_value(&self)?)?
+            };
             (bytes, etag_opt)
         } else if has_json_data {
             let value = &self.json_data;
@@ -595,6 +741,23 @@ impl<S: ResponseCode> Response<S> {
         Ok(bytes.into())
     }
 
+    /// Formats the response body with the response formatter if it has been set.
+    fn format_body(&self) -> Result<Option<JsonValue>, Error> {
+        let Some(formatter) = response_formatter::get_response_formatter() else {
+            return Ok(None);
+        };
+        let mut body = serde_json::to_value(self)?
+            .into_map_opt()
+            .unwrap_or_default();
+        body.upsert("timestamp", formatter.format_timestamp(DateTime::now()));
+        let body = if self.is_success() {
+            formatter.format_success(body)
+        } else {
+            formatter.format_error(body)
+        };
+        Ok(Some(body))
+    }
+
     /// Gets the response time.
     ///
     /// # Note
@@ -614,10 +777,77 @@ impl<S: ResponseCode> Response<S> {
     }
 
     /// Sends a file to the client.
+    #[inline]
     pub fn send_file(&mut self, file: NamedFile) {
+        let etag = file.etag();
+        self.send_file_with_etag(file, etag);
+    }
+
+    /// Sends a file to the client with the custom ETag,
+    /// which can be used when the file only contains a byte range of the whole content.
+    #[inline]
+    pub fn send_file_with_etag(&mut self, file: NamedFile, etag: impl ToString) {
+        self.send_named_file(file, etag.to_string(), false);
+    }
+
+    /// Sends a file to the client as an attachment to download,
+    /// even if the content type can be displayed inline.
+    #[inline]
+    pub fn send_attachment(&mut self, file: NamedFile) {
+        let etag = file.etag().to_string();
+        self.send_named_file(file, etag, true);
+    }
+
+    /// Sends a byte range of the local file to the client with the custom ETag.
+    /// The content is streamed from the disk in chunks instead of being read into memory.
+    pub fn send_file_range(
+        &mut self,
+        path: impl AsRef<Path>,
+        start: u64,
+        end: u64,
+        etag: impl ToString,
+    ) -> Result<(), Error> {
+        let path = path.as_ref();
+        let mut file = File::open(path)?;
+        file.seek(SeekFrom::Start(start))?;
+
+        let file_name = path
+            .file_name()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        self.set_file_headers(&NamedFile::new(file_name), etag.to_string(), false);
+
+        let length = end.saturating_sub(start) + 1;
+        let body = stream::unfold((file, length), |(mut file, remaining)| async move {
+            if remaining == 0 {
+                return None;
+            }
+
+            let mut buffer = vec![0; remaining.min(FILE_CHUNK_SIZE) as usize];
+            match file.read(&mut buffer) {
+                Ok(0) => None,
+                Ok(size) => {
+                    buffer.truncate(size);
+                    Some((Ok(Bytes::from(buffer)), (file, remaining - size as u64)))
+                }
+                Err(err) => Some((Err(Error::from(err)), (file, 0))),
+            }
+        });
+        self.set_body_stream(body);
+        Ok(())
+    }
+
+    /// Sends a named file to the client.
+    fn send_named_file(&mut self, file: NamedFile, etag: String, attachment: bool) {
+        self.set_file_headers(&file, etag, attachment);
+        self.set_bytes_data(Bytes::from(file));
+    }
+
+    /// Sets the headers for sending a named file.
+    fn set_file_headers(&mut self, file: &NamedFile, etag: String, attachment: bool) {
         let mut displayed_inline = false;
         if let Some(content_type) = file.content_type() {
-            displayed_inline = helper::displayed_inline(content_type);
+            displayed_inline = !attachment && helper::displayed_inline(content_type);
             self.set_content_type(content_type.to_string());
         }
         if !displayed_inline {
@@ -628,8 +858,17 @@ impl<S: ResponseCode> Response<S> {
                 );
             }
         }
-        self.insert_header("etag", file.etag());
-        self.set_bytes_data(Bytes::from(file));
+        self.insert_header("etag", etag);
+    }
+
+    /// Sets a stream of byte chunks as the response body.
+    fn set_body_stream<St>(&mut self, body: St)
+    where
+        St: Stream<Item = Result<Bytes, Error>> + Send + 'static,
+    {
+        self.json_data = JsonValue::Null;
+        self.bytes_data = Bytes::new();
+        self.body_stream = Some(SharedBodyStream(Arc::new(Mutex::new(Some(body.boxed())))));
     }
 
     /// Consumes `self` and returns the custom headers.
@@ -647,6 +886,25 @@ impl<S: ResponseCode> Response<S> {
         self.record_server_timing("total", None, Some(duration));
         self.insert_header("server-timing", self.server_timing());
 
+        // The response body is negotiated by the `accept` header.
+        #[cfg(any(feature = "cbor", feature = "msgpack"))]
+        if self.content_type.is_none() && self.bytes_data.is_empty() {
+            let vary = self
+                .headers
+                .iter_mut()
+                .find(|(key, _)| key.eq_ignore_ascii_case("vary"));
+            if let Some((_, value)) = vary {
+                if !value
+                    .split(',')
+                    .any(|s| s.trim().eq_ignore_ascii_case("accept"))
+                {
+                    value.push_str(", accept");
+                }
+            } else {
+                self.insert_header("vary", "accept");
+            }
+        }
+
         self.headers.into_iter()
     }
 }
@@ -683,6 +941,17 @@ impl Response<StatusCode> {
     }
 }
 
+/// A streaming body shared by the cloned responses.
+#[derive(Clone)]
+struct SharedBodyStream(Arc<Mutex<Option<BodyStream>>>);
+
+impl fmt::Debug for SharedBodyStream {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SharedBodyStream")
+    }
+}
+
 impl<S: ResponseCode> Default for Response<S> {
     #[inline]
     fn default() -> Self {
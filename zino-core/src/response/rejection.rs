@@ -17,6 +17,8 @@ pub struct Rejection {
     context: Option<Context>,
     /// Optional trace context.
     trace_context: Option<TraceContext>,
+    /// Custom headers.
+    headers: Vec<(SharedString, String)>,
 }
 
 /// Rejection kind.
@@ -35,10 +37,16 @@ enum RejectionKind {
     MethodNotAllowed(Error),
     /// 409 Conflict
     Conflict(Error),
+    /// 412 Precondition Failed
+    PreconditionFailed(Error),
+    /// 429 Too Many Requests
+    TooManyRequests(Error),
     /// 500 Internal Server Error
     InternalServerError(Error),
     /// 503 Service Unavailable
     ServiceUnavailable(Error),
+    /// 504 Gateway Timeout
+    GatewayTimeout(Error),
 }
 
 impl Rejection {
@@ -49,6 +57,7 @@ impl Rejection {
             kind: BadRequest(validation),
             context: None,
             trace_context: None,
+            headers: Vec::new(),
         }
     }
 
@@ -59,6 +68,7 @@ impl Rejection {
             kind: Unauthorized(err.into()),
             context: None,
             trace_context: None,
+            headers: Vec::new(),
         }
     }
 
@@ -69,6 +79,7 @@ impl Rejection {
             kind: Forbidden(err.into()),
             context: None,
             trace_context: None,
+            headers: Vec::new(),
         }
     }
 
@@ -79,6 +90,7 @@ impl Rejection {
             kind: NotFound(err.into()),
             context: None,
             trace_context: None,
+            headers: Vec::new(),
         }
     }
 
@@ -89,6 +101,7 @@ impl Rejection {
             kind: MethodNotAllowed(err.into()),
             context: None,
             trace_context: None,
+            headers: Vec::new(),
         }
     }
 
@@ -99,6 +112,29 @@ impl Rejection {
             kind: Conflict(err.into()),
             context: None,
             trace_context: None,
+            headers: Vec::new(),
+        }
+    }
+
+    /// Creates a `412 Precondition Failed` rejection.
+    #[inline]
+    pub fn precondition_failed(err: impl Into<Error>) -> Self {
+        Self {
+            kind: PreconditionFailed(err.into()),
+            context: None,
+            trace_context: None,
+            headers: Vec::new(),
+        }
+    }
+
+    /// Creates a `429 Too Many Requests` rejection.
+    #[inline]
+    pub fn too_many_requests(err: impl Into<Error>) -> Self {
+        Self {
+            kind: TooManyRequests(err.into()),
+            context: None,
+            trace_context: None,
+            headers: Vec::new(),
         }
     }
 
@@ -109,6 +145,7 @@ impl Rejection {
             kind: InternalServerError(err.into()),
             context: None,
             trace_context: None,
+            headers: Vec::new(),
         }
     }
 
@@ -119,6 +156,18 @@ impl Rejection {
             kind: ServiceUnavailable(err.into()),
             context: None,
             trace_context: None,
+            headers: Vec::new(),
+        }
+    }
+
+    /// Creates a `504 Gateway Timeout` rejection.
+    #[inline]
+    pub fn gateway_timeout(err: impl Into<Error>) -> Self {
+        Self {
+            kind: GatewayTimeout(err.into()),
+            context: None,
+            trace_context: None,
+            headers: Vec::new(),
         }
     }
 
@@ -129,7 +178,8 @@ impl Rejection {
         Self::bad_request(validation)
     }
 
-    /// Creates a new instance from an error classified by the error message.
+    /// Creates a new instance from an error classified by the error message
+    /// or the structured [error kind](crate::error::ErrorKind).
     pub fn from_error(err: impl Into<Error>) -> Self {
         let err = err.into();
         let message = err.message();
@@ -143,10 +193,21 @@ impl Rejection {
             Self::method_not_allowed(err)
         } else if message.starts_with("409 Conflict") {
             Self::conflict(err)
+        } else if message.starts_with("412 Precondition Failed") {
+            Self::precondition_failed(err)
+        } else if message.starts_with("429 Too Many Requests") {
+            Self::too_many_requests(err)
+        } else if message.starts_with("504 Gateway Timeout") || err.is_timeout() {
+            Self::gateway_timeout(err)
         } else if message.starts_with("503 Service Unavailable") {
             Self::service_unavailable(err)
         } else {
-            Self::internal_server_error(err)
+            match err.kind().status_code() {
+                404 => Self::not_found(err),
+                409 => Self::conflict(err),
+                503 => Self::service_unavailable(err),
+                _ => Self::internal_server_error(err),
+            }
         }
     }
 
@@ -164,6 +225,13 @@ impl Rejection {
         self
     }
 
+    /// Inserts a custom header for the rejection response,
+    /// such as `x-ratelimit-remaining`.
+    #[inline]
+    pub fn insert_header(&mut self, name: impl Into<SharedString>, value: impl ToString) {
+        self.headers.push((name.into(), value.to_string()));
+    }
+
     /// Returns the status code as `u16`.
     #[inline]
     pub fn status_code(&self) -> u16 {
@@ -174,8 +242,11 @@ impl Rejection {
             NotFound(_) => 404,
             MethodNotAllowed(_) => 405,
             Conflict(_) => 409,
+            PreconditionFailed(_) => 412,
+            TooManyRequests(_) => 429,
             InternalServerError(_) => 500,
             ServiceUnavailable(_) => 503,
+            GatewayTimeout(_) => 504,
         }
     }
 }
@@ -213,6 +284,16 @@ impl From<Rejection> for Response<StatusCode> {
                 res.set_error_message(err);
                 res
             }
+            PreconditionFailed(err) => {
+                let mut res = Response::new(StatusCode::PRECONDITION_FAILED);
+                res.set_error_message(err);
+                res
+            }
+            TooManyRequests(err) => {
+                let mut res = Response::new(StatusCode::TOO_MANY_REQUESTS);
+                res.set_error_message(err);
+                res
+            }
             InternalServerError(err) => {
                 let mut res = Response::new(StatusCode::INTERNAL_SERVER_ERROR);
                 res.set_error_message(err);
@@ -223,11 +304,20 @@ impl From<Rejection> for Response<StatusCode> {
                 res.set_error_message(err);
                 res
             }
+            GatewayTimeout(err) => {
+                let mut res = Response::new(StatusCode::GATEWAY_TIMEOUT);
+                res.set_error_message(err);
+                res
+            }
         };
         if let Some(ctx) = rejection.context {
             res.set_instance(ctx.instance().to_owned());
             res.set_start_time(ctx.start_time());
             res.set_request_id(ctx.request_id());
+            res.set_negotiated_content_type(ctx.content_type());
+        }
+        for (name, value) in rejection.headers {
+            res.insert_header(name, value);
         }
         res.set_trace_context(rejection.trace_context);
         res
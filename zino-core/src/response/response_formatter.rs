@@ -0,0 +1,97 @@
+use crate::{datetime::DateTime, extension::JsonObjectExt, JsonValue, Map};
+use std::sync::OnceLock;
+
+/// Customized envelopes for the response body.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino_core::{extension::JsonObjectExt, response::ResponseFormatter, JsonValue, Map};
+///
+/// struct CustomFormatter;
+///
+/// impl ResponseFormatter for CustomFormatter {
+///     fn format_success(&self, mut body: Map) -> JsonValue {
+///         let mut data = body.remove("data").unwrap_or_default();
+///         let mut pagination = Map::new();
+///         if let Some(data) = data.as_object_mut() {
+///             for key in ["total", "pages"] {
+///                 if let Some(value) = data.remove(key) {
+///                     pagination.upsert(key, value);
+///                 }
+///             }
+///         }
+///
+///         let mut envelope = Map::new();
+///         envelope.upsert("code", 0);
+///         envelope.upsert("message", body.remove("message"));
+///         envelope.upsert("data", data);
+///         envelope.upsert("pagination", pagination);
+///         envelope.into()
+///     }
+///
+///     fn pagination_keys(&self) -> (&'static str, &'static str) {
+///         ("total", "pages")
+///     }
+/// }
+///
+/// CustomFormatter.register();
+/// ```
+pub trait ResponseFormatter: Send + Sync {
+    /// Formats the body of a successful response.
+    /// The body contains the default fields and the `timestamp` of the response.
+    #[inline]
+    fn format_success(&self, body: Map) -> JsonValue {
+        body.into()
+    }
+
+    /// Formats the body of an error response.
+    /// The body contains the default fields and the `timestamp` of the response.
+    #[inline]
+    fn format_error(&self, body: Map) -> JsonValue {
+        body.into()
+    }
+
+    /// Formats the timestamp of the response.
+    #[inline]
+    fn format_timestamp(&self, timestamp: DateTime) -> JsonValue {
+        timestamp.to_string().into()
+    }
+
+    /// Returns the keys of the pagination metadata in the form `(total_rows, page_count)`.
+    #[inline]
+    fn pagination_keys(&self) -> (&'static str, &'static str) {
+        ("total_rows", "page_count")
+    }
+
+    /// Registers the formatter globally. It can only be registered once.
+    fn register(self)
+    where
+        Self: Sized + 'static,
+    {
+        if RESPONSE_FORMATTER.set(Box::new(self)).is_err() {
+            tracing::warn!("the response formatter has already been registered");
+        }
+    }
+}
+
+/// Formats the pagination metadata with the registered response formatter.
+pub fn format_pagination(total_rows: u64, page_count: u64) -> Map {
+    let (total_rows_key, page_count_key) = RESPONSE_FORMATTER
+        .get()
+        .map(|formatter| formatter.pagination_keys())
+        .unwrap_or(("total_rows", "page_count"));
+    let mut pagination = Map::new();
+    pagination.upsert(total_rows_key, total_rows);
+    pagination.upsert(page_count_key, page_count);
+    pagination
+}
+
+/// Returns the registered response formatter.
+#[inline]
+pub(super) fn get_response_formatter() -> Option<&'static dyn ResponseFormatter> {
+    RESPONSE_FORMATTER.get().map(|formatter| formatter.as_ref())
+}
+
+/// Global response formatter.
+static RESPONSE_FORMATTER: OnceLock<Box<dyn ResponseFormatter>> = OnceLock::new();
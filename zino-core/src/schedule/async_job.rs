@@ -1,10 +1,22 @@
 //! Scheduler for sync and async cron jobs.
 
-use super::AsyncScheduler;
-use crate::{datetime::DateTime, extension::TomlTableExt, BoxFuture, Map, Uuid};
+use super::{
+    delayed_task, AsyncScheduler, AsyncTask, ConcurrencyPolicy, DelayedTask, DstPolicy, JobCommand,
+    JobOutcome, JobRegistry, JobRunGuard, JobRunRecorder, JobSchedule, TaskHandle, TaskStore,
+};
+use crate::{
+    datetime::{Date, DateTime},
+    extension::{JsonObjectExt, TomlTableExt},
+    BoxFuture, Map, Uuid,
+};
 use chrono::Local;
-use cron::Schedule;
-use std::{str::FromStr, time::Duration};
+use futures::{
+    future::{self, Either},
+    stream::{FuturesUnordered, StreamExt},
+    FutureExt,
+};
+use futures_timer::Delay;
+use std::{panic::AssertUnwindSafe, time::Duration};
 use toml::Table;
 
 /// A function pointer of the async cron job.
@@ -15,6 +27,8 @@ pub type AsyncCronJob =
 pub struct AsyncJob {
     /// Job ID.
     id: Uuid,
+    /// Job name.
+    name: String,
     /// Job data.
     data: Map,
     /// Flag to indicate whether the job is disabled.
@@ -23,8 +37,14 @@ pub struct AsyncJob {
     immediate: bool,
     /// Remaining ticks.
     remaining_ticks: Option<usize>,
-    /// Cron expression parser.
-    schedule: Schedule,
+    /// Cron schedule.
+    schedule: JobSchedule,
+    /// Priority of the job.
+    priority: JobPriority,
+    /// Concurrency policy.
+    concurrency_policy: ConcurrencyPolicy,
+    /// Timeout for a run of the job.
+    timeout: Option<Duration>,
     /// Cron job to run.
     run: AsyncCronJob,
     /// Last time when running the job.
@@ -39,15 +59,18 @@ impl AsyncJob {
     /// Panics if the cron expression is invalid.
     #[inline]
     pub fn new(cron_expr: &str, exec: AsyncCronJob) -> Self {
-        let schedule = Schedule::from_str(cron_expr)
-            .unwrap_or_else(|err| panic!("invalid cron expression `{cron_expr}`: {err}"));
+        let schedule = JobSchedule::new(cron_expr);
         Self {
             id: Uuid::now_v7(),
+            name: String::new(),
             data: Map::new(),
             disabled: false,
             immediate: false,
             remaining_ticks: None,
             schedule,
+            priority: JobPriority::default(),
+            concurrency_policy: ConcurrencyPolicy::default(),
+            timeout: None,
             run: exec,
             last_tick: None,
         }
@@ -57,11 +80,10 @@ impl AsyncJob {
     ///
     /// # Panics
     ///
-    /// Panics if the `cron` expression is invalid.
+    /// Panics if the `cron` expression or the `timezone` is invalid.
     pub fn with_config(config: &Table, exec: AsyncCronJob) -> Self {
-        let cron_expr = config.get_str("cron").unwrap_or_default();
-        let schedule = Schedule::from_str(cron_expr)
-            .unwrap_or_else(|err| panic!("invalid cron expression `{cron_expr}`: {err}"));
+        let schedule = JobSchedule::with_config(config);
+        let name = config.get_str("name").unwrap_or_default().to_owned();
         let data = config
             .get_table("data")
             .map(|t| t.to_map())
@@ -72,18 +94,50 @@ impl AsyncJob {
             .get_bool("once")
             .and_then(|b| b.then_some(1))
             .or_else(|| config.get_usize("max-ticks"));
+        let priority = config
+            .get_str("priority")
+            .and_then(|s| match s.parse() {
+                Ok(priority) => Some(priority),
+                Err(err) => {
+                    tracing::warn!("{err}");
+                    None
+                }
+            })
+            .unwrap_or_default();
+        let concurrency_policy = config
+            .get_str("concurrency-policy")
+            .and_then(|s| match s.parse() {
+                Ok(policy) => Some(policy),
+                Err(err) => {
+                    tracing::warn!("{err}");
+                    None
+                }
+            })
+            .unwrap_or_default();
+        let timeout = config.get_duration("timeout");
         Self {
             id: Uuid::now_v7(),
+            name,
             data,
             disabled,
             immediate,
             remaining_ticks,
             schedule,
+            priority,
+            concurrency_policy,
+            timeout,
             run: exec,
             last_tick: None,
         }
     }
 
+    /// Sets the job name.
+    #[inline]
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
     /// Enables the flag to indicate whether the job is disabled.
     #[inline]
     pub fn disable(mut self, disabled: bool) -> Self {
@@ -98,6 +152,61 @@ impl AsyncJob {
         self
     }
 
+    /// Sets the timezone for the cron expression, such as `Asia/Shanghai`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the timezone is invalid.
+    #[inline]
+    pub fn timezone(mut self, timezone: &str) -> Self {
+        self.schedule.set_timezone(timezone);
+        self
+    }
+
+    /// Sets the policy for the scheduled times affected by DST transitions.
+    #[inline]
+    pub fn dst_policy(mut self, dst_policy: DstPolicy) -> Self {
+        self.schedule.set_dst_policy(dst_policy);
+        self
+    }
+
+    /// Excludes the dates in the timezone when the job should not be executed, such as holidays.
+    #[inline]
+    pub fn exclude_dates(mut self, dates: impl IntoIterator<Item = Date>) -> Self {
+        self.schedule.exclude_dates(dates);
+        self
+    }
+
+    /// Sets the priority of the job.
+    #[inline]
+    pub fn priority(mut self, priority: JobPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Sets the policy for the scheduled runs when the previous run is still in progress.
+    #[inline]
+    pub fn concurrency_policy(mut self, policy: ConcurrencyPolicy) -> Self {
+        self.concurrency_policy = policy;
+        self
+    }
+
+    /// Sets the timeout for a run of the job. The job future will be cancelled
+    /// if it does not finish in time.
+    #[inline]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the maximum jitter to delay the scheduled times randomly,
+    /// which avoids running the job on multiple instances at the same time.
+    #[inline]
+    pub fn jitter(mut self, max_jitter: Duration) -> Self {
+        self.schedule.set_jitter(max_jitter);
+        self
+    }
+
     /// Sets the number of maximum ticks.
     #[inline]
     pub fn max_ticks(mut self, ticks: usize) -> Self {
@@ -118,6 +227,18 @@ impl AsyncJob {
         self.id
     }
 
+    /// Returns the job name.
+    #[inline]
+    pub fn job_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the job priority.
+    #[inline]
+    pub fn job_priority(&self) -> JobPriority {
+        self.priority
+    }
+
     /// Returns a reference to the job data.
     #[inline]
     pub fn data(&self) -> &Map {
@@ -170,54 +291,270 @@ impl AsyncJob {
     pub async fn tick(&mut self) {
         let now = Local::now();
         let disabled = self.disabled;
-        let run = self.run;
+        let mut next_tick = now;
         if let Some(last_tick) = self.last_tick {
-            for event in self.schedule.after(&last_tick) {
-                if event > now || self.is_fused() {
+            let num_events = self
+                .schedule
+                .upcoming(last_tick)
+                .take_while(|event| *event <= now)
+                .count();
+            let num_runs = match self.concurrency_policy {
+                ConcurrencyPolicy::Allow => num_events,
+                _ => num_events.min(1),
+            };
+            for _ in 0..num_runs {
+                if self.is_fused() {
                     break;
                 }
                 if !disabled {
-                    run(self.id, &mut self.data, last_tick.into()).await;
+                    self.run_once(last_tick.into(), false).await;
                     if let Some(ticks) = self.remaining_ticks {
                         self.remaining_ticks = Some(ticks.saturating_sub(1));
                     }
                 }
             }
+            if !disabled && num_runs > 0 {
+                let mut num_skipped = num_events - num_runs;
+                if self.concurrency_policy == ConcurrencyPolicy::Forbid {
+                    // Skips the events scheduled while the job was running.
+                    next_tick = Local::now();
+                    num_skipped += self
+                        .schedule
+                        .upcoming(now)
+                        .take_while(|event| *event <= next_tick)
+                        .count();
+                }
+                if num_skipped > 0 {
+                    self.skip_runs(num_skipped);
+                }
+            }
         } else if !disabled && self.immediate && !self.is_fused() {
-            run(self.id, &mut self.data, now.into()).await;
+            self.run_once(now.into(), false).await;
             if let Some(ticks) = self.remaining_ticks {
                 self.remaining_ticks = Some(ticks.saturating_sub(1));
             }
         }
+        self.last_tick = Some(next_tick);
+    }
+
+    /// Returns `true` if the job has scheduled runs which are due at the time.
+    pub fn is_due(&self, now: chrono::DateTime<Local>) -> bool {
+        if self.disabled || self.is_fused() {
+            return false;
+        }
+        if let Some(last_tick) = self.last_tick {
+            self.schedule
+                .upcoming(last_tick)
+                .next()
+                .is_some_and(|event| event <= now)
+        } else {
+            self.immediate
+        }
+    }
+
+    /// Skips the scheduled runs which are due at the time.
+    pub fn skip_due(&mut self, now: chrono::DateTime<Local>) {
+        if let Some(last_tick) = self.last_tick {
+            let num_skipped = self
+                .schedule
+                .upcoming(last_tick)
+                .take_while(|event| *event <= now)
+                .count();
+            if num_skipped > 0 && !self.disabled {
+                self.skip_runs(num_skipped);
+            }
+        }
         self.last_tick = Some(now);
     }
 
     /// Executes the job manually.
     pub async fn execute(&mut self) {
         let now = Local::now();
-        let run = self.run;
-        run(self.id, &mut self.data, now.into()).await;
+        self.run_once(now.into(), true).await;
         self.last_tick = Some(now);
     }
+
+    /// Returns the job status as a json object.
+    pub fn status(&self) -> Map {
+        let mut map = Map::new();
+        map.upsert("id", self.id.to_string());
+        map.upsert("name", self.name.as_str());
+        map.upsert("kind", "async");
+        map.upsert("cron", self.schedule.cron_expr());
+        map.upsert("timezone", self.schedule.timezone_name());
+        map.upsert(
+            "concurrency_policy",
+            format!("{:?}", self.concurrency_policy),
+        );
+        map.upsert("timeout", self.timeout.map(|d| d.as_millis() as u64));
+        map.upsert("jitter", self.schedule.jitter().as_millis() as u64);
+        map.upsert("disabled", self.disabled);
+        map.upsert("remaining_ticks", self.remaining_ticks);
+        map.upsert("last_tick", self.last_tick.map(DateTime::from));
+        map.upsert(
+            "next_tick",
+            self.schedule
+                .upcoming(Local::now())
+                .next()
+                .map(DateTime::from),
+        );
+        map
+    }
+
+    /// Runs the job once and records the run.
+    async fn run_once(&mut self, last_tick: DateTime, manual: bool) {
+        let guard = JobRunGuard::start(self.id, &self.name, manual);
+        let deadline = self.deadline();
+        let run = self.run;
+        let future = AssertUnwindSafe(run(self.id, &mut self.data, last_tick)).catch_unwind();
+        let result = match deadline {
+            Some((duration, outcome)) => match future::select(future, Delay::new(duration)).await {
+                Either::Left((result, _)) => Ok(result),
+                Either::Right(_) => Err(outcome),
+            },
+            None => Ok(future.await),
+        };
+        match result {
+            Ok(result) => guard.finish(&mut self.data, result.err()),
+            Err(outcome) => guard.interrupt(&mut self.data, outcome),
+        }
+    }
+
+    /// Returns the duration after which the run should be cancelled, and the outcome.
+    fn deadline(&self) -> Option<(Duration, JobOutcome)> {
+        let timeout = self.timeout.map(|timeout| (timeout, JobOutcome::Timeout));
+        if self.concurrency_policy == ConcurrencyPolicy::Replace {
+            let now = Local::now();
+            let next_event = self.schedule.upcoming(now).next();
+            if let Some(duration) = next_event.and_then(|event| (event - now).to_std().ok()) {
+                if timeout.is_some_and(|(timeout, _)| timeout <= duration) {
+                    return timeout;
+                }
+                return Some((duration, JobOutcome::Cancelled));
+            }
+        }
+        timeout
+    }
+
+    /// Skips the scheduled runs.
+    fn skip_runs(&self, num_skipped: usize) {
+        tracing::warn!(
+            job_id = self.id.to_string(),
+            job_name = self.name.as_str(),
+            "skip {num_skipped} overlapping runs of the job"
+        );
+
+        // Emit metrics.
+        #[cfg(feature = "metrics")]
+        metrics::counter!("zino_job_skipped_runs_total", "job_name" => self.name.clone())
+            .increment(num_skipped as u64);
+    }
 }
 
-/// A type contains and executes the async scheduled jobs.
+/// A type contains and executes the async scheduled jobs and the delayed tasks.
 #[derive(Default)]
 pub struct AsyncJobScheduler {
     /// A list of async jobs.
     jobs: Vec<AsyncJob>,
+    /// An optional recorder for the job runs.
+    recorder: Option<JobRunRecorder>,
+    /// Registered tasks for the delayed tasks.
+    tasks: Vec<(String, AsyncTask)>,
+    /// An optional store for the delayed tasks.
+    task_store: Option<Box<dyn TaskStore>>,
+    /// Flag to indicate whether the delayed tasks have been restored from the store.
+    tasks_restored: bool,
+    /// Priority queues of the due jobs.
+    queue: JobQueue,
+    /// Jobs which are running and taken out of the list.
+    running: FuturesUnordered<BoxFuture<'static, AsyncJob>>,
 }
 
 impl AsyncJobScheduler {
     /// Creates a new instance.
     #[inline]
     pub fn new() -> Self {
-        Self { jobs: Vec::new() }
+        Self {
+            jobs: Vec::new(),
+            recorder: None,
+            tasks: Vec::new(),
+            task_store: None,
+            tasks_restored: false,
+            queue: JobQueue::new(),
+            running: FuturesUnordered::new(),
+        }
+    }
+
+    /// Sets a recorder for the runs of both sync and async jobs,
+    /// which will be called with the pending runs after each tick.
+    #[inline]
+    pub fn set_recorder(&mut self, recorder: JobRunRecorder) {
+        JobRegistry::enable_recording();
+        self.recorder = Some(recorder);
+    }
+
+    /// Sets the capacity of the queue for the due jobs with the priority.
+    #[inline]
+    pub fn set_queue_capacity(&mut self, priority: JobPriority, capacity: usize) {
+        self.queue.set_capacity(priority, capacity);
+    }
+
+    /// Sets the number of workers to run the jobs with the priority concurrently.
+    #[inline]
+    pub fn set_workers(&mut self, priority: JobPriority, workers: usize) {
+        self.queue.set_workers(priority, workers);
+    }
+
+    /// Sets the policy for the due jobs when the queue is full.
+    #[inline]
+    pub fn set_overflow_policy(&mut self, policy: OverflowPolicy) {
+        self.queue.set_overflow_policy(policy);
+    }
+
+    /// Sets a store for persisting the delayed tasks.
+    /// The pending tasks will be restored from the store in the first tick.
+    #[inline]
+    pub fn set_task_store(&mut self, store: impl TaskStore + 'static) {
+        self.task_store = Some(Box::new(store));
+    }
+
+    /// Registers a task so that the delayed tasks with the name can be executed
+    /// after they are restored from the store.
+    pub fn register_task(&mut self, name: impl Into<String>, exec: AsyncTask) {
+        let name = name.into();
+        if let Some(entry) = self.tasks.iter_mut().find(|(key, _)| key == &name) {
+            entry.1 = exec;
+        } else {
+            self.tasks.push((name, exec));
+        }
+    }
+
+    /// Schedules a one-shot task to be executed after a delay.
+    #[inline]
+    pub fn schedule_in(&mut self, delay: Duration, task: DelayedTask) -> TaskHandle {
+        self.schedule_at(DateTime::now() + delay, task)
+    }
+
+    /// Schedules a one-shot task to be executed at the time.
+    pub fn schedule_at(&mut self, run_at: DateTime, task: DelayedTask) -> TaskHandle {
+        if let Some(exec) = task.exec() {
+            if !self.tasks.iter().any(|(name, _)| name == task.name()) {
+                self.tasks.push((task.name().to_owned(), exec));
+            }
+        }
+        task.schedule_at(run_at)
+    }
+
+    /// Cancels a delayed task by ID.
+    #[inline]
+    pub fn cancel(&mut self, task_id: Uuid) -> bool {
+        TaskHandle::new(task_id).cancel()
     }
 
     /// Adds an async job to the scheduler and returns the job ID.
     pub fn add(&mut self, job: AsyncJob) -> Uuid {
         let job_id = job.id;
+        JobRegistry::update_job(job_id, job.status());
         self.jobs.push(job);
         job_id
     }
@@ -227,6 +564,8 @@ impl AsyncJobScheduler {
         let position = self.jobs.iter().position(|job| job.id == job_id);
         if let Some(index) = position {
             self.jobs.remove(index);
+            self.queue.remove(job_id);
+            JobRegistry::remove_job(job_id);
             true
         } else {
             false
@@ -246,40 +585,149 @@ impl AsyncJobScheduler {
     }
 
     /// Returns the duration till the next job is supposed to run.
+    /// It is zero if there are queued or running jobs, which are driven by [`tick()`].
+    ///
+    /// [`tick()`]: AsyncJobScheduler::tick
     pub fn time_till_next_job(&self) -> Duration {
+        if !(self.queue.is_empty() && self.running.is_empty()) {
+            Duration::ZERO
+        } else {
+            self.time_till_next_event()
+        }
+    }
+
+    /// Returns the duration till the next scheduled event of the jobs or the delayed tasks.
+    fn time_till_next_event(&self) -> Duration {
         if self.jobs.is_empty() {
             Duration::from_millis(500)
         } else {
             let mut duration = chrono::Duration::zero();
             let now = Local::now();
             for job in self.jobs.iter() {
-                for event in job.schedule.after(&now).take(1) {
+                if let Some(event) = job.schedule.upcoming(now).next() {
                     let interval = event - now;
                     if duration.is_zero() || interval < duration {
                         duration = interval;
                     }
                 }
             }
-            duration
+            let mut duration = duration
                 .to_std()
                 .unwrap_or_else(|_| Duration::from_millis(500))
+                .min(MAX_TICK_INTERVAL);
+            if let Some(run_at) = delayed_task::next_run_at() {
+                duration = duration.min(run_at.span_after_now().unwrap_or_default());
+            }
+            duration
         }
     }
 
     /// Increments time for the scheduler and executes any pending jobs asynchronously.
     /// It is recommended to sleep for at least 500 milliseconds between invocations of this method.
-    #[inline]
+    ///
+    /// The due jobs are put into the bounded queues for their priorities,
+    /// and they are started as soon as there are idle workers for their priorities.
+    /// The running jobs are driven until one of them finishes or the next event is due,
+    /// so that a long job never blocks the others from being started.
     pub async fn tick(&mut self) {
-        let mut fused_jobs = Vec::new();
+        while let Some(Some(job)) = self.running.next().now_or_never() {
+            self.finish_job(job);
+        }
+
+        let commands = JobRegistry::take_commands(|job_id| self.get(job_id).is_some());
+        for (job_id, command) in commands {
+            if let Some(job) = self.get_mut(job_id) {
+                match command {
+                    JobCommand::Pause => job.pause(),
+                    JobCommand::Resume => job.resume(),
+                    JobCommand::Trigger => job.execute().await,
+                }
+            }
+        }
+
+        // Enqueues the due jobs.
+        let now = Local::now();
+        let overflow_policy = self.queue.overflow_policy();
+        let mut dropped_jobs = Vec::new();
+        for job in &mut self.jobs {
+            let job_id = job.id();
+            if self.queue.contains(job_id) {
+                continue;
+            }
+            if !job.is_due(now) {
+                job.last_tick = Some(now);
+                continue;
+            }
+
+            let priority = job.job_priority();
+            if self.queue.is_full(priority) {
+                match overflow_policy {
+                    OverflowPolicy::DropNewest => {
+                        job.skip_due(now);
+                        continue;
+                    }
+                    OverflowPolicy::DropOldest => {
+                        if let Some(job_id) = self.queue.pop_oldest(priority) {
+                            dropped_jobs.push(job_id);
+                        }
+                    }
+                    _ => continue,
+                }
+            }
+            self.queue.push(priority, job_id);
+        }
         for job in &mut self.jobs {
-            job.tick().await;
+            if dropped_jobs.contains(&job.id()) {
+                job.skip_due(now);
+            }
+        }
+
+        // Starts the queued jobs with the idle workers, with the higher priorities first.
+        let batch = self.queue.take_batch();
+        if !batch.is_empty() {
+            let (jobs, idle_jobs) = std::mem::take(&mut self.jobs)
+                .into_iter()
+                .partition::<Vec<_>, _>(|job| batch.contains(&job.id()));
+            self.jobs = idle_jobs;
+            for mut job in jobs {
+                self.running.push(Box::pin(async move {
+                    job.tick().await;
+                    job
+                }));
+            }
+        }
+        if !self.running.is_empty() {
+            let delay = Delay::new(self.time_till_next_event());
+            if let Either::Left((Some(job), _)) = future::select(self.running.next(), delay).await {
+                self.finish_job(job);
+            }
+        }
+        self.queue.emit_metrics();
+
+        let mut fused_jobs = Vec::new();
+        for job in &self.jobs {
             if job.is_fused() {
                 fused_jobs.push(job.id());
+            } else {
+                JobRegistry::update_job(job.id(), job.status());
             }
         }
         for job_id in fused_jobs {
             self.remove(job_id);
         }
+        self.run_delayed_tasks().await;
+        if let Some(recorder) = self.recorder {
+            let runs = JobRegistry::take_pending_runs();
+            if !runs.is_empty() {
+                recorder(runs).await;
+            }
+        }
+    }
+
+    /// Puts the finished job back to the list and releases its worker.
+    fn finish_job(&mut self, job: AsyncJob) {
+        self.queue.release(job.job_priority());
+        self.jobs.push(job);
     }
 
     /// Executes all the job manually.
@@ -288,12 +736,77 @@ impl AsyncJobScheduler {
             job.execute().await;
         }
     }
+
+    /// Synchronizes the delayed tasks with the store and executes the due tasks.
+    async fn run_delayed_tasks(&mut self) {
+        let unsaved_tasks = delayed_task::take_unsaved_tasks();
+        let cancelled_tasks = delayed_task::take_cancelled_tasks();
+        if let Some(store) = self.task_store.as_deref() {
+            if !self.tasks_restored {
+                match store.load().await {
+                    Ok(tasks) => {
+                        delayed_task::restore_tasks(tasks);
+                        self.tasks_restored = true;
+                    }
+                    Err(err) => tracing::error!("fail to load the delayed tasks: {err}"),
+                }
+            }
+            for task in unsaved_tasks {
+                let task_id = task.id();
+                if let Err(err) = store.save(task).await {
+                    tracing::error!(
+                        task_id = task_id.to_string(),
+                        "fail to save the task: {err}"
+                    );
+                }
+            }
+            for task_id in cancelled_tasks {
+                if let Err(err) = store.remove(task_id).await {
+                    tracing::error!(
+                        task_id = task_id.to_string(),
+                        "fail to remove the task: {err}"
+                    );
+                }
+            }
+        }
+
+        for mut task in delayed_task::take_due_tasks(DateTime::now()) {
+            let task_id = task.id();
+            let exec = task.exec().or_else(|| {
+                self.tasks
+                    .iter()
+                    .find_map(|(name, exec)| (name == task.name()).then_some(*exec))
+            });
+            let Some(exec) = exec else {
+                tracing::warn!(
+                    task_id = task_id.to_string(),
+                    "the delayed task `{}` is not registered",
+                    task.name()
+                );
+                continue;
+            };
+
+            let guard = JobRunGuard::start(task_id, task.name(), false);
+            let result = AssertUnwindSafe(exec(task_id, task.data_mut()))
+                .catch_unwind()
+                .await;
+            guard.finish(task.data_mut(), result.err());
+            if let Some(store) = self.task_store.as_deref() {
+                if let Err(err) = store.remove(task_id).await {
+                    tracing::error!(
+                        task_id = task_id.to_string(),
+                        "fail to remove the task: {err}"
+                    );
+                }
+            }
+        }
+    }
 }
 
 impl AsyncScheduler for AsyncJobScheduler {
     #[inline]
     fn is_ready(&self) -> bool {
-        !self.jobs.is_empty()
+        !(self.jobs.is_empty() && self.tasks.is_empty() && self.running.is_empty())
     }
 
     #[inline]
@@ -306,3 +819,6 @@ impl AsyncScheduler for AsyncJobScheduler {
         self.tick().await;
     }
 }
+
+/// Maximum interval between the ticks to apply the management commands in time.
+const MAX_TICK_INTERVAL: Duration = Duration::from_secs(1);
@@ -0,0 +1,237 @@
+use crate::{
+    datetime::DateTime, error::Error, extension::JsonObjectExt, BoxFuture, LazyLock, Map, Uuid,
+};
+use parking_lot::Mutex;
+use std::{mem, time::Duration};
+
+/// A function pointer of the async one-shot task.
+pub type AsyncTask = for<'a> fn(id: Uuid, data: &'a mut Map) -> BoxFuture<'a>;
+
+/// An interface for persisting the delayed tasks so that they can survive restarts.
+pub trait TaskStore: Send + Sync {
+    /// Saves a delayed task.
+    fn save(&self, task: DelayedTask) -> BoxFuture<'_, Result<(), Error>>;
+
+    /// Removes a delayed task by ID.
+    fn remove(&self, task_id: Uuid) -> BoxFuture<'_, Result<(), Error>>;
+
+    /// Loads all the pending tasks.
+    fn load(&self) -> BoxFuture<'_, Result<Vec<DelayedTask>, Error>>;
+}
+
+/// A one-shot task executed at a specific time.
+///
+/// The task is executed by the `AsyncJobScheduler`. A task restored from the [`TaskStore`]
+/// is executed by the function registered with the same name in the scheduler.
+#[derive(Debug, Clone)]
+pub struct DelayedTask {
+    /// Task ID.
+    id: Uuid,
+    /// Task name.
+    name: String,
+    /// Task data.
+    data: Map,
+    /// The time when the task should be executed.
+    run_at: DateTime,
+    /// Task to run.
+    exec: Option<AsyncTask>,
+}
+
+impl DelayedTask {
+    /// Creates a new instance.
+    #[inline]
+    pub fn new(name: impl Into<String>, exec: AsyncTask) -> Self {
+        Self {
+            id: Uuid::now_v7(),
+            name: name.into(),
+            data: Map::new(),
+            run_at: DateTime::now(),
+            exec: Some(exec),
+        }
+    }
+
+    /// Restores a task from the persisted parts.
+    #[inline]
+    pub fn restore(id: Uuid, name: impl Into<String>, data: Map, run_at: DateTime) -> Self {
+        Self {
+            id,
+            name: name.into(),
+            data,
+            run_at,
+            exec: None,
+        }
+    }
+
+    /// Sets the task data.
+    #[inline]
+    pub fn with_data(mut self, data: Map) -> Self {
+        self.data = data;
+        self
+    }
+
+    /// Schedules the task to be executed after a delay.
+    #[inline]
+    pub fn schedule_in(self, delay: Duration) -> TaskHandle {
+        self.schedule_at(DateTime::now() + delay)
+    }
+
+    /// Schedules the task to be executed at the time.
+    pub fn schedule_at(mut self, run_at: DateTime) -> TaskHandle {
+        let task_id = self.id;
+        self.run_at = run_at;
+
+        let mut queue = TASK_QUEUE.lock();
+        queue.unsaved_tasks.push(self.clone());
+        queue.tasks.push(self);
+        TaskHandle { task_id }
+    }
+
+    /// Returns the task ID.
+    #[inline]
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Returns the task name.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns a reference to the task data.
+    #[inline]
+    pub fn data(&self) -> &Map {
+        &self.data
+    }
+
+    /// Returns the time when the task should be executed.
+    #[inline]
+    pub fn run_at(&self) -> DateTime {
+        self.run_at
+    }
+
+    /// Returns the function to run.
+    #[inline]
+    pub(super) fn exec(&self) -> Option<AsyncTask> {
+        self.exec
+    }
+
+    /// Returns a mutable reference to the task data.
+    #[inline]
+    pub(super) fn data_mut(&mut self) -> &mut Map {
+        &mut self.data
+    }
+
+    /// Converts `self` to a json object.
+    pub fn to_map(&self) -> Map {
+        let mut map = Map::new();
+        map.upsert("id", self.id.to_string());
+        map.upsert("name", self.name.as_str());
+        map.upsert("run_at", self.run_at);
+        map.upsert("data", self.data.clone());
+        map
+    }
+}
+
+/// A handle to cancel a delayed task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskHandle {
+    /// Task ID.
+    task_id: Uuid,
+}
+
+impl TaskHandle {
+    /// Creates a new instance for the task ID,
+    /// which can be used to cancel a task scheduled before restarts.
+    #[inline]
+    pub fn new(task_id: Uuid) -> Self {
+        Self { task_id }
+    }
+
+    /// Returns the task ID.
+    #[inline]
+    pub fn id(&self) -> Uuid {
+        self.task_id
+    }
+
+    /// Returns `true` if the task is waiting to be executed.
+    #[inline]
+    pub fn is_pending(&self) -> bool {
+        TASK_QUEUE
+            .lock()
+            .tasks
+            .iter()
+            .any(|task| task.id == self.task_id)
+    }
+
+    /// Cancels the task. Returns `false` if the task is not pending in the current process,
+    /// but it will still be removed from the task store.
+    pub fn cancel(&self) -> bool {
+        let task_id = self.task_id;
+        let mut queue = TASK_QUEUE.lock();
+        queue.unsaved_tasks.retain(|task| task.id != task_id);
+        queue.cancelled_tasks.push(task_id);
+
+        let num_tasks = queue.tasks.len();
+        queue.tasks.retain(|task| task.id != task_id);
+        queue.tasks.len() < num_tasks
+    }
+}
+
+/// Adds the tasks restored from the task store.
+pub(super) fn restore_tasks(tasks: Vec<DelayedTask>) {
+    let mut queue = TASK_QUEUE.lock();
+    let cancelled_tasks = mem::take(&mut queue.cancelled_tasks);
+    for task in tasks {
+        if cancelled_tasks.contains(&task.id) {
+            queue.cancelled_tasks.push(task.id);
+        } else if !queue.tasks.iter().any(|t| t.id == task.id) {
+            queue.tasks.push(task);
+        }
+    }
+}
+
+/// Takes the tasks which should be saved into the task store.
+#[inline]
+pub(super) fn take_unsaved_tasks() -> Vec<DelayedTask> {
+    mem::take(&mut TASK_QUEUE.lock().unsaved_tasks)
+}
+
+/// Takes the IDs of the cancelled tasks.
+#[inline]
+pub(super) fn take_cancelled_tasks() -> Vec<Uuid> {
+    mem::take(&mut TASK_QUEUE.lock().cancelled_tasks)
+}
+
+/// Takes the tasks which are due at the time.
+pub(super) fn take_due_tasks(now: DateTime) -> Vec<DelayedTask> {
+    let mut queue = TASK_QUEUE.lock();
+    if queue.tasks.is_empty() {
+        return Vec::new();
+    }
+
+    let (due_tasks, pending_tasks) = mem::take(&mut queue.tasks)
+        .into_iter()
+        .partition(|task| task.run_at <= now);
+    queue.tasks = pending_tasks;
+    due_tasks
+}
+
+/// Returns the earliest time when a pending task should be executed.
+pub(super) fn next_run_at() -> Option<DateTime> {
+    TASK_QUEUE.lock().tasks.iter().map(|task| task.run_at).min()
+}
+
+/// The queue of delayed tasks.
+#[derive(Default)]
+struct TaskQueue {
+    /// The pending tasks.
+    tasks: Vec<DelayedTask>,
+    /// The tasks which have not been saved.
+    unsaved_tasks: Vec<DelayedTask>,
+    /// The IDs of the cancelled tasks.
+    cancelled_tasks: Vec<Uuid>,
+}
+
+/// Shared task queue.
+static TASK_QUEUE: LazyLock<Mutex<TaskQueue>> = LazyLock::new(|| Mutex::new(TaskQueue::default()));
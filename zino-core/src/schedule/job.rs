@@ -1,10 +1,16 @@
 //! Scheduler for sync and async cron jobs.
 
-use super::Scheduler;
-use crate::{datetime::DateTime, extension::TomlTableExt, Map, Uuid};
+use super::{DstPolicy, JobCommand, JobRegistry, JobRunGuard, JobSchedule, Scheduler};
+use crate::{
+    datetime::{Date, DateTime},
+    extension::{JsonObjectExt, TomlTableExt},
+    Map, Uuid,
+};
 use chrono::Local;
-use cron::Schedule;
-use std::{str::FromStr, time::Duration};
+use std::{
+    panic::{self, AssertUnwindSafe},
+    time::Duration,
+};
 use toml::Table;
 
 /// A function pointer of the cron job.
@@ -14,6 +20,8 @@ pub type CronJob = fn(id: Uuid, data: &mut Map, last_tick: DateTime);
 pub struct Job {
     /// Job ID.
     id: Uuid,
+    /// Job name.
+    name: String,
     /// Job data.
     data: Map,
     /// Flag to indicate whether the job is disabled.
@@ -22,8 +30,8 @@ pub struct Job {
     immediate: bool,
     /// Remaining ticks.
     remaining_ticks: Option<usize>,
-    /// Cron expression parser.
-    schedule: Schedule,
+    /// Cron schedule.
+    schedule: JobSchedule,
     /// Cron job to run.
     run: CronJob,
     /// Last time when running the job.
@@ -38,10 +46,10 @@ impl Job {
     /// Panics if the cron expression is invalid.
     #[inline]
     pub fn new(cron_expr: &str, exec: CronJob) -> Self {
-        let schedule = Schedule::from_str(cron_expr)
-            .unwrap_or_else(|err| panic!("invalid cron expression `{cron_expr}`: {err}"));
+        let schedule = JobSchedule::new(cron_expr);
         Self {
             id: Uuid::now_v7(),
+            name: String::new(),
             data: Map::new(),
             disabled: false,
             immediate: false,
@@ -56,11 +64,10 @@ impl Job {
     ///
     /// # Panics
     ///
-    /// Panics if the `cron` expression is invalid.
+    /// Panics if the `cron` expression or the `timezone` is invalid.
     pub fn with_config(config: &Table, exec: CronJob) -> Self {
-        let cron_expr = config.get_str("cron").unwrap_or_default();
-        let schedule = Schedule::from_str(cron_expr)
-            .unwrap_or_else(|err| panic!("invalid cron expression `{cron_expr}`: {err}"));
+        let schedule = JobSchedule::with_config(config);
+        let name = config.get_str("name").unwrap_or_default().to_owned();
         let data = config
             .get_table("data")
             .map(|t| t.to_map())
@@ -73,6 +80,7 @@ impl Job {
             .or_else(|| config.get_usize("max-ticks"));
         Self {
             id: Uuid::now_v7(),
+            name,
             data,
             disabled,
             immediate,
@@ -83,6 +91,13 @@ impl Job {
         }
     }
 
+    /// Sets the job name.
+    #[inline]
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
     /// Enables the flag to indicate whether the job is disabled.
     #[inline]
     pub fn disable(mut self, disabled: bool) -> Self {
@@ -97,6 +112,39 @@ impl Job {
         self
     }
 
+    /// Sets the timezone for the cron expression, such as `Asia/Shanghai`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the timezone is invalid.
+    #[inline]
+    pub fn timezone(mut self, timezone: &str) -> Self {
+        self.schedule.set_timezone(timezone);
+        self
+    }
+
+    /// Sets the policy for the scheduled times affected by DST transitions.
+    #[inline]
+    pub fn dst_policy(mut self, dst_policy: DstPolicy) -> Self {
+        self.schedule.set_dst_policy(dst_policy);
+        self
+    }
+
+    /// Excludes the dates in the timezone when the job should not be executed, such as holidays.
+    #[inline]
+    pub fn exclude_dates(mut self, dates: impl IntoIterator<Item = Date>) -> Self {
+        self.schedule.exclude_dates(dates);
+        self
+    }
+
+    /// Sets the maximum jitter to delay the scheduled times randomly,
+    /// which avoids running the job on multiple instances at the same time.
+    #[inline]
+    pub fn jitter(mut self, max_jitter: Duration) -> Self {
+        self.schedule.set_jitter(max_jitter);
+        self
+    }
+
     /// Sets the number of maximum ticks.
     #[inline]
     pub fn max_ticks(mut self, ticks: usize) -> Self {
@@ -117,6 +165,12 @@ impl Job {
         self.id
     }
 
+    /// Returns the job name.
+    #[inline]
+    pub fn job_name(&self) -> &str {
+        &self.name
+    }
+
     /// Returns a reference to the job data.
     #[inline]
     pub fn data(&self) -> &Map {
@@ -169,21 +223,25 @@ impl Job {
     pub fn tick(&mut self) {
         let now = Local::now();
         let disabled = self.disabled;
-        let run = self.run;
         if let Some(last_tick) = self.last_tick {
-            for event in self.schedule.after(&last_tick) {
-                if event > now || self.is_fused() {
+            let num_events = self
+                .schedule
+                .upcoming(last_tick)
+                .take_while(|event| *event <= now)
+                .count();
+            for _ in 0..num_events {
+                if self.is_fused() {
                     break;
                 }
                 if !disabled {
-                    run(self.id, &mut self.data, last_tick.into());
+                    self.run_once(last_tick.into(), false);
                     if let Some(ticks) = self.remaining_ticks {
                         self.remaining_ticks = Some(ticks.saturating_sub(1));
                     }
                 }
             }
         } else if !disabled && self.immediate && !self.is_fused() {
-            run(self.id, &mut self.data, now.into());
+            self.run_once(now.into(), false);
             if let Some(ticks) = self.remaining_ticks {
                 self.remaining_ticks = Some(ticks.saturating_sub(1));
             }
@@ -194,10 +252,40 @@ impl Job {
     /// Executes the job manually.
     pub fn execute(&mut self) {
         let now = Local::now();
-        let run = self.run;
-        run(self.id, &mut self.data, now.into());
+        self.run_once(now.into(), true);
         self.last_tick = Some(now);
     }
+
+    /// Returns the job status as a json object.
+    pub fn status(&self) -> Map {
+        let mut map = Map::new();
+        map.upsert("id", self.id.to_string());
+        map.upsert("name", self.name.as_str());
+        map.upsert("kind", "sync");
+        map.upsert("cron", self.schedule.cron_expr());
+        map.upsert("timezone", self.schedule.timezone_name());
+        map.upsert("jitter", self.schedule.jitter().as_millis() as u64);
+        map.upsert("disabled", self.disabled);
+        map.upsert("remaining_ticks", self.remaining_ticks);
+        map.upsert("last_tick", self.last_tick.map(DateTime::from));
+        map.upsert(
+            "next_tick",
+            self.schedule
+                .upcoming(Local::now())
+                .next()
+                .map(DateTime::from),
+        );
+        map
+    }
+
+    /// Runs the job once and records the run.
+    fn run_once(&mut self, last_tick: DateTime, manual: bool) {
+        let guard = JobRunGuard::start(self.id, &self.name, manual);
+        let run = self.run;
+        let (id, data) = (self.id, &mut self.data);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| run(id, data, last_tick)));
+        guard.finish(&mut self.data, result.err());
+    }
 }
 
 /// A type contains and executes the scheduled jobs.
@@ -217,6 +305,7 @@ impl JobScheduler {
     /// Adds a job to the scheduler and returns the job ID.
     pub fn add(&mut self, job: Job) -> Uuid {
         let job_id = job.id;
+        JobRegistry::update_job(job_id, job.status());
         self.jobs.push(job);
         job_id
     }
@@ -226,6 +315,7 @@ impl JobScheduler {
         let position = self.jobs.iter().position(|job| job.id == job_id);
         if let Some(index) = position {
             self.jobs.remove(index);
+            JobRegistry::remove_job(job_id);
             true
         } else {
             false
@@ -252,7 +342,7 @@ impl JobScheduler {
             let mut duration = chrono::Duration::zero();
             let now = Local::now();
             for job in self.jobs.iter() {
-                for event in job.schedule.after(&now).take(1) {
+                if let Some(event) = job.schedule.upcoming(now).next() {
                     let interval = event - now;
                     if duration.is_zero() || interval < duration {
                         duration = interval;
@@ -262,18 +352,31 @@ impl JobScheduler {
             duration
                 .to_std()
                 .unwrap_or_else(|_| Duration::from_millis(500))
+                .min(MAX_TICK_INTERVAL)
         }
     }
 
     /// Increments time for the scheduler and executes any pending jobs.
     /// It is recommended to sleep for at least 500 milliseconds between invocations of this method.
-    #[inline]
     pub fn tick(&mut self) {
+        let commands = JobRegistry::take_commands(|job_id| self.get(job_id).is_some());
+        for (job_id, command) in commands {
+            if let Some(job) = self.get_mut(job_id) {
+                match command {
+                    JobCommand::Pause => job.pause(),
+                    JobCommand::Resume => job.resume(),
+                    JobCommand::Trigger => job.execute(),
+                }
+            }
+        }
+
         let mut fused_jobs = Vec::new();
         for job in &mut self.jobs {
             job.tick();
             if job.is_fused() {
                 fused_jobs.push(job.id());
+            } else {
+                JobRegistry::update_job(job.id(), job.status());
             }
         }
         for job_id in fused_jobs {
@@ -305,3 +408,6 @@ impl Scheduler for JobScheduler {
         self.tick();
     }
 }
+
+/// Maximum interval between the ticks to apply the management commands in time.
+const MAX_TICK_INTERVAL: Duration = Duration::from_secs(1);
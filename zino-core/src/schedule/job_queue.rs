@@ -0,0 +1,245 @@
+use crate::Uuid;
+use std::{collections::VecDeque, str::FromStr};
+
+/// Priority levels of the async jobs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum JobPriority {
+    /// Low priority for bulk jobs.
+    Low,
+    /// Normal priority.
+    #[default]
+    Normal,
+    /// High priority.
+    High,
+    /// Critical priority, which pre-empts all the other jobs.
+    Critical,
+}
+
+impl JobPriority {
+    /// All the priority levels in the descending order.
+    const LEVELS: [Self; 4] = [Self::Critical, Self::High, Self::Normal, Self::Low];
+
+    /// Returns the priority as a string.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Normal => "normal",
+            Self::High => "high",
+            Self::Critical => "critical",
+        }
+    }
+
+    /// Returns the index of the queue for the priority.
+    #[inline]
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl FromStr for JobPriority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "low" => Ok(Self::Low),
+            "normal" => Ok(Self::Normal),
+            "high" => Ok(Self::High),
+            "critical" => Ok(Self::Critical),
+            _ => Err(format!("invalid job priority `{s}`")),
+        }
+    }
+}
+
+/// Policies for the due jobs when the queue is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OverflowPolicy {
+    /// Keeps the job waiting until the queue has space.
+    #[default]
+    Block,
+    /// Skips the scheduled runs of the new job.
+    DropNewest,
+    /// Skips the scheduled runs of the oldest job in the queue.
+    DropOldest,
+}
+
+impl FromStr for OverflowPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "block" => Ok(Self::Block),
+            "drop-newest" => Ok(Self::DropNewest),
+            "drop-oldest" => Ok(Self::DropOldest),
+            _ => Err(format!("invalid overflow policy `{s}`")),
+        }
+    }
+}
+
+/// Bounded queues of the due jobs for each priority level.
+pub(super) struct JobQueue {
+    /// Queues of the job IDs.
+    queues: [VecDeque<Uuid>; 4],
+    /// Capacities of the queues.
+    capacities: [usize; 4],
+    /// Number of workers for each priority level.
+    workers: [usize; 4],
+    /// Number of busy workers for each priority level.
+    busy_workers: [usize; 4],
+    /// Overflow policy.
+    overflow_policy: OverflowPolicy,
+}
+
+impl Default for JobQueue {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JobQueue {
+    /// Creates a new instance.
+    pub(super) fn new() -> Self {
+        Self {
+            queues: Default::default(),
+            capacities: [DEFAULT_CAPACITY; 4],
+            workers: [1, 2, 2, 4],
+            busy_workers: [0; 4],
+            overflow_policy: OverflowPolicy::default(),
+        }
+    }
+
+    /// Sets the capacity of the queue for the priority.
+    #[inline]
+    pub(super) fn set_capacity(&mut self, priority: JobPriority, capacity: usize) {
+        self.capacities[priority.index()] = capacity.max(1);
+    }
+
+    /// Sets the number of workers for the priority.
+    #[inline]
+    pub(super) fn set_workers(&mut self, priority: JobPriority, workers: usize) {
+        self.workers[priority.index()] = workers.max(1);
+    }
+
+    /// Sets the overflow policy.
+    #[inline]
+    pub(super) fn set_overflow_policy(&mut self, policy: OverflowPolicy) {
+        self.overflow_policy = policy;
+    }
+
+    /// Returns the overflow policy.
+    #[inline]
+    pub(super) fn overflow_policy(&self) -> OverflowPolicy {
+        self.overflow_policy
+    }
+
+    /// Returns `true` if there are no jobs in the queues.
+    #[inline]
+    pub(super) fn is_empty(&self) -> bool {
+        self.queues.iter().all(|queue| queue.is_empty())
+    }
+
+    /// Returns `true` if the job is in the queues.
+    #[inline]
+    pub(super) fn contains(&self, job_id: Uuid) -> bool {
+        self.queues.iter().any(|queue| queue.contains(&job_id))
+    }
+
+    /// Returns `true` if the queue for the priority is full.
+    #[inline]
+    pub(super) fn is_full(&self, priority: JobPriority) -> bool {
+        let index = priority.index();
+        self.queues[index].len() >= self.capacities[index]
+    }
+
+    /// Pushes a job to the back of the queue.
+    #[inline]
+    pub(super) fn push(&mut self, priority: JobPriority, job_id: Uuid) {
+        self.queues[priority.index()].push_back(job_id);
+    }
+
+    /// Pops the oldest job from the queue.
+    #[inline]
+    pub(super) fn pop_oldest(&mut self, priority: JobPriority) -> Option<Uuid> {
+        self.queues[priority.index()].pop_front()
+    }
+
+    /// Removes a job from the queues.
+    pub(super) fn remove(&mut self, job_id: Uuid) {
+        for queue in self.queues.iter_mut() {
+            queue.retain(|&id| id != job_id);
+        }
+    }
+
+    /// Takes a batch of jobs to run concurrently, with the higher priorities first.
+    /// The size of the batch for each priority is limited by the number of idle workers,
+    /// which are busy until they are released.
+    pub(super) fn take_batch(&mut self) -> Vec<Uuid> {
+        let mut batch = Vec::new();
+        for priority in JobPriority::LEVELS {
+            let index = priority.index();
+            let queue = &mut self.queues[index];
+            let idle_workers = self.workers[index].saturating_sub(self.busy_workers[index]);
+            let size = idle_workers.min(queue.len());
+            batch.extend(queue.drain(..size));
+            self.busy_workers[index] += size;
+        }
+        batch
+    }
+
+    /// Releases a busy worker for the priority when a job has finished.
+    #[inline]
+    pub(super) fn release(&mut self, priority: JobPriority) {
+        let index = priority.index();
+        self.busy_workers[index] = self.busy_workers[index].saturating_sub(1);
+    }
+
+    /// Emits the metrics for the queue depth.
+    pub(super) fn emit_metrics(&self) {
+        #[cfg(feature = "metrics")]
+        for priority in JobPriority::LEVELS {
+            let depth = self.queues[priority.index()].len();
+            metrics::gauge!("zino_job_queue_depth", "priority" => priority.as_str())
+                .set(depth as f64);
+        }
+    }
+}
+
+/// Default capacity of the queue.
+const DEFAULT_CAPACITY: usize = 100;
+
+#[cfg(test)]
+mod tests {
+    use super::{JobPriority, JobQueue};
+    use crate::Uuid;
+
+    #[test]
+    fn it_takes_batches_by_priority() {
+        let mut queue = JobQueue::new();
+        queue.set_workers(JobPriority::Low, 1);
+        queue.set_workers(JobPriority::Critical, 2);
+
+        let low_jobs = [Uuid::now_v7(), Uuid::now_v7()];
+        let critical_jobs = [Uuid::now_v7(), Uuid::now_v7(), Uuid::now_v7()];
+        for job_id in low_jobs {
+            queue.push(JobPriority::Low, job_id);
+        }
+        for job_id in critical_jobs {
+            queue.push(JobPriority::Critical, job_id);
+        }
+
+        let batch = queue.take_batch();
+        assert_eq!(batch, [critical_jobs[0], critical_jobs[1], low_jobs[0]]);
+
+        // The remaining jobs wait until the workers are released.
+        assert!(queue.take_batch().is_empty());
+        queue.release(JobPriority::Critical);
+        assert_eq!(queue.take_batch(), [critical_jobs[2]]);
+
+        queue.release(JobPriority::Low);
+        assert_eq!(queue.take_batch(), [low_jobs[1]]);
+        assert!(queue.is_empty());
+    }
+}
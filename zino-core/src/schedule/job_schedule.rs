@@ -0,0 +1,312 @@
+use crate::{datetime::Date, extension::TomlTableExt, state::State};
+use chrono::{DateTime, Local, LocalResult, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use cron::Schedule;
+use rand::{thread_rng, Rng};
+use std::{str::FromStr, time::Duration};
+use toml::Table;
+
+/// Policies for the scheduled times affected by daylight saving time transitions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DstPolicy {
+    /// Skips the nonexistent times and runs once for the repeated times.
+    #[default]
+    Skip,
+    /// Runs at the end of the gap for the nonexistent times
+    /// and runs twice for the repeated times.
+    Run,
+}
+
+impl FromStr for DstPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "skip" => Ok(Self::Skip),
+            "run" => Ok(Self::Run),
+            _ => Err(format!("invalid DST policy `{s}`")),
+        }
+    }
+}
+
+/// Policies for the scheduled runs of a job when the previous run is still in progress.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConcurrencyPolicy {
+    /// Runs all the scheduled events one after another.
+    #[default]
+    Allow,
+    /// Skips the events scheduled while the previous run is in progress.
+    Forbid,
+    /// Cancels the previous run when the next event is scheduled.
+    Replace,
+}
+
+impl FromStr for ConcurrencyPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "allow" => Ok(Self::Allow),
+            "forbid" => Ok(Self::Forbid),
+            "replace" => Ok(Self::Replace),
+            _ => Err(format!("invalid concurrency policy `{s}`")),
+        }
+    }
+}
+
+/// A cron schedule with the timezone and the exclusion calendar.
+pub(super) struct JobSchedule {
+    /// Cron expression.
+    cron_expr: String,
+    /// Cron expression parser.
+    schedule: Schedule,
+    /// Timezone. The local timezone will be used if it is `None`.
+    timezone: Option<Tz>,
+    /// DST policy.
+    dst_policy: DstPolicy,
+    /// Dates in the timezone when the job should not be executed.
+    excluded_dates: Vec<NaiveDate>,
+    /// A random delay added to the scheduled times.
+    jitter: Duration,
+}
+
+impl JobSchedule {
+    /// Creates a new instance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cron expression is invalid.
+    pub(super) fn new(expr: &str) -> Self {
+        let cron_expr = match expr.trim() {
+            "@yearly" | "@annually" => "0 0 0 1 1 *",
+            "@monthly" => "0 0 0 1 * *",
+            "@weekly" => "0 0 0 * * Sun",
+            "@daily" | "@midnight" => "0 0 0 * * *",
+            "@hourly" => "0 0 * * * *",
+            cron_expr => cron_expr,
+        };
+        let schedule = Schedule::from_str(cron_expr)
+            .unwrap_or_else(|err| panic!("invalid cron expression `{cron_expr}`: {err}"));
+        Self {
+            cron_expr: expr.to_owned(),
+            schedule,
+            timezone: None,
+            dst_policy: DstPolicy::default(),
+            excluded_dates: Vec::new(),
+            jitter: Duration::ZERO,
+        }
+    }
+
+    /// Creates a new instance with the configuration.
+    ///
+    /// The dates of the `calendar` are loaded from the `[calendars]` table in the app config.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `cron` expression or the `timezone` is invalid.
+    pub(super) fn with_config(config: &Table) -> Self {
+        let cron_expr = config.get_str("cron").unwrap_or_default();
+        let mut schedule = Self::new(cron_expr);
+        if let Some(timezone) = config.get_str("timezone") {
+            schedule.set_timezone(timezone);
+        }
+        if let Some(dst_policy) = config.get_str("dst-policy") {
+            match dst_policy.parse() {
+                Ok(dst_policy) => schedule.dst_policy = dst_policy,
+                Err(err) => tracing::warn!("{err}"),
+            }
+        }
+        if let Some(dates) = config.get_str_array("exclude-dates") {
+            schedule.exclude_dates(parse_dates(dates));
+        }
+        if let Some(max_jitter) = config.get_duration("jitter") {
+            schedule.set_jitter(max_jitter);
+        }
+        if let Some(calendar) = config.get_str("calendar") {
+            let dates = State::shared()
+                .get_config("calendars")
+                .and_then(|config| config.get_str_array(calendar));
+            if let Some(dates) = dates {
+                schedule.exclude_dates(parse_dates(dates));
+            } else {
+                tracing::warn!("the calendar `{calendar}` does not exist");
+            }
+        }
+        schedule
+    }
+
+    /// Sets the timezone.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the timezone is invalid.
+    pub(super) fn set_timezone(&mut self, timezone: &str) {
+        let timezone = timezone
+            .parse()
+            .unwrap_or_else(|err| panic!("invalid timezone `{timezone}`: {err}"));
+        self.timezone = Some(timezone);
+    }
+
+    /// Sets the DST policy.
+    #[inline]
+    pub(super) fn set_dst_policy(&mut self, dst_policy: DstPolicy) {
+        self.dst_policy = dst_policy;
+    }
+
+    /// Sets the jitter as a random delay up to `max_jitter`.
+    /// The delay is chosen once so that the interval between the runs is preserved.
+    pub(super) fn set_jitter(&mut self, max_jitter: Duration) {
+        let max_millis = max_jitter.as_millis().try_into().unwrap_or(u64::MAX);
+        self.jitter = Duration::from_millis(thread_rng().gen_range(0..=max_millis));
+    }
+
+    /// Adds the excluded dates.
+    #[inline]
+    pub(super) fn exclude_dates(&mut self, dates: impl IntoIterator<Item = Date>) {
+        self.excluded_dates
+            .extend(dates.into_iter().map(NaiveDate::from));
+    }
+
+    /// Returns the cron expression.
+    #[inline]
+    pub(super) fn cron_expr(&self) -> &str {
+        &self.cron_expr
+    }
+
+    /// Returns the name of the timezone.
+    #[inline]
+    pub(super) fn timezone_name(&self) -> Option<&str> {
+        self.timezone.as_ref().map(|tz| tz.name())
+    }
+
+    /// Returns the jitter.
+    #[inline]
+    pub(super) fn jitter(&self) -> Duration {
+        self.jitter
+    }
+
+    /// Returns an iterator over the events after the time, with the jitter applied.
+    pub(super) fn upcoming(
+        &self,
+        after: DateTime<Local>,
+    ) -> Box<dyn Iterator<Item = DateTime<Local>> + '_> {
+        let jitter =
+            chrono::Duration::from_std(self.jitter).unwrap_or_else(|_| chrono::Duration::zero());
+        let naive_after = match self.timezone {
+            Some(tz) => (after - jitter).with_timezone(&tz).naive_local(),
+            None => (after - jitter).naive_local(),
+        };
+
+        // Iterates the wall-clock times by treating them as UTC.
+        let events = self
+            .schedule
+            .after(&Utc.from_utc_datetime(&naive_after))
+            .map(|event| event.naive_utc())
+            .filter(|event| !self.excluded_dates.contains(&event.date()))
+            .flat_map(|event| match self.timezone {
+                Some(tz) => resolve_local_datetime(&tz, event, self.dst_policy),
+                None => resolve_local_datetime(&Local, event, self.dst_policy),
+            })
+            .map(move |event| event + jitter)
+            .filter(move |event| *event > after);
+        Box::new(events)
+    }
+}
+
+/// Parses the dates in the format `%Y-%m-%d`.
+fn parse_dates(dates: Vec<&str>) -> Vec<Date> {
+    dates
+        .into_iter()
+        .filter_map(|date| match date.parse() {
+            Ok(date) => Some(date),
+            Err(err) => {
+                tracing::warn!("invalid excluded date `{date}`: {err}");
+                None
+            }
+        })
+        .collect()
+}
+
+/// Resolves the wall-clock time in the timezone according to the DST policy.
+fn resolve_local_datetime<Z: TimeZone>(
+    tz: &Z,
+    dt: NaiveDateTime,
+    dst_policy: DstPolicy,
+) -> Vec<DateTime<Local>> {
+    match tz.from_local_datetime(&dt) {
+        LocalResult::Single(dt) => vec![dt.with_timezone(&Local)],
+        LocalResult::Ambiguous(earliest, latest) => match dst_policy {
+            DstPolicy::Skip => vec![earliest.with_timezone(&Local)],
+            DstPolicy::Run => vec![earliest.with_timezone(&Local), latest.with_timezone(&Local)],
+        },
+        LocalResult::None => match dst_policy {
+            DstPolicy::Skip => Vec::new(),
+            DstPolicy::Run => (1..=16)
+                .find_map(|i| {
+                    let dt = dt + chrono::Duration::minutes(15 * i);
+                    tz.from_local_datetime(&dt).earliest()
+                })
+                .map(|dt| dt.with_timezone(&Local))
+                .into_iter()
+                .collect(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DstPolicy, JobSchedule};
+    use chrono::{Datelike, Local, TimeZone, Timelike};
+    use chrono_tz::Tz;
+
+    #[test]
+    fn it_resolves_dst_transitions() {
+        let tz: Tz = "America/New_York".parse().unwrap();
+        let start = tz
+            .with_ymd_and_hms(2024, 3, 9, 12, 0, 0)
+            .unwrap()
+            .with_timezone(&Local);
+
+        // 02:30 does not exist on 2024-03-10 in New York.
+        let mut schedule = JobSchedule::new("0 30 2 * * *");
+        schedule.set_timezone("America/New_York");
+        let event = schedule.upcoming(start).next().unwrap();
+        assert_eq!(event.with_timezone(&tz).day(), 11);
+
+        schedule.set_dst_policy(DstPolicy::Run);
+        let event = schedule.upcoming(start).next().unwrap().with_timezone(&tz);
+        assert_eq!(event.day(), 10);
+        assert_eq!(event.hour(), 3);
+
+        // 01:30 occurs twice on 2024-11-03 in New York.
+        let start = tz
+            .with_ymd_and_hms(2024, 11, 2, 12, 0, 0)
+            .unwrap()
+            .with_timezone(&Local);
+        let mut schedule = JobSchedule::new("0 30 1 * * *");
+        schedule.set_timezone("America/New_York");
+        let events = schedule.upcoming(start).take(2).collect::<Vec<_>>();
+        assert_eq!(events[1].with_timezone(&tz).day(), 4);
+
+        schedule.set_dst_policy(DstPolicy::Run);
+        let events = schedule.upcoming(start).take(2).collect::<Vec<_>>();
+        assert_eq!(events[1].with_timezone(&tz).day(), 3);
+    }
+
+    #[test]
+    fn it_skips_excluded_dates() {
+        let tz: Tz = "Asia/Shanghai".parse().unwrap();
+        let start = tz
+            .with_ymd_and_hms(2024, 12, 31, 12, 0, 0)
+            .unwrap()
+            .with_timezone(&Local);
+        let mut schedule = JobSchedule::new("@daily");
+        schedule.set_timezone("Asia/Shanghai");
+        schedule.exclude_dates(["2025-01-01".parse().unwrap()]);
+        let event = schedule.upcoming(start).next().unwrap().with_timezone(&tz);
+        assert_eq!(event.day(), 2);
+        assert_eq!(event.hour(), 0);
+    }
+}
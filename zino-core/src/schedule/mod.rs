@@ -1,12 +1,28 @@
-//! Scheduler for sync and async cron jobs.
+//! Scheduler for sync and async cron jobs, and the long-running operations.
 
 use std::{future::Future, time::Duration};
 
 mod async_job;
+mod delayed_task;
 mod job;
+mod job_queue;
+mod job_schedule;
+mod operation;
+mod registry;
 
 pub use async_job::{AsyncCronJob, AsyncJob, AsyncJobScheduler};
+pub use delayed_task::{AsyncTask, DelayedTask, TaskHandle, TaskStore};
 pub use job::{CronJob, Job, JobScheduler};
+pub use job_queue::{JobPriority, OverflowPolicy};
+pub use job_schedule::{ConcurrencyPolicy, DstPolicy};
+pub use operation::{
+    Operation, OperationHandle, OperationRegistry, OperationStatus, OperationStore,
+};
+pub use registry::{JobCommand, JobOutcome, JobRegistry, JobRun, JobRunRecorder};
+
+use job_queue::JobQueue;
+use job_schedule::JobSchedule;
+use registry::JobRunGuard;
 
 /// An interface for scheduling sync jobs.
 pub trait Scheduler {
@@ -31,3 +47,33 @@ pub trait AsyncScheduler {
     /// Increments time for the scheduler and executes any pending jobs asynchronously.
     fn tick(&mut self) -> impl Future<Output = ()> + Send;
 }
+
+impl<A, B> AsyncScheduler for (A, B)
+where
+    A: AsyncScheduler + Send,
+    B: AsyncScheduler + Send,
+{
+    #[inline]
+    fn is_ready(&self) -> bool {
+        self.0.is_ready() || self.1.is_ready()
+    }
+
+    fn time_till_next_job(&self) -> Duration {
+        match (self.0.is_ready(), self.1.is_ready()) {
+            (true, true) => self.0.time_till_next_job().min(self.1.time_till_next_job()),
+            (false, true) => self.1.time_till_next_job(),
+            _ => self.0.time_till_next_job(),
+        }
+    }
+
+    async fn tick(&mut self) {
+        match (self.0.is_ready(), self.1.is_ready()) {
+            (true, true) => {
+                futures::future::join(self.0.tick(), self.1.tick()).await;
+            }
+            (true, false) => self.0.tick().await,
+            (false, true) => self.1.tick().await,
+            _ => (),
+        }
+    }
+}
@@ -0,0 +1,412 @@
+use crate::{
+    datetime::DateTime, error::Error, extension::JsonObjectExt, BoxFuture, JsonValue, LazyLock,
+    Map, Uuid,
+};
+use futures::FutureExt;
+use parking_lot::Mutex;
+use std::{
+    collections::{HashMap, VecDeque},
+    future::Future,
+    panic::AssertUnwindSafe,
+    sync::OnceLock,
+};
+
+/// An interface for persisting the long-running operations
+/// so that their status can be queried across restarts and instances.
+pub trait OperationStore: Send + Sync {
+    /// Saves the current state of an operation.
+    fn save(&self, operation: Operation) -> BoxFuture<'_, Result<(), Error>>;
+
+    /// Loads an operation by ID.
+    fn load(&self, operation_id: Uuid) -> BoxFuture<'_, Result<Option<Operation>, Error>>;
+}
+
+/// Status of a long-running operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OperationStatus {
+    /// The operation is waiting to be executed.
+    Pending,
+    /// The operation is being executed.
+    Running,
+    /// The operation finished successfully.
+    Succeeded,
+    /// The operation failed with an error or panicked.
+    Failed,
+}
+
+impl OperationStatus {
+    /// Returns the status as a string.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "Pending",
+            Self::Running => "Running",
+            Self::Succeeded => "Succeeded",
+            Self::Failed => "Failed",
+        }
+    }
+}
+
+/// A long-running operation executed in the background,
+/// which can be polled by the client with the operation ID.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino_core::schedule::Operation;
+///
+/// let (operation_id, future) = Operation::prepare("export-report", None, |op| async move {
+///     op.update_progress(50, "collecting the data").await;
+///     let url = generate_report().await?;
+///     Ok(url.into())
+/// });
+/// tokio::spawn(future);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Operation {
+    /// Operation ID.
+    id: Uuid,
+    /// Operation name.
+    name: String,
+    /// The principal who owns the operation.
+    owner: Option<String>,
+    /// Status.
+    status: OperationStatus,
+    /// Progress in percentage.
+    progress: u8,
+    /// A message describing the current step.
+    message: Option<String>,
+    /// The result of a successful operation.
+    result: Option<JsonValue>,
+    /// The error of a failed operation.
+    error: Option<String>,
+    /// Creation time.
+    created_at: DateTime,
+    /// Last update time.
+    updated_at: DateTime,
+}
+
+impl Operation {
+    /// Creates a new pending operation.
+    fn new(name: impl Into<String>, owner: Option<String>) -> Self {
+        let now = DateTime::now();
+        Self {
+            id: Uuid::now_v7(),
+            name: name.into(),
+            owner,
+            status: OperationStatus::Pending,
+            progress: 0,
+            message: None,
+            result: None,
+            error: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Restores an operation from the persisted parts.
+    #[allow(clippy::too_many_arguments)]
+    pub fn restore(
+        id: Uuid,
+        name: impl Into<String>,
+        owner: Option<String>,
+        status: OperationStatus,
+        progress: u8,
+        message: Option<String>,
+        result: Option<JsonValue>,
+        error: Option<String>,
+        created_at: DateTime,
+        updated_at: DateTime,
+    ) -> Self {
+        Self {
+            id,
+            name: name.into(),
+            owner,
+            status,
+            progress: progress.min(100),
+            message,
+            result,
+            error,
+            created_at,
+            updated_at,
+        }
+    }
+
+    /// Registers a pending operation, and returns the operation ID
+    /// and a future to execute it which should be spawned by the runtime.
+    /// The operation is only visible to the `owner` if it is specified.
+    ///
+    /// The operation succeeds with the output of `f`, or fails if it returns an error or panics.
+    pub fn prepare<F, Fut>(
+        name: impl Into<String>,
+        owner: Option<String>,
+        f: F,
+    ) -> (Uuid, impl Future<Output = ()> + Send + 'static)
+    where
+        F: FnOnce(OperationHandle) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<JsonValue, Error>> + Send + 'static,
+    {
+        let operation = Self::new(name, owner);
+        let operation_id = operation.id;
+        OperationRegistry::insert(operation);
+
+        let handle = OperationHandle { id: operation_id };
+        let future = async move {
+            handle
+                .update(|op| op.status = OperationStatus::Running)
+                .await;
+            let result = AssertUnwindSafe(f(handle.clone())).catch_unwind().await;
+            handle
+                .update(|op| {
+                    match result {
+                        Ok(Ok(value)) => {
+                            op.status = OperationStatus::Succeeded;
+                            op.progress = 100;
+                            op.result = Some(value);
+                        }
+                        Ok(Err(err)) => {
+                            op.status = OperationStatus::Failed;
+                            op.error = Some(err.to_string());
+                        }
+                        Err(_) => {
+                            op.status = OperationStatus::Failed;
+                            op.error = Some("the operation panicked".to_owned());
+                        }
+                    }
+                    if let Some(err) = op.error.as_deref() {
+                        tracing::error!(
+                            operation_id = op.id.to_string(),
+                            operation_name = op.name.as_str(),
+                            "fail to execute the operation: {err}"
+                        );
+                    }
+                })
+                .await;
+        };
+        (operation_id, future)
+    }
+
+    /// Returns the operation ID.
+    #[inline]
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Returns the operation name.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the principal who owns the operation.
+    #[inline]
+    pub fn owner(&self) -> Option<&str> {
+        self.owner.as_deref()
+    }
+
+    /// Returns `true` if the operation is visible to the principal.
+    /// An operation without the owner is visible to everyone.
+    #[inline]
+    pub fn is_visible_to(&self, principal: Option<&str>) -> bool {
+        self.owner.is_none() || self.owner.as_deref() == principal
+    }
+
+    /// Returns the status.
+    #[inline]
+    pub fn status(&self) -> OperationStatus {
+        self.status
+    }
+
+    /// Returns the progress in percentage.
+    #[inline]
+    pub fn progress(&self) -> u8 {
+        self.progress
+    }
+
+    /// Returns the message describing the current step.
+    #[inline]
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    /// Returns the result of a successful operation.
+    #[inline]
+    pub fn result(&self) -> Option<&JsonValue> {
+        self.result.as_ref()
+    }
+
+    /// Returns the error of a failed operation.
+    #[inline]
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Returns the creation time.
+    #[inline]
+    pub fn created_at(&self) -> DateTime {
+        self.created_at
+    }
+
+    /// Returns the last update time.
+    #[inline]
+    pub fn updated_at(&self) -> DateTime {
+        self.updated_at
+    }
+
+    /// Returns `true` if the operation has succeeded or failed.
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self.status,
+            OperationStatus::Succeeded | OperationStatus::Failed
+        )
+    }
+
+    /// Converts `self` to a json object.
+    pub fn to_map(&self) -> Map {
+        let mut map = Map::new();
+        map.upsert("id", self.id.to_string());
+        map.upsert("name", self.name.as_str());
+        map.upsert("status", self.status.as_str());
+        map.upsert("progress", self.progress);
+        map.upsert("message", self.message.as_deref());
+        map.upsert("result", self.result.clone());
+        map.upsert("error", self.error.as_deref());
+        map.upsert("created_at", self.created_at);
+        map.upsert("updated_at", self.updated_at);
+        map
+    }
+}
+
+/// A handle for the running operation to report its progress.
+#[derive(Debug, Clone)]
+pub struct OperationHandle {
+    /// Operation ID.
+    id: Uuid,
+}
+
+impl OperationHandle {
+    /// Returns the operation ID.
+    #[inline]
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Updates the progress in percentage and the message describing the current step.
+    pub async fn update_progress(&self, progress: u8, message: impl Into<String>) {
+        let message = message.into();
+        self.update(|op| {
+            op.progress = progress.min(100);
+            op.message = Some(message);
+        })
+        .await;
+    }
+
+    /// Updates the operation and saves it into the store.
+    async fn update(&self, f: impl FnOnce(&mut Operation)) {
+        let operation = {
+            let mut state = OPERATION_REGISTRY.lock();
+            let Some(operation) = state.operations.get_mut(&self.id) else {
+                return;
+            };
+            f(operation);
+            operation.updated_at = DateTime::now();
+            operation.clone()
+        };
+        if operation.is_finished() {
+            OperationRegistry::finish(operation.id);
+        }
+        if let Some(store) = OPERATION_STORE.get() {
+            let operation_id = operation.id;
+            if let Err(err) = store.save(operation).await {
+                tracing::error!(
+                    operation_id = operation_id.to_string(),
+                    "fail to save the operation: {err}"
+                );
+            }
+        }
+    }
+}
+
+/// A registry of the long-running operations in the current process.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OperationRegistry;
+
+impl OperationRegistry {
+    /// Sets the store for persisting the operations. It can only be set once.
+    pub fn set_store(store: impl OperationStore + 'static) {
+        if OPERATION_STORE.set(Box::new(store)).is_err() {
+            tracing::warn!("the operation store has already been set");
+        }
+    }
+
+    /// Gets an operation by ID from the registry or the store.
+    pub async fn get(operation_id: Uuid) -> Result<Option<Operation>, Error> {
+        if let Some(operation) = Self::get_local(operation_id) {
+            return Ok(Some(operation));
+        }
+        if let Some(store) = OPERATION_STORE.get() {
+            store.load(operation_id).await
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Gets an operation by ID from the registry in the current process.
+    #[inline]
+    pub fn get_local(operation_id: Uuid) -> Option<Operation> {
+        OPERATION_REGISTRY
+            .lock()
+            .operations
+            .get(&operation_id)
+            .cloned()
+    }
+
+    /// Lists the most recent operations in the current process.
+    pub fn list(limit: usize) -> Vec<Operation> {
+        let state = OPERATION_REGISTRY.lock();
+        let mut operations = state.operations.values().cloned().collect::<Vec<_>>();
+        operations.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        operations.truncate(limit);
+        operations
+    }
+
+    /// Registers an operation.
+    fn insert(operation: Operation) {
+        OPERATION_REGISTRY
+            .lock()
+            .operations
+            .insert(operation.id, operation);
+    }
+
+    /// Marks an operation as finished, and evicts the oldest finished ones.
+    fn finish(operation_id: Uuid) {
+        let mut state = OPERATION_REGISTRY.lock();
+        state.finished.push_back(operation_id);
+        while state.finished.len() > MAX_FINISHED_OPERATIONS {
+            if let Some(operation_id) = state.finished.pop_front() {
+                state.operations.remove(&operation_id);
+            }
+        }
+    }
+}
+
+/// The state of the operation registry.
+#[derive(Default)]
+struct OperationRegistryState {
+    /// The operations.
+    operations: HashMap<Uuid, Operation>,
+    /// The IDs of the finished operations.
+    finished: VecDeque<Uuid>,
+}
+
+/// Maximum number of the finished operations kept in memory.
+const MAX_FINISHED_OPERATIONS: usize = 1000;
+
+/// Shared operation registry.
+static OPERATION_REGISTRY: LazyLock<Mutex<OperationRegistryState>> =
+    LazyLock::new(|| Mutex::new(OperationRegistryState::default()));
+
+/// Shared operation store.
+static OPERATION_STORE: OnceLock<Box<dyn OperationStore>> = OnceLock::new();
@@ -0,0 +1,382 @@
+use crate::{
+    datetime::DateTime,
+    extension::{JsonObjectExt, JsonValueExt},
+    BoxFuture, LazyLock, Map, Uuid,
+};
+use parking_lot::Mutex;
+use std::{
+    any::Any,
+    collections::{HashMap, VecDeque},
+    time::{Duration, Instant},
+};
+
+/// A function pointer of recording the job runs, such as inserting them into a `job_run` model.
+pub type JobRunRecorder = fn(runs: Vec<JobRun>) -> BoxFuture<'static>;
+
+/// Outcome of a job run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum JobOutcome {
+    /// The job finished successfully.
+    Success,
+    /// The job failed with an error or panicked.
+    Failure,
+    /// The job was cancelled because of the timeout.
+    Timeout,
+    /// The job was cancelled because it was replaced by the next run.
+    Cancelled,
+}
+
+impl JobOutcome {
+    /// Returns the outcome as a string.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Success => "Success",
+            Self::Failure => "Failure",
+            Self::Timeout => "Timeout",
+            Self::Cancelled => "Cancelled",
+        }
+    }
+}
+
+/// A record of a job run.
+///
+/// A job can report the output summary or the error by setting the `$output` or `$error`
+/// entry in the job data, which will be removed after the run.
+#[derive(Debug, Clone)]
+pub struct JobRun {
+    /// Run ID.
+    id: Uuid,
+    /// Job ID.
+    job_id: Uuid,
+    /// Job name.
+    job_name: String,
+    /// Start time.
+    started_at: DateTime,
+    /// Duration of the run.
+    duration: Duration,
+    /// Outcome of the run.
+    outcome: JobOutcome,
+    /// Error message.
+    error: Option<String>,
+    /// Output summary.
+    output: Option<String>,
+    /// Flag to indicate whether the run is triggered manually.
+    manual: bool,
+}
+
+impl JobRun {
+    /// Returns the run ID.
+    #[inline]
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Returns the job ID.
+    #[inline]
+    pub fn job_id(&self) -> Uuid {
+        self.job_id
+    }
+
+    /// Returns the job name.
+    #[inline]
+    pub fn job_name(&self) -> &str {
+        &self.job_name
+    }
+
+    /// Returns the start time.
+    #[inline]
+    pub fn started_at(&self) -> DateTime {
+        self.started_at
+    }
+
+    /// Returns the duration of the run.
+    #[inline]
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Returns the outcome of the run.
+    #[inline]
+    pub fn outcome(&self) -> JobOutcome {
+        self.outcome
+    }
+
+    /// Returns the error message.
+    #[inline]
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Returns the output summary.
+    #[inline]
+    pub fn output(&self) -> Option<&str> {
+        self.output.as_deref()
+    }
+
+    /// Returns `true` if the run is triggered manually.
+    #[inline]
+    pub fn is_manual(&self) -> bool {
+        self.manual
+    }
+
+    /// Returns `true` if the run is failed.
+    #[inline]
+    pub fn is_failed(&self) -> bool {
+        self.outcome != JobOutcome::Success
+    }
+
+    /// Converts `self` to a json object.
+    pub fn to_map(&self) -> Map {
+        let mut map = Map::new();
+        map.upsert("id", self.id.to_string());
+        map.upsert("job_id", self.job_id.to_string());
+        map.upsert("job_name", self.job_name.as_str());
+        map.upsert("started_at", self.started_at);
+        map.upsert("duration", self.duration.as_millis() as u64);
+        map.upsert("outcome", self.outcome.as_str());
+        map.upsert("error", self.error.as_deref());
+        map.upsert("output", self.output.as_deref());
+        map.upsert("manual", self.manual);
+        map
+    }
+}
+
+/// A guard for tracking a job run.
+pub(super) struct JobRunGuard {
+    /// The job run.
+    run: JobRun,
+    /// The start instant.
+    start: Instant,
+}
+
+impl JobRunGuard {
+    /// Starts a run of the job.
+    pub(super) fn start(job_id: Uuid, job_name: &str, manual: bool) -> Self {
+        let run = JobRun {
+            id: Uuid::now_v7(),
+            job_id,
+            job_name: job_name.to_owned(),
+            started_at: DateTime::now(),
+            duration: Duration::ZERO,
+            outcome: JobOutcome::Success,
+            error: None,
+            output: None,
+            manual,
+        };
+        Self {
+            run,
+            start: Instant::now(),
+        }
+    }
+
+    /// Finishes the run with the job data and an optional panic payload,
+    /// and records it into the registry.
+    pub(super) fn finish(self, data: &mut Map, panic: Option<Box<dyn Any + Send>>) {
+        let mut error = data
+            .remove("$error")
+            .map(|value| value.to_string_unquoted());
+        if let Some(payload) = panic {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| (*s).to_owned())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "the job panicked".to_owned());
+            error = Some(message);
+        }
+        let outcome = if error.is_some() {
+            JobOutcome::Failure
+        } else {
+            JobOutcome::Success
+        };
+        self.complete(data, outcome, error);
+    }
+
+    /// Interrupts the run with the outcome, and records it into the registry.
+    pub(super) fn interrupt(self, data: &mut Map, outcome: JobOutcome) {
+        data.remove("$error");
+
+        let elapsed = self.start.elapsed();
+        let message = match outcome {
+            JobOutcome::Timeout => format!("the job timed out after {elapsed:?}"),
+            _ => format!("the job was cancelled after {elapsed:?}"),
+        };
+        self.complete(data, outcome, Some(message));
+    }
+
+    /// Completes the run with the outcome and the error.
+    fn complete(self, data: &mut Map, outcome: JobOutcome, error: Option<String>) {
+        let mut run = self.run;
+        run.duration = self.start.elapsed();
+        run.outcome = outcome;
+        run.error = error;
+        run.output = data
+            .remove("$output")
+            .map(|value| value.to_string_unquoted());
+        if let Some(error) = run.error.as_deref() {
+            tracing::error!(
+                job_id = run.job_id.to_string(),
+                job_name = run.job_name.as_str(),
+                outcome = outcome.as_str(),
+                "fail to run the job: {error}"
+            );
+        }
+
+        // Emit metrics.
+        #[cfg(feature = "metrics")]
+        {
+            metrics::counter!(
+                "zino_job_runs_total",
+                "job_name" => run.job_name.clone(),
+                "outcome" => outcome.as_str(),
+            )
+            .increment(1);
+            metrics::histogram!(
+                "zino_job_run_duration_seconds",
+                "job_name" => run.job_name.clone(),
+            )
+            .record(run.duration.as_secs_f64());
+        }
+
+        JobRegistry::record(run);
+    }
+}
+
+/// Commands to manage a job at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum JobCommand {
+    /// Pauses the job.
+    Pause,
+    /// Resumes the job.
+    Resume,
+    /// Triggers a manual run of the job.
+    Trigger,
+}
+
+/// A registry of the scheduled jobs and their runs.
+///
+/// The management commands are applied by the schedulers in the next tick.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JobRegistry;
+
+impl JobRegistry {
+    /// Returns the status of all the jobs.
+    pub fn list_jobs() -> Vec<Map> {
+        let state = JOB_REGISTRY.lock();
+        let mut jobs = state.jobs.values().cloned().collect::<Vec<_>>();
+        jobs.sort_by(|a, b| a.get_str("id").cmp(&b.get_str("id")));
+        jobs
+    }
+
+    /// Returns the status of a job.
+    #[inline]
+    pub fn get_job(job_id: Uuid) -> Option<Map> {
+        JOB_REGISTRY.lock().jobs.get(&job_id).cloned()
+    }
+
+    /// Returns the most recent runs of all the jobs or a specific job.
+    pub fn list_runs(job_id: Option<Uuid>, limit: usize) -> Vec<JobRun> {
+        JOB_REGISTRY
+            .lock()
+            .runs
+            .iter()
+            .rev()
+            .filter(|run| job_id.is_none() || job_id == Some(run.job_id))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the last failed run of the job.
+    pub fn last_failure(job_id: Uuid) -> Option<JobRun> {
+        JOB_REGISTRY
+            .lock()
+            .runs
+            .iter()
+            .rev()
+            .find(|run| run.job_id == job_id && run.is_failed())
+            .cloned()
+    }
+
+    /// Sends a command to the job. Returns `false` if the job does not exist.
+    pub fn send_command(job_id: Uuid, command: JobCommand) -> bool {
+        let mut state = JOB_REGISTRY.lock();
+        if state.jobs.contains_key(&job_id) {
+            state.commands.push((job_id, command));
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Registers or updates the status of a job.
+    pub(super) fn update_job(job_id: Uuid, status: Map) {
+        JOB_REGISTRY.lock().jobs.insert(job_id, status);
+    }
+
+    /// Unregisters a job.
+    pub(super) fn remove_job(job_id: Uuid) {
+        JOB_REGISTRY.lock().jobs.remove(&job_id);
+    }
+
+    /// Takes the commands for the jobs satisfying the predicate.
+    pub(super) fn take_commands(predicate: impl Fn(Uuid) -> bool) -> Vec<(Uuid, JobCommand)> {
+        let mut state = JOB_REGISTRY.lock();
+        if state.commands.is_empty() {
+            return Vec::new();
+        }
+
+        let (commands, remaining) = state
+            .commands
+            .drain(..)
+            .partition(|(job_id, _)| predicate(*job_id));
+        state.commands = remaining;
+        commands
+    }
+
+    /// Enables the pending runs to be taken by a recorder.
+    pub(super) fn enable_recording() {
+        JOB_REGISTRY.lock().recording = true;
+    }
+
+    /// Takes the pending runs which have not been recorded.
+    pub(super) fn take_pending_runs() -> Vec<JobRun> {
+        std::mem::take(&mut JOB_REGISTRY.lock().pending_runs)
+    }
+
+    /// Records a job run.
+    fn record(run: JobRun) {
+        let mut state = JOB_REGISTRY.lock();
+        if state.recording {
+            state.pending_runs.push(run.clone());
+        }
+        if state.runs.len() >= MAX_RUNS {
+            state.runs.pop_front();
+        }
+        state.runs.push_back(run);
+    }
+}
+
+/// The state of the job registry.
+#[derive(Default)]
+struct JobRegistryState {
+    /// The status of jobs.
+    jobs: HashMap<Uuid, Map>,
+    /// The most recent runs.
+    runs: VecDeque<JobRun>,
+    /// The runs which have not been recorded.
+    pending_runs: Vec<JobRun>,
+    /// The pending commands.
+    commands: Vec<(Uuid, JobCommand)>,
+    /// Flag to indicate whether the runs should be recorded.
+    recording: bool,
+}
+
+/// Maximum number of the runs kept in memory.
+const MAX_RUNS: usize = 1000;
+
+/// Shared job registry.
+static JOB_REGISTRY: LazyLock<Mutex<JobRegistryState>> =
+    LazyLock::new(|| Mutex::new(JobRegistryState::default()));
@@ -0,0 +1,295 @@
+use super::State;
+use crate::{request::RequestContext, LazyLock};
+use parking_lot::{Mutex, RwLock};
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt,
+    ops::Deref,
+    sync::Arc,
+};
+
+/// A lightweight dependency injection container for the services.
+///
+/// The constructors are registered with a [`ServiceLifetime`] at startup,
+/// and the services can be resolved by type in the controllers or model hooks.
+/// A constructor can resolve its own dependencies from the container.
+/// The services provided by [`State::provide()`] can also be resolved.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino_core::state::{Container, ServiceLifetime};
+///
+/// let container = Container::shared();
+/// container.register(ServiceLifetime::Singleton, Mailer::new);
+/// container.register(ServiceLifetime::Request, || {
+///     let mailer = Container::shared()
+///         .resolve::<Mailer>()
+///         .expect("the mailer should be registered");
+///     InvitationService::new(mailer)
+/// });
+///
+/// // In a handler, the service is shared during the request.
+/// let service = container.resolve_in::<InvitationService, _>(&mut req);
+///
+/// // In tests, the service can be overridden.
+/// container.override_with(Mailer::mock());
+/// ```
+#[derive(Default)]
+pub struct Container {
+    /// Registered services.
+    registrations: RwLock<HashMap<TypeId, Registration>>,
+    /// Overridden services.
+    overrides: RwLock<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
+    /// Constructed singleton services.
+    singletons: RwLock<HashMap<TypeId, &'static (dyn Any + Send + Sync)>>,
+    /// A lock to ensure that the singleton services are constructed only once.
+    singleton_lock: Mutex<()>,
+}
+
+impl Container {
+    /// Creates a new instance without any services.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a constructor of the service `T` with the lifetime.
+    /// The previous registration of the same type will be replaced.
+    pub fn register<T, F>(&self, lifetime: ServiceLifetime, constructor: F)
+    where
+        T: Send + Sync + 'static,
+        F: Fn() -> T + Send + Sync + 'static,
+    {
+        let constructor: Constructor =
+            Arc::new(move || Box::new(constructor()) as Box<dyn Any + Send + Sync>);
+        let registration = Registration {
+            lifetime,
+            constructor,
+        };
+        self.registrations
+            .write()
+            .insert(TypeId::of::<T>(), registration);
+    }
+
+    /// Returns `true` if the service `T` has been registered or provided.
+    #[inline]
+    pub fn contains<T: Send + Sync + 'static>(&self) -> bool {
+        let type_id = TypeId::of::<T>();
+        self.overrides.read().contains_key(&type_id)
+            || self.registrations.read().contains_key(&type_id)
+            || State::inject::<T>().is_some()
+    }
+
+    /// Resolves the service `T` outside of a request.
+    /// A new instance is constructed each time for the [`ServiceLifetime::Request`].
+    pub fn resolve<T: Send + Sync + 'static>(&self) -> Option<Service<T>> {
+        if let Some(service) = self.resolve_override() {
+            return Some(service);
+        }
+        if let Some(value) = self.get_singleton::<T>().or_else(State::inject::<T>) {
+            return Some(Service::Static(value));
+        }
+
+        let (lifetime, constructor) = self.get_registration::<T>()?;
+        match lifetime {
+            ServiceLifetime::Singleton => {
+                // The dependencies are resolved before holding the lock.
+                let value = *constructor().downcast::<T>().ok()?;
+                let _guard = self.singleton_lock.lock();
+                if let Some(value) = self.get_singleton::<T>() {
+                    return Some(Service::Static(value));
+                }
+
+                let value: &'static T = Box::leak(Box::new(value));
+                self.singletons.write().insert(TypeId::of::<T>(), value);
+                Some(Service::Static(value))
+            }
+            ServiceLifetime::Request => {
+                let value = *constructor().downcast::<T>().ok()?;
+                Some(Service::Shared(Arc::new(value)))
+            }
+        }
+    }
+
+    /// Resolves the service `T` in the request.
+    /// The instance is shared during the request for the [`ServiceLifetime::Request`].
+    pub fn resolve_in<T, Ctx>(&self, ctx: &mut Ctx) -> Option<Service<T>>
+    where
+        T: Send + Sync + 'static,
+        Ctx: RequestContext,
+    {
+        if let Some(service) = self.resolve_override() {
+            return Some(service);
+        }
+        if let Some(ScopedService(value)) = ctx.get_data::<ScopedService<T>>() {
+            return Some(Service::Shared(value));
+        }
+
+        let service = self.resolve::<T>()?;
+        if let Service::Shared(value) = &service {
+            ctx.set_data(ScopedService(value.clone()));
+        }
+        Some(service)
+    }
+
+    /// Overrides the service `T` with an instance, which takes precedence over
+    /// the registered constructor. It is useful for the tests.
+    #[inline]
+    pub fn override_with<T: Send + Sync + 'static>(&self, value: T) {
+        self.overrides
+            .write()
+            .insert(TypeId::of::<T>(), Arc::new(value));
+    }
+
+    /// Removes all the overridden services.
+    #[inline]
+    pub fn clear_overrides(&self) {
+        self.overrides.write().clear();
+    }
+
+    /// Returns the shared container.
+    #[inline]
+    pub fn shared() -> &'static Self {
+        &SHARED_CONTAINER
+    }
+
+    /// Resolves the overridden service `T`.
+    fn resolve_override<T: Send + Sync + 'static>(&self) -> Option<Service<T>> {
+        let value = self.overrides.read().get(&TypeId::of::<T>()).cloned()?;
+        value.downcast::<T>().ok().map(Service::Shared)
+    }
+
+    /// Gets the constructed singleton service `T`.
+    fn get_singleton<T: Send + Sync + 'static>(&self) -> Option<&'static T> {
+        let value = self.singletons.read().get(&TypeId::of::<T>()).copied()?;
+        value.downcast_ref()
+    }
+
+    /// Gets the lifetime and constructor of the service `T`.
+    fn get_registration<T: Send + Sync + 'static>(&self) -> Option<(ServiceLifetime, Constructor)> {
+        self.registrations
+            .read()
+            .get(&TypeId::of::<T>())
+            .map(|registration| (registration.lifetime, registration.constructor.clone()))
+    }
+}
+
+impl fmt::Debug for Container {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Container")
+            .field("registrations", &self.registrations.read().len())
+            .field("overrides", &self.overrides.read().len())
+            .field("singletons", &self.singletons.read().len())
+            .finish()
+    }
+}
+
+/// Lifetime of a service registered in the [`Container`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ServiceLifetime {
+    /// The service is constructed once and shared by the application.
+    #[default]
+    Singleton,
+    /// The service is constructed once for each request.
+    Request,
+}
+
+/// A service resolved from the [`Container`].
+pub enum Service<T: 'static> {
+    /// A service living as long as the application.
+    Static(&'static T),
+    /// A service shared by reference counting.
+    Shared(Arc<T>),
+}
+
+impl<T: 'static> Clone for Service<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        match self {
+            Self::Static(value) => Self::Static(*value),
+            Self::Shared(value) => Self::Shared(value.clone()),
+        }
+    }
+}
+
+impl<T: 'static> Deref for Service<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Static(value) => value,
+            Self::Shared(value) => value,
+        }
+    }
+}
+
+impl<T: fmt::Debug + 'static> fmt::Debug for Service<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.deref().fmt(f)
+    }
+}
+
+/// A service shared during a request.
+struct ScopedService<T>(Arc<T>);
+
+impl<T> Clone for ScopedService<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+/// A type-erased constructor.
+type Constructor = Arc<dyn Fn() -> Box<dyn Any + Send + Sync> + Send + Sync>;
+
+/// Registration of a service.
+struct Registration {
+    /// Lifetime.
+    lifetime: ServiceLifetime,
+    /// Constructor.
+    constructor: Constructor,
+}
+
+/// Shared container.
+static SHARED_CONTAINER: LazyLock<Container> = LazyLock::new(Container::new);
+
+#[cfg(test)]
+mod tests {
+    use super::{Container, ServiceLifetime};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn it_resolves_services() {
+        struct Mailer(&'static str);
+        struct Notifier(&'static str);
+
+        let container: &'static Container = Box::leak(Box::default());
+        let num_instances: &'static AtomicUsize = Box::leak(Box::default());
+        container.register(ServiceLifetime::Singleton, move || {
+            num_instances.fetch_add(1, Ordering::Relaxed);
+            Mailer("smtp")
+        });
+        container.register(ServiceLifetime::Request, move || {
+            let mailer = container.resolve::<Mailer>().unwrap();
+            Notifier(mailer.0)
+        });
+        assert_eq!(container.resolve::<Notifier>().unwrap().0, "smtp");
+        assert_eq!(container.resolve::<Mailer>().unwrap().0, "smtp");
+        assert_eq!(num_instances.load(Ordering::Relaxed), 1);
+
+        container.override_with(Mailer("mock"));
+        assert_eq!(container.resolve::<Mailer>().unwrap().0, "mock");
+        assert_eq!(container.resolve::<Notifier>().unwrap().0, "mock");
+
+        container.clear_overrides();
+        assert_eq!(container.resolve::<Mailer>().unwrap().0, "smtp");
+        assert_eq!(container.resolve::<Notifier>().unwrap().0, "smtp");
+        assert_eq!(num_instances.load(Ordering::Relaxed), 1);
+        assert!(!container.contains::<String>());
+        assert!(!Container::shared().contains::<Mailer>());
+    }
+}
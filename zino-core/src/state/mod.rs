@@ -55,6 +55,20 @@
 //!     Mutex::new(connection)
 //! });
 //! ```
+//!
+//! Instead of `static` singletons, typed services can also be provided at startup
+//! and injected by type in the handlers or model hooks.
+//!
+//! ```rust,ignore
+//! use zino_core::state::State;
+//!
+//! zino::Cluster::boot()
+//!     .with_state(Mailer::new())
+//!     .register(router::routes())
+//!     .run();
+//!
+//! let mailer = State::inject::<Mailer>().expect("the mailer should be provided");
+//! ```
 
 use crate::{
     application::{self, ServerTag},
@@ -63,16 +77,21 @@ use crate::{
     extension::TomlTableExt,
     helper, LazyLock,
 };
+use parking_lot::RwLock;
 use std::{
+    any::{Any, TypeId},
     borrow::Cow,
+    collections::HashMap,
     net::{IpAddr, Ipv4Addr, SocketAddr},
 };
 use toml::value::Table;
 
 mod config;
+mod container;
 mod data;
 mod env;
 
+pub use container::{Container, Service, ServiceLifetime};
 pub use data::{Data, SharedData};
 pub use env::Env;
 
@@ -235,6 +254,22 @@ impl State {
         LazyLock::force(&SHARED_STATE)
     }
 
+    /// Provides a typed state container which lives as long as the application.
+    /// It should be called at startup, and the previous value of the same type
+    /// will be shadowed.
+    pub fn provide<T: Send + Sync + 'static>(value: T) -> &'static T {
+        let value: &'static T = Box::leak(Box::new(value));
+        TYPED_STATES.write().insert(TypeId::of::<T>(), value);
+        value
+    }
+
+    /// Injects a typed state container which has been provided.
+    #[inline]
+    pub fn inject<T: Send + Sync + 'static>() -> Option<&'static T> {
+        let value = TYPED_STATES.read().get(&TypeId::of::<T>()).copied()?;
+        value.downcast_ref()
+    }
+
     /// Encrypts the password in the config.
     pub fn encrypt_password(config: &Table) -> Option<Cow<'_, str>> {
         let password = config.get_str("password")?;
@@ -330,3 +365,7 @@ static SHARED_STATE: LazyLock<State> = LazyLock::new(|| {
     state.load_config();
     state
 });
+
+/// Typed state containers.
+static TYPED_STATES: LazyLock<RwLock<HashMap<TypeId, &'static (dyn Any + Send + Sync)>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
@@ -3,8 +3,13 @@ use crate::{error::Error, extension::JsonObjectExt, Map, SharedString};
 use smallvec::SmallVec;
 use std::fmt;
 
+mod rule;
+mod sanitizer;
 mod validator;
 
+pub use rule::FieldRule;
+pub use sanitizer::{escape_html, sanitize, sanitize_field};
+
 pub use validator::{
     AlphabeticValidator, AlphanumericValidator, AsciiAlphabeticValidator,
     AsciiAlphanumericValidator, AsciiDigitValidator, AsciiHexdigitValidator,
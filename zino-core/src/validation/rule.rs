@@ -0,0 +1,247 @@
+use super::Validation;
+use crate::{extension::JsonValueExt, JsonValue, Map};
+use std::cmp::Ordering;
+
+/// A builder for the validation rules of a field, created by [`Validation::field()`].
+///
+/// The key can be a nested path separated by `.`, such as `address.city` or `items.0.name`.
+/// A field is considered to be absent if the value is null, an empty string,
+/// an empty array or an empty object.
+///
+/// # Examples
+///
+/// ```rust
+/// use zino_core::{json, validation::Validation};
+///
+/// let data = json!({
+///     "type": "company",
+///     "start_date": "2024-05-01",
+///     "end_date": "2024-04-01",
+///     "address": { "city": "" },
+/// });
+/// let data = data.as_object().unwrap();
+///
+/// let mut validation = Validation::new();
+/// validation.field(data, "tax_id").required_if("type", "company");
+/// validation.field(data, "end_date").greater_than_field("start_date");
+/// validation.field(data, "address.city").required();
+/// validation.mutually_exclusive(data, &["email", "phone"]);
+/// assert_eq!(validation.invalid_params(), ["tax_id", "end_date", "address.city"]);
+/// ```
+pub struct FieldRule<'a> {
+    /// Validation.
+    validation: &'a mut Validation,
+    /// Data to be validated.
+    data: &'a Map,
+    /// Field key.
+    key: &'a str,
+}
+
+impl<'a> FieldRule<'a> {
+    /// Creates a new instance.
+    #[inline]
+    pub(super) fn new(validation: &'a mut Validation, data: &'a Map, key: &'a str) -> Self {
+        Self {
+            validation,
+            data,
+            key,
+        }
+    }
+
+    /// Requires the field to be present.
+    pub fn required(self) -> Self {
+        if lookup(self.data, self.key).is_none() {
+            self.validation
+                .record(self.key.to_owned(), "it is required");
+        }
+        self
+    }
+
+    /// Requires the field to be present if the other field equals to the value.
+    pub fn required_if(self, other_key: &str, other_value: impl Into<JsonValue>) -> Self {
+        let other_value = other_value.into();
+        if lookup(self.data, other_key) == Some(&other_value)
+            && lookup(self.data, self.key).is_none()
+        {
+            let message = format!("it is required when `{other_key}` is {other_value}");
+            self.validation.record(self.key.to_owned(), message);
+        }
+        self
+    }
+
+    /// Requires the field to be present if the other field is present.
+    pub fn required_with(self, other_key: &str) -> Self {
+        if lookup(self.data, other_key).is_some() && lookup(self.data, self.key).is_none() {
+            let message = format!("it is required when `{other_key}` is present");
+            self.validation.record(self.key.to_owned(), message);
+        }
+        self
+    }
+
+    /// Requires the field to be absent if the other field equals to the value.
+    pub fn forbidden_if(self, other_key: &str, other_value: impl Into<JsonValue>) -> Self {
+        let other_value = other_value.into();
+        if lookup(self.data, other_key) == Some(&other_value)
+            && lookup(self.data, self.key).is_some()
+        {
+            let message = format!("it is not allowed when `{other_key}` is {other_value}");
+            self.validation.record(self.key.to_owned(), message);
+        }
+        self
+    }
+
+    /// Requires the field to be greater than the other field if both of them are present.
+    pub fn greater_than_field(self, other_key: &str) -> Self {
+        self.compare_with(other_key, "greater than", |ordering| ordering.is_gt())
+    }
+
+    /// Requires the field to be greater than or equal to the other field
+    /// if both of them are present.
+    pub fn greater_equal_field(self, other_key: &str) -> Self {
+        self.compare_with(other_key, "greater than or equal to", |ordering| {
+            ordering.is_ge()
+        })
+    }
+
+    /// Requires the field to be less than the other field if both of them are present.
+    pub fn less_than_field(self, other_key: &str) -> Self {
+        self.compare_with(other_key, "less than", |ordering| ordering.is_lt())
+    }
+
+    /// Requires the field to be less than or equal to the other field
+    /// if both of them are present.
+    pub fn less_equal_field(self, other_key: &str) -> Self {
+        self.compare_with(other_key, "less than or equal to", |ordering| {
+            ordering.is_le()
+        })
+    }
+
+    /// Requires the field to be equal to the other field.
+    pub fn equal_to_field(self, other_key: &str) -> Self {
+        if lookup(self.data, self.key) != lookup(self.data, other_key) {
+            let message = format!("it should be equal to `{other_key}`");
+            self.validation.record(self.key.to_owned(), message);
+        }
+        self
+    }
+
+    /// Compares the field with the other field. Numbers are compared by values,
+    /// and strings are compared lexicographically, which is suitable for RFC 3339 datetimes.
+    fn compare_with(
+        self,
+        other_key: &str,
+        relation: &str,
+        predicate: impl FnOnce(Ordering) -> bool,
+    ) -> Self {
+        let (Some(value), Some(other_value)) =
+            (lookup(self.data, self.key), lookup(self.data, other_key))
+        else {
+            return self;
+        };
+        let ordering = match (value, other_value) {
+            (JsonValue::Number(a), JsonValue::Number(b)) => a
+                .as_f64()
+                .zip(b.as_f64())
+                .and_then(|(a, b)| a.partial_cmp(&b)),
+            (JsonValue::String(a), JsonValue::String(b)) => Some(a.cmp(b)),
+            _ => None,
+        };
+        if !ordering.is_some_and(predicate) {
+            let message = format!("it should be {relation} `{other_key}`");
+            self.validation.record(self.key.to_owned(), message);
+        }
+        self
+    }
+}
+
+impl Validation {
+    /// Creates a builder for the validation rules of a field in the data.
+    #[inline]
+    pub fn field<'a>(&'a mut self, data: &'a Map, key: &'a str) -> FieldRule<'a> {
+        FieldRule::new(self, data, key)
+    }
+
+    /// Requires at most one of the fields to be present.
+    pub fn mutually_exclusive(&mut self, data: &Map, keys: &[&str]) {
+        let present_keys = keys
+            .iter()
+            .filter(|key| lookup(data, key).is_some())
+            .collect::<Vec<_>>();
+        if present_keys.len() > 1 {
+            for key in present_keys {
+                let others = keys
+                    .iter()
+                    .filter(|k| *k != key)
+                    .map(|k| format!("`{k}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let message = format!("it can not be used together with {others}");
+                self.record(key.to_string(), message);
+            }
+        }
+    }
+
+    /// Requires at least one of the fields to be present.
+    pub fn required_one_of(&mut self, data: &Map, keys: &[&str]) {
+        if !keys.iter().any(|key| lookup(data, key).is_some()) {
+            let fields = keys
+                .iter()
+                .map(|k| format!("`{k}`"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let message = format!("one of the fields {fields} is required");
+            self.record(keys.join(","), message);
+        }
+    }
+}
+
+/// Looks up a present value by the nested path separated by `.`.
+fn lookup<'a>(data: &'a Map, key: &str) -> Option<&'a JsonValue> {
+    let mut segments = key.split('.');
+    let mut value = data.get(segments.next()?)?;
+    for segment in segments {
+        value = match value {
+            JsonValue::Object(map) => map.get(segment)?,
+            JsonValue::Array(vec) => vec.get(segment.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    (!value.is_ignorable()).then_some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{json, validation::Validation};
+
+    #[test]
+    fn it_validates_cross_field_rules() {
+        let data = json!({
+            "type": "company",
+            "tax_id": "",
+            "start_date": "2024-05-01",
+            "end_date": "2024-06-01",
+            "min": 10,
+            "max": 5,
+            "email": "alice@example.com",
+            "phone": "555-0100",
+            "items": [{ "name": "apple" }, { "name": null }],
+        });
+        let data = data.as_object().unwrap();
+
+        let mut validation = Validation::new();
+        validation
+            .field(data, "tax_id")
+            .required_if("type", "company");
+        validation
+            .field(data, "end_date")
+            .greater_than_field("start_date");
+        validation.field(data, "max").greater_equal_field("min");
+        validation.field(data, "items.0.name").required();
+        validation.field(data, "items.1.name").required();
+        validation.mutually_exclusive(data, &["email", "phone"]);
+        assert_eq!(
+            validation.invalid_params(),
+            ["tax_id", "max", "items.1.name", "email", "phone"]
+        );
+    }
+}
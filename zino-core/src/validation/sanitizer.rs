@@ -0,0 +1,110 @@
+use crate::{JsonValue, LazyLock, Map};
+use regex::Regex;
+use unicode_normalization::UnicodeNormalization;
+
+/// Sanitizes the string value with the rules separated by `,`.
+///
+/// Supported rules: `trim` | `lowercase` | `uppercase` | `nfc` | `nfkc`
+/// | `strip_html` | `escape_html` | `email` | `phone_number`.
+/// The rules are applied in order, and the unsupported ones are ignored with a warning.
+pub fn sanitize(value: &str, rules: &str) -> String {
+    let mut value = value.to_owned();
+    for rule in rules.split(',').map(|s| s.trim()) {
+        value = match rule {
+            "trim" => value.trim().to_owned(),
+            "lowercase" => value.to_lowercase(),
+            "uppercase" => value.to_uppercase(),
+            "nfc" => value.nfc().collect(),
+            "nfkc" => value.nfkc().collect(),
+            "strip_html" => HTML_TAG_PATTERN.replace_all(&value, "").into_owned(),
+            "escape_html" => escape_html(&value),
+            "email" => canonicalize_email(&value),
+            "phone_number" => canonicalize_phone_number(&value),
+            "" => continue,
+            _ => {
+                tracing::warn!("unsupported sanitization rule `{rule}`");
+                continue;
+            }
+        };
+    }
+    value
+}
+
+/// Sanitizes the string values of a field in the map,
+/// including the string elements of an array.
+pub fn sanitize_field(data: &mut Map, key: &str, rules: &str) {
+    match data.get_mut(key) {
+        Some(JsonValue::String(value)) => {
+            *value = sanitize(value, rules);
+        }
+        Some(JsonValue::Array(values)) => {
+            for value in values.iter_mut() {
+                if let JsonValue::String(value) = value {
+                    *value = sanitize(value, rules);
+                }
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Escapes the HTML special characters.
+pub fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#x27;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Trims the email address and converts the domain part to lowercase.
+fn canonicalize_email(value: &str) -> String {
+    let value = value.trim();
+    if let Some((local_part, domain)) = value.rsplit_once('@') {
+        format!("{local_part}@{}", domain.to_lowercase())
+    } else {
+        value.to_owned()
+    }
+}
+
+/// Removes the separators in a phone number and keeps the leading `+`.
+fn canonicalize_phone_number(value: &str) -> String {
+    let value = value.trim();
+    let digits = value.chars().filter(|c| c.is_ascii_digit());
+    if value.starts_with('+') {
+        std::iter::once('+').chain(digits).collect()
+    } else {
+        digits.collect()
+    }
+}
+
+/// Pattern for the HTML tags.
+static HTML_TAG_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<[^>]*>").expect("fail to create a regex for HTML tags"));
+
+#[cfg(test)]
+mod tests {
+    use super::sanitize;
+
+    #[test]
+    fn it_sanitizes_strings() {
+        assert_eq!(sanitize("  Alice  ", "trim"), "Alice");
+        assert_eq!(
+            sanitize("<b>Tom & Jerry</b>", "strip_html,escape_html"),
+            "Tom &amp; Jerry"
+        );
+        assert_eq!(sanitize(" Bob@Example.COM ", "email"), "Bob@example.com");
+        assert_eq!(
+            sanitize("+1 (555) 010-0100", "phone_number"),
+            "+15550100100"
+        );
+        assert_eq!(sanitize("e\u{301}", "nfc"), "\u{e9}");
+    }
+}
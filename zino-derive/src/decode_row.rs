@@ -18,19 +18,35 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
         if let Some(ident) = field.ident {
             let name = ident.to_string();
             let mut ignore = false;
+            let mut currency = None;
+            let mut model_enum = false;
             'inner: for attr in field.attrs.iter() {
                 let arguments = parser::parse_schema_attr(attr);
-                for (key, _value) in arguments.iter() {
+                for (key, value) in arguments.into_iter() {
                     if key == "ignore" || key == "write_only" {
                         ignore = true;
                         break 'inner;
+                    } else if key == "currency" {
+                        currency = value;
+                    } else if key == "model_enum" {
+                        model_enum = true;
                     }
                 }
             }
             if ignore {
                 continue;
             }
-            if type_name == "Uuid" {
+            if model_enum {
+                if parser::check_option_type(&type_name) {
+                    decode_model_fields.push(quote! {
+                        model.#ident = orm::decode_optional_enum(row, #name)?;
+                    });
+                } else {
+                    decode_model_fields.push(quote! {
+                        model.#ident = orm::decode_enum(row, #name)?;
+                    });
+                }
+            } else if type_name == "Uuid" {
                 decode_model_fields.push(quote! {
                     model.#ident = orm::decode_uuid(row, #name)?;
                 });
@@ -42,6 +58,23 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                 decode_model_fields.push(quote! {
                     model.#ident = orm::decode_decimal(row, #name)?;
                 });
+            } else if type_name == "Money" {
+                let currency = if let Some(code) = currency {
+                    quote! { Some(#code) }
+                } else {
+                    quote! { None }
+                };
+                decode_model_fields.push(quote! {
+                    model.#ident = orm::decode_money(row, #name, #currency)?;
+                });
+            } else if type_name == "Duration" {
+                decode_model_fields.push(quote! {
+                    model.#ident = orm::decode_duration(row, #name)?;
+                });
+            } else if type_name.starts_with("Range<") {
+                decode_model_fields.push(quote! {
+                    model.#ident = orm::decode_range(row, #name)?;
+                });
             } else if type_name == "Map" {
                 decode_model_fields.push(quote! {
                     if let JsonValue::Object(map) = orm::decode(row, #name)? {
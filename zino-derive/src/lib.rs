@@ -9,9 +9,12 @@ use syn::{parse_macro_input, DeriveInput};
 mod decode_row;
 mod model;
 mod model_accessor;
+mod model_enum;
 mod model_hooks;
 mod parser;
+mod query_params;
 mod schema;
+mod state_machine;
 
 #[doc = include_str!("../docs/schema.md")]
 #[proc_macro_derive(Schema, attributes(schema))]
@@ -52,3 +55,27 @@ pub fn derive_model(item: TokenStream) -> TokenStream {
     let output = model::parse_token_stream(input);
     TokenStream::from(output)
 }
+
+#[doc = include_str!("../docs/model_enum.md")]
+#[proc_macro_derive(ModelEnum, attributes(schema))]
+pub fn derive_model_enum(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let output = model_enum::parse_token_stream(input);
+    TokenStream::from(output)
+}
+
+#[doc = include_str!("../docs/query_params.md")]
+#[proc_macro_derive(QueryParams, attributes(schema))]
+pub fn derive_query_params(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let output = query_params::parse_token_stream(input);
+    TokenStream::from(output)
+}
+
+#[doc = include_str!("../docs/state_machine.md")]
+#[proc_macro_derive(StateMachine, attributes(schema))]
+pub fn derive_state_machine(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let output = state_machine::parse_token_stream(input);
+    TokenStream::from(output)
+}
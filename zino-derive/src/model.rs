@@ -25,6 +25,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
     // Parsing struct attributes
     let mut item_name = "entry".to_owned();
     let mut item_name_plural = "entries".to_owned();
+    let mut primary_key_strategy = None;
     for attr in input.attrs.iter() {
         for (key, value) in parser::parse_schema_attr(attr).into_iter() {
             if let Some(value) = value {
@@ -38,6 +39,9 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                     "item_name_plural" => {
                         item_name_plural = value;
                     }
+                    "primary_key_strategy" => {
+                        primary_key_strategy = Some(value);
+                    }
                     _ => (),
                 }
             }
@@ -47,16 +51,25 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
     // Parsing field attributes
     let mut field_constructors = Vec::new();
     let mut field_setters = Vec::new();
+    let mut field_sanitizers = Vec::new();
+    let mut primary_key_ident = None;
     for field in parser::parse_struct_fields(input.data) {
         let type_name = parser::get_type_name(&field.ty);
         if let Some(ident) = field.ident {
             let name = ident.to_string();
             let mut enable_setter = true;
             let mut is_inherent = false;
+            let mut model_enum = false;
+            if name == "id" && primary_key_ident.is_none() {
+                primary_key_ident = Some(ident.clone());
+            }
             for attr in field.attrs.iter() {
                 let arguments = parser::parse_schema_attr(attr);
                 for (key, value) in arguments.into_iter() {
                     match key.as_str() {
+                        "primary_key" => {
+                            primary_key_ident = Some(ident.clone());
+                        }
                         "constructor" => {
                             if let Some(value) = value {
                                 if let Some((cons_name, cons_fn)) = value.split_once("::") {
@@ -196,18 +209,56 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                 }
                             }
                         }
+                        "sanitize" => {
+                            if let Some(rules) = value {
+                                field_sanitizers.push(quote! {
+                                    zino_core::validation::sanitize_field(data, #name, #rules);
+                                });
+                            }
+                        }
                         "ignore" | "read_only" | "generated" | "reserved" => {
                             enable_setter = false;
                         }
                         "inherent" => {
                             is_inherent = true;
                         }
+                        "model_enum" => {
+                            model_enum = true;
+                        }
                         _ => (),
                     }
                 }
             }
             if enable_setter && !RESERVED_FIELDS.contains(&name.as_str()) {
-                let setter = if type_name == "String" {
+                let setter = if model_enum {
+                    if let Some(enum_type) = parser::parse_option_type(&type_name) {
+                        let enum_type_ident = format_ident!("{}", enum_type);
+                        quote! {
+                            if let Some(value) = data.get(#name) {
+                                if value.is_null() {
+                                    self.#ident = None;
+                                } else {
+                                    use zino_core::model::ModelEnum;
+                                    match <#enum_type_ident>::parse_value(value) {
+                                        Ok(value) => self.#ident = Some(value),
+                                        Err(err) => validation.record_fail(#name, err),
+                                    }
+                                }
+                            }
+                        }
+                    } else {
+                        let enum_type_ident = format_ident!("{}", type_name);
+                        quote! {
+                            if let Some(value) = data.get(#name) {
+                                use zino_core::model::ModelEnum;
+                                match <#enum_type_ident>::parse_value(value) {
+                                    Ok(value) => self.#ident = value,
+                                    Err(err) => validation.record_fail(#name, err),
+                                }
+                            }
+                        }
+                    }
+                } else if type_name == "String" {
                     if is_inherent {
                         let parser_ident = format_ident!("parse_{}", name.to_case(Case::Snake));
                         quote! {
@@ -302,6 +353,24 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
         }
     }
 
+    if let Some(ident) = primary_key_ident {
+        let constructor = match primary_key_strategy.as_deref() {
+            Some("uuid_v4") => Some(quote! {
+                model.#ident = zino_core::Uuid::new_v4();
+            }),
+            Some("uuid_v7") => Some(quote! {
+                model.#ident = zino_core::Uuid::now_v7();
+            }),
+            Some("snowflake") => Some(quote! {
+                model.#ident = zino_core::model::Snowflake::shared().next_id() as _;
+            }),
+            _ => None,
+        };
+        if let Some(constructor) = constructor {
+            field_constructors.push(constructor);
+        }
+    }
+
     let model_name_snake = model_name.to_case(Case::Snake);
     let model_constructor = if field_constructors.is_empty() {
         quote! { Self::default() }
@@ -312,6 +381,15 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
             model
         }
     };
+    let model_sanitizer = if field_sanitizers.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            fn sanitize(data: &mut Map) {
+                #(#field_sanitizers)*
+            }
+        }
+    };
     quote! {
         use zino_core::validation::Validation;
 
@@ -324,6 +402,8 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                 #model_constructor
             }
 
+            #model_sanitizer
+
             #[must_use]
             fn read_map(&mut self, data: &Map) -> Validation {
                 let mut validation = Validation::new();
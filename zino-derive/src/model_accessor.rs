@@ -1,5 +1,5 @@
 use super::parser;
-use proc_macro2::TokenStream;
+use proc_macro2::{Ident, TokenStream};
 use quote::{format_ident, quote};
 use std::collections::HashMap;
 use syn::DeriveInput;
@@ -11,9 +11,17 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
 
     // Parsing struct attributes
     let mut composite_constraints = Vec::new();
+    let mut status_transitions = Vec::new();
     for attr in input.attrs.iter() {
         for (key, value) in parser::parse_schema_attr(attr).into_iter() {
-            if key == "unique_on" {
+            if key == "transition" {
+                if let Some(value) = value {
+                    let (_, transitions) = parser::parse_status_transitions(&value);
+                    for (from, to) in transitions {
+                        status_transitions.push(quote! { (#from, #to) });
+                    }
+                }
+            } else if key == "unique_on" {
                 if let Some(value) = value {
                     let mut fields = Vec::new();
                     let column_values = value
@@ -46,10 +54,13 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
     let mut snapshot_fields = Vec::new();
     let mut snapshot_entries = Vec::new();
     let mut field_constraints = Vec::new();
+    let mut filterable_fields = Vec::new();
     let mut ignored_list_fields = Vec::new();
     let mut list_query_methods = Vec::new();
     let mut fetched_queries = Vec::new();
     let mut fetched_one_queries = Vec::new();
+    let mut included_queries = Vec::new();
+    let mut included_one_queries = Vec::new();
     let mut sample_queries = Vec::new();
     let mut soft_delete_updates = Vec::new();
     let mut lock_updates = Vec::new();
@@ -63,6 +74,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
         if let Some(ident) = field.ident {
             let name = ident.to_string();
             let mut field_alias = None;
+            let mut model_enum = false;
             for attr in field.attrs.iter() {
                 let type_name = type_name.as_str();
                 let arguments = parser::parse_schema_attr(attr);
@@ -72,13 +84,26 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                         "alias" => {
                             field_alias = value;
                         }
+                        "model_enum" => {
+                            model_enum = true;
+                        }
                         "primary_key" => {
                             primary_key_name.clone_from(&name);
                         }
+                        "filterable" => {
+                            let operators = value
+                                .as_deref()
+                                .unwrap_or_default()
+                                .split('|')
+                                .map(|s| s.trim())
+                                .filter(|s| !s.is_empty())
+                                .collect::<Vec<_>>();
+                            filterable_fields.push(quote! { (#name, &[#(#operators),*]) });
+                        }
                         "snapshot" => {
                             let field = name.clone();
                             let field_ident = format_ident!("{}", field);
-                            if matches!(type_name, "Uuid" | "Decimal") {
+                            if matches!(type_name, "Uuid" | "Decimal" | "Money") {
                                 snapshot_entries.push(quote! {
                                     snapshot.upsert(#field, self.#field_ident.to_string());
                                 });
@@ -204,7 +229,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                         if !value.is_nil() {
                                             let columns = vec![(#name, value.to_string().into())];
                                             if !self.is_unique_on(columns).await? {
-                                                let message = format!("the value `{value}` is not unique");
+                                                let message = format!("the value `{value}` is already taken");
                                                 validation.record(#name, message);
                                             }
                                         }
@@ -215,7 +240,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                         if !value.is_empty() {
                                             let columns = vec![(#name, value.into())];
                                             if !self.is_unique_on(columns).await? {
-                                                let message = format!("the value `{value}` is not unique");
+                                                let message = format!("the value `{value}` is already taken");
                                                 validation.record(#name, message);
                                             }
                                         }
@@ -225,7 +250,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                         if let Some(value) = self.#ident.as_deref() && !value.is_empty() {
                                             let columns = vec![(#name, value.into())];
                                             if !self.is_unique_on(columns).await? {
-                                                let message = format!("the value `{value}` is not unique");
+                                                let message = format!("the value `{value}` is already taken");
                                                 validation.record(#name, message);
                                             }
                                         }
@@ -235,7 +260,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                         if let Some(value) = self.#ident && !value.is_nil() {
                                             let columns = vec![(#name, value.to_string().into())];
                                             if !self.is_unique_on(columns).await? {
-                                                let message = format!("the value `{value}` is not unique");
+                                                let message = format!("the value `{value}` is already taken");
                                                 validation.record(#name, message);
                                             }
                                         }
@@ -245,7 +270,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                         if let Some(value) = self.#ident {
                                             let columns = vec![(#name, value.into())];
                                             if !self.is_unique_on(columns).await? {
-                                                let message = format!("the value `{value}` is not unique");
+                                                let message = format!("the value `{value}` is already taken");
                                                 validation.record(#name, message);
                                             }
                                         }
@@ -255,7 +280,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                     let value = self.#ident;
                                     let columns = vec![(#name, value.into())];
                                     if !self.is_unique_on(columns).await? {
-                                        let message = format!("the value `{value}` is not unique");
+                                        let message = format!("the value `{value}` is already taken");
                                         validation.record(#name, message);
                                     }
                                 });
@@ -533,6 +558,9 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                         };
                         column_methods.push(method);
                     }
+                    "namespace" | "visibility" | "description" if model_enum => {
+                        column_methods.push(quote_enum_str_method(&field_ident, &type_name));
+                    }
                     "status" if type_name == "String" => {
                         let method = quote! {
                             #[inline]
@@ -555,6 +583,33 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                             updates.upsert(#field_name, "Archived");
                         });
                     }
+                    "status" if model_enum => {
+                        let enum_type = parser::parse_option_type(&type_name).unwrap_or(&type_name);
+                        let enum_type_ident = format_ident!("{}", enum_type);
+                        column_methods.push(quote_enum_str_method(&field_ident, &type_name));
+                        snapshot_field = Some(field_name);
+                        list_query_methods.push(quote! {
+                            let value = zino_core::model::ModelEnum::to_json_value(
+                                &#enum_type_ident::Deleted
+                            );
+                            query.add_filter(#field_name, Map::from_entry("$ne", value));
+                        });
+                        for (updates, status) in [
+                            (&mut soft_delete_updates, "Deleted"),
+                            (&mut lock_updates, "Locked"),
+                            (&mut archive_updates, "Archived"),
+                        ] {
+                            // The variant is resolved by the Rust ident,
+                            // so a missing variant is reported at compile time.
+                            let variant_ident = format_ident!("{}", status);
+                            updates.push(quote! {
+                                let value = zino_core::model::ModelEnum::to_json_value(
+                                    &#enum_type_ident::#variant_ident
+                                );
+                                updates.upsert(#field_name, value);
+                            });
+                        }
+                    }
                     "extra" if type_name == "Map" => {
                         let method = quote! {
                             #[inline]
@@ -715,8 +770,32 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                 query.set_extra_flag("translate", true);
                 #model_ident::populate_one(&mut query, &mut model, &[#(#ref_fields),*]).await?;
             };
+            let included_query = quote! {
+                let fields = [#(#ref_fields),*]
+                    .into_iter()
+                    .filter(|field| columns.contains(field))
+                    .collect::<Vec<_>>();
+                if !fields.is_empty() {
+                    let mut query = #model_ident::default_snapshot_query();
+                    query.set_extra_flag("translate", translate_enabled);
+                    #model_ident::populate(&mut query, models, &fields).await?;
+                }
+            };
+            let included_one_query = quote! {
+                let fields = [#(#ref_fields),*]
+                    .into_iter()
+                    .filter(|field| columns.contains(field))
+                    .collect::<Vec<_>>();
+                if !fields.is_empty() {
+                    let mut query = #model_ident::default_query();
+                    query.set_extra_flag("translate", true);
+                    #model_ident::populate_one(&mut query, model, &fields).await?;
+                }
+            };
             fetched_queries.push(populated_query);
             fetched_one_queries.push(populated_one_query);
+            included_queries.push(included_query);
+            included_one_queries.push(included_one_query);
         }
     }
     if !populated_field_mappings.is_empty() {
@@ -737,9 +816,48 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
             fetched_one_queries.push(populated_one_query);
         }
     }
+    if !included_queries.is_empty() {
+        column_methods.push(quote! {
+            async fn include_references(
+                query: &Query,
+                models: &mut Vec<ZinoMap>,
+                columns: &[&str],
+            ) -> Result<(), ZinoError> {
+                let translate_enabled = query.translate_enabled();
+                #(#included_queries)*
+                Ok(())
+            }
+
+            async fn include_references_one(
+                model: &mut ZinoMap,
+                columns: &[&str],
+            ) -> Result<(), ZinoError> {
+                #(#included_one_queries)*
+                Ok(())
+            }
+        });
+    }
     fetched_queries.push(quote! { Ok(models) });
     fetched_one_queries.push(quote! { Ok(model) });
 
+    if !status_transitions.is_empty() {
+        column_methods.push(quote! {
+            #[inline]
+            fn status_transitions() -> &'static [(&'static str, &'static str)] {
+                &[#(#status_transitions),*]
+            }
+        });
+    }
+
+    if !filterable_fields.is_empty() {
+        column_methods.push(quote! {
+            #[inline]
+            fn filterable_fields() -> &'static [(&'static str, &'static [&'static str])] {
+                &[#(#filterable_fields),*]
+            }
+        });
+    }
+
     // Output
     let model_primary_key_type = format_ident!("{}", primary_key_type);
     let model_primary_key = format_ident!("{}", primary_key_name);
@@ -837,3 +955,25 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
         }
     }
 }
+
+/// Quotes the method which returns the string value of a `ModelEnum` field.
+fn quote_enum_str_method(field_ident: &Ident, type_name: &str) -> TokenStream {
+    if parser::check_option_type(type_name) {
+        quote! {
+            #[inline]
+            fn #field_ident(&self) -> &str {
+                self.#field_ident
+                    .as_ref()
+                    .map(zino_core::model::ModelEnum::as_str)
+                    .unwrap_or_default()
+            }
+        }
+    } else {
+        quote! {
+            #[inline]
+            fn #field_ident(&self) -> &str {
+                zino_core::model::ModelEnum::as_str(&self.#field_ident)
+            }
+        }
+    }
+}
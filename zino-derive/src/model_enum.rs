@@ -0,0 +1,129 @@
+use super::parser;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Expr, Fields, Lit};
+
+/// Parses the token stream for the `ModelEnum` trait derivation.
+pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
+    // Enum name
+    let name = input.ident;
+
+    // Parsing enum attributes
+    let mut integer_repr = false;
+    for attr in input.attrs.iter() {
+        for (key, value) in parser::parse_schema_attr(attr).into_iter() {
+            if key == "repr" {
+                integer_repr = matches!(value.as_deref(), Some("int" | "integer"));
+            }
+        }
+    }
+
+    // Parsing variants
+    let mut variant_idents = Vec::new();
+    let mut variant_values = Vec::new();
+    let mut variant_integers = Vec::new();
+    let mut next_integer = 0;
+    if let Data::Enum(data) = input.data {
+        for variant in data.variants.into_iter() {
+            if !matches!(variant.fields, Fields::Unit) {
+                let message = format!(
+                    "`ModelEnum` can only be derived for unit variants, but `{}::{}` has fields",
+                    name, variant.ident
+                );
+                return syn::Error::new_spanned(variant, message).to_compile_error();
+            }
+
+            let mut value = variant.ident.to_string();
+            for attr in variant.attrs.iter() {
+                for (key, attr_value) in parser::parse_schema_attr(attr).into_iter() {
+                    if key == "value" {
+                        if let Some(attr_value) = attr_value {
+                            value = attr_value;
+                        }
+                    }
+                }
+            }
+            if let Some((_, Expr::Lit(expr))) = variant.discriminant.as_ref() {
+                if let Lit::Int(ref lit) = expr.lit {
+                    if let Ok(integer) = lit.base10_parse::<i32>() {
+                        next_integer = integer;
+                    }
+                }
+            }
+            variant_idents.push(variant.ident);
+            variant_values.push(value);
+            variant_integers.push(next_integer);
+            next_integer += 1;
+        }
+    }
+    quote! {
+        impl zino_core::model::ModelEnum for #name {
+            const INTEGER_REPR: bool = #integer_repr;
+
+            #[inline]
+            fn variants() -> &'static [Self] {
+                &[#(Self::#variant_idents),*]
+            }
+
+            #[inline]
+            fn as_str(&self) -> &'static str {
+                match self {
+                    #(Self::#variant_idents => #variant_values,)*
+                }
+            }
+
+            #[inline]
+            fn as_i32(&self) -> i32 {
+                match self {
+                    #(Self::#variant_idents => #variant_integers,)*
+                }
+            }
+        }
+
+        impl AsRef<str> for #name {
+            #[inline]
+            fn as_ref(&self) -> &str {
+                zino_core::model::ModelEnum::as_str(self)
+            }
+        }
+
+        impl std::fmt::Display for #name {
+            #[inline]
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str(zino_core::model::ModelEnum::as_str(self))
+            }
+        }
+
+        impl std::str::FromStr for #name {
+            type Err = zino_core::error::Error;
+
+            #[inline]
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                <Self as zino_core::model::ModelEnum>::parse_value(&s.into())
+            }
+        }
+
+        impl From<#name> for zino_core::JsonValue {
+            #[inline]
+            fn from(value: #name) -> Self {
+                zino_core::model::ModelEnum::to_json_value(&value)
+            }
+        }
+
+        impl serde::Serialize for #name {
+            #[inline]
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let value = zino_core::model::ModelEnum::to_json_value(self);
+                serde::Serialize::serialize(&value, serializer)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for #name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = <zino_core::JsonValue as serde::Deserialize>::deserialize(deserializer)?;
+                <Self as zino_core::model::ModelEnum>::parse_value(&value)
+                    .map_err(serde::de::Error::custom)
+            }
+        }
+    }
+}
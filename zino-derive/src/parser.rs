@@ -81,6 +81,62 @@ pub(super) fn parse_schema_attr(attr: &Attribute) -> Vec<(String, Option<String>
     arguments
 }
 
+/// Parses the status transitions in the form `action: From1 | From2 -> To1 -> To2`
+/// and returns the optional action name and a list of transitions.
+pub(super) fn parse_status_transitions(value: &str) -> (Option<String>, Vec<(String, String)>) {
+    let (action, expr) = match value.split_once(':') {
+        Some((action, expr)) => (Some(action.trim().to_owned()), expr),
+        None => (None, value),
+    };
+    let states = expr
+        .split("->")
+        .map(|s| {
+            s.split('|')
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let mut transitions = Vec::new();
+    for pair in states.windows(2) {
+        for from in pair[0].iter() {
+            for to in pair[1].iter() {
+                transitions.push((from.to_string(), to.to_string()));
+            }
+        }
+    }
+    (action, transitions)
+}
+
+/// Parses the computed fields in the form of `method(field1, field2)`,
+/// and returns a list of the methods with the fields they depend on.
+pub(super) fn parse_computed_fields(value: &str) -> Vec<(String, Vec<String>)> {
+    let mut computed_fields = Vec::new();
+    let mut rest = value;
+    while !rest.trim().is_empty() {
+        let end = rest.find([',', '(']).unwrap_or(rest.len());
+        let method = rest[..end].trim();
+        let mut dependencies = Vec::new();
+        rest = &rest[end..];
+        if let Some(s) = rest.strip_prefix('(') {
+            let (fields, remainder) = s.split_once(')').unwrap_or((s, ""));
+            dependencies = fields
+                .split(',')
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_owned())
+                .collect();
+            rest = remainder;
+        }
+        rest = rest.trim_start();
+        rest = rest.strip_prefix(',').unwrap_or(rest);
+        if !method.is_empty() {
+            computed_fields.push((method.to_owned(), dependencies));
+        }
+    }
+    computed_fields
+}
+
 /// Parses the struct data and returns a list of fields.
 pub(super) fn parse_struct_fields(data: Data) -> Vec<Field> {
     if let Data::Struct(data) = data {
@@ -90,3 +146,23 @@ pub(super) fn parse_struct_fields(data: Data) -> Vec<Field> {
     }
     Vec::new()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::parse_computed_fields;
+
+    #[test]
+    fn it_parses_computed_fields() {
+        let computed_fields =
+            parse_computed_fields("full_name(first_name, last_name), age(birthday), avatar_url");
+        let methods = computed_fields
+            .iter()
+            .map(|(method, _)| method.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(methods, ["full_name", "age", "avatar_url"]);
+        assert_eq!(computed_fields[0].1, ["first_name", "last_name"]);
+        assert_eq!(computed_fields[1].1, ["birthday"]);
+        assert!(computed_fields[2].1.is_empty());
+        assert!(parse_computed_fields(" , ").is_empty());
+    }
+}
@@ -0,0 +1,174 @@
+use super::parser;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, GenericArgument, PathArguments, Type};
+
+/// Parses the token stream for the `QueryParams` trait derivation.
+pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
+    // Struct name
+    let name = input.ident;
+
+    // Parsing field attributes
+    let mut field_parsers = Vec::new();
+    let mut field_idents = Vec::new();
+    for field in parser::parse_struct_fields(input.data) {
+        let type_name = parser::get_type_name(&field.ty);
+        if let Some(ident) = field.ident {
+            let mut param_name = ident.to_string();
+            let mut default_value = None;
+            let mut max_length = None;
+            let mut format = None;
+            for attr in field.attrs.iter() {
+                for (key, value) in parser::parse_schema_attr(attr).into_iter() {
+                    match key.as_str() {
+                        "rename" => {
+                            if let Some(value) = value {
+                                param_name = value;
+                            }
+                        }
+                        "default_value" => default_value = value,
+                        "max_length" => {
+                            max_length = value.and_then(|s| s.parse::<usize>().ok());
+                        }
+                        "format" => format = value,
+                        _ => (),
+                    }
+                }
+            }
+
+            let inner_type = get_inner_type(&field.ty);
+            let parser = if type_name.starts_with("Option<") {
+                let default_value = if let Some(value) = default_value {
+                    quote! { Some(#value.into()) }
+                } else {
+                    quote! { None }
+                };
+                quote! {
+                    let #ident = match query.parse_string(#param_name).or(#default_value) {
+                        Some(value) => match value.parse::<#inner_type>() {
+                            Ok(value) => Some(value),
+                            Err(err) => {
+                                validation.record_fail(#param_name, err);
+                                None
+                            }
+                        },
+                        None => None,
+                    };
+                }
+            } else if parser::check_vec_type(&type_name) {
+                let default_value = if let Some(value) = default_value {
+                    quote! {
+                        #value.split(',')
+                            .filter_map(|s| s.trim().parse::<#inner_type>().ok())
+                            .collect()
+                    }
+                } else {
+                    quote! { Vec::new() }
+                };
+                quote! {
+                    let #ident = match query.parse_array::<#inner_type>(#param_name) {
+                        Some(Ok(values)) => values,
+                        Some(Err(err)) => {
+                            validation.record_fail(#param_name, err);
+                            Vec::new()
+                        }
+                        None => #default_value,
+                    };
+                }
+            } else {
+                let ty = &field.ty;
+                let missing_value = if let Some(value) = default_value {
+                    quote! {
+                        match #value.parse::<#ty>() {
+                            Ok(value) => value,
+                            Err(err) => {
+                                validation.record_fail(#param_name, err);
+                                Default::default()
+                            }
+                        }
+                    }
+                } else {
+                    quote! {
+                        {
+                            validation.record(#param_name, "it should be specified");
+                            Default::default()
+                        }
+                    }
+                };
+                quote! {
+                    let #ident = match query.parse_string(#param_name) {
+                        Some(value) => match value.parse::<#ty>() {
+                            Ok(value) => value,
+                            Err(err) => {
+                                validation.record_fail(#param_name, err);
+                                Default::default()
+                            }
+                        },
+                        None => #missing_value,
+                    };
+                }
+            };
+            field_parsers.push(parser);
+
+            if matches!(type_name.as_str(), "String" | "Option<String>") {
+                let value = if type_name == "String" {
+                    quote! { Some(#ident.as_str()) }
+                } else {
+                    quote! { #ident.as_deref() }
+                };
+                if let Some(max_length) = max_length {
+                    field_parsers.push(quote! {
+                        if let Some(value) = #value {
+                            let length = value.chars().count();
+                            if length > #max_length {
+                                let message = format!("the length should be at most {}", #max_length);
+                                validation.record(#param_name, message);
+                            }
+                        }
+                    });
+                }
+                if let Some(format) = format {
+                    field_parsers.push(quote! {
+                        if let Some(value) = #value.filter(|s| !s.is_empty()) {
+                            validation.validate_format(#param_name, value, #format);
+                        }
+                    });
+                }
+            }
+            field_idents.push(ident);
+        }
+    }
+    quote! {
+        impl zino_core::request::QueryParams for #name {
+            fn from_query_map(query: &zino_core::Map) -> Result<Self, zino_core::validation::Validation> {
+                use zino_core::{extension::JsonObjectExt, validation::Validation};
+
+                let mut validation = Validation::new();
+                #(#field_parsers)*
+                if validation.is_success() {
+                    Ok(Self {
+                        #(#field_idents),*
+                    })
+                } else {
+                    Err(validation)
+                }
+            }
+        }
+    }
+}
+
+/// Returns the inner type of `Option<T>` or `Vec<T>`, or the type itself.
+fn get_inner_type(ty: &Type) -> &Type {
+    if let Type::Path(type_path) = ty {
+        if let Some(segment) = type_path.path.segments.last() {
+            if matches!(segment.ident.to_string().as_str(), "Option" | "Vec") {
+                if let PathArguments::AngleBracketed(ref generics) = segment.arguments {
+                    if let Some(GenericArgument::Type(ref ty)) = generics.args.first() {
+                        return ty;
+                    }
+                }
+            }
+        }
+    }
+    ty
+}
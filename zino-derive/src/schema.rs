@@ -10,11 +10,12 @@ const INTEGER_TYPES: [&str; 10] = [
 ];
 
 // Special attributes
-const SPECIAL_ATTRIBUTES: [&str; 9] = [
+const SPECIAL_ATTRIBUTES: [&str; 10] = [
     "ignore",
     "type_name",
     "not_null",
     "default_value",
+    "default",
     "index_type",
     "reference",
     "comment",
@@ -36,6 +37,17 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
     let mut writer_name = String::from("main");
     let mut table_name = None;
     let mut model_comment = None;
+    let mut retention = None;
+    let mut retention_field = String::from("created_at");
+    let mut primary_key_strategy = None;
+    let mut partition_by = None;
+    let mut partition_interval = None;
+    let mut tree_parent_field = None;
+    let mut tree_path_field = String::from("path");
+    let mut computed_fields = Vec::new();
+    let mut max_rows = None;
+    let mut view = None;
+    let mut materialized_view = false;
     for attr in input.attrs.iter() {
         for (key, value) in parser::parse_schema_attr(attr).into_iter() {
             if let Some(value) = value {
@@ -55,6 +67,42 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                     "comment" => {
                         model_comment = Some(value);
                     }
+                    "retention" => {
+                        retention = Some(value);
+                    }
+                    "retention_field" => {
+                        retention_field = value;
+                    }
+                    "primary_key_strategy" => {
+                        primary_key_strategy = Some(value);
+                    }
+                    "partition_by" => {
+                        partition_by = Some(value);
+                    }
+                    "partition_interval" => {
+                        partition_interval = Some(value);
+                    }
+                    "tree" => {
+                        tree_parent_field = Some(value);
+                    }
+                    "tree_path" => {
+                        tree_path_field = value;
+                    }
+                    "max_rows" => {
+                        if let Ok(value) = value.parse::<usize>() {
+                            max_rows = Some(value);
+                        }
+                    }
+                    "view" => {
+                        view = Some(value);
+                    }
+                    "materialized_view" => {
+                        view = Some(value);
+                        materialized_view = true;
+                    }
+                    "computed" => {
+                        computed_fields.append(&mut parser::parse_computed_fields(&value));
+                    }
                     _ => (),
                 }
             }
@@ -66,10 +114,13 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
     let mut primary_key_name = String::from("id");
     let mut primary_key_value = None;
     let mut primary_key_column = None;
+    let mut primary_key_auto_increment = false;
     let mut columns = Vec::new();
     let mut column_fields = Vec::new();
     let mut read_only_fields = Vec::new();
     let mut write_only_fields = Vec::new();
+    let mut position_field = None;
+    let mut public_id_fields = Vec::new();
     if let Data::Struct(data) = input.data {
         if let Fields::Named(fields) = data.fields {
             for field in fields.named.into_iter() {
@@ -84,6 +135,9 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                     let mut index_type = None;
                     let mut reference = None;
                     let mut comment = None;
+                    let mut read_only = false;
+                    let mut model_enum = false;
+                    let mut unique = false;
                     let mut extra_attributes = Vec::new();
                     'inner: for attr in field.attrs.iter() {
                         let arguments = parser::parse_schema_attr(attr);
@@ -144,6 +198,19 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                 "default_value" => {
                                     default_value = value;
                                 }
+                                "default" => {
+                                    if let Some(value) = value {
+                                        extra_attributes.push(quote! {
+                                            column.set_extra_attribute("default_expression", #value);
+                                        });
+                                    }
+                                }
+                                "generated" if value.is_some() => {
+                                    read_only = true;
+                                    extra_attributes.push(quote! {
+                                        column.set_extra_attribute("read_only", true);
+                                    });
+                                }
                                 "auto_increment" => {
                                     default_value = Some("auto_increment".to_owned());
                                 }
@@ -153,6 +220,9 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                 "index_type" => {
                                     index_type = value;
                                 }
+                                "unique" => {
+                                    unique = true;
+                                }
                                 "reference" => {
                                     reference = value;
                                 }
@@ -163,11 +233,20 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                     primary_key_name.clone_from(&name);
                                 }
                                 "read_only" => {
-                                    read_only_fields.push(quote! { #name });
+                                    read_only = true;
+                                }
+                                "model_enum" => {
+                                    model_enum = true;
                                 }
                                 "write_only" => {
                                     write_only_fields.push(quote! { #name });
                                 }
+                                "position" => {
+                                    position_field = Some(name.clone());
+                                }
+                                "public_id" => {
+                                    public_id_fields.push(quote! { #name });
+                                }
                                 "constructor" | "validator" => {
                                     extra_attributes.push(quote! {
                                         column.set_extra_attribute(#key, true);
@@ -180,8 +259,24 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                     if ignore {
                         continue;
                     }
+                    if unique {
+                        extra_attributes.push(quote! {
+                            column.set_extra_attribute("unique", true);
+                        });
+                        if index_type.is_none() {
+                            index_type = Some("unique".to_owned());
+                        }
+                    }
+                    if read_only {
+                        read_only_fields.push(quote! { #name });
+                    }
                     if primary_key_name == name {
+                        if primary_key_strategy.as_deref() == Some("auto_increment") {
+                            default_value = Some("auto_increment".to_owned());
+                        }
                         primary_key_type.clone_from(&type_name);
+                        primary_key_auto_increment =
+                            default_value.as_deref() == Some("auto_increment");
                         not_null = true;
                         extra_attributes.push(quote! {
                             column.set_extra_attribute("primary_key", true);
@@ -225,8 +320,23 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                         quote! { None }
                     };
                     let quote_comment = parser::quote_option_string(comment);
+                    let quote_type_name = if model_enum {
+                        let optional = parser::check_option_type(&type_name);
+                        let enum_type = parser::parse_option_type(&type_name).unwrap_or(&type_name);
+                        let enum_type_ident = format_ident!("{}", enum_type);
+                        extra_attributes.push(quote! {
+                            let values = <#enum_type_ident as zino_core::model::ModelEnum>::enum_values();
+                            column.set_extra_attribute("enum_values", values);
+                        });
+                        quote! {
+                            <#enum_type_ident as zino_core::model::ModelEnum>::type_name(#optional)
+                        }
+                    } else {
+                        quote! { #type_name }
+                    };
                     let column = quote! {{
-                        let mut column = zino_core::model::Column::new(#name, #type_name, #not_null);
+                        let mut column =
+                            zino_core::model::Column::new(#name, #quote_type_name, #not_null);
                         if let Some(default_value) = #quote_value {
                             column.set_default_value(default_value);
                         }
@@ -277,6 +387,151 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
     let num_write_only_fields = write_only_fields.len();
     let quote_table_name = parser::quote_option_string(table_name);
     let quote_model_comment = parser::quote_option_string(model_comment);
+    let retention_job = if retention.is_some() && partition_by.is_none() {
+        quote! {
+            fn retention_job() -> Option<zino_core::schedule::AsyncJob> {
+                fn purge_expired<'a>(
+                    _job_id: zino_core::Uuid,
+                    data: &'a mut zino_core::Map,
+                    _last_tick: zino_core::datetime::DateTime,
+                ) -> zino_core::BoxFuture<'a> {
+                    use zino_core::extension::JsonObjectExt;
+
+                    Box::pin(async move {
+                        let dry_run = data.get_bool("dry_run").unwrap_or_default();
+                        match <#name>::purge_expired(dry_run).await {
+                            Ok(num_rows) => {
+                                data.upsert("$output", num_rows);
+                            }
+                            Err(err) => {
+                                data.upsert("$error", err.to_string());
+                            }
+                        }
+                    })
+                }
+                Some(orm::new_retention_job(Self::MODEL_NAME, purge_expired))
+            }
+        }
+    } else {
+        quote! {}
+    };
+    let partition_job = if partition_by.is_some() {
+        quote! {
+            fn partition_job() -> Option<zino_core::schedule::AsyncJob> {
+                fn maintain_partitions<'a>(
+                    _job_id: zino_core::Uuid,
+                    data: &'a mut zino_core::Map,
+                    _last_tick: zino_core::datetime::DateTime,
+                ) -> zino_core::BoxFuture<'a> {
+                    use zino_core::extension::JsonObjectExt;
+
+                    Box::pin(async move {
+                        let dry_run = data.get_bool("dry_run").unwrap_or_default();
+                        let result = match <#name>::create_partitions().await {
+                            Ok(num_created) => <#name>::prune_partitions(dry_run)
+                                .await
+                                .map(|num_pruned| (num_created, num_pruned)),
+                            Err(err) => Err(err),
+                        };
+                        match result {
+                            Ok((num_created, num_pruned)) => {
+                                let mut output = zino_core::Map::new();
+                                output.upsert("created", num_created);
+                                output.upsert("pruned", num_pruned);
+                                data.upsert("$output", output);
+                            }
+                            Err(err) => {
+                                data.upsert("$error", err.to_string());
+                            }
+                        }
+                    })
+                }
+                orm::new_partition_job(Self::MODEL_NAME, maintain_partitions)
+            }
+        }
+    } else {
+        quote! {}
+    };
+    let refresh_job = if materialized_view {
+        quote! {
+            fn refresh_job() -> Option<zino_core::schedule::AsyncJob> {
+                fn refresh_materialized_view<'a>(
+                    _job_id: zino_core::Uuid,
+                    data: &'a mut zino_core::Map,
+                    _last_tick: zino_core::datetime::DateTime,
+                ) -> zino_core::BoxFuture<'a> {
+                    use zino_core::extension::JsonObjectExt;
+
+                    Box::pin(async move {
+                        let concurrently = data.get_bool("concurrently").unwrap_or_default();
+                        if let Err(err) = <#name>::refresh_view(concurrently).await {
+                            data.upsert("$error", err.to_string());
+                        }
+                    })
+                }
+                Some(orm::new_refresh_job(Self::MODEL_NAME, refresh_materialized_view))
+            }
+        }
+    } else {
+        quote! {}
+    };
+    let compute_fields = if computed_fields.is_empty() {
+        quote! {}
+    } else {
+        let computed_flags = computed_fields.iter().map(|(_, dependencies)| {
+            quote! {
+                {
+                    let dependencies: &[&str] = &[#(#dependencies),*];
+                    dependencies.iter().all(|&field| data.contains_key(field))
+                }
+            }
+        });
+        let computed_entries = computed_fields
+            .iter()
+            .enumerate()
+            .map(|(index, (field, _))| {
+                let method = format_ident!("{}", field);
+                quote! {
+                    if computed_flags[#index] {
+                        data.insert(#field.to_owned(), model.#method().into());
+                    }
+                }
+            });
+        quote! {
+            fn compute_fields(data: &mut zino_core::Map) {
+                use zino_core::model::Model;
+
+                // Only the fields whose dependencies are present in the data are computed.
+                let computed_flags = [#(#computed_flags),*];
+                if computed_flags.contains(&true) {
+                    let mut model = Self::new();
+                    if model.read_map(data).is_success() {
+                        #(#computed_entries)*
+                    }
+                }
+            }
+        }
+    };
+    let computed_field_names = computed_fields.iter().map(|(field, _)| field);
+    let quote_max_rows = match max_rows {
+        Some(value) => quote! { Some(#value) },
+        None => quote! { None },
+    };
+    let quote_view = parser::quote_option_string(view);
+    let quote_retention = parser::quote_option_string(retention);
+    let quote_partition_by = parser::quote_option_string(partition_by);
+    let quote_partition_interval = parser::quote_option_string(partition_interval);
+    let quote_tree_parent_field = parser::quote_option_string(tree_parent_field);
+    let quote_position_field = parser::quote_option_string(position_field);
+    let quote_primary_key_strategy = match primary_key_strategy.as_deref() {
+        Some("uuid_v4") => quote! { UuidV4 },
+        Some("uuid_v7") => quote! { UuidV7 },
+        Some("snowflake") => quote! { Snowflake },
+        Some("manual") => quote! { Manual },
+        _ if primary_key_auto_increment => quote! { AutoIncrement },
+        _ if primary_key_type == "Uuid" => quote! { UuidV7 },
+        _ => quote! { Manual },
+    };
     quote! {
         use zino_core::{
             error::Error as ZinoError,
@@ -324,9 +579,23 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
             type PrimaryKey = #schema_primary_key_type;
 
             const PRIMARY_KEY_NAME: &'static str = #primary_key_name;
+            const PRIMARY_KEY_STRATEGY: zino_core::model::PrimaryKeyStrategy =
+                zino_core::model::PrimaryKeyStrategy::#quote_primary_key_strategy;
             const READER_NAME: &'static str = #reader_name;
             const WRITER_NAME: &'static str = #writer_name;
             const TABLE_NAME: Option<&'static str> = #quote_table_name;
+            const RETENTION: Option<&'static str> = #quote_retention;
+            const RETENTION_FIELD: &'static str = #retention_field;
+            const PARTITION_BY: Option<&'static str> = #quote_partition_by;
+            const PARTITION_INTERVAL: Option<&'static str> = #quote_partition_interval;
+            const TREE_PARENT_FIELD: Option<&'static str> = #quote_tree_parent_field;
+            const TREE_PATH_FIELD: &'static str = #tree_path_field;
+            const POSITION_FIELD: Option<&'static str> = #quote_position_field;
+            const PUBLIC_ID_FIELDS: &'static [&'static str] = &[#(#public_id_fields),*];
+            const COMPUTED_FIELDS: &'static [&'static str] = &[#(#computed_field_names),*];
+            const MAX_ROWS: Option<usize> = #quote_max_rows;
+            const VIEW: Option<&'static str> = #quote_view;
+            const MATERIALIZED_VIEW: bool = #materialized_view;
 
             #[inline]
             fn primary_key(&self) -> &Self::PrimaryKey {
@@ -371,6 +640,9 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
             async fn acquire_reader() -> Result<&'static ConnectionPool, ZinoError> {
                 use zino_core::{bail, orm::PoolManager, warn};
 
+                if let Some(connection_pool) = orm::DatabaseContext::current_pool() {
+                    return Ok(connection_pool);
+                }
                 if let Some(reader) = #schema_reader.get() {
                     if reader.is_available()
                         || reader.is_retryable() && reader.check_availability().await
@@ -409,6 +681,14 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                             err
                         );
                     }
+                    if let Err(err) = Self::verify_schema().await {
+                        connection_pool.store_availability(false);
+                        bail!(
+                            "503 Service Unavailable: fail to acquire reader for the model `{}`: {}",
+                            model_name,
+                            err
+                        );
+                    }
                     #schema_reader.set(connection_pool).map_err(|_| {
                         warn!(
                             "503 Service Unavailable: fail to acquire reader for the model `{}`",
@@ -422,6 +702,15 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
             async fn acquire_writer() -> Result<&'static ConnectionPool, ZinoError> {
                 use zino_core::{bail, orm::PoolManager, warn};
 
+                if Self::VIEW.is_some() {
+                    bail!(
+                        "405 Method Not Allowed: the model `{}` is backed by a read-only view",
+                        Self::MODEL_NAME
+                    );
+                }
+                if let Some(connection_pool) = orm::DatabaseContext::current_pool() {
+                    return Ok(connection_pool);
+                }
                 if let Some(writer) = #schema_writer.get() {
                     if writer.is_available()
                         || writer.is_retryable() && writer.check_availability().await
@@ -458,6 +747,13 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                             err
                         );
                     }
+                    if let Err(err) = Self::verify_schema().await {
+                        bail!(
+                            "503 Service Unavailable: fail to acquire writer for the model `{}`: {}",
+                            model_name,
+                            err
+                        );
+                    }
                     #schema_writer.set(connection_pool).map_err(|_| {
                         warn!(
                             "503 Service Unavailable: fail to acquire writer for the model `{}`",
@@ -471,8 +767,11 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
             #[inline]
             fn table_name() -> &'static str {
                 Self::TABLE_NAME.unwrap_or_else(|| {
-                    #schema_table_name
-                        .get_or_init(|| [Self::table_prefix(), Self::MODEL_NAME].concat().leak())
+                    #schema_table_name.get_or_init(|| {
+                        let naming = zino_core::orm::NamingConvention::shared();
+                        let table_name = naming.table_name(Self::MODEL_NAME);
+                        [Self::table_prefix(), &table_name].concat().leak()
+                    })
                 })
             }
 
@@ -481,6 +780,14 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                 #schema_model_namespace
                     .get_or_init(|| [Self::namespace_prefix(), Self::MODEL_NAME].concat().leak())
             }
+
+            #retention_job
+
+            #partition_job
+
+            #refresh_job
+
+            #compute_fields
         }
 
         impl PartialEq for #name {
@@ -0,0 +1,51 @@
+use super::parser;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::DeriveInput;
+
+/// Parses the token stream for the `StateMachine` derivation.
+pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
+    // Model name
+    let name = input.ident;
+
+    // Parsing struct attributes
+    let mut transition_methods = Vec::new();
+    for attr in input.attrs.iter() {
+        for (key, value) in parser::parse_schema_attr(attr).into_iter() {
+            if key == "transition" {
+                if let Some(value) = value {
+                    let (action, transitions) = parser::parse_status_transitions(&value);
+                    if let Some(action) = action.filter(|_| !transitions.is_empty()) {
+                        let method_ident = format_ident!("{}", action);
+                        let method_doc =
+                            format!("Transitions the status by one step of the action `{action}`.");
+                        let hops = transitions.iter().map(|(from, to)| {
+                            quote! {
+                                if zino_core::orm::ModelAccessor::has_status(self, #from) {
+                                    return zino_core::orm::ModelAccessor::transition(self, #to).await;
+                                }
+                            }
+                        });
+                        transition_methods.push(quote! {
+                            #[doc = #method_doc]
+                            pub async fn #method_ident(&mut self) -> Result<(), zino_core::error::Error> {
+                                #(#hops)*
+                                let message = format!(
+                                    "409 Conflict: the action `{}` is not allowed for the status `{}`",
+                                    #action,
+                                    zino_core::orm::ModelAccessor::status(self)
+                                );
+                                Err(zino_core::error::Error::new(message))
+                            }
+                        });
+                    }
+                }
+            }
+        }
+    }
+    quote! {
+        impl #name {
+            #(#transition_methods)*
+        }
+    }
+}
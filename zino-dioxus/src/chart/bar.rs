@@ -0,0 +1,107 @@
+use super::{default_colors, format_label, get_color, parse_value, render_axes, PlotArea};
+use crate::class::Class;
+use dioxus::prelude::*;
+use zino_core::{Map, SharedString};
+
+/// A bar chart for the series of values. Multiple series will be rendered as grouped bars.
+pub fn BarChart(props: BarChartProps) -> Element {
+    let data = &props.data;
+    let area = PlotArea::new(
+        props.width,
+        props.height,
+        data.iter().flat_map(|row| {
+            props
+                .y_fields
+                .iter()
+                .map(|field| parse_value(row, field))
+                .collect::<Vec<_>>()
+        }),
+    );
+    let band_width = area.width / (data.len().max(1) as f64);
+    let series_count = props.y_fields.len().max(1) as f64;
+    let bar_width = band_width * 0.8 / series_count;
+    let origin = area.y(0.0);
+    let labels = data
+        .iter()
+        .enumerate()
+        .map(|(index, row)| {
+            let x = area.left + band_width * (index as f64 + 0.5);
+            (x, format_label(row, &props.x_field))
+        })
+        .collect::<Vec<_>>();
+    let mut bars = Vec::new();
+    for (index, row) in data.iter().enumerate() {
+        let band_start = area.left + band_width * (index as f64 + 0.1);
+        for (series_index, field) in props.y_fields.iter().enumerate() {
+            let value = parse_value(row, field);
+            let y = area.y(value);
+            let x = band_start + bar_width * (series_index as f64);
+            let (top, height) = if y < origin {
+                (y, origin - y)
+            } else {
+                (origin, y - origin)
+            };
+            let tooltip = format!("{}: {value}", format_label(row, &props.x_field));
+            let color = get_color(&props.colors, series_index);
+            bars.push((x, top, height, color, tooltip));
+        }
+    }
+    let label_y = area.bottom() + 16.0;
+    rsx! {
+        svg {
+            class: props.class,
+            view_box: "0 0 {props.width} {props.height}",
+            width: "100%",
+            { render_axes(area) }
+            g {
+                class: "chart-labels",
+                font_size: "10",
+                text_anchor: "middle",
+                fill: "currentColor",
+                for (x, label) in labels {
+                    text {
+                        x: "{x}",
+                        y: "{label_y}",
+                        "{label}"
+                    }
+                }
+            }
+            for (x, y, height, color, tooltip) in bars {
+                rect {
+                    class: "chart-bar",
+                    x: "{x}",
+                    y: "{y}",
+                    width: "{bar_width}",
+                    height: "{height}",
+                    fill: "{color}",
+                    title { "{tooltip}" }
+                }
+            }
+        }
+    }
+}
+
+/// The [`BarChart`] properties struct for the configuration of the component.
+#[derive(Clone, PartialEq, Props)]
+pub struct BarChartProps {
+    /// The class attribute for the component.
+    #[props(into, default = "chart chart-bar".into())]
+    pub class: Class,
+    /// The data rows.
+    pub data: Vec<Map>,
+    /// The field for the labels of the horizontal axis.
+    #[props(into, default = "label".into())]
+    pub x_field: SharedString,
+    /// The fields for the values of each series.
+    #[props(default = vec!["value".into()])]
+    pub y_fields: Vec<SharedString>,
+    /// The colors of the series.
+    #[props(default = default_colors())]
+    pub colors: Vec<SharedString>,
+    /// The width of the view box.
+    #[props(default = 600)]
+    pub width: u32,
+    /// The height of the view box.
+    #[props(default = 300)]
+    pub height: u32,
+}
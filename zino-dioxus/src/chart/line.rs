@@ -0,0 +1,113 @@
+use super::{default_colors, format_label, get_color, parse_value, render_axes, PlotArea};
+use crate::class::Class;
+use dioxus::prelude::*;
+use zino_core::{Map, SharedString};
+
+/// A line chart for the series of values.
+pub fn LineChart(props: LineChartProps) -> Element {
+    let data = &props.data;
+    let area = PlotArea::new(
+        props.width,
+        props.height,
+        data.iter().flat_map(|row| {
+            props
+                .y_fields
+                .iter()
+                .map(|field| parse_value(row, field))
+                .collect::<Vec<_>>()
+        }),
+    );
+    let step = if data.len() > 1 {
+        area.width / ((data.len() - 1) as f64)
+    } else {
+        0.0
+    };
+    let offset = if data.len() > 1 {
+        area.left
+    } else {
+        area.left + area.width / 2.0
+    };
+    let labels = data
+        .iter()
+        .enumerate()
+        .map(|(index, row)| {
+            let x = offset + step * (index as f64);
+            (x, format_label(row, &props.x_field))
+        })
+        .collect::<Vec<_>>();
+    let series = props
+        .y_fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let points = data
+                .iter()
+                .enumerate()
+                .map(|(i, row)| {
+                    let x = offset + step * (i as f64);
+                    let y = area.y(parse_value(row, field));
+                    format!("{x:.2},{y:.2}")
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            (field.clone(), points, get_color(&props.colors, index))
+        })
+        .collect::<Vec<_>>();
+    let label_y = area.bottom() + 16.0;
+    rsx! {
+        svg {
+            class: props.class,
+            view_box: "0 0 {props.width} {props.height}",
+            width: "100%",
+            { render_axes(area) }
+            g {
+                class: "chart-labels",
+                font_size: "10",
+                text_anchor: "middle",
+                fill: "currentColor",
+                for (x, label) in labels {
+                    text {
+                        x: "{x}",
+                        y: "{label_y}",
+                        "{label}"
+                    }
+                }
+            }
+            for (field, points, color) in series {
+                polyline {
+                    key: "{field}",
+                    class: "chart-line",
+                    fill: "none",
+                    stroke: "{color}",
+                    stroke_width: "2",
+                    points: points,
+                }
+            }
+        }
+    }
+}
+
+/// The [`LineChart`] properties struct for the configuration of the component.
+#[derive(Clone, PartialEq, Props)]
+pub struct LineChartProps {
+    /// The class attribute for the component.
+    #[props(into, default = "chart chart-line".into())]
+    pub class: Class,
+    /// The data rows.
+    pub data: Vec<Map>,
+    /// The field for the labels of the horizontal axis.
+    #[props(into, default = "label".into())]
+    pub x_field: SharedString,
+    /// The fields for the values of each series.
+    #[props(default = vec!["value".into()])]
+    pub y_fields: Vec<SharedString>,
+    /// The colors of the series.
+    #[props(default = default_colors())]
+    pub colors: Vec<SharedString>,
+    /// The width of the view box.
+    #[props(default = 600)]
+    pub width: u32,
+    /// The height of the view box.
+    #[props(default = 300)]
+    pub height: u32,
+}
@@ -0,0 +1,217 @@
+//! Charts rendered as SVG elements.
+
+use crate::helper;
+use dioxus::prelude::*;
+use std::time::Duration;
+use zino_core::{
+    extension::{JsonObjectExt, JsonValueExt},
+    JsonValue, Map, SharedString,
+};
+
+mod bar;
+mod line;
+mod pie;
+
+pub use bar::{BarChart, BarChartProps};
+pub use line::{LineChart, LineChartProps};
+pub use pie::{PieChart, PieChartProps};
+
+/// Fetches the chart data from the URL and refreshes it in the interval of milliseconds.
+/// A zero interval means the data will be fetched only once.
+///
+/// The `data` field in the response body can be an array of objects,
+/// an object with the `entries` array, or an object of numeric values which will be
+/// converted into the rows with the `label` and `value` fields.
+pub fn use_chart_data(url: impl Into<SharedString>, refresh_interval: u64) -> Signal<Vec<Map>> {
+    let mut data = use_signal(Vec::new);
+    let url = url.into();
+    use_future(move || {
+        let url = url.clone();
+        async move {
+            loop {
+                if let Ok(value) = helper::fetch_data(&url, None).await {
+                    data.set(parse_chart_data(value));
+                }
+                if refresh_interval == 0 {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(refresh_interval)).await;
+            }
+        }
+    });
+    data
+}
+
+/// Parses the chart data from a JSON value.
+fn parse_chart_data(value: JsonValue) -> Vec<Map> {
+    match value {
+        JsonValue::Array(vec) => vec.into_iter().filter_map(|v| v.into_map_opt()).collect(),
+        JsonValue::Object(mut map) => {
+            if let Some(JsonValue::Array(vec)) = map.remove("entries") {
+                vec.into_iter().filter_map(|v| v.into_map_opt()).collect()
+            } else {
+                map.into_iter()
+                    .filter(|(_, value)| value.is_number())
+                    .map(|(key, value)| {
+                        let mut row = Map::new();
+                        row.upsert("label", key);
+                        row.upsert("value", value);
+                        row
+                    })
+                    .collect()
+            }
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Default colors of the chart series.
+const DEFAULT_COLORS: [&str; 8] = [
+    "#00d1b2", "#485fc7", "#3e8ed0", "#48c78e", "#ffb70f", "#f14668", "#9b59b6", "#7a7a7a",
+];
+
+/// Returns the default colors of the chart series.
+fn default_colors() -> Vec<SharedString> {
+    DEFAULT_COLORS.into_iter().map(|s| s.into()).collect()
+}
+
+/// Returns the color for the index.
+fn get_color(colors: &[SharedString], index: usize) -> SharedString {
+    if colors.is_empty() {
+        DEFAULT_COLORS[index % DEFAULT_COLORS.len()].into()
+    } else {
+        colors[index % colors.len()].clone()
+    }
+}
+
+/// Formats the label of a row.
+fn format_label(row: &Map, field: &str) -> String {
+    match row.get(field) {
+        Some(JsonValue::String(s)) => s.to_owned(),
+        Some(JsonValue::Null) | None => String::new(),
+        Some(value) => value.to_string(),
+    }
+}
+
+/// Parses the value of a row as a number.
+fn parse_value(row: &Map, field: &str) -> f64 {
+    row.get_f64(field)
+        .or_else(|| row.parse_f64(field)?.ok())
+        .unwrap_or_default()
+}
+
+/// The plot area of a chart with axes.
+#[derive(Debug, Clone, Copy)]
+struct PlotArea {
+    /// The left position.
+    left: f64,
+    /// The top position.
+    top: f64,
+    /// The width.
+    width: f64,
+    /// The height.
+    height: f64,
+    /// The minimum value.
+    min_value: f64,
+    /// The maximum value.
+    max_value: f64,
+}
+
+impl PlotArea {
+    /// Creates a new instance for the values.
+    fn new(width: u32, height: u32, values: impl Iterator<Item = f64>) -> Self {
+        let (mut min_value, mut max_value) = (0.0_f64, 0.0_f64);
+        for value in values {
+            min_value = min_value.min(value);
+            max_value = max_value.max(value);
+        }
+        if max_value == min_value {
+            max_value = min_value + 1.0;
+        }
+
+        let (left, right, top, bottom) = (48.0, 16.0, 16.0, 32.0);
+        Self {
+            left,
+            top,
+            width: (f64::from(width) - left - right).max(1.0),
+            height: (f64::from(height) - top - bottom).max(1.0),
+            min_value,
+            max_value,
+        }
+    }
+
+    /// Returns the vertical position of the value.
+    fn y(&self, value: f64) -> f64 {
+        self.top + (self.max_value - value) / (self.max_value - self.min_value) * self.height
+    }
+
+    /// Returns the bottom position.
+    fn bottom(&self) -> f64 {
+        self.top + self.height
+    }
+
+    /// Returns the right position.
+    fn right(&self) -> f64 {
+        self.left + self.width
+    }
+
+    /// Returns the ticks of the value axis in the form `(position, label)`.
+    fn ticks(&self, count: usize) -> Vec<(f64, String)> {
+        let step = (self.max_value - self.min_value) / (count as f64);
+        (0..=count)
+            .map(|index| {
+                let value = self.min_value + step * (index as f64);
+                let label = if step.fract() == 0.0 && value.fract() == 0.0 {
+                    format!("{value}")
+                } else {
+                    format!("{value:.2}")
+                };
+                (self.y(value), label)
+            })
+            .collect()
+    }
+}
+
+/// Renders the axes of the plot area.
+fn render_axes(area: PlotArea) -> Element {
+    let (left, top, right, bottom) = (area.left, area.top, area.right(), area.bottom());
+    let origin = area.y(0.0);
+    let tick_x = left - 6.0;
+    let ticks = area
+        .ticks(5)
+        .into_iter()
+        .map(|(y, label)| (y + 3.0, label))
+        .collect::<Vec<_>>();
+    rsx! {
+        g {
+            class: "chart-axes",
+            stroke: "currentColor",
+            stroke_opacity: "0.3",
+            line {
+                x1: "{left}",
+                y1: "{top}",
+                x2: "{left}",
+                y2: "{bottom}",
+            }
+            line {
+                x1: "{left}",
+                y1: "{origin}",
+                x2: "{right}",
+                y2: "{origin}",
+            }
+        }
+        g {
+            class: "chart-ticks",
+            font_size: "10",
+            text_anchor: "end",
+            fill: "currentColor",
+            for (y, label) in ticks {
+                text {
+                    x: "{tick_x}",
+                    y: "{y}",
+                    "{label}"
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use super::{default_colors, format_label, get_color, parse_value};
+use crate::class::Class;
+use dioxus::prelude::*;
+use std::f64::consts::PI;
+use zino_core::{Map, SharedString};
+
+/// A pie chart for the proportions of values.
+pub fn PieChart(props: PieChartProps) -> Element {
+    let values = props
+        .data
+        .iter()
+        .map(|row| {
+            let label = format_label(row, &props.label_field);
+            let value = parse_value(row, &props.value_field).max(0.0);
+            (label, value)
+        })
+        .collect::<Vec<_>>();
+    let total = values.iter().map(|(_, value)| value).sum::<f64>();
+    let size = f64::from(props.size);
+    let (cx, cy) = (size / 2.0, size / 2.0);
+    let radius = size / 2.0 - 4.0;
+    let inner_radius = radius * props.inner_radius.clamp(0.0, 0.9);
+    let mut slices = Vec::new();
+    let mut start_angle = -PI / 2.0;
+    for (index, (label, value)) in values.into_iter().enumerate() {
+        if total <= 0.0 || value <= 0.0 {
+            continue;
+        }
+
+        let ratio = value / total;
+        let end_angle = if ratio >= 1.0 {
+            // A full circle can not be drawn with a single arc.
+            start_angle + 2.0 * PI - 1e-4
+        } else {
+            start_angle + ratio * 2.0 * PI
+        };
+        let d = format_slice_path(cx, cy, radius, inner_radius, start_angle, end_angle);
+        let tooltip = format!("{label}: {value} ({:.1}%)", ratio * 100.0);
+        let color = get_color(&props.colors, index);
+        slices.push((d, color, tooltip));
+        start_angle = end_angle;
+    }
+    rsx! {
+        svg {
+            class: props.class,
+            view_box: "0 0 {size} {size}",
+            width: "100%",
+            for (d, color, tooltip) in slices {
+                path {
+                    class: "chart-slice",
+                    d: d,
+                    fill: "{color}",
+                    stroke: "#fff",
+                    stroke_width: "1",
+                    title { "{tooltip}" }
+                }
+            }
+        }
+    }
+}
+
+/// The [`PieChart`] properties struct for the configuration of the component.
+#[derive(Clone, PartialEq, Props)]
+pub struct PieChartProps {
+    /// The class attribute for the component.
+    #[props(into, default = "chart chart-pie".into())]
+    pub class: Class,
+    /// The data rows.
+    pub data: Vec<Map>,
+    /// The field for the labels of slices.
+    #[props(into, default = "label".into())]
+    pub label_field: SharedString,
+    /// The field for the values of slices.
+    #[props(into, default = "value".into())]
+    pub value_field: SharedString,
+    /// The colors of the slices.
+    #[props(default = default_colors())]
+    pub colors: Vec<SharedString>,
+    /// The ratio of the inner radius. A nonzero value renders a donut chart.
+    #[props(default)]
+    pub inner_radius: f64,
+    /// The size of the view box.
+    #[props(default = 300)]
+    pub size: u32,
+}
+
+/// Formats the path of a slice.
+fn format_slice_path(
+    cx: f64,
+    cy: f64,
+    radius: f64,
+    inner_radius: f64,
+    start_angle: f64,
+    end_angle: f64,
+) -> String {
+    let large_arc = if end_angle - start_angle > PI { 1 } else { 0 };
+    let (x1, y1) = (
+        cx + radius * start_angle.cos(),
+        cy + radius * start_angle.sin(),
+    );
+    let (x2, y2) = (cx + radius * end_angle.cos(), cy + radius * end_angle.sin());
+    if inner_radius > 0.0 {
+        let (x3, y3) = (
+            cx + inner_radius * end_angle.cos(),
+            cy + inner_radius * end_angle.sin(),
+        );
+        let (x4, y4) = (
+            cx + inner_radius * start_angle.cos(),
+            cy + inner_radius * start_angle.sin(),
+        );
+        format!(
+            "M {x1:.2} {y1:.2} A {radius:.2} {radius:.2} 0 {large_arc} 1 {x2:.2} {y2:.2} \
+                L {x3:.2} {y3:.2} A {inner_radius:.2} {inner_radius:.2} 0 {large_arc} 0 {x4:.2} {y4:.2} Z"
+        )
+    } else {
+        format!(
+            "M {cx:.2} {cy:.2} L {x1:.2} {y1:.2} \
+                A {radius:.2} {radius:.2} 0 {large_arc} 1 {x2:.2} {y2:.2} Z"
+        )
+    }
+}
@@ -0,0 +1,7 @@
+//! Data display components.
+
+mod table;
+mod virtual_list;
+
+pub use table::{CellRenderer, DataTable, DataTableProps, TableColumn, TableQuery};
+pub use virtual_list::{RowRenderer, VirtualList, VirtualListProps};
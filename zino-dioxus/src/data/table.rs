@@ -0,0 +1,432 @@
+use crate::{class::Class, navigation::Pagination};
+use dioxus::prelude::*;
+use zino_core::{extension::JsonObjectExt, JsonValue, Map, SharedString};
+
+/// A function pointer of rendering the table cell for a row.
+pub type CellRenderer = fn(row: &Map, field: &str) -> Element;
+
+/// A data table with server-side sorting, filtering and pagination.
+///
+/// The table does not fetch the data by itself. Instead, the `on_query_change` handler
+/// should be used to request the `list` endpoint of the `DefaultController` with
+/// the query parameters generated by [`TableQuery::to_map()`].
+pub fn DataTable(props: DataTableProps) -> Element {
+    let mut query = use_signal(|| props.query.clone());
+    let mut selected_keys = use_signal(Vec::<String>::new);
+    let row_key = props.row_key.clone();
+    let page_keys = props
+        .data
+        .iter()
+        .filter_map(|row| format_row_key(row, &row_key))
+        .collect::<Vec<_>>();
+    let all_selected = !page_keys.is_empty()
+        && page_keys
+            .iter()
+            .all(|key| selected_keys.read().contains(key));
+    let has_filters = props.columns.iter().any(|col| col.filterable);
+    let on_query_change = props.on_query_change;
+    let on_select = props.on_select;
+    let selectable = props.selectable;
+    rsx! {
+        div {
+            class: props.container_class,
+            table {
+                class: props.class,
+                thead {
+                    tr {
+                        if selectable {
+                            th {
+                                input {
+                                    r#type: "checkbox",
+                                    checked: all_selected,
+                                    onchange: move |event| {
+                                        let mut keys = selected_keys.write();
+                                        if event.value() == "true" {
+                                            for key in page_keys.iter() {
+                                                if !keys.contains(key) {
+                                                    keys.push(key.clone());
+                                                }
+                                            }
+                                        } else {
+                                            keys.retain(|key| !page_keys.contains(key));
+                                        }
+                                        if let Some(handler) = on_select.as_ref() {
+                                            handler.call(keys.clone());
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        for column in props.columns.iter() {
+                            { render_header_cell(column, query, on_query_change) }
+                        }
+                    }
+                    if has_filters {
+                        tr {
+                            if selectable {
+                                th {}
+                            }
+                            for column in props.columns.iter() {
+                                { render_filter_cell(column, query, on_query_change) }
+                            }
+                        }
+                    }
+                }
+                tbody {
+                    for row in props.data.iter() {
+                        tr {
+                            key: "{format_row_key(row, &row_key).unwrap_or_default()}",
+                            if selectable {
+                                { render_selection_cell(row, &row_key, selected_keys, on_select) }
+                            }
+                            for column in props.columns.iter() {
+                                td {
+                                    { render_cell(row, column) }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Pagination {
+            total: props.total,
+            page_size: query.read().page_size,
+            current_page: query.read().current_page,
+            on_change: move |page| {
+                query.write().current_page = page;
+                if let Some(handler) = on_query_change.as_ref() {
+                    handler.call(query());
+                }
+            }
+        }
+    }
+}
+
+/// The [`DataTable`] properties struct for the configuration of the component.
+#[derive(Clone, PartialEq, Props)]
+pub struct DataTableProps {
+    /// The class attribute for the component.
+    #[props(into, default = "table is-fullwidth is-hoverable".into())]
+    pub class: Class,
+    /// A class to apply to the container element.
+    #[props(into, default = "table-container".into())]
+    pub container_class: Class,
+    /// The column definitions.
+    pub columns: Vec<TableColumn>,
+    /// The data rows of the current page.
+    pub data: Vec<Map>,
+    /// Total number of data rows.
+    #[props(default)]
+    pub total: usize,
+    /// The initial query.
+    #[props(default)]
+    pub query: TableQuery,
+    /// The field used as the unique key of a row.
+    #[props(into, default = "id".into())]
+    pub row_key: SharedString,
+    /// A flag to determine whether the rows are selectable or not.
+    #[props(default)]
+    pub selectable: bool,
+    /// An event handler to be called when the query is changed.
+    pub on_query_change: Option<EventHandler<TableQuery>>,
+    /// An event handler to be called when the selected row keys are changed.
+    pub on_select: Option<EventHandler<Vec<String>>>,
+}
+
+/// A column definition for the data table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableColumn {
+    /// The field name.
+    field: SharedString,
+    /// The label text.
+    label: SharedString,
+    /// A flag to determine whether the column is sortable or not.
+    sortable: bool,
+    /// A flag to determine whether the column is filterable or not.
+    filterable: bool,
+    /// The filter operator.
+    filter_operator: SharedString,
+    /// An optional cell renderer.
+    renderer: Option<CellRenderer>,
+}
+
+impl TableColumn {
+    /// Creates a new instance.
+    #[inline]
+    pub fn new(field: impl Into<SharedString>, label: impl Into<SharedString>) -> Self {
+        Self {
+            field: field.into(),
+            label: label.into(),
+            sortable: false,
+            filterable: false,
+            filter_operator: "eq".into(),
+            renderer: None,
+        }
+    }
+
+    /// Creates a list of columns from the model definition
+    /// returned by the `definition` endpoint of the `DefaultController`.
+    pub fn from_definition(definition: &Map) -> Vec<Self> {
+        let mut columns = Vec::new();
+        if let Some(properties) = definition.get_object("properties") {
+            for (field, property) in properties {
+                let Some(property) = property.as_object() else {
+                    continue;
+                };
+                let label = property
+                    .get_str("title")
+                    .or_else(|| property.get_str("description"))
+                    .unwrap_or(field.as_str())
+                    .to_owned();
+                let mut column = Self::new(field.to_owned(), label);
+                match property.get_str("type") {
+                    Some("string") => {
+                        column.sortable = true;
+                        column.filterable = property.get_str("format").is_none();
+                        column.filter_operator = "ilike".into();
+                    }
+                    Some("integer" | "number" | "boolean") => {
+                        column.sortable = true;
+                        column.filterable = true;
+                    }
+                    _ => (),
+                }
+                columns.push(column);
+            }
+        }
+        columns
+    }
+
+    /// Makes the column sortable.
+    #[inline]
+    pub fn sortable(mut self) -> Self {
+        self.sortable = true;
+        self
+    }
+
+    /// Makes the column filterable with the operator,
+    /// such as `eq` | `ne` | `lt` | `le` | `gt` | `ge` | `like` | `ilike`.
+    #[inline]
+    pub fn filterable(mut self, operator: impl Into<SharedString>) -> Self {
+        self.filterable = true;
+        self.filter_operator = operator.into();
+        self
+    }
+
+    /// Sets the cell renderer.
+    #[inline]
+    pub fn render_with(mut self, renderer: CellRenderer) -> Self {
+        self.renderer = Some(renderer);
+        self
+    }
+
+    /// Returns the field name.
+    #[inline]
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    /// Returns the label text.
+    #[inline]
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+}
+
+/// The query state of the data table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableQuery {
+    /// The current page number.
+    pub current_page: usize,
+    /// Number of data rows per page.
+    pub page_size: usize,
+    /// The sort field and a flag for the descending order.
+    pub sort_order: Option<(SharedString, bool)>,
+    /// The filters in the form `(field, expression)`.
+    pub filters: Vec<(SharedString, String)>,
+}
+
+impl TableQuery {
+    /// Sets the filter for the field. An empty value removes the filter.
+    pub fn set_filter(&mut self, field: impl Into<SharedString>, value: String) {
+        let field = field.into();
+        self.filters.retain(|(key, _)| key != &field);
+        if !value.is_empty() {
+            self.filters.push((field, value));
+        }
+    }
+
+    /// Toggles the sort order of the field in the sequence: descending, ascending and none.
+    pub fn toggle_sort_order(&mut self, field: impl Into<SharedString>) {
+        let field = field.into();
+        self.sort_order = match self.sort_order.take() {
+            Some((key, true)) if key == field => Some((field, false)),
+            Some((key, false)) if key == field => None,
+            _ => Some((field, true)),
+        };
+    }
+
+    /// Converts `self` to the query parameters for the `list` endpoint.
+    pub fn to_map(&self) -> Map {
+        let mut map = Map::new();
+        map.upsert("current_page", self.current_page);
+        map.upsert("page_size", self.page_size);
+        if let Some((field, descending)) = self.sort_order.as_ref() {
+            let order = if *descending { "desc" } else { "asc" };
+            map.upsert("order_by", format!("{field}|{order}"));
+        }
+        for (field, expr) in self.filters.iter() {
+            map.upsert(field.as_ref(), expr.as_str());
+        }
+        map
+    }
+}
+
+impl Default for TableQuery {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            current_page: 1,
+            page_size: 10,
+            sort_order: None,
+            filters: Vec::new(),
+        }
+    }
+}
+
+/// Renders the header cell for the column.
+fn render_header_cell(
+    column: &TableColumn,
+    mut query: Signal<TableQuery>,
+    on_query_change: Option<EventHandler<TableQuery>>,
+) -> Element {
+    let field = column.field.clone();
+    let sort_indicator = match query.read().sort_order.as_ref() {
+        Some((key, true)) if key == &field => " ↓",
+        Some((key, false)) if key == &field => " ↑",
+        _ => "",
+    };
+    if column.sortable {
+        rsx! {
+            th {
+                cursor: "pointer",
+                onclick: move |_| {
+                    let mut query = query.write();
+                    query.toggle_sort_order(field.clone());
+                    query.current_page = 1;
+                    if let Some(handler) = on_query_change.as_ref() {
+                        handler.call(query.clone());
+                    }
+                },
+                "{column.label}{sort_indicator}"
+            }
+        }
+    } else {
+        rsx! {
+            th { "{column.label}" }
+        }
+    }
+}
+
+/// Renders the filter cell for the column.
+fn render_filter_cell(
+    column: &TableColumn,
+    mut query: Signal<TableQuery>,
+    on_query_change: Option<EventHandler<TableQuery>>,
+) -> Element {
+    if !column.filterable {
+        return rsx! { th {} };
+    }
+
+    let field = column.field.clone();
+    let operator = column.filter_operator.clone();
+    rsx! {
+        th {
+            input {
+                class: "input is-small",
+                r#type: "search",
+                onchange: move |event| {
+                    let value = event.value();
+                    let expr = if value.is_empty() {
+                        value
+                    } else if matches!(operator.as_ref(), "like" | "ilike") {
+                        format!("{operator}.%{value}%")
+                    } else {
+                        format!("{operator}.{value}")
+                    };
+                    let mut query = query.write();
+                    query.set_filter(field.clone(), expr);
+                    query.current_page = 1;
+                    if let Some(handler) = on_query_change.as_ref() {
+                        handler.call(query.clone());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Renders the cell of the row for the column.
+fn render_cell(row: &Map, column: &TableColumn) -> Element {
+    if let Some(renderer) = column.renderer {
+        renderer(row, &column.field)
+    } else {
+        let value = format_cell_value(row.get(column.field.as_ref()));
+        rsx! { "{value}" }
+    }
+}
+
+/// Renders the selection cell for the row.
+fn render_selection_cell(
+    row: &Map,
+    row_key: &str,
+    mut selected_keys: Signal<Vec<String>>,
+    on_select: Option<EventHandler<Vec<String>>>,
+) -> Element {
+    let key = format_row_key(row, row_key)?;
+    let checked = selected_keys.read().contains(&key);
+    rsx! {
+        td {
+            input {
+                r#type: "checkbox",
+                checked: checked,
+                onchange: move |event| {
+                    let mut keys = selected_keys.write();
+                    if event.value() == "true" {
+                        if !keys.contains(&key) {
+                            keys.push(key.clone());
+                        }
+                    } else {
+                        keys.retain(|k| k != &key);
+                    }
+                    if let Some(handler) = on_select.as_ref() {
+                        handler.call(keys.clone());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Formats the unique key of the row.
+fn format_row_key(row: &Map, row_key: &str) -> Option<String> {
+    row.get(row_key).map(|value| match value {
+        JsonValue::String(s) => s.to_owned(),
+        _ => value.to_string(),
+    })
+}
+
+/// Formats the cell value as a string.
+fn format_cell_value(value: Option<&JsonValue>) -> String {
+    match value {
+        Some(JsonValue::String(s)) => s.to_owned(),
+        Some(JsonValue::Null) | None => String::new(),
+        Some(JsonValue::Array(values)) => values
+            .iter()
+            .map(|value| format_cell_value(Some(value)))
+            .collect::<Vec<_>>()
+            .join(", "),
+        Some(value) => value.to_string(),
+    }
+}
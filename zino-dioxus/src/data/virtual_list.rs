@@ -0,0 +1,211 @@
+use crate::class::Class;
+use dioxus::prelude::*;
+use zino_core::{extension::JsonObjectExt, JsonValue, Map, Uuid};
+
+/// A function pointer of rendering a row with the index.
+pub type RowRenderer = fn(row: &Map, index: usize) -> Element;
+
+/// A virtual scrolling container which only renders the visible rows.
+///
+/// The heights of rendered rows are measured so that the rows can have dynamic heights.
+/// When the user scrolls near the end, the `on_load_more` handler will be called
+/// to fetch the next page of data, such as the next cursor of the `list` endpoint.
+pub fn VirtualList(props: VirtualListProps) -> Element {
+    let container_id = use_hook(|| format!("virtual-list-{}", Uuid::now_v7()));
+    let mut scroll_top = use_signal(|| 0.0);
+    let mut viewport_height = use_signal(|| f64::from(props.height));
+    let mut measured_heights = use_signal(Map::new);
+    let mut requested_count = use_signal(|| None::<usize>);
+    use_future({
+        let container_id = container_id.clone();
+        move || {
+            let container_id = container_id.clone();
+            async move {
+                let script = format!(
+                    r#"
+                    const container = document.getElementById({});
+                    const report = () => {{
+                        const heights = {{}};
+                        for (const row of container.querySelectorAll("[data-row-index]")) {{
+                            heights[row.dataset.rowIndex] = row.offsetHeight;
+                        }}
+                        dioxus.send({{
+                            scroll_top: container.scrollTop,
+                            client_height: container.clientHeight,
+                            heights,
+                        }});
+                    }};
+                    container.addEventListener("scroll", () => requestAnimationFrame(report), {{ passive: true }});
+                    new ResizeObserver(report).observe(container);
+                    new MutationObserver(report).observe(container, {{ childList: true, subtree: true }});
+                    report();
+                    await new Promise(() => {{}});
+                    "#,
+                    JsonValue::from(container_id.as_str())
+                );
+                let mut evaluator = eval(&script);
+                while let Ok(JsonValue::Object(mut data)) = evaluator.recv().await {
+                    if let Some(value) = data.get_f64("scroll_top") {
+                        scroll_top.set(value);
+                    }
+                    if let Some(value) = data.get_f64("client_height") {
+                        viewport_height.set(value);
+                    }
+                    if let Some(JsonValue::Object(heights)) = data.remove("heights") {
+                        let changed = heights
+                            .iter()
+                            .any(|(key, value)| measured_heights.peek().get(key) != Some(value));
+                        if changed {
+                            measured_heights.write().extend(heights);
+                        }
+                    }
+                }
+            }
+        }
+    });
+
+    let estimated_height = f64::from(props.estimated_row_height);
+    let heights = {
+        let measured_heights = measured_heights.read();
+        (0..props.data.len())
+            .map(|index| {
+                measured_heights
+                    .get_f64(&index.to_string())
+                    .filter(|&height| height > 0.0)
+                    .unwrap_or(estimated_height)
+            })
+            .collect::<Vec<_>>()
+    };
+    let range = VisibleRange::new(&heights, scroll_top(), viewport_height(), props.overscan);
+    let row_count = props.data.len();
+    let should_load = props.has_more && range.end + props.overscan >= row_count;
+    let on_load_more = props.on_load_more;
+    use_effect(use_reactive(
+        (&should_load, &row_count),
+        move |(should_load, row_count)| {
+            if should_load && requested_count.peek().as_ref() != Some(&row_count) {
+                requested_count.set(Some(row_count));
+                if let Some(handler) = on_load_more.as_ref() {
+                    handler.call(());
+                }
+            }
+        },
+    ));
+
+    let render_row = props.render_row;
+    rsx! {
+        div {
+            class: props.class,
+            id: "{container_id}",
+            overflow_y: "auto",
+            height: "{props.height}px",
+            if props.header.is_some() {
+                div {
+                    class: props.header_class,
+                    position: "sticky",
+                    top: "0",
+                    z_index: 1,
+                    { props.header }
+                }
+            }
+            div {
+                height: "{range.top_offset}px",
+            }
+            for index in range.start..range.end {
+                div {
+                    key: "{index}",
+                    "data-row-index": "{index}",
+                    { render_row(&props.data[index], index) }
+                }
+            }
+            div {
+                height: "{range.bottom_offset}px",
+            }
+            if props.has_more {
+                div {
+                    class: "has-text-centered py-2",
+                    { props.loading }
+                }
+            }
+        }
+    }
+}
+
+/// The [`VirtualList`] properties struct for the configuration of the component.
+#[derive(Clone, PartialEq, Props)]
+pub struct VirtualListProps {
+    /// The class attribute for the component.
+    #[props(into, default = "virtual-list".into())]
+    pub class: Class,
+    /// A class to apply to the sticky header.
+    #[props(into, default = "has-background-white".into())]
+    pub header_class: Class,
+    /// The height of the container in pixels.
+    #[props(default = 480)]
+    pub height: u32,
+    /// The estimated height of a row in pixels before it is measured.
+    #[props(default = 40)]
+    pub estimated_row_height: u32,
+    /// Number of the rows to render outside of the visible area.
+    #[props(default = 5)]
+    pub overscan: usize,
+    /// The data rows.
+    pub data: Vec<Map>,
+    /// The row renderer.
+    pub render_row: RowRenderer,
+    /// The sticky header.
+    #[props(default)]
+    pub header: Element,
+    /// The content to render when there are more rows to be loaded.
+    #[props(default)]
+    pub loading: Element,
+    /// A flag to determine whether there are more rows to be loaded or not.
+    #[props(default)]
+    pub has_more: bool,
+    /// An event handler to be called when the user scrolls near the end.
+    pub on_load_more: Option<EventHandler<()>>,
+}
+
+/// The range of visible rows.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct VisibleRange {
+    /// The start index.
+    start: usize,
+    /// The end index (exclusive).
+    end: usize,
+    /// The height of the rows before the start index.
+    top_offset: f64,
+    /// The height of the rows after the end index.
+    bottom_offset: f64,
+}
+
+impl VisibleRange {
+    /// Computes the range of visible rows with the heights.
+    fn new(heights: &[f64], scroll_top: f64, viewport_height: f64, overscan: usize) -> Self {
+        let len = heights.len();
+        let mut offset = 0.0;
+        let mut first_visible = len;
+        let mut last_visible = len;
+        for (index, height) in heights.iter().enumerate() {
+            if first_visible == len && offset + height > scroll_top {
+                first_visible = index;
+            }
+            if offset >= scroll_top + viewport_height {
+                last_visible = index;
+                break;
+            }
+            offset += height;
+        }
+
+        let start = first_visible.saturating_sub(overscan);
+        let end = last_visible.saturating_add(overscan).min(len);
+        let top_offset = heights[..start].iter().sum();
+        let bottom_offset = heights[end..].iter().sum();
+        Self {
+            start,
+            end,
+            top_offset,
+            bottom_offset,
+        }
+    }
+}
@@ -3,9 +3,11 @@
 mod message;
 mod modal;
 mod notification;
+mod notifier;
 mod result;
 
 pub use message::{Message, MessageProps};
 pub use modal::{ModalCard, ModalCardProps, ModalData};
 pub use notification::{Notification, NotificationProps};
+pub use notifier::{use_notifier, Notifier, NotifierProvider, NotifierProviderProps};
 pub use result::{OperationResult, OperationResultProps};
@@ -0,0 +1,193 @@
+use crate::class::Class;
+use dioxus::prelude::*;
+use std::time::Duration;
+use zino_core::SharedString;
+
+/// A provider of the global [`Notifier`] which renders the stacking toasts.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use dioxus::prelude::*;
+/// use zino_dioxus::prelude::*;
+///
+/// fn App() -> Element {
+///     rsx! {
+///         NotifierProvider {
+///             Router::<Route> {}
+///         }
+///     }
+/// }
+///
+/// fn UserForm() -> Element {
+///     let notifier = use_notifier();
+///     rsx! {
+///         button {
+///             onclick: move |_| notifier.notify_success("The user has been saved"),
+///             "Save"
+///         }
+///     }
+/// }
+/// ```
+pub fn NotifierProvider(props: NotifierProviderProps) -> Element {
+    let notifier = use_context_provider(|| Notifier {
+        toasts: Signal::new(Vec::new()),
+        next_id: Signal::new(0),
+        duration: props.duration,
+        max_toasts: props.max_toasts,
+    });
+    rsx! {
+        { props.children }
+        div {
+            class: props.class,
+            position: "fixed",
+            top: "4rem",
+            right: "0.75rem",
+            z_index: 99,
+            for toast in notifier.toasts.read().iter() {
+                div {
+                    key: "{toast.id}",
+                    class: props.toast_class.clone(),
+                    class: "is-{toast.color}",
+                    class: if !props.theme.is_empty() { "is-{props.theme}" },
+                    button {
+                        class: props.close_class.clone(),
+                        onclick: {
+                            let id = toast.id;
+                            move |_| notifier.dismiss(id)
+                        }
+                    }
+                    "{toast.message}"
+                }
+            }
+        }
+    }
+}
+
+/// The [`NotifierProvider`] properties struct for the configuration of the component.
+#[derive(Clone, PartialEq, Props)]
+pub struct NotifierProviderProps {
+    /// The class attribute for the toast container.
+    #[props(into, default)]
+    pub class: Class,
+    /// A class to apply to the toast element.
+    #[props(into, default = "notification".into())]
+    pub toast_class: Class,
+    /// A class to apply to the `close` button element.
+    #[props(into, default = "delete".into())]
+    pub close_class: Class,
+    /// The theme of the toasts: `light`.
+    #[props(into, default)]
+    pub theme: SharedString,
+    /// A duration in milliseconds before a toast is dismissed automatically.
+    /// A zero value means the toasts should be dismissed manually.
+    #[props(default = 3000)]
+    pub duration: u64,
+    /// The maximum number of toasts to be displayed at the same time.
+    #[props(default = 5)]
+    pub max_toasts: usize,
+    /// The children to render within the component.
+    children: Element,
+}
+
+/// A global notifier for displaying toasts.
+/// It should be obtained by [`use_notifier()`] within a [`NotifierProvider`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Notifier {
+    /// The toasts.
+    toasts: Signal<Vec<Toast>>,
+    /// The ID for the next toast.
+    next_id: Signal<usize>,
+    /// The duration in milliseconds.
+    duration: u64,
+    /// The maximum number of toasts.
+    max_toasts: usize,
+}
+
+impl Notifier {
+    /// Displays a toast with the color:
+    /// `primary` | `link` | `info` | `success` | `warning` | `danger`.
+    pub fn notify(mut self, color: impl Into<SharedString>, message: impl Into<SharedString>) {
+        let id = self.next_id();
+        self.next_id.set(id + 1);
+
+        {
+            let mut toasts = self.toasts.write();
+            toasts.push(Toast {
+                id,
+                color: color.into(),
+                message: message.into(),
+            });
+            if self.max_toasts > 0 && toasts.len() > self.max_toasts {
+                let excess = toasts.len() - self.max_toasts;
+                toasts.drain(..excess);
+            }
+        }
+        if self.duration > 0 {
+            let duration = self.duration;
+            spawn(async move {
+                tokio::time::sleep(Duration::from_millis(duration)).await;
+                self.dismiss(id);
+            });
+        }
+    }
+
+    /// Displays an info toast.
+    #[inline]
+    pub fn notify_info(self, message: impl Into<SharedString>) {
+        self.notify("info", message);
+    }
+
+    /// Displays a success toast.
+    #[inline]
+    pub fn notify_success(self, message: impl Into<SharedString>) {
+        self.notify("success", message);
+    }
+
+    /// Displays a warning toast.
+    #[inline]
+    pub fn notify_warning(self, message: impl Into<SharedString>) {
+        self.notify("warning", message);
+    }
+
+    /// Displays an error toast.
+    #[inline]
+    pub fn notify_error(self, message: impl Into<SharedString>) {
+        self.notify("danger", message);
+    }
+
+    /// Dismisses a toast with the ID.
+    #[inline]
+    pub fn dismiss(mut self, id: usize) {
+        self.toasts.write().retain(|toast| toast.id != id);
+    }
+
+    /// Dismisses all the toasts.
+    #[inline]
+    pub fn dismiss_all(mut self) {
+        self.toasts.write().clear();
+    }
+
+    /// Returns the ID for the next toast.
+    #[inline]
+    fn next_id(&self) -> usize {
+        *self.next_id.peek()
+    }
+}
+
+/// Obtains the global [`Notifier`] provided by the [`NotifierProvider`].
+#[inline]
+pub fn use_notifier() -> Notifier {
+    use_context::<Notifier>()
+}
+
+/// A toast message.
+#[derive(Debug, Clone, PartialEq)]
+struct Toast {
+    /// The toast ID.
+    id: usize,
+    /// The color.
+    color: SharedString,
+    /// The message.
+    message: SharedString,
+}
@@ -1,13 +1,35 @@
+use super::FieldBinding;
 use crate::class::Class;
 use dioxus::prelude::*;
 
 /// The text input and its variations.
+///
+/// If the `binding` is specified, the value will be synchronized with the form state
+/// and the control will be marked as `is-danger` when the field is invalid.
 pub fn Input(props: InputProps) -> Element {
+    let binding = props.binding;
+    let value = binding.as_ref().map(|binding| binding.value());
+    let invalid = binding
+        .as_ref()
+        .is_some_and(|binding| binding.error().is_some());
+    let input_binding = binding.clone();
     rsx! {
        input {
             class: props.class,
+            class: if invalid { "is-danger" },
             r#type: "text",
+            value: value,
             ..props.attributes,
+            oninput: move |event| {
+                if let Some(binding) = input_binding.as_ref() {
+                    binding.set_value(event.value());
+                }
+            },
+            onblur: move |_event| {
+                if let Some(binding) = binding.as_ref() {
+                    binding.touch();
+                }
+            },
             onchange: move |event| async move {
                 if let Some(handler) = props.on_change.as_ref() {
                     handler.call(event.value());
@@ -25,6 +47,8 @@ pub struct InputProps {
     pub class: Class,
     /// An event handler to be called when the input state is changed.
     pub on_change: Option<EventHandler<String>>,
+    /// An optional binding of the field in a form state.
+    pub binding: Option<FieldBinding>,
     /// Spreading the props of the `input` element.
     #[props(extends = input)]
     attributes: Vec<Attribute>,
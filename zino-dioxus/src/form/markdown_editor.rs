@@ -0,0 +1,239 @@
+use crate::class::Class;
+use dioxus::prelude::*;
+use markdown::{to_html_with_options, Options};
+use zino_core::{
+    extension::{JsonObjectExt, JsonValueExt},
+    file::NamedFile,
+    JsonValue, Map, SharedString, Uuid,
+};
+
+/// A markdown editor with a toolbar and a preview pane.
+///
+/// The preview is sanitized: raw HTML in the content is escaped and the URLs
+/// with dangerous protocols such as `javascript:` are removed.
+/// If the `upload_url` is specified, the images pasted into the editor will be uploaded
+/// to the file controller and the links will be inserted at the cursor.
+pub fn MarkdownEditor(props: MarkdownEditorProps) -> Element {
+    let editor_id = use_hook(|| format!("markdown-editor-{}", Uuid::now_v7()));
+    let mut content = use_signal(|| props.content.to_string());
+    let mut show_preview = use_signal(|| props.preview);
+    let on_change = props.on_change;
+    let upload_url = props.upload_url.clone();
+    use_future({
+        let editor_id = editor_id.clone();
+        move || {
+            let editor_id = editor_id.clone();
+            let upload_url = upload_url.clone();
+            async move {
+                let Some(upload_url) = upload_url else {
+                    return;
+                };
+                let script = format!(
+                    r#"
+                    const editor = document.getElementById({});
+                    editor.addEventListener("paste", (event) => {{
+                        for (const item of event.clipboardData.items) {{
+                            if (item.kind === "file" && item.type.startsWith("image/")) {{
+                                event.preventDefault();
+                                const file = item.getAsFile();
+                                const reader = new FileReader();
+                                reader.onload = () => dioxus.send({{
+                                    name: file.name,
+                                    data: reader.result.split(",")[1],
+                                }});
+                                reader.readAsDataURL(file);
+                            }}
+                        }}
+                    }});
+                    await new Promise(() => {{}});
+                    "#,
+                    JsonValue::from(editor_id.as_str())
+                );
+                let mut evaluator = eval(&script);
+                while let Ok(value) = evaluator.recv().await {
+                    let file_name = value.get("name").and_then(|v| v.as_str());
+                    let data = value.get("data").and_then(|v| v.as_str());
+                    if let (Some(file_name), Some(data)) = (file_name, data) {
+                        if let Some(url) = upload_image(&upload_url, file_name, data).await {
+                            let link = format!("![{file_name}]({url})");
+                            if let Some(value) = insert_text(&editor_id, &link, "", "").await {
+                                content.set(value.clone());
+                                if let Some(handler) = on_change.as_ref() {
+                                    handler.call(value);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    });
+
+    let html = to_html_with_options(content.read().as_str(), &Options::gfm()).unwrap_or_default();
+    rsx! {
+        div {
+            class: props.class,
+            div {
+                class: "buttons has-addons mb-2",
+                for (label, title, prefix, suffix, placeholder) in TOOLBAR_ACTIONS {
+                    button {
+                        class: props.button_class.clone(),
+                        r#type: "button",
+                        title: title,
+                        onclick: {
+                            let editor_id = editor_id.clone();
+                            move |_| {
+                                let editor_id = editor_id.clone();
+                                async move {
+                                    if let Some(value) = insert_text(&editor_id, prefix, suffix, placeholder).await {
+                                        content.set(value.clone());
+                                        if let Some(handler) = on_change.as_ref() {
+                                            handler.call(value);
+                                        }
+                                    }
+                                }
+                            }
+                        },
+                        "{label}"
+                    }
+                }
+                button {
+                    class: props.button_class.clone(),
+                    class: if show_preview() { "is-selected" },
+                    r#type: "button",
+                    onclick: move |_| show_preview.toggle(),
+                    "Preview"
+                }
+            }
+            div {
+                class: "columns",
+                div {
+                    class: "column",
+                    textarea {
+                        class: "textarea",
+                        id: "{editor_id}",
+                        name: "{props.name}",
+                        rows: "{props.rows}",
+                        placeholder: "{props.placeholder}",
+                        value: "{content}",
+                        oninput: move |event| {
+                            let value = event.value();
+                            content.set(value.clone());
+                            if let Some(handler) = on_change.as_ref() {
+                                handler.call(value);
+                            }
+                        }
+                    }
+                }
+                if show_preview() {
+                    div {
+                        class: "column",
+                        div {
+                            class: props.preview_class,
+                            dangerous_inner_html: html,
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The [`MarkdownEditor`] properties struct for the configuration of the component.
+#[derive(Clone, PartialEq, Props)]
+pub struct MarkdownEditorProps {
+    /// The class attribute for the component.
+    #[props(into, default = "markdown-editor".into())]
+    pub class: Class,
+    /// A class to apply to the toolbar buttons.
+    #[props(into, default = "button is-small".into())]
+    pub button_class: Class,
+    /// A class to apply to the preview pane.
+    #[props(into, default = "content markdown".into())]
+    pub preview_class: Class,
+    /// The name of the `textarea` element.
+    #[props(into, default)]
+    pub name: SharedString,
+    /// The initial content.
+    #[props(into, default)]
+    pub content: SharedString,
+    /// The placeholder text.
+    #[props(into, default)]
+    pub placeholder: SharedString,
+    /// Number of the visible text lines.
+    #[props(default = 12)]
+    pub rows: u32,
+    /// A flag to determine whether the preview pane is displayed or not.
+    #[props(default = true)]
+    pub preview: bool,
+    /// An optional URL of the file controller for uploading the pasted images.
+    #[props(into)]
+    pub upload_url: Option<SharedString>,
+    /// An event handler to be called when the content is changed.
+    pub on_change: Option<EventHandler<String>>,
+}
+
+/// Toolbar actions in the form `(label, title, prefix, suffix, placeholder)`.
+const TOOLBAR_ACTIONS: [(&str, &str, &str, &str, &str); 8] = [
+    ("B", "Bold", "**", "**", "bold text"),
+    ("I", "Italic", "_", "_", "italic text"),
+    ("H", "Heading", "\n## ", "\n", "Heading"),
+    ("Link", "Link", "[", "](https://)", "link text"),
+    ("Code", "Code", "`", "`", "code"),
+    ("List", "List", "\n- ", "\n", "list item"),
+    ("Quote", "Quote", "\n> ", "\n", "quote"),
+    ("Image", "Image", "![", "](https://)", "alt text"),
+];
+
+/// Inserts the text around the selection of the editor and returns the new content.
+async fn insert_text(
+    editor_id: &str,
+    prefix: &str,
+    suffix: &str,
+    placeholder: &str,
+) -> Option<String> {
+    let script = format!(
+        r#"
+        const editor = document.getElementById({});
+        const start = editor.selectionStart;
+        const end = editor.selectionEnd;
+        const selection = editor.value.substring(start, end) || {};
+        editor.setRangeText({} + selection + {}, start, end, "end");
+        editor.focus();
+        return editor.value;
+        "#,
+        JsonValue::from(editor_id),
+        JsonValue::from(placeholder),
+        JsonValue::from(prefix),
+        JsonValue::from(suffix),
+    );
+    eval(&script)
+        .join()
+        .await
+        .ok()
+        .and_then(|value| value.as_str().map(|s| s.to_owned()))
+}
+
+/// Uploads the image encoded as a base64 string and returns the URL.
+async fn upload_image(upload_url: &str, file_name: &str, data: &str) -> Option<String> {
+    let mut file = NamedFile::new(file_name);
+    file.set_field_name("file");
+    file.read_base64_string(data).ok()?;
+
+    let response = file.upload_to(upload_url, None).await.ok()?;
+    let body = response.text().await.ok()?.parse::<JsonValue>().ok()?;
+    let data = body.into_map_opt()?.remove("data")?.into_map_opt()?;
+    parse_upload_url(&data)
+}
+
+/// Parses the URL of the uploaded file in the form `{ url }` or `{ files: [{ url }] }`.
+fn parse_upload_url(data: &Map) -> Option<String> {
+    data.get_str("url")
+        .or_else(|| {
+            data.get_array("files")?
+                .first()?
+                .as_object()?
+                .get_str("url")
+        })
+        .map(|s| s.to_owned())
+}
@@ -7,9 +7,13 @@ mod checkbox;
 mod field;
 mod file;
 mod input;
+mod markdown_editor;
+mod model_form;
 mod radio;
 mod select;
+mod state;
 mod textarea;
+mod upload;
 
 pub use button::{Button, ButtonProps};
 pub use checkbox::{Checkbox, CheckboxProps};
@@ -19,9 +23,13 @@ pub use field::{
 };
 pub use file::{FileUpload, FileUploadProps};
 pub use input::{Input, InputProps};
+pub use markdown_editor::{MarkdownEditor, MarkdownEditorProps};
+pub use model_form::{normalize_form_data, validate_form_data, ModelForm, ModelFormProps};
 pub use radio::{Radio, RadioProps};
 pub use select::{DataSelect, DataSelectProps};
+pub use state::{use_form_state, FieldBinding, FormState};
 pub use textarea::{Textarea, TextareaProps};
+pub use upload::{Upload, UploadProgress, UploadProps};
 
 /// An interface for the data entries.
 pub trait DataEntry {
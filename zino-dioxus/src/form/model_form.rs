@@ -0,0 +1,350 @@
+use super::{DataSelect, FormField, FormFieldContainer};
+use crate::{class::Class, extension::FormDataExt};
+use dioxus::prelude::*;
+use zino_core::{
+    extension::{JsonObjectExt, JsonValueExt},
+    validation::Validation,
+    JsonValue, Map, SharedString,
+};
+
+/// A form generated from the model definition.
+///
+/// The definition is returned by the `definition` endpoint of the `DefaultController`.
+/// The form data will be validated before submitting, and the `on_submit` handler
+/// should be used to request the `new` or `update` endpoint.
+pub fn ModelForm(props: ModelFormProps) -> Element {
+    let mut errors = use_signal(Map::new);
+    let definition = props.definition.clone();
+    let required_fields = props
+        .definition
+        .parse_str_array("required")
+        .unwrap_or_default();
+    let properties = props
+        .definition
+        .get_object("properties")
+        .map(|properties| {
+            properties
+                .iter()
+                .filter_map(|(field, property)| {
+                    property
+                        .as_object()
+                        .map(|property| (field.as_str(), property))
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let on_submit = props.on_submit;
+    rsx! {
+        form {
+            class: props.class,
+            prevent_default: "onsubmit",
+            onsubmit: move |event| {
+                let mut data = event.to_map();
+                normalize_form_data(&definition, &mut data);
+
+                let validation = validate_form_data(&definition, &data);
+                if validation.is_success() {
+                    errors.write().clear();
+                    if let Some(handler) = on_submit.as_ref() {
+                        handler.call(data);
+                    }
+                } else {
+                    errors.set(validation.into_map());
+                }
+            },
+            for (field, property) in properties {
+                {
+                    render_form_field(
+                        field,
+                        property,
+                        required_fields.contains(&field),
+                        props.data.get(field),
+                        props.options.iter().find_map(|(key, options)| (key == field).then_some(options)),
+                        errors.read().get_str(field),
+                    )
+                }
+            }
+            div {
+                class: "field is-grouped is-grouped-right",
+                div {
+                    class: "control",
+                    button {
+                        class: props.submit_class,
+                        r#type: "submit",
+                        { props.submit_text }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The [`ModelForm`] properties struct for the configuration of the component.
+#[derive(Clone, PartialEq, Props)]
+pub struct ModelFormProps {
+    /// The class attribute for the component.
+    #[props(into, default)]
+    pub class: Class,
+    /// The model definition.
+    pub definition: Map,
+    /// The initial data for editing a model.
+    #[props(default)]
+    pub data: Map,
+    /// The options for the reference fields in the form `(field, [(value, label)])`.
+    #[props(default)]
+    pub options: Vec<(SharedString, Vec<(String, String)>)>,
+    /// A class to apply to the `submit` button.
+    #[props(into, default = "button is-primary".into())]
+    pub submit_class: Class,
+    /// The text for the `submit` button.
+    #[props(into, default = "Submit".into())]
+    pub submit_text: SharedString,
+    /// An event handler to be called when the form data is validated and submitted.
+    pub on_submit: Option<EventHandler<Map>>,
+}
+
+/// Normalizes the form data according to the types in the model definition.
+pub fn normalize_form_data(definition: &Map, data: &mut Map) {
+    let Some(properties) = definition.get_object("properties") else {
+        return;
+    };
+    for (field, property) in properties {
+        let Some(property) = property.as_object() else {
+            continue;
+        };
+        if !data.contains_key(field) {
+            if property.get_str("type") == Some("boolean") {
+                data.upsert(field, false);
+            }
+            continue;
+        }
+
+        let value = match data.get(field) {
+            Some(JsonValue::String(s)) => s.trim().to_owned(),
+            _ => continue,
+        };
+        if value.is_empty() {
+            data.remove(field);
+            continue;
+        }
+
+        let normalized_value: JsonValue = match property.get_str("type") {
+            Some("boolean") => matches!(value.as_str(), "true" | "on").into(),
+            Some("integer") => match value.parse::<i64>() {
+                Ok(value) => value.into(),
+                Err(_) => continue,
+            },
+            Some("number") => match value.parse::<f64>() {
+                Ok(value) => value.into(),
+                Err(_) => continue,
+            },
+            Some("array") => value
+                .split(',')
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .collect::<Vec<_>>()
+                .into(),
+            Some("object") => match value.parse::<JsonValue>() {
+                Ok(value) => value,
+                Err(_) => continue,
+            },
+            _ => continue,
+        };
+        data.upsert(field, normalized_value);
+    }
+}
+
+/// Validates the form data with the rules in the model definition,
+/// which mirrors the validation on the server side.
+pub fn validate_form_data(definition: &Map, data: &Map) -> Validation {
+    let mut validation = Validation::new();
+    let required_fields = definition.parse_str_array("required").unwrap_or_default();
+    let Some(properties) = definition.get_object("properties") else {
+        return validation;
+    };
+    for (field, property) in properties {
+        let Some(property) = property.as_object() else {
+            continue;
+        };
+        let Some(value) = data.get(field).filter(|v| !v.is_ignorable()) else {
+            if required_fields.contains(&field.as_str()) {
+                validation.record(field.to_owned(), "it should be nonempty");
+            }
+            continue;
+        };
+        match value {
+            JsonValue::String(value) => {
+                let length = value.chars().count();
+                if let Some(min_length) = property.get_usize("minLength") {
+                    if length < min_length {
+                        let message = format!("the length should be at least {min_length}");
+                        validation.record(field.to_owned(), message);
+                    }
+                }
+                if let Some(max_length) = property.get_usize("maxLength") {
+                    if length > max_length {
+                        let message = format!("the length should be at most {max_length}");
+                        validation.record(field.to_owned(), message);
+                    }
+                }
+                if let Some(values) = property.parse_str_array("enum") {
+                    if !values.contains(&value.as_str()) {
+                        let message = format!("the value `{value}` is not allowed");
+                        validation.record(field.to_owned(), message);
+                    }
+                }
+                if let Some(format) = property.get_str("format") {
+                    if !matches!(format, "binary" | "password") {
+                        validation.validate_format(field.to_owned(), value, format);
+                    }
+                }
+            }
+            JsonValue::Number(number) => {
+                if let Some(value) = number.as_f64() {
+                    if let Some(minimum) = property.get_f64("minimum") {
+                        if value < minimum {
+                            let message = format!("the value should be at least {minimum}");
+                            validation.record(field.to_owned(), message);
+                        }
+                    }
+                    if let Some(maximum) = property.get_f64("maximum") {
+                        if value > maximum {
+                            let message = format!("the value should be at most {maximum}");
+                            validation.record(field.to_owned(), message);
+                        }
+                    }
+                }
+            }
+            JsonValue::Array(values) => {
+                let length = values.len();
+                if let Some(min_items) = property.get_usize("minItems") {
+                    if length < min_items {
+                        let message = format!("the length should be at least {min_items}");
+                        validation.record(field.to_owned(), message);
+                    }
+                }
+                if let Some(max_items) = property.get_usize("maxItems") {
+                    if length > max_items {
+                        let message = format!("the length should be at most {max_items}");
+                        validation.record(field.to_owned(), message);
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+    validation
+}
+
+/// Renders the form field for the property.
+fn render_form_field(
+    field: &str,
+    property: &Map,
+    required: bool,
+    value: Option<&JsonValue>,
+    options: Option<&Vec<(String, String)>>,
+    error: Option<&str>,
+) -> Element {
+    let label_text = property
+        .get_str("title")
+        .or_else(|| property.get_str("description"))
+        .unwrap_or(field)
+        .to_owned();
+    let value = match value {
+        Some(JsonValue::String(s)) => s.to_owned(),
+        Some(JsonValue::Array(values)) => values
+            .iter()
+            .map(|v| {
+                v.as_str()
+                    .map(|s| s.to_owned())
+                    .unwrap_or_else(|| v.to_string())
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        Some(JsonValue::Null) | None => property
+            .get("default")
+            .map(|v| {
+                v.as_str()
+                    .map(|s| s.to_owned())
+                    .unwrap_or_else(|| v.to_string())
+            })
+            .unwrap_or_default(),
+        Some(value) => value.to_string(),
+    };
+    let name = field.to_owned();
+    let options = if let Some(options) = options {
+        Some(options.clone())
+    } else {
+        property.parse_str_array("enum").map(|values| {
+            values
+                .into_iter()
+                .map(|value| (value.to_owned(), value.to_owned()))
+                .collect::<Vec<_>>()
+        })
+    };
+    let input_type = match (property.get_str("type"), property.get_str("format")) {
+        (Some("boolean"), _) => "checkbox",
+        (Some("integer" | "number"), _) => "number",
+        (_, Some("date")) => "date",
+        (_, Some("date-time")) => "datetime-local",
+        (_, Some("time")) => "time",
+        (_, Some("email")) => "email",
+        (_, Some("uri")) => "url",
+        (_, Some("password")) => "password",
+        _ => "text",
+    };
+    let is_object = property.get_str("type") == Some("object");
+    rsx! {
+        FormFieldContainer {
+            label: label_text,
+            FormField {
+                if let Some(options) = options {
+                    DataSelect {
+                        name: name,
+                        options: options,
+                        selected: value,
+                        required: required,
+                        fullwidth: true,
+                    }
+                } else if input_type == "checkbox" {
+                    label {
+                        class: "checkbox",
+                        input {
+                            r#type: "checkbox",
+                            name: name,
+                            value: "true",
+                            checked: value == "true",
+                        }
+                    }
+                } else if is_object {
+                    textarea {
+                        class: "textarea",
+                        class: if error.is_some() { "is-danger" },
+                        name: name,
+                        required: required,
+                        initial_value: value,
+                    }
+                } else {
+                    input {
+                        class: "input",
+                        class: if error.is_some() { "is-danger" },
+                        r#type: input_type,
+                        name: name,
+                        required: required,
+                        initial_value: value,
+                        min: property.get("minimum").map(|v| v.to_string()),
+                        max: property.get("maximum").map(|v| v.to_string()),
+                        minlength: property.get_usize("minLength").map(|n| n.to_string()),
+                        maxlength: property.get_usize("maxLength").map(|n| n.to_string()),
+                    }
+                }
+                if let Some(error) = error {
+                    p {
+                        class: "help is-danger",
+                        "{error}"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,12 +1,23 @@
-use super::DataEntry;
+use super::{DataEntry, FieldBinding};
 use crate::class::Class;
 use dioxus::prelude::*;
 use zino_core::SharedString;
 
 /// A control that provides a menu of data entries.
+///
+/// If the `binding` is specified, the selected value will be synchronized with the form state.
 pub fn DataSelect<T: DataEntry + Clone + PartialEq>(props: DataSelectProps<T>) -> Element {
     let options = props.options;
-    let selected_value = props.selected;
+    let binding = props.binding;
+    let selected_value = binding
+        .as_ref()
+        .map(|binding| binding.value())
+        .filter(|value| !value.is_empty())
+        .map(SharedString::from)
+        .unwrap_or(props.selected);
+    let invalid = binding
+        .as_ref()
+        .is_some_and(|binding| binding.error().is_some());
     let required = props.required;
     let selected_option = options
         .iter()
@@ -19,6 +30,7 @@ pub fn DataSelect<T: DataEntry + Clone + PartialEq>(props: DataSelectProps<T>) -
         div {
             class: props.class,
             class: if props.fullwidth { "is-fullwidth" },
+            class: if invalid { "is-danger" },
             select {
                 name: props.name.into_owned(),
                 required: required,
@@ -30,6 +42,10 @@ pub fn DataSelect<T: DataEntry + Clone + PartialEq>(props: DataSelectProps<T>) -
                     }
                 },
                 onchange: move |event| {
+                    if let Some(binding) = binding.as_ref() {
+                        binding.touch();
+                        binding.set_value(event.value());
+                    }
                     if let Some(handler) = props.on_select.as_ref() {
                         let value = event.value();
                         if let Some(entry) = entries.iter().find(|d| d.value() == value) {
@@ -81,4 +97,6 @@ pub struct DataSelectProps<T: Clone + PartialEq + 'static> {
     pub empty: SharedString,
     /// An event handler to be called when the choice is selected.
     pub on_select: Option<EventHandler<T>>,
+    /// An optional binding of the field in a form state.
+    pub binding: Option<FieldBinding>,
 }
@@ -0,0 +1,321 @@
+use dioxus::prelude::*;
+use std::{collections::HashSet, future::Future, pin::Pin, rc::Rc};
+use zino_core::{
+    extension::{JsonObjectExt, JsonValueExt},
+    validation::Validation,
+    JsonValue, Map, SharedString,
+};
+
+/// A boxed future returned by the async validators.
+type ValidationFuture = Pin<Box<dyn Future<Output = Result<(), String>>>>;
+
+/// A validator for the field value.
+#[derive(Clone)]
+enum FieldValidator {
+    /// A sync validator.
+    Sync(Rc<dyn Fn(&JsonValue) -> Result<(), String>>),
+    /// An async validator.
+    Async(Rc<dyn Fn(JsonValue) -> ValidationFuture>),
+}
+
+/// Creates a [`FormState`] with the initial values.
+pub fn use_form_state(init: impl FnOnce() -> Map) -> FormState {
+    let initial_values = use_signal(init);
+    let values = use_signal(|| initial_values.peek().clone());
+    FormState {
+        initial_values,
+        values,
+        touched_fields: use_signal(HashSet::new),
+        errors: use_signal(Map::new),
+        validators: use_signal(Vec::new),
+        pending_validations: use_signal(|| 0),
+    }
+}
+
+/// The state of a form with per-field values, flags and validation errors.
+///
+/// The errors are recorded as a map of the field and the message,
+/// which is the same shape as the `data` of a server-side validation response.
+/// Sync validators are run before async validators, and the async validators
+/// of a field will be skipped if any sync validator fails.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let form_state = use_form_state(Map::new);
+/// use_hook(|| {
+///     form_state.add_validator("name", |value| {
+///         if value.is_ignorable() {
+///             Err("it should be nonempty".to_owned())
+///         } else {
+///             Ok(())
+///         }
+///     });
+///     form_state.add_async_validator("name", |value| async move {
+///         let url = format!("/user/list?name={}&limit=1", value.to_string_unquoted());
+///         if check_exists(&url).await {
+///             Err("it should be unique".to_owned())
+///         } else {
+///             Ok(())
+///         }
+///     });
+/// });
+/// rsx! {
+///     Input { binding: form_state.bind("name"), name: "name" }
+/// }
+/// ```
+#[derive(Clone, Copy, PartialEq)]
+pub struct FormState {
+    /// The initial values.
+    initial_values: Signal<Map>,
+    /// The current values.
+    values: Signal<Map>,
+    /// The fields which have been touched.
+    touched_fields: Signal<HashSet<SharedString>>,
+    /// The validation errors.
+    errors: Signal<Map>,
+    /// The field validators.
+    validators: Signal<Vec<(SharedString, FieldValidator)>>,
+    /// Number of the pending async validations.
+    pending_validations: Signal<usize>,
+}
+
+impl FormState {
+    /// Adds a sync validator for the field.
+    /// The validator should return an error message if the value is invalid.
+    ///
+    /// Validators should be added only once, e.g. within `use_hook`.
+    pub fn add_validator<F>(mut self, field: impl Into<SharedString>, validator: F)
+    where
+        F: Fn(&JsonValue) -> Result<(), String> + 'static,
+    {
+        let validator = FieldValidator::Sync(Rc::new(validator));
+        self.validators.write().push((field.into(), validator));
+    }
+
+    /// Adds an async validator for the field, such as a uniqueness check against an endpoint.
+    /// The validator should return an error message if the value is invalid.
+    pub fn add_async_validator<F, Fut>(mut self, field: impl Into<SharedString>, validator: F)
+    where
+        F: Fn(JsonValue) -> Fut + 'static,
+        Fut: Future<Output = Result<(), String>> + 'static,
+    {
+        let validator = FieldValidator::Async(Rc::new(move |value| Box::pin(validator(value))));
+        self.validators.write().push((field.into(), validator));
+    }
+
+    /// Returns a binding of the field for the form controls.
+    #[inline]
+    pub fn bind(self, field: impl Into<SharedString>) -> FieldBinding {
+        FieldBinding {
+            form_state: self,
+            field: field.into(),
+        }
+    }
+
+    /// Returns a clone of the field value.
+    #[inline]
+    pub fn value(&self, field: &str) -> Option<JsonValue> {
+        self.values.read().get(field).cloned()
+    }
+
+    /// Returns a clone of the current values.
+    #[inline]
+    pub fn values(&self) -> Map {
+        self.values.read().clone()
+    }
+
+    /// Sets the field value and validates it in the background.
+    pub fn set_value(mut self, field: &str, value: impl Into<JsonValue>) {
+        self.values.write().upsert(field, value);
+
+        let field = field.to_owned();
+        spawn(async move {
+            self.validate_field(&field).await;
+        });
+    }
+
+    /// Marks the field as touched.
+    #[inline]
+    pub fn touch(mut self, field: &str) {
+        if !self.touched_fields.peek().contains(field) {
+            self.touched_fields.write().insert(field.to_owned().into());
+        }
+    }
+
+    /// Returns `true` if the field has been touched.
+    #[inline]
+    pub fn is_touched(&self, field: &str) -> bool {
+        self.touched_fields.read().contains(field)
+    }
+
+    /// Returns `true` if the field value differs from the initial value.
+    #[inline]
+    pub fn is_dirty(&self, field: &str) -> bool {
+        self.values.read().get(field) != self.initial_values.read().get(field)
+    }
+
+    /// Returns `true` if any field value differs from the initial value.
+    #[inline]
+    pub fn is_form_dirty(&self) -> bool {
+        *self.values.read() != *self.initial_values.read()
+    }
+
+    /// Returns the error message of the field.
+    #[inline]
+    pub fn error(&self, field: &str) -> Option<String> {
+        self.errors.read().get_str(field).map(|s| s.to_owned())
+    }
+
+    /// Returns a clone of the errors in the form `{ field: message }`.
+    #[inline]
+    pub fn errors(&self) -> Map {
+        self.errors.read().clone()
+    }
+
+    /// Sets the errors in the form `{ field: message }`,
+    /// such as the `data` of a server-side validation response.
+    #[inline]
+    pub fn set_errors(mut self, errors: Map) {
+        self.touched_fields
+            .write()
+            .extend(errors.keys().map(|key| key.to_owned().into()));
+        self.errors.set(errors);
+    }
+
+    /// Returns `true` if there are no validation errors.
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.errors.read().is_empty()
+    }
+
+    /// Returns `true` if there are pending async validations.
+    #[inline]
+    pub fn is_validating(&self) -> bool {
+        *self.pending_validations.read() > 0
+    }
+
+    /// Validates the field value and returns the error message if it is invalid.
+    pub async fn validate_field(mut self, field: &str) -> Option<String> {
+        let current_value = self.values.peek().get(field).cloned();
+        let value = current_value.clone().unwrap_or_default();
+        let validators = self
+            .validators
+            .peek()
+            .iter()
+            .filter(|(key, _)| key == field)
+            .map(|(_, validator)| validator.clone())
+            .collect::<Vec<_>>();
+        let mut result = Ok(());
+        for validator in validators.iter() {
+            if let FieldValidator::Sync(validator) = validator {
+                result = validator(&value);
+                if result.is_err() {
+                    break;
+                }
+            }
+        }
+        if result.is_ok() {
+            for validator in validators {
+                if let FieldValidator::Async(validator) = validator {
+                    *self.pending_validations.write() += 1;
+                    result = validator(value.clone()).await;
+                    *self.pending_validations.write() -= 1;
+                    if result.is_err() {
+                        break;
+                    }
+                }
+            }
+
+            // Discards the outdated result if the value has been changed.
+            if self.values.peek().get(field) != current_value.as_ref() {
+                return self.error(field);
+            }
+        }
+        match result {
+            Ok(()) => {
+                self.errors.write().remove(field);
+                None
+            }
+            Err(message) => {
+                self.errors.write().upsert(field, message.as_str());
+                Some(message)
+            }
+        }
+    }
+
+    /// Validates all the fields with validators and marks them as touched.
+    pub async fn validate(mut self) -> Validation {
+        let mut fields = Vec::new();
+        for (field, _) in self.validators.peek().iter() {
+            if !fields.contains(field) {
+                fields.push(field.clone());
+            }
+        }
+
+        let mut validation = Validation::new();
+        for field in fields {
+            self.touch(&field);
+            if let Some(message) = self.validate_field(&field).await {
+                validation.record(field, message);
+            }
+        }
+        validation
+    }
+
+    /// Resets the values to the initial values and clears the flags and errors.
+    pub fn reset(mut self) {
+        let initial_values = self.initial_values.peek().clone();
+        self.values.set(initial_values);
+        self.touched_fields.write().clear();
+        self.errors.write().clear();
+    }
+}
+
+/// A binding of the field in a [`FormState`].
+#[derive(Clone, PartialEq)]
+pub struct FieldBinding {
+    /// The form state.
+    form_state: FormState,
+    /// The field name.
+    field: SharedString,
+}
+
+impl FieldBinding {
+    /// Returns the field name.
+    #[inline]
+    pub fn field(&self) -> &str {
+        self.field.as_ref()
+    }
+
+    /// Returns the field value as a string.
+    #[inline]
+    pub fn value(&self) -> String {
+        self.form_state
+            .value(&self.field)
+            .map(|value| value.to_string_unquoted())
+            .unwrap_or_default()
+    }
+
+    /// Sets the field value.
+    #[inline]
+    pub fn set_value(&self, value: impl Into<JsonValue>) {
+        self.form_state.set_value(&self.field, value);
+    }
+
+    /// Marks the field as touched.
+    #[inline]
+    pub fn touch(&self) {
+        self.form_state.touch(&self.field);
+    }
+
+    /// Returns the error message if the field has been touched.
+    #[inline]
+    pub fn error(&self) -> Option<String> {
+        if self.form_state.is_touched(&self.field) {
+            self.form_state.error(&self.field)
+        } else {
+            None
+        }
+    }
+}
@@ -1,12 +1,34 @@
+use super::FieldBinding;
 use crate::class::Class;
 use dioxus::prelude::*;
 
 /// The multiline textarea and its variations.
+///
+/// If the `binding` is specified, the value will be synchronized with the form state
+/// and the control will be marked as `is-danger` when the field is invalid.
 pub fn Textarea(props: TextareaProps) -> Element {
+    let binding = props.binding;
+    let value = binding.as_ref().map(|binding| binding.value());
+    let invalid = binding
+        .as_ref()
+        .is_some_and(|binding| binding.error().is_some());
+    let input_binding = binding.clone();
     rsx! {
         textarea {
             class: props.class,
+            class: if invalid { "is-danger" },
+            value: value,
             ..props.attributes,
+            oninput: move |event| {
+                if let Some(binding) = input_binding.as_ref() {
+                    binding.set_value(event.value());
+                }
+            },
+            onblur: move |_event| {
+                if let Some(binding) = binding.as_ref() {
+                    binding.touch();
+                }
+            },
         }
     }
 }
@@ -17,6 +39,8 @@ pub struct TextareaProps {
     /// The class attribute for the component.
     #[props(into, default = "textarea".into())]
     pub class: Class,
+    /// An optional binding of the field in a form state.
+    pub binding: Option<FieldBinding>,
     /// Spreading the props of the `textarea` element.
     #[props(extends = textarea)]
     attributes: Vec<Attribute>,
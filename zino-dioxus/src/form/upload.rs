@@ -0,0 +1,377 @@
+use crate::{class::Class, helper, icon::SvgIcon};
+use dioxus::{html::FileEngine, prelude::*};
+use dioxus_free_icons::icons::fa_solid_icons::FaUpload;
+use std::{path::Path, sync::Arc};
+use zino_core::{
+    error::Error,
+    extension::{JsonObjectExt, JsonValueExt},
+    file::NamedFile,
+    json, Map, SharedString,
+};
+
+/// A file upload component with drag-and-drop and chunked uploads.
+///
+/// The files are validated by the `accept` and `max_size` before uploading,
+/// and each file is split into chunks which will be uploaded to the `upload_chunk` endpoint
+/// of the `UploadController`. If the `status_url` is specified, the chunks which
+/// have been uploaded will be skipped, so an interrupted upload can be resumed.
+pub fn Upload(props: UploadProps) -> Element {
+    let mut dragging = use_signal(|| false);
+    let mut uploads = use_signal(Vec::<UploadProgress>::new);
+    let options = UploadOptions {
+        accept: props.accept.clone(),
+        multiple: props.multiple,
+        max_size: props.max_size,
+        chunk_size: props.chunk_size,
+        upload_url: props.upload_url.clone(),
+        status_url: props.status_url.clone(),
+        complete_url: props.complete_url.clone(),
+        on_progress: props.on_progress,
+        on_complete: props.on_complete,
+    };
+    let drop_options = options.clone();
+    rsx! {
+        div {
+            class: props.class,
+            class: if dragging() { "{props.dragover_class}" },
+            prevent_default: "ondragover ondrop",
+            ondragover: move |_| dragging.set(true),
+            ondragleave: move |_| dragging.set(false),
+            ondrop: move |event| {
+                let options = drop_options.clone();
+                async move {
+                    dragging.set(false);
+                    if let Some(file_engine) = event.files() {
+                        let files = read_files(file_engine).await;
+                        options.upload_files(files, uploads).await;
+                    }
+                }
+            },
+            label {
+                class: "file-label",
+                input {
+                    class: "file-input",
+                    r#type: "file",
+                    accept: "{props.accept}",
+                    multiple: props.multiple,
+                    onchange: move |event| {
+                        let options = options.clone();
+                        async move {
+                            if let Some(file_engine) = event.files() {
+                                let files = read_files(file_engine).await;
+                                options.upload_files(files, uploads).await;
+                            }
+                        }
+                    }
+                }
+                span {
+                    class: "file-cta",
+                    span {
+                        class: "file-icon",
+                        SvgIcon {
+                            shape: FaUpload,
+                            width: 16,
+                        }
+                    }
+                    span {
+                        class: "file-label",
+                        { props.label }
+                    }
+                }
+            }
+            for upload in uploads.read().iter() {
+                div {
+                    key: "{upload.file_name}",
+                    class: "mt-2",
+                    p {
+                        class: "is-size-7",
+                        "{upload.file_name}"
+                    }
+                    if let Some(error) = upload.error.as_ref() {
+                        p {
+                            class: "help is-danger",
+                            "{error}"
+                        }
+                    } else {
+                        progress {
+                            class: "progress is-small",
+                            class: if upload.is_completed() { "is-success" } else { "is-primary" },
+                            max: "{upload.total_chunks}",
+                            value: "{upload.uploaded_chunks}",
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The [`Upload`] properties struct for the configuration of the component.
+#[derive(Clone, PartialEq, Props)]
+pub struct UploadProps {
+    /// The class attribute for the component.
+    #[props(into, default = "box file is-boxed is-centered".into())]
+    pub class: Class,
+    /// A class to apply when the files are dragged over the component.
+    #[props(into, default = "has-background-light".into())]
+    pub dragover_class: Class,
+    /// The label content.
+    #[props(into, default = "Drop files here or click to upload".into())]
+    pub label: SharedString,
+    /// The file types that can be uploaded, such as `image/*,.pdf`.
+    #[props(into, default)]
+    pub accept: SharedString,
+    /// A flag to determine whether multiple files can be selected or not.
+    #[props(default)]
+    pub multiple: bool,
+    /// The maximum size of a file in bytes. A zero value means unlimited.
+    #[props(default)]
+    pub max_size: u64,
+    /// The size of a file chunk in bytes.
+    #[props(default = 1024 * 1024)]
+    pub chunk_size: usize,
+    /// The URL of the `upload_chunk` endpoint.
+    #[props(into)]
+    pub upload_url: SharedString,
+    /// An optional URL of the `upload_status` endpoint for resuming uploads.
+    #[props(into)]
+    pub status_url: Option<SharedString>,
+    /// An optional URL of the `complete_upload` endpoint for concatenating the chunks.
+    #[props(into)]
+    pub complete_url: Option<SharedString>,
+    /// An event handler to be called when the upload progress of a file is changed.
+    pub on_progress: Option<EventHandler<UploadProgress>>,
+    /// An event handler to be called with the response data of the `complete_upload` endpoint.
+    pub on_complete: Option<EventHandler<Map>>,
+}
+
+/// The upload progress of a file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UploadProgress {
+    /// The file name.
+    pub file_name: String,
+    /// The upload ID, which is the checksum of the file.
+    pub upload_id: String,
+    /// Number of the uploaded chunks.
+    pub uploaded_chunks: usize,
+    /// Total number of chunks.
+    pub total_chunks: usize,
+    /// An error message if the upload fails.
+    pub error: Option<String>,
+}
+
+impl UploadProgress {
+    /// Returns the percentage of the upload progress.
+    #[inline]
+    pub fn percentage(&self) -> f64 {
+        if self.total_chunks == 0 {
+            0.0
+        } else {
+            (self.uploaded_chunks as f64) * 100.0 / (self.total_chunks as f64)
+        }
+    }
+
+    /// Returns `true` if all the chunks have been uploaded.
+    #[inline]
+    pub fn is_completed(&self) -> bool {
+        self.total_chunks > 0 && self.uploaded_chunks == self.total_chunks
+    }
+}
+
+/// Options for uploading files.
+#[derive(Clone)]
+struct UploadOptions {
+    /// The accepted file types.
+    accept: SharedString,
+    /// A flag for multiple files.
+    multiple: bool,
+    /// The maximum file size.
+    max_size: u64,
+    /// The chunk size.
+    chunk_size: usize,
+    /// The URL for uploading chunks.
+    upload_url: SharedString,
+    /// The URL for querying the uploaded chunks.
+    status_url: Option<SharedString>,
+    /// The URL for completing an upload.
+    complete_url: Option<SharedString>,
+    /// The progress handler.
+    on_progress: Option<EventHandler<UploadProgress>>,
+    /// The completion handler.
+    on_complete: Option<EventHandler<Map>>,
+}
+
+impl UploadOptions {
+    /// Validates and uploads the files.
+    async fn upload_files(
+        &self,
+        mut files: Vec<NamedFile>,
+        mut uploads: Signal<Vec<UploadProgress>>,
+    ) {
+        if !self.multiple {
+            files.truncate(1);
+        }
+        for file in files {
+            let file_name = file.file_name().unwrap_or_default().to_owned();
+            let mut progress = UploadProgress {
+                upload_id: format!("{:x}", file.checksum()),
+                file_name,
+                ..UploadProgress::default()
+            };
+            uploads
+                .write()
+                .retain(|p| p.file_name != progress.file_name);
+            uploads.write().push(progress.clone());
+            if let Err(err) = self.validate_file(&file) {
+                progress.error = Some(err.to_string());
+                self.update_progress(&progress, uploads);
+                continue;
+            }
+            match self.upload_file(&file, &mut progress, uploads).await {
+                Ok(Some(data)) => {
+                    if let Some(handler) = self.on_complete.as_ref() {
+                        handler.call(data);
+                    }
+                }
+                Ok(None) => (),
+                Err(err) => {
+                    progress.error = Some(err.to_string());
+                    self.update_progress(&progress, uploads);
+                }
+            }
+        }
+    }
+
+    /// Validates the file type and size.
+    fn validate_file(&self, file: &NamedFile) -> Result<(), Error> {
+        let file_size = file.file_size();
+        if self.max_size > 0 && file_size > self.max_size {
+            let max_size = self.max_size;
+            return Err(Error::new(format!(
+                "the file size {file_size} exceeds the limit {max_size}"
+            )));
+        }
+        if !self.accept.is_empty() && !accepts_file(&self.accept, file) {
+            return Err(Error::new("the file type is not accepted"));
+        }
+        Ok(())
+    }
+
+    /// Uploads the chunks of a file which have not been uploaded.
+    async fn upload_file(
+        &self,
+        file: &NamedFile,
+        progress: &mut UploadProgress,
+        uploads: Signal<Vec<UploadProgress>>,
+    ) -> Result<Option<Map>, Error> {
+        let upload_id = progress.upload_id.clone();
+        let uploaded_chunks = if let Some(status_url) = self.status_url.as_ref() {
+            let url = format!("{status_url}?upload_id={upload_id}");
+            request_data(&url, None)
+                .await?
+                .get_array("chunks")
+                .map(|chunks| {
+                    chunks
+                        .iter()
+                        .filter_map(|v| v.as_u64().map(|n| n as usize))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+
+        let chunks = file.split_chunks(self.chunk_size.max(1));
+        progress.total_chunks = chunks.len();
+        for mut chunk in chunks {
+            let chunk_number = chunk.chunk_number().unwrap_or_default();
+            if !uploaded_chunks.contains(&chunk_number) {
+                chunk.set_field_name("file");
+                chunk.set_extra_attribute("upload_id", upload_id.as_str());
+                chunk.set_extra_attribute("checksum", format!("{:x}", chunk.checksum()));
+                chunk
+                    .upload_to(&self.upload_url, None)
+                    .await?
+                    .error_for_status()?;
+            }
+            progress.uploaded_chunks += 1;
+            self.update_progress(progress, uploads);
+        }
+
+        if let Some(complete_url) = self.complete_url.as_ref() {
+            let options = json!({
+                "method": "POST",
+                "data_type": "json",
+                "body": {
+                    "upload_id": upload_id,
+                    "file_name": progress.file_name,
+                    "total_chunks": progress.total_chunks,
+                },
+            });
+            request_data(complete_url, options.as_object())
+                .await
+                .map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Updates the upload progress.
+    fn update_progress(&self, progress: &UploadProgress, mut uploads: Signal<Vec<UploadProgress>>) {
+        if let Some(p) = uploads
+            .write()
+            .iter_mut()
+            .find(|p| p.file_name == progress.file_name)
+        {
+            *p = progress.clone();
+        }
+        if let Some(handler) = self.on_progress.as_ref() {
+            handler.call(progress.clone());
+        }
+    }
+}
+
+/// Reads the files from the file engine.
+async fn read_files(file_engine: Arc<dyn FileEngine>) -> Vec<NamedFile> {
+    let mut files = Vec::new();
+    for file in file_engine.files() {
+        if let Some(bytes) = file_engine.read_file(&file).await {
+            if let Some(file_name) = Path::new(&file).file_name() {
+                let mut file = NamedFile::new(file_name.to_string_lossy());
+                file.set_bytes(bytes);
+                files.push(file);
+            }
+        }
+    }
+    files
+}
+
+/// Returns `true` if the file matches one of the accepted file types,
+/// which can be a file extension like `.pdf`, a MIME type like `image/png`,
+/// or a wildcard MIME type like `image/*`.
+fn accepts_file(accept: &str, file: &NamedFile) -> bool {
+    let file_name = file.file_name().unwrap_or_default().to_ascii_lowercase();
+    let content_type = file
+        .content_type()
+        .map(|mime| mime.essence_str().to_owned())
+        .unwrap_or_default();
+    accept
+        .split(',')
+        .map(|s| s.trim().to_ascii_lowercase())
+        .filter(|s| !s.is_empty())
+        .any(|s| {
+            if s.starts_with('.') {
+                file_name.ends_with(&s)
+            } else if let Some(prefix) = s.strip_suffix("/*") {
+                content_type.split('/').next() == Some(prefix)
+            } else {
+                content_type == s
+            }
+        })
+}
+
+/// Sends a request and returns the `data` object in the response.
+async fn request_data(url: &str, options: Option<&Map>) -> Result<Map, Error> {
+    let data = helper::fetch_data(url, options).await?;
+    Ok(data.into_map_opt().unwrap_or_default())
+}
@@ -0,0 +1,12 @@
+use zino_core::{error::Error, extension::JsonValueExt, file::NamedFile, JsonValue, Map};
+
+/// Sends a request and returns the `data` field in the response body.
+pub(crate) async fn fetch_data(url: &str, options: Option<&Map>) -> Result<JsonValue, Error> {
+    let file = NamedFile::download_from(url, options).await?;
+    let body = std::str::from_utf8(file.as_ref())?.parse::<JsonValue>()?;
+    let data = body
+        .into_map_opt()
+        .and_then(|mut body| body.remove("data"))
+        .unwrap_or_default();
+    Ok(data)
+}
@@ -0,0 +1,144 @@
+//! Internationalization with the message catalogs shared with the server.
+//!
+//! The messages are loaded from the FTL files in the `config/locale` directory,
+//! so the plural rules are supported by the selectors of the Fluent syntax:
+//!
+//! ```ftl
+//! unread-messages = { $count ->
+//!     [one] You have one unread message.
+//!    *[other] You have { $count } unread messages.
+//! }
+//! ```
+
+use dioxus::prelude::*;
+use zino_core::{
+    error::Error,
+    i18n::{self, FluentArgs, LanguageIdentifier},
+};
+
+#[doc(hidden)]
+pub use zino_core::fluent_args;
+
+/// Translates the localization message with the current locale.
+/// It returns the message itself if the translation fails.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino_dioxus::t;
+///
+/// let greeting = t!("greeting");
+/// let unread_messages = t!("unread-messages", count = 3);
+/// ```
+#[macro_export]
+macro_rules! t {
+    ($message:expr $(,)?) => {
+        $crate::i18n::translate($message, None)
+    };
+    ($message:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::translate(
+            $message,
+            Some($crate::i18n::fluent_args![$(stringify!($name) => $value),+]),
+        )
+    };
+}
+
+/// A provider of the [`Locale`] context for the descendants.
+///
+/// The locale will be restored from and persisted to the local storage.
+pub fn LocaleProvider(props: LocaleProviderProps) -> Element {
+    let mut locale = use_context_provider(|| {
+        let lang_id = props
+            .default_locale
+            .as_deref()
+            .unwrap_or_else(i18n::default_locale)
+            .parse()
+            .unwrap_or_default();
+        Locale {
+            lang_id: Signal::new(lang_id),
+        }
+    });
+    use_future(move || async move {
+        let script = format!("return localStorage.getItem('{LOCALE_STORAGE_KEY}');");
+        if let Ok(value) = eval(&script).join().await {
+            if let Some(lang_id) = value.as_str().and_then(|s| s.parse().ok()) {
+                locale.lang_id.set(lang_id);
+            }
+        }
+    });
+    rsx! {
+        { props.children }
+    }
+}
+
+/// The [`LocaleProvider`] properties struct for the configuration of the component.
+#[derive(Clone, PartialEq, Props)]
+pub struct LocaleProviderProps {
+    /// The default locale. If it is `None`, the `i18n.default-locale` config will be used.
+    #[props(into)]
+    pub default_locale: Option<String>,
+    /// The children to render within the component.
+    children: Element,
+}
+
+/// A context for the current locale.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Locale {
+    /// The language identifier.
+    lang_id: Signal<LanguageIdentifier>,
+}
+
+impl Locale {
+    /// Returns the language identifier.
+    #[inline]
+    pub fn lang_id(&self) -> LanguageIdentifier {
+        self.lang_id.read().clone()
+    }
+
+    /// Switches the current locale and persists it to the local storage.
+    pub fn set_locale(mut self, locale: &str) -> Result<(), Error> {
+        let lang_id = locale.parse::<LanguageIdentifier>()?;
+        let script = format!("localStorage.setItem('{LOCALE_STORAGE_KEY}', '{lang_id}');");
+        eval(&script);
+        self.lang_id.set(lang_id);
+        Ok(())
+    }
+
+    /// Translates the localization message.
+    /// It returns the message itself if the translation fails.
+    pub fn translate(&self, message: &str, args: Option<FluentArgs<'_>>) -> String {
+        i18n::translate(&self.lang_id.read(), message, args)
+            .map(|s| s.into_owned())
+            .unwrap_or_else(|_| message.to_owned())
+    }
+
+    /// Returns the supported locales.
+    #[inline]
+    pub fn supported_locales(&self) -> &'static [&'static str] {
+        i18n::supported_locales()
+    }
+}
+
+/// Obtains the [`Locale`] context provided by the [`LocaleProvider`].
+#[inline]
+pub fn use_locale() -> Locale {
+    use_context::<Locale>()
+}
+
+/// Translates the localization message with the [`Locale`] context if it exists,
+/// or with the default locale otherwise.
+pub fn translate(message: &str, args: Option<FluentArgs<'_>>) -> String {
+    if let Some(locale) = try_consume_context::<Locale>() {
+        locale.translate(message, args)
+    } else {
+        i18n::default_locale()
+            .parse::<LanguageIdentifier>()
+            .map_err(Error::from)
+            .and_then(|lang_id| i18n::translate(&lang_id, message, args))
+            .map(|s| s.into_owned())
+            .unwrap_or_else(|_| message.to_owned())
+    }
+}
+
+/// The key of the local storage for the locale.
+const LOCALE_STORAGE_KEY: &str = "zino-locale";
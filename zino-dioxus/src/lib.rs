@@ -4,13 +4,21 @@
 #![allow(non_snake_case)]
 #![forbid(unsafe_code)]
 
+pub mod chart;
 pub mod class;
+pub mod data;
 pub mod extension;
 pub mod feedback;
 pub mod form;
 pub mod icon;
 pub mod layout;
+pub mod live;
 pub mod navigation;
 pub mod prelude;
 pub mod theme;
 pub mod typography;
+
+#[cfg(feature = "i18n")]
+pub mod i18n;
+
+mod helper;
@@ -0,0 +1,175 @@
+//! Live data with Server-Sent Events and WebSockets.
+
+use dioxus::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+use std::time::Duration;
+use zino_core::{error::Error, JsonValue, SharedString};
+
+/// Subscribes to the Server-Sent Events from the URL.
+///
+/// The `data` of each event will be deserialized as a message of type `T`,
+/// and the messages which fail to be deserialized will be ignored.
+/// The connection will be reestablished with an exponential backoff if it is closed.
+pub fn use_sse<T: DeserializeOwned + 'static>(url: impl Into<SharedString>) -> LiveStream<T> {
+    let script = format!(
+        r#"
+        const source = new EventSource({});
+        source.onopen = () => dioxus.send({{ type: "open" }});
+        source.onmessage = (event) => dioxus.send({{ type: "message", data: event.data }});
+        source.onerror = () => {{
+            source.close();
+            dioxus.send({{ type: "close" }});
+        }};
+        "#,
+        JsonValue::from(url.into().as_ref())
+    );
+    use_live_stream(script)
+}
+
+/// Connects to the WebSocket server at the URL.
+///
+/// The text frames will be deserialized as messages of type `T`,
+/// and the messages can be sent by [`LiveStream::send()`].
+/// The connection will be reestablished with an exponential backoff if it is closed.
+pub fn use_websocket<T: DeserializeOwned + 'static>(url: impl Into<SharedString>) -> LiveStream<T> {
+    let script = format!(
+        r#"
+        const socket = new WebSocket({});
+        socket.onopen = () => dioxus.send({{ type: "open" }});
+        socket.onmessage = (event) => dioxus.send({{ type: "message", data: event.data }});
+        socket.onclose = () => dioxus.send({{ type: "close" }});
+        while (true) {{
+            const message = await dioxus.recv();
+            if (socket.readyState === WebSocket.OPEN) {{
+                socket.send(message);
+            }}
+        }}
+        "#,
+        JsonValue::from(url.into().as_ref())
+    );
+    use_live_stream(script)
+}
+
+/// A reactive stream of the typed messages.
+pub struct LiveStream<T: 'static> {
+    /// The latest message.
+    message: Signal<Option<T>>,
+    /// The connection status.
+    status: Signal<ConnectionStatus>,
+    /// The channel to the connection.
+    channel: Signal<Option<UseEval>>,
+}
+
+impl<T: 'static> LiveStream<T> {
+    /// Returns the latest message.
+    #[inline]
+    pub fn message(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.message.read().clone()
+    }
+
+    /// Returns the connection status.
+    #[inline]
+    pub fn status(&self) -> ConnectionStatus {
+        *self.status.read()
+    }
+
+    /// Sends a message to the WebSocket server.
+    pub fn send<M: Serialize>(&self, message: &M) -> Result<(), Error> {
+        if self.status() != ConnectionStatus::Open {
+            return Err(Error::new("the connection is not open"));
+        }
+
+        let channel = self.channel.peek();
+        let channel = channel
+            .as_ref()
+            .ok_or_else(|| Error::new("the channel does not exist"))?;
+        let text = serde_json::to_string(message)?;
+        channel
+            .send(text.into())
+            .map_err(|err| Error::new(format!("fail to send the message: {err:?}")))
+    }
+}
+
+impl<T: 'static> Clone for LiveStream<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: 'static> Copy for LiveStream<T> {}
+
+impl<T: 'static> PartialEq for LiveStream<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.message == other.message
+    }
+}
+
+/// Status of a live connection.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConnectionStatus {
+    /// The connection is being established.
+    #[default]
+    Connecting,
+    /// The connection is open.
+    Open,
+    /// The connection is closed and will be reestablished.
+    Closed,
+}
+
+/// Runs the script for a live connection and reconnects with an exponential backoff.
+fn use_live_stream<T: DeserializeOwned + 'static>(script: String) -> LiveStream<T> {
+    let mut message = use_signal(|| None);
+    let mut status = use_signal(ConnectionStatus::default);
+    let mut channel = use_signal(|| None);
+    use_future(move || {
+        let script = script.clone();
+        async move {
+            let mut delay = INITIAL_RECONNECT_DELAY;
+            loop {
+                status.set(ConnectionStatus::Connecting);
+
+                let mut evaluator = eval(&script);
+                channel.set(Some(evaluator));
+                while let Ok(event) = evaluator.recv().await {
+                    match event.get("type").and_then(|v| v.as_str()) {
+                        Some("open") => {
+                            status.set(ConnectionStatus::Open);
+                            delay = INITIAL_RECONNECT_DELAY;
+                        }
+                        Some("message") => {
+                            if let Some(data) = event.get("data").and_then(|v| v.as_str()) {
+                                let value =
+                                    data.parse::<JsonValue>().unwrap_or_else(|_| data.into());
+                                if let Ok(data) = serde_json::from_value(value) {
+                                    message.set(Some(data));
+                                }
+                            }
+                        }
+                        _ => break,
+                    }
+                }
+                channel.set(None);
+                status.set(ConnectionStatus::Closed);
+
+                tokio::time::sleep(delay).await;
+                delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+            }
+        }
+    });
+    LiveStream {
+        message,
+        status,
+        channel,
+    }
+}
+
+/// Initial delay for reconnecting.
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Maximum delay for reconnecting.
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
@@ -0,0 +1,160 @@
+use dioxus::prelude::*;
+use dioxus_router::{
+    components::IntoRoutable,
+    prelude::{navigator, router},
+};
+use zino_core::{auth::UserSession, SharedString};
+
+/// A guard for the routes which require authentication or specific roles.
+///
+/// If there is no user session, it will redirect to the `login_route`
+/// and preserve the intended destination which can be obtained by
+/// [`SessionContext::take_redirect_to()`] after logging in.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use dioxus::prelude::*;
+/// use dioxus_router::prelude::*;
+/// use zino_dioxus::prelude::*;
+///
+/// #[derive(Clone, PartialEq, Eq, Routable)]
+/// #[rustfmt::skip]
+/// pub enum Route {
+///     #[layout(AdminGuard)]
+///         #[route("/users")]
+///         UserList {},
+///     #[end_layout]
+///     #[route("/login")]
+///     Login {},
+/// }
+///
+/// fn AdminGuard() -> Element {
+///     rsx! {
+///         RouteGuard {
+///             requires_role: "admin",
+///             login_route: Route::Login {},
+///             Outlet::<Route> {}
+///         }
+///     }
+/// }
+/// ```
+pub fn RouteGuard(props: RouteGuardProps) -> Element {
+    let session = use_session();
+    let requires_auth = props.requires_auth || props.requires_role.is_some();
+    let login_route = props.login_route.clone();
+    use_effect(move || {
+        if requires_auth && !session.is_authenticated() {
+            session.set_redirect_to(router().full_route_string());
+            navigator().replace(login_route.clone());
+        }
+    });
+    if !requires_auth {
+        return props.children;
+    }
+
+    let user_session = session.session.read();
+    let Some(user_session) = user_session.as_ref() else {
+        return None;
+    };
+    let authorized = props
+        .requires_role
+        .as_ref()
+        .map(|role| user_session.has_role(role))
+        .unwrap_or(true);
+    if authorized {
+        props.children
+    } else {
+        props.fallback
+    }
+}
+
+/// The [`RouteGuard`] properties struct for the configuration of the component.
+#[derive(Clone, PartialEq, Props)]
+pub struct RouteGuardProps {
+    /// A flag to determine whether the authentication is required or not.
+    #[props(default = true)]
+    pub requires_auth: bool,
+    /// The role required to access the routes.
+    #[props(into)]
+    pub requires_role: Option<SharedString>,
+    /// The route to redirect to if the user has not logged in.
+    #[props(into, default = IntoRoutable::FromStr("/login".to_owned()))]
+    pub login_route: IntoRoutable,
+    /// The content to render if the user does not have the required role.
+    #[props(default)]
+    pub fallback: Element,
+    /// The children to render within the component.
+    children: Element,
+}
+
+/// A context for the user session of the client.
+#[derive(Clone, Copy, PartialEq)]
+pub struct SessionContext {
+    /// The user session.
+    session: Signal<Option<UserSession<String>>>,
+    /// The intended destination before redirecting to the login route.
+    redirect_to: Signal<Option<String>>,
+}
+
+impl SessionContext {
+    /// Sets the user session after logging in.
+    #[inline]
+    pub fn login(mut self, user_session: UserSession<String>) {
+        self.session.set(Some(user_session));
+    }
+
+    /// Removes the user session.
+    #[inline]
+    pub fn logout(mut self) {
+        self.session.set(None);
+    }
+
+    /// Returns a clone of the user session.
+    #[inline]
+    pub fn user_session(&self) -> Option<UserSession<String>> {
+        self.session.read().clone()
+    }
+
+    /// Returns `true` if there is a user session.
+    #[inline]
+    pub fn is_authenticated(&self) -> bool {
+        self.session.read().is_some()
+    }
+
+    /// Returns `true` if the user has the specific `role`.
+    #[inline]
+    pub fn has_role(&self, role: &str) -> bool {
+        self.session
+            .read()
+            .as_ref()
+            .is_some_and(|session| session.has_role(role))
+    }
+
+    /// Sets the intended destination before redirecting to the login route.
+    #[inline]
+    pub fn set_redirect_to(mut self, route: String) {
+        self.redirect_to.set(Some(route));
+    }
+
+    /// Takes the intended destination before redirecting to the login route.
+    #[inline]
+    pub fn take_redirect_to(mut self) -> Option<String> {
+        self.redirect_to.write().take()
+    }
+}
+
+/// Provides the [`SessionContext`] with an optional user session for the descendants.
+#[inline]
+pub fn use_session_provider(init: impl FnOnce() -> Option<UserSession<String>>) -> SessionContext {
+    use_context_provider(|| SessionContext {
+        session: Signal::new(init()),
+        redirect_to: Signal::new(None),
+    })
+}
+
+/// Obtains the [`SessionContext`] provided by [`use_session_provider()`].
+#[inline]
+pub fn use_session() -> SessionContext {
+    use_context::<SessionContext>()
+}
@@ -1,11 +1,13 @@
 //! Navigation bars and menus.
 
 mod dropdown;
+mod guard;
 mod navbar;
 mod pagination;
 mod sidebar;
 
 pub use dropdown::{Dropdown, DropdownProps};
+pub use guard::{use_session, use_session_provider, RouteGuard, RouteGuardProps, SessionContext};
 pub use navbar::{
     Navbar, NavbarBrand, NavbarBrandProps, NavbarCenter, NavbarCenterProps, NavbarDropdown,
     NavbarDropdownProps, NavbarEnd, NavbarEndProps, NavbarItem, NavbarItemProps, NavbarLink,
@@ -1,18 +1,26 @@
 //! Re-exports of components and common types.
 
 pub use crate::{
+    chart::{use_chart_data, BarChart, LineChart, PieChart},
     class::Class,
+    data::{DataTable, TableColumn, TableQuery, VirtualList},
     extension::FormDataExt,
-    feedback::{Message, ModalCard, ModalData, Notification, OperationResult},
+    feedback::{
+        use_notifier, Message, ModalCard, ModalData, Notification, Notifier, NotifierProvider,
+        OperationResult,
+    },
     form::{
-        Button, Checkbox, DataEntry, DataSelect, FileUpload, FormAddons, FormField,
-        FormFieldContainer, FormGroup, Input, Radio, Textarea,
+        use_form_state, Button, Checkbox, DataEntry, DataSelect, FieldBinding, FileUpload,
+        FormAddons, FormField, FormFieldContainer, FormGroup, FormState, Input, MarkdownEditor,
+        ModelForm, Radio, Textarea, Upload,
     },
     icon::{Icon, IconText, SvgIcon},
     layout::{Columns, Container, FluidContainer, MainContainer},
+    live::{use_sse, use_websocket, ConnectionStatus, LiveStream},
     navigation::{
-        Dropdown, Navbar, NavbarBrand, NavbarCenter, NavbarDropdown, NavbarEnd, NavbarItem,
-        NavbarLink, NavbarMenu, NavbarStart, Pagination, Sidebar,
+        use_session, use_session_provider, Dropdown, Navbar, NavbarBrand, NavbarCenter,
+        NavbarDropdown, NavbarEnd, NavbarItem, NavbarLink, NavbarMenu, NavbarStart, Pagination,
+        RouteGuard, SessionContext, Sidebar,
     },
     theme::Theme,
     typography::{Card, FixedWidthSpan, Markdown, Tag, Tags},
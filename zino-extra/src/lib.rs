@@ -8,3 +8,5 @@
 pub mod cache;
 #[cfg(feature = "format")]
 pub mod format;
+#[cfg(feature = "report")]
+pub mod report;
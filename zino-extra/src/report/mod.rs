@@ -0,0 +1,274 @@
+//! Generating PDF reports from templates.
+//!
+//! The templates are rendered by the view engine of [`zino-core`], and converted to PDF
+//! by the configured backend in the `[report]` table:
+//!
+//! | Backend    | Template format | Default executable |
+//! |------------|-----------------|--------------------|
+//! | `chromium` | HTML            | `chromium`         |
+//! | `typst`    | Typst markup    | `typst`            |
+//!
+//! [`zino-core`]: https://docs.rs/zino-core
+
+use bytes::Bytes;
+use futures::{stream, Stream};
+use std::{
+    env,
+    fs::{self, File},
+    path::Path,
+    process::{Command, Stdio},
+    str::FromStr,
+    thread,
+    time::{Duration, Instant},
+};
+use zino_core::{
+    bail, error::Error, extension::TomlTableExt, file::NamedFile, state::State, view, warn,
+    LazyLock, Map, Uuid,
+};
+
+/// Backends for converting the rendered templates to PDF.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ReportBackend {
+    /// Prints the HTML document with a headless Chromium.
+    #[default]
+    Chromium,
+    /// Compiles the Typst document with the `typst` CLI.
+    Typst,
+}
+
+impl ReportBackend {
+    /// Returns the backend name.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Chromium => "chromium",
+            Self::Typst => "typst",
+        }
+    }
+
+    /// Returns the file extension of the rendered template.
+    #[inline]
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Chromium => "html",
+            Self::Typst => "typ",
+        }
+    }
+
+    /// Converts the source file to a PDF file with the executable.
+    /// The process is killed if it does not exit within the timeout.
+    fn convert(
+        &self,
+        executable: &str,
+        source: &Path,
+        output: &Path,
+        timeout: Duration,
+    ) -> Result<(), Error> {
+        let mut command = Command::new(executable);
+        match self {
+            Self::Chromium => {
+                command
+                    .arg("--headless")
+                    .arg("--disable-gpu")
+                    .arg("--no-pdf-header-footer")
+                    .arg(format!("--print-to-pdf={}", output.display()))
+                    .arg(format!("file://{}", source.display()));
+            }
+            Self::Typst => {
+                command.arg("compile").arg(source).arg(output);
+            }
+        }
+
+        // The stderr is redirected to a file so that the process never blocks on a full pipe.
+        let stderr_path = output.with_extension("log");
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(File::create(&stderr_path)?)
+            .spawn()?;
+        let deadline = Instant::now() + timeout;
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                child.kill()?;
+                child.wait()?;
+                bail!(
+                    "fail to generate the PDF with `{}` in {:?}",
+                    self.as_str(),
+                    timeout
+                );
+            }
+            thread::sleep(POLL_INTERVAL);
+        };
+        if !status.success() {
+            let stderr = fs::read_to_string(&stderr_path).unwrap_or_default();
+            bail!(
+                "fail to generate the PDF with `{}`: {}",
+                self.as_str(),
+                stderr.trim()
+            );
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ReportBackend {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "chromium" => Ok(Self::Chromium),
+            "typst" => Ok(Self::Typst),
+            _ => bail!("the report backend `{}` is unsupported", s),
+        }
+    }
+}
+
+/// A PDF report generated from a template.
+///
+/// The generation runs an external process which blocks the current thread,
+/// so it is recommended to run it in a blocking task or a long-running operation.
+/// The process is killed if it exceeds the `timeout` in the `[report]` table,
+/// which defaults to 60 seconds.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino_extra::report::Report;
+///
+/// let report = Report::generate("invoice.html", data)?;
+/// let mut res = Response::default().context(&req);
+/// res.send_attachment(report.into_named_file("invoice.pdf"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Report {
+    /// PDF content.
+    bytes: Bytes,
+}
+
+impl Report {
+    /// Generates a report by rendering the template with the data
+    /// using the backend configured by the `[report]` table.
+    #[inline]
+    pub fn generate(template_name: &str, data: Map) -> Result<Self, Error> {
+        let config = &*SHARED_REPORT_CONFIG;
+        Self::generate_with(
+            config.backend,
+            &config.executable,
+            config.timeout,
+            template_name,
+            data,
+        )
+    }
+
+    /// Generates a report by rendering the template with the data
+    /// using the specific backend and executable within the timeout.
+    pub fn generate_with(
+        backend: ReportBackend,
+        executable: &str,
+        timeout: Duration,
+        template_name: &str,
+        data: Map,
+    ) -> Result<Self, Error> {
+        let content = view::render(template_name, data)?;
+        let work_dir = env::temp_dir().join(format!("zino-report-{}", Uuid::now_v7()));
+        fs::create_dir_all(&work_dir)?;
+
+        let source = work_dir.join(format!("report.{}", backend.extension()));
+        let output = work_dir.join("report.pdf");
+        let result = fs::write(&source, content)
+            .map_err(Error::from)
+            .and_then(|_| backend.convert(executable, &source, &output, timeout))
+            .and_then(|_| fs::read(&output).map_err(Error::from));
+        if let Err(err) = fs::remove_dir_all(&work_dir) {
+            tracing::warn!("fail to remove the report directory: {err}");
+        }
+
+        let bytes = result?;
+        if !bytes.starts_with(b"%PDF") {
+            return Err(warn!("the output of `{}` is not a PDF", backend.as_str()));
+        }
+        Ok(Self {
+            bytes: bytes.into(),
+        })
+    }
+
+    /// Returns the PDF content.
+    #[inline]
+    pub fn bytes(&self) -> Bytes {
+        self.bytes.clone()
+    }
+
+    /// Returns the size of the PDF content.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns `true` if the PDF content is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Consumes `self` and returns a stream of the PDF content in chunks.
+    pub fn into_stream(self) -> impl Stream<Item = Result<Bytes, Error>> + Send + 'static {
+        let bytes = self.bytes;
+        let num_chunks = bytes.len().div_ceil(CHUNK_SIZE);
+        stream::iter((0..num_chunks).map(move |index| {
+            let start = index * CHUNK_SIZE;
+            let end = (start + CHUNK_SIZE).min(bytes.len());
+            Ok(bytes.slice(start..end))
+        }))
+    }
+
+    /// Consumes `self` and returns a PDF file with the file name,
+    /// which can be sent to the client as a download.
+    pub fn into_named_file(self, file_name: impl Into<String>) -> NamedFile {
+        let mut file = NamedFile::new(file_name);
+        file.set_content_type(mime::APPLICATION_PDF);
+        file.set_bytes(self.bytes);
+        file
+    }
+}
+
+/// Configuration of the report generation.
+#[derive(Debug)]
+struct ReportConfig {
+    /// Backend.
+    backend: ReportBackend,
+    /// Path of the executable.
+    executable: String,
+    /// Timeout for the conversion.
+    timeout: Duration,
+}
+
+/// Interval for polling the status of the conversion process.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Size of the chunks in the stream.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Shared report config.
+static SHARED_REPORT_CONFIG: LazyLock<ReportConfig> = LazyLock::new(|| {
+    let config = State::shared().get_config("report");
+    let backend = config
+        .and_then(|t| t.get_str("backend"))
+        .and_then(|s| s.parse().map_err(|err| tracing::error!("{err}")).ok())
+        .unwrap_or_default();
+    let executable = config
+        .and_then(|t| t.get_str("executable"))
+        .unwrap_or(backend.as_str())
+        .to_owned();
+    let timeout = config
+        .and_then(|t| t.get_duration("timeout"))
+        .unwrap_or_else(|| Duration::from_secs(60));
+    ReportConfig {
+        backend,
+        executable,
+        timeout,
+    }
+});
@@ -0,0 +1,415 @@
+//! The `access_quota` model and related services.
+
+use serde::{Deserialize, Serialize};
+use zino_core::{
+    auth::AccessKeyId,
+    datetime::DateTime,
+    error::Error,
+    extension::JsonObjectExt,
+    model::{Model, ModelHooks, Mutation, Query},
+    orm::Schema,
+    validation::Validation,
+    Map, Uuid,
+};
+use zino_derive::{DecodeRow, ModelAccessor, Schema};
+
+/// The `access_quota` model. It tracks the daily and monthly request quotas
+/// for an access key, which can be used to provide tiered API plans.
+/// A limit of `0` means that the quota is unlimited.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, DecodeRow, Schema, ModelAccessor)]
+#[serde(default)]
+pub struct AccessQuota {
+    // Basic fields.
+    #[schema(read_only)]
+    id: Uuid,
+    #[schema(not_null, comment = "Plan name")]
+    name: String,
+    #[cfg(feature = "namespace")]
+    #[schema(default_value = "AccessQuota::model_namespace", index_type = "hash")]
+    namespace: String,
+    #[cfg(feature = "visibility")]
+    #[schema(default_value = "Internal")]
+    visibility: String,
+    #[schema(
+        enum_values = "Active | Inactive",
+        default_value = "Active",
+        index_type = "hash"
+    )]
+    status: String,
+    description: String,
+
+    // Info fields.
+    #[schema(not_null, unique, read_only)]
+    access_key_id: String,
+    daily_limit: u64,
+    monthly_limit: u64,
+    #[schema(read_only)]
+    daily_usage: u64,
+    #[schema(read_only)]
+    monthly_usage: u64,
+    #[schema(read_only)]
+    daily_reset_at: DateTime,
+    #[schema(read_only)]
+    monthly_reset_at: DateTime,
+
+    // Extensions.
+    extra: Map,
+
+    // Revisions.
+    #[schema(read_only, default_value = "now", index_type = "btree")]
+    created_at: DateTime,
+    #[schema(default_value = "now", index_type = "btree")]
+    updated_at: DateTime,
+    version: u64,
+    #[cfg(feature = "edition")]
+    edition: u32,
+}
+
+impl Model for AccessQuota {
+    const MODEL_NAME: &'static str = "access_quota";
+
+    #[inline]
+    fn new() -> Self {
+        let now = DateTime::now();
+        Self {
+            id: Uuid::now_v7(),
+            status: "Active".to_owned(),
+            daily_reset_at: next_daily_reset(now),
+            monthly_reset_at: next_monthly_reset(now),
+            ..Self::default()
+        }
+    }
+
+    fn read_map(&mut self, data: &Map) -> Validation {
+        let mut validation = Validation::new();
+        if let Some(result) = data.parse_uuid("id") {
+            match result {
+                Ok(id) => self.id = id,
+                Err(err) => validation.record_fail("id", err),
+            }
+        }
+        if let Some(name) = data.parse_string("name") {
+            self.name = name.into_owned();
+        }
+        if let Some(description) = data.parse_string("description") {
+            self.description = description.into_owned();
+        }
+        if let Some(access_key_id) = data.parse_string("access_key_id") {
+            self.access_key_id = access_key_id.into_owned();
+        }
+        if let Some(result) = data.parse_u64("daily_limit") {
+            match result {
+                Ok(daily_limit) => self.daily_limit = daily_limit,
+                Err(err) => validation.record_fail("daily_limit", err),
+            }
+        }
+        if let Some(result) = data.parse_u64("monthly_limit") {
+            match result {
+                Ok(monthly_limit) => self.monthly_limit = monthly_limit,
+                Err(err) => validation.record_fail("monthly_limit", err),
+            }
+        }
+        if self.access_key_id.is_empty() {
+            validation.record("access_key_id", "should be nonempty");
+        }
+        validation
+    }
+}
+
+impl ModelHooks for AccessQuota {
+    type Data = ();
+    type Extension = ();
+}
+
+impl AccessQuota {
+    /// Creates a new instance with the plan for the access key.
+    pub fn with_plan(
+        access_key_id: &AccessKeyId,
+        plan: &str,
+        daily_limit: u64,
+        monthly_limit: u64,
+    ) -> Self {
+        let mut quota = Self::new();
+        quota.name = plan.to_owned();
+        quota.access_key_id = access_key_id.to_string();
+        quota.daily_limit = daily_limit;
+        quota.monthly_limit = monthly_limit;
+        quota
+    }
+
+    /// Returns the plan name.
+    #[inline]
+    pub fn plan(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the `access_key_id` field.
+    #[inline]
+    pub fn access_key_id(&self) -> &str {
+        &self.access_key_id
+    }
+
+    /// Returns the `daily_limit` field.
+    #[inline]
+    pub fn daily_limit(&self) -> u64 {
+        self.daily_limit
+    }
+
+    /// Returns the `monthly_limit` field.
+    #[inline]
+    pub fn monthly_limit(&self) -> u64 {
+        self.monthly_limit
+    }
+
+    /// Returns the `daily_usage` field.
+    #[inline]
+    pub fn daily_usage(&self) -> u64 {
+        self.daily_usage
+    }
+
+    /// Returns the `monthly_usage` field.
+    #[inline]
+    pub fn monthly_usage(&self) -> u64 {
+        self.monthly_usage
+    }
+
+    /// Returns `true` if the quota is active.
+    #[inline]
+    pub fn is_active(&self) -> bool {
+        self.status == "Active"
+    }
+
+    /// Returns the current usage of the quota.
+    pub fn usage(&self) -> QuotaUsage {
+        let daily_remaining = remaining(self.daily_limit, self.daily_usage);
+        let monthly_remaining = remaining(self.monthly_limit, self.monthly_usage);
+        let (limit, remaining, reset_at) = match (daily_remaining, monthly_remaining) {
+            (Some(daily), Some(monthly)) if monthly < daily => {
+                (self.monthly_limit, monthly, self.monthly_reset_at)
+            }
+            (Some(daily), _) => (self.daily_limit, daily, self.daily_reset_at),
+            (None, Some(monthly)) => (self.monthly_limit, monthly, self.monthly_reset_at),
+            (None, None) => (0, u64::MAX, self.monthly_reset_at),
+        };
+        QuotaUsage {
+            plan: self.name.clone(),
+            limit,
+            remaining,
+            reset_at,
+            exceeded: false,
+        }
+    }
+
+    /// Finds the active quota for the access key.
+    pub async fn find_by_access_key_id(access_key_id: &AccessKeyId) -> Result<Option<Self>, Error> {
+        let mut query = Query::default();
+        query.add_filter("access_key_id", access_key_id.as_str());
+        query.add_filter("status", "Active");
+        Self::find_one_as(&query).await
+    }
+
+    /// Consumes the quota of the access key with the cost of a request.
+    /// It returns `None` if there is no active quota for the access key,
+    /// and the usage is not increased if the quota has been exceeded.
+    pub async fn consume(
+        access_key_id: &AccessKeyId,
+        cost: u64,
+    ) -> Result<Option<QuotaUsage>, Error> {
+        let Some(mut quota) = Self::find_by_access_key_id(access_key_id).await? else {
+            return Ok(None);
+        };
+        quota.reset_expired_usage().await?;
+
+        let mut usage = quota.usage();
+        let daily_exceeded =
+            quota.daily_limit > 0 && quota.daily_usage.saturating_add(cost) > quota.daily_limit;
+        let monthly_exceeded = quota.monthly_limit > 0
+            && quota.monthly_usage.saturating_add(cost) > quota.monthly_limit;
+        if daily_exceeded || monthly_exceeded {
+            usage.exceeded = true;
+            return Ok(Some(usage));
+        }
+
+        // The limits are checked in the filters to prevent the concurrent requests
+        // from exceeding the quota.
+        let mut query = Query::default();
+        query.add_filter("id", quota.id.to_string());
+        if quota.daily_limit > 0 {
+            let max_usage = quota.daily_limit - cost;
+            query.add_filter("daily_usage", Map::from_entry("$le", max_usage));
+        }
+        if quota.monthly_limit > 0 {
+            let max_usage = quota.monthly_limit - cost;
+            query.add_filter("monthly_usage", Map::from_entry("$le", max_usage));
+        }
+
+        let mut increments = Map::new();
+        increments.upsert("daily_usage", cost);
+        increments.upsert("monthly_usage", cost);
+
+        let mut mutation = Mutation::default();
+        mutation.add_update("$inc", increments);
+        let ctx = Self::update_one(&query, &mut mutation).await?;
+        if ctx.rows_affected() == Some(0) {
+            usage.exceeded = true;
+        } else {
+            usage.remaining = usage.remaining.saturating_sub(cost);
+        }
+        Ok(Some(usage))
+    }
+
+    /// Adjusts the plan and limits of the quota for the access key.
+    /// A new quota is created if it does not exist.
+    pub async fn adjust_quota(
+        access_key_id: &AccessKeyId,
+        plan: &str,
+        daily_limit: u64,
+        monthly_limit: u64,
+    ) -> Result<Self, Error> {
+        let mut query = Query::default();
+        query.add_filter("access_key_id", access_key_id.as_str());
+        if let Some(mut quota) = Self::find_one_as::<Self>(&query).await? {
+            let mut mutation = Mutation::default();
+            mutation.add_update("name", plan);
+            mutation.add_update("status", "Active");
+            mutation.add_update("daily_limit", daily_limit);
+            mutation.add_update("monthly_limit", monthly_limit);
+            mutation.add_update("updated_at", DateTime::now());
+            Self::update_one(&query, &mut mutation).await?;
+
+            quota.name = plan.to_owned();
+            quota.status = "Active".to_owned();
+            quota.daily_limit = daily_limit;
+            quota.monthly_limit = monthly_limit;
+            Ok(quota)
+        } else {
+            let quota = Self::with_plan(access_key_id, plan, daily_limit, monthly_limit);
+            quota.clone().insert().await?;
+            Ok(quota)
+        }
+    }
+
+    /// Resets the daily and monthly usage of the quota for the access key.
+    pub async fn reset_usage(access_key_id: &AccessKeyId) -> Result<(), Error> {
+        let now = DateTime::now();
+        let mut query = Query::default();
+        query.add_filter("access_key_id", access_key_id.as_str());
+
+        let mut mutation = Mutation::default();
+        mutation.add_update("daily_usage", 0);
+        mutation.add_update("monthly_usage", 0);
+        mutation.add_update("daily_reset_at", next_daily_reset(now));
+        mutation.add_update("monthly_reset_at", next_monthly_reset(now));
+        Self::update_one(&query, &mut mutation).await?;
+        Ok(())
+    }
+
+    /// Resets the usage if the daily or monthly period has expired.
+    async fn reset_expired_usage(&mut self) -> Result<(), Error> {
+        let now = DateTime::now();
+        let mut mutation = Mutation::default();
+        if self.daily_reset_at <= now {
+            self.daily_usage = 0;
+            self.daily_reset_at = next_daily_reset(now);
+            mutation.add_update("daily_usage", 0);
+            mutation.add_update("daily_reset_at", self.daily_reset_at);
+        }
+        if self.monthly_reset_at <= now {
+            self.monthly_usage = 0;
+            self.monthly_reset_at = next_monthly_reset(now);
+            mutation.add_update("monthly_usage", 0);
+            mutation.add_update("monthly_reset_at", self.monthly_reset_at);
+        }
+        if !mutation.updates().is_empty() {
+            let mut query = Query::default();
+            query.add_filter("id", self.id.to_string());
+            Self::update_one(&query, &mut mutation).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Usage of an access quota for a request.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct QuotaUsage {
+    /// Plan name.
+    plan: String,
+    /// Limit of the period which is closest to be exhausted.
+    limit: u64,
+    /// Remaining requests.
+    remaining: u64,
+    /// Time when the quota is reset.
+    reset_at: DateTime,
+    /// A flag to indicate whether the quota has been exceeded.
+    exceeded: bool,
+}
+
+impl QuotaUsage {
+    /// Returns the plan name.
+    #[inline]
+    pub fn plan(&self) -> &str {
+        &self.plan
+    }
+
+    /// Returns the limit of the period which is closest to be exhausted.
+    /// A limit of `0` means that the quota is unlimited.
+    #[inline]
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    /// Returns the number of remaining requests.
+    #[inline]
+    pub fn remaining(&self) -> u64 {
+        if self.exceeded {
+            0
+        } else {
+            self.remaining
+        }
+    }
+
+    /// Returns the time when the quota is reset.
+    #[inline]
+    pub fn reset_at(&self) -> DateTime {
+        self.reset_at
+    }
+
+    /// Returns `true` if the quota has been exceeded.
+    #[inline]
+    pub fn is_exceeded(&self) -> bool {
+        self.exceeded
+    }
+
+    /// Returns the usage headers for the response. The `x-quota-reset` header
+    /// is the number of seconds since the Unix epoch.
+    pub fn headers(&self) -> Vec<(&'static str, String)> {
+        if self.limit == 0 {
+            return Vec::new();
+        }
+        vec![
+            ("x-ratelimit-limit", self.limit.to_string()),
+            ("x-ratelimit-remaining", self.remaining().to_string()),
+            ("x-quota-reset", self.reset_at.timestamp().to_string()),
+        ]
+    }
+}
+
+/// Returns the remaining requests for the limit.
+#[inline]
+fn remaining(limit: u64, usage: u64) -> Option<u64> {
+    (limit > 0).then(|| limit.saturating_sub(usage))
+}
+
+/// Returns the time for the next daily reset.
+#[inline]
+fn next_daily_reset(now: DateTime) -> DateTime {
+    let start = now.start_of_current_day();
+    start.checked_add_days(1).unwrap_or(start)
+}
+
+/// Returns the time for the next monthly reset.
+#[inline]
+fn next_monthly_reset(now: DateTime) -> DateTime {
+    let start = now.start_of_current_month();
+    start.checked_add_months(1).unwrap_or(start)
+}
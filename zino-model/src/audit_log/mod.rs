@@ -0,0 +1,155 @@
+//! The `audit_log` model and related services.
+
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, net::IpAddr};
+use zino_core::{
+    datetime::DateTime,
+    extension::JsonObjectExt,
+    model::{Model, ModelHooks},
+    validation::Validation,
+    Map, Uuid,
+};
+use zino_derive::{DecodeRow, ModelAccessor, Schema};
+
+/// The `audit_log` model. It records the changes of other models made by the users,
+/// which are keyed by the model name and the model ID.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, DecodeRow, Schema, ModelAccessor)]
+#[serde(default)]
+pub struct AuditLog {
+    // Basic fields.
+    #[schema(read_only)]
+    id: Uuid,
+    #[schema(
+        not_null,
+        read_only,
+        enum_values = "create | update | delete",
+        index_type = "hash"
+    )]
+    name: String,
+    #[cfg(feature = "namespace")]
+    #[schema(default_value = "AuditLog::model_namespace", index_type = "hash")]
+    namespace: String,
+    #[cfg(feature = "visibility")]
+    #[schema(default_value = "Internal")]
+    visibility: String,
+    #[schema(default_value = "Active", index_type = "hash")]
+    status: String,
+    description: String,
+
+    // Info fields.
+    #[schema(not_null, read_only, index_type = "hash")]
+    model_name: String,
+    #[schema(not_null, read_only, index_type = "hash")]
+    model_id: String,
+    #[schema(read_only, index_type = "hash")]
+    user_id: String,
+    #[schema(read_only)]
+    client_ip: String,
+    #[schema(read_only)]
+    changes: Map,
+
+    // Extensions.
+    extra: Map,
+
+    // Revisions.
+    #[schema(read_only, default_value = "now", index_type = "btree")]
+    created_at: DateTime,
+    #[schema(default_value = "now", index_type = "btree")]
+    updated_at: DateTime,
+    version: u64,
+    #[cfg(feature = "edition")]
+    edition: u32,
+}
+
+impl Model for AuditLog {
+    const MODEL_NAME: &'static str = "audit_log";
+
+    #[inline]
+    fn new() -> Self {
+        Self {
+            id: Uuid::now_v7(),
+            ..Self::default()
+        }
+    }
+
+    fn read_map(&mut self, data: &Map) -> Validation {
+        let mut validation = Validation::new();
+        if let Some(result) = data.parse_uuid("id") {
+            match result {
+                Ok(id) => self.id = id,
+                Err(err) => validation.record_fail("id", err),
+            }
+        }
+        if let Some(description) = data.parse_string("description") {
+            self.description = description.into_owned();
+        }
+        validation
+    }
+}
+
+impl ModelHooks for AuditLog {
+    type Data = ();
+    type Extension = ();
+}
+
+impl AuditLog {
+    /// Creates a new audit log of the action on a model,
+    /// where the action is one of `create`, `update` and `delete`.
+    pub fn with_action(action: &str, model_name: &str, model_id: impl Display) -> Self {
+        let mut log = Self::new();
+        log.name = action.to_owned();
+        log.status = "Active".to_owned();
+        log.model_name = model_name.to_owned();
+        log.model_id = model_id.to_string();
+        log
+    }
+
+    /// Sets the user ID of the operator.
+    #[inline]
+    pub fn set_user_id(&mut self, user_id: impl Display) {
+        self.user_id = user_id.to_string();
+    }
+
+    /// Sets the client IP of the operator.
+    #[inline]
+    pub fn set_client_ip(&mut self, client_ip: IpAddr) {
+        self.client_ip = client_ip.to_string();
+    }
+
+    /// Sets the changed fields with the values in the form of
+    /// `{ "before": ..., "after": ... }`.
+    #[inline]
+    pub fn set_changes(&mut self, changes: Map) {
+        self.changes = changes;
+    }
+
+    /// Returns the action of the audit log.
+    #[inline]
+    pub fn action(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the `model_name` field.
+    #[inline]
+    pub fn model_name(&self) -> &str {
+        &self.model_name
+    }
+
+    /// Returns the `model_id` field.
+    #[inline]
+    pub fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    /// Returns the `user_id` field.
+    #[inline]
+    pub fn user_id(&self) -> &str {
+        &self.user_id
+    }
+
+    /// Returns a reference to the changed fields.
+    #[inline]
+    pub fn changes(&self) -> &Map {
+        &self.changes
+    }
+}
@@ -0,0 +1,144 @@
+//! The `delayed_task` model and related services.
+
+use serde::{Deserialize, Serialize};
+use zino_core::{
+    datetime::DateTime,
+    error::Error,
+    extension::JsonObjectExt,
+    model::{Model, ModelHooks, Query},
+    orm::Schema,
+    schedule::{self, TaskStore},
+    validation::Validation,
+    BoxFuture, Map, Uuid,
+};
+use zino_derive::{DecodeRow, ModelAccessor, Schema};
+
+/// The `delayed_task` model.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, DecodeRow, Schema, ModelAccessor)]
+#[serde(default)]
+pub struct DelayedTask {
+    // Basic fields.
+    #[schema(read_only)]
+    id: Uuid,
+    #[schema(not_null, read_only, index_type = "hash")]
+    name: String,
+    #[cfg(feature = "namespace")]
+    #[schema(default_value = "DelayedTask::model_namespace", index_type = "hash")]
+    namespace: String,
+    #[cfg(feature = "visibility")]
+    #[schema(default_value = "Internal")]
+    visibility: String,
+    #[schema(default_value = "Active", index_type = "hash")]
+    status: String,
+    description: String,
+
+    // Info fields.
+    #[schema(read_only, index_type = "btree")]
+    run_at: DateTime,
+    #[schema(read_only)]
+    data: Map,
+
+    // Extensions.
+    extra: Map,
+
+    // Revisions.
+    #[schema(read_only, default_value = "now", index_type = "btree")]
+    created_at: DateTime,
+    #[schema(default_value = "now", index_type = "btree")]
+    updated_at: DateTime,
+    version: u64,
+    #[cfg(feature = "edition")]
+    edition: u32,
+}
+
+impl Model for DelayedTask {
+    const MODEL_NAME: &'static str = "delayed_task";
+
+    #[inline]
+    fn new() -> Self {
+        Self {
+            id: Uuid::now_v7(),
+            ..Self::default()
+        }
+    }
+
+    fn read_map(&mut self, data: &Map) -> Validation {
+        let mut validation = Validation::new();
+        if let Some(result) = data.parse_uuid("id") {
+            match result {
+                Ok(id) => self.id = id,
+                Err(err) => validation.record_fail("id", err),
+            }
+        }
+        if let Some(description) = data.parse_string("description") {
+            self.description = description.into_owned();
+        }
+        validation
+    }
+}
+
+impl ModelHooks for DelayedTask {
+    type Data = ();
+    type Extension = ();
+}
+
+impl From<schedule::DelayedTask> for DelayedTask {
+    fn from(task: schedule::DelayedTask) -> Self {
+        Self {
+            id: task.id(),
+            name: task.name().to_owned(),
+            run_at: task.run_at(),
+            data: task.data().clone(),
+            ..Self::new()
+        }
+    }
+}
+
+impl From<DelayedTask> for schedule::DelayedTask {
+    #[inline]
+    fn from(task: DelayedTask) -> Self {
+        Self::restore(task.id, task.name, task.data, task.run_at)
+    }
+}
+
+/// A task store which persists the delayed tasks into the `delayed_task` table.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino_model::delayed_task::DelayedTaskStore;
+///
+/// let mut scheduler = AsyncJobScheduler::new();
+/// scheduler.set_task_store(DelayedTaskStore);
+/// scheduler.register_task("unlock_account", unlock_account as AsyncTask);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DelayedTaskStore;
+
+impl TaskStore for DelayedTaskStore {
+    fn save(&self, task: schedule::DelayedTask) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move {
+            DelayedTask::from(task).insert().await?;
+            Ok(())
+        })
+    }
+
+    fn remove(&self, task_id: Uuid) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move {
+            DelayedTask::delete_by_id(&task_id).await?;
+            Ok(())
+        })
+    }
+
+    fn load(&self) -> BoxFuture<'_, Result<Vec<schedule::DelayedTask>, Error>> {
+        Box::pin(async move {
+            let mut query = Query::default();
+            query.add_filter("status", "Active");
+            query.order_asc("run_at");
+            query.disable_limit();
+
+            let tasks = DelayedTask::find::<DelayedTask>(&query).await?;
+            Ok(tasks.into_iter().map(schedule::DelayedTask::from).collect())
+        })
+    }
+}
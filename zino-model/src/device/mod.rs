@@ -0,0 +1,249 @@
+//! The `device` model and related services.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use zino_core::{
+    datetime::DateTime,
+    error::Error,
+    extension::JsonObjectExt,
+    model::{Model, ModelHooks, Mutation, Query},
+    orm::Schema,
+    validation::Validation,
+    Map, Uuid,
+};
+use zino_derive::{DecodeRow, ModelAccessor, Schema};
+
+#[cfg(any(feature = "owner-id", feature = "maintainer-id"))]
+use crate::user::User;
+
+/// The `device` model. It tracks a login session of the user on a device.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, DecodeRow, Schema, ModelAccessor)]
+#[serde(default)]
+pub struct Device {
+    // Basic fields.
+    #[schema(read_only)]
+    id: Uuid,
+    #[schema(not_null)]
+    name: String,
+    #[cfg(feature = "namespace")]
+    #[schema(default_value = "Device::model_namespace", index_type = "hash")]
+    namespace: String,
+    #[cfg(feature = "visibility")]
+    #[schema(default_value = "Internal")]
+    visibility: String,
+    #[schema(
+        enum_values = "Active | Revoked",
+        default_value = "Active",
+        index_type = "hash"
+    )]
+    status: String,
+    description: String,
+
+    // Info fields.
+    #[schema(not_null, read_only, index_type = "hash")]
+    user_id: String, // user.id
+    #[schema(not_null, read_only)]
+    user_agent: String,
+    #[schema(format = "ip")]
+    client_ip: String,
+    geo_location: String,
+    first_seen_at: DateTime,
+    last_seen_at: DateTime,
+    revoked_at: Option<DateTime>,
+
+    // Extensions.
+    extra: Map,
+
+    // Revisions.
+    #[cfg(feature = "owner-id")]
+    #[schema(reference = "User")]
+    owner_id: Option<Uuid>, // user.id
+    #[cfg(feature = "maintainer-id")]
+    #[schema(reference = "User")]
+    maintainer_id: Option<Uuid>, // user.id
+    #[schema(read_only, default_value = "now", index_type = "btree")]
+    created_at: DateTime,
+    #[schema(default_value = "now", index_type = "btree")]
+    updated_at: DateTime,
+    version: u64,
+    #[cfg(feature = "edition")]
+    edition: u32,
+}
+
+impl Model for Device {
+    const MODEL_NAME: &'static str = "device";
+
+    #[inline]
+    fn new() -> Self {
+        let current_time = DateTime::now();
+        Self {
+            id: Uuid::now_v7(),
+            status: "Active".to_owned(),
+            first_seen_at: current_time,
+            last_seen_at: current_time,
+            ..Self::default()
+        }
+    }
+
+    fn read_map(&mut self, data: &Map) -> Validation {
+        let mut validation = Validation::new();
+        if let Some(result) = data.parse_uuid("id") {
+            match result {
+                Ok(id) => self.id = id,
+                Err(err) => validation.record_fail("id", err),
+            }
+        }
+        if let Some(name) = data.parse_string("name") {
+            self.name = name.into_owned();
+        }
+        if let Some(description) = data.parse_string("description") {
+            self.description = description.into_owned();
+        }
+        validation
+    }
+}
+
+impl ModelHooks for Device {
+    type Data = ();
+    type Extension = ();
+}
+
+impl Device {
+    /// Creates a new device for the user.
+    pub fn with_user_agent(user_id: impl Display, user_agent: &str) -> Self {
+        let mut device = Self::new();
+        device.name = if user_agent.is_empty() {
+            "Unknown device".to_owned()
+        } else {
+            user_agent.to_owned()
+        };
+        device.user_id = user_id.to_string();
+        device.user_agent = user_agent.to_owned();
+        device
+    }
+
+    /// Sets the `client_ip` field.
+    #[inline]
+    pub fn set_client_ip(&mut self, client_ip: impl Into<String>) {
+        self.client_ip = client_ip.into();
+    }
+
+    /// Sets the `geo_location` field.
+    #[inline]
+    pub fn set_geo_location(&mut self, geo_location: impl Into<String>) {
+        self.geo_location = geo_location.into();
+    }
+
+    /// Returns the `user_id` field.
+    #[inline]
+    pub fn user_id(&self) -> &str {
+        &self.user_id
+    }
+
+    /// Returns the `user_agent` field.
+    #[inline]
+    pub fn user_agent(&self) -> &str {
+        &self.user_agent
+    }
+
+    /// Returns the `client_ip` field.
+    #[inline]
+    pub fn client_ip(&self) -> &str {
+        &self.client_ip
+    }
+
+    /// Returns the `geo_location` field.
+    #[inline]
+    pub fn geo_location(&self) -> &str {
+        &self.geo_location
+    }
+
+    /// Returns the `last_seen_at` field.
+    #[inline]
+    pub fn last_seen_at(&self) -> DateTime {
+        self.last_seen_at
+    }
+
+    /// Returns the `revoked_at` field.
+    #[inline]
+    pub fn revoked_at(&self) -> Option<DateTime> {
+        self.revoked_at
+    }
+
+    /// Returns `true` if the device has been revoked.
+    #[inline]
+    pub fn is_revoked(&self) -> bool {
+        self.status == "Revoked"
+    }
+
+    /// Finds the device of the user by the user agent.
+    pub async fn find_by_user_agent(
+        user_id: impl Display,
+        user_agent: &str,
+    ) -> Result<Option<Self>, Error> {
+        let mut query = Query::default();
+        query.add_filter("user_id", user_id.to_string());
+        query.add_filter("user_agent", user_agent);
+        Self::find_one_as(&query).await
+    }
+
+    /// Finds the device of the user by the device ID.
+    pub async fn find_by_user(
+        user_id: impl Display,
+        device_id: &Uuid,
+    ) -> Result<Option<Self>, Error> {
+        let mut query = Query::default();
+        query.add_filter("id", device_id.to_string());
+        query.add_filter("user_id", user_id.to_string());
+        Self::find_one_as(&query).await
+    }
+
+    /// Lists the devices of the user in the order of the last seen time.
+    pub async fn list_by_user(user_id: impl Display) -> Result<Vec<Map>, Error> {
+        let mut query = Query::default();
+        query.allow_fields(&[
+            "id",
+            "name",
+            "status",
+            "user_agent",
+            "client_ip",
+            "geo_location",
+            "first_seen_at",
+            "last_seen_at",
+            "revoked_at",
+        ]);
+        query.add_filter("user_id", user_id.to_string());
+        query.order_desc("last_seen_at");
+        Self::find(&query).await
+    }
+
+    /// Revokes a device of the user.
+    pub async fn revoke(user_id: impl Display, device_id: &Uuid) -> Result<bool, Error> {
+        let mut query = Query::default();
+        query.add_filter("id", device_id.to_string());
+        query.add_filter("user_id", user_id.to_string());
+        let ctx = Self::update_one(&query, &mut Self::revocation_mutation()).await?;
+        Ok(ctx.rows_affected().is_some_and(|rows| rows > 0))
+    }
+
+    /// Revokes all the devices of the user except for an optional device,
+    /// and returns the number of revoked devices.
+    pub async fn revoke_all(user_id: impl Display, except: Option<&Uuid>) -> Result<u64, Error> {
+        let mut query = Query::default();
+        query.add_filter("user_id", user_id.to_string());
+        query.add_filter("status", "Active");
+        if let Some(device_id) = except {
+            query.add_filter("id", Map::from_entry("$ne", device_id.to_string()));
+        }
+        let ctx = Self::update_many(&query, &mut Self::revocation_mutation()).await?;
+        Ok(ctx.rows_affected().unwrap_or_default())
+    }
+
+    /// Returns a mutation to revoke the device.
+    fn revocation_mutation() -> Mutation {
+        let mut mutation = Mutation::default();
+        mutation.add_update("status", "Revoked");
+        mutation.add_update("revoked_at", DateTime::now());
+        mutation
+    }
+}
@@ -7,6 +7,7 @@ use zino_core::{
     error::Error,
     extension::JsonObjectExt,
     model::{Model, ModelHooks},
+    orm::TreeQuery,
     validation::Validation,
     Map, Uuid,
 };
@@ -21,6 +22,7 @@ use zino_core::auth::UserSession;
 /// The `group` model.
 #[derive(Debug, Clone, Default, Serialize, Deserialize, DecodeRow, Schema, ModelAccessor)]
 #[serde(default)]
+#[schema(tree = "parent_id")]
 pub struct Group {
     // Basic fields.
     #[schema(read_only)]
@@ -38,6 +40,10 @@ pub struct Group {
     description: String,
 
     // Info fields.
+    #[schema(snapshot, reference = "Group")]
+    parent_id: Option<Uuid>, // group.id
+    #[schema(index_type = "btree")]
+    path: String,
     #[schema(reference = "User")]
     manager_id: Uuid, // user.id
     #[schema(reference = "User", index_type = "gin")]
@@ -90,6 +96,12 @@ impl Model for Group {
         if let Some(description) = data.parse_string("description") {
             self.description = description.into_owned();
         }
+        if let Some(result) = data.parse_uuid("parent_id") {
+            match result {
+                Ok(parent_id) => self.parent_id = Some(parent_id),
+                Err(err) => validation.record_fail("parent_id", err),
+            }
+        }
         if let Some(result) = data.parse_uuid("manager_id") {
             match result {
                 Ok(manager_id) => self.manager_id = manager_id,
@@ -153,3 +165,28 @@ impl ModelHooks for Group {
         Ok(())
     }
 }
+
+impl Group {
+    /// Returns the `parent_id`.
+    #[inline]
+    pub fn parent_id(&self) -> Option<&Uuid> {
+        self.parent_id
+            .as_ref()
+            .filter(|parent_id| !parent_id.is_nil())
+    }
+
+    /// Returns the `members` field.
+    #[inline]
+    pub fn members(&self) -> &[Uuid] {
+        self.members.as_slice()
+    }
+
+    /// Resolves the members of the group and all its subgroups.
+    pub async fn resolve_members(id: &Uuid) -> Result<Vec<Uuid>, Error> {
+        let members = Self::subtree_members(id, "members").await?;
+        Ok(members
+            .into_iter()
+            .filter_map(|member| member.as_str().and_then(|s| s.parse().ok()))
+            .collect())
+    }
+}
@@ -0,0 +1,127 @@
+//! The `job_run` model and related services.
+
+use serde::{Deserialize, Serialize};
+use zino_core::{
+    datetime::DateTime,
+    extension::JsonObjectExt,
+    model::{Model, ModelHooks},
+    orm::Schema,
+    schedule,
+    validation::Validation,
+    BoxFuture, Map, Uuid,
+};
+use zino_derive::{DecodeRow, ModelAccessor, Schema};
+
+/// The `job_run` model.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, DecodeRow, Schema, ModelAccessor)]
+#[serde(default)]
+pub struct JobRun {
+    // Basic fields.
+    #[schema(read_only)]
+    id: Uuid,
+    #[schema(not_null, read_only, index_type = "hash")]
+    name: String,
+    #[cfg(feature = "namespace")]
+    #[schema(default_value = "JobRun::model_namespace", index_type = "hash")]
+    namespace: String,
+    #[cfg(feature = "visibility")]
+    #[schema(default_value = "Internal")]
+    visibility: String,
+    #[schema(default_value = "Active", index_type = "hash")]
+    status: String,
+    description: String,
+
+    // Info fields.
+    #[schema(read_only, index_type = "hash")]
+    job_id: Uuid,
+    #[schema(read_only, index_type = "btree")]
+    started_at: DateTime,
+    #[schema(read_only)]
+    duration: u64, // in milliseconds
+    #[schema(
+        read_only,
+        enum_values = "Success | Failure | Timeout | Cancelled",
+        index_type = "hash"
+    )]
+    outcome: String,
+    #[schema(read_only)]
+    error_message: String,
+    #[schema(read_only)]
+    output: String,
+    #[schema(read_only)]
+    manual: bool,
+
+    // Extensions.
+    #[schema(read_only)]
+    extra: Map,
+
+    // Revisions.
+    #[schema(read_only, default_value = "now", index_type = "btree")]
+    created_at: DateTime,
+    #[schema(default_value = "now", index_type = "btree")]
+    updated_at: DateTime,
+    version: u64,
+    #[cfg(feature = "edition")]
+    edition: u32,
+}
+
+impl Model for JobRun {
+    const MODEL_NAME: &'static str = "job_run";
+
+    #[inline]
+    fn new() -> Self {
+        Self {
+            id: Uuid::now_v7(),
+            ..Self::default()
+        }
+    }
+
+    fn read_map(&mut self, data: &Map) -> Validation {
+        let mut validation = Validation::new();
+        if let Some(result) = data.parse_uuid("id") {
+            match result {
+                Ok(id) => self.id = id,
+                Err(err) => validation.record_fail("id", err),
+            }
+        }
+        if let Some(description) = data.parse_string("description") {
+            self.description = description.into_owned();
+        }
+        validation
+    }
+}
+
+impl ModelHooks for JobRun {
+    type Data = ();
+    type Extension = ();
+}
+
+impl From<schedule::JobRun> for JobRun {
+    fn from(run: schedule::JobRun) -> Self {
+        Self {
+            id: run.id(),
+            name: run.job_name().to_owned(),
+            job_id: run.job_id(),
+            started_at: run.started_at(),
+            duration: run.duration().as_millis() as u64,
+            outcome: run.outcome().as_str().to_owned(),
+            error_message: run.error().unwrap_or_default().to_owned(),
+            output: run.output().unwrap_or_default().to_owned(),
+            manual: run.is_manual(),
+            ..Self::new()
+        }
+    }
+}
+
+impl JobRun {
+    /// Records the job runs into the table.
+    /// It can be used as the recorder of the `AsyncJobScheduler`.
+    pub fn record_runs(runs: Vec<schedule::JobRun>) -> BoxFuture<'static> {
+        Box::pin(async move {
+            let models = runs.into_iter().map(Self::from).collect::<Vec<_>>();
+            if let Err(err) = Self::insert_many(models).await {
+                tracing::error!("fail to record the job runs: {err}");
+            }
+        })
+    }
+}
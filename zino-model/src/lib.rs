@@ -5,12 +5,16 @@
 #![allow(async_fn_in_trait)]
 #![forbid(unsafe_code)]
 
+pub mod device;
 pub mod group;
+pub mod login_attempt;
 pub mod policy;
 pub mod resource;
 pub mod tag;
 pub mod user;
+pub mod user_token;
 
+pub mod access_quota;
 pub mod application;
 pub mod message;
 pub mod order;
@@ -21,15 +25,24 @@ pub mod project;
 pub mod source;
 pub mod task;
 
+pub mod audit_log;
+pub mod delayed_task;
+pub mod job_run;
 pub mod log;
+pub mod model_snapshot;
+pub mod outbox_event;
 pub mod record;
 
+pub use device::Device;
 pub use group::Group;
+pub use login_attempt::LoginAttempt;
 pub use policy::Policy;
 pub use resource::Resource;
 pub use tag::Tag;
 pub use user::User;
+pub use user_token::UserToken;
 
+pub use access_quota::AccessQuota;
 pub use application::Application;
 pub use message::Message;
 pub use order::Order;
@@ -40,5 +53,10 @@ pub use project::Project;
 pub use source::Source;
 pub use task::Task;
 
+pub use audit_log::AuditLog;
+pub use delayed_task::DelayedTask;
+pub use job_run::JobRun;
 pub use log::Log;
+pub use model_snapshot::ModelSnapshot;
+pub use outbox_event::OutboxEvent;
 pub use record::Record;
@@ -0,0 +1,214 @@
+//! The `login_attempt` model and related services.
+
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, time::Duration};
+use zino_core::{
+    datetime::DateTime,
+    error::Error,
+    extension::JsonObjectExt,
+    model::{Model, ModelHooks, Query},
+    orm::Schema,
+    validation::Validation,
+    Map, Uuid,
+};
+use zino_derive::{DecodeRow, ModelAccessor, Schema};
+
+use crate::device::Device;
+
+#[cfg(any(feature = "owner-id", feature = "maintainer-id"))]
+use crate::user::User;
+
+/// The `login_attempt` model.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, DecodeRow, Schema, ModelAccessor)]
+#[serde(default)]
+pub struct LoginAttempt {
+    // Basic fields.
+    #[schema(read_only)]
+    id: Uuid,
+    #[schema(not_null)]
+    name: String,
+    #[cfg(feature = "namespace")]
+    #[schema(default_value = "LoginAttempt::model_namespace", index_type = "hash")]
+    namespace: String,
+    #[cfg(feature = "visibility")]
+    #[schema(default_value = "Internal")]
+    visibility: String,
+    #[schema(
+        enum_values = "Succeeded | Failed",
+        default_value = "Failed",
+        index_type = "hash"
+    )]
+    status: String,
+    description: String,
+
+    // Info fields.
+    #[schema(read_only, index_type = "hash")]
+    user_id: String, // user.id
+    #[schema(not_null, read_only, index_type = "hash")]
+    account: String,
+    #[schema(read_only, format = "ip")]
+    client_ip: String,
+    #[schema(read_only)]
+    user_agent: String,
+    geo_location: String,
+    #[schema(reference = "Device")]
+    device_id: Option<Uuid>, // device.id
+    failure_reason: String,
+    #[schema(read_only, index_type = "btree")]
+    attempted_at: DateTime,
+
+    // Extensions.
+    extra: Map,
+
+    // Revisions.
+    #[cfg(feature = "owner-id")]
+    #[schema(reference = "User")]
+    owner_id: Option<Uuid>, // user.id
+    #[cfg(feature = "maintainer-id")]
+    #[schema(reference = "User")]
+    maintainer_id: Option<Uuid>, // user.id
+    #[schema(read_only, default_value = "now", index_type = "btree")]
+    created_at: DateTime,
+    #[schema(default_value = "now", index_type = "btree")]
+    updated_at: DateTime,
+    version: u64,
+    #[cfg(feature = "edition")]
+    edition: u32,
+}
+
+impl Model for LoginAttempt {
+    const MODEL_NAME: &'static str = "login_attempt";
+
+    #[inline]
+    fn new() -> Self {
+        Self {
+            id: Uuid::now_v7(),
+            attempted_at: DateTime::now(),
+            ..Self::default()
+        }
+    }
+
+    fn read_map(&mut self, data: &Map) -> Validation {
+        let mut validation = Validation::new();
+        if let Some(result) = data.parse_uuid("id") {
+            match result {
+                Ok(id) => self.id = id,
+                Err(err) => validation.record_fail("id", err),
+            }
+        }
+        if let Some(name) = data.parse_string("name") {
+            self.name = name.into_owned();
+        }
+        if let Some(description) = data.parse_string("description") {
+            self.description = description.into_owned();
+        }
+        if let Some(geo_location) = data.parse_string("geo_location") {
+            self.geo_location = geo_location.into_owned();
+        }
+        validation
+    }
+}
+
+impl ModelHooks for LoginAttempt {
+    type Data = ();
+    type Extension = ();
+}
+
+impl LoginAttempt {
+    /// Creates a new failed attempt for the account.
+    /// It should be marked as succeeded after the credentials have been verified.
+    pub fn with_account(account: &str, client_ip: &str, user_agent: &str) -> Self {
+        let mut attempt = Self::new();
+        attempt.name = account.to_owned();
+        attempt.status = "Failed".to_owned();
+        attempt.account = account.to_owned();
+        attempt.client_ip = client_ip.to_owned();
+        attempt.user_agent = user_agent.to_owned();
+        attempt
+    }
+
+    /// Marks the attempt as succeeded for the user.
+    #[inline]
+    pub fn mark_succeeded(&mut self, user_id: impl Display) {
+        self.status = "Succeeded".to_owned();
+        self.user_id = user_id.to_string();
+        self.failure_reason.clear();
+    }
+
+    /// Marks the attempt as failed with a reason.
+    #[inline]
+    pub fn mark_failed(&mut self, reason: impl ToString) {
+        self.status = "Failed".to_owned();
+        self.failure_reason = reason.to_string();
+    }
+
+    /// Sets the `geo_location` field.
+    #[inline]
+    pub fn set_geo_location(&mut self, geo_location: impl Into<String>) {
+        self.geo_location = geo_location.into();
+    }
+
+    /// Sets the `device_id` field.
+    #[inline]
+    pub fn set_device_id(&mut self, device_id: Uuid) {
+        self.device_id = Some(device_id);
+    }
+
+    /// Returns `true` if the attempt has succeeded.
+    #[inline]
+    pub fn is_succeeded(&self) -> bool {
+        self.status == "Succeeded"
+    }
+
+    /// Returns the `user_id` field.
+    #[inline]
+    pub fn user_id(&self) -> &str {
+        &self.user_id
+    }
+
+    /// Returns the `account` field.
+    #[inline]
+    pub fn account(&self) -> &str {
+        &self.account
+    }
+
+    /// Returns the `client_ip` field.
+    #[inline]
+    pub fn client_ip(&self) -> &str {
+        &self.client_ip
+    }
+
+    /// Returns the `user_agent` field.
+    #[inline]
+    pub fn user_agent(&self) -> &str {
+        &self.user_agent
+    }
+
+    /// Returns the `geo_location` field.
+    #[inline]
+    pub fn geo_location(&self) -> &str {
+        &self.geo_location
+    }
+
+    /// Returns the `failure_reason` field.
+    #[inline]
+    pub fn failure_reason(&self) -> &str {
+        &self.failure_reason
+    }
+
+    /// Returns the `attempted_at` field.
+    #[inline]
+    pub fn attempted_at(&self) -> DateTime {
+        self.attempted_at
+    }
+
+    /// Counts the failed attempts for the account within a recent period.
+    pub async fn count_recent_failures(account: &str, period: Duration) -> Result<u64, Error> {
+        let since = DateTime::now() - period;
+        let mut query = Query::default();
+        query.add_filter("account", account);
+        query.add_filter("status", "Failed");
+        query.add_filter("attempted_at", Map::from_entry("$ge", since));
+        Self::count(&query).await
+    }
+}
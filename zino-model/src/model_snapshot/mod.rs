@@ -0,0 +1,139 @@
+//! The `model_snapshot` model and related services.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use zino_core::{
+    datetime::DateTime,
+    extension::JsonObjectExt,
+    model::{Model, ModelHooks},
+    validation::Validation,
+    Map, Uuid,
+};
+use zino_derive::{DecodeRow, ModelAccessor, Schema};
+
+mod service;
+
+pub use service::ModelSnapshotService;
+
+/// The `model_snapshot` model. It stores the tagged snapshots of other models,
+/// which are keyed by the model name and the model ID.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, DecodeRow, Schema, ModelAccessor)]
+#[serde(default)]
+pub struct ModelSnapshot {
+    // Basic fields.
+    #[schema(read_only)]
+    id: Uuid,
+    #[schema(not_null, read_only, index_type = "hash")]
+    name: String,
+    #[cfg(feature = "namespace")]
+    #[schema(default_value = "ModelSnapshot::model_namespace", index_type = "hash")]
+    namespace: String,
+    #[cfg(feature = "visibility")]
+    #[schema(default_value = "Internal")]
+    visibility: String,
+    #[schema(default_value = "Active", index_type = "hash")]
+    status: String,
+    description: String,
+
+    // Info fields.
+    #[schema(not_null, read_only, index_type = "hash")]
+    model_name: String,
+    #[schema(not_null, read_only, index_type = "hash")]
+    model_id: String,
+    #[schema(read_only)]
+    model_version: u64,
+    #[schema(read_only)]
+    data: Map,
+
+    // Extensions.
+    extra: Map,
+
+    // Revisions.
+    #[schema(read_only, default_value = "now", index_type = "btree")]
+    created_at: DateTime,
+    #[schema(default_value = "now", index_type = "btree")]
+    updated_at: DateTime,
+    version: u64,
+    #[cfg(feature = "edition")]
+    edition: u32,
+}
+
+impl Model for ModelSnapshot {
+    const MODEL_NAME: &'static str = "model_snapshot";
+
+    #[inline]
+    fn new() -> Self {
+        Self {
+            id: Uuid::now_v7(),
+            ..Self::default()
+        }
+    }
+
+    fn read_map(&mut self, data: &Map) -> Validation {
+        let mut validation = Validation::new();
+        if let Some(result) = data.parse_uuid("id") {
+            match result {
+                Ok(id) => self.id = id,
+                Err(err) => validation.record_fail("id", err),
+            }
+        }
+        if let Some(description) = data.parse_string("description") {
+            self.description = description.into_owned();
+        }
+        validation
+    }
+}
+
+impl ModelHooks for ModelSnapshot {
+    type Data = ();
+    type Extension = ();
+}
+
+impl ModelSnapshot {
+    /// Creates a new snapshot of the model data with a label.
+    pub fn with_model_data(
+        model_name: &str,
+        model_id: impl Display,
+        label: &str,
+        data: Map,
+    ) -> Self {
+        let mut snapshot = Self::new();
+        snapshot.name = label.to_owned();
+        snapshot.status = "Active".to_owned();
+        snapshot.model_name = model_name.to_owned();
+        snapshot.model_id = model_id.to_string();
+        snapshot.model_version = data.get_u64("version").unwrap_or_default();
+        snapshot.data = data;
+        snapshot
+    }
+
+    /// Returns the label of the snapshot.
+    #[inline]
+    pub fn label(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the `model_id` field.
+    #[inline]
+    pub fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    /// Returns the `model_version` field.
+    #[inline]
+    pub fn model_version(&self) -> u64 {
+        self.model_version
+    }
+
+    /// Returns a reference to the snapshot data.
+    #[inline]
+    pub fn data(&self) -> &Map {
+        &self.data
+    }
+
+    /// Consumes `self` and returns the snapshot data.
+    #[inline]
+    pub fn into_data(self) -> Map {
+        self.data
+    }
+}
@@ -0,0 +1,145 @@
+use super::ModelSnapshot;
+use std::fmt::Display;
+use zino_core::{
+    bail,
+    error::Error,
+    extension::JsonObjectExt,
+    model::{ModelHooks, Query},
+    orm::{ModelAccessor, Schema},
+    validation::Validation,
+    warn, JsonValue, Map,
+};
+
+/// Snapshot service for tagging the versions of models and rolling back to them.
+///
+/// The snapshots are persisted in the [`ModelSnapshot`] table,
+/// which are keyed by the model name and the model ID.
+pub trait ModelSnapshotService<K>: ModelAccessor<K>
+where
+    K: Default + Display + PartialEq,
+{
+    /// Fields ignored by the diffs and rollbacks.
+    const SNAPSHOT_IGNORED_FIELDS: &'static [&'static str] =
+        &["created_at", "updated_at", "version", "edition"];
+
+    /// Takes a snapshot of the model with a label, such as `before-price-change`.
+    /// The data is loaded from the table, so the unsaved changes are not included.
+    async fn take_snapshot(&self, label: &str) -> Result<ModelSnapshot, Error> {
+        Self::snapshot_by_id(self.id(), label).await
+    }
+
+    /// Takes a snapshot of the model selected by the primary key.
+    async fn snapshot_by_id(id: &K, label: &str) -> Result<ModelSnapshot, Error> {
+        if label.is_empty() {
+            bail!("the snapshot label should be nonempty");
+        }
+
+        let query = snapshot_query::<Self, K>(id, Some(label));
+        if ModelSnapshot::exists(&query).await? {
+            bail!(
+                "409 Conflict: the snapshot `{}` already exists for the model `{}`",
+                label,
+                id
+            );
+        }
+
+        let data = Self::find_by_id::<Map>(id)
+            .await?
+            .ok_or_else(|| warn!("404 Not Found: cannot find the model `{}`", id))?;
+        let snapshot = ModelSnapshot::with_model_data(Self::model_name(), id, label, data);
+        snapshot.clone().insert().await?;
+        Ok(snapshot)
+    }
+
+    /// Lists the snapshots of the model, in the order from the latest to the earliest.
+    /// The snapshot data is not included.
+    async fn list_snapshots(id: &K) -> Result<Vec<Map>, Error> {
+        let mut query = snapshot_query::<Self, K>(id, None);
+        query.allow_fields(&["id", "name", "description", "model_version", "created_at"]);
+        query.order_desc("created_at");
+        query.disable_limit();
+        ModelSnapshot::find(&query).await
+    }
+
+    /// Finds the snapshot of the model with the label.
+    async fn find_snapshot(id: &K, label: &str) -> Result<ModelSnapshot, Error> {
+        let query = snapshot_query::<Self, K>(id, Some(label));
+        ModelSnapshot::find_one_as(&query).await?.ok_or_else(|| {
+            warn!(
+                "404 Not Found: cannot find the snapshot `{}` for the model `{}`",
+                label, id
+            )
+        })
+    }
+
+    /// Diffs the snapshot against the current data of the model.
+    /// It returns the changed fields with the values in the form of
+    /// `{ "snapshot": ..., "current": ... }`.
+    async fn diff_snapshot(id: &K, label: &str) -> Result<Map, Error> {
+        let snapshot_data = Self::find_snapshot(id, label).await?.into_data();
+        let mut current_data = Self::find_by_id::<Map>(id)
+            .await?
+            .ok_or_else(|| warn!("404 Not Found: cannot find the model `{}`", id))?;
+        let ignored_fields = Self::SNAPSHOT_IGNORED_FIELDS;
+        let mut changes = Map::new();
+        for (key, value) in snapshot_data {
+            if ignored_fields.contains(&key.as_str()) {
+                continue;
+            }
+
+            let current_value = current_data.remove(&key).unwrap_or_default();
+            if current_value != value {
+                let mut change = Map::new();
+                change.upsert("snapshot", value);
+                change.upsert("current", current_value);
+                changes.upsert(key, change);
+            }
+        }
+        for (key, value) in current_data {
+            if !ignored_fields.contains(&key.as_str()) {
+                let mut change = Map::new();
+                change.upsert("snapshot", JsonValue::Null);
+                change.upsert("current", value);
+                changes.upsert(key, change);
+            }
+        }
+        Ok(changes)
+    }
+
+    /// Rolls back the model to the snapshot with the label.
+    /// The primary key, read-only fields and ignored fields are not restored.
+    async fn rollback_to_snapshot(
+        id: &K,
+        label: &str,
+        extension: Option<<Self as ModelHooks>::Extension>,
+    ) -> Result<(Validation, Self), Error> {
+        let mut data = Self::find_snapshot(id, label).await?.into_data();
+        let read_only_fields = Self::read_only_fields();
+        data.retain(|key, _value| {
+            let key = key.as_str();
+            key != Self::PRIMARY_KEY_NAME
+                && !read_only_fields.contains(&key)
+                && !Self::SNAPSHOT_IGNORED_FIELDS.contains(&key)
+        });
+        Self::update_by_id(id, &mut data, extension).await
+    }
+}
+
+impl<M, K> ModelSnapshotService<K> for M
+where
+    M: ModelAccessor<K>,
+    K: Default + Display + PartialEq,
+{
+}
+
+/// Constructs a query for the snapshots of the model.
+fn snapshot_query<M: Schema, K: Display>(id: &K, label: Option<&str>) -> Query {
+    let mut query = Query::default();
+    query.add_filter("model_name", M::model_name());
+    query.add_filter("model_id", id.to_string());
+    if let Some(label) = label {
+        query.add_filter("name", label);
+    }
+    query.add_filter("status", Map::from_entry("$ne", "Deleted"));
+    query
+}
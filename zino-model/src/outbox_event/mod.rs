@@ -0,0 +1,177 @@
+//! The `outbox_event` model and related services.
+
+use serde::{Deserialize, Serialize};
+use zino_core::{
+    channel::{CloudEvent, Projection},
+    datetime::DateTime,
+    error::Error,
+    extension::JsonObjectExt,
+    model::{Model, ModelHooks, Mutation, Query},
+    orm::Schema,
+    validation::Validation,
+    BoxFuture, JsonValue, Map, Uuid,
+};
+use zino_derive::{DecodeRow, ModelAccessor, Schema};
+
+/// The `outbox_event` model. It persists the published model events,
+/// which can be replayed to rebuild the projections.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, DecodeRow, Schema, ModelAccessor)]
+#[serde(default)]
+pub struct OutboxEvent {
+    // Basic fields.
+    #[schema(read_only)]
+    id: Uuid,
+    #[schema(not_null, read_only, index_type = "hash")]
+    name: String,
+    #[cfg(feature = "namespace")]
+    #[schema(default_value = "OutboxEvent::model_namespace", index_type = "hash")]
+    namespace: String,
+    #[cfg(feature = "visibility")]
+    #[schema(default_value = "Internal")]
+    visibility: String,
+    #[schema(
+        enum_values = "Pending | Published | Failed",
+        default_value = "Pending",
+        index_type = "hash"
+    )]
+    status: String,
+    description: String,
+
+    // Info fields.
+    #[schema(not_null, read_only, index_type = "hash")]
+    source: String,
+    #[schema(read_only, index_type = "hash")]
+    subject: String,
+    #[schema(read_only)]
+    event: Map,
+    published_at: Option<DateTime>,
+
+    // Extensions.
+    extra: Map,
+
+    // Revisions.
+    #[schema(read_only, default_value = "now", index_type = "btree")]
+    created_at: DateTime,
+    #[schema(default_value = "now", index_type = "btree")]
+    updated_at: DateTime,
+    version: u64,
+    #[cfg(feature = "edition")]
+    edition: u32,
+}
+
+impl Model for OutboxEvent {
+    const MODEL_NAME: &'static str = "outbox_event";
+
+    #[inline]
+    fn new() -> Self {
+        Self {
+            id: Uuid::now_v7(),
+            status: "Pending".to_owned(),
+            ..Self::default()
+        }
+    }
+
+    fn read_map(&mut self, data: &Map) -> Validation {
+        let mut validation = Validation::new();
+        if let Some(result) = data.parse_uuid("id") {
+            match result {
+                Ok(id) => self.id = id,
+                Err(err) => validation.record_fail("id", err),
+            }
+        }
+        if let Some(description) = data.parse_string("description") {
+            self.description = description.into_owned();
+        }
+        validation
+    }
+}
+
+impl ModelHooks for OutboxEvent {
+    type Data = ();
+    type Extension = ();
+}
+
+impl OutboxEvent {
+    /// Creates a new record for the cloud event.
+    pub fn with_cloud_event(event: &CloudEvent) -> Self {
+        let mut record = Self::new();
+        if let Ok(id) = event.id().parse() {
+            record.id = id;
+        }
+        record.name = event.event_type().to_owned();
+        record.source = event.source().to_owned();
+        record.subject = event.subject().unwrap_or_default().to_owned();
+        record.event = event.clone().into_map();
+        record
+    }
+
+    /// Returns the event type.
+    #[inline]
+    pub fn event_type(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the `published_at` field.
+    #[inline]
+    pub fn published_at(&self) -> Option<DateTime> {
+        self.published_at
+    }
+
+    /// Converts the record into a cloud event.
+    pub fn into_cloud_event(self) -> Result<CloudEvent, Error> {
+        CloudEvent::deserialize(JsonValue::Object(self.event)).map_err(Error::from)
+    }
+
+    /// Persists the event in the outbox and dispatches it to the projections.
+    /// The record is marked as failed if any of the projections fails,
+    /// so that the failures can be found and replayed later.
+    pub async fn publish(event: CloudEvent) -> Result<(), Error> {
+        let record = Self::with_cloud_event(&event);
+        let id = record.id.to_string();
+        record.insert().await?;
+
+        let result = Projection::dispatch(&event).await;
+        let status = if result.is_ok() {
+            "Published"
+        } else {
+            "Failed"
+        };
+        let mut mutation = Mutation::default();
+        mutation.add_update("status", status);
+        mutation.add_update("published_at", DateTime::now());
+
+        let mut query = Query::default();
+        query.add_filter("id", id);
+        Self::update_one(&query, &mut mutation).await?;
+        result
+    }
+
+    /// Loads the events in the order of IDs after the specific one.
+    /// It can be used as an [`OutboxLoader`](zino_core::channel::OutboxLoader).
+    pub fn load_events(
+        after: Option<String>,
+        limit: usize,
+    ) -> BoxFuture<'static, Result<Vec<CloudEvent>, Error>> {
+        Box::pin(async move {
+            let mut query = Query::default();
+            query.allow_fields(&["id", "event"]);
+            if let Some(after) = after {
+                query.add_filter("id", Map::from_entry("$gt", after));
+            }
+            query.order_asc("id");
+            query.set_limit(limit);
+
+            let records = Self::find_as::<Self>(&query).await?;
+            records
+                .into_iter()
+                .map(|record| record.into_cloud_event())
+                .collect()
+        })
+    }
+
+    /// Registers the outbox as the loader for replaying the projections.
+    #[inline]
+    pub fn register_outbox_loader() {
+        Projection::set_outbox_loader(Self::load_events);
+    }
+}
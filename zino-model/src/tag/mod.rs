@@ -20,6 +20,7 @@ use zino_core::auth::UserSession;
 /// The `tag` model.
 #[derive(Debug, Clone, Default, Serialize, Deserialize, DecodeRow, Schema, ModelAccessor)]
 #[serde(default)]
+#[schema(tree = "parent_id")]
 pub struct Tag {
     // Basic fields.
     #[schema(read_only)]
@@ -41,6 +42,8 @@ pub struct Tag {
     category: String,
     #[schema(snapshot, reference = "Tag")]
     parent_id: Option<Uuid>, // tag.id, tag.namespace = {tag.namespace}, tag.category = {tag.category}
+    #[schema(index_type = "btree")]
+    path: String,
 
     // Extensions.
     extra: Map,
@@ -5,7 +5,7 @@ use zino_core::{
     datetime::DateTime,
     error::Error,
     extension::{JsonObjectExt, JsonValueExt},
-    model::Query,
+    model::{Mutation, Query},
     orm::{ModelAccessor, ModelHelper},
     warn, Map, Uuid,
 };
@@ -29,6 +29,10 @@ where
     const LOGIN_AT_FIELD: Option<&'static str> = None;
     /// Login-IP field name.
     const LOGIN_IP_FIELD: Option<&'static str> = None;
+    /// Failed-login-count field name.
+    const FAILED_LOGIN_COUNT_FIELD: Option<&'static str> = None;
+    /// Maximum number of consecutive failed logins before the account is locked.
+    const MAX_FAILED_LOGINS: u64 = 5;
 
     /// Consumes the user into standard claims without a `sub` field,
     /// which can be used to create a [`JwtClaims`] and generate an ID token.
@@ -115,6 +119,14 @@ where
 
     /// Generates the access token and refresh token.
     async fn generate_token(body: Map) -> Result<(K, Map), Error> {
+        let (user_id, user) = Self::authenticate(body).await?;
+        let data = Self::issue_token(&user_id, user, None)?;
+        Ok((user_id, data))
+    }
+
+    /// Authenticates the user with the account and password,
+    /// and returns the user ID with the fields used to issue the token.
+    async fn authenticate(body: Map) -> Result<(K, Map), Error> {
         let account = body
             .get_str("account")
             .ok_or_else(|| warn!("401 Unauthorized: user `account` should be specified"))?;
@@ -135,50 +147,102 @@ where
         if let Some(login_ip_field) = Self::LOGIN_IP_FIELD {
             fields.push(login_ip_field);
         }
+        if let Some(failed_login_count_field) = Self::FAILED_LOGIN_COUNT_FIELD {
+            fields.push(failed_login_count_field);
+        }
         query.allow_fields(&fields);
         query.add_filter("status", Map::from_entry("$nin", vec!["Locked", "Deleted"]));
         query.add_filter(Self::ACCOUNT_FIELD, account);
 
-        let mut user: Map = Self::find_one(&query)
-            .await?
-            .ok_or_else(|| warn!("404 Not Found: invalid user account or password"))?;
+        let Some(mut user) = Self::find_one::<Map>(&query).await? else {
+            // Verifies a dummy password so that the nonexistent account
+            // can not be inferred from the response time
+            Self::verify_dummy_password(passowrd);
+            bail!("404 Not Found: invalid user account or password");
+        };
         let encrypted_password = user
             .get_str(Self::PASSWORD_FIELD)
             .ok_or_else(|| warn!("404 Not Found: user password is absent"))?;
-        if Self::verify_password(passowrd, encrypted_password)
-            .map_err(|_| warn!("401 Unauthorized: invalid user account or password"))?
-        {
-            // Cann't use `get_str` because the primary key may be an integer
-            let user_id = user
-                .parse_string(Self::PRIMARY_KEY_NAME)
-                .ok_or_else(|| warn!("404 Not Found: user id is absent"))?;
-            let mut claims = JwtClaims::new(user_id.as_ref());
+        // A verification error is treated as a failed match so that it counts as a failed login
+        let password_verified =
+            Self::verify_password(passowrd, encrypted_password).unwrap_or_default();
 
-            let user_id = user_id.parse()?;
-            if let Some(role_field) = Self::ROLE_FIELD.filter(|&field| user.contains_key(field)) {
-                claims.add_data_entry("roles", user.parse_str_array(role_field));
-            }
-            if let Some(tenant_id_field) = Self::TENANT_ID_FIELD {
-                if let Some(tenant_id) = user.remove(tenant_id_field) {
-                    claims.add_data_entry("tenant_id", tenant_id);
+        let mut user_query = Query::default();
+        if let Some(user_id) = user.get(Self::PRIMARY_KEY_NAME) {
+            user_query.add_filter(Self::PRIMARY_KEY_NAME, user_id.clone());
+        }
+        let failed_login_count = Self::FAILED_LOGIN_COUNT_FIELD
+            .and_then(|field| user.get_u64(field))
+            .unwrap_or_default();
+        if !password_verified {
+            if let Some(failed_login_count_field) = Self::FAILED_LOGIN_COUNT_FIELD {
+                let mut updates =
+                    Map::from_entry("$inc", Map::from_entry(failed_login_count_field, 1));
+                if failed_login_count + 1 >= Self::MAX_FAILED_LOGINS {
+                    updates.upsert("status", "Locked");
+                    tracing::warn!(
+                        account,
+                        "user account is locked after repeated failed logins"
+                    );
                 }
+                let mut mutation = Mutation::new(updates);
+                Self::update_one(&user_query, &mut mutation).await?;
             }
+            bail!("401 Unauthorized: invalid user account or password");
+        }
 
-            let mut data = Map::new();
-            data.upsert("token_type", "Bearer");
-            data.upsert("expires_in", claims.expires_in().as_secs());
-            data.upsert("refresh_token", claims.refresh_token()?);
-            data.upsert("access_token", claims.access_token()?);
-            if let Some(login_at_field) = Self::LOGIN_AT_FIELD {
-                data.upsert(login_at_field, user.remove(login_at_field));
+        let mut updates = Map::new();
+        if let Some(failed_login_count_field) = Self::FAILED_LOGIN_COUNT_FIELD {
+            if failed_login_count > 0 {
+                updates.upsert(failed_login_count_field, 0);
             }
-            if let Some(login_ip_field) = Self::LOGIN_IP_FIELD {
-                data.upsert(login_ip_field, user.remove(login_ip_field));
+        }
+        if Self::password_needs_rehash(encrypted_password).unwrap_or_default() {
+            updates.upsert(Self::PASSWORD_FIELD, Self::encrypt_password(passowrd)?);
+        }
+        if !updates.is_empty() {
+            let mut mutation = Mutation::new(updates);
+            Self::update_one(&user_query, &mut mutation).await?;
+        }
+
+        // Cann't use `get_str` because the primary key may be an integer
+        let user_id = user
+            .parse_string(Self::PRIMARY_KEY_NAME)
+            .ok_or_else(|| warn!("404 Not Found: user id is absent"))?
+            .parse()?;
+        user.remove(Self::PASSWORD_FIELD);
+        Ok((user_id, user))
+    }
+
+    /// Issues the access token and refresh token for an authenticated user.
+    /// If the device ID is specified, it is carried by the `nonce` claim
+    /// so that the tokens are bound to the device.
+    fn issue_token(user_id: &K, mut user: Map, device_id: Option<&Uuid>) -> Result<Map, Error> {
+        let mut claims = JwtClaims::new(user_id);
+        if let Some(device_id) = device_id {
+            claims.set_nonce(device_id);
+        }
+        if let Some(role_field) = Self::ROLE_FIELD.filter(|&field| user.contains_key(field)) {
+            claims.add_data_entry("roles", user.parse_str_array(role_field));
+        }
+        if let Some(tenant_id_field) = Self::TENANT_ID_FIELD {
+            if let Some(tenant_id) = user.remove(tenant_id_field) {
+                claims.add_data_entry("tenant_id", tenant_id);
             }
-            Ok((user_id, data))
-        } else {
-            Err(warn!("fail to generate access token"))
         }
+
+        let mut data = Map::new();
+        data.upsert("token_type", "Bearer");
+        data.upsert("expires_in", claims.expires_in().as_secs());
+        data.upsert("refresh_token", claims.refresh_token()?);
+        data.upsert("access_token", claims.access_token()?);
+        if let Some(login_at_field) = Self::LOGIN_AT_FIELD {
+            data.upsert(login_at_field, user.remove(login_at_field));
+        }
+        if let Some(login_ip_field) = Self::LOGIN_IP_FIELD {
+            data.upsert(login_ip_field, user.remove(login_ip_field));
+        }
+        Ok(data)
     }
 
     /// Refreshes the access token.
@@ -209,7 +273,11 @@ where
         let mut user: Map = Self::find_one(&query)
             .await?
             .ok_or_else(|| warn!("404 Not Found: cannot get the user `{}`", user_id))?;
+        let nonce = claims.nonce();
         let mut claims = JwtClaims::new(user_id);
+        if let Some(nonce) = nonce {
+            claims.set_nonce(nonce);
+        }
         if let Some(role_field) = Self::ROLE_FIELD.filter(|&field| user.contains_key(field)) {
             claims.add_data_entry("roles", user.parse_str_array(role_field));
         }
@@ -326,4 +394,5 @@ where
 impl JwtAuthService<Uuid> for super::User {
     const LOGIN_AT_FIELD: Option<&'static str> = Some("current_login_at");
     const LOGIN_IP_FIELD: Option<&'static str> = Some("current_login_ip");
+    const FAILED_LOGIN_COUNT_FIELD: Option<&'static str> = Some("failed_login_count");
 }
@@ -18,10 +18,14 @@ use zino_derive::{DecodeRow, ModelAccessor, Schema};
 use crate::tag::Tag;
 
 mod jwt_auth;
+mod security;
 mod status;
+mod verification;
 
 pub use jwt_auth::JwtAuthService;
+pub use security::AccountSecurityService;
 pub use status::UserStatus;
+pub use verification::AccountVerificationService;
 
 #[cfg(feature = "visibility")]
 mod visibility;
@@ -54,7 +58,7 @@ pub struct User {
 
     // Info fields.
     #[schema(unique)]
-    union_id: String,
+    union_id: Option<String>,
     #[schema(not_null, unique, write_only)]
     access_key_id: String,
     #[schema(not_null, unique, write_only)]
@@ -68,6 +72,7 @@ pub struct User {
     website: String,
     #[schema(format = "email")]
     email: String,
+    email_verified: bool,
     location: String,
     locale: String,
     mobile: String,
@@ -130,7 +135,7 @@ impl Model for User {
             self.name = name.into_owned();
         }
         if let Some(union_id) = data.parse_string("union_id") {
-            self.union_id = union_id.into_owned();
+            self.union_id = (!union_id.is_empty()).then(|| union_id.into_owned());
         }
         if let Some(account) = data.parse_string("account") {
             self.account = account.into_owned();
@@ -225,8 +230,8 @@ impl User {
 
     /// Returns the `union_id` field.
     #[inline]
-    pub fn union_id(&self) -> &str {
-        &self.union_id
+    pub fn union_id(&self) -> Option<&str> {
+        self.union_id.as_deref()
     }
 
     /// Returns the `access_key_id` field.
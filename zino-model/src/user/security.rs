@@ -0,0 +1,149 @@
+use crate::{device::Device, login_attempt::LoginAttempt};
+use std::{fmt::Display, time::Duration};
+use zino_core::{
+    auth::JwtClaims,
+    bail,
+    error::Error,
+    model::{Mutation, Query},
+    orm::{ModelAccessor, Schema},
+    Map, Uuid,
+};
+
+/// Account security service for login attempts and devices.
+///
+/// The default hooks only emit logs. You can override them to send an email
+/// or a notification when a new device or a suspicious activity is found.
+pub trait AccountSecurityService<K = Uuid>
+where
+    Self: ModelAccessor<K>,
+    K: Default + Display + PartialEq,
+{
+    /// Number of recent failed attempts for an account to be considered suspicious.
+    const SUSPICIOUS_FAILURE_THRESHOLD: u64 = 3;
+    /// Period for counting the recent failed attempts.
+    const SUSPICIOUS_FAILURE_PERIOD: Duration = Duration::from_secs(15 * 60);
+
+    /// Resolves the geo location of the client IP. It returns `None` by default.
+    async fn resolve_geo_location(_client_ip: &str) -> Option<String> {
+        None
+    }
+
+    /// A hook running when the user logins from a new device.
+    async fn on_new_device(user_id: &K, device: &Device) -> Result<(), Error> {
+        tracing::warn!(
+            user_id = user_id.to_string(),
+            user_agent = device.user_agent(),
+            client_ip = device.client_ip(),
+            "the user logins from a new device"
+        );
+        Ok(())
+    }
+
+    /// A hook running when a suspicious login attempt is found.
+    async fn on_suspicious_activity(attempt: &LoginAttempt) -> Result<(), Error> {
+        tracing::warn!(
+            account = attempt.account(),
+            client_ip = attempt.client_ip(),
+            failure_reason = attempt.failure_reason(),
+            "suspicious login attempt"
+        );
+        Ok(())
+    }
+
+    /// Records a login attempt. For a succeeded attempt, the device of the user
+    /// is registered or reactivated and returned.
+    async fn record_login_attempt(
+        user_id: Option<&K>,
+        mut attempt: LoginAttempt,
+    ) -> Result<Option<Device>, Error> {
+        if attempt.geo_location().is_empty() {
+            if let Some(geo_location) = Self::resolve_geo_location(attempt.client_ip()).await {
+                attempt.set_geo_location(geo_location);
+            }
+        }
+
+        let device = if let Some(user_id) = user_id.filter(|_| attempt.is_succeeded()) {
+            let device = Self::register_device(user_id, &attempt).await?;
+            attempt.set_device_id(*device.id());
+            Some(device)
+        } else {
+            let period = Self::SUSPICIOUS_FAILURE_PERIOD;
+            let num_failures =
+                LoginAttempt::count_recent_failures(attempt.account(), period).await?;
+            if num_failures + 1 >= Self::SUSPICIOUS_FAILURE_THRESHOLD {
+                Self::on_suspicious_activity(&attempt).await?;
+            }
+            None
+        };
+        attempt.insert().await?;
+        Ok(device)
+    }
+
+    /// Registers the device on which the user logins successfully.
+    async fn register_device(user_id: &K, attempt: &LoginAttempt) -> Result<Device, Error> {
+        let user_agent = attempt.user_agent();
+        if let Some(mut device) = Device::find_by_user_agent(user_id, user_agent).await? {
+            let mut mutation = Mutation::default();
+            mutation.add_update("status", "Active");
+            mutation.add_update("client_ip", attempt.client_ip());
+            mutation.add_update("geo_location", attempt.geo_location());
+            mutation.add_update("last_seen_at", attempt.attempted_at());
+
+            let mut query = Query::default();
+            query.add_filter("id", device.id().to_string());
+            Device::update_one(&query, &mut mutation).await?;
+            device.set_client_ip(attempt.client_ip());
+            device.set_geo_location(attempt.geo_location());
+            Ok(device)
+        } else {
+            let mut device = Device::with_user_agent(user_id, user_agent);
+            device.set_client_ip(attempt.client_ip());
+            device.set_geo_location(attempt.geo_location());
+            device.clone().insert().await?;
+            Self::on_new_device(user_id, &device).await?;
+            Ok(device)
+        }
+    }
+
+    /// Lists the devices of the user.
+    #[inline]
+    async fn list_devices(user_id: &K) -> Result<Vec<Map>, Error> {
+        Device::list_by_user(user_id).await
+    }
+
+    /// Revokes a device of the user.
+    async fn revoke_device(user_id: &K, device_id: &Uuid) -> Result<(), Error> {
+        if !Device::revoke(user_id, device_id).await? {
+            bail!("404 Not Found: cannot find the device `{}`", device_id);
+        }
+        Ok(())
+    }
+
+    /// Revokes all the devices of the user except for an optional device.
+    #[inline]
+    async fn revoke_all_devices(user_id: &K, except: Option<&Uuid>) -> Result<u64, Error> {
+        Device::revoke_all(user_id, except).await
+    }
+
+    /// Verifies that the token is bound to a known device of the user
+    /// which has not been revoked since the token was issued.
+    /// The device ID is carried by the `nonce` claim of the token.
+    async fn verify_device(user_id: &K, claims: &JwtClaims) -> Result<bool, Error> {
+        let Some(device_id) = claims.nonce().and_then(|nonce| nonce.parse::<Uuid>().ok()) else {
+            return Ok(false);
+        };
+        let Some(device) = Device::find_by_user(user_id, &device_id).await? else {
+            return Ok(false);
+        };
+        if device.is_revoked() {
+            return Ok(false);
+        }
+        let issued_at = claims.issued_at();
+        let revoked = device
+            .revoked_at()
+            .is_some_and(|revoked_at| issued_at.timestamp() < revoked_at.timestamp());
+        Ok(!revoked)
+    }
+}
+
+impl AccountSecurityService<Uuid> for super::User {}
@@ -0,0 +1,283 @@
+use super::JwtAuthService;
+use crate::user_token::UserToken;
+use std::{fmt::Display, str::FromStr, time::Duration};
+use zino_core::{
+    auth::ActionToken,
+    bail,
+    error::Error,
+    extension::JsonObjectExt,
+    model::{Mutation, Query},
+    orm::{ModelAccessor, ModelHelper, Schema},
+    validation::Validation,
+    warn, Map, Uuid,
+};
+
+/// Account verification service for invitations, email verifications and password resets.
+///
+/// The tokens are issued as [`ActionToken`]s signed with the model's secret key,
+/// and recorded in the [`UserToken`] table for auditing and single-use semantics.
+/// Override [`on_token_issued()`](Self::on_token_issued) to deliver the tokens to users.
+pub trait AccountVerificationService<K = Uuid>
+where
+    Self: ModelAccessor<K> + ModelHelper<K> + JwtAuthService<K>,
+    K: Default + Display + FromStr + PartialEq + serde::de::DeserializeOwned,
+    <K as FromStr>::Err: std::error::Error + Send + 'static,
+{
+    /// Email field name.
+    const EMAIL_FIELD: &'static str = "email";
+    /// Email-verified field name.
+    const EMAIL_VERIFIED_FIELD: Option<&'static str> = None;
+    /// Fields which can not be set by the invitee when accepting an invitation.
+    /// The roles and the tenant are taken from the invitation record instead.
+    const PRIVILEGED_FIELDS: &'static [&'static str] = &[
+        "status",
+        "access_key_id",
+        "login_count",
+        "failed_login_count",
+    ];
+    /// Default roles of the invited user if they are not granted by the inviter.
+    const INVITATION_DEFAULT_ROLES: &'static [&'static str] = &["user"];
+    /// Expires duration of the invitation token.
+    const INVITATION_EXPIRES_IN: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+    /// Expires duration of the email-verification token.
+    const EMAIL_VERIFICATION_EXPIRES_IN: Duration = Duration::from_secs(24 * 60 * 60);
+    /// Expires duration of the password-reset token.
+    const PASSWORD_RESET_EXPIRES_IN: Duration = Duration::from_secs(30 * 60);
+
+    /// A hook running after the token has been issued.
+    /// It only emits a log by default.
+    async fn on_token_issued(token: &ActionToken) -> Result<(), Error> {
+        tracing::info!(
+            action = token.action(),
+            subject = token.subject(),
+            expires_at = token.expires_at().to_string(),
+            "an action token has been issued"
+        );
+        Ok(())
+    }
+
+    /// Issues an action token and records it. The `user_id` and `issued_by`
+    /// can be empty if they are not applicable, and the `extra` data is stored in the record.
+    async fn issue_action_token(
+        action: &str,
+        subject: &str,
+        expires_in: Duration,
+        user_id: &str,
+        issued_by: &str,
+        extra: Map,
+    ) -> Result<ActionToken, Error> {
+        let token = ActionToken::try_new(action, subject, expires_in, Self::secret_key())?;
+        let mut record = UserToken::with_action_token(&token);
+        record.set_user_id(user_id);
+        record.set_issued_by(issued_by);
+        record.set_extra(extra);
+        record.insert().await?;
+        Self::on_token_issued(&token).await?;
+        Ok(token)
+    }
+
+    /// Parses the action token without consuming it.
+    fn parse_action_token(action: &str, token: &str) -> Result<ActionToken, Error> {
+        ActionToken::parse_with(token, action, Self::secret_key())
+            .map_err(|err| warn!("401 Unauthorized: {}", err.message()))
+    }
+
+    /// Invites a user with the email. The roles and the tenant in the `grants`
+    /// are stored in the invitation record and assigned to the user on acceptance.
+    async fn invite_user(email: &str, inviter: &K, grants: &Map) -> Result<ActionToken, Error> {
+        let mut query = Query::default();
+        query.add_filter(Self::EMAIL_FIELD, email);
+        if Self::exists(&query).await? {
+            bail!("409 Conflict: the email `{}` has been registered", email);
+        }
+        UserToken::revoke_all("invitation", email).await?;
+
+        let mut extra = Map::new();
+        for field in Self::ROLE_FIELD.into_iter().chain(Self::TENANT_ID_FIELD) {
+            if let Some(value) = grants.get(field) {
+                extra.upsert(field, value.clone());
+            }
+        }
+        Self::issue_action_token(
+            "invitation",
+            email,
+            Self::INVITATION_EXPIRES_IN,
+            "",
+            &inviter.to_string(),
+            extra,
+        )
+        .await
+    }
+
+    /// Accepts the invitation and creates a user with the data.
+    /// The email of the user is taken from the invitation token,
+    /// and the privileged fields are taken from the invitation record.
+    async fn accept_invitation(
+        token: &str,
+        data: &mut Map,
+        client_ip: &str,
+    ) -> Result<(Validation, Map), Error> {
+        let token = Self::parse_action_token("invitation", token)?;
+        let invitation = UserToken::find_issued(&token).await?;
+        let privileged_fields = [Self::PRIMARY_KEY_NAME]
+            .into_iter()
+            .chain(Self::PRIVILEGED_FIELDS.iter().copied())
+            .chain(Self::EMAIL_VERIFIED_FIELD)
+            .chain(Self::ROLE_FIELD)
+            .chain(Self::TENANT_ID_FIELD)
+            .collect::<Vec<_>>();
+        data.retain(|key, _| !privileged_fields.contains(&key.as_str()));
+
+        let grants = invitation.extra();
+        if let Some(role_field) = Self::ROLE_FIELD {
+            let roles = grants
+                .get(role_field)
+                .cloned()
+                .unwrap_or_else(|| Self::INVITATION_DEFAULT_ROLES.into());
+            data.upsert(role_field, roles);
+        }
+        if let Some(tenant_id_field) = Self::TENANT_ID_FIELD {
+            if let Some(tenant_id) = grants.get(tenant_id_field) {
+                data.upsert(tenant_id_field, tenant_id.clone());
+            }
+        }
+        data.upsert(Self::EMAIL_FIELD, token.subject());
+
+        let mut user = Self::new();
+        let validation = user.read_map(data);
+        if !validation.is_success() {
+            return Ok((validation, user.snapshot()));
+        }
+
+        let validation = user.check_constraints().await?;
+        if !validation.is_success() {
+            return Ok((validation, user.snapshot()));
+        }
+
+        let record = UserToken::consume(&token, client_ip).await?;
+        let user_id = user.id().to_string();
+        let snapshot = user.snapshot();
+        user.insert().await?;
+        tracing::info!(
+            user_id = user_id.as_str(),
+            issued_by = record.issued_by(),
+            "the invitation has been accepted"
+        );
+        if let Some(email_verified_field) = Self::EMAIL_VERIFIED_FIELD {
+            let mut query = Query::default();
+            query.add_filter(Self::PRIMARY_KEY_NAME, user_id);
+            let mut mutation = Mutation::from_entry(email_verified_field, true);
+            Self::update_one(&query, &mut mutation).await?;
+        }
+        Ok((Validation::new(), snapshot))
+    }
+
+    /// Sends an email-verification token to the user.
+    async fn send_email_verification(user_id: &K) -> Result<ActionToken, Error> {
+        let mut query = Query::default();
+        query.allow_fields(&[Self::PRIMARY_KEY_NAME, Self::EMAIL_FIELD]);
+        query.add_filter(Self::PRIMARY_KEY_NAME, user_id.to_string());
+
+        let user: Map = Self::find_one(&query)
+            .await?
+            .ok_or_else(|| warn!("404 Not Found: cannot get the user `{}`", user_id))?;
+        let Some(email) = user.get_str(Self::EMAIL_FIELD).filter(|s| !s.is_empty()) else {
+            bail!("the email of the user `{}` is absent", user_id);
+        };
+        let user_id = user_id.to_string();
+        UserToken::revoke_all("email_verification", email).await?;
+        Self::issue_action_token(
+            "email_verification",
+            email,
+            Self::EMAIL_VERIFICATION_EXPIRES_IN,
+            &user_id,
+            &user_id,
+            Map::new(),
+        )
+        .await
+    }
+
+    /// Verifies the email of the user with the token.
+    async fn verify_email(token: &str, client_ip: &str) -> Result<(), Error> {
+        let token = Self::parse_action_token("email_verification", token)?;
+        let record = UserToken::consume(&token, client_ip).await?;
+        if let Some(email_verified_field) = Self::EMAIL_VERIFIED_FIELD {
+            let mut query = Query::default();
+            query.add_filter(Self::PRIMARY_KEY_NAME, record.user_id());
+            query.add_filter(Self::EMAIL_FIELD, record.subject());
+
+            let mut mutation = Mutation::from_entry(email_verified_field, true);
+            let ctx = Self::update_one(&query, &mut mutation).await?;
+            if ctx.rows_affected() == Some(0) {
+                bail!("409 Conflict: the email of the user has been changed");
+            }
+        }
+        Ok(())
+    }
+
+    /// Requests a password-reset token for the user with the email.
+    /// It returns `None` if there is no such user, which should not be exposed to the client.
+    async fn request_password_reset(email: &str) -> Result<Option<ActionToken>, Error> {
+        let mut query = Query::default();
+        query.allow_fields(&[Self::PRIMARY_KEY_NAME]);
+        query.add_filter(Self::EMAIL_FIELD, email);
+        query.add_filter("status", Map::from_entry("$nin", vec!["Locked", "Deleted"]));
+
+        let Some(user) = Self::find_one::<Map>(&query).await? else {
+            return Ok(None);
+        };
+        let user_id = user
+            .parse_string(Self::PRIMARY_KEY_NAME)
+            .ok_or_else(|| warn!("404 Not Found: user id is absent"))?;
+        UserToken::revoke_all("password_reset", email).await?;
+
+        let token = Self::issue_action_token(
+            "password_reset",
+            email,
+            Self::PASSWORD_RESET_EXPIRES_IN,
+            &user_id,
+            "",
+            Map::new(),
+        )
+        .await?;
+        Ok(Some(token))
+    }
+
+    /// Resets the password of the user with the token.
+    /// The failed login count is cleared and the locked account is unlocked as well.
+    async fn reset_password(token: &str, password: &str, client_ip: &str) -> Result<(), Error> {
+        let token = Self::parse_action_token("password_reset", token)?;
+        let encrypted_password = Self::encrypt_password(password)?;
+        let record = UserToken::consume(&token, client_ip).await?;
+
+        let mut query = Query::default();
+        query.add_filter(Self::PRIMARY_KEY_NAME, record.user_id());
+        query.add_filter(Self::EMAIL_FIELD, record.subject());
+        query.allow_fields(&["status"]);
+
+        let user: Map = Self::find_one(&query)
+            .await?
+            .ok_or_else(|| warn!("404 Not Found: the user does not exist"))?;
+        let status = user.get_str("status").unwrap_or_default();
+        query.add_filter("status", status);
+
+        let mut updates = Map::from_entry(Self::PASSWORD_FIELD, encrypted_password);
+        if let Some(failed_login_count_field) = Self::FAILED_LOGIN_COUNT_FIELD {
+            updates.upsert(failed_login_count_field, 0);
+        }
+        if status == "Locked" {
+            updates.upsert("status", "Active");
+        }
+
+        let mut mutation = Mutation::new(updates);
+        let ctx = Self::update_one(&query, &mut mutation).await?;
+        if ctx.rows_affected() == Some(0) {
+            bail!("409 Conflict: the user has been changed");
+        }
+        Ok(())
+    }
+}
+
+impl AccountVerificationService<Uuid> for super::User {
+    const EMAIL_VERIFIED_FIELD: Option<&'static str> = Some("email_verified");
+}
@@ -0,0 +1,244 @@
+//! The `user_token` model and related services.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use zino_core::{
+    auth::ActionToken,
+    bail,
+    datetime::DateTime,
+    error::Error,
+    extension::JsonObjectExt,
+    model::{Model, ModelHooks, Mutation, Query},
+    orm::Schema,
+    validation::Validation,
+    warn, Map, Uuid,
+};
+use zino_derive::{DecodeRow, ModelAccessor, Schema};
+
+#[cfg(any(feature = "owner-id", feature = "maintainer-id"))]
+use crate::user::User;
+
+/// The `user_token` model. It records the issued action tokens
+/// for auditing and single-use semantics.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, DecodeRow, Schema, ModelAccessor)]
+#[serde(default)]
+pub struct UserToken {
+    // Basic fields.
+    #[schema(read_only)]
+    id: Uuid,
+    #[schema(not_null)]
+    name: String,
+    #[cfg(feature = "namespace")]
+    #[schema(default_value = "UserToken::model_namespace", index_type = "hash")]
+    namespace: String,
+    #[cfg(feature = "visibility")]
+    #[schema(default_value = "Internal")]
+    visibility: String,
+    #[schema(
+        enum_values = "Issued | Consumed | Revoked",
+        default_value = "Issued",
+        index_type = "hash"
+    )]
+    status: String,
+    description: String,
+
+    // Info fields.
+    #[schema(
+        not_null,
+        read_only,
+        enum_values = "invitation | email_verification | password_reset",
+        index_type = "hash"
+    )]
+    purpose: String,
+    #[schema(not_null, read_only, index_type = "hash")]
+    subject: String,
+    #[schema(read_only, index_type = "hash")]
+    user_id: String, // user.id
+    #[schema(read_only)]
+    issued_by: String, // user.id
+    #[schema(read_only)]
+    expires_at: DateTime,
+    consumed_at: Option<DateTime>,
+    #[schema(format = "ip")]
+    consumed_ip: String,
+
+    // Extensions.
+    extra: Map,
+
+    // Revisions.
+    #[cfg(feature = "owner-id")]
+    #[schema(reference = "User")]
+    owner_id: Option<Uuid>, // user.id
+    #[cfg(feature = "maintainer-id")]
+    #[schema(reference = "User")]
+    maintainer_id: Option<Uuid>, // user.id
+    #[schema(read_only, default_value = "now", index_type = "btree")]
+    created_at: DateTime,
+    #[schema(default_value = "now", index_type = "btree")]
+    updated_at: DateTime,
+    version: u64,
+    #[cfg(feature = "edition")]
+    edition: u32,
+}
+
+impl Model for UserToken {
+    const MODEL_NAME: &'static str = "user_token";
+
+    #[inline]
+    fn new() -> Self {
+        Self {
+            id: Uuid::now_v7(),
+            status: "Issued".to_owned(),
+            ..Self::default()
+        }
+    }
+
+    fn read_map(&mut self, data: &Map) -> Validation {
+        let mut validation = Validation::new();
+        if let Some(result) = data.parse_uuid("id") {
+            match result {
+                Ok(id) => self.id = id,
+                Err(err) => validation.record_fail("id", err),
+            }
+        }
+        if let Some(name) = data.parse_string("name") {
+            self.name = name.into_owned();
+        }
+        if let Some(description) = data.parse_string("description") {
+            self.description = description.into_owned();
+        }
+        validation
+    }
+}
+
+impl ModelHooks for UserToken {
+    type Data = ();
+    type Extension = ();
+}
+
+impl UserToken {
+    /// Creates a new record for the action token.
+    pub fn with_action_token(token: &ActionToken) -> Self {
+        Self {
+            id: *token.nonce(),
+            name: token.action().to_owned(),
+            status: "Issued".to_owned(),
+            purpose: token.action().to_owned(),
+            subject: token.subject().to_owned(),
+            expires_at: token.expires_at(),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the `user_id` field.
+    #[inline]
+    pub fn set_user_id(&mut self, user_id: impl Display) {
+        self.user_id = user_id.to_string();
+    }
+
+    /// Sets the `issued_by` field.
+    #[inline]
+    pub fn set_issued_by(&mut self, issued_by: impl Display) {
+        self.issued_by = issued_by.to_string();
+    }
+
+    /// Sets the `extra` field.
+    #[inline]
+    pub fn set_extra(&mut self, extra: Map) {
+        self.extra = extra;
+    }
+
+    /// Returns the `purpose` field.
+    #[inline]
+    pub fn purpose(&self) -> &str {
+        &self.purpose
+    }
+
+    /// Returns the `subject` field.
+    #[inline]
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    /// Returns the `user_id` field.
+    #[inline]
+    pub fn user_id(&self) -> &str {
+        &self.user_id
+    }
+
+    /// Returns the `issued_by` field.
+    #[inline]
+    pub fn issued_by(&self) -> &str {
+        &self.issued_by
+    }
+
+    /// Returns the `expires_at` field.
+    #[inline]
+    pub fn expires_at(&self) -> DateTime {
+        self.expires_at
+    }
+
+    /// Returns the `consumed_at` field.
+    #[inline]
+    pub fn consumed_at(&self) -> Option<DateTime> {
+        self.consumed_at
+    }
+
+    /// Returns the `extra` field.
+    #[inline]
+    pub fn extra(&self) -> &Map {
+        &self.extra
+    }
+
+    /// Finds the issued record of the action token without consuming it.
+    pub async fn find_issued(token: &ActionToken) -> Result<Self, Error> {
+        let mut query = Query::default();
+        query.add_filter("id", token.nonce().to_string());
+        query.add_filter("purpose", token.action());
+        query.add_filter("status", "Issued");
+        Self::find_one_as::<Self>(&query)
+            .await?
+            .ok_or_else(|| warn!("409 Conflict: the token has been used or revoked"))
+    }
+
+    /// Consumes the record of the action token. It fails if the token
+    /// has been consumed or revoked, which guarantees the single-use semantics.
+    pub async fn consume(token: &ActionToken, client_ip: &str) -> Result<Self, Error> {
+        let consumed_at = DateTime::now();
+        let mut mutation = Mutation::default();
+        mutation.add_update("status", "Consumed");
+        mutation.add_update("consumed_at", consumed_at);
+        mutation.add_update("consumed_ip", client_ip);
+
+        let mut query = Query::default();
+        query.add_filter("id", token.nonce().to_string());
+        query.add_filter("purpose", token.action());
+        query.add_filter("status", "Issued");
+        let ctx = Self::update_one(&query, &mut mutation).await?;
+        if ctx.rows_affected() != Some(1) {
+            bail!("409 Conflict: the token has been used or revoked");
+        }
+
+        let mut query = Query::default();
+        query.add_filter("id", token.nonce().to_string());
+        let Some(mut record) = Self::find_one_as::<Self>(&query).await? else {
+            bail!("404 Not Found: cannot find the token `{}`", token.nonce());
+        };
+        record.status = "Consumed".to_owned();
+        record.consumed_at = Some(consumed_at);
+        record.consumed_ip = client_ip.to_owned();
+        Ok(record)
+    }
+
+    /// Revokes all the issued tokens with the purpose for the subject,
+    /// and returns the number of revoked tokens.
+    pub async fn revoke_all(purpose: &str, subject: &str) -> Result<u64, Error> {
+        let mut mutation = Mutation::from_entry("status", "Revoked");
+        let mut query = Query::default();
+        query.add_filter("purpose", purpose);
+        query.add_filter("subject", subject);
+        query.add_filter("status", "Issued");
+        let ctx = Self::update_many(&query, &mut mutation).await?;
+        Ok(ctx.rows_affected().unwrap_or_default())
+    }
+}
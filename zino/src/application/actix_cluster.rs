@@ -1,4 +1,7 @@
-use crate::{middleware, ActixResponse, Request, RouterConfigure};
+use crate::{
+    middleware::{self, Middleware},
+    ActixResponse, Request, RouterConfigure,
+};
 use actix_files::{Files, NamedFile};
 use actix_web::{
     dev::{fn_service, ServiceRequest, ServiceResponse},
@@ -11,7 +14,7 @@ use actix_web::{
 use std::{fs, path::PathBuf, time::Duration};
 use utoipa_rapidoc::RapiDoc;
 use zino_core::{
-    application::{Application, Plugin, ServerTag},
+    application::{Application, Module, Plugin, ServerTag},
     extension::TomlTableExt,
     response::Response,
     schedule::AsyncScheduler,
@@ -22,12 +25,25 @@ use zino_core::{
 pub struct ActixCluster {
     /// Custom plugins.
     custom_plugins: Vec<Plugin>,
+    /// Custom middlewares.
+    custom_middlewares: Vec<Box<dyn Middleware>>,
+    /// Custom modules.
+    custom_modules: Vec<Box<dyn Module<ActixCluster>>>,
     /// Default routes.
     default_routes: Vec<RouterConfigure>,
     /// Tagged routes.
     tagged_routes: Vec<(ServerTag, Vec<RouterConfigure>)>,
 }
 
+impl ActixCluster {
+    /// Adds a custom middleware which runs for all the routes.
+    #[inline]
+    pub fn add_middleware(mut self, middleware: impl Middleware) -> Self {
+        self.custom_middlewares.push(Box::new(middleware));
+        self
+    }
+}
+
 impl Application for ActixCluster {
     type Routes = Vec<RouterConfigure>;
 
@@ -46,13 +62,21 @@ impl Application for ActixCluster {
         self
     }
 
-    fn run_with<T: AsyncScheduler + Send + 'static>(self, mut scheduler: T) {
+    fn add_module<M: Module<Self>>(mut self, module: M) -> Self {
+        self.custom_modules.push(Box::new(module));
+        self
+    }
+
+    fn run_with<T: AsyncScheduler + Send + 'static>(self, scheduler: T) {
         let runtime = Runtime::new().expect("fail to build Tokio runtime for `ActixCluster`");
         let app_env = Self::env();
-        runtime.block_on(async {
+        let custom_modules = self.custom_modules;
+        let module_scheduler = runtime.block_on(async {
             Self::load().await;
             super::load_plugins(self.custom_plugins, app_env).await;
+            super::load_modules(&custom_modules, app_env).await
         });
+        let mut scheduler = (scheduler, module_scheduler);
         if scheduler.is_ready() {
             runtime.spawn(async move {
                 loop {
@@ -65,8 +89,13 @@ impl Application for ActixCluster {
         }
 
         runtime.block_on(async {
-            let default_routes = self.default_routes.leak() as &'static [_];
+            let mut default_routes = self.default_routes;
+            for module in &custom_modules {
+                default_routes.extend(module.routes());
+            }
+            let default_routes = default_routes.leak() as &'static [_];
             let tagged_routes = self.tagged_routes.leak() as &'static [_];
+            let custom_middlewares = self.custom_middlewares.leak() as &'static [_];
             let app_state = Self::shared_state();
             let app_name = Self::name();
             let app_version = Self::version();
@@ -215,6 +244,10 @@ impl Application for ActixCluster {
                         .app_data(JsonConfig::default().limit(body_limit))
                         .app_data(PayloadConfig::default().limit(body_limit))
                         .wrap(Compress::default())
+                        .wrap(middleware::RequestTimeout)
+                        .wrap(middleware::LoadShedding)
+                        .wrap(middleware::CustomMiddlewares(custom_middlewares))
+                        .wrap(middleware::MaintenanceGuard)
                         .wrap(middleware::RequestContextInitializer)
                         .wrap(middleware::tracing_middleware())
                         .wrap(middleware::cors_middleware())
@@ -233,6 +266,7 @@ impl Application for ActixCluster {
                     tracing::error!("actix server error: {err}");
                 }
             }
+            super::shutdown_modules(&custom_modules).await;
         });
     }
 }
@@ -1,19 +1,18 @@
-use crate::{middleware, AxumExtractor, AxumResponse};
+use crate::{
+    middleware::{self, Middleware},
+    AxumExtractor, AxumResponse,
+};
 use axum::{
-    error_handling::HandleErrorLayer,
-    extract::{rejection::LengthLimitError, DefaultBodyLimit},
+    extract::DefaultBodyLimit,
     http::StatusCode,
-    middleware::from_fn,
-    BoxError, Router,
+    middleware::{from_fn, from_fn_with_state},
+    Router,
 };
 use std::{
     any::Any, borrow::Cow, convert::Infallible, fs, net::SocketAddr, path::PathBuf, time::Duration,
 };
 use tokio::{net::TcpListener, runtime::Builder, signal};
-use tower::{
-    timeout::{error::Elapsed, TimeoutLayer},
-    ServiceBuilder,
-};
+use tower::ServiceBuilder;
 use tower_http::{
     catch_panic::CatchPanicLayer,
     compression::{predicate::DefaultPredicate, CompressionLayer},
@@ -22,8 +21,9 @@ use tower_http::{
 };
 use utoipa_rapidoc::RapiDoc;
 use zino_core::{
-    application::{Application, Plugin, ServerTag},
+    application::{Application, Module, Plugin, ServerTag},
     extension::TomlTableExt,
+    request::RequestRecorder,
     response::Response,
     schedule::AsyncScheduler,
     LazyLock,
@@ -34,12 +34,25 @@ use zino_core::{
 pub struct AxumCluster {
     /// Custom plugins.
     custom_plugins: Vec<Plugin>,
+    /// Custom middlewares.
+    custom_middlewares: Vec<Box<dyn Middleware>>,
+    /// Custom modules.
+    custom_modules: Vec<Box<dyn Module<AxumCluster>>>,
     /// Default routes.
     default_routes: Vec<Router>,
     /// Tagged routes.
     tagged_routes: Vec<(ServerTag, Vec<Router>)>,
 }
 
+impl AxumCluster {
+    /// Adds a custom middleware which runs for all the routes.
+    #[inline]
+    pub fn add_middleware(mut self, middleware: impl Middleware) -> Self {
+        self.custom_middlewares.push(Box::new(middleware));
+        self
+    }
+}
+
 impl Application for AxumCluster {
     type Routes = Vec<Router>;
 
@@ -58,7 +71,12 @@ impl Application for AxumCluster {
         self
     }
 
-    fn run_with<T: AsyncScheduler + Send + 'static>(self, mut scheduler: T) {
+    fn add_module<M: Module<Self>>(mut self, module: M) -> Self {
+        self.custom_modules.push(Box::new(module));
+        self
+    }
+
+    fn run_with<T: AsyncScheduler + Send + 'static>(self, scheduler: T) {
         let runtime = Builder::new_multi_thread()
             .thread_keep_alive(Duration::from_secs(60))
             .thread_stack_size(2 * 1024 * 1024)
@@ -67,10 +85,13 @@ impl Application for AxumCluster {
             .build()
             .expect("fail to build Tokio runtime for `AxumCluster`");
         let app_env = Self::env();
-        runtime.block_on(async {
+        let custom_modules = self.custom_modules;
+        let module_scheduler = runtime.block_on(async {
             Self::load().await;
             super::load_plugins(self.custom_plugins, app_env).await;
+            super::load_modules(&custom_modules, app_env).await
         });
+        let mut scheduler = (scheduler, module_scheduler);
         if scheduler.is_ready() {
             runtime.spawn(async move {
                 loop {
@@ -83,8 +104,12 @@ impl Application for AxumCluster {
         }
 
         runtime.block_on(async {
-            let default_routes = self.default_routes;
+            let mut default_routes = self.default_routes;
+            for module in &custom_modules {
+                default_routes.extend(module.routes());
+            }
             let tagged_routes = self.tagged_routes;
+            let custom_middlewares = self.custom_middlewares.leak() as &'static [_];
             let app_state = Self::shared_state();
             let app_name = Self::name();
             let app_version = Self::version();
@@ -108,7 +133,6 @@ impl Application for AxumCluster {
                 let mut public_route_prefix = "/public";
                 let mut public_dir = PathBuf::new();
                 let mut body_limit = 128 * 1024 * 1024; // 128MB
-                let mut request_timeout = Duration::from_secs(60); // 60 seconds
                 if let Some(config) = app_state.get_config("server") {
                     if let Some(dir) = config.get_str("page-dir") {
                         public_route_prefix = "/page";
@@ -124,9 +148,6 @@ impl Application for AxumCluster {
                     if let Some(limit) = config.get_usize("body-limit") {
                         body_limit = limit;
                     }
-                    if let Some(timeout) = config.get_duration("request-timeout") {
-                        request_timeout = timeout;
-                    }
                 } else {
                     public_dir = default_public_dir;
                 }
@@ -208,6 +229,21 @@ impl Application for AxumCluster {
                     }
                 }
 
+                // Register the routes of the request recorder.
+                if is_docs_server && RequestRecorder::shared().is_some() {
+                    app = app.merge(middleware::request_recorder_routes());
+                    tracing::info!(
+                        "Request recorder router `/debug/requests` is registered for `{addr}`"
+                    );
+                }
+
+                // Register the routes of the query log.
+                #[cfg(feature = "orm")]
+                if is_docs_server && zino_core::orm::QueryLog::is_enabled() {
+                    app = app.merge(super::axum_query_log::query_log_routes());
+                    tracing::info!("Query log router `/debug/queries` is registered for `{addr}`");
+                }
+
                 app = app
                     .fallback_service(tower::service_fn(|req| async {
                         let req = AxumExtractor::from(req);
@@ -226,18 +262,15 @@ impl Application for AxumCluster {
                             .layer(LazyLock::force(&middleware::TRACING_MIDDLEWARE))
                             .layer(LazyLock::force(&middleware::CORS_MIDDLEWARE))
                             .layer(from_fn(middleware::request_context))
+                            .layer(from_fn(middleware::request_recorder))
+                            .layer(from_fn(middleware::maintenance_mode))
+                            .layer(from_fn_with_state(
+                                custom_middlewares,
+                                middleware::custom_middlewares,
+                            ))
+                            .layer(from_fn(middleware::load_shedding))
                             .layer(from_fn(middleware::extract_etag))
-                            .layer(HandleErrorLayer::new(|err: BoxError| async move {
-                                let status_code = if err.is::<Elapsed>() {
-                                    StatusCode::REQUEST_TIMEOUT
-                                } else if err.is::<LengthLimitError>() {
-                                    StatusCode::PAYLOAD_TOO_LARGE
-                                } else {
-                                    StatusCode::INTERNAL_SERVER_ERROR
-                                };
-                                let res = Response::new(status_code);
-                                Ok::<AxumResponse, Infallible>(res.into())
-                            }))
+                            .layer(from_fn(middleware::request_timeout))
                             .layer(CatchPanicLayer::custom(
                                 |err: Box<dyn Any + Send + 'static>| {
                                     let details = if let Some(s) = err.downcast_ref::<String>() {
@@ -251,8 +284,7 @@ impl Application for AxumCluster {
                                     res.set_message(details);
                                     crate::response::axum_response::build_http_response(res)
                                 },
-                            ))
-                            .layer(TimeoutLayer::new(request_timeout)),
+                            )),
                     );
                 Box::pin(async move {
                     let tcp_listener = TcpListener::bind(&addr)
@@ -271,6 +303,7 @@ impl Application for AxumCluster {
                     tracing::error!("axum server error: {err}");
                 }
             }
+            super::shutdown_modules(&custom_modules).await;
         });
     }
 
@@ -0,0 +1,161 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+use std::fmt::Write;
+use zino_core::{
+    extension::JsonObjectExt,
+    orm::{QueryLog, QueryLogEntry},
+    request::RequestContext,
+    response::{ExtractRejection, Rejection},
+    validation::escape_html,
+    warn, Map, Uuid,
+};
+
+/// Route of the query log.
+pub(super) const QUERY_LOG_ROUTE: &str = "/debug/queries";
+
+/// Returns the router for viewing and explaining the logged SQL statements.
+pub(super) fn query_log_routes() -> Router {
+    Router::new()
+        .route(QUERY_LOG_ROUTE, get(list_queries))
+        .route(
+            &format!("{QUERY_LOG_ROUTE}/:id/explain"),
+            post(explain_query),
+        )
+}
+
+/// Lists the logged SQL statements with the newest first, which can be filtered by
+/// the `model_name`, `pool`, `contains`, `min_duration` and `failed` query params.
+/// An HTML page is rendered if the client accepts `text/html`.
+async fn list_queries(req: crate::Request) -> crate::Result {
+    check_query_log(&req)?;
+
+    let model_name = req.get_query("model_name").filter(|s| !s.is_empty());
+    let pool = req.get_query("pool").filter(|s| !s.is_empty());
+    let contains = req.get_query("contains").filter(|s| !s.is_empty());
+    let min_duration = req
+        .get_query("min_duration")
+        .and_then(|s| s.parse::<f64>().ok());
+    let failed = req.get_query("failed") == Some("true");
+    let entries = QueryLog::entries()
+        .into_iter()
+        .filter(|entry| {
+            model_name.map_or(true, |name| entry.model_name() == name)
+                && pool.map_or(true, |pool| entry.pool() == Some(pool))
+                && contains.map_or(true, |s| entry.query().contains(s))
+                && min_duration.map_or(true, |millis| entry.duration_millis() >= millis)
+                && (!failed || !entry.is_success())
+        })
+        .collect::<Vec<_>>();
+
+    let mut res = crate::Response::default().context(&req);
+    if req
+        .get_header("accept")
+        .is_some_and(|accept| accept.contains("text/html"))
+    {
+        res.set_html_response(render_queries(&req, &entries));
+    } else {
+        let entries = serde_json::to_value(entries).extract(&req)?;
+        res.set_json_data(Map::from_entry("queries", entries));
+    }
+    Ok(res.into())
+}
+
+/// Explains the execution plan of a logged SQL statement.
+async fn explain_query(req: crate::Request) -> crate::Result {
+    check_query_log(&req)?;
+
+    let id = req.parse_param::<Uuid>("id")?;
+    let plan = QueryLog::explain(&id).await.extract(&req)?;
+    let mut res = crate::Response::default().context(&req);
+    res.set_json_data(Map::from_entry("plan", plan));
+    Ok(res.into())
+}
+
+/// Checks whether the query log is enabled.
+fn check_query_log(req: &crate::Request) -> Result<(), Rejection> {
+    if QueryLog::is_enabled() {
+        Ok(())
+    } else {
+        let err = warn!("404 Not Found: the query log is not enabled");
+        Err(Rejection::not_found(err).context(req))
+    }
+}
+
+/// Renders the logged SQL statements with the filters.
+fn render_queries(req: &crate::Request, entries: &[QueryLogEntry]) -> String {
+    let filter = |key: &str| escape_html(req.get_query(key).unwrap_or_default());
+    let checked = if req.get_query("failed") == Some("true") {
+        " checked"
+    } else {
+        ""
+    };
+    let mut rows = String::new();
+    for entry in entries {
+        let query_id = entry.query_id();
+        let rows_affected = entry
+            .rows_affected()
+            .map(|num_rows| num_rows.to_string())
+            .unwrap_or_default();
+        let status = if entry.is_success() {
+            ""
+        } else {
+            " class=\"failed\""
+        };
+        let _ = write!(
+            rows,
+            r#"<tr{status}><td>{}</td><td>{}</td><td>{:.3}</td><td>{rows_affected}</td><td>{}</td><td><pre>{}</pre><pre id="plan-{query_id}"></pre></td><td><button type="button" onclick="explain('{query_id}')">EXPLAIN</button></td></tr>"#,
+            escape_html(entry.model_name()),
+            escape_html(entry.pool().unwrap_or_default()),
+            entry.duration_millis(),
+            escape_html(entry.span().unwrap_or_default()),
+            escape_html(entry.query()),
+        );
+    }
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>SQL queries</title>
+<style>
+body {{ margin: 16px; font-family: sans-serif; }}
+form {{ margin-bottom: 16px; }}
+table {{ border-collapse: collapse; width: 100%; }}
+th, td {{ border: 1px solid #ddd; padding: 6px 8px; text-align: left; vertical-align: top; }}
+pre {{ margin: 0; white-space: pre-wrap; }}
+.failed {{ background: #fee; }}
+</style>
+</head>
+<body>
+<h1>SQL queries</h1>
+<form method="get">
+<input type="text" name="model_name" placeholder="Model" value="{}">
+<input type="text" name="pool" placeholder="Pool" value="{}">
+<input type="text" name="contains" placeholder="SQL contains" value="{}">
+<input type="number" step="any" name="min_duration" placeholder="Min duration (ms)" value="{}">
+<label><input type="checkbox" name="failed" value="true"{checked}> Failed only</label>
+<button type="submit">Filter</button>
+</form>
+<table>
+<thead><tr><th>Model</th><th>Pool</th><th>Duration (ms)</th><th>Rows</th><th>Span</th><th>Query</th><th></th></tr></thead>
+<tbody>{rows}</tbody>
+</table>
+<script>
+async function explain(id) {{
+  const output = document.getElementById("plan-" + id);
+  const res = await fetch("{QUERY_LOG_ROUTE}/" + id + "/explain", {{ method: "POST" }});
+  const body = await res.json();
+  output.textContent = JSON.stringify(body.data ? body.data.plan : body, null, 2);
+}}
+</script>
+</body>
+</html>"#,
+        filter("model_name"),
+        filter("pool"),
+        filter("contains"),
+        filter("min_duration"),
+    )
+}
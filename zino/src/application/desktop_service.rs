@@ -0,0 +1,284 @@
+use image::{error::ImageError, io::Reader};
+use notify_rust::Notification;
+use std::{
+    collections::hash_map::DefaultHasher,
+    env,
+    hash::{Hash, Hasher},
+    io::{BufRead, BufReader, Write},
+    net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    thread,
+};
+use tokio::sync::broadcast::{self, Receiver, Sender};
+use tray_icon::{
+    menu::{Menu, MenuItem, PredefinedMenuItem},
+    Icon, TrayIcon, TrayIconBuilder,
+};
+use zino_core::error::Error;
+
+/// Desktop-specific services: system tray, notifications, single instance and deep links.
+///
+/// The service is configured by the `[desktop]` table in the application config:
+///
+/// ```toml
+/// [desktop]
+/// single-instance = true
+/// url-schemes = ["data-cube"]
+///
+/// [desktop.tray]
+/// icon = "public/favicon.ico"
+/// tooltip = "DataCube"
+/// menu = [
+///     { id = "show", text = "Show" },
+///     { id = "quit", text = "Quit" },
+/// ]
+/// ```
+///
+/// The tray menu items with the ids `show`, `hide` and `quit` have built-in actions,
+/// and the ids of all the clicked items can be received by [`DesktopService::subscribe_menu_events()`].
+pub struct DesktopService {
+    /// The app name.
+    app_name: &'static str,
+    /// The project directory.
+    project_dir: &'static Path,
+    /// A flag to determine whether only a single instance is allowed to run.
+    single_instance: bool,
+    /// The port for communicating with the single instance.
+    instance_port: u16,
+    /// Custom URL schemes.
+    url_schemes: Vec<String>,
+    /// The tray icon file.
+    tray_icon: Option<PathBuf>,
+    /// The tray tooltip.
+    tray_tooltip: Option<String>,
+    /// The tray menu items in the form `(id, text)`.
+    tray_menu: Vec<(String, String)>,
+    /// The deep link to launch the app.
+    launch_deep_link: Option<String>,
+    /// The sender for deep links.
+    deep_link_sender: Sender<String>,
+    /// The sender for menu events.
+    menu_event_sender: Sender<String>,
+}
+
+impl DesktopService {
+    /// Creates a new instance.
+    pub(crate) fn new(app_name: &'static str, project_dir: &'static Path) -> Self {
+        let mut hasher = DefaultHasher::new();
+        app_name.hash(&mut hasher);
+        let instance_port = 49152 + (hasher.finish() % 16384) as u16;
+        Self {
+            app_name,
+            project_dir,
+            single_instance: false,
+            instance_port,
+            url_schemes: Vec::new(),
+            tray_icon: None,
+            tray_tooltip: None,
+            tray_menu: Vec::new(),
+            launch_deep_link: None,
+            deep_link_sender: broadcast::channel(16).0,
+            menu_event_sender: broadcast::channel(16).0,
+        }
+    }
+
+    /// Sets whether only a single instance is allowed to run.
+    #[inline]
+    pub(crate) fn set_single_instance(&mut self, single_instance: bool) {
+        self.single_instance = single_instance;
+    }
+
+    /// Sets the port for communicating with the single instance.
+    #[inline]
+    pub(crate) fn set_instance_port(&mut self, port: u16) {
+        self.instance_port = port;
+    }
+
+    /// Adds a custom URL scheme.
+    #[inline]
+    pub(crate) fn add_url_scheme(&mut self, scheme: &str) {
+        self.url_schemes.push(scheme.to_owned());
+    }
+
+    /// Sets the tray icon file.
+    #[inline]
+    pub(crate) fn set_tray_icon(&mut self, icon: &str) {
+        self.tray_icon = Some(self.project_dir.join(icon));
+    }
+
+    /// Sets the tray tooltip.
+    #[inline]
+    pub(crate) fn set_tray_tooltip(&mut self, tooltip: &str) {
+        self.tray_tooltip = Some(tooltip.to_owned());
+    }
+
+    /// Adds a tray menu item.
+    #[inline]
+    pub(crate) fn add_tray_menu_item(&mut self, id: &str, text: &str) {
+        self.tray_menu.push((id.to_owned(), text.to_owned()));
+    }
+
+    /// Initializes the shared service and returns `false` if another instance is running,
+    /// in which case the deep link in the command line arguments has been forwarded to it.
+    pub(crate) fn init(mut self) -> bool {
+        self.launch_deep_link = env::args()
+            .skip(1)
+            .find(|arg| self.parse_deep_link(arg).is_some());
+
+        let mut listener = None;
+        if self.single_instance {
+            let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, self.instance_port));
+            match TcpListener::bind(addr) {
+                Ok(tcp_listener) => listener = Some(tcp_listener),
+                Err(_) => {
+                    let deep_link = self.launch_deep_link.as_deref().unwrap_or_default();
+                    if let Err(err) = TcpStream::connect(addr)
+                        .and_then(|mut stream| writeln!(stream, "{deep_link}"))
+                    {
+                        tracing::error!("fail to connect to the running instance: {err}");
+                    }
+                    return false;
+                }
+            }
+        }
+
+        let service = SHARED_DESKTOP_SERVICE.get_or_init(|| self);
+        if let Some(listener) = listener {
+            thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    for line in BufReader::new(stream).lines().map_while(Result::ok) {
+                        let deep_link = service.parse_deep_link(&line).map(|s| s.to_owned());
+                        service.receive_deep_link(deep_link);
+                    }
+                }
+            });
+        }
+        true
+    }
+
+    /// Returns the shared service.
+    ///
+    /// # Panics
+    ///
+    /// It will panic if the desktop application has not been launched.
+    #[inline]
+    pub fn shared() -> &'static Self {
+        SHARED_DESKTOP_SERVICE
+            .get()
+            .expect("the desktop service should be initialized")
+    }
+
+    /// Shows a native notification.
+    pub fn notify(&self, summary: &str, body: &str) -> Result<(), Error> {
+        Notification::new()
+            .appname(self.app_name)
+            .summary(summary)
+            .body(body)
+            .show()
+            .map_err(|err| Error::new(format!("fail to show the notification: {err}")))?;
+        Ok(())
+    }
+
+    /// Returns the deep link which is used to launch the app.
+    #[inline]
+    pub fn launch_deep_link(&self) -> Option<&str> {
+        self.launch_deep_link.as_deref()
+    }
+
+    /// Subscribes to the deep links opened when the app is running.
+    /// An empty string is received if the app is activated without a deep link.
+    #[inline]
+    pub fn subscribe_deep_links(&self) -> Receiver<String> {
+        self.deep_link_sender.subscribe()
+    }
+
+    /// Subscribes to the ids of the clicked tray menu items.
+    #[inline]
+    pub fn subscribe_menu_events(&self) -> Receiver<String> {
+        self.menu_event_sender.subscribe()
+    }
+
+    /// Builds the tray icon. It should be kept alive while the app is running.
+    pub(crate) fn build_tray_icon(&self) -> Option<TrayIcon> {
+        let icon_file = self.tray_icon.as_ref()?;
+        let icon = match Reader::open(icon_file)
+            .map_err(ImageError::IoError)
+            .and_then(|reader| reader.decode())
+        {
+            Ok(img) => {
+                let width = img.width();
+                let height = img.height();
+                match Icon::from_rgba(img.into_rgba8().into_raw(), width, height) {
+                    Ok(icon) => icon,
+                    Err(err) => {
+                        let icon_file = icon_file.display();
+                        tracing::error!("fail to set the tray icon `{icon_file}`: {err}");
+                        return None;
+                    }
+                }
+            }
+            Err(err) => {
+                let icon_file = icon_file.display();
+                tracing::error!("fail to decode the tray icon file `{icon_file}`: {err}");
+                return None;
+            }
+        };
+
+        let menu = Menu::new();
+        for (id, text) in self.tray_menu.iter() {
+            let result = if id == "separator" {
+                menu.append(&PredefinedMenuItem::separator())
+            } else {
+                menu.append(&MenuItem::with_id(id.as_str(), text, true, None))
+            };
+            if let Err(err) = result {
+                tracing::error!("fail to add the tray menu item `{id}`: {err}");
+            }
+        }
+
+        let mut builder = TrayIconBuilder::new()
+            .with_icon(icon)
+            .with_menu(Box::new(menu));
+        if let Some(tooltip) = self.tray_tooltip.as_ref() {
+            builder = builder.with_tooltip(tooltip);
+        }
+        match builder.build() {
+            Ok(tray_icon) => Some(tray_icon),
+            Err(err) => {
+                tracing::error!("fail to build the tray icon: {err}");
+                None
+            }
+        }
+    }
+
+    /// Dispatches a tray menu event to the subscribers.
+    pub(crate) fn dispatch_menu_event(&self, id: &str) {
+        if id == "quit" {
+            std::process::exit(0);
+        }
+        self.menu_event_sender.send(id.to_owned()).ok();
+    }
+
+    /// Receives a deep link and dispatches it to the subscribers.
+    fn receive_deep_link(&self, deep_link: Option<String>) {
+        let deep_link = deep_link.unwrap_or_default();
+        tracing::info!(deep_link, "the running instance is activated");
+        self.deep_link_sender.send(deep_link).ok();
+    }
+
+    /// Parses the deep link with a custom URL scheme.
+    fn parse_deep_link<'a>(&self, arg: &'a str) -> Option<&'a str> {
+        let arg = arg.trim();
+        self.url_schemes
+            .iter()
+            .any(|scheme| {
+                arg.strip_prefix(scheme.as_str())
+                    .is_some_and(|s| s.starts_with(':'))
+            })
+            .then_some(arg)
+    }
+}
+
+/// Shared desktop service.
+static SHARED_DESKTOP_SERVICE: OnceLock<DesktopService> = OnceLock::new();
@@ -1,3 +1,4 @@
+use super::desktop_service::DesktopService;
 use dioxus::prelude::*;
 use dioxus_desktop::{
     tao::window::{Icon, Theme},
@@ -5,19 +6,26 @@ use dioxus_desktop::{
 };
 use dioxus_router::{components::Router, routable::Routable};
 use image::{error::ImageError, io::Reader};
-use std::{fmt::Display, fs, marker::PhantomData, str::FromStr, time::Duration};
+use std::{fmt::Display, fs, marker::PhantomData, rc::Rc, str::FromStr, time::Duration};
 use tokio::runtime::Builder;
+use tray_icon::menu::MenuEvent;
 use zino_core::{
-    application::{Application, Plugin},
+    application::{Application, Module, Plugin},
     extension::TomlTableExt,
     schedule::AsyncScheduler,
 };
 
 /// A webview-based desktop renderer for the Dioxus VirtualDom.
 #[derive(Default)]
-pub struct DioxusDesktop<R> {
+pub struct DioxusDesktop<R>
+where
+    R: Routable,
+    <R as FromStr>::Err: Display,
+{
     /// Custom plugins.
     custom_plugins: Vec<Plugin>,
+    /// Custom modules.
+    custom_modules: Vec<Box<dyn Module<DioxusDesktop<R>>>>,
     /// Phantom type of Dioxus router.
     phantom: PhantomData<R>,
 }
@@ -29,6 +37,33 @@ where
 {
     /// Renders the app root.
     fn app_root() -> Element {
+        let desktop_service = DesktopService::shared();
+        use_hook(|| desktop_service.build_tray_icon().map(Rc::new));
+        use_future(move || async move {
+            let window = dioxus_desktop::window();
+            let mut deep_links = desktop_service.subscribe_deep_links();
+            loop {
+                while let Ok(event) = MenuEvent::receiver().try_recv() {
+                    let id = event.id.as_ref();
+                    match id {
+                        "show" => {
+                            window.set_visible(true);
+                            window.set_focus();
+                        }
+                        "hide" => window.set_visible(false),
+                        _ => (),
+                    }
+                    desktop_service.dispatch_menu_event(id);
+                }
+                if deep_links.try_recv().is_ok() {
+                    // Brings the window to the front when the running instance is activated.
+                    window.set_visible(true);
+                    window.set_minimized(false);
+                    window.set_focus();
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        });
         rsx! { Router::<R> {} }
     }
 }
@@ -49,7 +84,14 @@ where
         self
     }
 
-    fn run_with<T: AsyncScheduler + Send + 'static>(self, mut scheduler: T) {
+    /// Adds a custom module. The routes of the module are ignored
+    /// since the pages are rendered by the Dioxus router.
+    fn add_module<M: Module<Self>>(mut self, module: M) -> Self {
+        self.custom_modules.push(Box::new(module));
+        self
+    }
+
+    fn run_with<T: AsyncScheduler + Send + 'static>(self, scheduler: T) {
         let runtime = Builder::new_multi_thread()
             .thread_keep_alive(Duration::from_secs(60))
             .thread_stack_size(2 * 1024 * 1024)
@@ -58,10 +100,13 @@ where
             .build()
             .expect("fail to build Tokio runtime for `DioxusDesktop`");
         let app_env = Self::env();
-        runtime.block_on(async {
+        let custom_modules = self.custom_modules;
+        let module_scheduler = runtime.block_on(async {
             Self::load().await;
             super::load_plugins(self.custom_plugins, app_env).await;
+            super::load_modules(&custom_modules, app_env).await
         });
+        let mut scheduler = (scheduler, module_scheduler);
         if scheduler.is_ready() {
             runtime.spawn(async move {
                 loop {
@@ -130,6 +175,7 @@ where
         let mut desktop_config = Config::new()
             .with_window(app_window)
             .with_disable_context_menu(in_prod_mode);
+        let mut desktop_service = DesktopService::new(app_name, project_dir);
         if let Some(config) = app_state.get_config("desktop") {
             let mut custom_heads = Vec::new();
             if let Some(icon) = config.get_str("icon") {
@@ -202,6 +248,37 @@ where
             if let Some(name) = config.get_str("root-name") {
                 desktop_config = desktop_config.with_root_name(name);
             }
+            if let Some(single_instance) = config.get_bool("single-instance") {
+                desktop_service.set_single_instance(single_instance);
+            }
+            if let Some(port) = config.get_u16("single-instance-port") {
+                desktop_service.set_instance_port(port);
+            }
+            if let Some(schemes) = config.get_str_array("url-schemes") {
+                for scheme in schemes {
+                    desktop_service.add_url_scheme(scheme);
+                }
+            }
+            if let Some(tray) = config.get_table("tray") {
+                if let Some(icon) = tray.get_str("icon") {
+                    desktop_service.set_tray_icon(icon);
+                }
+                if let Some(tooltip) = tray.get_str("tooltip") {
+                    desktop_service.set_tray_tooltip(tooltip);
+                }
+                if let Some(items) = tray.get_array("menu") {
+                    for item in items.iter().filter_map(|v| v.as_table()) {
+                        if let Some(id) = item.get_str("id") {
+                            let text = item.get_str("text").unwrap_or(id);
+                            desktop_service.add_tray_menu_item(id, text);
+                        }
+                    }
+                }
+            }
+        }
+        if !desktop_service.init() {
+            tracing::warn!(app_name, "the app is already running in another instance");
+            return;
         }
 
         tracing::warn!(
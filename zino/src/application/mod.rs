@@ -3,21 +3,25 @@ cfg_if::cfg_if! {
         mod plugin_loader;
         pub(crate) mod actix_cluster;
 
-        use plugin_loader::load_plugins;
+        use plugin_loader::{load_modules, load_plugins, shutdown_modules};
     } else if #[cfg(feature = "axum")] {
         mod plugin_loader;
         pub(crate) mod axum_cluster;
 
-        use plugin_loader::load_plugins;
+        #[cfg(feature = "orm")]
+        mod axum_query_log;
+
+        use plugin_loader::{load_modules, load_plugins, shutdown_modules};
     } else if #[cfg(feature = "dioxus-desktop")] {
         mod plugin_loader;
+        pub(crate) mod desktop_service;
         pub(crate) mod dioxus_desktop;
 
-        use plugin_loader::load_plugins;
+        use plugin_loader::{load_modules, load_plugins};
     } else if #[cfg(feature = "ntex")] {
         mod plugin_loader;
         pub(crate) mod ntex_cluster;
 
-        use plugin_loader::load_plugins;
+        use plugin_loader::{load_modules, load_plugins, shutdown_modules};
     }
 }
@@ -1,4 +1,7 @@
-use crate::RouterConfigure;
+use crate::{
+    middleware::{self, Middleware},
+    RouterConfigure,
+};
 use ntex::{
     rt::System,
     time::{self, Seconds},
@@ -12,7 +15,7 @@ use ntex::{
 use ntex_files::{Files, NamedFile};
 use std::path::PathBuf;
 use zino_core::{
-    application::{Application, Plugin, ServerTag},
+    application::{Application, Module, Plugin, ServerTag},
     extension::TomlTableExt,
     schedule::AsyncScheduler,
 };
@@ -22,12 +25,25 @@ use zino_core::{
 pub struct NtexCluster {
     /// Custom plugins.
     custom_plugins: Vec<Plugin>,
+    /// Custom middlewares.
+    custom_middlewares: Vec<Box<dyn Middleware>>,
+    /// Custom modules.
+    custom_modules: Vec<Box<dyn Module<NtexCluster>>>,
     /// Default routes.
     default_routes: Vec<RouterConfigure>,
     /// Tagged routes.
     tagged_routes: Vec<(ServerTag, Vec<RouterConfigure>)>,
 }
 
+impl NtexCluster {
+    /// Adds a custom middleware which runs for all the routes.
+    #[inline]
+    pub fn add_middleware(mut self, middleware: impl Middleware) -> Self {
+        self.custom_middlewares.push(Box::new(middleware));
+        self
+    }
+}
+
 impl Application for NtexCluster {
     type Routes = Vec<RouterConfigure>;
 
@@ -46,12 +62,20 @@ impl Application for NtexCluster {
         self
     }
 
-    fn run_with<T: AsyncScheduler + Send + 'static>(self, mut scheduler: T) {
+    fn add_module<M: Module<Self>>(mut self, module: M) -> Self {
+        self.custom_modules.push(Box::new(module));
+        self
+    }
+
+    fn run_with<T: AsyncScheduler + Send + 'static>(self, scheduler: T) {
         let app_env = Self::env();
-        System::new("prelude").block_on(async {
+        let custom_modules = self.custom_modules;
+        let module_scheduler = System::new("prelude").block_on(async {
             Self::load().await;
             super::load_plugins(self.custom_plugins, app_env).await;
+            super::load_modules(&custom_modules, app_env).await
         });
+        let mut scheduler = (scheduler, module_scheduler);
         if scheduler.is_ready() {
             // It should be fixed by pasing `System::current()` from `block_on`.
             // https://github.com/ntex-rs/ntex/issues/335#issuecomment-2071498572
@@ -69,8 +93,13 @@ impl Application for NtexCluster {
         }
 
         System::new("main").block_on(async {
-            let default_routes = self.default_routes.leak() as &'static [_];
+            let mut default_routes = self.default_routes;
+            for module in &custom_modules {
+                default_routes.extend(module.routes());
+            }
+            let default_routes = default_routes.leak() as &'static [_];
             let tagged_routes = self.tagged_routes.leak() as &'static [_];
+            let custom_middlewares = self.custom_middlewares.leak() as &'static [_];
             let app_state = Self::shared_state();
             let app_name = Self::name();
             let app_version = Self::version();
@@ -167,6 +196,7 @@ impl Application for NtexCluster {
                         .state(JsonConfig::default().limit(body_limit))
                         .state(PayloadConfig::default().limit(body_limit))
                         .wrap(Compress::default())
+                        .wrap(middleware::CustomMiddlewares(custom_middlewares))
                 })
                 .stop_runtime()
                 .disable_signals()
@@ -183,6 +213,7 @@ impl Application for NtexCluster {
                     tracing::error!("ntex server error: {err}");
                 }
             }
+            super::shutdown_modules(&custom_modules).await;
         });
     }
 }
@@ -1,4 +1,8 @@
-use zino_core::{application::Plugin, state::Env};
+use zino_core::{
+    application::{Application, Module, Plugin},
+    schedule::AsyncJobScheduler,
+    state::Env,
+};
 
 /// Loads the plugins for the application.
 pub(super) async fn load_plugins(plugins: Vec<Plugin>, app_env: &Env) {
@@ -43,3 +47,46 @@ pub(super) async fn load_plugins(plugins: Vec<Plugin>, app_env: &Env) {
         }
     }
 }
+
+/// Initializes the modules for the application,
+/// and returns a scheduler for the background jobs of them.
+pub(super) async fn load_modules<App: Application>(
+    modules: &[Box<dyn Module<App>>],
+    app_env: &Env,
+) -> AsyncJobScheduler {
+    let mut scheduler = AsyncJobScheduler::new();
+    #[cfg(feature = "orm")]
+    for job in zino_core::orm::take_model_jobs() {
+        scheduler.add(job);
+    }
+    for module in modules {
+        let module_name = module.name();
+        if let Err(err) = module.init().await {
+            tracing::error!(
+                app_env = app_env.as_str(),
+                module_name,
+                "fail to initialize the module `{module_name}`: {err}",
+            );
+        } else {
+            for job in module.jobs() {
+                scheduler.add(job);
+            }
+            tracing::warn!(
+                app_env = app_env.as_str(),
+                module_name,
+                "loaded the module `{module_name}`",
+            );
+        }
+    }
+    scheduler
+}
+
+/// Shuts down the modules in the reverse order.
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+pub(super) async fn shutdown_modules<App: Application>(modules: &[Box<dyn Module<App>>]) {
+    for module in modules.iter().rev() {
+        let module_name = module.name();
+        module.shutdown().await;
+        tracing::warn!(module_name, "shut down the module `{module_name}`");
+    }
+}
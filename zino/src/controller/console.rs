@@ -0,0 +1,78 @@
+use zino_core::{validation::escape_html, JsonValue, Map};
+
+/// Renders the interactive console of a model.
+pub(super) fn render_console(model_name: &str, definitions: &[Map]) -> String {
+    let mut rows = String::new();
+    for definition in definitions {
+        let field = |key: &str| match definition.get(key) {
+            Some(JsonValue::String(s)) => escape_html(s),
+            Some(value) => escape_html(&value.to_string()),
+            None => String::new(),
+        };
+        rows.push_str("<tr><td>");
+        rows.push_str(&field("name"));
+        rows.push_str("</td><td>");
+        rows.push_str(&field("type"));
+        rows.push_str("</td><td>");
+        rows.push_str(&field("format"));
+        rows.push_str("</td><td>");
+        rows.push_str(&field("description"));
+        rows.push_str("</td></tr>");
+    }
+
+    let model_name = escape_html(model_name);
+    let sample = escape_html(
+        r#"[
+  { "action": "find", "query": { "fields": ["id", "name"], "limit": 10 } },
+  { "action": "insert", "data": { "name": "console" } },
+  { "action": "update", "query": { "name": "console" }, "data": { "description": "updated" } },
+  { "action": "delete", "query": { "name": "console" } }
+]"#,
+    );
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{model_name} console</title>
+<style>
+body {{ margin: 16px; font-family: sans-serif; }}
+table {{ border-collapse: collapse; width: 100%; }}
+th, td {{ border: 1px solid #ddd; padding: 6px 8px; text-align: left; }}
+textarea {{ width: 100%; font-family: monospace; }}
+pre {{ background: #f5f5f5; padding: 8px; overflow: auto; }}
+</style>
+</head>
+<body>
+<h1>{model_name} console</h1>
+<h2>Definition</h2>
+<table>
+<thead><tr><th>Field</th><th>Type</th><th>Format</th><th>Description</th></tr></thead>
+<tbody>{rows}</tbody>
+</table>
+<h2>Sandbox</h2>
+<p>The operations are executed inside of a transaction which is always rolled back.</p>
+<textarea id="operations" rows="12">{sample}</textarea>
+<p><button id="execute" type="button">Execute</button></p>
+<pre id="result"></pre>
+<script>
+document.getElementById("execute").addEventListener("click", async () => {{
+  const result = document.getElementById("result");
+  try {{
+    const body = JSON.parse(document.getElementById("operations").value);
+    const res = await fetch(window.location.pathname, {{
+      method: "POST",
+      headers: {{ "content-type": "application/json" }},
+      body: JSON.stringify(body),
+    }});
+    result.textContent = JSON.stringify(await res.json(), null, 2);
+  }} catch (err) {{
+    result.textContent = String(err);
+  }}
+}});
+</script>
+</body>
+</html>"#
+    )
+}
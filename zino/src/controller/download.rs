@@ -0,0 +1,238 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+use zino_core::{
+    application::Application,
+    auth::{AccessKeyId, SecretAccessKey, SecurityToken},
+    datetime::DateTime,
+    error::Error,
+    extension::JsonObjectExt,
+    file::NamedFile,
+    request::RequestContext,
+    response::{ExtractRejection, Rejection, Response, StatusCode},
+    warn, Map,
+};
+
+/// Controller for downloading files with range requests and signed URLs.
+///
+/// The `Range` and `If-Range` headers are supported so that the downloads can be resumed.
+/// A signed URL generated by [`sign_url()`](DownloadController::sign_url) is bound to
+/// the file name, the `decrypt` flag and the expiration time,
+/// which can be used for unauthenticated access.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino::DownloadController;
+///
+/// pub struct FileDownload;
+///
+/// impl DownloadController for FileDownload {}
+///
+/// let router = Router::new()
+///     .route("/file/download", get(FileDownload::download))
+///     .route("/file/shared", get(FileDownload::download_signed));
+///
+/// let expires_at = DateTime::now() + Duration::from_secs(600);
+/// let url = FileDownload::sign_url("/file/shared", "report.pdf", AccessKeyId::new(), expires_at, false)?;
+/// ```
+pub trait DownloadController {
+    /// Returns the directory for the files to download.
+    #[inline]
+    fn download_dir() -> PathBuf {
+        crate::Cluster::shared_dir("uploads")
+    }
+
+    /// Returns `true` if the file is blocked from download.
+    /// The quarantined files are stored under generated IDs in a hidden directory,
+    /// so the dot files are always blocked.
+    #[inline]
+    fn is_blocked(file_name: &str) -> bool {
+        file_name.starts_with('.')
+    }
+
+    /// Generates a signed URL for the file with the access key ID, which expires at the time.
+    /// If `decrypt` is `true`, the file will be decrypted on the fly
+    /// with the secret access key derived from the access key ID.
+    fn sign_url(
+        base_url: &str,
+        file_name: &str,
+        access_key_id: AccessKeyId,
+        expires_at: DateTime,
+        decrypt: bool,
+    ) -> Result<String, Error> {
+        let expires = expires_at.timestamp();
+        let secret_key = SecretAccessKey::new(&access_key_id);
+        let key = signing_key(&secret_key, file_name, decrypt, expires);
+        let security_token = SecurityToken::try_new(access_key_id.clone(), expires_at, key)?;
+
+        let mut query = Map::new();
+        query.upsert("file_name", file_name);
+        query.upsert("access_key_id", access_key_id.to_string());
+        query.upsert("security_token", security_token.to_string());
+        query.upsert("expires", expires);
+        if decrypt {
+            query.upsert("decrypt", true);
+        }
+        Ok(format!("{base_url}?{}", query.to_query_string()))
+    }
+
+    /// Downloads a file with the `file_name` query.
+    async fn download(req: crate::Request) -> crate::Result {
+        let file_name = parse_file_name(&req)?;
+        if Self::is_blocked(file_name) {
+            let err = warn!("403 Forbidden: the file `{}` is blocked", file_name);
+            return Err(Rejection::forbidden(err).context(&req).into());
+        }
+        send_file(&req, &Self::download_dir().join(file_name), None)
+    }
+
+    /// Downloads a file with the signed URL generated by [`sign_url()`](Self::sign_url).
+    async fn download_signed(req: crate::Request) -> crate::Result {
+        let file_name = parse_file_name(&req)?;
+        let Some(expires) = req.get_query("expires").and_then(|s| s.parse::<i64>().ok()) else {
+            let err = warn!("403 Forbidden: the expiration time of the signed URL is invalid");
+            return Err(Rejection::forbidden(err).context(&req).into());
+        };
+        let decrypt = req.get_query("decrypt") == Some("true");
+        let access_key_id = req.parse_access_key_id()?;
+        let secret_key = SecretAccessKey::new(&access_key_id);
+        let key = signing_key(&secret_key, file_name, decrypt, expires);
+        let security_token = req.parse_security_token(&key)?;
+        if security_token.is_expired() {
+            let err = warn!("403 Forbidden: the signed URL has expired");
+            return Err(Rejection::forbidden(err).context(&req).into());
+        }
+        if Self::is_blocked(file_name) {
+            let err = warn!("403 Forbidden: the file `{}` is blocked", file_name);
+            return Err(Rejection::forbidden(err).context(&req).into());
+        }
+
+        let key = decrypt.then_some(secret_key.as_ref());
+        send_file(&req, &Self::download_dir().join(file_name), key)
+    }
+}
+
+/// Sends a file with the support of the `Range` and `If-Range` headers.
+/// The file will be decrypted on the fly if the key is specified.
+pub(super) fn send_file(req: &crate::Request, path: &Path, key: Option<&[u8]>) -> crate::Result {
+    let file_name = path
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) if metadata.is_file() => metadata,
+        _ => {
+            let err = warn!("404 Not Found: the file `{}` does not exist", file_name);
+            return Err(Rejection::not_found(err).context(req).into());
+        }
+    };
+    let modified_at = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let etag = format!(r#""{:x}-{:x}""#, metadata.len(), modified_at);
+    let last_modified =
+        DateTime::from_timestamp(modified_at.try_into().unwrap_or_default()).to_utc_string();
+
+    // The whole content is required for the decryption.
+    let decrypted_file = if let Some(key) = key {
+        let mut file = NamedFile::try_from_local(path).extract(req)?;
+        file.decrypt_with(key).extract(req)?;
+        Some(file)
+    } else {
+        None
+    };
+    let total_size = decrypted_file
+        .as_ref()
+        .map(|file| file.file_size())
+        .unwrap_or(metadata.len());
+    let range = req.get_header("range").filter(|_| {
+        req.get_header("if-range")
+            .map_or(true, |s| s == etag || s == last_modified)
+    });
+
+    let mut res = Response::default().context(req);
+    res.insert_header("accept-ranges", "bytes");
+    res.insert_header("last-modified", last_modified);
+    match range.and_then(|range| parse_byte_range(range, total_size)) {
+        Some(Some((start, end))) => {
+            res.set_code(StatusCode::PARTIAL_CONTENT);
+            res.insert_header("content-range", format!("bytes {start}-{end}/{total_size}"));
+            if let Some(file) = decrypted_file {
+                let range =
+                    usize::try_from(start).extract(req)?..=usize::try_from(end).extract(req)?;
+                let mut partial_file = NamedFile::new(file_name);
+                partial_file.set_bytes(file.bytes().slice(range));
+                res.send_file_with_etag(partial_file, etag);
+            } else {
+                res.send_file_range(path, start, end, etag).extract(req)?;
+            }
+        }
+        Some(None) => {
+            let mut res = Response::new(StatusCode::RANGE_NOT_SATISFIABLE).context(req);
+            res.insert_header("content-range", format!("bytes */{total_size}"));
+            return Ok(res.into());
+        }
+        None => {
+            if let Some(file) = decrypted_file {
+                res.send_file_with_etag(file, etag);
+            } else {
+                let end = total_size.saturating_sub(1);
+                res.send_file_range(path, 0, end, etag).extract(req)?;
+            }
+        }
+    }
+    Ok(res.into())
+}
+
+/// Parses the file name in the query.
+fn parse_file_name(req: &crate::Request) -> Result<&str, Rejection> {
+    req.get_query("file_name")
+        .and_then(|s| Path::new(s).file_name())
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| {
+            let err = warn!("the file name should be specified");
+            Rejection::from_validation_entry("file_name", err).context(req)
+        })
+}
+
+/// Derives the key for signing the URL, which is bound to the file name,
+/// the `decrypt` flag and the expiration time.
+fn signing_key(
+    secret_key: &SecretAccessKey,
+    file_name: &str,
+    decrypt: bool,
+    expires: i64,
+) -> Vec<u8> {
+    let material = format!("{file_name}\n{decrypt}\n{expires}");
+    [secret_key.as_ref(), material.as_bytes()].concat()
+}
+
+/// Parses a single byte range such as `bytes=0-499`, `bytes=500-` or `bytes=-500`.
+/// It returns `None` if the range is unsupported, which should be ignored,
+/// and `Some(None)` if the range is unsatisfiable.
+fn parse_byte_range(range: &str, total_size: u64) -> Option<Option<(u64, u64)>> {
+    let (start, end) = range.strip_prefix("bytes=")?.trim().split_once('-')?;
+    if start.contains(',') || end.contains(',') {
+        return None;
+    }
+    let range = if start.is_empty() {
+        let suffix_length = end.parse::<u64>().ok()?;
+        (suffix_length > 0 && total_size > 0)
+            .then(|| (total_size.saturating_sub(suffix_length), total_size - 1))
+    } else {
+        let start = start.parse::<u64>().ok()?;
+        let end = if end.is_empty() {
+            total_size.saturating_sub(1)
+        } else {
+            end.parse::<u64>().ok()?.min(total_size.saturating_sub(1))
+        };
+        (start <= end && start < total_size).then_some((start, end))
+    };
+    Some(range)
+}
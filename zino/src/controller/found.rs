@@ -0,0 +1,189 @@
+use super::parse_model_id;
+use std::{
+    fmt::Display,
+    ops::{Deref, DerefMut},
+    str::FromStr,
+};
+use zino_core::{
+    extension::JsonObjectExt,
+    model::Query,
+    orm::Schema,
+    request::{FromRequest, RequestContext},
+    response::{ExtractRejection, Rejection},
+    warn, JsonValue, Map,
+};
+
+/// A model found by the `id` param of the request path.
+///
+/// It parses the `id` param as the primary key of the model, which is decoded by
+/// the shared `IdCodec` if the primary key is exposed as a public ID, and fetches the record.
+/// A `404 Not Found` rejection will be returned if the record does not exist.
+/// As an extractor, the logically deleted model is excluded. Use [`Found::lookup()`]
+/// to customize the lookup.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino::{prelude::*, Found, Request, Response, Result};
+///
+/// pub async fn view(mut req: Request) -> Result {
+///     let user = req.extract::<Found<User>>().await?;
+///     let mut res = Response::default().context(&req);
+///     res.set_json_data(Map::data_entry(user.into_inner()));
+///     Ok(res.into())
+/// }
+///
+/// pub async fn archive(req: Request) -> Result {
+///     let session = req.get_data::<UserSession<Uuid>>().extract(&req)?;
+///     let tenant_id = session.tenant_id().extract(&req)?;
+///     let order = Found::<Order>::lookup()
+///         .include_deleted()
+///         .scope("tenant_id", tenant_id.to_string())
+///         .fetch(&req)
+///         .await?;
+///     // ...
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Found<M>(M);
+
+impl<M> Found<M>
+where
+    M: Schema,
+    <M as Schema>::PrimaryKey: FromStr,
+    <<M as Schema>::PrimaryKey as FromStr>::Err: std::error::Error + Send + 'static,
+{
+    /// Fetches the model by the `id` param, excluding the logically deleted one.
+    #[inline]
+    pub async fn fetch<Ctx: RequestContext>(req: &Ctx) -> Result<Self, Rejection> {
+        Self::lookup().fetch(req).await
+    }
+
+    /// Creates a lookup of the model which can be customized before fetching.
+    #[inline]
+    pub fn lookup() -> Lookup<M> {
+        Lookup::default()
+    }
+}
+
+impl<M> FromRequest for Found<M>
+where
+    M: Schema,
+    <M as Schema>::PrimaryKey: FromStr,
+    <<M as Schema>::PrimaryKey as FromStr>::Err: std::error::Error + Send + 'static,
+{
+    #[inline]
+    async fn from_request<Ctx: RequestContext>(req: &mut Ctx) -> Result<Self, Rejection> {
+        Self::fetch(req).await
+    }
+
+    async fn from_request_optional<Ctx: RequestContext>(
+        req: &mut Ctx,
+    ) -> Result<Option<Self>, Rejection> {
+        match Self::fetch(req).await {
+            Ok(found) => Ok(Some(found)),
+            Err(rejection) if rejection.status_code() == 404 => Ok(None),
+            Err(rejection) => Err(rejection),
+        }
+    }
+}
+
+impl<M> Found<M> {
+    /// Consumes `self` and returns the model.
+    #[inline]
+    pub fn into_inner(self) -> M {
+        self.0
+    }
+}
+
+impl<M> Deref for Found<M> {
+    type Target = M;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<M> DerefMut for Found<M> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// A lookup of the model by the `id` param of the request path.
+#[derive(Debug, Clone)]
+pub struct Lookup<M> {
+    /// A flag to indicate whether the logically deleted model is included.
+    include_deleted: bool,
+    /// Additional filters to scope the lookup, such as the tenant ID.
+    scopes: Map,
+    /// Phantom type of the model.
+    phantom: std::marker::PhantomData<M>,
+}
+
+impl<M> Default for Lookup<M> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            include_deleted: false,
+            scopes: Map::new(),
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<M> Lookup<M>
+where
+    M: Schema,
+    <M as Schema>::PrimaryKey: FromStr,
+    <<M as Schema>::PrimaryKey as FromStr>::Err: std::error::Error + Send + 'static,
+{
+    /// Includes the model even if it has been logically deleted.
+    #[inline]
+    pub fn include_deleted(mut self) -> Self {
+        self.include_deleted = true;
+        self
+    }
+
+    /// Scopes the lookup by the field, such as the tenant ID.
+    /// The model is regarded as absent if the field value does not match.
+    #[inline]
+    pub fn scope(mut self, field: &str, value: impl Into<JsonValue>) -> Self {
+        self.scopes.insert(field.to_owned(), value.into());
+        self
+    }
+
+    /// Fetches the model by the `id` param.
+    pub async fn fetch<Ctx: RequestContext>(self, req: &Ctx) -> Result<Found<M>, Rejection> {
+        let id = parse_model_id::<M, M::PrimaryKey>(req)?;
+        let query = self.build_query(&id);
+        let mut data = M::find_one::<Map>(&query)
+            .await
+            .map_err(|err| Rejection::from_error(err).context(req))?
+            .ok_or_else(|| {
+                let err = warn!(
+                    "cannot find the model `{}` with the key `{}`",
+                    M::MODEL_NAME,
+                    id
+                );
+                Rejection::not_found(err).context(req)
+            })?;
+        M::after_decode(&mut data).await.extract(req)?;
+        M::try_from_map(data).map(Found).extract(req)
+    }
+
+    /// Builds a query for the model with the primary key.
+    fn build_query(self, id: &impl Display) -> Query {
+        let mut query = M::default_query();
+        query.add_filter(M::PRIMARY_KEY_NAME, id.to_string());
+        if !self.include_deleted && M::has_column("status") {
+            query.add_filter("status", Map::from_entry("$ne", "Deleted"));
+        }
+        for (field, value) in self.scopes {
+            query.add_filter(field, value);
+        }
+        query
+    }
+}
@@ -0,0 +1,110 @@
+use zino_core::{
+    extension::JsonObjectExt,
+    request::RequestContext,
+    response::{Rejection, Response, StatusCode},
+    schedule::{JobCommand, JobRegistry},
+    warn, Map, Uuid,
+};
+
+/// Controller for managing the scheduled jobs at runtime.
+///
+/// The runs of the jobs can be persisted by setting a recorder for the `AsyncJobScheduler`,
+/// such as `JobRun::record_runs` in `zino-model`.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino::JobController;
+///
+/// pub struct JobManager;
+///
+/// impl JobController for JobManager {}
+///
+/// let router = Router::new()
+///     .route("/jobs", get(JobManager::list_jobs))
+///     .route("/job/:id/runs", get(JobManager::list_runs))
+///     .route("/job/:id/failure", get(JobManager::last_failure))
+///     .route("/job/:id/trigger", post(JobManager::trigger))
+///     .route("/job/:id/pause", post(JobManager::pause))
+///     .route("/job/:id/resume", post(JobManager::resume));
+/// ```
+pub trait JobController {
+    /// Lists the status of all the jobs.
+    async fn list_jobs(req: crate::Request) -> crate::Result {
+        let jobs = JobRegistry::list_jobs();
+        let mut res = Response::default().context(&req);
+        res.set_json_data(Map::data_entries(jobs));
+        Ok(res.into())
+    }
+
+    /// Lists the most recent runs of the job. The number of runs is limited by `limit`.
+    async fn list_runs(req: crate::Request) -> crate::Result {
+        let job_id = parse_job_id(&req)?;
+        let limit = req
+            .get_query("limit")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(20);
+        let runs = JobRegistry::list_runs(Some(job_id), limit)
+            .iter()
+            .map(|run| run.to_map())
+            .collect::<Vec<_>>();
+        let mut res = Response::default().context(&req);
+        res.set_json_data(Map::data_entries(runs));
+        Ok(res.into())
+    }
+
+    /// Returns the last failed run of the job.
+    async fn last_failure(req: crate::Request) -> crate::Result {
+        let job_id = parse_job_id(&req)?;
+        let run = JobRegistry::last_failure(job_id).map(|run| run.to_map());
+        let mut res = Response::default().context(&req);
+        res.set_json_data(Map::data_entry(run.unwrap_or_default()));
+        Ok(res.into())
+    }
+
+    /// Triggers a manual run of the job.
+    #[inline]
+    async fn trigger(req: crate::Request) -> crate::Result {
+        send_command(req, JobCommand::Trigger)
+    }
+
+    /// Pauses the job.
+    #[inline]
+    async fn pause(req: crate::Request) -> crate::Result {
+        send_command(req, JobCommand::Pause)
+    }
+
+    /// Resumes the job.
+    #[inline]
+    async fn resume(req: crate::Request) -> crate::Result {
+        send_command(req, JobCommand::Resume)
+    }
+}
+
+/// Parses the job ID and checks whether the job exists.
+fn parse_job_id(req: &crate::Request) -> Result<Uuid, Rejection> {
+    let job_id = req.parse_param::<Uuid>("id")?;
+    if JobRegistry::get_job(job_id).is_none() {
+        let err = warn!("the job `{}` does not exist", job_id);
+        return Err(Rejection::not_found(err).context(req));
+    }
+    Ok(job_id)
+}
+
+/// Sends a command to the job.
+fn send_command(req: crate::Request, command: JobCommand) -> crate::Result {
+    let job_id = parse_job_id(&req)?;
+    if !JobRegistry::send_command(job_id, command) {
+        let err = warn!("the job `{}` does not exist", job_id);
+        return Err(Rejection::not_found(err).context(&req).into());
+    }
+
+    let mut data = Map::new();
+    data.upsert("job_id", job_id.to_string());
+    data.upsert("command", format!("{command:?}"));
+
+    let mut res = Response::default().context(&req);
+    res.set_code(StatusCode::ACCEPTED);
+    res.set_json_data(Map::data_entry(data));
+    Ok(res.into())
+}
@@ -1,3 +1,30 @@
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+#[cfg(feature = "orm")]
+mod console;
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+mod download;
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+#[cfg(feature = "orm")]
+mod found;
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+mod job;
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+mod operation;
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+mod upload;
+
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+pub use download::DownloadController;
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+#[cfg(feature = "orm")]
+pub use found::{Found, Lookup};
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+pub use job::JobController;
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+pub use operation::{OperationController, OperationExt};
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+pub use upload::UploadController;
+
 /// Default controller for the `Model`.
 pub trait DefaultController<K> {
     /// A type for the request extractor.
@@ -10,12 +37,14 @@ pub trait DefaultController<K> {
     async fn new(req: Self::Request) -> Self::Result;
 
     /// Deletes a model.
+    /// The `if-match` header is checked against the entity tag of the model.
     async fn delete(req: Self::Request) -> Self::Result;
 
     /// Updates a model.
+    /// The `if-match` header is checked against the entity tag of the model.
     async fn update(req: Self::Request) -> Self::Result;
 
-    /// Views a model.
+    /// Views a model with the `etag` header derived from its version.
     async fn view(req: Self::Request) -> Self::Result;
 
     /// Lists models.
@@ -42,15 +71,24 @@ pub trait DefaultController<K> {
     /// Batch updates multiple models.
     async fn batch_update(req: Self::Request) -> Self::Result;
 
+    /// Batch inserts, updates and deletes models in a single transaction.
+    async fn batch_mutate(req: Self::Request) -> Self::Result;
+
     /// Imports model data.
     async fn import(req: Self::Request) -> Self::Result;
 
     /// Exports model data.
     async fn export(req: Self::Request) -> Self::Result;
 
+    /// Streams models in the JSON Lines format without pagination.
+    async fn stream(req: Self::Request) -> Self::Result;
+
     /// Gets the tree hierarchy data.
     async fn tree(req: Self::Request) -> Self::Result;
 
+    /// Reorders models or moves a model before or after another one.
+    async fn reorder(req: Self::Request) -> Self::Result;
+
     /// Gets the Avro schema for the model.
     async fn schema(req: Self::Request) -> Self::Result;
 
@@ -59,19 +97,64 @@ pub trait DefaultController<K> {
 
     /// Mocks the model data.
     async fn mock(req: Self::Request) -> Self::Result;
+
+    /// Renders an interactive console with the model definition for the `GET` method,
+    /// and executes the operations inside of a sandbox transaction for the `POST` method.
+    /// The sandbox transaction is always rolled back, and the `before_insert` hooks
+    /// are not run since their side effects can not be rolled back.
+    async fn console(req: Self::Request) -> Self::Result;
+
+    /// Lists the related models referenced by or referencing a model.
+    #[cfg(feature = "orm")]
+    async fn list_related<R, J>(req: Self::Request) -> Self::Result
+    where
+        R: zino_core::orm::ModelAccessor<J>,
+        J: Default + std::fmt::Display + PartialEq;
+
+    /// Adds the related models to a model.
+    #[cfg(feature = "orm")]
+    async fn add_related<R, J>(req: Self::Request) -> Self::Result
+    where
+        R: zino_core::orm::ModelAccessor<J>,
+        J: Default + std::fmt::Display + PartialEq;
+
+    /// Removes the related models from a model.
+    #[cfg(feature = "orm")]
+    async fn remove_related<R, J>(req: Self::Request) -> Self::Result
+    where
+        R: zino_core::orm::ModelAccessor<J>,
+        J: Default + std::fmt::Display + PartialEq;
 }
 
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+#[cfg(feature = "orm")]
+use futures::StreamExt;
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+#[cfg(feature = "orm")]
+use std::time::Duration;
 #[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
 #[cfg(feature = "orm")]
 use zino_core::{
-    extension::JsonObjectExt,
-    model::{ModelHooks, Mutation, Query},
-    orm::{ModelAccessor, ModelHelper},
+    error::Error,
+    extension::{JsonObjectExt, JsonValueExt, TomlTableExt},
+    model::{IdCodec, ModelHooks, Mutation, Query, SheetReader, SheetRow},
+    orm::{ModelAccessor, ModelHelper, PositionQuery, Schema, Transaction},
     request::RequestContext,
-    response::{ExtractRejection, Rejection, Response, StatusCode},
-    JsonValue, Map,
+    response::{format_pagination, ExtractRejection, Rejection, Response, StatusCode},
+    validation::Validation,
+    warn, JsonValue, Map,
 };
 
+/// Max page size of the `list` endpoint.
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+#[cfg(feature = "orm")]
+static MAX_PAGE_SIZE: zino_core::LazyLock<usize> = zino_core::LazyLock::new(|| {
+    zino_core::state::State::shared()
+        .get_config("database")
+        .and_then(|config| config.get_usize("max-page-size"))
+        .unwrap_or(1000)
+});
+
 #[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
 #[cfg(feature = "orm")]
 impl<K, M> DefaultController<K> for M
@@ -110,52 +193,165 @@ where
         }
 
         Self::translate_model(&mut model_snapshot);
+        Self::compute_fields(&mut model_snapshot);
         Self::before_respond(&mut model_snapshot, extension.as_ref())
             .await
             .extract(&req)?;
+        Self::encode_public_ids(&mut model_snapshot).extract(&req)?;
         res.set_code(StatusCode::CREATED);
         res.set_json_data(Self::data_item(model_snapshot));
         Ok(res.into())
     }
 
     async fn delete(req: Self::Request) -> Self::Result {
-        let id = req.parse_param::<K>("id")?;
+        let id = parse_model_id::<Self, K>(&req)?;
         let model = Self::try_get_model(&id).await.extract(&req)?;
-        model.delete().await.extract(&req)?;
+        check_if_match::<Self>(&req, &model.snapshot())?;
+        if parse_if_match_version::<Self>(&req).is_some() {
+            model.delete_current_version().await.extract(&req)?;
+        } else {
+            model.delete().await.extract(&req)?;
+        }
 
         let res = Response::default().context(&req);
         Ok(res.into())
     }
 
     async fn update(mut req: Self::Request) -> Self::Result {
-        let id = req.parse_param::<K>("id")?;
-        let mut body = req.parse_body().await?;
+        let id = parse_model_id::<Self, K>(&req)?;
+        let patch_format = req.get_header("content-type").and_then(|content_type| {
+            let essence = content_type.split(';').next()?.trim();
+            if essence.eq_ignore_ascii_case("application/merge-patch+json") {
+                Some("merge-patch")
+            } else if essence.eq_ignore_ascii_case("application/json-patch+json") {
+                Some("json-patch")
+            } else {
+                None
+            }
+        });
+        let expected_version = parse_if_match_version::<Self>(&req);
+        if expected_version.is_none() && req.get_header("if-match").is_some() {
+            let model: Map = Self::find_by_id(&id).await.extract(&req)?;
+            check_if_match::<Self>(&req, &model)?;
+        }
 
         let extension = req.get_data::<<Self as ModelHooks>::Extension>();
-        let (validation, model) = Self::update_by_id(&id, &mut body, extension)
-            .await
-            .extract(&req)?;
+        let (validation, model) = if let Some(patch_format) = patch_format {
+            let mut patch = req.parse_body::<JsonValue>().await?;
+            match (patch_format, &patch) {
+                ("merge-patch", JsonValue::Object(_)) | ("json-patch", JsonValue::Array(_)) => (),
+                ("merge-patch", _) => {
+                    let err = warn!("the JSON Merge Patch should be an object");
+                    return Err(Rejection::from_validation_entry("body", err)
+                        .context(&req)
+                        .into());
+                }
+                _ => {
+                    let err = warn!("the JSON Patch should be an array of operations");
+                    return Err(Rejection::from_validation_entry("body", err)
+                        .context(&req)
+                        .into());
+                }
+            }
+            Self::decode_patch_public_ids(&mut patch).extract(&req)?;
+            if let Some(version) = expected_version {
+                // The update is conditioned on the version of the patched data.
+                match &mut patch {
+                    JsonValue::Object(patch) => {
+                        patch.upsert("version", version);
+                    }
+                    JsonValue::Array(operations) => {
+                        let mut operation = Map::new();
+                        operation.upsert("op", "replace");
+                        operation.upsert("path", "/version");
+                        operation.upsert("value", version);
+                        operations.push(operation.into());
+                    }
+                    _ => (),
+                }
+            }
+            Self::patch_by_id(&id, &patch, extension)
+                .await
+                .extract(&req)?
+        } else {
+            let mut body = req.parse_body().await?;
+            Self::decode_public_ids(&mut body).extract(&req)?;
+            if let Some(version) = expected_version {
+                body.upsert("version", version);
+            }
+            Self::update_by_id(&id, &mut body, extension)
+                .await
+                .extract(&req)?
+        };
         let mut res = Response::from(validation).context(&req);
         if res.is_success() {
-            let model_filters = model.next_version_filters();
+            let mut model_filters = model.next_version_filters();
+            if let Some(etag) = derive_entity_tag::<Self>(&model_filters) {
+                res.insert_header("etag", etag);
+            }
+            Self::encode_public_ids(&mut model_filters).extract(&req)?;
             res.set_json_data(Self::data_item(model_filters));
         }
         Ok(res.into())
     }
 
     async fn view(req: Self::Request) -> Self::Result {
-        let id = req.parse_param::<K>("id")?;
+        let id = parse_model_id::<Self, K>(&req)?;
+        let mut validation = Validation::new();
+        let fields = req
+            .get_query("fields")
+            .map(Self::parse_sparse_fields)
+            .transpose()
+            .unwrap_or_else(|err| {
+                validation.record_fail("fields", err);
+                None
+            });
+        let included_columns = req
+            .get_query("include")
+            .map(Self::parse_included_columns)
+            .transpose()
+            .unwrap_or_else(|err| {
+                validation.record_fail("include", err);
+                None
+            });
+        if !validation.is_success() {
+            return Err(Rejection::bad_request(validation).context(&req).into());
+        }
+
         let extension = req.get_data::<<Self as ModelHooks>::Extension>();
-        let mut model = if req.get_query("fetch") == Some("false") {
+        let mut model = if let Some(columns) = included_columns.as_deref() {
+            let mut model: Map = Self::find_by_id(&id).await.extract(&req)?;
+            Self::after_decode(&mut model).await.extract(&req)?;
+            Self::translate_model(&mut model);
+            Self::include_references_one(&mut model, columns)
+                .await
+                .extract(&req)?;
+            model
+        } else if req.get_query("fetch") == Some("false") {
             Self::find_by_id(&id).await.extract(&req)?
         } else {
             Self::fetch_by_id(&id).await.extract(&req)?
         };
+        let etag = derive_entity_tag::<Self>(&model);
+        Self::compute_fields(&mut model);
+        if let Some(fields) = fields {
+            let columns = included_columns.unwrap_or_default();
+            model.retain(|key, _| {
+                fields.contains(&key.as_str())
+                    || key
+                        .strip_suffix("_populated")
+                        .is_some_and(|col| fields.contains(&col) || columns.contains(&col))
+            });
+        }
         Self::before_respond(&mut model, extension.as_ref())
             .await
             .extract(&req)?;
+        Self::encode_public_ids(&mut model).extract(&req)?;
 
         let mut res = Response::default().context(&req);
+        if let Some(etag) = etag {
+            res.insert_header("etag", etag);
+        }
         res.set_json_data(Self::data_item(model));
         Ok(res.into())
     }
@@ -166,40 +362,101 @@ where
             Some("snapshot") => Self::default_snapshot_query(),
             _ => Self::default_list_query(),
         };
+        query.allow_filters(Self::fields(), Self::filterable_fields());
         let mut res = req.query_validation(&mut query)?;
+        Self::decode_public_id_filters(&mut query).extract(&req)?;
+
+        let mut validation = Validation::new();
+        if let Some(Err(err)) = req
+            .get_query("fields")
+            .or_else(|| req.get_query("columns"))
+            .map(Self::parse_sparse_fields)
+        {
+            validation.record_fail("fields", err);
+        }
+        let included_columns = req
+            .get_query("include")
+            .map(Self::parse_included_columns)
+            .transpose()
+            .unwrap_or_else(|err| {
+                validation.record_fail("include", err);
+                None
+            });
+        if !validation.is_success() {
+            return Err(Rejection::bad_request(validation).context(&req).into());
+        }
+        if let Some(columns) = included_columns.as_deref() {
+            if !query.fields().is_empty() {
+                for &col in columns {
+                    query.add_field(col);
+                }
+            }
+        }
+
         let extension = req.get_data::<<Self as ModelHooks>::Extension>();
         Self::before_list(&mut query, extension.as_ref())
             .await
             .extract(&req)?;
 
+        // The page size is capped by the max number of rows,
+        // and the extra row is fetched to detect whether the results are truncated.
+        let max_page_size = Self::max_rows(&query).min(*MAX_PAGE_SIZE);
+        let capped = query.cap_limit(max_page_size);
+        if capped {
+            query.set_max_rows(query.limit());
+        }
+
+        let mut truncated = false;
         let models = if query.populate_enabled() {
             let mut models = Self::fetch(&query).await.extract(&req)?;
+            if capped && models.len() > max_page_size {
+                models.truncate(max_page_size);
+                truncated = true;
+            }
             for model in models.iter_mut() {
+                Self::compute_fields(model);
                 Self::before_respond(model, extension.as_ref())
                     .await
                     .extract(&req)?;
+                Self::encode_public_ids(model).extract(&req)?;
             }
             models
         } else {
             let mut models = Self::find(&query).await.extract(&req)?;
+            if capped && models.len() > max_page_size {
+                models.truncate(max_page_size);
+                truncated = true;
+            }
             let translate_enabled = query.translate_enabled();
             for model in models.iter_mut() {
                 Self::after_decode(model).await.extract(&req)?;
                 translate_enabled.then(|| Self::translate_model(model));
+            }
+            if let Some(columns) = included_columns.as_deref() {
+                Self::include_references(&query, &mut models, columns)
+                    .await
+                    .extract(&req)?;
+            }
+            for model in models.iter_mut() {
+                Self::compute_fields(model);
                 Self::before_respond(model, extension.as_ref())
                     .await
                     .extract(&req)?;
+                Self::encode_public_ids(model).extract(&req)?;
             }
             models
         };
 
         let mut data = Self::data_items(models);
+        if truncated {
+            let warning = format!("the results are truncated to the max page size {max_page_size}");
+            data.upsert("warning", warning);
+        }
         if let Some(page_size) = req.get_query("page_size").and_then(|s| s.parse().ok()) {
             if req.get_query("total_rows").is_none() {
                 let total_rows = Self::count(&query).await.extract(&req)?;
-                let page_count = total_rows.div_ceil(page_size);
-                data.upsert("total_rows", total_rows);
-                data.upsert("page_count", page_count);
+                let page_count = total_rows.div_ceil(max_page_size.min(page_size));
+                data.append(&mut format_pagination(total_rows, page_count));
             }
         }
         res.set_json_data(data);
@@ -211,6 +468,7 @@ where
         let mut res = req.query_validation(&mut query)?;
         let mut body = req.parse_body().await?;
         query.append_filters(&mut body);
+        Self::decode_public_id_filters(&mut query).extract(&req)?;
 
         let extension = req.get_data::<<Self as ModelHooks>::Extension>();
         Self::before_list(&mut query, extension.as_ref())
@@ -219,9 +477,11 @@ where
 
         let mut models = Self::fetch(&query).await.extract(&req)?;
         for model in models.iter_mut() {
+            Self::compute_fields(model);
             Self::before_respond(model, extension.as_ref())
                 .await
                 .extract(&req)?;
+            Self::encode_public_ids(model).extract(&req)?;
         }
 
         let mut data = Self::data_items(models);
@@ -229,8 +489,7 @@ where
             if req.get_query("total_rows").is_none() {
                 let total_rows = Self::count(&query).await.extract(&req)?;
                 let page_count = total_rows.div_ceil(page_size);
-                data.upsert("total_rows", total_rows);
-                data.upsert("page_count", page_count);
+                data.append(&mut format_pagination(total_rows, page_count));
             }
         }
         res.set_json_data(data);
@@ -238,7 +497,7 @@ where
     }
 
     async fn soft_delete(req: Self::Request) -> Self::Result {
-        let id = req.parse_param::<K>("id")?;
+        let id = parse_model_id::<Self, K>(&req)?;
         Self::soft_delete_by_id(&id).await.extract(&req)?;
 
         let res = Response::default().context(&req);
@@ -246,7 +505,7 @@ where
     }
 
     async fn lock(req: Self::Request) -> Self::Result {
-        let id = req.parse_param::<K>("id")?;
+        let id = parse_model_id::<Self, K>(&req)?;
         Self::lock_by_id(&id).await.extract(&req)?;
 
         let res = Response::default().context(&req);
@@ -254,7 +513,7 @@ where
     }
 
     async fn archive(req: Self::Request) -> Self::Result {
-        let id = req.parse_param::<K>("id")?;
+        let id = parse_model_id::<Self, K>(&req)?;
         Self::archive_by_id(&id).await.extract(&req)?;
 
         let res = Response::default().context(&req);
@@ -270,6 +529,11 @@ where
             Self::before_extract()
                 .await
                 .map_err(|err| Rejection::from_error(err).context(&req))?;
+
+            // The computed fields in the exported data are ignored.
+            map.retain(|key, _| !Self::COMPUTED_FIELDS.contains(&key.as_str()));
+            Self::decode_public_ids(&mut map).extract(&req)?;
+            Self::sanitize(&mut map);
             Self::before_validation(&mut map, extension.as_ref())
                 .await
                 .extract(&req)?;
@@ -319,6 +583,7 @@ where
             let primary_key_values = Map::from_entry("$in", data);
             Query::from_entry(Self::PRIMARY_KEY_NAME, primary_key_values)
         };
+        Self::decode_public_id_filters(&mut query).extract(&req)?;
         let extension = req.get_data::<<Self as ModelHooks>::Extension>();
         Self::before_list(&mut query, extension.as_ref())
             .await
@@ -332,13 +597,32 @@ where
     }
 
     async fn batch_update(mut req: Self::Request) -> Self::Result {
-        let data = req.parse_body::<Vec<Map>>().await?;
+        let mut data = req.parse_body::<Vec<Map>>().await?;
 
-        // Should use `Self::transaction` when the `Send` bound is resolved
+        // The status transitions are checked for every row before any update.
         let primary_key_name = Self::PRIMARY_KEY_NAME;
+        for map in data.iter_mut() {
+            Self::decode_public_ids(map).extract(&req)?;
+            if let Some(status) = map.get_str("status") {
+                let id = map
+                    .parse_string(primary_key_name)
+                    .and_then(|s| s.parse::<K>().ok());
+                if let Some(id) = id {
+                    let mut model = Self::try_get_model(&id).await.extract(&req)?;
+                    if !model.has_status(status) {
+                        model.check_status_transition(status).extract(&req)?;
+                        model.before_transition(status).await.extract(&req)?;
+                    }
+                }
+            }
+        }
+
+        // Should use `Self::transaction` when the `Send` bound is resolved
         let mut rows_affected = 0;
         for mut map in data.into_iter() {
             if let Some(id) = map.remove(primary_key_name) {
+                Self::sanitize(&mut map);
+
                 let query = Query::from_entry(primary_key_name, id);
                 let mut mutation = Mutation::new(map);
                 let ctx = Self::update_one(&query, &mut mutation)
@@ -353,16 +637,200 @@ where
         Ok(res.into())
     }
 
+    async fn batch_mutate(mut req: Self::Request) -> Self::Result {
+        let operations = req.parse_body::<Vec<Map>>().await?;
+        let extension = req.get_data::<<Self as ModelHooks>::Extension>();
+        let primary_key_name = Self::PRIMARY_KEY_NAME;
+        let mut ctxs = Vec::with_capacity(operations.len());
+        let mut entries = Vec::with_capacity(operations.len());
+        let mut hooks = Vec::with_capacity(operations.len());
+        let mut validations = Vec::new();
+        for (index, mut operation) in operations.into_iter().enumerate() {
+            Self::decode_public_ids(&mut operation).extract(&req)?;
+            let op = operation.get_str("op").unwrap_or_default().to_owned();
+            let id = operation
+                .parse_string(primary_key_name)
+                .and_then(|s| s.parse::<K>().ok());
+            let mut data = operation
+                .remove("data")
+                .and_then(|v| v.into_map_opt())
+                .unwrap_or_default();
+            Self::decode_public_ids(&mut data).extract(&req)?;
+
+            let mut validation = Validation::new();
+            match (op.as_str(), id) {
+                ("insert", _) => {
+                    Self::before_extract()
+                        .await
+                        .map_err(|err| Rejection::from_error(err).context(&req))?;
+                    Self::sanitize(&mut data);
+                    Self::before_validation(&mut data, extension.as_ref())
+                        .await
+                        .extract(&req)?;
+
+                    let mut model = Self::new();
+                    validation = model.read_map(&data);
+                    if validation.is_success() {
+                        model
+                            .before_insert_check(extension.as_ref())
+                            .await
+                            .extract(&req)?;
+                        validation = model.check_constraints().await.extract(&req)?;
+                    }
+                    if validation.is_success() {
+                        model.after_validation(&mut data).await.extract(&req)?;
+                        let model_data = model.before_insert().await.extract(&req)?;
+                        ctxs.push(model.prepare_insert().await.extract(&req)?);
+                        hooks.push((None, model_data));
+                    }
+                }
+                ("update", Some(id)) => match Self::try_get_model(&id).await {
+                    Ok(mut model) => {
+                        if model.is_archived() {
+                            validation
+                                .record(primary_key_name, "archived model can not be modified");
+                        } else if let Some(status) = data
+                            .get_str("status")
+                            .filter(|&status| !model.has_status(status))
+                        {
+                            match model.check_status_transition(status) {
+                                Ok(()) => model.before_transition(status).await.extract(&req)?,
+                                Err(err) => validation.record_fail("status", err),
+                            }
+                        }
+                        if validation.is_success() {
+                            Self::sanitize(&mut data);
+                            Self::before_validation(&mut data, extension.as_ref())
+                                .await
+                                .extract(&req)?;
+                            validation = model.read_map(&data);
+                        }
+                        if validation.is_success() {
+                            validation = model.check_constraints().await.extract(&req)?;
+                        }
+                        if validation.is_success() {
+                            model.after_validation(&mut data).await.extract(&req)?;
+
+                            let query = model.current_version_query();
+                            let mut mutation = model.next_version_mutation(&mut data);
+                            let model_data = model.before_update().await.extract(&req)?;
+                            let ctx = Self::prepare_update_one(&query, &mut mutation)
+                                .await
+                                .extract(&req)?;
+                            ctxs.push(ctx);
+                            hooks.push((None, model_data));
+                        }
+                    }
+                    Err(err) => validation.record_fail(primary_key_name, err),
+                },
+                ("delete", Some(id)) => match Self::try_get_model(&id).await {
+                    Ok(mut model) => {
+                        let model_data = model.before_delete().await.extract(&req)?;
+                        let query = Query::from_entry(primary_key_name, id.to_string());
+                        ctxs.push(Self::prepare_delete_one(&query).await.extract(&req)?);
+                        hooks.push((Some(model), model_data));
+                    }
+                    Err(err) => validation.record_fail(primary_key_name, err),
+                },
+                ("update" | "delete", None) => {
+                    validation.record(primary_key_name, "should be a valid primary key");
+                }
+                _ => {
+                    validation.record("op", "should be one of `insert`, `update` or `delete`");
+                }
+            }
+            if validation.is_success() {
+                let mut entry = Map::from_entry("index", index);
+                entry.upsert("op", op);
+                entries.push(entry);
+            } else {
+                let mut map = validation.into_map();
+                map.upsert("index", index);
+                validations.push(map);
+            }
+        }
+        if !validations.is_empty() {
+            let mut res = Response::bad_request().context(&req);
+            res.set_json_data(validations);
+            return Ok(res.into());
+        }
+
+        let rows_affected = <Self as Transaction<K, _>>::transactional_batch(&mut ctxs)
+            .await
+            .extract(&req)?;
+
+        // Runs the hooks after the transaction has been committed.
+        for ((entry, ctx), (model, model_data)) in entries.iter_mut().zip(ctxs.iter()).zip(hooks) {
+            if ctx.is_cancelled() {
+                continue;
+            }
+            match (entry.get_str("op"), model) {
+                (Some("insert"), _) => Self::after_insert(ctx, model_data).await,
+                (Some("update"), _) => Self::after_update(ctx, model_data).await,
+                (_, Some(model)) => model.after_delete(ctx, model_data).await,
+                _ => Ok(()),
+            }
+            .extract(&req)?;
+            entry.upsert("rows_affected", ctx.rows_affected());
+            if let Some(last_insert_id) = ctx.last_insert_id() {
+                entry.upsert("last_insert_id", last_insert_id);
+            }
+        }
+
+        let mut data = Map::from_entry("rows_affected", rows_affected);
+        data.upsert("results", entries);
+        let mut res = Response::default().context(&req);
+        res.set_json_data(data);
+        Ok(res.into())
+    }
+
     async fn import(mut req: Self::Request) -> Self::Result {
         let mut query = Query::new(Map::new());
         let mut res = req.query_validation(&mut query)?;
 
-        let data = req.parse_body::<Vec<Map>>().await?;
-        let extension = req.get_data::<<Self as ModelHooks>::Extension>();
         let validate_only = query.validate_only();
         let no_check = query.no_check();
         let limit = query.limit();
         let query_filters = query.filters();
+        let data_type = req.data_type().unwrap_or("json");
+        let (is_csv, is_xlsx) = (data_type == "csv", data_type == "xlsx");
+        let rows = if is_csv || is_xlsx {
+            let mut reader = SheetReader::new(Self::columns());
+            if let Some(mapping) = query_filters.get_object("mapping") {
+                reader.append_column_mapping(mapping);
+            }
+            if let Some(sheet_name) = query_filters.get_str("sheet") {
+                reader.set_sheet_name(sheet_name);
+            }
+
+            let bytes = req
+                .read_body_bytes()
+                .await
+                .map_err(|err| Rejection::from_validation_entry("body", err).context(&req))?;
+            let sheet_rows = if is_csv {
+                reader
+                    .read_csv(bytes.as_slice())
+                    .collect::<Result<Vec<_>, _>>()
+            } else {
+                read_xlsx(&reader, bytes)
+            };
+            sheet_rows
+                .map_err(|err| Rejection::from_validation_entry("body", err).context(&req))?
+                .into_iter()
+                .map(|row| {
+                    let row_number = row.row_number();
+                    let (map, validation) = row.into_parts();
+                    (map, validation, Some(row_number))
+                })
+                .collect::<Vec<_>>()
+        } else {
+            req.parse_body::<Vec<Map>>()
+                .await?
+                .into_iter()
+                .map(|map| (map, Validation::new(), None))
+                .collect::<Vec<_>>()
+        };
+        let extension = req.get_data::<<Self as ModelHooks>::Extension>();
         let (enable_upsert, batch_size) = if query_filters.get_str("upsert") == Some("true") {
             (true, 1)
         } else if validate_only {
@@ -379,7 +847,7 @@ where
         let mut rows_affected = 0;
         let mut validations = Vec::new();
         let mut batch_models = Vec::with_capacity(batch_size);
-        for (index, mut map) in data.into_iter().enumerate() {
+        for (index, (mut map, cell_validation, row_number)) in rows.into_iter().enumerate() {
             if limit > 0 && rows_affected >= limit {
                 break;
             }
@@ -391,12 +859,17 @@ where
             Self::before_extract()
                 .await
                 .map_err(|err| Rejection::from_error(err).context(&req))?;
+            Self::sanitize(&mut map);
             Self::before_validation(&mut map, extension.as_ref())
                 .await
                 .extract(&req)?;
 
             let mut model = Self::new();
-            let mut validation = model.read_map(&map);
+            let mut validation = if cell_validation.is_success() {
+                model.read_map(&map)
+            } else {
+                cell_validation
+            };
             if validation.is_success() && !no_check {
                 model
                     .before_insert_check(extension.as_ref())
@@ -425,6 +898,9 @@ where
             } else {
                 let mut map = validation.into_map();
                 map.upsert("index", index);
+                if let Some(row_number) = row_number {
+                    map.upsert("row_number", row_number);
+                }
 
                 if validate_only {
                     validations.push(map);
@@ -450,7 +926,10 @@ where
 
     async fn export(req: Self::Request) -> Self::Result {
         let mut query = Self::default_query();
+        query.allow_filters(Self::fields(), Self::filterable_fields());
         let mut res = req.query_validation(&mut query)?;
+        Self::decode_public_id_filters(&mut query).extract(&req)?;
+
         let extension = req.get_data::<<Self as ModelHooks>::Extension>();
         Self::before_list(&mut query, extension.as_ref())
             .await
@@ -461,6 +940,7 @@ where
         for model in models.iter_mut() {
             Self::after_decode(model).await.extract(&req)?;
             translate_enabled.then(|| Self::translate_model(model));
+            Self::compute_fields(model);
             Self::before_respond(model, extension.as_ref())
                 .await
                 .extract(&req)?;
@@ -475,16 +955,52 @@ where
         Ok(res.into())
     }
 
+    async fn stream(req: Self::Request) -> Self::Result {
+        let mut query = Self::default_list_query();
+        query.set_limit(0);
+        query.allow_filters(Self::fields(), Self::filterable_fields());
+        let res = req.query_validation(&mut query)?;
+        Self::decode_public_id_filters(&mut query).extract(&req)?;
+
+        let extension = req.get_data::<<Self as ModelHooks>::Extension>();
+        Self::before_list(&mut query, extension.as_ref())
+            .await
+            .extract(&req)?;
+
+        // The hooks are run for each model as it comes off the database cursor.
+        let translate_enabled = query.translate_enabled();
+        let models = Self::find_stream::<Map>(&query).await.extract(&req)?;
+        let values = models.then(move |result| {
+            let extension = extension.clone();
+            async move {
+                let mut model = result?;
+                Self::after_decode(&mut model).await?;
+                if translate_enabled {
+                    Self::translate_model(&mut model);
+                }
+                Self::compute_fields(&mut model);
+                Self::before_respond(&mut model, extension.as_ref()).await?;
+                Self::encode_public_ids(&mut model)?;
+                Ok(JsonValue::from(model))
+            }
+        });
+        Ok(res.stream_jsonlines(values, Duration::from_secs(15)).into())
+    }
+
     async fn tree(req: Self::Request) -> Self::Result {
         let mut query = Self::default_list_query();
+        query.allow_filters(Self::fields(), Self::filterable_fields());
         let mut res = req.query_validation(&mut query)?;
+        Self::decode_public_id_filters(&mut query).extract(&req)?;
+
         let extension = req.get_data::<<Self as ModelHooks>::Extension>();
         Self::before_list(&mut query, extension.as_ref())
             .await
             .extract(&req)?;
 
-        let parent_id = req.get_query("parent_id").unwrap_or("null");
-        query.add_filter("parent_id", parent_id);
+        let parent_field = Self::TREE_PARENT_FIELD.unwrap_or("parent_id");
+        let parent_id = req.get_query(parent_field).unwrap_or("null");
+        query.add_filter(parent_field, parent_id);
 
         let mut models = if query.populate_enabled() {
             Self::fetch(&query).await.extract(&req)?
@@ -504,29 +1020,40 @@ where
             .filter_map(|model| model.get(primary_key_name).cloned())
             .collect::<Vec<_>>();
         let mut query = Self::default_snapshot_query();
-        query.add_filter("parent_id", Map::from_entry("$in", values));
+        query.add_field(parent_field);
+        if Self::TREE_PARENT_FIELD.is_some() {
+            // Fetches all the descendants by the materialized paths.
+            let path_field = Self::TREE_PATH_FIELD;
+            let filters = models
+                .iter()
+                .filter_map(|model| model.get_str(path_field).filter(|s| !s.is_empty()))
+                .map(|path| {
+                    let filter = Map::from_entry("$like", format!("{path}_%"));
+                    Map::from_entry(path_field, filter)
+                })
+                .collect::<Vec<_>>();
+            query.add_field(path_field);
+            query.add_filter("$or", filters);
+            query.order_asc(path_field);
+        } else {
+            query.add_filter(parent_field, Map::from_entry("$in", values));
+            query.order_desc(parent_field);
+        }
         query.add_filter("status", Map::from_entry("$ne", "Deleted"));
-        query.order_desc("parent_id");
         query.order_desc("created_at");
         query.disable_limit();
 
         let mut children = Self::find::<Map>(&query).await.extract(&req)?;
         let total_rows = children.len();
+        let recursive = Self::TREE_PARENT_FIELD.is_some();
         for model in models.iter_mut() {
-            let model_id = model.get(primary_key_name);
-
-            // Should use `extract_if` when it is stabilized.
-            let mut model_children = Vec::new();
-            let mut index = 0;
-            while index < children.len() {
-                if children[index].get("parent_id") == model_id {
-                    let child = children.remove(index);
-                    model_children.push(child);
-                } else {
-                    index += 1;
-                }
-            }
-            model.upsert("children", model_children);
+            attach_children(
+                model,
+                &mut children,
+                primary_key_name,
+                parent_field,
+                recursive,
+            );
         }
 
         let mut data = Self::data_items(models);
@@ -535,6 +1062,49 @@ where
         Ok(res.into())
     }
 
+    async fn reorder(mut req: Self::Request) -> Self::Result {
+        let data = req.parse_body::<JsonValue>().await?;
+        let parse_id = |key: &str, value: Option<&JsonValue>| {
+            value
+                .and_then(|v| v.parse_string())
+                .ok_or_else(|| warn!("the `{}` should be specified", key))
+                .and_then(|s| s.parse::<K>().map_err(Error::from))
+                .map_err(|err| Rejection::from_validation_entry(key.to_owned(), err).context(&req))
+        };
+        let mut res = Response::default().context(&req);
+        match data {
+            JsonValue::Array(values) => {
+                let mut ids = Vec::with_capacity(values.len());
+                for value in values.iter() {
+                    ids.push(parse_id("ids", Some(value))?);
+                }
+
+                let rows_affected = <Self as PositionQuery<K>>::reorder(&ids)
+                    .await
+                    .extract(&req)?;
+                res.set_json_data(Map::from_entry("rows_affected", rows_affected));
+            }
+            JsonValue::Object(map) => {
+                let id = parse_id("id", map.get("id"))?;
+                let position = if map.contains_key("before") {
+                    let target_id = parse_id("before", map.get("before"))?;
+                    Self::move_before(&id, &target_id).await.extract(&req)?
+                } else {
+                    let target_id = parse_id("after", map.get("after"))?;
+                    Self::move_after(&id, &target_id).await.extract(&req)?
+                };
+                res.set_json_data(Map::from_entry("position", position));
+            }
+            _ => {
+                let err = warn!("the body should be an array of IDs or an object");
+                return Err(Rejection::from_validation_entry("body", err)
+                    .context(&req)
+                    .into());
+            }
+        }
+        Ok(res.into())
+    }
+
     async fn schema(req: Self::Request) -> Self::Result {
         let schema = serde_json::to_value(Self::schema()).extract(&req)?;
         let mut res = Response::default().context(&req);
@@ -612,4 +1182,452 @@ where
         res.set_json_data(data);
         Ok(res.into())
     }
+
+    async fn console(mut req: Self::Request) -> Self::Result {
+        if req.request_method() != "POST" {
+            let definitions = Self::columns()
+                .iter()
+                .map(|col| {
+                    let mut definition = col.definition();
+                    definition.upsert("name", col.name());
+                    definition
+                })
+                .collect::<Vec<_>>();
+            let content = console::render_console(Self::MODEL_NAME, &definitions);
+            let mut res = Response::default().context(&req);
+            res.set_html_response(content);
+            return Ok(res.into());
+        }
+
+        let operations = match req.parse_body::<JsonValue>().await? {
+            JsonValue::Array(values) => values,
+            value => vec![value],
+        };
+        let extension = req.get_data::<<Self as ModelHooks>::Extension>();
+        let mut ctxs = Vec::with_capacity(operations.len());
+        let mut entries = Vec::with_capacity(operations.len());
+        let mut validations = Vec::new();
+        for (index, operation) in operations.into_iter().enumerate() {
+            let mut operation = operation.into_map_opt().unwrap_or_default();
+            let action = operation.get_str("action").unwrap_or_default().to_owned();
+            let filters = operation
+                .remove("query")
+                .and_then(|v| v.into_map_opt())
+                .unwrap_or_default();
+            let mut data = operation
+                .remove("data")
+                .and_then(|v| v.into_map_opt())
+                .unwrap_or_default();
+            let mut validation = Validation::new();
+            match action.as_str() {
+                "find" => {
+                    let mut query = Self::default_list_query();
+                    validation = query.read_map(&filters);
+                    if validation.is_success() {
+                        Self::before_list(&mut query, extension.as_ref())
+                            .await
+                            .extract(&req)?;
+
+                        let sql = query.to_sql::<Self>();
+                        let mut ctx = Self::before_scan(&sql).await.extract(&req)?;
+                        ctx.set_query(sql);
+                        ctxs.push(ctx);
+                    }
+                }
+                "insert" => {
+                    Self::sanitize(&mut data);
+                    Self::before_validation(&mut data, extension.as_ref())
+                        .await
+                        .extract(&req)?;
+
+                    let mut model = Self::new();
+                    validation = model.read_map(&data);
+                    if validation.is_success() {
+                        model
+                            .before_insert_check(extension.as_ref())
+                            .await
+                            .extract(&req)?;
+
+                        // The `before_insert` hook is skipped since it may have side effects
+                        // which can not be rolled back by the sandbox transaction.
+                        ctxs.push(model.prepare_insert().await.extract(&req)?);
+                    }
+                }
+                "update" => {
+                    let mut query = Self::default_query();
+                    let mut mutation = Self::default_mutation();
+                    validation = query.read_map(&filters);
+                    if validation.is_success() {
+                        Self::sanitize(&mut data);
+                        validation = mutation.read_map(&data);
+                    }
+                    if validation.is_success() {
+                        let ctx = Self::prepare_update_many(&query, &mut mutation)
+                            .await
+                            .extract(&req)?;
+                        ctxs.push(ctx);
+                    }
+                }
+                "delete" => {
+                    let mut query = Self::default_query();
+                    validation = query.read_map(&filters);
+                    if validation.is_success() {
+                        ctxs.push(Self::prepare_delete_many(&query).await.extract(&req)?);
+                    }
+                }
+                _ => {
+                    validation.record(
+                        "action",
+                        "should be one of `find`, `insert`, `update` or `delete`",
+                    );
+                }
+            }
+            if validation.is_success() {
+                let mut entry = Map::from_entry("index", index);
+                entry.upsert("action", action);
+                entries.push(entry);
+            } else {
+                let mut map = validation.into_map();
+                map.upsert("index", index);
+                validations.push(map);
+            }
+        }
+        if !validations.is_empty() {
+            let mut res = Response::bad_request().context(&req);
+            res.set_json_data(validations);
+            return Ok(res.into());
+        }
+
+        let results = <Self as Transaction<K, _>>::sandbox_batch(&mut ctxs)
+            .await
+            .extract(&req)?;
+        for ((entry, ctx), mut rows) in entries.iter_mut().zip(ctxs.iter()).zip(results) {
+            entry.upsert("query", ctx.query());
+            entry.upsert("rows_affected", ctx.rows_affected());
+            if let Some(last_insert_id) = ctx.last_insert_id() {
+                entry.upsert("last_insert_id", last_insert_id);
+            }
+            if entry.get_str("action") == Some("find") {
+                for row in rows.iter_mut() {
+                    Self::after_decode(row).await.extract(&req)?;
+                }
+                entry.upsert("rows", rows);
+            }
+        }
+
+        let mut data = Map::from_entry("rolled_back", true);
+        data.upsert("results", entries);
+        let mut res = Response::default().context(&req);
+        res.set_json_data(data);
+        Ok(res.into())
+    }
+
+    async fn list_related<R, J>(req: Self::Request) -> Self::Result
+    where
+        R: ModelAccessor<J>,
+        J: Default + std::fmt::Display + PartialEq,
+    {
+        let id = parse_model_id::<Self, K>(&req)?;
+        let mut query = R::default_list_query();
+        let mut res = req.query_validation(&mut query)?;
+        R::decode_public_id_filters(&mut query).extract(&req)?;
+
+        let extension = req.get_data::<<R as ModelHooks>::Extension>();
+        R::before_list(&mut query, extension.as_ref())
+            .await
+            .extract(&req)?;
+
+        let field = req.get_query("field");
+        if let Some(col) = Self::get_reference_column::<R>(field) {
+            let mut model = Self::find_by_id::<Map>(&id).await.extract(&req)?;
+            let values = match model.remove(col.name()) {
+                Some(JsonValue::Array(values)) => values,
+                Some(value) => vec![value],
+                None => Vec::new(),
+            };
+            query.add_filter(R::PRIMARY_KEY_NAME, Map::from_entry("$in", values));
+        } else if let Some(col) = R::get_reference_column::<Self>(field) {
+            query.add_filter(col.name(), id.to_string());
+        } else {
+            let err = warn!(
+                "404 Not Found: there is no relation between `{}` and `{}`",
+                Self::model_name(),
+                R::model_name()
+            );
+            return Err(Rejection::not_found(err).context(&req).into());
+        }
+
+        let mut models = if query.populate_enabled() {
+            R::fetch(&query).await.extract(&req)?
+        } else {
+            let mut models = R::find(&query).await.extract(&req)?;
+            let translate_enabled = query.translate_enabled();
+            for model in models.iter_mut() {
+                R::after_decode(model).await.extract(&req)?;
+                translate_enabled.then(|| R::translate_model(model));
+            }
+            models
+        };
+        for model in models.iter_mut() {
+            R::compute_fields(model);
+            R::before_respond(model, extension.as_ref())
+                .await
+                .extract(&req)?;
+        }
+
+        let mut data = R::data_items(models);
+        if let Some(page_size) = req.get_query("page_size").and_then(|s| s.parse().ok()) {
+            if req.get_query("total_rows").is_none() {
+                let total_rows = R::count(&query).await.extract(&req)?;
+                let page_count = total_rows.div_ceil(page_size);
+                data.append(&mut format_pagination(total_rows, page_count));
+            }
+        }
+        res.set_json_data(data);
+        Ok(res.into())
+    }
+
+    async fn add_related<R, J>(mut req: Self::Request) -> Self::Result
+    where
+        R: ModelAccessor<J>,
+        J: Default + std::fmt::Display + PartialEq,
+    {
+        let id = parse_model_id::<Self, K>(&req)?;
+        let col = Self::get_reference_column::<R>(req.get_query("field"))
+            .filter(|col| col.is_array_type())
+            .ok_or_else(|| {
+                warn!(
+                    "404 Not Found: the model `{}` has no references to `{}`",
+                    Self::model_name(),
+                    R::model_name()
+                )
+            })
+            .extract(&req)?;
+        let mut values = req.parse_body::<Vec<JsonValue>>().await?;
+        dedup_related_values(&mut values);
+
+        let num_values = values.len();
+        let related_values = R::filter(values).await.extract(&req)?;
+        if related_values.len() != num_values {
+            let err = warn!("404 Not Found: some related models do not exist");
+            return Err(Rejection::not_found(err).context(&req).into());
+        }
+
+        let field = col.name();
+        let mut model = Self::find_by_id::<Map>(&id).await.extract(&req)?;
+        let mut values = match model.remove(field) {
+            Some(JsonValue::Array(values)) => values,
+            _ => Vec::new(),
+        };
+        values.extend(related_values);
+        dedup_related_values(&mut values);
+
+        let mut data = Map::from_entry(field, values);
+        let extension = req.get_data::<<Self as ModelHooks>::Extension>();
+        let (validation, model) = Self::update_by_id(&id, &mut data, extension)
+            .await
+            .extract(&req)?;
+        let mut res = Response::from(validation).context(&req);
+        if res.is_success() {
+            let mut model_filters = model.next_version_filters();
+            Self::encode_public_ids(&mut model_filters).extract(&req)?;
+            res.set_json_data(Self::data_item(model_filters));
+        }
+        Ok(res.into())
+    }
+
+    async fn remove_related<R, J>(mut req: Self::Request) -> Self::Result
+    where
+        R: ModelAccessor<J>,
+        J: Default + std::fmt::Display + PartialEq,
+    {
+        let id = parse_model_id::<Self, K>(&req)?;
+        let col = Self::get_reference_column::<R>(req.get_query("field"))
+            .filter(|col| col.is_array_type())
+            .ok_or_else(|| {
+                warn!(
+                    "404 Not Found: the model `{}` has no references to `{}`",
+                    Self::model_name(),
+                    R::model_name()
+                )
+            })
+            .extract(&req)?;
+        let related_values = req.parse_body::<Vec<JsonValue>>().await?;
+
+        let field = col.name();
+        let mut model = Self::find_by_id::<Map>(&id).await.extract(&req)?;
+        let mut values = match model.remove(field) {
+            Some(JsonValue::Array(values)) => values,
+            _ => Vec::new(),
+        };
+        let related_values = related_values
+            .iter()
+            .filter_map(|value| value.parse_string())
+            .collect::<Vec<_>>();
+        values.retain(|value| {
+            value
+                .parse_string()
+                .is_some_and(|value| !related_values.contains(&value))
+        });
+
+        let mut data = Map::from_entry(field, values);
+        let extension = req.get_data::<<Self as ModelHooks>::Extension>();
+        let (validation, model) = Self::update_by_id(&id, &mut data, extension)
+            .await
+            .extract(&req)?;
+        let mut res = Response::from(validation).context(&req);
+        if res.is_success() {
+            let mut model_filters = model.next_version_filters();
+            Self::encode_public_ids(&mut model_filters).extract(&req)?;
+            res.set_json_data(Self::data_item(model_filters));
+        }
+        Ok(res.into())
+    }
+}
+
+/// Moves the children of the model out of the candidates and attaches them to the model.
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+#[cfg(feature = "orm")]
+fn attach_children(
+    model: &mut Map,
+    candidates: &mut Vec<Map>,
+    primary_key_name: &str,
+    parent_field: &str,
+    recursive: bool,
+) {
+    let model_id = model.get(primary_key_name);
+
+    // Should use `extract_if` when it is stabilized.
+    let mut children = Vec::new();
+    let mut index = 0;
+    while index < candidates.len() {
+        if candidates[index].get(parent_field) == model_id {
+            let child = candidates.remove(index);
+            children.push(child);
+        } else {
+            index += 1;
+        }
+    }
+    if recursive {
+        for child in children.iter_mut() {
+            attach_children(child, candidates, primary_key_name, parent_field, true);
+        }
+    }
+    model.upsert("children", children);
+}
+
+/// Removes the duplicate values of the related models while preserving the order.
+/// The values are compared as strings since the primary keys can be numbers or strings.
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+#[cfg(feature = "orm")]
+fn dedup_related_values(values: &mut Vec<JsonValue>) {
+    let mut keys = std::collections::HashSet::with_capacity(values.len());
+    values.retain(|value| {
+        value
+            .parse_string()
+            .is_some_and(|key| keys.insert(key.into_owned()))
+    });
+}
+
+/// Parses the `id` param of the model, which is decoded by the shared [`IdCodec`]
+/// if the primary key is exposed as a public ID.
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+#[cfg(feature = "orm")]
+fn parse_model_id<M, K>(req: &impl RequestContext) -> Result<K, Rejection>
+where
+    M: Schema,
+    K: std::str::FromStr,
+    <K as std::str::FromStr>::Err: std::error::Error + Send + 'static,
+{
+    if M::PUBLIC_ID_FIELDS.contains(&M::PRIMARY_KEY_NAME) {
+        let public_id = req.parse_param::<String>("id")?;
+        IdCodec::shared()
+            .decode(&public_id)
+            .and_then(|id| id.parse().map_err(Error::from))
+            .map_err(|err| Rejection::not_found(err).context(req))
+    } else {
+        req.parse_param("id")
+    }
+}
+
+/// Derives an entity tag from the `version` field of the model data,
+/// or the `updated_at` field if the model is not versioned.
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+#[cfg(feature = "orm")]
+fn derive_entity_tag<M: Schema>(data: &Map) -> Option<String> {
+    if M::has_column("version") {
+        data.get_u64("version")
+            .map(|version| format!(r#""{version}""#))
+    } else if M::has_column("updated_at") {
+        data.parse_datetime("updated_at")?
+            .ok()
+            .map(|dt| format!(r#""{}""#, dt.timestamp_micros()))
+    } else {
+        None
+    }
+}
+
+/// Checks the `if-match` header against the entity tag of the model data,
+/// so that the concurrent edits will not be overwritten silently.
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+#[cfg(feature = "orm")]
+fn check_if_match<M: Schema>(req: &crate::Request, data: &Map) -> Result<(), Rejection> {
+    let Some(if_match) = req.get_header("if-match") else {
+        return Ok(());
+    };
+    if if_match.trim() == "*" {
+        return Ok(());
+    }
+
+    let etag = derive_entity_tag::<M>(data);
+    let matched = etag.as_deref().is_some_and(|etag| {
+        if_match
+            .split(',')
+            .any(|tag| tag.trim().trim_start_matches("W/") == etag)
+    });
+    if matched {
+        Ok(())
+    } else {
+        let err = warn!(
+            "412 Precondition Failed: the model `{}` has been modified",
+            M::MODEL_NAME
+        );
+        Err(Rejection::precondition_failed(err).context(req))
+    }
+}
+
+/// Parses the expected version from the `if-match` header.
+/// It returns `None` if the model has no `version` column,
+/// or the header does not consist of a single version tag.
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+#[cfg(feature = "orm")]
+fn parse_if_match_version<M: Schema>(req: &crate::Request) -> Option<u64> {
+    if !M::has_column("version") {
+        return None;
+    }
+    req.get_header("if-match")?
+        .trim()
+        .trim_start_matches("W/")
+        .strip_prefix('"')?
+        .strip_suffix('"')?
+        .parse()
+        .ok()
+}
+
+/// Reads the XLSX workbook as rows.
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+#[cfg(feature = "orm")]
+#[cfg(feature = "xlsx")]
+fn read_xlsx(reader: &SheetReader<'_>, bytes: Vec<u8>) -> Result<Vec<SheetRow>, Error> {
+    reader.read_xlsx(std::io::Cursor::new(bytes))
+}
+
+/// Reads the XLSX workbook as rows.
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+#[cfg(feature = "orm")]
+#[cfg(not(feature = "xlsx"))]
+fn read_xlsx(_reader: &SheetReader<'_>, _bytes: Vec<u8>) -> Result<Vec<SheetRow>, Error> {
+    Err(warn!(
+        "the `xlsx` feature should be enabled to import XLSX files"
+    ))
 }
@@ -0,0 +1,154 @@
+use std::future::Future;
+use zino_core::{
+    auth::UserSession,
+    error::Error,
+    extension::JsonObjectExt,
+    request::RequestContext,
+    response::{Rejection, Response},
+    schedule::{Operation, OperationHandle, OperationRegistry},
+    warn, JsonValue, Map, Uuid,
+};
+
+/// An extension trait for spawning the long-running operations.
+pub trait OperationExt {
+    /// Spawns a long-running operation in the background and returns the operation ID,
+    /// which can be polled by the client with the endpoints of [`OperationController`].
+    /// The operation is owned by the user of the request if there is a user session.
+    fn spawn<F, Fut>(req: &crate::Request, name: &str, f: F) -> Uuid
+    where
+        F: FnOnce(OperationHandle) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<JsonValue, Error>> + Send + 'static;
+}
+
+impl OperationExt for Operation {
+    fn spawn<F, Fut>(req: &crate::Request, name: &str, f: F) -> Uuid
+    where
+        F: FnOnce(OperationHandle) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<JsonValue, Error>> + Send + 'static,
+    {
+        let (operation_id, future) = Operation::prepare(name, parse_principal(req), f);
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "actix")] {
+                actix_web::rt::spawn(future);
+            } else if #[cfg(feature = "axum")] {
+                tokio::spawn(future);
+            } else {
+                ntex::rt::spawn(future);
+            }
+        }
+        operation_id
+    }
+}
+
+/// Controller for polling the long-running operations.
+/// The operations owned by other users are treated as nonexistent.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino::{prelude::*, OperationController, OperationExt};
+///
+/// pub struct OperationManager;
+///
+/// impl OperationController for OperationManager {}
+///
+/// pub async fn export(req: Request) -> Result {
+///     let operation_id = Operation::spawn(&req, "export-report", |op| async move {
+///         op.update_progress(50, "collecting the data").await;
+///         let url = generate_report().await?;
+///         Ok(url.into())
+///     });
+///     let mut res = Response::default().context(&req);
+///     res.set_code(StatusCode::ACCEPTED);
+///     res.set_json_data(Map::data_entry(Map::from_entry("operation_id", operation_id.to_string())));
+///     Ok(res.into())
+/// }
+///
+/// let router = Router::new()
+///     .route("/report/export", post(export))
+///     .route("/operations/:id", get(OperationManager::view_operation))
+///     .route("/operations/:id/events", get(OperationManager::watch_operation));
+/// ```
+pub trait OperationController {
+    /// Views the status, progress and result of the operation.
+    async fn view_operation(req: crate::Request) -> crate::Result {
+        let operation = get_operation(&req).await?;
+        let mut res = Response::default().context(&req);
+        res.set_json_data(Map::data_entry(operation.to_map()));
+        Ok(res.into())
+    }
+
+    /// Subscribes to the updates of the operation via server-sent events.
+    /// A `progress` event is sent whenever the operation is updated,
+    /// and the stream ends with a `done` event when the operation has finished.
+    #[cfg(feature = "axum")]
+    async fn watch_operation(req: crate::Request) -> crate::Result<axum::response::Response> {
+        use axum::response::{
+            sse::{Event, KeepAlive, Sse},
+            IntoResponse,
+        };
+        use std::{convert::Infallible, time::Duration};
+
+        let operation = get_operation(&req).await?;
+        let initial_state = (operation.id(), None, false);
+        let stream = futures::stream::unfold(
+            initial_state,
+            |(operation_id, last_updated_at, finished)| async move {
+                if finished {
+                    return None;
+                }
+                loop {
+                    let operation = match OperationRegistry::get(operation_id).await {
+                        Ok(Some(operation)) => operation,
+                        Ok(None) => return None,
+                        Err(err) => {
+                            tracing::error!(
+                                operation_id = operation_id.to_string(),
+                                "fail to get the operation: {err}"
+                            );
+                            return None;
+                        }
+                    };
+                    let updated_at = operation.updated_at();
+                    if last_updated_at != Some(updated_at) {
+                        let finished = operation.is_finished();
+                        let event = Event::default()
+                            .event(if finished { "done" } else { "progress" })
+                            .data(JsonValue::from(operation.to_map()).to_string());
+                        let state = (operation_id, Some(updated_at), finished);
+                        return Some((Ok::<_, Infallible>(event), state));
+                    }
+                    tokio::time::sleep(Duration::from_millis(500)).await;
+                }
+            },
+        );
+        Ok(Sse::new(stream)
+            .keep_alive(KeepAlive::default())
+            .into_response())
+    }
+}
+
+/// Parses the operation ID and gets the operation visible to the user of the request.
+async fn get_operation(req: &crate::Request) -> Result<Operation, Rejection> {
+    let operation_id = req.parse_param::<Uuid>("id")?;
+    OperationRegistry::get(operation_id)
+        .await
+        .map_err(|err| Rejection::from_error(err).context(req))?
+        .filter(|operation| operation.is_visible_to(parse_principal(req).as_deref()))
+        .ok_or_else(|| {
+            let err = warn!("the operation `{}` does not exist", operation_id);
+            Rejection::not_found(err).context(req)
+        })
+}
+
+/// Parses the user ID from the user session verified by the middleware of authentication.
+fn parse_principal(req: &crate::Request) -> Option<String> {
+    if let Some(session) = req.get_data::<UserSession<Uuid>>() {
+        return Some(session.user_id().to_string());
+    }
+    if let Some(session) = req.get_data::<UserSession<i64>>() {
+        return Some(session.user_id().to_string());
+    }
+    req.get_data::<UserSession<String>>()
+        .map(|session| session.user_id().to_owned())
+}
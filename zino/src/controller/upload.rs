@@ -0,0 +1,587 @@
+use std::{
+    collections::HashSet,
+    fs::{self, OpenOptions},
+    io::{Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+use zino_core::{
+    application::Application,
+    channel::{new_model_event, Projection},
+    datetime::DateTime,
+    error::Error,
+    extension::JsonObjectExt,
+    file::{content_scanner, NamedFile, ScanVerdict},
+    request::RequestContext,
+    response::{ExtractRejection, Rejection, Response, StatusCode},
+    validation::Validation,
+    warn, LazyLock, Map, Uuid,
+};
+
+/// Controller for the chunked file uploads with resume.
+///
+/// The chunks of a file are identified by the `upload_id`, which should be generated
+/// by the client, such as the checksum of the whole file. The chunked uploads can be resumed
+/// by fetching the uploaded chunk numbers from the `upload_status` endpoint.
+///
+/// For large files, an offset-based protocol is also supported. The client creates
+/// an upload session with the file size and an optional checksum, sends the ranged chunks
+/// with the `Upload-Offset` header, queries the offset to resume after failures,
+/// and finishes the upload when all the bytes have been received.
+///
+/// The uploaded files are scanned by the shared [`ContentScanner`](zino_core::file::ContentScanner)
+/// if it has been set. The infected files are moved to the quarantine directory
+/// under generated IDs instead of the file names, so they are never served for download,
+/// and a `file.quarantined` event with the quarantine ID is published.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino::UploadController;
+///
+/// pub struct FileUpload;
+///
+/// impl UploadController for FileUpload {}
+///
+/// let router = Router::new()
+///     .route("/file/chunk", post(FileUpload::upload_chunk))
+///     .route("/file/chunks", get(FileUpload::upload_status))
+///     .route("/file/complete", post(FileUpload::complete_upload))
+///     .route("/upload/session", post(FileUpload::create_upload))
+///     .route("/upload/range", patch(FileUpload::upload_range))
+///     .route("/upload/progress", get(FileUpload::upload_progress))
+///     .route("/upload/finish", post(FileUpload::finish_upload))
+///     .route("/file/download", get(FileUpload::download_file));
+/// ```
+pub trait UploadController {
+    /// Returns the directory for the uploaded files.
+    #[inline]
+    fn upload_dir() -> PathBuf {
+        crate::Cluster::shared_dir("uploads")
+    }
+
+    /// Returns the directory for the file chunks of an upload.
+    #[inline]
+    fn chunk_dir(upload_id: &str) -> PathBuf {
+        Self::upload_dir().join(".chunks").join(upload_id)
+    }
+
+    /// Returns the directory for the session of an offset-based upload.
+    #[inline]
+    fn session_dir(upload_id: &str) -> PathBuf {
+        Self::upload_dir().join(".sessions").join(upload_id)
+    }
+
+    /// Returns the directory for the quarantined files which are flagged as infected.
+    #[inline]
+    fn quarantine_dir() -> PathBuf {
+        Self::upload_dir().join(".quarantine")
+    }
+
+    /// Returns `true` if the uploaded files should be scanned by the shared content scanner.
+    /// It can be overridden to opt out of scanning for the routes of the controller.
+    #[inline]
+    fn scan_content() -> bool {
+        true
+    }
+
+    /// Uploads a file chunk with the `upload_id`, `chunk_number` and `total_chunks` fields.
+    /// The integrity will be checked if the `chunk_size` or `checksum` is specified.
+    async fn upload_chunk(mut req: crate::Request) -> crate::Result {
+        let file = req.parse_file().await?;
+        let upload_id = parse_upload_id(file.extra().get_str("upload_id")).extract(&req)?;
+        let Some(chunk_number) = file.chunk_number() else {
+            let err = warn!("the chunk number should be specified");
+            return Err(Rejection::from_validation_entry("chunk_number", err)
+                .context(&req)
+                .into());
+        };
+
+        let chunk_dir = Self::chunk_dir(upload_id);
+        fs::create_dir_all(&chunk_dir).extract(&req)?;
+        file.write(&chunk_dir).extract(&req)?;
+
+        let mut data = Map::new();
+        data.upsert("upload_id", upload_id);
+        data.upsert("chunk_number", chunk_number);
+        data.upsert("total_chunks", file.total_chunks());
+
+        let mut res = Response::default().context(&req);
+        res.set_json_data(Map::data_entry(data));
+        Ok(res.into())
+    }
+
+    /// Returns the uploaded chunk numbers for the `upload_id`.
+    async fn upload_status(req: crate::Request) -> crate::Result {
+        let upload_id = parse_upload_id(req.get_query("upload_id")).extract(&req)?;
+        let chunk_dir = Self::chunk_dir(upload_id);
+        let mut chunk_numbers = Vec::new();
+        if chunk_dir.try_exists().extract(&req)? {
+            for entry in fs::read_dir(&chunk_dir).extract(&req)? {
+                let file_name = entry.extract(&req)?.file_name();
+                let chunk_number = file_name
+                    .to_str()
+                    .and_then(|s| s.strip_prefix('.')?.strip_suffix(".part"))
+                    .and_then(|s| s.parse::<usize>().ok());
+                if let Some(chunk_number) = chunk_number {
+                    chunk_numbers.push(chunk_number);
+                }
+            }
+            chunk_numbers.sort_unstable();
+        }
+
+        let mut data = Map::new();
+        data.upsert("upload_id", upload_id);
+        data.upsert("chunks", chunk_numbers);
+
+        let mut res = Response::default().context(&req);
+        res.set_json_data(Map::data_entry(data));
+        Ok(res.into())
+    }
+
+    /// Completes an upload by concatenating the file chunks
+    /// with the `upload_id`, `file_name` and `total_chunks` fields.
+    async fn complete_upload(mut req: crate::Request) -> crate::Result {
+        let body = req.parse_body::<Map>().await?;
+        let upload_id = parse_upload_id(body.get_str("upload_id")).extract(&req)?;
+        let Some(file_name) = body
+            .get_str("file_name")
+            .and_then(|s| std::path::Path::new(s).file_name())
+            .and_then(|s| s.to_str())
+        else {
+            let err = warn!("the file name should be specified");
+            return Err(Rejection::from_validation_entry("file_name", err)
+                .context(&req)
+                .into());
+        };
+        let Some(Ok(total_chunks)) = body.parse_usize("total_chunks") else {
+            let err = warn!("the total number of chunks should be specified");
+            return Err(Rejection::from_validation_entry("total_chunks", err)
+                .context(&req)
+                .into());
+        };
+
+        let chunk_dir = Self::chunk_dir(upload_id);
+        let file = NamedFile::try_concat_chunks(&chunk_dir, total_chunks).extract(&req)?;
+        let verdict = if Self::scan_content() {
+            let file = file.clone();
+            run_blocking(move || file.scan()).await.extract(&req)?
+        } else {
+            ScanVerdict::Clean
+        };
+        if let ScanVerdict::Infected(signature) = verdict {
+            let quarantine_dir = Self::quarantine_dir();
+            let quarantine_id = Uuid::now_v7().to_string();
+            fs::create_dir_all(&quarantine_dir).extract(&req)?;
+            file.write(quarantine_dir.join(&quarantine_id))
+                .extract(&req)?;
+            if let Err(err) = fs::remove_dir_all(&chunk_dir) {
+                tracing::warn!("fail to remove the chunk directory: {}", err);
+            }
+
+            let quarantine = Quarantine {
+                dir: &quarantine_dir,
+                id: &quarantine_id,
+                upload_id,
+                file_name,
+                signature: &signature,
+            };
+            return Err(quarantine_file(&req, quarantine).await.into());
+        }
+        file.write(Self::upload_dir().join(file_name))
+            .extract(&req)?;
+        if let Err(err) = fs::remove_dir_all(&chunk_dir) {
+            tracing::warn!("fail to remove the chunk directory: {}", err);
+        }
+
+        let mut data = Map::new();
+        data.upsert("upload_id", upload_id);
+        data.upsert("file_name", file_name);
+        data.upsert("file_size", file.file_size());
+        data.upsert("checksum", format!("{:x}", file.checksum()));
+
+        let mut res = Response::default().context(&req);
+        res.set_code(StatusCode::CREATED);
+        res.set_json_data(Map::data_entry(data));
+        Ok(res.into())
+    }
+
+    /// Downloads an uploaded file with the `file_name` query, and the range requests are supported.
+    async fn download_file(req: crate::Request) -> crate::Result {
+        let Some(file_name) = req
+            .get_query("file_name")
+            .and_then(|s| Path::new(s).file_name())
+            .and_then(|s| s.to_str())
+        else {
+            let err = warn!("the file name should be specified");
+            return Err(Rejection::from_validation_entry("file_name", err)
+                .context(&req)
+                .into());
+        };
+        super::download::send_file(&req, &Self::upload_dir().join(file_name), None)
+    }
+
+    /// Creates an upload session with the `file_name`, `file_size` and optional `checksum` fields.
+    /// The checksum should be the hex-encoded SHA1 digest of the whole file.
+    async fn create_upload(mut req: crate::Request) -> crate::Result {
+        let body = req.parse_body::<Map>().await?;
+        let mut validation = Validation::new();
+        let file_name = body
+            .get_str("file_name")
+            .and_then(|s| Path::new(s).file_name())
+            .and_then(|s| s.to_str());
+        if file_name.is_none() {
+            validation.record("file_name", "the file name should be specified");
+        }
+        let file_size = match body.parse_u64("file_size") {
+            Some(Ok(file_size)) => file_size,
+            Some(Err(err)) => {
+                validation.record_fail("file_size", err);
+                0
+            }
+            None => {
+                validation.record("file_size", "the file size should be specified");
+                0
+            }
+        };
+        let checksum = body.get_str("checksum").map(|s| s.to_ascii_lowercase());
+        if checksum
+            .as_ref()
+            .is_some_and(|s| s.len() != 40 || !s.chars().all(|c| c.is_ascii_hexdigit()))
+        {
+            validation.record(
+                "checksum",
+                "the checksum should be a hex-encoded SHA1 digest",
+            );
+        }
+        if !validation.is_success() {
+            return Err(Rejection::bad_request(validation).context(&req).into());
+        }
+
+        let upload_id = Uuid::now_v7().to_string();
+        let mut session = Map::new();
+        session.upsert("upload_id", upload_id.as_str());
+        session.upsert("file_name", file_name);
+        session.upsert("file_size", file_size);
+        session.upsert("checksum", checksum);
+        session.upsert("created_at", DateTime::now());
+
+        let session_dir = Self::session_dir(&upload_id);
+        fs::create_dir_all(&session_dir).extract(&req)?;
+        let metadata = serde_json::to_vec(&session).extract(&req)?;
+        fs::write(session_dir.join("session.json"), metadata).extract(&req)?;
+        fs::File::create(session_dir.join("data.part")).extract(&req)?;
+
+        session.upsert("offset", 0);
+        let mut res = Response::default().context(&req);
+        res.set_code(StatusCode::CREATED);
+        res.set_json_data(Map::data_entry(session));
+        Ok(res.into())
+    }
+
+    /// Uploads a range of bytes in the request body for the `upload_id`.
+    /// The offset should be specified by the `Upload-Offset` header or the `offset` query,
+    /// and it must be equal to the number of bytes received.
+    /// The concurrent ranges for the same upload are rejected with a `409 Conflict` response.
+    async fn upload_range(mut req: crate::Request) -> crate::Result {
+        let upload_id = parse_upload_id(req.get_query("upload_id"))
+            .extract(&req)?
+            .to_owned();
+        let Some(offset) = req
+            .get_header("upload-offset")
+            .or_else(|| req.get_query("offset"))
+            .and_then(|s| s.parse::<u64>().ok())
+        else {
+            let err = warn!("the upload offset should be specified");
+            return Err(Rejection::from_validation_entry("offset", err)
+                .context(&req)
+                .into());
+        };
+
+        let session_dir = Self::session_dir(&upload_id);
+        let session = read_session(&session_dir).extract(&req)?;
+        let file_size = session.get_u64("file_size").unwrap_or_default();
+        let data_path = session_dir.join("data.part");
+        let _lock = UploadLock::try_acquire(&upload_id).extract(&req)?;
+        let received_size = fs::metadata(&data_path).extract(&req)?.len();
+        if offset != received_size {
+            let err = warn!(
+                "the upload offset `{}` does not match the received size `{}`",
+                offset, received_size
+            );
+            return Err(Rejection::conflict(err).context(&req).into());
+        }
+
+        let bytes = req.read_body_bytes().await.extract(&req)?;
+        let offset = received_size + u64::try_from(bytes.len()).unwrap_or_default();
+        if offset > file_size {
+            let err = warn!("the uploaded bytes exceed the file size `{}`", file_size);
+            return Err(Rejection::from_validation_entry("offset", err)
+                .context(&req)
+                .into());
+        }
+        OpenOptions::new()
+            .write(true)
+            .open(&data_path)
+            .and_then(|mut file| {
+                file.seek(SeekFrom::Start(received_size))?;
+                file.write_all(&bytes)
+            })
+            .extract(&req)?;
+
+        let mut res = Response::default().context(&req);
+        res.set_json_data(Map::data_entry(upload_progress(
+            &upload_id, offset, file_size,
+        )));
+        Ok(res.into())
+    }
+
+    /// Returns the received offset and the progress for the `upload_id`.
+    async fn upload_progress(req: crate::Request) -> crate::Result {
+        let upload_id = parse_upload_id(req.get_query("upload_id")).extract(&req)?;
+        let session_dir = Self::session_dir(upload_id);
+        let session = read_session(&session_dir).extract(&req)?;
+        let file_size = session.get_u64("file_size").unwrap_or_default();
+        let offset = fs::metadata(session_dir.join("data.part"))
+            .extract(&req)?
+            .len();
+
+        let mut res = Response::default().context(&req);
+        res.set_json_data(Map::data_entry(upload_progress(
+            upload_id, offset, file_size,
+        )));
+        Ok(res.into())
+    }
+
+    /// Finishes an upload session with the `upload_id` field when all the bytes have been received.
+    /// The checksum will be verified if it has been specified when creating the session.
+    async fn finish_upload(mut req: crate::Request) -> crate::Result {
+        let body = req.parse_body::<Map>().await?;
+        let upload_id = parse_upload_id(body.get_str("upload_id")).extract(&req)?;
+        let session_dir = Self::session_dir(upload_id);
+        let session = read_session(&session_dir).extract(&req)?;
+        let file_name = session.get_str("file_name").unwrap_or_default();
+        let file_size = session.get_u64("file_size").unwrap_or_default();
+        let data_path = session_dir.join("data.part");
+        let _lock = UploadLock::try_acquire(upload_id).extract(&req)?;
+        let received_size = fs::metadata(&data_path).extract(&req)?.len();
+        if received_size != file_size {
+            let err = warn!(
+                "the upload is incomplete with `{}` of `{}` bytes received",
+                received_size, file_size
+            );
+            return Err(Rejection::conflict(err).context(&req).into());
+        }
+
+        let checksum = {
+            let data_path = data_path.clone();
+            run_blocking(move || Ok(NamedFile::checksum_local(data_path)?))
+                .await
+                .extract(&req)?
+        };
+        let checksum = format!("{checksum:x}");
+        if session
+            .get_str("checksum")
+            .is_some_and(|expected| expected != checksum)
+        {
+            let err = warn!(
+                "the checksum `{}` does not match the uploaded file",
+                checksum
+            );
+            return Err(Rejection::from_validation_entry("checksum", err)
+                .context(&req)
+                .into());
+        }
+        let verdict = match content_scanner().filter(|_| Self::scan_content()) {
+            Some(scanner) => {
+                let data_path = data_path.clone();
+                run_blocking(move || {
+                    let mut file = fs::File::open(data_path)?;
+                    scanner.scan(&mut file)
+                })
+                .await
+                .extract(&req)?
+            }
+            None => ScanVerdict::Clean,
+        };
+        if let ScanVerdict::Infected(signature) = verdict {
+            let quarantine_dir = Self::quarantine_dir();
+            let quarantine_id = Uuid::now_v7().to_string();
+            fs::create_dir_all(&quarantine_dir).extract(&req)?;
+            fs::rename(&data_path, quarantine_dir.join(&quarantine_id)).extract(&req)?;
+            if let Err(err) = fs::remove_dir_all(&session_dir) {
+                tracing::warn!("fail to remove the session directory: {}", err);
+            }
+
+            let quarantine = Quarantine {
+                dir: &quarantine_dir,
+                id: &quarantine_id,
+                upload_id,
+                file_name,
+                signature: &signature,
+            };
+            return Err(quarantine_file(&req, quarantine).await.into());
+        }
+        fs::rename(&data_path, Self::upload_dir().join(file_name)).extract(&req)?;
+        if let Err(err) = fs::remove_dir_all(&session_dir) {
+            tracing::warn!("fail to remove the session directory: {}", err);
+        }
+
+        let mut data = Map::new();
+        data.upsert("upload_id", upload_id);
+        data.upsert("file_name", file_name);
+        data.upsert("file_size", file_size);
+        data.upsert("checksum", checksum);
+
+        let mut res = Response::default().context(&req);
+        res.set_code(StatusCode::CREATED);
+        res.set_json_data(Map::data_entry(data));
+        Ok(res.into())
+    }
+}
+
+/// An infected file moved to the quarantine directory.
+struct Quarantine<'a> {
+    /// Quarantine directory.
+    dir: &'a Path,
+    /// Quarantine ID, which is the file name in the quarantine directory.
+    id: &'a str,
+    /// Upload ID.
+    upload_id: &'a str,
+    /// Original file name.
+    file_name: &'a str,
+    /// Signature of the threat.
+    signature: &'a str,
+}
+
+/// Records the metadata of the quarantined file, publishes a `file.quarantined` event,
+/// and returns a rejection for the upload.
+async fn quarantine_file(req: &crate::Request, quarantine: Quarantine<'_>) -> Rejection {
+    let Quarantine {
+        dir,
+        id,
+        upload_id,
+        file_name,
+        signature,
+    } = quarantine;
+    tracing::warn!(
+        quarantine_id = id,
+        upload_id,
+        file_name,
+        signature,
+        "the uploaded file is quarantined"
+    );
+
+    let mut data = Map::new();
+    data.upsert("quarantine_id", id);
+    data.upsert("upload_id", upload_id);
+    data.upsert("file_name", file_name);
+    data.upsert("signature", signature);
+    data.upsert("quarantined_at", DateTime::now());
+    let metadata = serde_json::to_vec(&data).unwrap_or_default();
+    if let Err(err) = fs::write(dir.join(format!("{id}.json")), metadata) {
+        tracing::warn!("fail to write the quarantine metadata: {}", err);
+    }
+
+    let event = new_model_event("file", "quarantined", id, data);
+    if Projection::dispatch(&event).await.is_err() {
+        tracing::warn!(file_name, "fail to publish the quarantine event");
+    }
+
+    let err = warn!("the file `{}` is infected with `{}`", file_name, signature);
+    Rejection::from_validation_entry("file", err).context(req)
+}
+
+/// A lock of the upload session held by the request in progress.
+struct UploadLock(String);
+
+impl UploadLock {
+    /// Attempts to acquire the lock of the upload session.
+    fn try_acquire(upload_id: &str) -> Result<Self, Error> {
+        let mut uploads = UPLOADS_IN_PROGRESS
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        if uploads.insert(upload_id.to_owned()) {
+            Ok(Self(upload_id.to_owned()))
+        } else {
+            Err(warn!(
+                "409 Conflict: the upload session `{}` is in progress",
+                upload_id
+            ))
+        }
+    }
+}
+
+impl Drop for UploadLock {
+    fn drop(&mut self) {
+        UPLOADS_IN_PROGRESS
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .remove(&self.0);
+    }
+}
+
+/// Runs the blocking file operations on a dedicated thread.
+async fn run_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, Error> + Send + 'static,
+) -> Result<T, Error> {
+    tokio::task::spawn_blocking(f).await?
+}
+
+/// Reads the metadata of an upload session.
+fn read_session(session_dir: &Path) -> Result<Map, Error> {
+    let path = session_dir.join("session.json");
+    if !path.try_exists()? {
+        let upload_id = session_dir
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy();
+        return Err(warn!(
+            "404 Not Found: the upload session `{}` does not exist",
+            upload_id
+        ));
+    }
+    let session = serde_json::from_slice(&fs::read(path)?)?;
+    Ok(session)
+}
+
+/// Returns the progress of an upload session.
+fn upload_progress(upload_id: &str, offset: u64, file_size: u64) -> Map {
+    let progress = if file_size > 0 {
+        offset as f64 / file_size as f64
+    } else {
+        1.0
+    };
+    let mut data = Map::new();
+    data.upsert("upload_id", upload_id);
+    data.upsert("offset", offset);
+    data.upsert("file_size", file_size);
+    data.upsert("progress", progress);
+    data
+}
+
+/// Parses the upload ID, which should only contain ASCII alphanumerics, `-` or `_`.
+fn parse_upload_id(upload_id: Option<&str>) -> Result<&str, Validation> {
+    match upload_id {
+        Some(upload_id)
+            if !upload_id.is_empty()
+                && upload_id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_')) =>
+        {
+            Ok(upload_id)
+        }
+        Some(_) => {
+            let err = warn!("the upload ID is invalid");
+            Err(Validation::from_entry("upload_id", err))
+        }
+        None => {
+            let err = warn!("the upload ID should be specified");
+            Err(Validation::from_entry("upload_id", err))
+        }
+    }
+}
+
+/// Upload sessions which are in progress.
+static UPLOADS_IN_PROGRESS: LazyLock<Mutex<HashSet<String>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
@@ -15,6 +15,21 @@ pub mod prelude;
 
 pub use controller::DefaultController;
 
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+pub use controller::{
+    DownloadController, JobController, OperationController, OperationExt, UploadController,
+};
+
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+#[cfg(feature = "orm")]
+pub use controller::{Found, Lookup};
+
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+pub use middleware::{Middleware, MiddlewareFuture, ResponseHead};
+
+#[cfg(feature = "axum")]
+pub use middleware::{RequireIdempotency, RequireScope};
+
 cfg_if::cfg_if! {
     if #[cfg(feature = "actix")] {
         use crate::application::actix_cluster::ActixCluster;
@@ -54,6 +69,8 @@ cfg_if::cfg_if! {
     } else if #[cfg(feature = "dioxus-desktop")] {
         use crate::application::dioxus_desktop::DioxusDesktop;
 
+        pub use crate::application::desktop_service::DesktopService;
+
         /// Desktop applications for `dioxus`.
         pub type Desktop<R> = DioxusDesktop<R>;
     } else if #[cfg(feature = "ntex")] {
@@ -0,0 +1,93 @@
+use super::{custom, Middleware, ResponseHead};
+use crate::response::actix_response::{ActixRejection, ActixResponse};
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{HeaderName, HeaderValue},
+    Error, Responder,
+};
+use std::{
+    future::{ready, Future, Ready},
+    pin::Pin,
+    rc::Rc,
+};
+
+/// Runs the custom middlewares registered by the `add_middleware` method of the cluster.
+#[derive(Clone, Copy)]
+pub struct CustomMiddlewares(pub(crate) &'static [Box<dyn Middleware>]);
+
+impl<S, B> Transform<S, ServiceRequest> for CustomMiddlewares
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = CustomMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(CustomMiddleware {
+            service: Rc::new(service),
+            middlewares: self.0,
+        }))
+    }
+}
+
+pub struct CustomMiddleware<S> {
+    service: Rc<S>,
+    middlewares: &'static [Box<dyn Middleware>],
+}
+
+impl<S, B> Service<ServiceRequest> for CustomMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let middlewares = self.middlewares;
+        if middlewares.is_empty() {
+            let fut = self.service.call(req);
+            return Box::pin(async move {
+                let res = fut.await?;
+                Ok(res.map_into_left_body())
+            });
+        }
+
+        let service = self.service.clone();
+        Box::pin(async move {
+            let mut req = crate::Request::from(req);
+            match custom::handle_request(middlewares, &mut req).await {
+                Ok(Some((res, index))) => {
+                    let res =
+                        custom::finalize_early_response(&middlewares[..index], &req, res).await;
+                    let req = ServiceRequest::from(req);
+                    let res = ActixResponse::from(res).respond_to(req.request());
+                    return Ok(req.into_response(res).map_into_right_body());
+                }
+                Ok(None) => (),
+                Err(rejection) => return Err(ActixRejection::from(rejection).into()),
+            }
+
+            let mut res = service.call(ServiceRequest::from(req)).await?;
+            let req = crate::Request::from(res.request().clone());
+            let mut res_head = ResponseHead::new(res.status().as_u16());
+            custom::handle_response(middlewares, &req, &mut res_head).await;
+            for (key, value) in res_head.into_headers() {
+                if let Ok(header_name) = HeaderName::try_from(key.as_ref()) {
+                    if let Ok(header_value) = HeaderValue::try_from(value) {
+                        res.headers_mut().insert(header_name, header_value);
+                    }
+                }
+            }
+            Ok(res.map_into_left_body())
+        })
+    }
+}
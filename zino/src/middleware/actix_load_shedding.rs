@@ -0,0 +1,73 @@
+use crate::response::actix_response::ActixRejection;
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    Error,
+};
+use std::{
+    future::{ready, Future, Ready},
+    pin::Pin,
+    rc::Rc,
+};
+use zino_core::request::LoadShedder;
+
+#[derive(Default)]
+pub struct LoadShedding;
+
+impl<S, B> Transform<S, ServiceRequest> for LoadShedding
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = LoadSheddingMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(LoadSheddingMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct LoadSheddingMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for LoadSheddingMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let Some(shedder) = LoadShedder::shared() else {
+            let fut = self.service.call(req);
+            return Box::pin(async move {
+                let res = fut.await?;
+                Ok(res)
+            });
+        };
+
+        let service = self.service.clone();
+        Box::pin(async move {
+            match shedder.acquire().await {
+                Ok(permit) => {
+                    let res = service.call(req).await;
+                    drop(permit);
+                    res
+                }
+                Err(rejection) => {
+                    let req = crate::Request::from(req);
+                    Err(ActixRejection::from(rejection.context(&req)).into())
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,79 @@
+use crate::response::actix_response::ActixRejection;
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    rt::time,
+    Error,
+};
+use std::{
+    future::{ready, Future, Ready},
+    pin::Pin,
+};
+use zino_core::{
+    request::{self, RequestContext},
+    response::Rejection,
+};
+
+#[derive(Default)]
+pub struct RequestTimeout;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestTimeout
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RequestTimeoutMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestTimeoutMiddleware { service }))
+    }
+}
+
+pub struct RequestTimeoutMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestTimeoutMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let req = crate::Request::from(req);
+        let timeout = match req.get_context() {
+            Some(ctx) => ctx.remaining_time(),
+            None => req.request_timeout(),
+        };
+        let Some(timeout) = timeout else {
+            let fut = self.service.call(ServiceRequest::from(req));
+            return Box::pin(async move {
+                let res = fut.await?;
+                Ok(res)
+            });
+        };
+
+        let millis = timeout.as_millis();
+        let err =
+            zino_core::error::Error::new(format!("the handler exceeded the timeout of {millis}ms"));
+        let rejection = Rejection::gateway_timeout(err).context(&req);
+        let fut = request::with_deadline(timeout, self.service.call(ServiceRequest::from(req)));
+        Box::pin(async move {
+            match time::timeout(timeout, fut).await {
+                Ok(result) => result,
+                Err(_) => {
+                    tracing::warn!("handler is cancelled after {millis}ms");
+                    Err(ActixRejection::from(rejection).into())
+                }
+            }
+        })
+    }
+}
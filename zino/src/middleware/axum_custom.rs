@@ -0,0 +1,57 @@
+use super::{custom, Middleware, ResponseHead};
+use crate::{
+    request::axum_request::AxumExtractor,
+    response::axum_response::{AxumRejection, AxumResponse},
+};
+use axum::{
+    body::Body,
+    extract::State,
+    http::{HeaderName, HeaderValue, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+/// Runs the custom middlewares registered by the `add_middleware` method of the cluster.
+pub(crate) async fn custom_middlewares(
+    State(middlewares): State<&'static [Box<dyn Middleware>]>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    if middlewares.is_empty() {
+        return next.run(req).await;
+    }
+
+    let mut req = AxumExtractor::from(req);
+    match custom::handle_request(middlewares, &mut req).await {
+        Ok(Some((res, index))) => {
+            let res = custom::finalize_early_response(&middlewares[..index], &req, res).await;
+            return AxumResponse::from(res).into_response();
+        }
+        Ok(None) => (),
+        Err(rejection) => return AxumRejection::from(rejection).into_response(),
+    }
+
+    let req_head = request_head(&req);
+    let mut res = next.run(Request::from(req)).await;
+    let mut res_head = ResponseHead::new(res.status().as_u16());
+    custom::handle_response(middlewares, &req_head, &mut res_head).await;
+    for (key, value) in res_head.into_headers() {
+        if let Ok(header_name) = HeaderName::try_from(key.as_ref()) {
+            if let Ok(header_value) = HeaderValue::try_from(value) {
+                res.headers_mut().insert(header_name, header_value);
+            }
+        }
+    }
+    res
+}
+
+/// Returns a copy of the request without the body.
+fn request_head(req: &Request<Body>) -> crate::Request {
+    let mut head = Request::new(Body::empty());
+    *head.method_mut() = req.method().clone();
+    *head.uri_mut() = req.uri().clone();
+    *head.version_mut() = req.version();
+    *head.headers_mut() = req.headers().clone();
+    *head.extensions_mut() = req.extensions().clone();
+    AxumExtractor::from(head)
+}
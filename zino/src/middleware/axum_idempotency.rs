@@ -0,0 +1,266 @@
+use crate::{request::axum_request::AxumExtractor, response::axum_response::AxumRejection};
+use axum::{
+    body::{to_bytes, Body, Bytes, HttpBody},
+    http::{header::HeaderName, HeaderMap, HeaderValue, Method, Request, StatusCode},
+    middleware::{from_fn, Next},
+    response::{IntoResponse, Response},
+    routing::MethodRouter,
+    Router,
+};
+use parking_lot::Mutex;
+use std::{
+    collections::{hash_map::RandomState, HashMap},
+    hash::BuildHasher,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::Mutex as AsyncMutex;
+use zino_core::{
+    application::Application, auth::UserSession, extension::TomlTableExt, request::RequestContext,
+    response::Rejection, warn, LazyLock, Uuid,
+};
+
+/// Header name for the idempotency key.
+const IDEMPOTENCY_KEY: HeaderName = HeaderName::from_static("idempotency-key");
+
+/// Header name to indicate that the response is replayed.
+const IDEMPOTENT_REPLAYED: HeaderName = HeaderName::from_static("idempotent-replayed");
+
+/// Max length of the idempotency key.
+const MAX_KEY_LENGTH: usize = 255;
+
+/// An extension trait for honoring the `Idempotency-Key` header on `POST` routes.
+///
+/// The first response is stored for the key, the request path and the user of the
+/// [`UserSession`], and it will be replayed for the duplicate requests within the configured
+/// window. Since the user session is required, the route layer should be wrapped by
+/// the middleware of authentication.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use axum::{middleware::from_fn, routing::post, Router};
+/// use zino::RequireIdempotency;
+///
+/// let router = Router::new()
+///     .route("/order/new", post(Order::new).idempotent())
+///     .layer(from_fn(middleware::init_user_session));
+/// ```
+pub trait RequireIdempotency {
+    /// Honors the `Idempotency-Key` header for the routes.
+    fn idempotent(self) -> Self;
+}
+
+impl RequireIdempotency for Router {
+    #[inline]
+    fn idempotent(self) -> Self {
+        self.route_layer(from_fn(idempotency))
+    }
+}
+
+impl RequireIdempotency for MethodRouter {
+    #[inline]
+    fn idempotent(self) -> Self {
+        self.route_layer(from_fn(idempotency))
+    }
+}
+
+/// Honors the `Idempotency-Key` header on `POST` requests.
+///
+/// Concurrent duplicates wait for the in-flight request instead of being executed again.
+/// The request is rejected if there is no user session, and a duplicate request
+/// whose body differs from the stored one is rejected with `409 Conflict`.
+/// Server errors, `401` and `429` responses, and the responses whose body exceeds
+/// the limit are not stored.
+async fn idempotency(req: Request<Body>, next: Next) -> Response {
+    if req.method() != Method::POST {
+        return next.run(req).await;
+    }
+    let Some(key) = req.headers().get(IDEMPOTENCY_KEY) else {
+        return next.run(req).await;
+    };
+    let key = match key.to_str() {
+        Ok(key) if !key.is_empty() && key.len() <= MAX_KEY_LENGTH => key.to_owned(),
+        _ => {
+            let req = AxumExtractor::from(req);
+            let err = warn!(
+                "400 Bad Request: the idempotency key should be a visible ASCII string \
+                    of at most {} characters",
+                MAX_KEY_LENGTH
+            );
+            let rejection = Rejection::bad_request(err).context(&req);
+            return AxumRejection::from(rejection).into_response();
+        }
+    };
+
+    let req = AxumExtractor::from(req);
+    let Some(user_id) = parse_user_id(&req) else {
+        let err = warn!("401 Unauthorized: the user session is required for the idempotency key");
+        let rejection = Rejection::unauthorized(err).context(&req);
+        return AxumRejection::from(rejection).into_response();
+    };
+    let cache_key = format!("{}:{}:{}", req.uri().path(), user_id, key);
+
+    // The request body is fingerprinted to detect the reuse of the key with another payload.
+    let (parts, body) = Request::from(req).into_parts();
+    let body = match to_bytes(body, SHARED_IDEMPOTENCY_STORE.max_body_size).await {
+        Ok(body) => body,
+        Err(err) => {
+            let req = AxumExtractor::from(Request::from_parts(parts, Body::empty()));
+            let rejection = Rejection::from_validation_entry("body", err).context(&req);
+            return AxumRejection::from(rejection).into_response();
+        }
+    };
+    let fingerprint = SHARED_IDEMPOTENCY_STORE.fingerprint(&body);
+    let req = Request::from_parts(parts, Body::from(body));
+
+    let slot = SHARED_IDEMPOTENCY_STORE.slot(cache_key);
+    let mut cached_response = slot.lock().await;
+    if let Some(cached) = cached_response.as_ref() {
+        if cached.fingerprint != fingerprint {
+            let req = AxumExtractor::from(req);
+            let err =
+                warn!("409 Conflict: the idempotency key is reused with another request body");
+            let rejection = Rejection::conflict(err).context(&req);
+            return AxumRejection::from(rejection).into_response();
+        }
+        return cached.replay();
+    }
+
+    let res = next.run(req).await;
+    let status = res.status();
+    if status.is_server_error()
+        || status == StatusCode::UNAUTHORIZED
+        || status == StatusCode::TOO_MANY_REQUESTS
+        || res.body().size_hint().upper().map_or(true, |size| {
+            size > SHARED_IDEMPOTENCY_STORE.max_body_size as u64
+        })
+    {
+        return res;
+    }
+
+    let (parts, body) = res.into_parts();
+    match to_bytes(body, SHARED_IDEMPOTENCY_STORE.max_body_size).await {
+        Ok(bytes) => {
+            *cached_response = Some(CachedResponse {
+                fingerprint,
+                status: parts.status,
+                headers: parts.headers.clone(),
+                body: bytes.clone(),
+            });
+            Response::from_parts(parts, Body::from(bytes))
+        }
+        Err(err) => {
+            tracing::error!("fail to read the response body: {err}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// Parses the user ID from the user session verified by the middleware of authentication.
+fn parse_user_id(req: &AxumExtractor<Request<Body>>) -> Option<String> {
+    if let Some(session) = req.get_data::<UserSession<Uuid>>() {
+        return Some(session.user_id().to_string());
+    }
+    if let Some(session) = req.get_data::<UserSession<i64>>() {
+        return Some(session.user_id().to_string());
+    }
+    req.get_data::<UserSession<String>>()
+        .map(|session| session.user_id().to_owned())
+}
+
+/// A stored response for the idempotency key.
+struct CachedResponse {
+    /// Fingerprint of the request body.
+    fingerprint: u64,
+    /// Status code.
+    status: StatusCode,
+    /// Response headers.
+    headers: HeaderMap,
+    /// Response body.
+    body: Bytes,
+}
+
+impl CachedResponse {
+    /// Replays the stored response.
+    fn replay(&self) -> Response {
+        let mut res = Response::new(Body::from(self.body.clone()));
+        *res.status_mut() = self.status;
+        *res.headers_mut() = self.headers.clone();
+        res.headers_mut()
+            .insert(IDEMPOTENT_REPLAYED, HeaderValue::from_static("true"));
+        res
+    }
+}
+
+/// A slot for the response of the idempotency key.
+type Slot = Arc<AsyncMutex<Option<CachedResponse>>>;
+
+/// An in-memory store of the responses.
+struct IdempotencyStore {
+    /// Slots with the creation time.
+    slots: Mutex<HashMap<String, (Instant, Slot)>>,
+    /// The window for replaying the responses.
+    ttl: Duration,
+    /// Max body size of the requests and the stored responses.
+    max_body_size: usize,
+    /// Max number of the slots.
+    max_entries: usize,
+    /// Randomly keyed hasher for the fingerprints.
+    hasher: RandomState,
+}
+
+impl IdempotencyStore {
+    /// Computes the fingerprint of the request body.
+    #[inline]
+    fn fingerprint(&self, body: &Bytes) -> u64 {
+        self.hasher.hash_one(body)
+    }
+
+    /// Gets the slot for the key or inserts a new one,
+    /// and evicts the expired slots which are not in use.
+    /// If the store is full, the oldest slot not in use is evicted.
+    fn slot(&self, key: String) -> Slot {
+        let mut slots = self.slots.lock();
+        let ttl = self.ttl;
+        slots.retain(|_, (created_at, slot)| {
+            created_at.elapsed() < ttl || Arc::strong_count(slot) > 1
+        });
+        if slots.len() >= self.max_entries && !slots.contains_key(&key) {
+            let oldest_key = slots
+                .iter()
+                .filter(|(_, (_, slot))| Arc::strong_count(slot) == 1)
+                .min_by_key(|(_, (created_at, _))| *created_at)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest_key) = oldest_key {
+                slots.remove(&oldest_key);
+            }
+        }
+        slots
+            .entry(key)
+            .or_insert_with(|| (Instant::now(), Slot::default()))
+            .1
+            .clone()
+    }
+}
+
+/// Shared idempotency store.
+static SHARED_IDEMPOTENCY_STORE: LazyLock<IdempotencyStore> = LazyLock::new(|| {
+    let config = crate::Cluster::config().get_table("idempotency");
+    let ttl = config
+        .and_then(|t| t.get_duration("ttl"))
+        .unwrap_or_else(|| Duration::from_secs(24 * 60 * 60));
+    let max_body_size = config
+        .and_then(|t| t.get_usize("max-body-size"))
+        .unwrap_or(1024 * 1024);
+    let max_entries = config
+        .and_then(|t| t.get_usize("max-entries"))
+        .unwrap_or(10_000);
+    IdempotencyStore {
+        slots: Mutex::new(HashMap::new()),
+        ttl,
+        max_body_size,
+        max_entries,
+        hasher: RandomState::new(),
+    }
+});
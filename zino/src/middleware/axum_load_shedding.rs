@@ -0,0 +1,27 @@
+use crate::{request::axum_request::AxumExtractor, response::axum_response::AxumRejection};
+use axum::{
+    body::Body,
+    http::Request,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use zino_core::request::LoadShedder;
+
+/// Limits the in-flight requests with the shared load shedder,
+/// and responds with `503 Service Unavailable` when the service is overloaded.
+pub(crate) async fn load_shedding(req: Request<Body>, next: Next) -> Response {
+    let Some(shedder) = LoadShedder::shared() else {
+        return next.run(req).await;
+    };
+    match shedder.acquire().await {
+        Ok(permit) => {
+            let res = next.run(req).await;
+            drop(permit);
+            res
+        }
+        Err(rejection) => {
+            let req = AxumExtractor::from(req);
+            AxumRejection::from(rejection.context(&req)).into_response()
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use crate::{request::axum_request::AxumExtractor, response::axum_response::AxumResponse};
+use axum::{
+    body::Body,
+    http::{self, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use zino_core::application::MaintenanceMode;
+
+/// Responds with `503 Service Unavailable` for the routes not allowlisted
+/// when the maintenance mode is enabled.
+pub(crate) async fn maintenance_mode(req: Request<Body>, next: Next) -> Response {
+    if !MaintenanceMode::is_enabled() {
+        return next.run(req).await;
+    }
+
+    let req = AxumExtractor::from(req);
+    match MaintenanceMode::check(&req) {
+        Some(res) => AxumResponse::from(res).into_response(),
+        None => next.run(http::Request::from(req)).await,
+    }
+}
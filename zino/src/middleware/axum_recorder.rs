@@ -0,0 +1,220 @@
+use crate::{request::axum_request::AxumExtractor, response::axum_response::AxumRejection};
+use axum::{
+    body::{to_bytes, Body, HttpBody},
+    http::{Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Router,
+};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use zino_core::{
+    application::Application,
+    extension::{JsonObjectExt, JsonValueExt},
+    request::{RequestContext, RequestRecord, RequestRecorder},
+    response::{ExtractRejection, Rejection},
+    warn, JsonValue, Map, Uuid,
+};
+
+/// Route of the recorded requests.
+pub(crate) const RECORDER_ROUTE: &str = "/debug/requests";
+
+/// Records the request/response pairs with the shared request recorder.
+/// The bodies whose size is unknown or exceeds the limit are not recorded.
+pub(crate) async fn request_recorder(req: Request<Body>, next: Next) -> Response {
+    let Some(recorder) = RequestRecorder::shared() else {
+        return next.run(req).await;
+    };
+    if req.uri().path().starts_with(RECORDER_ROUTE) {
+        return next.run(req).await;
+    }
+
+    let max_body_size = recorder.max_body_size();
+    let mut record = RequestRecord::new(req.method().as_str(), req.uri().to_string());
+    for (name, value) in req.headers() {
+        if let Ok(value) = value.to_str() {
+            record.set_request_header(name.as_str(), value);
+        }
+    }
+
+    let req = if is_bounded(req.body(), max_body_size) {
+        let (parts, body) = req.into_parts();
+        match to_bytes(body, max_body_size).await {
+            Ok(bytes) => {
+                record.set_request_body(&bytes);
+                Request::from_parts(parts, Body::from(bytes))
+            }
+            Err(err) => {
+                let req = AxumExtractor::from(Request::from_parts(parts, Body::empty()));
+                let err = warn!("400 Bad Request: fail to read the request body: {}", err);
+                let rejection = Rejection::bad_request(err).context(&req);
+                return AxumRejection::from(rejection).into_response();
+            }
+        }
+    } else {
+        req
+    };
+
+    let res = record.scope(next.run(req)).await;
+    record.set_status_code(res.status().as_u16());
+    for (name, value) in res.headers() {
+        if let Ok(value) = value.to_str() {
+            record.set_response_header(name.as_str(), value);
+        }
+    }
+
+    let res = if is_bounded(res.body(), max_body_size) {
+        let (parts, body) = res.into_parts();
+        match to_bytes(body, max_body_size).await {
+            Ok(bytes) => {
+                record.set_response_body(&bytes);
+                Response::from_parts(parts, Body::from(bytes))
+            }
+            Err(err) => {
+                tracing::error!("fail to read the response body: {err}");
+                record.set_status_code(StatusCode::INTERNAL_SERVER_ERROR.as_u16());
+                recorder.record(record);
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        }
+    } else {
+        res
+    };
+    recorder.record(record);
+    res
+}
+
+/// Returns the router for viewing and replaying the recorded requests.
+pub(crate) fn request_recorder_routes() -> Router {
+    Router::new()
+        .route(RECORDER_ROUTE, get(list_requests))
+        .route(&format!("{RECORDER_ROUTE}/:id"), get(view_request))
+        .route(
+            &format!("{RECORDER_ROUTE}/:id/replay"),
+            post(replay_request),
+        )
+}
+
+/// Lists the recorded requests with the newest first,
+/// which can be filtered by the `method`, `path` and `status_code` query params.
+async fn list_requests(req: crate::Request) -> crate::Result {
+    let recorder = shared_recorder(&req)?;
+    let method = req.get_query("method");
+    let path = req.get_query("path");
+    let status_code = req
+        .get_query("status_code")
+        .and_then(|s| s.parse::<u16>().ok());
+    let records = recorder
+        .records()
+        .into_iter()
+        .filter(|record| {
+            method.map_or(true, |method| record.method().eq_ignore_ascii_case(method))
+                && path.map_or(true, |path| record.uri().starts_with(path))
+                && status_code.map_or(true, |code| record.status_code() == code)
+        })
+        .collect::<Vec<_>>();
+    let records = serde_json::to_value(records).extract(&req)?;
+
+    let mut res = crate::Response::default().context(&req);
+    res.set_json_data(Map::from_entry("requests", records));
+    Ok(res.into())
+}
+
+/// Views a recorded request.
+async fn view_request(req: crate::Request) -> crate::Result {
+    let recorder = shared_recorder(&req)?;
+    let id = req.parse_param::<Uuid>("id")?;
+    let record = recorder.get(&id).extract(&req)?;
+    let record = serde_json::to_value(record).extract(&req)?;
+
+    let mut res = crate::Response::default().context(&req);
+    res.set_json_data(record);
+    Ok(res.into())
+}
+
+/// Re-issues a recorded request to the listen address of the main server.
+/// Since the sensitive values have been redacted, the `headers` and `body`
+/// in the request body can be used to override the recorded ones.
+async fn replay_request(mut req: crate::Request) -> crate::Result {
+    let recorder = shared_recorder(&req)?;
+    let id = req.parse_param::<Uuid>("id")?;
+    let record = recorder.get(&id).extract(&req)?;
+    let Some(addr) = listen_addr() else {
+        let err = warn!("404 Not Found: the main server is not configured");
+        return Err(Rejection::not_found(err).context(&req).into());
+    };
+
+    let url = format!("http://{addr}{}", record.uri());
+    let mut options = record.to_fetch_options();
+    if req.get_header("content-type").is_some() {
+        let mut overrides = req.parse_body::<Map>().await?;
+        if let Some(mut headers) = overrides.remove("headers").and_then(|v| v.into_map_opt()) {
+            if let Some(recorded_headers) =
+                options.get_mut("headers").and_then(|v| v.as_object_mut())
+            {
+                recorded_headers.append(&mut headers);
+            }
+        }
+        if let Some(body) = overrides.remove("body") {
+            let body = body
+                .as_str()
+                .map(|s| s.to_owned())
+                .unwrap_or_else(|| body.to_string());
+            options.upsert("body", body);
+        }
+    }
+
+    let response = crate::Cluster::fetch(&url, Some(&options))
+        .await
+        .extract(&req)?;
+    let status_code = response.status().as_u16();
+    let mut headers = Map::new();
+    for (name, value) in response.headers() {
+        if let Ok(value) = value.to_str() {
+            headers.upsert(name.as_str(), value);
+        }
+    }
+    let body = response.text().await.extract(&req)?;
+
+    let mut data = Map::from_entry("url", url);
+    data.upsert("status_code", status_code);
+    data.upsert("headers", headers);
+    let body = serde_json::from_str::<JsonValue>(&body).unwrap_or_else(|_| body.into());
+    data.upsert("body", body);
+
+    let mut res = crate::Response::default().context(&req);
+    res.set_json_data(data);
+    Ok(res.into())
+}
+
+/// Returns the listen address of the main server,
+/// where an unspecified IP address is replaced with the loopback address.
+fn listen_addr() -> Option<SocketAddr> {
+    let mut addr = crate::Cluster::shared_state()
+        .listeners()
+        .into_iter()
+        .find_map(|(server_tag, addr)| server_tag.is_main().then_some(addr))?;
+    if addr.ip().is_unspecified() {
+        let ip = match addr {
+            SocketAddr::V4(_) => IpAddr::V4(Ipv4Addr::LOCALHOST),
+            SocketAddr::V6(_) => IpAddr::V6(Ipv6Addr::LOCALHOST),
+        };
+        addr.set_ip(ip);
+    }
+    Some(addr)
+}
+
+/// Returns the shared request recorder or a `404 Not Found` rejection.
+fn shared_recorder(req: &crate::Request) -> Result<&'static RequestRecorder, Rejection> {
+    RequestRecorder::shared().ok_or_else(|| {
+        let err = warn!("404 Not Found: the request recorder is not enabled");
+        Rejection::not_found(err).context(req)
+    })
+}
+
+/// Returns `true` if the body size is known and does not exceed the limit.
+fn is_bounded(body: &Body, limit: usize) -> bool {
+    body.size_hint()
+        .upper()
+        .is_some_and(|size| size <= limit as u64)
+}
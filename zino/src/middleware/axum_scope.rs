@@ -0,0 +1,84 @@
+use crate::{request::axum_request::AxumExtractor, response::axum_response::AxumRejection};
+use axum::{
+    body::Body,
+    http::Request,
+    middleware::{from_fn, Next},
+    response::{IntoResponse, Response},
+    routing::MethodRouter,
+    Router,
+};
+use zino_core::{auth::AccessScope, request::RequestContext, response::Rejection, warn};
+
+/// An extension trait for declaring the scope requirements on routes.
+///
+/// The scopes are obtained from the request data of [`AccessScope`],
+/// which can be set by the middleware verifying an access key,
+/// or the `scope` claim of the JWT token if the request data is absent.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use axum::{routing::get, Router};
+/// use zino::RequireScope;
+///
+/// let router = Router::new()
+///     .route("/task/list", get(Task::list).require_scope("tasks:read"))
+///     .route("/task/new", post(Task::new).require_scope("tasks:write"));
+/// ```
+pub trait RequireScope {
+    /// Requires the scope to be granted, such as `tasks:read`.
+    /// It responds a `403 Forbidden` problem details if the scope is not granted.
+    fn require_scope(self, scope: &'static str) -> Self;
+}
+
+impl RequireScope for Router {
+    #[inline]
+    fn require_scope(self, scope: &'static str) -> Self {
+        self.route_layer(from_fn(move |req: Request<Body>, next: Next| {
+            check_scope(scope, req, next)
+        }))
+    }
+}
+
+impl RequireScope for MethodRouter {
+    #[inline]
+    fn require_scope(self, scope: &'static str) -> Self {
+        self.route_layer(from_fn(move |req: Request<Body>, next: Next| {
+            check_scope(scope, req, next)
+        }))
+    }
+}
+
+/// Checks whether the scope is granted for the request.
+async fn check_scope(scope: &'static str, req: Request<Body>, next: Next) -> Response {
+    let req = AxumExtractor::from(req);
+    let access_scope = match req.get_data::<AccessScope>() {
+        Some(access_scope) => access_scope,
+        None => match parse_access_scope(&req) {
+            Ok(access_scope) => access_scope,
+            Err(rejection) => return AxumRejection::from(rejection).into_response(),
+        },
+    };
+    if !access_scope.contains(scope) {
+        let err = warn!("403 Forbidden: the scope `{}` is required", scope);
+        let rejection = Rejection::forbidden(err).context(&req);
+        return AxumRejection::from(rejection).into_response();
+    }
+    next.run(req.into()).await
+}
+
+/// Parses the access scope from the `scope` claim of the JWT token.
+#[cfg(feature = "jwt")]
+fn parse_access_scope(req: &AxumExtractor<Request<Body>>) -> Result<AccessScope, Rejection> {
+    use zino_core::auth::JwtClaims;
+
+    let claims = req.parse_jwt_claims(JwtClaims::shared_key())?;
+    Ok(AccessScope::from_jwt_claims(&claims))
+}
+
+/// Parses the access scope from the request.
+#[cfg(not(feature = "jwt"))]
+fn parse_access_scope(req: &AxumExtractor<Request<Body>>) -> Result<AccessScope, Rejection> {
+    let err = warn!("401 Unauthorized: the access scope is absent");
+    Err(Rejection::unauthorized(err).context(req))
+}
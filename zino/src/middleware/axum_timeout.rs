@@ -0,0 +1,37 @@
+use crate::{request::axum_request::AxumExtractor, response::axum_response::AxumRejection};
+use axum::{
+    body::Body,
+    http::{self, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use zino_core::{
+    error::Error,
+    request::{self, RequestContext},
+    response::Rejection,
+};
+
+/// Cancels the handler if it does not complete within the timeout for the matched route,
+/// and responds with `504 Gateway Timeout`.
+pub(crate) async fn request_timeout(req: Request<Body>, next: Next) -> Response {
+    let req = AxumExtractor::from(req);
+    let timeout = match req.get_context() {
+        Some(ctx) => ctx.remaining_time(),
+        None => req.request_timeout(),
+    };
+    let Some(timeout) = timeout else {
+        return next.run(http::Request::from(req)).await;
+    };
+
+    let millis = timeout.as_millis();
+    let err = Error::new(format!("the handler exceeded the timeout of {millis}ms"));
+    let rejection = Rejection::gateway_timeout(err).context(&req);
+    let fut = request::with_deadline(timeout, next.run(http::Request::from(req)));
+    match tokio::time::timeout(timeout, fut).await {
+        Ok(res) => res,
+        Err(_) => {
+            tracing::warn!("handler is cancelled after {millis}ms");
+            AxumRejection::from(rejection).into_response()
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use futures::future;
+use zino_core::{response::Rejection, SharedString};
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "axum")] {
+        /// An owned dynamically typed future returned by the [`Middleware`] hooks.
+        pub type MiddlewareFuture<'a, T> = future::BoxFuture<'a, T>;
+    } else {
+        /// An owned dynamically typed future returned by the [`Middleware`] hooks.
+        /// It does not need to be `Send` since the request is bound to the worker thread.
+        pub type MiddlewareFuture<'a, T> = future::LocalBoxFuture<'a, T>;
+    }
+}
+
+/// A framework-agnostic middleware operating on the [`Request`](crate::Request).
+///
+/// It only needs to be written once, and can be registered by the `add_middleware` method
+/// of the cluster for `actix-web`, `axum` and `ntex`. The `handle_request` hooks are called
+/// in the order of registration, while the `handle_response` hooks are called in the reverse order.
+/// Both hooks are asynchronous, so the middleware can perform I/O such as looking up
+/// the credentials or the rate limits in a store.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino::{prelude::*, Middleware, MiddlewareFuture, ResponseHead};
+///
+/// struct ApiKeyGuard;
+///
+/// impl Middleware for ApiKeyGuard {
+///     fn handle_request<'a>(
+///         &'a self,
+///         req: &'a mut zino::Request,
+///     ) -> MiddlewareFuture<'a, Result<Option<zino::Response>, Rejection>> {
+///         Box::pin(async move {
+///             if req.request_method() == "OPTIONS" {
+///                 // Responds to the preflight request without calling the route handler.
+///                 let res = zino::Response::new(StatusCode::NO_CONTENT);
+///                 return Ok(Some(res));
+///             }
+///             match req.get_header("x-api-key") {
+///                 Some(api_key) if ApiKey::verify(api_key).await => Ok(None),
+///                 _ => {
+///                     let err = warn!("401 Unauthorized: the `x-api-key` header is invalid");
+///                     Err(Rejection::unauthorized(err).context(req))
+///                 }
+///             }
+///         })
+///     }
+///
+///     fn handle_response<'a>(
+///         &'a self,
+///         _req: &'a zino::Request,
+///         res: &'a mut ResponseHead,
+///     ) -> MiddlewareFuture<'a, ()> {
+///         Box::pin(async move {
+///             res.insert_header("x-api-version", "v1");
+///         })
+///     }
+/// }
+///
+/// zino::Cluster::boot()
+///     .register(router::routes())
+///     .add_middleware(ApiKeyGuard)
+///     .run()
+/// ```
+pub trait Middleware: Send + Sync + 'static {
+    /// Handles the request before it is dispatched to the route handler.
+    /// The request will be rejected if an error is returned. If a response is returned,
+    /// it is sent to the client directly without calling the remaining middlewares
+    /// and the route handler, while the `handle_response` hooks of the preceding middlewares
+    /// are still called.
+    #[inline]
+    fn handle_request<'a>(
+        &'a self,
+        _req: &'a mut crate::Request,
+    ) -> MiddlewareFuture<'a, Result<Option<crate::Response>, Rejection>> {
+        Box::pin(future::ready(Ok(None)))
+    }
+
+    /// Handles the response head after the route handler has been called.
+    #[inline]
+    fn handle_response<'a>(
+        &'a self,
+        _req: &'a crate::Request,
+        _res: &'a mut ResponseHead,
+    ) -> MiddlewareFuture<'a, ()> {
+        Box::pin(future::ready(()))
+    }
+}
+
+/// Runs the `handle_request` hooks of the middlewares in order.
+/// It returns the early response and the number of middlewares which have been called.
+pub(crate) async fn handle_request(
+    middlewares: &[Box<dyn Middleware>],
+    req: &mut crate::Request,
+) -> Result<Option<(crate::Response, usize)>, Rejection> {
+    for (index, middleware) in middlewares.iter().enumerate() {
+        if let Some(res) = middleware.handle_request(req).await? {
+            return Ok(Some((res, index)));
+        }
+    }
+    Ok(None)
+}
+
+/// Runs the `handle_response` hooks of the middlewares in the reverse order.
+pub(crate) async fn handle_response(
+    middlewares: &[Box<dyn Middleware>],
+    req: &crate::Request,
+    res: &mut ResponseHead,
+) {
+    for middleware in middlewares.iter().rev() {
+        middleware.handle_response(req, res).await;
+    }
+}
+
+/// Runs the `handle_response` hooks of the middlewares preceding the one
+/// which returns the early response.
+pub(crate) async fn finalize_early_response(
+    middlewares: &[Box<dyn Middleware>],
+    req: &crate::Request,
+    res: crate::Response,
+) -> crate::Response {
+    let mut res = if res.has_context() {
+        res
+    } else {
+        res.context(req)
+    };
+    let mut res_head = ResponseHead::new(res.status_code());
+    handle_response(middlewares, req, &mut res_head).await;
+    for (key, value) in res_head.into_headers() {
+        res.insert_header(key, value);
+    }
+    res
+}
+
+/// Status code and extra headers of a response visible to the [`Middleware`].
+#[derive(Debug, Clone)]
+pub struct ResponseHead {
+    /// Status code.
+    status_code: u16,
+    /// Headers to be inserted.
+    headers: Vec<(SharedString, String)>,
+}
+
+impl ResponseHead {
+    /// Creates a new instance.
+    #[inline]
+    pub(crate) fn new(status_code: u16) -> Self {
+        Self {
+            status_code,
+            headers: Vec::new(),
+        }
+    }
+
+    /// Inserts a header to the response.
+    #[inline]
+    pub fn insert_header(&mut self, name: impl Into<SharedString>, value: impl ToString) {
+        self.headers.push((name.into(), value.to_string()));
+    }
+
+    /// Returns the status code as `u16`.
+    #[inline]
+    pub fn status_code(&self) -> u16 {
+        self.status_code
+    }
+
+    /// Returns `true` if the response is successful.
+    #[inline]
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status_code)
+    }
+
+    /// Consumes `self` and returns the headers to be inserted.
+    #[inline]
+    pub(crate) fn into_headers(self) -> Vec<(SharedString, String)> {
+        self.headers
+    }
+}
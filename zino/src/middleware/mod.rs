@@ -1,25 +1,57 @@
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+mod custom;
+
+#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+pub use custom::{Middleware, MiddlewareFuture, ResponseHead};
+
 cfg_if::cfg_if! {
     if #[cfg(feature = "actix")] {
         mod actix_context;
         mod actix_cors;
+        mod actix_custom;
         mod actix_etag;
+        mod actix_load_shedding;
+        mod actix_maintenance;
+        mod actix_timeout;
         mod actix_tracing;
 
         pub(crate) use self::actix_context::RequestContextInitializer;
         pub(crate) use self::actix_cors::cors_middleware;
+        pub(crate) use self::actix_custom::CustomMiddlewares;
         pub(crate) use self::actix_etag::ETagFinalizer;
+        pub(crate) use self::actix_load_shedding::LoadShedding;
+        pub(crate) use self::actix_maintenance::MaintenanceGuard;
+        pub(crate) use self::actix_timeout::RequestTimeout;
         pub(crate) use self::actix_tracing::tracing_middleware;
     } else if #[cfg(feature = "axum")] {
         mod axum_context;
+        mod axum_custom;
         mod axum_etag;
+        mod axum_idempotency;
+        mod axum_load_shedding;
+        mod axum_maintenance;
+        mod axum_recorder;
+        mod axum_scope;
         mod axum_static_pages;
+        mod axum_timeout;
         mod tower_cors;
         mod tower_tracing;
 
         pub(crate) use self::axum_context::request_context;
+        pub(crate) use self::axum_custom::custom_middlewares;
         pub(crate) use self::axum_etag::extract_etag;
+        pub use self::axum_idempotency::RequireIdempotency;
+        pub(crate) use self::axum_load_shedding::load_shedding;
+        pub(crate) use self::axum_maintenance::maintenance_mode;
+        pub(crate) use self::axum_recorder::{request_recorder, request_recorder_routes};
+        pub use self::axum_scope::RequireScope;
         pub(crate) use self::axum_static_pages::serve_static_pages;
+        pub(crate) use self::axum_timeout::request_timeout;
         pub(crate) use self::tower_cors::CORS_MIDDLEWARE;
         pub(crate) use self::tower_tracing::TRACING_MIDDLEWARE;
+    } else if #[cfg(feature = "ntex")] {
+        mod ntex_custom;
+
+        pub(crate) use self::ntex_custom::CustomMiddlewares;
     }
 }
@@ -0,0 +1,74 @@
+use super::{custom, Middleware, ResponseHead};
+use crate::response::ntex_response::{NtexRejection, NtexResponse};
+use ntex::{
+    http::header::{HeaderName, HeaderValue},
+    service::{Middleware as ServiceMiddleware, Service, ServiceCtx},
+    web::{Error, ErrorRenderer, Responder, WebRequest, WebResponse},
+};
+
+/// Runs the custom middlewares registered by the `add_middleware` method of the cluster.
+#[derive(Clone, Copy)]
+pub struct CustomMiddlewares(pub(crate) &'static [Box<dyn Middleware>]);
+
+impl<S> ServiceMiddleware<S> for CustomMiddlewares {
+    type Service = CustomMiddleware<S>;
+
+    fn create(&self, service: S) -> Self::Service {
+        CustomMiddleware {
+            service,
+            middlewares: self.0,
+        }
+    }
+}
+
+pub struct CustomMiddleware<S> {
+    service: S,
+    middlewares: &'static [Box<dyn Middleware>],
+}
+
+impl<S, Err> Service<WebRequest<Err>> for CustomMiddleware<S>
+where
+    S: Service<WebRequest<Err>, Response = WebResponse, Error = Error>,
+    Err: ErrorRenderer,
+{
+    type Response = WebResponse;
+    type Error = Error;
+
+    ntex::forward_ready!(service);
+
+    async fn call(
+        &self,
+        req: WebRequest<Err>,
+        ctx: ServiceCtx<'_, Self>,
+    ) -> Result<Self::Response, Self::Error> {
+        let middlewares = self.middlewares;
+        if middlewares.is_empty() {
+            return ctx.call(&self.service, req).await;
+        }
+
+        let mut req = crate::Request::from(req);
+        match custom::handle_request(middlewares, &mut req).await {
+            Ok(Some((res, index))) => {
+                let res = custom::finalize_early_response(&middlewares[..index], &req, res).await;
+                let res = NtexResponse::from(res).respond_to(&req).await;
+                return Ok(WebRequest::<Err>::try_from(req)?.into_response(res));
+            }
+            Ok(None) => (),
+            Err(rejection) => return Err(NtexRejection::from(rejection).into()),
+        }
+
+        let req = WebRequest::<Err>::try_from(req)?;
+        let mut res = ctx.call(&self.service, req).await?;
+        let req = crate::Request::from(res.request().clone());
+        let mut res_head = ResponseHead::new(res.status().as_u16());
+        custom::handle_response(middlewares, &req, &mut res_head).await;
+        for (key, value) in res_head.into_headers() {
+            if let Ok(header_name) = HeaderName::try_from(key.as_ref()) {
+                if let Ok(header_value) = HeaderValue::try_from(value) {
+                    res.headers_mut().insert(header_name, header_value);
+                }
+            }
+        }
+        Ok(res)
+    }
+}
@@ -5,7 +5,10 @@
 #[doc(no_inline)]
 pub use zino_core::{
     application::{Application, Plugin},
-    auth::{AccessKeyId, AuthorizationProvider, SecretAccessKey, SecurityToken, UserSession},
+    auth::{
+        AccessKeyId, AccessScope, AuthorizationProvider, SecretAccessKey, SecurityToken,
+        UserSession,
+    },
     bail,
     datetime::{Date, DateTime, Time},
     error::Error,
@@ -14,9 +17,12 @@ pub use zino_core::{
     json,
     model::{Model, ModelHooks, Mutation, Query, QueryContext},
     reject,
-    request::RequestContext,
+    request::{FromRequest, QueryParams, RequestContext},
     response::{ExtractRejection, Rejection, StatusCode, WebHook},
-    schedule::{AsyncCronJob, AsyncJob, AsyncJobScheduler, CronJob, Job, JobScheduler},
+    schedule::{
+        AsyncCronJob, AsyncJob, AsyncJobScheduler, AsyncTask, CronJob, DelayedTask, Job,
+        JobRegistry, JobScheduler, Operation, OperationHandle, TaskHandle,
+    },
     state::State,
     validation::Validation,
     warn, BoxFuture, Decimal, LazyLock, Map, Record, Uuid,
@@ -3,10 +3,11 @@ use actix_web::{
     web::Bytes,
     FromRequest, HttpMessage, HttpRequest,
 };
+use futures::{Stream, TryStreamExt};
 use std::{
     borrow::Cow,
     convert::Infallible,
-    future,
+    future, mem,
     net::IpAddr,
     ops::{Deref, DerefMut},
 };
@@ -92,6 +93,12 @@ impl RequestContext for ActixExtractor<HttpRequest> {
             .map_err(Error::from_error)?;
         Ok(bytes.to_vec())
     }
+
+    #[inline]
+    fn body_stream(&mut self) -> impl Stream<Item = Result<Bytes, Error>> + Unpin + 'static {
+        let payload = mem::replace(&mut self.1, Payload::None);
+        payload.map_err(Error::from)
+    }
 }
 
 impl From<ServiceRequest> for ActixExtractor<HttpRequest> {
@@ -1,5 +1,9 @@
 use async_trait::async_trait;
-use axum::extract::{ConnectInfo, FromRequest, MatchedPath, OriginalUri, Request};
+use axum::{
+    body::Bytes,
+    extract::{ConnectInfo, FromRequest, MatchedPath, OriginalUri, Request},
+};
+use futures::{Stream, TryStreamExt};
 use std::{
     borrow::Cow,
     convert::Infallible,
@@ -110,6 +114,12 @@ impl RequestContext for AxumExtractor<Request> {
         let bytes = axum::body::to_bytes(body, usize::MAX).await?;
         Ok(bytes.to_vec())
     }
+
+    #[inline]
+    fn body_stream(&mut self) -> impl Stream<Item = Result<Bytes, Error>> + Unpin + 'static {
+        let body = mem::take(self.body_mut());
+        body.into_data_stream().map_err(Error::from)
+    }
 }
 
 #[async_trait]
@@ -1,4 +1,5 @@
 use crate::response::ntex_response::NtexRejection;
+use futures::{stream, Stream};
 use ntex::{
     http::Payload,
     util::Bytes,
@@ -10,6 +11,7 @@ use ntex::{
 use std::{
     borrow::Cow,
     convert::Infallible,
+    mem,
     net::IpAddr,
     ops::{Deref, DerefMut},
 };
@@ -89,6 +91,19 @@ impl RequestContext for NtexExtractor<HttpRequest> {
             <Bytes as FromRequest<DefaultError>>::from_request(&self.0, &mut self.1).await?;
         Ok(bytes.to_vec())
     }
+
+    fn body_stream(&mut self) -> impl Stream<Item = Result<bytes::Bytes, Error>> + Unpin + 'static {
+        let mut payload = mem::replace(&mut self.1, Payload::None);
+        stream::poll_fn(move |cx| {
+            payload.poll_recv(cx).map(|item| {
+                item.map(|result| {
+                    result
+                        .map(|chunk| bytes::Bytes::copy_from_slice(&chunk))
+                        .map_err(Error::from)
+                })
+            })
+        })
+    }
 }
 
 impl<Err: ErrorRenderer> From<WebRequest<Err>> for NtexExtractor<HttpRequest> {
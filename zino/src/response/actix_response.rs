@@ -1,12 +1,13 @@
 use actix_web::{
-    body::BoxBody,
+    body::{BodyStream, BoxBody},
     http::{
         header::{self, HeaderName, HeaderValue},
         StatusCode,
     },
     HttpRequest, HttpResponse, Responder, ResponseError,
 };
-use std::fmt;
+use futures::TryStreamExt;
+use std::{fmt, io};
 use zino_core::{
     response::{Rejection, Response, ResponseCode},
     trace::TimingMetric,
@@ -67,6 +68,13 @@ impl From<Rejection> for ActixRejection {
     }
 }
 
+impl From<Response<StatusCode>> for ActixRejection {
+    #[inline]
+    fn from(response: Response<StatusCode>) -> Self {
+        Self(response)
+    }
+}
+
 impl ResponseError for ActixRejection {
     #[inline]
     fn status_code(&self) -> StatusCode {
@@ -119,13 +127,18 @@ impl ResponseError for ActixRejection {
 
 /// Build http response from `zino_core::response::Response`.
 fn build_http_response<S: ResponseCode>(response: &mut Response<S>) -> HttpResponse<BoxBody> {
-    match response.read_bytes() {
-        Ok(data) => {
+    let body = match response.take_body_stream() {
+        Some(stream) => Ok(BoxBody::new(BodyStream::new(
+            stream.map_err(|err| io::Error::other(err.to_string())),
+        ))),
+        None => response.read_bytes().map(BoxBody::new),
+    };
+    match body {
+        Ok(body) => {
             let status_code = response
                 .status_code()
                 .try_into()
                 .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
-            let body = BoxBody::new(data);
             let mut res = HttpResponse::with_body(status_code, body);
             if let Ok(header_value) = HeaderValue::try_from(response.content_type()) {
                 res.headers_mut().insert(header::CONTENT_TYPE, header_value);
@@ -1,12 +1,13 @@
+use futures::TryStreamExt;
 use ntex::{
     http::{
-        body::Body,
+        body::{Body, BodyStream},
         header::{self, HeaderName, HeaderValue},
         ResponseError, StatusCode,
     },
     web::{HttpRequest, HttpResponse, Responder, WebResponseError},
 };
-use std::fmt;
+use std::{fmt, io};
 use zino_core::{
     response::{Rejection, Response, ResponseCode},
     trace::TimingMetric,
@@ -115,13 +116,21 @@ impl WebResponseError for NtexRejection {
 
 /// Build http response from `zino_core::response::Response`.
 fn build_http_response<S: ResponseCode>(response: &mut Response<S>) -> HttpResponse {
-    match response.read_bytes() {
-        Ok(data) => {
+    let body = match response.take_body_stream() {
+        Some(stream) => {
+            let stream = stream
+                .map_ok(|chunk| ntex::util::Bytes::copy_from_slice(&chunk))
+                .map_err(|err| io::Error::other(err.to_string()));
+            Ok(Body::from_message(BodyStream::new(stream)))
+        }
+        None => response.read_bytes().map(|data| Body::from(data.to_vec())),
+    };
+    match body {
+        Ok(body) => {
             let status_code = response
                 .status_code()
                 .try_into()
                 .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
-            let body = Body::from(data.to_vec());
             let mut res = HttpResponse::with_body(status_code, body);
             if let Ok(header_value) = HeaderValue::try_from(response.content_type()) {
                 res.headers_mut().insert(header::CONTENT_TYPE, header_value);